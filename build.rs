@@ -3,7 +3,12 @@ use std::env;
 use std::path::Path;
 
 fn main() {
-    if cfg!(feature = "unsafe_pointer") {
+    // without `std` there is no lock to build the safe pointers on, and the serial core is single-threaded anyway
+    if !cfg!(feature = "std") && !cfg!(feature = "unsafe_pointer") {
+        println!("cargo:rustc-cfg=feature=\"unsafe_pointer\"");
+    }
+
+    if cfg!(feature = "unsafe_pointer") || !cfg!(feature = "std") {
         println!("cargo:allow=dropping_references");
     }
 
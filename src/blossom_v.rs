@@ -1,11 +1,34 @@
 use super::cfg_if;
+use super::util::Weight;
+use crate::prelude::*;
+
+/// convert the edge weights to the `c_int` range of the blossom V library, such that the total weight of any perfect matching
+/// does not overflow. If any weight is too large (which only happens with wide weights, see feature `wide_weights`), all the
+/// weights are scaled down proportionally and the result is minimum-weight only up to the rounding error.
+#[allow(clippy::unnecessary_cast)]
+pub fn legacy_weighted_edges(node_num: usize, weighted_edges: &[(usize, usize, Weight)]) -> Vec<(usize, usize, u32)> {
+    let max_safe_weight = (i32::MAX as i128) / (node_num as i128).max(1);
+    let max_weight = weighted_edges.iter().map(|&(_, _, weight)| weight as i128).max().unwrap_or(0);
+    weighted_edges
+        .iter()
+        .map(|&(i, j, weight)| {
+            assert!(weight >= 0, "blossom V only accepts non-negative weights");
+            let weight = if max_weight > max_safe_weight {
+                ((weight as f64) * (max_safe_weight as f64) / (max_weight as f64)).round() as u32
+            } else {
+                weight as u32
+            };
+            (i, j, weight)
+        })
+        .collect()
+}
 
 cfg_if::cfg_if! {
     if #[cfg(feature="blossom_v")] {
 
         use super::libc;
         use libc::{c_int};
-        use std::collections::BTreeSet;
+        use alloc::collections::BTreeSet;
 
         #[link(name = "blossomV")]
         extern {
@@ -21,13 +44,13 @@ cfg_if::cfg_if! {
                 let mut sanity_check_passed = true;
                 for &(i, j, _weight) in weighted_edges.iter() {
                     if i == j {
-                        eprintln!("invalid edge between the same vertex {}", i);
+                        log_warn!(vertex = i; "invalid edge between the same vertex {}", i);
                         sanity_check_passed = false;
                     }
                     let left: usize = if i < j { i } else { j };
                     let right: usize = if i < j { j } else { i };
                     if existing_edges.contains(&(left, right)) {
-                        eprintln!("duplicate edge between the vertices {} and {}", i, j);
+                        log_warn!(left = i, right = j; "duplicate edge between the vertices {} and {}", i, j);
                         sanity_check_passed = false;
                     }
                     existing_edges.insert((left, right));
@@ -60,7 +83,6 @@ cfg_if::cfg_if! {
 
 #[cfg(test)]
 mod tests {
-    #[cfg(feature = "blossom_v")]
     use super::*;
 
     #[test]
@@ -72,4 +94,24 @@ mod tests {
         let output = safe_minimum_weight_perfect_matching(node_num, &edges);
         assert_eq!(output, vec![1, 0, 3, 2]);
     }
+
+    #[test]
+    fn blossom_v_legacy_weighted_edges_1() {
+        // cargo test blossom_v_legacy_weighted_edges_1 -- --nocapture
+        let edges: Vec<(usize, usize, Weight)> = vec![(0, 1, 100), (2, 3, 110), (0, 2, 500), (1, 3, 300)];
+        let legacy_edges = legacy_weighted_edges(4, &edges);
+        assert_eq!(legacy_edges, vec![(0, 1, 100), (2, 3, 110), (0, 2, 500), (1, 3, 300)]);
+        // weights that would overflow `c_int` are scaled down proportionally
+        let large = (i32::MAX / 2) as Weight;
+        let edges: Vec<(usize, usize, Weight)> = vec![(0, 1, large), (2, 3, large / 2), (0, 2, 0)];
+        let legacy_edges = legacy_weighted_edges(4, &edges);
+        let max_safe_weight = (i32::MAX / 4) as u32;
+        assert_eq!(
+            legacy_edges,
+            vec![(0, 1, max_safe_weight), (2, 3, max_safe_weight / 2), (0, 2, 0)]
+        );
+        assert!(legacy_edges
+            .iter()
+            .all(|&(_, _, weight)| weight as u64 * 4 <= i32::MAX as u64));
+    }
 }
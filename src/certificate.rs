@@ -0,0 +1,468 @@
+//! Optimality Certificate
+//!
+//! The dual variables found by the fusion algorithm prove that the matching is minimum-weight: if the dual solution is
+//! feasible and its objective equals the weight of the correction subgraph, then by weak duality no correction of the
+//! same syndrome has a lower weight (this is equivalent to complementary slackness). A [`Certificate`] carries exactly
+//! this information, and [`verify_certificate`] checks it without running any solver.
+//!
+//! Feasibility is checked on the decoding graph rather than on the complete graph of defects: let `r_u` be the total
+//! dual variable of all nodes containing defect `u`, then a single multi-source Dijkstra computes for each vertex `x`
+//! the maximum `a(x) = r_u - d(u, x)` over all defects and the defect `u` achieving it (the owner). The duals are
+//! feasible if and only if every defect owns itself, no virtual vertex has `a(x) > 0` and every edge `(x, z)` with
+//! different owners `u`, `v` satisfies `a(x) + a(z) <= w(x, z) + 2 c(u, v)`, where `c(u, v)` is the total dual
+//! variable of the blossoms containing both `u` and `v`.
+//!
+
+#![cfg_attr(feature = "unsafe_pointer", allow(clippy::needless_borrow))]
+
+use super::dual_module::*;
+use super::pointers::*;
+use super::util::*;
+use crate::prelude::*;
+use crate::serde::{Deserialize, Serialize};
+use alloc::collections::BinaryHeap;
+
+/// a dual node in the certificate, either a single defect vertex or a blossom given by all the defect vertices inside
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateNode {
+    /// the dual variable of this node, must be non-negative
+    pub dual_variable: Weight,
+    /// a single defect vertex, or the (odd number of) defect vertices of a blossom including those of its sub-blossoms
+    pub defect_vertices: Vec<VertexIndex>,
+}
+
+/// a verifiable proof that the correction subgraph has minimum weight, see [`verify_certificate`]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Certificate {
+    /// the dual nodes, whose defect vertex sets must form a laminar family
+    pub nodes: Vec<CertificateNode>,
+    /// the correction: the matched paths as a set of edges in the decoding graph
+    pub subgraph: Vec<EdgeIndex>,
+}
+
+/// the reason why a certificate is rejected by [`verify_certificate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateError {
+    /// the certificate is inconsistent with the decoding graph or the syndrome, e.g. an index is out of range, a defect
+    /// is missing or the blossoms don't form a laminar family
+    Malformed(String),
+    /// the odd-degree vertices of the subgraph are not exactly the defect vertices
+    InvalidSubgraph(String),
+    /// the dual variables are not feasible, so they don't prove anything
+    Infeasible(String),
+    /// the dual variables are feasible but their sum is not the weight of the subgraph
+    NotOptimal { dual_sum: Weight, subgraph_weight: Weight },
+}
+
+impl core::fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed(message) => write!(f, "malformed certificate: {message}"),
+            Self::InvalidSubgraph(message) => write!(f, "invalid subgraph: {message}"),
+            Self::Infeasible(message) => write!(f, "infeasible dual variables: {message}"),
+            Self::NotOptimal {
+                dual_sum,
+                subgraph_weight,
+            } => write!(
+                f,
+                "the sum of dual variables {dual_sum} doesn't match the subgraph weight {subgraph_weight}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for CertificateError {}
+
+impl Certificate {
+    /// extract the dual variables of all nodes in the interface after solving; `subgraph` is the correction, e.g. from
+    /// [`crate::mwpm_solver::PrimalDualSolver::subgraph`]
+    pub fn from_interface(interface_ptr: &DualModuleInterfacePtr, subgraph: Vec<EdgeIndex>) -> Self {
+        let mut flattened_nodes = vec![];
+        interface_ptr.flatten_nodes(&mut flattened_nodes);
+        let interface = interface_ptr.read_recursive();
+        let nodes = flattened_nodes
+            .iter()
+            .flatten()
+            .map(|dual_node_ptr| {
+                let mut defect_vertices = vec![];
+                collect_defect_vertices(dual_node_ptr, &mut defect_vertices);
+                CertificateNode {
+                    dual_variable: dual_node_ptr.read_recursive().get_dual_variable(&interface),
+                    defect_vertices,
+                }
+            })
+            .collect();
+        Self { nodes, subgraph }
+    }
+
+    /// the dual objective, i.e. the sum of all dual variables
+    pub fn dual_sum(&self) -> Weight {
+        self.nodes.iter().map(|node| node.dual_variable).sum()
+    }
+}
+
+/// all the defect vertices inside a dual node
+fn collect_defect_vertices(dual_node_ptr: &DualNodePtr, defect_vertices: &mut Vec<VertexIndex>) {
+    let dual_node = dual_node_ptr.read_recursive();
+    match &dual_node.class {
+        DualNodeClass::DefectVertex { defect_index } => defect_vertices.push(*defect_index),
+        DualNodeClass::Blossom { nodes_circle, .. } => {
+            for node_weak in nodes_circle.iter() {
+                collect_defect_vertices(&node_weak.upgrade_force(), defect_vertices);
+            }
+        }
+    }
+}
+
+/// check that `certificate` proves its subgraph to be a minimum-weight correction of `syndrome_pattern`, without running
+/// any solver; erasures, dynamic weights and erasure regions of the syndrome are applied to the edge weights the same way
+/// as the solvers do. It runs in `O(E log V)` time plus the total size of the blossoms times their nesting depth.
+/// Note that the certificate is about the syndrome that was actually decoded: if a solver repaired an odd-parity
+/// component by removing a defect, the certificate is rejected for the original syndrome
+#[allow(clippy::unnecessary_cast)]
+pub fn verify_certificate(
+    initializer: &SolverInitializer,
+    syndrome_pattern: &SyndromePattern,
+    certificate: &Certificate,
+) -> Result<(), CertificateError> {
+    let vertex_num = initializer.vertex_num as usize;
+    let edge_num = initializer.weighted_edges.len();
+    let expanded_syndrome_pattern = ErasureRegionIndex::new(initializer).expand(syndrome_pattern);
+    let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+    syndrome_pattern
+        .check_indices(initializer.vertex_num, edge_num)
+        .map_err(CertificateError::Malformed)?;
+    // apply the edge modifiers of the syndrome
+    let mut weights: Vec<Weight> = initializer.weighted_edges.iter().map(|(_, _, weight)| *weight).collect();
+    for &edge_index in syndrome_pattern.erasures.iter() {
+        weights[edge_index as usize] = 0;
+    }
+    for &(edge_index, weight) in syndrome_pattern.dynamic_weights.iter() {
+        weights[edge_index as usize] = weight;
+    }
+    let mut is_virtual = vec![false; vertex_num];
+    for &vertex_index in initializer.virtual_vertices.iter() {
+        is_virtual[vertex_index as usize] = true;
+    }
+    // the defect index of each defect vertex
+    let defect_num = syndrome_pattern.defect_vertices.len();
+    let mut defect_of_vertex: Vec<Option<usize>> = vec![None; vertex_num];
+    for (defect, &vertex_index) in syndrome_pattern.defect_vertices.iter().enumerate() {
+        if is_virtual[vertex_index as usize] {
+            return Err(CertificateError::Malformed(format!(
+                "defect vertex {vertex_index} is virtual"
+            )));
+        }
+        defect_of_vertex[vertex_index as usize] = Some(defect);
+    }
+    // the subgraph must flip exactly the defect vertices
+    let mut is_odd = vec![false; vertex_num];
+    let mut in_subgraph = vec![false; edge_num];
+    let mut subgraph_weight: Weight = 0;
+    for &edge_index in certificate.subgraph.iter() {
+        if edge_index as usize >= edge_num {
+            return Err(CertificateError::InvalidSubgraph(format!(
+                "edge {edge_index} out of range, there are {edge_num} edges"
+            )));
+        }
+        if core::mem::replace(&mut in_subgraph[edge_index as usize], true) {
+            return Err(CertificateError::InvalidSubgraph(format!("edge {edge_index} is repeated")));
+        }
+        let (i, j, _) = initializer.weighted_edges[edge_index as usize];
+        is_odd[i as usize] ^= true;
+        is_odd[j as usize] ^= true;
+        subgraph_weight = checked_weight_sum([subgraph_weight, weights[edge_index as usize]])
+            .map_err(CertificateError::InvalidSubgraph)?;
+    }
+    for vertex_index in 0..vertex_num {
+        if !is_virtual[vertex_index] && is_odd[vertex_index] != defect_of_vertex[vertex_index].is_some() {
+            return Err(CertificateError::InvalidSubgraph(format!(
+                "vertex {vertex_index} is {} but it's {}flipped by the subgraph",
+                if is_odd[vertex_index] { "not a defect" } else { "a defect" },
+                if is_odd[vertex_index] { "" } else { "not " }
+            )));
+        }
+    }
+    // each defect must be a single node, and the blossoms must form a laminar family of odd sets
+    let mut singleton_of_defect: Vec<Option<usize>> = vec![None; defect_num];
+    let mut node_defects: Vec<Vec<usize>> = Vec::with_capacity(certificate.nodes.len());
+    for (node_index, node) in certificate.nodes.iter().enumerate() {
+        if node.dual_variable < 0 {
+            return Err(CertificateError::Malformed(format!(
+                "node {node_index} has negative dual variable {}",
+                node.dual_variable
+            )));
+        }
+        if node.defect_vertices.len() % 2 == 0 {
+            return Err(CertificateError::Malformed(format!(
+                "node {node_index} has an even number of defect vertices"
+            )));
+        }
+        let mut defects = Vec::with_capacity(node.defect_vertices.len());
+        for &vertex_index in node.defect_vertices.iter() {
+            match defect_of_vertex.get(vertex_index as usize).copied().flatten() {
+                Some(defect) => defects.push(defect),
+                None => {
+                    return Err(CertificateError::Malformed(format!(
+                        "node {node_index} contains vertex {vertex_index} which is not a defect"
+                    )))
+                }
+            }
+        }
+        if defects.len() == 1 && singleton_of_defect[defects[0]].replace(node_index).is_some() {
+            return Err(CertificateError::Malformed(format!(
+                "defect vertex {} has multiple nodes",
+                node.defect_vertices[0]
+            )));
+        }
+        node_defects.push(defects);
+    }
+    if let Some(defect) = singleton_of_defect.iter().position(|node| node.is_none()) {
+        return Err(CertificateError::Malformed(format!(
+            "defect vertex {} has no node",
+            syndrome_pattern.defect_vertices[defect]
+        )));
+    }
+    // process the nodes from the largest to the smallest: the defects of a node must all be in the same smallest node
+    // processed so far, which is its parent; `accumulated` is the sum of dual variables of a node and its ancestors
+    let mut order: Vec<usize> = (0..certificate.nodes.len()).collect();
+    order.sort_by_key(|&node_index| core::cmp::Reverse(node_defects[node_index].len()));
+    let mut parent: Vec<Option<usize>> = vec![None; certificate.nodes.len()];
+    let mut depth = vec![0usize; certificate.nodes.len()];
+    let mut accumulated: Vec<Weight> = vec![0; certificate.nodes.len()];
+    let mut innermost: Vec<Option<usize>> = vec![None; defect_num];
+    let mut visited_timestamp = vec![usize::MAX; defect_num];
+    for (timestamp, &node_index) in order.iter().enumerate() {
+        let defects = &node_defects[node_index];
+        let node_parent = innermost[defects[0]];
+        for &defect in defects.iter() {
+            if core::mem::replace(&mut visited_timestamp[defect], timestamp) == timestamp {
+                return Err(CertificateError::Malformed(format!(
+                    "node {node_index} contains defect vertex {} multiple times",
+                    syndrome_pattern.defect_vertices[defect]
+                )));
+            }
+            if innermost[defect] != node_parent {
+                return Err(CertificateError::Malformed(format!(
+                    "node {node_index} partially overlaps with another node"
+                )));
+            }
+        }
+        if let Some(node_parent) = node_parent {
+            if node_defects[node_parent].len() == defects.len() {
+                return Err(CertificateError::Malformed(format!(
+                    "nodes {node_parent} and {node_index} have the same defect vertices"
+                )));
+            }
+        }
+        for &defect in defects.iter() {
+            innermost[defect] = Some(node_index);
+        }
+        parent[node_index] = node_parent;
+        depth[node_index] = node_parent.map(|node_parent| depth[node_parent] + 1).unwrap_or(0);
+        accumulated[node_index] = checked_weight_sum([
+            certificate.nodes[node_index].dual_variable,
+            node_parent.map(|node_parent| accumulated[node_parent]).unwrap_or(0),
+        ])
+        .map_err(CertificateError::Malformed)?;
+    }
+    // the total dual variable of the blossoms containing both defects, i.e. the accumulated value of their lowest
+    // common ancestor; the singleton nodes contain only one defect, so they're never common
+    let common_dual = |defect_1: usize, defect_2: usize| -> Weight {
+        let mut node_1 = parent[singleton_of_defect[defect_1].unwrap()];
+        let mut node_2 = parent[singleton_of_defect[defect_2].unwrap()];
+        loop {
+            match (node_1, node_2) {
+                (Some(index_1), Some(index_2)) => {
+                    if index_1 == index_2 {
+                        return accumulated[index_1];
+                    }
+                    if depth[index_1] >= depth[index_2] {
+                        node_1 = parent[index_1];
+                    } else {
+                        node_2 = parent[index_2];
+                    }
+                }
+                _ => return 0,
+            }
+        }
+    };
+    // multi-source Dijkstra maximizing `a(x) = r_u - d(u, x)`
+    let mut neighbors: Vec<Vec<(VertexIndex, Weight)>> = vec![vec![]; vertex_num];
+    for (edge_index, &(i, j, _)) in initializer.weighted_edges.iter().enumerate() {
+        neighbors[i as usize].push((j, weights[edge_index]));
+        neighbors[j as usize].push((i, weights[edge_index]));
+    }
+    let mut best: Vec<Option<(Weight, usize)>> = vec![None; vertex_num];
+    let mut heap = BinaryHeap::with_capacity(defect_num);
+    for (defect, &vertex_index) in syndrome_pattern.defect_vertices.iter().enumerate() {
+        let radius = accumulated[singleton_of_defect[defect].unwrap()];
+        best[vertex_index as usize] = Some((radius, defect));
+        heap.push((radius, vertex_index));
+    }
+    while let Some((value, vertex_index)) = heap.pop() {
+        let (best_value, owner) = best[vertex_index as usize].unwrap();
+        if value != best_value {
+            continue; // outdated entry
+        }
+        for &(peer, weight) in neighbors[vertex_index as usize].iter() {
+            let peer_value = value - weight;
+            if best[peer as usize].is_none_or(|(peer_best, _)| peer_value > peer_best) {
+                if let Some(defect) = defect_of_vertex[peer as usize] {
+                    return Err(CertificateError::Infeasible(format!(
+                        "defect vertex {} is covered by the dual variables of defect vertex {} through vertex {vertex_index}",
+                        syndrome_pattern.defect_vertices[defect], syndrome_pattern.defect_vertices[owner]
+                    )));
+                }
+                best[peer as usize] = Some((peer_value, owner));
+                heap.push((peer_value, peer));
+            }
+        }
+    }
+    for &vertex_index in initializer.virtual_vertices.iter() {
+        if let Some((value, owner)) = best[vertex_index as usize] {
+            if value > 0 {
+                return Err(CertificateError::Infeasible(format!(
+                    "the dual variables of defect vertex {} exceed the distance to virtual vertex {vertex_index} by {value}",
+                    syndrome_pattern.defect_vertices[owner]
+                )));
+            }
+        }
+    }
+    for (edge_index, &(i, j, _)) in initializer.weighted_edges.iter().enumerate() {
+        if let (Some((value_1, owner_1)), Some((value_2, owner_2))) = (best[i as usize], best[j as usize]) {
+            if owner_1 != owner_2 && value_1 + value_2 > weights[edge_index] + 2 * common_dual(owner_1, owner_2) {
+                return Err(CertificateError::Infeasible(format!(
+                    "edge {edge_index} is overgrown by the dual variables of defect vertices {} and {}",
+                    syndrome_pattern.defect_vertices[owner_1], syndrome_pattern.defect_vertices[owner_2]
+                )));
+            }
+        }
+    }
+    let dual_sum =
+        checked_weight_sum(certificate.nodes.iter().map(|node| node.dual_variable)).map_err(CertificateError::Malformed)?;
+    if dual_sum != subgraph_weight {
+        return Err(CertificateError::NotOptimal {
+            dual_sum,
+            subgraph_weight,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::super::mwpm_solver::*;
+    use super::*;
+
+    /// the certificates of the serial solver are accepted, and tampering with them is detected
+    #[test]
+    fn certificate_verify_1() {
+        // cargo test certificate_verify_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        code.set_erasure_probability(0.02);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut blossom_count = 0;
+        for seed in 0..100 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let certificate = solver.optimality_certificate().unwrap();
+            assert_eq!(certificate.dual_sum(), solver.sum_dual_variables());
+            verify_certificate(&initializer, &syndrome_pattern, &certificate).unwrap();
+            blossom_count += certificate.nodes.iter().filter(|node| node.defect_vertices.len() > 1).count();
+            // a positive dual variable raised further overgrows some edge
+            if let Some(node_index) = certificate.nodes.iter().position(|node| node.dual_variable > 0) {
+                let mut tampered = certificate.clone();
+                tampered.nodes[node_index].dual_variable += 2;
+                assert!(verify_certificate(&initializer, &syndrome_pattern, &tampered).is_err());
+            }
+            solver.clear();
+        }
+        assert!(blossom_count > 0, "the test should cover blossoms");
+    }
+
+    #[test]
+    fn certificate_verify_2() {
+        // cargo test certificate_verify_2 -- --nocapture
+        // a chain 0 - 1 - 2 - 3 with virtual vertices at both ends
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![1, 2]);
+        let node = |dual_variable: Weight, defect_vertex: VertexIndex| CertificateNode {
+            dual_variable,
+            defect_vertices: vec![defect_vertex],
+        };
+        // matching both to the boundary is optimal
+        let certificate = Certificate {
+            nodes: vec![node(100, 1), node(100, 2)],
+            subgraph: vec![0, 2],
+        };
+        verify_certificate(&initializer, &syndrome_pattern, &certificate).unwrap();
+        // a feasible but non-optimal dual solution doesn't prove anything
+        let weak_certificate = Certificate {
+            nodes: vec![node(100, 1), node(50, 2)],
+            subgraph: vec![0, 2],
+        };
+        assert_eq!(
+            verify_certificate(&initializer, &syndrome_pattern, &weak_certificate),
+            Err(CertificateError::NotOptimal {
+                dual_sum: 150,
+                subgraph_weight: 200
+            })
+        );
+        // crossing the boundary
+        let infeasible_certificate = Certificate {
+            nodes: vec![node(150, 1), node(50, 2)],
+            subgraph: vec![0, 2],
+        };
+        assert!(matches!(
+            verify_certificate(&initializer, &syndrome_pattern, &infeasible_certificate),
+            Err(CertificateError::Infeasible(_))
+        ));
+        // the subgraph must match the defects
+        let wrong_subgraph = Certificate {
+            nodes: vec![node(100, 1), node(100, 2)],
+            subgraph: vec![0],
+        };
+        assert!(matches!(
+            verify_certificate(&initializer, &syndrome_pattern, &wrong_subgraph),
+            Err(CertificateError::InvalidSubgraph(_))
+        ));
+        // with the middle edge erased, matching the two defects costs nothing
+        let erased_syndrome_pattern = SyndromePattern::new(vec![1, 2], vec![1]);
+        let erased_certificate = Certificate {
+            nodes: vec![node(0, 1), node(0, 2)],
+            subgraph: vec![1],
+        };
+        verify_certificate(&initializer, &erased_syndrome_pattern, &erased_certificate).unwrap();
+        assert!(verify_certificate(&initializer, &erased_syndrome_pattern, &certificate).is_err());
+    }
+
+    /// the full result of the serial solver is consistent with the separate APIs and carries a valid certificate
+    #[test]
+    fn certificate_solve_full_1() {
+        // cargo test certificate_solve_full_1 -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.03, 500);
+        code.set_erasure_probability(0.01);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut reference_solver = SolverSerial::new(&initializer);
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let result = solver.solve_full(&syndrome_pattern);
+            verify_certificate(&initializer, &syndrome_pattern, &result.certificate).unwrap();
+            assert_eq!(result.certificate.subgraph, result.subgraph);
+            assert_eq!(result.certificate.dual_sum(), result.weight);
+            reference_solver.solve(&syndrome_pattern);
+            assert_eq!(result.weight, reference_solver.sum_dual_variables());
+            assert_eq!(result.subgraph, reference_solver.subgraph());
+            assert_eq!(
+                result.perfect_matching.matched_pairs(),
+                reference_solver.perfect_matching().matched_pairs()
+            );
+            solver.clear();
+            reference_solver.clear();
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use super::certificate::verify_certificate;
 use super::dual_module::*;
 use super::example_codes::*;
 use super::example_partition;
 use super::mwpm_solver::*;
+use super::pointers::*;
 use super::primal_module::*;
 use super::util::*;
 use super::visualize::*;
@@ -12,9 +14,10 @@ use derivative::Derivative;
 #[cfg(feature = "progress_bar")]
 use pbr::ProgressBar;
 use rand::{thread_rng, Rng};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::env;
+use std::io::Write;
 
 const TEST_EACH_ROUNDS: usize = 100;
 
@@ -60,9 +63,30 @@ pub struct BenchmarkParameters {
     /// visualizer file at visualize/data/<visualizer_filename.json>
     #[clap(long, default_value_t = crate::visualize::static_visualize_data_filename())]
     pub visualizer_filename: String,
+    /// ignore the positions of the code and lay out the decoding graph automatically in the visualizer,
+    /// useful for graphs without meaningful coordinates, e.g. `--code-type error-pattern-reader`
+    #[clap(long, action)]
+    pub visualizer_auto_layout: bool,
+    /// stop recording the visualizer file after this number of snapshots, leaving a warning in the file
+    #[clap(long)]
+    pub visualizer_max_snapshots: Option<usize>,
+    /// with `--visualizer-max-snapshots`, continue in `<visualizer_filename>.part2.json` and so on instead of stopping
+    #[clap(long, action)]
+    pub visualizer_roll_over: bool,
     /// print syndrome patterns
     #[clap(long, action)]
     pub print_syndrome_pattern: bool,
+    /// export every generated syndrome to this file so that other decoders can be evaluated on identical inputs;
+    /// the format is JSON lines, one record per round: `{"round": 0, "seed": 0, "defect_vertices": [...], "erasures": [...]}`
+    #[clap(long)]
+    pub export_syndromes: Option<String>,
+    /// measure the decoding throughput on the syndromes of this file instead of generated ones, e.g. to compare with
+    /// other decoders on identical inputs: either the JSON lines written by `--export-syndromes` or an error pattern
+    /// file (see `--code-type error-pattern-reader`). All syndromes are loaded up front and only solve and clear are
+    /// timed, without any verifier; `--total-rounds` is ignored. An error pattern file recorded on a different decoding
+    /// graph than the one of the code is rejected
+    #[clap(long)]
+    pub syndrome_file: Option<String>,
     /// the method to verify the correctness of the decoding result
     #[clap(long, value_enum, default_value_t = Verifier::BlossomV)]
     pub verifier: Verifier,
@@ -90,9 +114,42 @@ pub struct BenchmarkParameters {
     /// the benchmark profile output file path
     #[clap(long)]
     pub benchmark_profiler_output: Option<String>,
+    /// record the latency of every round and of each phase (solve, verifier, clear) into log-bucketed histograms
+    /// and write them to this file as JSON, with the bucket boundaries, counts, summary percentiles and a
+    /// `schema` field documenting the format
+    #[clap(long)]
+    pub latency_histogram_output: Option<String>,
+    /// comma-separated bucket edges of the latency histograms in nanoseconds, e.g. around a deadline, instead of the
+    /// log-scaled buckets; this also enables the histograms, whose p99 and max are shown in the brief statistics
+    #[clap(long, value_delimiter = ',')]
+    pub latency_bucket_edges: Vec<u64>,
     /// skip some iterations, useful when debugging
     #[clap(long, default_value_t = 0)]
     pub starting_iteration: usize,
+    /// aggregate the per-defect matched weight across all rounds and write the per-vertex statistics to this file
+    #[clap(long)]
+    pub weight_attribution_output: Option<String>,
+    /// count the logical errors of the decoding results and print the logical error rate at the end;
+    /// only supported by codes that generate errors on the edges of the decoding graph
+    #[clap(long, action)]
+    pub compute_logical_error_rate: bool,
+    /// decode the same syndromes with a serial solver of each `max_tree_size` value (`inf` is the exact MWPM decoder
+    /// and is always included as the baseline), e.g. `0,2,4,8,16,inf`, and print a JSON table of the results;
+    /// the seeds are always deterministic so that the comparison is paired
+    #[clap(long, value_delimiter = ',')]
+    pub sweep_max_tree_size: Vec<String>,
+    /// stop decoding with a `max_tree_size` once the confidence interval of its paired logical error rate difference
+    /// to the baseline excludes 0, requires `--compute-logical-error-rate`
+    #[clap(long, action)]
+    pub sweep_early_stop: bool,
+    /// write the JSON table of `--sweep-max-tree-size` to this file instead of printing it
+    #[clap(long)]
+    pub sweep_output: Option<String>,
+    /// print the warnings and the spans of solve, load, grow, resolve and fuse up to this level (error, warn, info,
+    /// debug or trace) to stderr
+    #[cfg(feature = "logging")]
+    #[clap(long)]
+    pub log_level: Option<tracing::Level>,
 }
 
 #[derive(Subcommand, Clone, Derivative)]
@@ -110,6 +167,58 @@ pub enum Commands {
     },
     /// visualize a syndrome graph
     VisualizeSyndromes(VisualizeSyndromesParameters),
+    /// decode a fixed set of syndromes repeatedly and report the first divergent state, if any
+    AuditDeterminism(AuditDeterminismParameters),
+    /// compare two decoding graphs (JSON of solver initializers) and print their difference as JSON
+    DiffGraph(DiffGraphParameters),
+}
+
+#[derive(Parser, Clone, Debug)]
+pub struct DiffGraphParameters {
+    /// the JSON file of the original solver initializer
+    #[clap(value_parser)]
+    pub a: String,
+    /// the JSON file of the new solver initializer
+    #[clap(value_parser)]
+    pub b: String,
+    /// a syndrome file (see `--syndrome-file` of `benchmark`) decoded under both graphs to count the changed corrections;
+    /// skipped with the reason in `impact_skipped` if the graphs have different numbers of vertices
+    #[clap(long)]
+    pub syndromes: Option<String>,
+}
+
+#[derive(Parser, Clone, Debug)]
+pub struct AuditDeterminismParameters {
+    /// code distance
+    #[clap(value_parser)]
+    pub d: VertexNum,
+    /// physical error rate: the probability of each edge to
+    #[clap(value_parser)]
+    pub p: f64,
+    /// rounds of noisy measurement, valid only when multiple rounds
+    #[clap(short = 'n', long, default_value_t = 0)]
+    pub noisy_measurements: VertexNum,
+    /// maximum half weight of edges
+    #[clap(long, default_value_t = 500)]
+    pub max_half_weight: Weight,
+    /// example code type
+    #[clap(short = 'c', long, value_enum, default_value_t = ExampleCodeType::CodeCapacityPlanarCode)]
+    pub code_type: ExampleCodeType,
+    /// the configuration of the code builder
+    #[clap(long, default_value_t = ("{}").to_string())]
+    pub code_config: String,
+    /// the number of syndromes, each generated using its index as the seed
+    #[clap(short = 's', long, default_value_t = 10)]
+    pub syndrome_count: usize,
+    /// the number of times each syndrome is decoded
+    #[clap(short = 'r', long, default_value_t = 3)]
+    pub repeat: usize,
+    /// save the state dumps of the first decoding as a baseline file
+    #[clap(long)]
+    pub save_baseline: Option<String>,
+    /// compare the state dumps against a previously saved baseline file, e.g. generated on another machine
+    #[clap(long)]
+    pub compare_baseline: Option<String>,
 }
 
 #[derive(Parser, Clone, Debug)]
@@ -120,6 +229,9 @@ pub struct VisualizeSyndromesParameters {
     /// visualizer file at visualize/data/<visualizer_filename.json>
     #[clap(long, default_value_t = crate::visualize::static_visualize_data_filename())]
     pub visualizer_filename: String,
+    /// ignore the positions in the syndromes file and lay out the decoding graph automatically
+    #[clap(long, action)]
+    pub visualizer_auto_layout: bool,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -153,6 +265,9 @@ pub enum TestCommands {
         /// enable print syndrome pattern
         #[clap(short = 's', long, action)]
         print_syndrome_pattern: bool,
+        /// cross-check every deterministically seeded syndrome against the serial solver instead of blossom V
+        #[clap(long, action)]
+        compare_serial: bool,
     },
     /// test parallel primal and dual module
     Parallel {
@@ -168,6 +283,9 @@ pub enum TestCommands {
         /// enable print syndrome pattern
         #[clap(short = 's', long, action)]
         print_syndrome_pattern: bool,
+        /// cross-check every deterministically seeded syndrome against the serial solver instead of blossom V
+        #[clap(long, action)]
+        compare_serial: bool,
     },
 }
 
@@ -194,6 +312,9 @@ pub enum ExampleCodeType {
     CircuitLevelPlanarCodeParallel,
     /// read from error pattern file, generated using option `--primal-dual-type error-pattern-logger`
     ErrorPatternReader,
+    /// generate new shots from the error model of the decoding graph in an error pattern file, pass the probabilities
+    /// using `--code-config`, e.g. `{"filename": "...", "probabilities": [...], "pe_scale": 1.0}`, see `ResampledCode`
+    Resampled,
     /// rotated surface code with perfect stabilizer measurement
     CodeCapacityRotatedCode,
     /// rotated surface code with phenomenological noise model
@@ -217,6 +338,8 @@ pub enum PartitionStrategy {
     PhenomenologicalPlanarCodeTimePartition,
     /// partition a phenomenological (or circuit-level) rotated code with time axis
     PhenomenologicalRotatedCodeTimePartition,
+    /// partition a phenomenological (or circuit-level) planar code with time axis and then each time slice spatially
+    PhenomenologicalPlanarCodeSpaceTimePartition,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
@@ -241,6 +364,8 @@ pub enum Verifier {
     BlossomV,
     /// use the serial version of fusion algorithm to verify the correctness of result
     FusionSerial,
+    /// check the optimality certificate (dual variables) of the result, without running any other solver
+    Certificate,
 }
 
 pub struct RunnableBenchmarkParameters {
@@ -269,12 +394,17 @@ impl From<BenchmarkParameters> for RunnableBenchmarkParameters {
             code_config,
             partition_config,
             benchmark_profiler_output,
+            latency_histogram_output,
+            latency_bucket_edges,
+            syndrome_file,
             ..
         } = parameters.clone();
         let code_config: serde_json::Value = serde_json::from_str(&code_config).unwrap();
         let primal_dual_config: serde_json::Value = serde_json::from_str(&primal_dual_config).unwrap();
         let partition_config: serde_json::Value = serde_json::from_str(&partition_config).unwrap();
         // check for dependency early
+        // no verifier is used when decoding a syndrome file
+        let verifier = if syndrome_file.is_some() { Verifier::None } else { verifier };
         if matches!(verifier, Verifier::BlossomV) && cfg!(not(feature = "blossom_v")) {
             panic!("need blossom V library, see README.md")
         }
@@ -290,8 +420,21 @@ impl From<BenchmarkParameters> for RunnableBenchmarkParameters {
         let (initializer, partition_config) = partition_strategy.build(&mut *code, d, noisy_measurements, partition_config);
         let partition_info = partition_config.info();
         let primal_dual_solver = primal_dual_type.build(&initializer, &partition_info, &*code, primal_dual_config);
-        let benchmark_profiler =
-            BenchmarkProfiler::new(noisy_measurements, benchmark_profiler_output.map(|x| (x, &partition_info)));
+        let profiler_header = if syndrome_file.is_some() {
+            json!({ "excluded": "syndrome generation and file IO are excluded, only solve and clear are timed" })
+        } else {
+            json!({})
+        };
+        let mut benchmark_profiler = BenchmarkProfiler::new_with_header(
+            noisy_measurements,
+            benchmark_profiler_output.map(|x| (x, &partition_info)),
+            profiler_header,
+        );
+        if !latency_bucket_edges.is_empty() {
+            benchmark_profiler.enable_latency_histograms_with_bucket_edges(latency_bucket_edges);
+        } else if latency_histogram_output.is_some() {
+            benchmark_profiler.enable_latency_histograms();
+        }
         let result_verifier = verifier.build(&initializer);
         Self {
             code,
@@ -316,10 +459,17 @@ impl RunnableBenchmarkParameters {
                     total_rounds,
                     use_deterministic_seed,
                     print_syndrome_pattern,
+                    export_syndromes,
                     #[cfg(feature = "progress_bar")]
                     pb_message,
                     enable_visualizer,
                     visualizer_filename,
+                    visualizer_auto_layout,
+                    visualizer_max_snapshots,
+                    visualizer_roll_over,
+                    weight_attribution_output,
+                    compute_logical_error_rate,
+                    latency_histogram_output,
                     ..
                 },
         } = self;
@@ -341,28 +491,67 @@ impl RunnableBenchmarkParameters {
         // share the same visualizer across all rounds
         let mut visualizer = None;
         if enable_visualizer {
-            let new_visualizer = Visualizer::new(
-                Some(visualize_data_folder() + visualizer_filename.as_str()),
-                code.get_positions(),
-                true,
-            )
+            let filepath = Some(visualize_data_folder() + visualizer_filename.as_str());
+            let mut new_visualizer = if visualizer_auto_layout {
+                Visualizer::new_auto_layout(filepath, &code.get_initializer_cached(), 0)
+            } else {
+                Visualizer::new_with_initializer(filepath, code.get_positions(), &code.get_initializer_cached(), true)
+            }
             .unwrap();
+            if visualizer_max_snapshots.is_some() {
+                let policy = if visualizer_roll_over {
+                    VisualizerLimitPolicy::RollOver
+                } else {
+                    VisualizerLimitPolicy::Stop
+                };
+                new_visualizer.set_limits(visualizer_max_snapshots, None, policy);
+            }
             visualizer = Some(new_visualizer);
         }
+        let mut export_syndromes_file =
+            export_syndromes.map(|filepath| std::fs::File::create(filepath).expect("cannot create syndrome export file"));
+        let initializer = code.get_initializer_cached();
+        let mut weight_attribution_stats = WeightAttributionStats::new();
+        let mut logical_errors = 0;
         for round in (starting_iteration as u64)..(total_rounds as u64) {
             #[cfg(feature = "progress_bar")]
             pb.as_mut().map(|pb| pb.set(round));
             let seed = if use_deterministic_seed { round } else { rng.gen() };
-            let syndrome_pattern = code.generate_random_errors(seed);
+            let (syndrome_pattern, error_edges) = if compute_logical_error_rate {
+                code.generate_random_errors_with_edges(seed)
+                    .expect("this code type doesn't know the errors, cannot compute logical error rate")
+            } else {
+                (code.generate_random_errors(seed), vec![])
+            };
             if print_syndrome_pattern {
                 println!("syndrome_pattern: {:?}", syndrome_pattern);
             }
+            if let Some(file) = export_syndromes_file.as_mut() {
+                let record = json!({
+                    "round": round,
+                    "seed": seed,
+                    "defect_vertices": syndrome_pattern.defect_vertices,
+                    "erasures": syndrome_pattern.erasures,
+                });
+                file.write_all(serde_json::to_string(&record).unwrap().as_bytes()).unwrap();
+                file.write_all(b"\n").unwrap();
+            }
             benchmark_profiler.begin(&syndrome_pattern);
             primal_dual_solver.solve_visualizer(&syndrome_pattern, visualizer.as_mut());
             benchmark_profiler.event("decoded".to_string());
+            result_verifier.set_seed(seed);
             result_verifier.verify(&mut primal_dual_solver, &syndrome_pattern, visualizer.as_mut());
             benchmark_profiler.event("verified".to_string());
-            primal_dual_solver.clear(); // also count the clear operation
+            if weight_attribution_output.is_some() {
+                let perfect_matching = primal_dual_solver.perfect_matching();
+                weight_attribution_stats.add(&perfect_matching.weight_attribution(&initializer));
+            }
+            if compute_logical_error_rate && code.is_logical_error(&error_edges, &primal_dual_solver.subgraph()) {
+                logical_errors += 1;
+            }
+            benchmark_profiler.event("analyzed".to_string());
+            primal_dual_solver.reset().expect("solver not clean after reset"); // also count the clear operation
+            benchmark_profiler.event("cleared".to_string());
             benchmark_profiler.end(Some(&*primal_dual_solver));
             primal_dual_solver.reset_profiler();
             #[cfg(feature = "progress_bar")]
@@ -382,15 +571,320 @@ impl RunnableBenchmarkParameters {
             }
             println!();
         }
+        if let Some(filepath) = latency_histogram_output {
+            let latency_histograms = benchmark_profiler.latency_histograms.as_ref().unwrap();
+            let content = serde_json::to_string(&latency_histograms.to_json()).unwrap();
+            std::fs::write(&filepath, content).expect("cannot write latency histogram output file");
+        }
+        if let Some(filepath) = weight_attribution_output {
+            let content = serde_json::to_string(&weight_attribution_stats.to_json()).unwrap();
+            std::fs::write(&filepath, content).expect("cannot write weight attribution output file");
+        }
+        if compute_logical_error_rate {
+            let shots = total_rounds.saturating_sub(starting_iteration);
+            println!(
+                "logical error rate: {logical_errors}/{shots} = {}",
+                logical_errors as f64 / shots as f64
+            );
+        }
+    }
+
+    /// measure the throughput of decoding the syndromes of [`BenchmarkParameters::syndrome_file`]; the syndromes are
+    /// loaded and checked before timing, and only solve and clear are timed
+    pub fn run_syndrome_file(self) {
+        let Self {
+            code,
+            mut primal_dual_solver,
+            mut benchmark_profiler,
+            parameters:
+                BenchmarkParameters {
+                    syndrome_file,
+                    latency_histogram_output,
+                    ..
+                },
+            ..
+        } = self;
+        let initializer = code.get_initializer();
+        let syndrome_patterns = read_syndrome_file(&syndrome_file.expect("syndrome file required"), Some(&initializer));
+        for (index, syndrome_pattern) in syndrome_patterns.iter().enumerate() {
+            if let Err(error) = primal_dual_solver.check_syndrome_pattern(syndrome_pattern) {
+                panic!("syndrome {index} in the file doesn't fit the decoding graph: {error}");
+            }
+        }
+        // only the solving and the clearing are timed, the profiler and the clean check are excluded
+        let mut elapsed = 0.;
+        for syndrome_pattern in syndrome_patterns.iter() {
+            benchmark_profiler.begin(syndrome_pattern);
+            let begin = std::time::Instant::now();
+            primal_dual_solver.solve(syndrome_pattern);
+            elapsed += begin.elapsed().as_secs_f64();
+            benchmark_profiler.event("decoded".to_string());
+            let begin = std::time::Instant::now();
+            primal_dual_solver.clear();
+            elapsed += begin.elapsed().as_secs_f64();
+            benchmark_profiler.event("cleared".to_string());
+            primal_dual_solver.check_clean().expect("solver not clean after clear");
+            benchmark_profiler.end(Some(&*primal_dual_solver));
+            primal_dual_solver.reset_profiler();
+        }
+        let shots = syndrome_patterns.len();
+        println!("{}", benchmark_profiler.brief());
+        println!(
+            "decoded {shots} shots in {elapsed:.3}s: {:.1} shots/s, {:.3} us/shot (only solving and clearing are timed)",
+            shots as f64 / elapsed,
+            elapsed * 1e6 / shots as f64
+        );
+        if let Some(filepath) = latency_histogram_output {
+            let latency_histograms = benchmark_profiler.latency_histograms.as_ref().unwrap();
+            let content = serde_json::to_string(&latency_histograms.to_json()).unwrap();
+            std::fs::write(&filepath, content).expect("cannot write latency histogram output file");
+        }
+    }
+
+    /// decode the same deterministic syndromes with serial solvers of different `max_tree_size`, see
+    /// [`BenchmarkParameters::sweep_max_tree_size`]; the solver built from the parameters serves as the exact baseline
+    /// and is checked by the verifier
+    pub fn run_max_tree_size_sweep(self) {
+        let Self {
+            mut code,
+            primal_dual_solver,
+            mut result_verifier,
+            parameters:
+                BenchmarkParameters {
+                    starting_iteration,
+                    total_rounds,
+                    primal_dual_type,
+                    compute_logical_error_rate,
+                    sweep_max_tree_size,
+                    sweep_early_stop,
+                    sweep_output,
+                    ..
+                },
+            ..
+        } = self;
+        assert!(
+            matches!(primal_dual_type, PrimalDualType::Serial),
+            "the max_tree_size sweep only supports the serial primal-dual type"
+        );
+        assert!(
+            !sweep_early_stop || compute_logical_error_rate,
+            "early stop of the sweep requires --compute-logical-error-rate"
+        );
+        let mut max_tree_sizes: Vec<usize> = sweep_max_tree_size
+            .iter()
+            .map(|value| match value.trim() {
+                "inf" => usize::MAX,
+                value => value.parse().expect("max_tree_size must be a non-negative integer or `inf`"),
+            })
+            .collect();
+        if !max_tree_sizes.contains(&usize::MAX) {
+            max_tree_sizes.push(usize::MAX);
+        }
+        let initializer = code.get_initializer();
+        let mut baseline_solver = Some(primal_dual_solver);
+        let mut settings: Vec<MaxTreeSizeSweepSetting> = max_tree_sizes
+            .iter()
+            .map(|&max_tree_size| {
+                let solver = if max_tree_size == usize::MAX && baseline_solver.is_some() {
+                    baseline_solver.take().unwrap()
+                } else {
+                    let solver = SolverSerial::new(&initializer);
+                    solver.primal_module.write().max_tree_size = max_tree_size;
+                    Box::new(solver)
+                };
+                MaxTreeSizeSweepSetting::new(max_tree_size, solver)
+            })
+            .collect();
+        let baseline_index = max_tree_sizes.iter().position(|x| *x == usize::MAX).unwrap();
+        for round in (starting_iteration as u64)..(total_rounds as u64) {
+            let (syndrome_pattern, error_edges) = if compute_logical_error_rate {
+                code.generate_random_errors_with_edges(round)
+                    .expect("this code type doesn't know the errors, cannot compute logical error rate")
+            } else {
+                (code.generate_random_errors(round), vec![])
+            };
+            for (index, setting) in settings.iter_mut().enumerate() {
+                if setting.stopped_early {
+                    continue;
+                }
+                let begin = std::time::Instant::now();
+                setting.solver.solve(&syndrome_pattern);
+                setting.total_latency += begin.elapsed().as_secs_f64();
+                if index == baseline_index {
+                    result_verifier.verify(&mut setting.solver, &syndrome_pattern, None);
+                }
+                if compute_logical_error_rate {
+                    let correction = setting.solver.subgraph();
+                    setting.logical_errors.push(code.is_logical_error(&error_edges, &correction));
+                }
+                setting.shots += 1;
+                setting.solver.reset().expect("solver not clean after reset");
+            }
+            if sweep_early_stop {
+                let (baseline, others) = split_baseline(&mut settings, baseline_index);
+                for setting in others {
+                    if !setting.stopped_early && setting.shots >= MaxTreeSizeSweepSetting::EARLY_STOP_MIN_SHOTS {
+                        let (low, high) = setting.paired_difference(baseline).1;
+                        setting.stopped_early = low > 0. || high < 0.;
+                    }
+                }
+            }
+        }
+        let baseline = &settings[baseline_index];
+        let table: Vec<serde_json::Value> = settings
+            .iter()
+            .map(|setting| setting.to_json(baseline, compute_logical_error_rate))
+            .collect();
+        let output = json!({
+            "total_rounds": total_rounds.saturating_sub(starting_iteration),
+            "baseline_max_tree_size": "inf",
+            "settings": table,
+        });
+        let content = serde_json::to_string_pretty(&output).unwrap();
+        match sweep_output {
+            Some(filepath) => std::fs::write(&filepath, content).expect("cannot write sweep output file"),
+            None => println!("{content}"),
+        }
+    }
+}
+
+/// read all the syndromes of a file given to `--syndrome-file`: an error pattern file if it starts with its header line,
+/// otherwise JSON lines of syndrome patterns like those written by `--export-syndromes`. An error pattern file records
+/// its decoding graph, which must match `initializer` if given, see [`ErrorPatternReader::check_structural_hash`]
+pub fn read_syndrome_file(filepath: &str, initializer: Option<&SolverInitializer>) -> Vec<SyndromePattern> {
+    let content = std::fs::read_to_string(filepath).expect("cannot read syndrome file");
+    if content.starts_with("Syndrome Pattern v1.0 ") {
+        let reader = ErrorPatternReader::new(json!({ "filename": filepath }));
+        if let Some(initializer) = initializer {
+            if let Err(error) = reader.check_structural_hash(initializer) {
+                panic!("syndrome file {filepath} doesn't fit the decoding graph: {error}");
+            }
+        }
+        return reader.syndrome_patterns;
+    }
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_index, line)| {
+            serde_json::from_str(line).unwrap_or_else(|error| panic!("invalid syndrome at line {}: {error}", line_index + 1))
+        })
+        .collect()
+}
+
+/// the results of a single `max_tree_size` value in the sweep
+pub struct MaxTreeSizeSweepSetting {
+    pub max_tree_size: usize,
+    pub solver: Box<dyn PrimalDualSolver>,
+    pub shots: usize,
+    pub total_latency: f64,
+    /// whether each shot has a logical error, only recorded with `--compute-logical-error-rate`
+    pub logical_errors: Vec<bool>,
+    pub stopped_early: bool,
+}
+
+/// the z-score of a two-sided 95% confidence interval
+const CONFIDENCE_Z: f64 = 1.96;
+
+impl MaxTreeSizeSweepSetting {
+    /// avoid stopping on the very unreliable intervals of the first few shots
+    pub const EARLY_STOP_MIN_SHOTS: usize = 100;
+
+    pub fn new(max_tree_size: usize, solver: Box<dyn PrimalDualSolver>) -> Self {
+        Self {
+            max_tree_size,
+            solver,
+            shots: 0,
+            total_latency: 0.,
+            logical_errors: vec![],
+            stopped_early: false,
+        }
+    }
+
+    /// logical error rate with its normal-approximation confidence interval
+    pub fn logical_error_rate(&self) -> (f64, (f64, f64)) {
+        let n = self.logical_errors.len() as f64;
+        let rate = self.logical_errors.iter().filter(|x| **x).count() as f64 / n;
+        let half_width = CONFIDENCE_Z * (rate * (1. - rate) / n).sqrt();
+        (rate, (rate - half_width, rate + half_width))
+    }
+
+    /// the mean difference of logical errors to the baseline over the shots decoded by both, and its confidence
+    /// interval; because both decode the same syndromes, the per-shot differences are paired samples
+    pub fn paired_difference(&self, baseline: &Self) -> (f64, (f64, f64)) {
+        let differences: Vec<f64> = self
+            .logical_errors
+            .iter()
+            .zip(baseline.logical_errors.iter())
+            .map(|(a, b)| (*a as i32 - *b as i32) as f64)
+            .collect();
+        let n = differences.len() as f64;
+        let mean = differences.iter().sum::<f64>() / n;
+        let variance = if n > 1. {
+            differences.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.)
+        } else {
+            0.
+        };
+        let half_width = CONFIDENCE_Z * (variance / n).sqrt();
+        (mean, (mean - half_width, mean + half_width))
+    }
+
+    pub fn to_json(&self, baseline: &Self, compute_logical_error_rate: bool) -> serde_json::Value {
+        let mut value = json!({
+            "max_tree_size": if self.max_tree_size == usize::MAX { json!("inf") } else { json!(self.max_tree_size) },
+            "shots": self.shots,
+            "average_latency": self.total_latency / self.shots as f64,
+            "stopped_early": self.stopped_early,
+        });
+        if compute_logical_error_rate {
+            let (rate, rate_interval) = self.logical_error_rate();
+            let (difference, difference_interval) = self.paired_difference(baseline);
+            let object = value.as_object_mut().unwrap();
+            object.insert(
+                "logical_errors".to_string(),
+                json!(self.logical_errors.iter().filter(|x| **x).count()),
+            );
+            object.insert("logical_error_rate".to_string(), json!(rate));
+            object.insert("logical_error_rate_interval".to_string(), json!(rate_interval));
+            object.insert("paired_difference".to_string(), json!(difference));
+            object.insert("paired_difference_interval".to_string(), json!(difference_interval));
+        }
+        value
     }
 }
 
+/// borrow the baseline setting immutably and all the others mutably
+fn split_baseline(
+    settings: &mut [MaxTreeSizeSweepSetting],
+    baseline_index: usize,
+) -> (&MaxTreeSizeSweepSetting, impl Iterator<Item = &mut MaxTreeSizeSweepSetting>) {
+    let (before, rest) = settings.split_at_mut(baseline_index);
+    let (baseline, after) = rest.split_first_mut().unwrap();
+    (&*baseline, before.iter_mut().chain(after.iter_mut()))
+}
+
 impl Cli {
     pub fn run(self) {
         match self.command {
             Commands::Benchmark(benchmark_parameters) => {
+                #[cfg(feature = "logging")]
+                if let Some(log_level) = benchmark_parameters.log_level {
+                    tracing_subscriber::fmt()
+                        .with_max_level(log_level)
+                        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+                        .with_writer(std::io::stderr)
+                        .init();
+                }
+                let is_sweep = !benchmark_parameters.sweep_max_tree_size.is_empty();
+                let is_syndrome_file = benchmark_parameters.syndrome_file.is_some();
                 let runnable = RunnableBenchmarkParameters::from(benchmark_parameters);
-                runnable.run();
+                if is_sweep {
+                    runnable.run_max_tree_size_sweep();
+                } else if is_syndrome_file {
+                    runnable.run_syndrome_file();
+                } else {
+                    runnable.run();
+                }
             }
             Commands::VisualizeSyndromes(parameters) => {
                 let code_config = json!({
@@ -400,7 +894,7 @@ impl Cli {
                 let code_config_str = serde_json::to_string(&code_config).unwrap();
                 let total_rounds_str = format!("{}", reader.syndrome_patterns.len());
                 drop(reader);
-                let command: Vec<String> = [
+                let mut command: Vec<String> = [
                     "",
                     "benchmark",
                     "0",
@@ -420,6 +914,9 @@ impl Cli {
                 .into_iter()
                 .map(|s| s.to_string())
                 .collect();
+                if parameters.visualizer_auto_layout {
+                    command.push("--visualizer-auto-layout".to_string());
+                }
                 execute_in_cli(command.iter(), true);
             }
             Commands::Test { command } => {
@@ -523,6 +1020,7 @@ impl Cli {
                         enable_visualizer,
                         disable_blossom,
                         print_syndrome_pattern,
+                        compare_serial,
                     } => {
                         let mut parameters = vec![];
                         for p in [0.001, 0.003, 0.01, 0.03, 0.1, 0.3, 0.499] {
@@ -628,6 +1126,27 @@ impl Cli {
                                 ]);
                             }
                         }
+                        for p in [0.001, 0.003, 0.01, 0.03, 0.1, 0.3, 0.499] {
+                            for (d, time_partitions, space_partitions) in [(7, 2, 2), (15, 4, 2)] {
+                                // two-level partition: each time slice is further split spatially
+                                parameters.push(vec![
+                                    format!("{d}"),
+                                    format!("{p}"),
+                                    format!("--code-type"),
+                                    format!("phenomenological-planar-code"),
+                                    format!("--noisy-measurements"),
+                                    format!("{d}"),
+                                    format!("--partition-strategy"),
+                                    format!("phenomenological-planar-code-space-time-partition"),
+                                    format!("--partition-config"),
+                                    format!("{{\"time_partitions\":{time_partitions},\"space_partitions\":{space_partitions}}}"),
+                                    format!("--pb-message"),
+                                    format!(
+                                        "dual-parallel {space_partitions}x{time_partitions}-partition phenomenological {d} {d} {p}"
+                                    ),
+                                ]);
+                            }
+                        }
                         let command_head = [String::new(), "benchmark".to_string()];
                         let mut command_tail = vec![
                             format!("--primal-dual-type"),
@@ -635,7 +1154,13 @@ impl Cli {
                             "--total-rounds".to_string(),
                             format!("{TEST_EACH_ROUNDS}"),
                         ];
-                        if !disable_blossom {
+                        if compare_serial {
+                            command_tail.append(&mut vec![
+                                format!("--verifier"),
+                                format!("fusion-serial"),
+                                format!("--use-deterministic-seed"),
+                            ]);
+                        } else if !disable_blossom {
                             command_tail.append(&mut vec![format!("--verifier"), format!("blossom-v")]);
                         } else {
                             command_tail.append(&mut vec![format!("--verifier"), format!("none")]);
@@ -658,6 +1183,7 @@ impl Cli {
                         enable_visualizer,
                         disable_blossom,
                         print_syndrome_pattern,
+                        compare_serial,
                     } => {
                         let mut parameters = vec![];
                         for p in [0.001, 0.003, 0.01, 0.03, 0.1, 0.3, 0.499] {
@@ -761,6 +1287,27 @@ impl Cli {
                                 ]);
                             }
                         }
+                        for p in [0.001, 0.003, 0.01, 0.03, 0.1, 0.3, 0.499] {
+                            for (d, time_partitions, space_partitions) in [(7, 2, 2), (15, 4, 2)] {
+                                // two-level partition: each time slice is further split spatially
+                                parameters.push(vec![
+                                    format!("{d}"),
+                                    format!("{p}"),
+                                    format!("--code-type"),
+                                    format!("phenomenological-planar-code"),
+                                    format!("--noisy-measurements"),
+                                    format!("{d}"),
+                                    format!("--partition-strategy"),
+                                    format!("phenomenological-planar-code-space-time-partition"),
+                                    format!("--partition-config"),
+                                    format!("{{\"time_partitions\":{time_partitions},\"space_partitions\":{space_partitions}}}"),
+                                    format!("--pb-message"),
+                                    format!(
+                                        "parallel {space_partitions}x{time_partitions}-partition phenomenological {d} {d} {p}"
+                                    ),
+                                ]);
+                            }
+                        }
                         let command_head = [String::new(), "benchmark".to_string()];
                         let mut command_tail = vec![
                             format!("--primal-dual-type"),
@@ -768,7 +1315,13 @@ impl Cli {
                             "--total-rounds".to_string(),
                             format!("{TEST_EACH_ROUNDS}"),
                         ];
-                        if !disable_blossom {
+                        if compare_serial {
+                            command_tail.append(&mut vec![
+                                format!("--verifier"),
+                                format!("fusion-serial"),
+                                format!("--use-deterministic-seed"),
+                            ]);
+                        } else if !disable_blossom {
                             command_tail.append(&mut vec![format!("--verifier"), format!("blossom-v")]);
                         } else {
                             command_tail.append(&mut vec![format!("--verifier"), format!("none")]);
@@ -788,6 +1341,12 @@ impl Cli {
                     }
                 }
             }
+            Commands::AuditDeterminism(parameters) => {
+                parameters.run();
+            }
+            Commands::DiffGraph(parameters) => {
+                println!("{}", parameters.run());
+            }
             #[cfg(feature = "qecp_integrate")]
             Commands::Qecp(benchmark_parameters) => {
                 println!("{}", benchmark_parameters.run().unwrap());
@@ -812,6 +1371,7 @@ pub fn execute_in_cli<'a>(iter: impl Iterator<Item = &'a String> + Clone, print_
 }
 
 impl ExampleCodeType {
+    #[allow(clippy::unnecessary_cast)]
     pub fn build(
         &self,
         d: VertexNum,
@@ -825,6 +1385,14 @@ impl ExampleCodeType {
                 assert_eq!(code_config, json!({}), "config not supported");
                 Box::new(CodeCapacityRepetitionCode::new(d, p, max_half_weight))
             }
+            Self::PhenomenologicalRepetitionCode => {
+                assert_eq!(code_config, json!({}), "config not supported");
+                Box::new(PhenomenologicalRepetitionCode::new(d, noisy_measurements, p, max_half_weight))
+            }
+            Self::CircuitLevelRepetitionCode => {
+                assert_eq!(code_config, json!({}), "config not supported");
+                Box::new(CircuitLevelRepetitionCode::new(d, noisy_measurements, p, max_half_weight))
+            }
             Self::CodeCapacityPlanarCode => {
                 assert_eq!(code_config, json!({}), "config not supported");
                 Box::new(CodeCapacityPlanarCode::new(d, p, max_half_weight))
@@ -860,6 +1428,7 @@ impl ExampleCodeType {
                 ))
             }
             Self::ErrorPatternReader => Box::new(ErrorPatternReader::new(code_config)),
+            Self::Resampled => Box::new(ResampledCode::new(code_config)),
             Self::CodeCapacityRotatedCode => {
                 assert_eq!(code_config, json!({}), "config not supported");
                 Box::new(CodeCapacityRotatedCode::new(d, p, max_half_weight))
@@ -870,7 +1439,8 @@ impl ExampleCodeType {
             }
             #[cfg(feature = "qecp_integrate")]
             Self::QECPlaygroundCode => Box::new(QECPlaygroundCode::new(d as usize, p, code_config)),
-            _ => unimplemented!(),
+            #[cfg(not(feature = "qecp_integrate"))]
+            Self::QECPlaygroundCode => panic!("qec-playground-code requires the `qecp_integrate` feature"),
         }
     }
 }
@@ -915,17 +1485,38 @@ impl PartitionStrategy {
                 if let Some(value) = config.remove("maximum_tree_leaf_size") {
                     maximum_tree_leaf_size = value.as_u64().expect("maximum_tree_leaf_size: usize") as usize;
                 }
+                let mut block_rounds = None;
+                if let Some(value) = config.remove("block_rounds") {
+                    block_rounds = Some(
+                        value
+                            .as_array()
+                            .expect("block_rounds: [usize]")
+                            .iter()
+                            .map(|rounds| rounds.as_u64().expect("block_rounds: [usize]") as VertexNum)
+                            .collect::<Vec<_>>(),
+                    );
+                }
                 if !config.is_empty() {
                     panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
                 }
-                PhenomenologicalPlanarCodeTimePartition::new_tree(
-                    d,
-                    noisy_measurements,
-                    partition_num,
-                    enable_tree_fusion,
-                    maximum_tree_leaf_size,
-                )
-                .build_apply(code)
+                match block_rounds {
+                    Some(block_rounds) => PhenomenologicalPlanarCodeTimePartition::new_block_rounds(
+                        d,
+                        noisy_measurements,
+                        block_rounds,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code),
+                    None => PhenomenologicalPlanarCodeTimePartition::new_tree(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code),
+                }
             }
             Self::PhenomenologicalRotatedCodeTimePartition => {
                 let config = partition_config.as_object_mut().expect("config must be JSON object");
@@ -941,17 +1532,54 @@ impl PartitionStrategy {
                 if let Some(value) = config.remove("maximum_tree_leaf_size") {
                     maximum_tree_leaf_size = value.as_u64().expect("maximum_tree_leaf_size: usize") as usize;
                 }
+                let mut block_rounds = None;
+                if let Some(value) = config.remove("block_rounds") {
+                    block_rounds = Some(
+                        value
+                            .as_array()
+                            .expect("block_rounds: [usize]")
+                            .iter()
+                            .map(|rounds| rounds.as_u64().expect("block_rounds: [usize]") as VertexNum)
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                if !config.is_empty() {
+                    panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+                }
+                match block_rounds {
+                    Some(block_rounds) => PhenomenologicalRotatedCodeTimePartition::new_block_rounds(
+                        d,
+                        noisy_measurements,
+                        block_rounds,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code),
+                    None => PhenomenologicalRotatedCodeTimePartition::new_tree(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code),
+                }
+            }
+            Self::PhenomenologicalPlanarCodeSpaceTimePartition => {
+                let config = partition_config.as_object_mut().expect("config must be JSON object");
+                let mut time_partitions = 2;
+                let mut space_partitions = 2;
+                if let Some(value) = config.remove("time_partitions") {
+                    time_partitions = value.as_u64().expect("time_partitions: usize") as usize;
+                }
+                if let Some(value) = config.remove("space_partitions") {
+                    space_partitions = value.as_u64().expect("space_partitions: usize") as usize;
+                }
                 if !config.is_empty() {
                     panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
                 }
-                PhenomenologicalRotatedCodeTimePartition::new_tree(
-                    d,
-                    noisy_measurements,
-                    partition_num,
-                    enable_tree_fusion,
-                    maximum_tree_leaf_size,
-                )
-                .build_apply(code)
+                PhenomenologicalPlanarCodeSpaceTimePartition::new(d, noisy_measurements, time_partitions, space_partitions)
+                    .build_apply(code)
             }
         };
         (code.get_initializer(), partition_config)
@@ -968,13 +1596,12 @@ impl PrimalDualType {
     ) -> Box<dyn PrimalDualSolver> {
         match self {
             Self::Serial => {
-                assert_eq!(primal_dual_config, json!({}));
                 assert_eq!(
                     partition_info.config.partitions.len(),
                     1,
                     "no partition is supported by serial algorithm, consider using other primal-dual-type"
                 );
-                Box::new(SolverSerial::new(initializer))
+                Box::new(SolverSerial::new_config(initializer, primal_dual_config))
             }
             Self::DualParallel => Box::new(SolverDualParallel::new(initializer, partition_info, primal_dual_config)),
             Self::Parallel => Box::new(SolverParallel::new(initializer, partition_info, primal_dual_config)),
@@ -997,11 +1624,17 @@ impl Verifier {
                 subgraph_builder: SubGraphBuilder::new(initializer),
             }),
             Self::FusionSerial => Box::new(VerifierFusionSerial::new(initializer)),
+            Self::Certificate => Box::new(VerifierCertificate {
+                initializer: initializer.clone(),
+                seed: None,
+            }),
         }
     }
 }
 
 pub trait ResultVerifier {
+    /// the seed that generated the next syndrome to verify, so that a failure can report how to reproduce it
+    fn set_seed(&mut self, _seed: u64) {}
     fn verify(
         &mut self,
         primal_dual_solver: &mut Box<dyn PrimalDualSolver>,
@@ -1025,6 +1658,9 @@ impl ResultVerifier for VerifierNone {
     }
 }
 
+/// check the solver against the blossom V library by the total weight of the matching, and check that its subgraph
+/// explains the syndrome; the matched pairs are not compared, because different matchers may pick different pairs of
+/// the same weight when the MWPM is degenerate
 pub struct VerifierBlossomV {
     initializer: SolverInitializer,
     subgraph_builder: SubGraphBuilder,
@@ -1038,10 +1674,7 @@ pub fn get_primal_dual_solver_total_weight(
     let mwpm = primal_dual_solver.perfect_matching();
     let legacy_mwpm = mwpm.legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
     let fusion_details = super::detailed_matching(initializer, &syndrome_pattern.defect_vertices, &legacy_mwpm);
-    let mut total_weight = 0;
-    for detail in fusion_details.iter() {
-        total_weight += detail.weight;
-    }
+    let total_weight = super::detailed_matching_total_weight(&fusion_details).unwrap();
     (mwpm, total_weight)
 }
 
@@ -1053,6 +1686,8 @@ impl ResultVerifier for VerifierBlossomV {
         syndrome_pattern: &SyndromePattern,
         visualizer: Option<&mut Visualizer>,
     ) {
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         // prepare modified weighted edges
         let mut edge_modifier = EdgeWeightModifier::new();
         for edge_index in syndrome_pattern.erasures.iter() {
@@ -1064,10 +1699,7 @@ impl ResultVerifier for VerifierBlossomV {
         let blossom_mwpm_result = super::blossom_v_mwpm(&self.initializer, &syndrome_pattern.defect_vertices);
         let blossom_details =
             super::detailed_matching(&self.initializer, &syndrome_pattern.defect_vertices, &blossom_mwpm_result);
-        let mut blossom_total_weight = 0;
-        for detail in blossom_details.iter() {
-            blossom_total_weight += detail.weight;
-        }
+        let blossom_total_weight = super::detailed_matching_total_weight(&blossom_details).unwrap();
         // if blossom_total_weight > 0 { println!("w {} {}", primal_dual_solver.sum_dual_variables(), blossom_total_weight); }
         assert_eq!(
             primal_dual_solver.sum_dual_variables(),
@@ -1078,10 +1710,15 @@ impl ResultVerifier for VerifierBlossomV {
         let (fusion_mwpm, fusion_total_weight) =
             get_primal_dual_solver_total_weight(primal_dual_solver, syndrome_pattern, &self.initializer);
         // compare with ground truth from the blossom V algorithm
-        assert_eq!(
-            fusion_total_weight, blossom_total_weight,
-            "unexpected final dual variable sum"
-        );
+        if fusion_total_weight != blossom_total_weight {
+            let blossom_matched_pairs =
+                MatchingDiff::legacy_matched_pairs(&syndrome_pattern.defect_vertices, &blossom_mwpm_result);
+            let matching_diff = MatchingDiff::new(&fusion_mwpm.matched_pairs(), &blossom_matched_pairs);
+            panic!(
+                "unexpected final dual variable sum: {} != {}, differing pairs (fusion, blossom V): {:?}",
+                fusion_total_weight, blossom_total_weight, matching_diff
+            );
+        }
         // recover those weighted_edges
         while edge_modifier.has_modified_edges() {
             let (edge_index, original_weight) = edge_modifier.pop_modified_edge();
@@ -1098,6 +1735,13 @@ impl ResultVerifier for VerifierBlossomV {
             blossom_total_weight,
             "unexpected final dual variable sum"
         );
+        let defect_vertices: std::collections::BTreeSet<VertexIndex> =
+            syndrome_pattern.defect_vertices.iter().copied().collect();
+        assert_eq!(
+            self.initializer.syndrome_of(&self.subgraph_builder.get_subgraph()),
+            defect_vertices,
+            "the subgraph doesn't explain the syndrome"
+        );
         if visualizer.is_some() {
             primal_dual_solver.subgraph_visualizer(visualizer);
         }
@@ -1108,6 +1752,7 @@ pub struct VerifierFusionSerial {
     pub solver: SolverSerial,
     pub initializer: SolverInitializer,
     pub subgraph_builder: SubGraphBuilder,
+    pub seed: Option<u64>,
 }
 
 impl VerifierFusionSerial {
@@ -1116,11 +1761,16 @@ impl VerifierFusionSerial {
             solver: SolverSerial::new(initializer),
             initializer: initializer.clone(),
             subgraph_builder: SubGraphBuilder::new(initializer),
+            seed: None,
         }
     }
 }
 
 impl ResultVerifier for VerifierFusionSerial {
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
     #[allow(clippy::unnecessary_cast)]
     fn verify(
         &mut self,
@@ -1128,13 +1778,19 @@ impl ResultVerifier for VerifierFusionSerial {
         syndrome_pattern: &SyndromePattern,
         visualizer: Option<&mut Visualizer>,
     ) {
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         self.solver.clear();
         self.solver.solve_visualizer(syndrome_pattern, None);
         let standard_total_weight = self.solver.sum_dual_variables();
+        // the serial solver is the reference: any difference is a bug in the solver under test, e.g. in partition or fusion
         assert_eq!(
             primal_dual_solver.sum_dual_variables(),
             standard_total_weight,
-            "unexpected final dual variable sum"
+            "unexpected final dual variable sum compared to serial solver, seed: {:?}, defect_vertices: {:?}, erasures: {:?}",
+            self.seed,
+            syndrome_pattern.defect_vertices,
+            syndrome_pattern.erasures
         );
         self.subgraph_builder.clear();
         self.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
@@ -1143,10 +1799,598 @@ impl ResultVerifier for VerifierFusionSerial {
         assert_eq!(
             self.subgraph_builder.total_weight(),
             standard_total_weight,
-            "unexpected perfect matching weight"
+            "unexpected perfect matching weight compared to serial solver, seed: {:?}, defect_vertices: {:?}, erasures: {:?}",
+            self.seed,
+            syndrome_pattern.defect_vertices,
+            syndrome_pattern.erasures
         );
         if visualizer.is_some() {
             primal_dual_solver.subgraph_visualizer(visualizer);
         }
     }
 }
+
+pub struct VerifierCertificate {
+    pub initializer: SolverInitializer,
+    pub seed: Option<u64>,
+}
+
+impl ResultVerifier for VerifierCertificate {
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    fn verify(
+        &mut self,
+        primal_dual_solver: &mut Box<dyn PrimalDualSolver>,
+        syndrome_pattern: &SyndromePattern,
+        visualizer: Option<&mut Visualizer>,
+    ) {
+        let certificate = primal_dual_solver
+            .optimality_certificate()
+            .expect("the solver doesn't provide an optimality certificate");
+        if let Err(error) = verify_certificate(&self.initializer, syndrome_pattern, &certificate) {
+            panic!(
+                "{error}, seed: {:?}, defect_vertices: {:?}, erasures: {:?}",
+                self.seed, syndrome_pattern.defect_vertices, syndrome_pattern.erasures
+            );
+        }
+        if visualizer.is_some() {
+            primal_dual_solver.subgraph_visualizer(visualizer);
+        }
+    }
+}
+
+/// the state dumps of decoding a single syndrome, one after each round of the primal-dual loop plus the final state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeterminismAuditRecord {
+    pub syndrome_pattern: SyndromePattern,
+    pub round_hashes: Vec<u64>,
+    pub round_states: Vec<serde_json::Value>,
+}
+
+/// the first place where two decoding processes diverge
+#[derive(Debug, Clone)]
+pub struct DeterminismDivergence {
+    pub syndrome_index: usize,
+    pub round: usize,
+    /// JSON paths of the differing values in the state dumps
+    pub paths: Vec<String>,
+}
+
+impl DeterminismAuditRecord {
+    /// decode the syndrome using the given solver, which is cleared afterwards
+    pub fn new(solver: &mut SolverSerial, syndrome_pattern: &SyndromePattern) -> Self {
+        let mut round_states = vec![];
+        let SolverSerial {
+            dual_module,
+            primal_module,
+            interface_ptr,
+            ..
+        } = solver;
+        let dump_state = |interface_ptr: &DualModuleInterfacePtr,
+                          dual_module: &crate::dual_module_serial::DualModuleSerial,
+                          primal_module: &crate::primal_module_serial::PrimalModuleSerialPtr| {
+            json!({
+                "interface": interface_ptr.snapshot(false),
+                "dual": dual_module.snapshot(false),
+                "primal": primal_module.snapshot(false),
+            })
+        };
+        primal_module.solve_step_callback(
+            interface_ptr,
+            syndrome_pattern,
+            dual_module,
+            |interface_ptr, dual_module, primal_module, _| {
+                round_states.push(dump_state(interface_ptr, dual_module, primal_module));
+            },
+        );
+        round_states.push(dump_state(interface_ptr, dual_module, primal_module));
+        solver.clear();
+        let round_hashes = round_states.iter().map(determinism_audit_hash).collect();
+        Self {
+            syndrome_pattern: syndrome_pattern.clone(),
+            round_hashes,
+            round_states,
+        }
+    }
+
+    /// find the first divergent round compared to another record of the same syndrome
+    pub fn first_divergence(&self, other: &Self) -> Option<(usize, Vec<String>)> {
+        if self.syndrome_pattern.defect_vertices != other.syndrome_pattern.defect_vertices
+            || self.syndrome_pattern.erasures != other.syndrome_pattern.erasures
+        {
+            return Some((0, vec!["syndrome_pattern".to_string()]));
+        }
+        for (round, (hash, other_hash)) in self.round_hashes.iter().zip(other.round_hashes.iter()).enumerate() {
+            if hash != other_hash {
+                let mut paths = vec![];
+                json_diff_paths(&self.round_states[round], &other.round_states[round], "", &mut paths);
+                return Some((round, paths));
+            }
+        }
+        if self.round_hashes.len() != other.round_hashes.len() {
+            let round = self.round_hashes.len().min(other.round_hashes.len());
+            return Some((
+                round,
+                vec![format!(
+                    "<number of rounds: {} vs {}>",
+                    self.round_hashes.len(),
+                    other.round_hashes.len()
+                )],
+            ));
+        }
+        None
+    }
+}
+
+/// compare two lists of records of the same syndromes and return the first divergence
+pub fn determinism_audit_compare(
+    baseline: &[DeterminismAuditRecord],
+    records: &[DeterminismAuditRecord],
+) -> Option<DeterminismDivergence> {
+    for (syndrome_index, (baseline_record, record)) in baseline.iter().zip(records.iter()).enumerate() {
+        if let Some((round, paths)) = baseline_record.first_divergence(record) {
+            return Some(DeterminismDivergence {
+                syndrome_index,
+                round,
+                paths,
+            });
+        }
+    }
+    if baseline.len() != records.len() {
+        return Some(DeterminismDivergence {
+            syndrome_index: baseline.len().min(records.len()),
+            round: 0,
+            paths: vec![format!("<number of syndromes: {} vs {}>", baseline.len(), records.len())],
+        });
+    }
+    None
+}
+
+/// a stable hash of the JSON state (FNV-1a of the serialized string), independent of the platform and Rust version;
+/// note that JSON object keys are always sorted so the serialization is deterministic
+pub fn determinism_audit_hash(state: &serde_json::Value) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in serde_json::to_string(state).unwrap().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// collect the JSON paths (e.g. `/dual/edges/3/lg`) where two values differ
+pub fn json_diff_paths(a: &serde_json::Value, b: &serde_json::Value, path: &str, paths: &mut Vec<String>) {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Object(map_a), Value::Object(map_b)) => {
+            let keys: std::collections::BTreeSet<&String> = map_a.keys().chain(map_b.keys()).collect();
+            for key in keys {
+                let sub_path = format!("{path}/{key}");
+                match (map_a.get(key), map_b.get(key)) {
+                    (Some(value_a), Some(value_b)) => json_diff_paths(value_a, value_b, &sub_path, paths),
+                    _ => paths.push(sub_path),
+                }
+            }
+        }
+        (Value::Array(array_a), Value::Array(array_b)) => {
+            for (index, (value_a, value_b)) in array_a.iter().zip(array_b.iter()).enumerate() {
+                json_diff_paths(value_a, value_b, &format!("{path}/{index}"), paths);
+            }
+            for index in array_a.len().min(array_b.len())..array_a.len().max(array_b.len()) {
+                paths.push(format!("{path}/{index}"));
+            }
+        }
+        _ => {
+            if a != b {
+                paths.push(path.to_string());
+            }
+        }
+    }
+}
+
+impl DiffGraphParameters {
+    pub fn run(self) -> serde_json::Value {
+        let read_initializer = |filepath: &str| -> SolverInitializer {
+            let content = std::fs::read_to_string(filepath).expect("cannot read solver initializer file");
+            serde_json::from_str(&content)
+                .unwrap_or_else(|error| panic!("invalid solver initializer in {filepath}: {error}"))
+        };
+        let initializer = read_initializer(&self.a);
+        let other = read_initializer(&self.b);
+        let diff = initializer.diff(&other);
+        let mut output = json!({
+            "identical": diff.is_empty(),
+            "diff": diff,
+        });
+        if let Some(filepath) = self.syndromes.as_ref() {
+            let syndrome_patterns = read_syndrome_file(filepath, Some(&initializer));
+            output["syndromes"] = json!(syndrome_patterns.len());
+            match initializer.diff_impact(&other, &syndrome_patterns) {
+                Ok(changed_corrections) => output["changed_corrections"] = json!(changed_corrections),
+                Err(reason) => output["impact_skipped"] = json!(reason),
+            }
+        }
+        output
+    }
+}
+
+impl AuditDeterminismParameters {
+    pub fn run(self) {
+        let code_config: serde_json::Value = serde_json::from_str(&self.code_config).unwrap();
+        let mut code = self
+            .code_type
+            .build(self.d, self.p, self.noisy_measurements, self.max_half_weight, code_config);
+        let syndrome_patterns: Vec<SyndromePattern> = (0..self.syndrome_count as u64)
+            .map(|seed| code.generate_random_errors(seed))
+            .collect();
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let decode_all = |solver: &mut SolverSerial| -> Vec<DeterminismAuditRecord> {
+            syndrome_patterns
+                .iter()
+                .map(|syndrome_pattern| DeterminismAuditRecord::new(solver, syndrome_pattern))
+                .collect()
+        };
+        let report = |divergence: DeterminismDivergence, description: String| {
+            println!(
+                "{description}: first divergence at syndrome {} round {}",
+                divergence.syndrome_index, divergence.round
+            );
+            for path in divergence.paths.iter() {
+                println!("    {path}");
+            }
+            panic!("nondeterminism detected");
+        };
+        let records = decode_all(&mut solver);
+        for repeat in 1..self.repeat {
+            let repeated_records = decode_all(&mut solver);
+            if let Some(divergence) = determinism_audit_compare(&records, &repeated_records) {
+                report(divergence, format!("repeat {repeat}"));
+            }
+        }
+        if let Some(filepath) = self.compare_baseline.as_ref() {
+            let file = std::fs::File::open(filepath).unwrap();
+            let baseline: Vec<DeterminismAuditRecord> = serde_json::from_reader(std::io::BufReader::new(file)).unwrap();
+            if let Some(divergence) = determinism_audit_compare(&baseline, &records) {
+                report(divergence, format!("baseline {filepath}"));
+            }
+        }
+        if let Some(filepath) = self.save_baseline.as_ref() {
+            let file = std::fs::File::create(filepath).unwrap();
+            serde_json::to_writer(std::io::BufWriter::new(file), &records).unwrap();
+        }
+        let total_rounds: usize = records.iter().map(|record| record.round_hashes.len()).sum();
+        println!(
+            "deterministic: {} syndromes, {} rounds, {} repeats",
+            records.len(),
+            total_rounds,
+            self.repeat
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// every deterministically seeded syndrome decoded by the parallel solver has the same weight as the serial one
+    #[test]
+    fn cli_compare_serial_1() {
+        // cargo test cli_compare_serial_1 -- --nocapture
+        for primal_dual_type in ["dual-parallel", "parallel"] {
+            let command: Vec<String> = [
+                "",
+                "benchmark",
+                "7",
+                "0.1",
+                "--code-type",
+                "code-capacity-planar-code",
+                "--partition-strategy",
+                "code-capacity-planar-code-vertical-partition-four",
+                "--primal-dual-type",
+                primal_dual_type,
+                "--verifier",
+                "fusion-serial",
+                "--use-deterministic-seed",
+                "--total-rounds",
+                "20",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+            execute_in_cli(command.iter(), true);
+        }
+    }
+
+    /// the repetition codes spanning multiple rounds are decoded the same by every solver as by the serial one
+    #[test]
+    fn cli_repetition_code_types_1() {
+        // cargo test cli_repetition_code_types_1 -- --nocapture
+        for code_type in ["phenomenological-repetition-code", "circuit-level-repetition-code"] {
+            for primal_dual_type in ["serial", "parallel"] {
+                let command: Vec<String> = [
+                    "",
+                    "benchmark",
+                    "7",
+                    "0.05",
+                    "--code-type",
+                    code_type,
+                    "--noisy-measurements",
+                    "6",
+                    "--primal-dual-type",
+                    primal_dual_type,
+                    "--verifier",
+                    "fusion-serial",
+                    "--use-deterministic-seed",
+                    "--total-rounds",
+                    "50",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+                execute_in_cli(command.iter(), true);
+            }
+        }
+    }
+
+    /// every fusion solver provides a certificate that proves its result optimal
+    #[test]
+    fn cli_verify_certificate_1() {
+        // cargo test cli_verify_certificate_1 -- --nocapture
+        for primal_dual_type in ["serial", "dual-parallel", "parallel"] {
+            let command: Vec<String> = [
+                "",
+                "benchmark",
+                "7",
+                "0.1",
+                "--code-type",
+                "phenomenological-planar-code",
+                "--noisy-measurements",
+                "3",
+                "--primal-dual-type",
+                primal_dual_type,
+                "--verifier",
+                "certificate",
+                "--use-deterministic-seed",
+                "--total-rounds",
+                "20",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+            execute_in_cli(command.iter(), true);
+        }
+    }
+
+    /// a mismatch against the serial solver reports how to reproduce it
+    #[test]
+    #[should_panic(expected = "seed: Some(42)")]
+    fn cli_compare_serial_2() {
+        // cargo test cli_compare_serial_2 -- --nocapture
+        let code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut primal_dual_solver: Box<dyn PrimalDualSolver> = Box::new(SolverSerial::new(&initializer));
+        primal_dual_solver.solve(&SyndromePattern::new_vertices(vec![19, 20]));
+        let mut verifier = VerifierFusionSerial::new(&initializer);
+        verifier.set_seed(42);
+        verifier.verify(&mut primal_dual_solver, &SyndromePattern::new_vertices(vec![19, 35]), None);
+    }
+
+    /// the sweep decodes the same syndromes with every `max_tree_size` and the exact baseline is always included
+    #[test]
+    fn cli_sweep_max_tree_size_1() {
+        // cargo test cli_sweep_max_tree_size_1 -- --nocapture
+        let output_filename = "tmp/cli_sweep_max_tree_size_1.json".to_string();
+        std::fs::create_dir_all("tmp").unwrap();
+        let command: Vec<String> = [
+            "",
+            "benchmark",
+            "5",
+            "0.05",
+            "--code-type",
+            "code-capacity-planar-code",
+            "--verifier",
+            "fusion-serial",
+            "--total-rounds",
+            "200",
+            "--sweep-max-tree-size",
+            "0,4",
+            "--compute-logical-error-rate",
+            "--sweep-output",
+            output_filename.as_str(),
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        execute_in_cli(command.iter(), true);
+        let output: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&output_filename).unwrap()).unwrap();
+        std::fs::remove_file(&output_filename).unwrap();
+        let settings = output["settings"].as_array().unwrap();
+        assert_eq!(settings.len(), 3);
+        assert_eq!(settings[2]["max_tree_size"], json!("inf"));
+        assert_eq!(settings[2]["paired_difference"], json!(0.));
+        for setting in settings.iter() {
+            assert_eq!(setting["shots"], json!(200));
+            assert!(setting["logical_error_rate"].as_f64().unwrap() < 0.1);
+        }
+    }
+
+    /// every exported record can be regenerated from its seed
+    #[test]
+    fn cli_export_syndromes_1() {
+        // cargo test cli_export_syndromes_1 -- --nocapture
+        let export_filename = "tmp/cli_export_syndromes_1.jsonl".to_string();
+        std::fs::create_dir_all("tmp").unwrap();
+        let command: Vec<String> = [
+            "",
+            "benchmark",
+            "5",
+            "0.1",
+            "--code-type",
+            "code-capacity-planar-code",
+            "--verifier",
+            "none",
+            "--use-deterministic-seed",
+            "--total-rounds",
+            "10",
+            "--export-syndromes",
+            export_filename.as_str(),
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        execute_in_cli(command.iter(), true);
+        let content = std::fs::read_to_string(&export_filename).unwrap();
+        std::fs::remove_file(&export_filename).unwrap();
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let records: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 10);
+        for (round, record) in records.iter().enumerate() {
+            assert_eq!(record["round"], json!(round));
+            let syndrome_pattern = code.generate_random_errors(record["seed"].as_u64().unwrap());
+            assert_eq!(record["defect_vertices"], json!(syndrome_pattern.defect_vertices));
+            assert_eq!(record["erasures"], json!(syndrome_pattern.erasures));
+        }
+    }
+
+    /// exported syndromes are read back identically and decoded by the throughput-only benchmark
+    #[test]
+    fn cli_syndrome_file_1() {
+        // cargo test cli_syndrome_file_1 -- --nocapture
+        let export_filename = "tmp/cli_syndrome_file_1.jsonl".to_string();
+        let profiler_filename = "tmp/cli_syndrome_file_1.profile".to_string();
+        std::fs::create_dir_all("tmp").unwrap();
+        let command = |tail: &[&str]| -> Vec<String> {
+            ["", "benchmark", "5", "0.1", "--code-type", "code-capacity-planar-code"]
+                .iter()
+                .chain(tail.iter())
+                .map(|s| s.to_string())
+                .collect()
+        };
+        let export_command = command(&[
+            "--verifier",
+            "none",
+            "--use-deterministic-seed",
+            "--total-rounds",
+            "10",
+            "--export-syndromes",
+            export_filename.as_str(),
+        ]);
+        execute_in_cli(export_command.iter(), true);
+        let syndrome_patterns = read_syndrome_file(&export_filename, None);
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        assert_eq!(syndrome_patterns.len(), 10);
+        for (seed, syndrome_pattern) in syndrome_patterns.iter().enumerate() {
+            assert_eq!(
+                syndrome_pattern.defect_vertices,
+                code.generate_random_errors(seed as u64).defect_vertices
+            );
+        }
+        let decode_command = command(&[
+            "--syndrome-file",
+            export_filename.as_str(),
+            "--benchmark-profiler-output",
+            profiler_filename.as_str(),
+        ]);
+        execute_in_cli(decode_command.iter(), true);
+        let profile = std::fs::read_to_string(&profiler_filename).unwrap();
+        std::fs::remove_file(&export_filename).unwrap();
+        std::fs::remove_file(&profiler_filename).unwrap();
+        let lines: Vec<&str> = profile.lines().collect();
+        assert_eq!(lines.len(), 2 + 10);
+        let header: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert!(header["excluded"].is_string());
+        // an error pattern file only fits the decoding graph it was recorded on
+        let pattern_filename = "tmp/cli_syndrome_file_1.txt".to_string();
+        let mut other_code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        {
+            let other_initializer = other_code.get_initializer();
+            let mut logger = SolverErrorPatternLogger::new(
+                &other_initializer,
+                &other_code.get_positions(),
+                json!({ "filename": pattern_filename }),
+            );
+            logger.solve(&other_code.generate_random_errors(0));
+        }
+        let initializer = code.get_initializer();
+        let result = std::panic::catch_unwind(|| read_syndrome_file(&pattern_filename, Some(&initializer)));
+        assert_eq!(
+            read_syndrome_file(&pattern_filename, Some(&other_code.get_initializer())).len(),
+            1
+        );
+        std::fs::remove_file(&pattern_filename).unwrap();
+        assert!(result.is_err(), "syndromes of another decoding graph should be rejected");
+    }
+
+    /// the paired difference is computed shot by shot against the baseline
+    #[test]
+    fn cli_sweep_paired_difference() {
+        // cargo test cli_sweep_paired_difference -- --nocapture
+        let initializer = CodeCapacityPlanarCode::new(3, 0.1, 500).get_initializer();
+        let mut baseline = MaxTreeSizeSweepSetting::new(usize::MAX, Box::new(SolverSerial::new(&initializer)));
+        let mut setting = MaxTreeSizeSweepSetting::new(0, Box::new(SolverSerial::new(&initializer)));
+        baseline.logical_errors = vec![false, false, true, false];
+        setting.logical_errors = vec![true, true, true, false];
+        let (rate, (low, high)) = setting.logical_error_rate();
+        assert_eq!(rate, 0.75);
+        assert!(low < rate && rate < high);
+        let (difference, (low, high)) = setting.paired_difference(&baseline);
+        assert_eq!(difference, 0.5);
+        assert!(low < 0.5 && high > 0.5);
+        assert_eq!(baseline.paired_difference(&baseline), (0., (0., 0.)));
+    }
+
+    #[test]
+    fn cli_diff_graph_1() {
+        // cargo test cli_diff_graph_1 -- --nocapture
+        std::fs::create_dir_all("tmp").unwrap();
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut drifted = initializer.clone();
+        drifted.weighted_edges[0].2 += 100;
+        drifted.weighted_edges[1].2 -= 200;
+        let a_filename = "tmp/cli_diff_graph_1_a.json".to_string();
+        let b_filename = "tmp/cli_diff_graph_1_b.json".to_string();
+        let syndromes_filename = "tmp/cli_diff_graph_1_syndromes.jsonl".to_string();
+        std::fs::write(&a_filename, serde_json::to_string(&initializer).unwrap()).unwrap();
+        std::fs::write(&b_filename, serde_json::to_string(&drifted).unwrap()).unwrap();
+        let syndrome_patterns: Vec<String> = (0..20)
+            .map(|seed| serde_json::to_string(&code.generate_random_errors(seed)).unwrap())
+            .collect();
+        std::fs::write(&syndromes_filename, syndrome_patterns.join("\n")).unwrap();
+        let output = Cli::parse_from(["", "diff-graph", &a_filename, &a_filename]);
+        let Commands::DiffGraph(parameters) = output.command else {
+            unreachable!()
+        };
+        assert_eq!(parameters.run()["identical"], json!(true));
+        let output = Cli::parse_from(["", "diff-graph", &a_filename, &b_filename, "--syndromes", &syndromes_filename]);
+        let Commands::DiffGraph(parameters) = output.command else {
+            unreachable!()
+        };
+        let output = parameters.run();
+        assert_eq!(output["identical"], json!(false));
+        assert_eq!(output["diff"]["weight_changes"].as_array().unwrap().len(), 2);
+        assert_eq!(output["diff"]["max_weight_change"], json!(200));
+        assert_eq!(output["diff"]["mean_weight_change"], json!(150.));
+        assert_eq!(output["syndromes"], json!(20));
+        assert_eq!(output["changed_corrections"], json!(3));
+        // the syndromes are not decoded on a graph with a different number of vertices
+        let mut extended = initializer.clone();
+        extended.vertex_num += 1;
+        std::fs::write(&b_filename, serde_json::to_string(&extended).unwrap()).unwrap();
+        let output = Cli::parse_from(["", "diff-graph", &a_filename, &b_filename, "--syndromes", &syndromes_filename]);
+        let Commands::DiffGraph(parameters) = output.command else {
+            unreachable!()
+        };
+        let output = parameters.run();
+        assert_eq!(output["changed_corrections"], json!(null));
+        assert!(output["impact_skipped"]
+            .as_str()
+            .unwrap()
+            .contains("different numbers of vertices"));
+        for filename in [a_filename, b_filename, syndromes_filename] {
+            std::fs::remove_file(filename).unwrap();
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use super::dual_module::EdgeWeightModifier;
 use super::util::*;
+use crate::prelude::*;
 use crate::priority_queue::PriorityQueue;
+#[cfg(feature = "parallel")]
 use crate::rayon::prelude::*;
-use std::collections::BTreeMap;
+use alloc::collections::BTreeMap;
 
 /// build complete graph out of skeleton graph using Dijkstra's algorithm
 #[derive(Debug, Clone)]
@@ -17,24 +19,71 @@ pub struct CompleteGraph {
     pub edge_modifier: EdgeWeightModifier,
     /// original edge weights
     pub weighted_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    /// whether each vertex is virtual; all false unless constructed with [`CompleteGraph::from_initializer`] or set by
+    /// [`CompleteGraph::set_virtual_vertices`]
+    pub is_virtual: Vec<bool>,
+    /// the priority queue of Dijkstra's algorithm, kept to reuse its memory
+    queue: DijkstraQueue,
+    /// the vertices settled by the last Dijkstra's algorithm, kept to reuse its memory
+    settled: Vec<VertexIndex>,
 }
 
+/// vertices are dense small integers, so hashing them with SipHash (the default) is an unnecessary cost on every
+/// decrease-key; the multiplicative hash keeps both the high and the low bits well distributed for the hash table
+#[derive(Debug, Clone, Default)]
+struct VertexIndexHasher(u64);
+
+impl core::hash::Hasher for VertexIndexHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x9e3779b97f4a7c15);
+    }
+    #[inline]
+    fn write_u32(&mut self, value: u32) {
+        self.write_u64(value as u64);
+    }
+    #[inline]
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+type DijkstraQueue = PriorityQueue<VertexIndex, PriorityElement, core::hash::BuildHasherDefault<VertexIndexHasher>>;
+
 #[derive(Debug, Clone)]
 pub struct CompleteGraphVertex {
     /// all skeleton graph edges connected to this vertex
     pub edges: BTreeMap<VertexIndex, Weight>,
     /// timestamp for Dijkstra's algorithm
     timestamp: FastClearTimestamp,
+    /// the previous vertex on the minimum-weight path found by the last Dijkstra's algorithm, valid only if `timestamp` is active
+    previous: VertexIndex,
+    /// the weight of the minimum-weight path found by the last Dijkstra's algorithm, valid only if `timestamp` is active
+    weight: Weight,
 }
 
 impl CompleteGraph {
-    /// create complete graph given skeleton graph
+    /// create complete graph given skeleton graph; it doesn't know the virtual vertices, so
+    /// [`CompleteGraph::nearby_virtual`] finds none until they are given by [`CompleteGraph::set_virtual_vertices`],
+    /// see [`CompleteGraph::from_initializer`]
     #[allow(clippy::unnecessary_cast)]
     pub fn new(vertex_num: VertexNum, weighted_edges: &[(VertexIndex, VertexIndex, Weight)]) -> Self {
         let mut vertices: Vec<CompleteGraphVertex> = (0..vertex_num)
             .map(|_| CompleteGraphVertex {
                 edges: BTreeMap::new(),
                 timestamp: 0,
+                previous: 0,
+                weight: 0,
             })
             .collect();
         for &(i, j, weight) in weighted_edges.iter() {
@@ -47,6 +96,25 @@ impl CompleteGraph {
             active_timestamp: 0,
             edge_modifier: EdgeWeightModifier::new(),
             weighted_edges: weighted_edges.to_owned(),
+            is_virtual: vec![false; vertex_num as usize],
+            queue: DijkstraQueue::with_default_hasher(),
+            settled: Vec::new(),
+        }
+    }
+
+    /// create complete graph from the solver initializer, remembering which vertices are virtual
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_initializer(initializer: &SolverInitializer) -> Self {
+        let mut complete_graph = Self::new(initializer.vertex_num, &initializer.weighted_edges);
+        complete_graph.set_virtual_vertices(&initializer.virtual_vertices);
+        complete_graph
+    }
+
+    /// mark the vertices as virtual, in addition to those already known
+    #[allow(clippy::unnecessary_cast)]
+    pub fn set_virtual_vertices(&mut self, virtual_vertices: &[VertexIndex]) {
+        for &virtual_vertex in virtual_vertices.iter() {
+            self.is_virtual[virtual_vertex as usize] = true;
         }
     }
 
@@ -108,74 +176,97 @@ impl CompleteGraph {
     }
 
     /// get all complete graph edges from the specific vertex, but will terminate if `terminate` vertex is found
-    #[allow(clippy::unnecessary_cast)]
     pub fn all_edges_with_terminate(
         &mut self,
         vertex: VertexIndex,
         terminate: VertexIndex,
     ) -> BTreeMap<VertexIndex, (VertexIndex, Weight)> {
+        self.all_edges_within(vertex, terminate, Weight::MAX)
+    }
+
+    /// run Dijkstra's algorithm from `vertex` until `terminate` is settled or all the remaining vertices are further
+    /// than `radius`; each settled vertex remembers its previous vertex and weight, and all of them except `vertex` are
+    /// appended to `self.settled` in the order of settlement
+    #[allow(clippy::unnecessary_cast)]
+    fn dijkstra(&mut self, vertex: VertexIndex, terminate: VertexIndex, radius: Weight) {
         let active_timestamp = self.invalidate_previous_dijkstra();
-        let mut pq = PriorityQueue::<EdgeIndex, PriorityElement>::new();
-        pq.push(vertex, PriorityElement::new(0, vertex));
-        let mut computed_edges = BTreeMap::<VertexIndex, (VertexIndex, Weight)>::new(); // { peer: (previous, weight) }
-        loop {
-            // until no more elements
-            if pq.is_empty() {
-                break;
+        self.settled.clear();
+        self.queue.clear();
+        self.queue.push(vertex, PriorityElement::with_hops(0, 0, vertex));
+        while let Some((target, PriorityElement { weight, hops, previous })) = self.queue.pop() {
+            if weight > radius {
+                break; // all the remaining vertices are even further away
             }
-            let (target, PriorityElement { weight, previous }) = pq.pop().unwrap();
-            // eprintln!("target: {}, weight: {}, next: {}", target, weight, next);
-            debug_assert!({
-                !computed_edges.contains_key(&target) // this entry shouldn't have been set
-            });
             // update entry
-            self.vertices[target as usize].timestamp = active_timestamp; // mark as visited
+            let target_vertex = &mut self.vertices[target as usize];
+            debug_assert_ne!(
+                target_vertex.timestamp, active_timestamp,
+                "a vertex must not be settled twice"
+            );
+            target_vertex.timestamp = active_timestamp; // mark as visited
+            target_vertex.previous = previous;
+            target_vertex.weight = weight;
             if target != vertex {
-                computed_edges.insert(target, (previous, weight));
+                self.settled.push(target);
                 if target == terminate {
                     break; // early terminate
                 }
             }
             // add its neighbors to priority queue
             for (&neighbor, &neighbor_weight) in self.vertices[target as usize].edges.iter() {
+                if self.vertices[neighbor as usize].timestamp == active_timestamp {
+                    continue; // already settled, which is cheaper to check than looking up the queue
+                }
                 let edge_weight = weight + neighbor_weight;
+                let edge_hops = hops + 1;
                 if let Some(PriorityElement {
                     weight: existing_weight,
+                    hops: existing_hops,
                     previous: existing_previous,
-                }) = pq.get_priority(&neighbor)
+                }) = self.queue.get_priority(&neighbor)
                 {
-                    // update the priority if weight is smaller or weight is equal but distance is smaller
-                    // this is necessary if the graph has weight-0 edges, which could lead to cycles in the graph and cause deadlock
-                    let mut update = &edge_weight < existing_weight;
-                    if &edge_weight == existing_weight {
-                        let distance = if neighbor > previous {
-                            neighbor - previous
-                        } else {
-                            previous - neighbor
-                        };
-                        let existing_distance = if &neighbor > existing_previous {
-                            neighbor - existing_previous
-                        } else {
-                            existing_previous - neighbor
-                        };
+                    // update the priority if weight is smaller, or weight is equal but it takes fewer hops, or both are equal
+                    // but distance is smaller; the latter is necessary if the graph has weight-0 edges, which could lead to
+                    // cycles in the graph and cause deadlock
+                    let mut update = (edge_weight, edge_hops) < (*existing_weight, *existing_hops);
+                    if &edge_weight == existing_weight && &edge_hops == existing_hops {
+                        let distance = neighbor.abs_diff(previous);
+                        let existing_distance = neighbor.abs_diff(*existing_previous);
                         // prevent loop by enforcing strong non-descending
                         if distance < existing_distance || (distance == existing_distance && &previous < existing_previous) {
                             update = true;
                         }
                     }
                     if update {
-                        pq.change_priority(&neighbor, PriorityElement::new(edge_weight, target));
+                        self.queue
+                            .change_priority(&neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
                     }
                 } else {
-                    // insert new entry only if neighbor has not been visited
-                    if self.vertices[neighbor as usize].timestamp != active_timestamp {
-                        pq.push(neighbor, PriorityElement::new(edge_weight, target));
-                    }
+                    self.queue
+                        .push(neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
                 }
             }
         }
-        // println!("[debug] computed_edges: {:?}", computed_edges);
-        computed_edges
+    }
+
+    /// get all complete graph edges from the specific vertex whose weight is no larger than `radius`,
+    /// but will terminate if `terminate` vertex is found
+    #[allow(clippy::unnecessary_cast)]
+    pub fn all_edges_within(
+        &mut self,
+        vertex: VertexIndex,
+        terminate: VertexIndex,
+        radius: Weight,
+    ) -> BTreeMap<VertexIndex, (VertexIndex, Weight)> {
+        self.dijkstra(vertex, terminate, radius);
+        // { peer: (previous, weight) }
+        self.settled
+            .iter()
+            .map(|&peer| {
+                let peer_vertex = &self.vertices[peer as usize];
+                (peer, (peer_vertex.previous, peer_vertex.weight))
+            })
+            .collect()
     }
 
     /// get all complete graph edges from the specific vertex
@@ -183,18 +274,34 @@ impl CompleteGraph {
         self.all_edges_with_terminate(vertex, VertexIndex::MAX)
     }
 
-    /// get minimum-weight path between any two vertices `a` and `b`, in the order `a -> path[0].0 -> path[1].0 -> .... -> path[-1].0` and it's guaranteed that path[-1].0 = b
+    /// get all virtual vertices within `radius` from `defect`, sorted by weight (and then vertex index);
+    /// this requires the complete graph to know the virtual vertices, see [`CompleteGraph::from_initializer`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn nearby_virtual(&mut self, defect: VertexIndex, radius: Weight) -> Vec<(VertexIndex, Weight)> {
+        let mut nearby: Vec<(VertexIndex, Weight)> = self
+            .all_edges_within(defect, VertexIndex::MAX, radius)
+            .into_iter()
+            .filter(|(peer, _)| self.is_virtual[*peer as usize])
+            .map(|(peer, (_, weight))| (peer, weight))
+            .collect();
+        nearby.sort_by_key(|&(peer, weight)| (weight, peer));
+        nearby
+    }
+
+    /// get minimum-weight path between any two vertices `a` and `b`, in the order `a -> path[0].0 -> path[1].0 -> .... -> path[-1].0` and it's guaranteed that path[-1].0 = b;
+    /// the search stops as soon as `b` is settled and the path is traced back without collecting the other settled vertices
+    #[allow(clippy::unnecessary_cast)]
     pub fn get_path(&mut self, a: VertexIndex, b: VertexIndex) -> (Vec<(VertexIndex, Weight)>, Weight) {
         assert_ne!(a, b, "cannot get path between the same vertex");
-        let edges = self.all_edges_with_terminate(a, b);
-        // println!("edges: {:?}", edges);
+        self.dijkstra(a, b, Weight::MAX);
+        assert!(
+            self.vertices[b as usize].timestamp == self.active_timestamp,
+            "vertex {b} is not reachable from vertex {a}"
+        );
         let mut vertex = b;
         let mut path = Vec::new();
-        loop {
-            if vertex == a {
-                break;
-            }
-            let &(previous, weight) = &edges[&vertex];
+        while vertex != a {
+            let CompleteGraphVertex { previous, weight, .. } = self.vertices[vertex as usize];
             path.push((vertex, weight));
             if path.len() > 1 {
                 let previous_index = path.len() - 2;
@@ -203,10 +310,30 @@ impl CompleteGraph {
             vertex = previous;
         }
         path.reverse();
-        (path, edges[&b].1)
+        (path, self.vertices[b as usize].weight)
     }
 }
 
+/// evaluate `f` on every vertex using the thread pool
+#[cfg(feature = "parallel")]
+fn map_vertices<T: Send>(
+    thread_pool: &rayon::ThreadPool,
+    vertex_num: usize,
+    f: impl Fn(usize) -> T + Sync + Send,
+) -> Vec<T> {
+    let mut results = vec![];
+    thread_pool.scope(|_| {
+        (0..vertex_num).into_par_iter().map(f).collect_into_vec(&mut results);
+    });
+    results
+}
+
+/// evaluate `f` on every vertex sequentially
+#[cfg(not(feature = "parallel"))]
+fn map_vertices<T>(_thread_pool: &(), vertex_num: usize, f: impl Fn(usize) -> T) -> Vec<T> {
+    (0..vertex_num).map(f).collect()
+}
+
 #[derive(Clone)]
 pub struct PrebuiltCompleteGraph {
     /// number of vertices
@@ -218,13 +345,22 @@ pub struct PrebuiltCompleteGraph {
 }
 
 impl PrebuiltCompleteGraph {
+    /// build the complete graph using a thread pool of `thread_pool_size` (0 for the rayon default); without the
+    /// `parallel` feature the vertices are processed sequentially and `thread_pool_size` is ignored
     #[allow(clippy::unnecessary_cast)]
     pub fn new_threaded(initializer: &SolverInitializer, thread_pool_size: usize) -> Self {
-        let mut thread_pool_builder = rayon::ThreadPoolBuilder::new();
-        if thread_pool_size != 0 {
-            thread_pool_builder = thread_pool_builder.num_threads(thread_pool_size);
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "parallel")] {
+                let mut thread_pool_builder = rayon::ThreadPoolBuilder::new();
+                if thread_pool_size != 0 {
+                    thread_pool_builder = thread_pool_builder.num_threads(thread_pool_size);
+                }
+                let thread_pool = thread_pool_builder.build().expect("creating thread pool failed");
+            } else {
+                let _ = thread_pool_size;
+                let thread_pool = ();
+            }
         }
-        let thread_pool = thread_pool_builder.build().expect("creating thread pool failed");
         let vertex_num = initializer.vertex_num as usize;
         // first collect virtual vertices and real vertices
         let mut is_virtual = vec![false; vertex_num];
@@ -232,64 +368,52 @@ impl PrebuiltCompleteGraph {
             is_virtual[virtual_vertex as usize] = true;
         }
         type Result = (BTreeMap<VertexIndex, Weight>, Option<(VertexIndex, Weight)>);
-        let mut results: Vec<Result> = vec![];
-        thread_pool.scope(|_| {
-            (0..vertex_num)
-                .into_par_iter()
-                .map(|vertex_index| {
-                    let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
-                    let mut edges = BTreeMap::new();
-                    let mut virtual_boundary_weight = None;
-                    if !is_virtual[vertex_index] {
-                        // only build graph for non-virtual vertices
-                        let complete_graph_edges = complete_graph.all_edges(vertex_index as VertexIndex);
-                        let mut boundary: Option<(VertexIndex, Weight)> = None;
-                        for (&peer, &(_, weight)) in complete_graph_edges.iter() {
-                            if !is_virtual[peer as usize] {
-                                edges.insert(peer, weight);
-                            }
-                            if is_virtual[peer as usize] && (boundary.is_none() || weight < boundary.as_ref().unwrap().1) {
-                                boundary = Some((peer, weight));
-                            }
-                        }
-                        virtual_boundary_weight = boundary;
+        let results: Vec<Result> = map_vertices(&thread_pool, vertex_num, |vertex_index| {
+            let mut complete_graph = CompleteGraph::from_initializer(initializer);
+            let mut edges = BTreeMap::new();
+            let mut virtual_boundary_weight = None;
+            if !is_virtual[vertex_index] {
+                // only build graph for non-virtual vertices
+                let complete_graph_edges = complete_graph.all_edges(vertex_index as VertexIndex);
+                let mut boundary: Option<(VertexIndex, Weight)> = None;
+                for (&peer, &(_, weight)) in complete_graph_edges.iter() {
+                    if !is_virtual[peer as usize] {
+                        edges.insert(peer, weight);
+                    }
+                    if is_virtual[peer as usize] && (boundary.is_none() || weight < boundary.as_ref().unwrap().1) {
+                        boundary = Some((peer, weight));
                     }
-                    (edges, virtual_boundary_weight)
-                })
-                .collect_into_vec(&mut results);
+                }
+                virtual_boundary_weight = boundary;
+            }
+            (edges, virtual_boundary_weight)
         });
         // optimization: remove edges in the middle
         type UnzipResult = (Vec<BTreeMap<VertexIndex, Weight>>, Vec<Option<(VertexIndex, Weight)>>);
         let (mut edges, virtual_boundary_weight): UnzipResult = results.into_iter().unzip();
-        let mut to_be_removed_vec: Vec<Vec<VertexIndex>> = vec![];
-        thread_pool.scope(|_| {
-            (0..vertex_num)
-                .into_par_iter()
-                .map(|vertex_index| {
-                    let mut to_be_removed = vec![];
-                    if !is_virtual[vertex_index] {
-                        for (&peer, &weight) in edges[vertex_index].iter() {
-                            let boundary_weight = if let Some((_, weight)) = virtual_boundary_weight[vertex_index as usize] {
-                                weight
-                            } else {
-                                Weight::MAX
-                            };
-                            let boundary_weight_peer = if let Some((_, weight)) = virtual_boundary_weight[peer as usize] {
-                                weight
-                            } else {
-                                Weight::MAX
-                            };
-                            if boundary_weight != Weight::MAX
-                                && boundary_weight_peer != Weight::MAX
-                                && weight > boundary_weight + boundary_weight_peer
-                            {
-                                to_be_removed.push(peer);
-                            }
-                        }
+        let to_be_removed_vec: Vec<Vec<VertexIndex>> = map_vertices(&thread_pool, vertex_num, |vertex_index| {
+            let mut to_be_removed = vec![];
+            if !is_virtual[vertex_index] {
+                for (&peer, &weight) in edges[vertex_index].iter() {
+                    let boundary_weight = if let Some((_, weight)) = virtual_boundary_weight[vertex_index as usize] {
+                        weight
+                    } else {
+                        Weight::MAX
+                    };
+                    let boundary_weight_peer = if let Some((_, weight)) = virtual_boundary_weight[peer as usize] {
+                        weight
+                    } else {
+                        Weight::MAX
+                    };
+                    if boundary_weight != Weight::MAX
+                        && boundary_weight_peer != Weight::MAX
+                        && weight > boundary_weight + boundary_weight_peer
+                    {
+                        to_be_removed.push(peer);
                     }
-                    to_be_removed
-                })
-                .collect_into_vec(&mut to_be_removed_vec);
+                }
+            }
+            to_be_removed
         });
         for vertex_index in 0..vertex_num {
             for peer in to_be_removed_vec[vertex_index].iter() {
@@ -318,35 +442,208 @@ impl PrebuiltCompleteGraph {
     }
 }
 
-#[derive(Eq, Debug)]
+#[derive(Eq, Debug, Clone)]
 pub struct PriorityElement {
     pub weight: Weight,
+    /// number of edges from the source; among equal weights, fewer hops go first so that a search does not sweep
+    /// through every vertex of a zero-weight region (e.g. erasures) before reaching the target
+    pub hops: usize,
     pub previous: VertexIndex,
 }
 
-impl std::cmp::PartialEq for PriorityElement {
+impl core::cmp::PartialEq for PriorityElement {
     #[inline]
     fn eq(&self, other: &PriorityElement) -> bool {
-        self.weight == other.weight
+        self.weight == other.weight && self.hops == other.hops
     }
 }
 
-impl std::cmp::PartialOrd for PriorityElement {
+impl core::cmp::PartialOrd for PriorityElement {
     #[inline]
-    fn partial_cmp(&self, other: &PriorityElement) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &PriorityElement) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl std::cmp::Ord for PriorityElement {
+impl core::cmp::Ord for PriorityElement {
     #[inline]
-    fn cmp(&self, other: &PriorityElement) -> std::cmp::Ordering {
-        other.weight.cmp(&self.weight) // reverse `self` and `other` to prioritize smaller weight
+    fn cmp(&self, other: &PriorityElement) -> core::cmp::Ordering {
+        // reverse `self` and `other` to prioritize smaller weight and then fewer hops
+        (other.weight, other.hops).cmp(&(self.weight, self.hops))
     }
 }
 
 impl PriorityElement {
     pub fn new(weight: Weight, previous: VertexIndex) -> Self {
-        Self { weight, previous }
+        Self::with_hops(weight, 0, previous)
+    }
+
+    pub fn with_hops(weight: Weight, hops: usize, previous: VertexIndex) -> Self {
+        Self { weight, hops, previous }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::*;
+
+    /// in a zero-weight region, a path to a neighbor must only settle the vertices one hop away instead of sweeping
+    /// the region in the order of vertex index
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn complete_graph_erased_region_path_1() {
+        // cargo test complete_graph_erased_region_path_1 -- --nocapture
+        let code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut complete_graph = CompleteGraph::from_initializer(&initializer);
+        let erasures: Vec<EdgeIndex> = (0..initializer.weighted_edges.len() as EdgeIndex).collect();
+        complete_graph.load_erasures(&erasures);
+        for &(vertex_1, vertex_2, _) in initializer.weighted_edges.iter() {
+            let (path, weight) = complete_graph.get_path(vertex_1, vertex_2);
+            assert_eq!((path.len(), weight), (1, 0));
+            assert!(
+                complete_graph.settled.len() <= 4,
+                "swept {} vertices",
+                complete_graph.settled.len()
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn complete_graph_nearby_virtual_1() {
+        // cargo test complete_graph_nearby_virtual_1 -- --nocapture
+        let half_weight = 500;
+        let code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut complete_graph = CompleteGraph::from_initializer(&initializer);
+        let is_virtual = complete_graph.is_virtual.clone();
+        for defect in (0..initializer.vertex_num).filter(|&vertex| !is_virtual[vertex as usize]) {
+            let all_virtual = complete_graph.nearby_virtual(defect, Weight::MAX);
+            assert_eq!(all_virtual.len(), initializer.virtual_vertices.len());
+            assert!(
+                all_virtual.windows(2).all(|pair| pair[0].1 <= pair[1].1),
+                "must be sorted by weight"
+            );
+            for &(virtual_vertex, weight) in all_virtual.iter() {
+                assert!(is_virtual[virtual_vertex as usize]);
+                assert_eq!(complete_graph.get_path(defect, virtual_vertex).1, weight);
+            }
+            // limiting the radius must return exactly the prefix of those within the radius
+            let radius = all_virtual[0].1 + 2 * half_weight;
+            let nearby = complete_graph.nearby_virtual(defect, radius);
+            let expected: Vec<_> = all_virtual.iter().filter(|(_, weight)| *weight <= radius).cloned().collect();
+            assert_eq!(nearby, expected);
+            assert!(complete_graph.nearby_virtual(defect, all_virtual[0].1 - 1).is_empty());
+        }
+        // the skeleton graph alone doesn't know the virtual vertices until they are given
+        let mut skeleton_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        let defect = (0..initializer.vertex_num)
+            .find(|&vertex| !is_virtual[vertex as usize])
+            .unwrap();
+        assert!(skeleton_graph.nearby_virtual(defect, Weight::MAX).is_empty());
+        skeleton_graph.set_virtual_vertices(&initializer.virtual_vertices);
+        assert_eq!(
+            skeleton_graph.nearby_virtual(defect, Weight::MAX),
+            complete_graph.nearby_virtual(defect, Weight::MAX)
+        );
+    }
+
+    /// the previous implementation of [`CompleteGraph::get_path`]: a Dijkstra's algorithm using SipHash in the priority
+    /// queue, collecting all the settled vertices into a map before tracing back the path
+    #[allow(clippy::unnecessary_cast)]
+    fn reference_get_path(
+        complete_graph: &CompleteGraph,
+        a: VertexIndex,
+        b: VertexIndex,
+    ) -> (Vec<(VertexIndex, Weight)>, Weight) {
+        let mut visited = vec![false; complete_graph.vertex_num as usize];
+        let mut pq = PriorityQueue::<VertexIndex, PriorityElement>::new();
+        pq.push(a, PriorityElement::new(0, a));
+        let mut computed_edges = BTreeMap::<VertexIndex, (VertexIndex, Weight)>::new();
+        while let Some((target, PriorityElement { weight, hops, previous })) = pq.pop() {
+            visited[target as usize] = true;
+            if target != a {
+                computed_edges.insert(target, (previous, weight));
+                if target == b {
+                    break;
+                }
+            }
+            for (&neighbor, &neighbor_weight) in complete_graph.vertices[target as usize].edges.iter() {
+                let edge_weight = weight + neighbor_weight;
+                let edge_hops = hops + 1;
+                if let Some(existing) = pq.get_priority(&neighbor) {
+                    let mut update = (edge_weight, edge_hops) < (existing.weight, existing.hops);
+                    if edge_weight == existing.weight && edge_hops == existing.hops {
+                        let distance = neighbor.abs_diff(previous);
+                        let existing_distance = neighbor.abs_diff(existing.previous);
+                        if distance < existing_distance || (distance == existing_distance && previous < existing.previous) {
+                            update = true;
+                        }
+                    }
+                    if update {
+                        pq.change_priority(&neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
+                    }
+                } else if !visited[neighbor as usize] {
+                    pq.push(neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
+                }
+            }
+        }
+        let mut vertex = b;
+        let mut path = Vec::new();
+        while vertex != a {
+            let (previous, weight) = computed_edges[&vertex];
+            path.push((vertex, weight));
+            if path.len() > 1 {
+                let previous_index = path.len() - 2;
+                path[previous_index].1 -= weight;
+            }
+            vertex = previous;
+        }
+        path.reverse();
+        (path, computed_edges[&b].1)
+    }
+
+    /// `get_path` returns exactly the same paths as the previous implementation, including the tie-breaking among
+    /// equal-weight paths, and is faster on an 11x11 planar code
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn complete_graph_get_path_benchmark_1() {
+        // cargo test --release complete_graph_get_path_benchmark_1 -- --nocapture
+        let initializer = CodeCapacityPlanarCode::new(11, 0.1, 500).get_initializer();
+        let mut complete_graph = CompleteGraph::from_initializer(&initializer);
+        let pairs: Vec<(VertexIndex, VertexIndex)> = (0..initializer.vertex_num)
+            .step_by(4)
+            .flat_map(|a| (0..initializer.vertex_num).filter(move |&b| b != a).map(move |b| (a, b)))
+            .collect();
+        let begin = std::time::Instant::now();
+        let reference_paths: Vec<_> = pairs
+            .iter()
+            .map(|&(a, b)| reference_get_path(&complete_graph, a, b))
+            .collect();
+        let reference_time = begin.elapsed().as_secs_f64();
+        let begin = std::time::Instant::now();
+        let paths: Vec<_> = pairs.iter().map(|&(a, b)| complete_graph.get_path(a, b)).collect();
+        let time = begin.elapsed().as_secs_f64();
+        assert_eq!(paths, reference_paths);
+        println!(
+            "{} paths, previous: {reference_time:.3e}s, current: {time:.3e}s, speedup: {:.2}",
+            pairs.len(),
+            reference_time / time
+        );
+        // erasures introduce many equal-weight paths, which must be broken in the same way
+        let mut code = PhenomenologicalPlanarCode::new(11, 4, 0.01, 500);
+        code.set_erasure_probability(0.2);
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(0);
+        assert!(!syndrome_pattern.erasures.is_empty());
+        let mut complete_graph = CompleteGraph::from_initializer(&initializer);
+        complete_graph.load_erasures(&syndrome_pattern.erasures);
+        for a in (0..initializer.vertex_num).step_by(23) {
+            for b in (0..initializer.vertex_num).step_by(5).filter(|&b| b != a) {
+                assert_eq!(complete_graph.get_path(a, b), reference_get_path(&complete_graph, a, b));
+            }
+        }
     }
 }
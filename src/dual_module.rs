@@ -3,13 +3,14 @@
 //! Generics for dual modules, defining the necessary interfaces for a dual module
 //!
 
-#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references))]
+#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references, clippy::needless_borrow))]
 
-use core::cmp::Ordering;
-use std::collections::{BTreeMap, HashSet};
-use std::num::NonZeroUsize;
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
 #[cfg(not(feature = "dangerous_pointer"))]
-use std::sync::Arc;
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+use core::num::NonZeroUsize;
 
 use nonzero::nonzero as nz;
 
@@ -47,6 +48,88 @@ pub enum DualNodeGrowState {
     Shrink,
 }
 
+/// an illegal grow state transition issued by a primal module, see [`DualModuleInterfacePtr::check_grow_state`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrowStateError {
+    /// the node doesn't belong to this interface, e.g. a blossom that has been expanded or a node of a previous decoding
+    NotInInterface { node_index: NodeIndex },
+    /// only outer nodes can change grow state; a node inside a blossom always follows its outermost blossom
+    InsideBlossom {
+        node_index: NodeIndex,
+        blossom_index: NodeIndex,
+    },
+    /// a shrinking node must have a non-negative dual variable; note that shrinking a defect vertex with zero dual
+    /// variable is legal and results in [`MaxUpdateLength::VertexShrinkStop`]
+    NegativeDualVariable { node_index: NodeIndex, dual_variable: Weight },
+    /// the dual module rejects the transition, e.g. it doesn't host the node, see [`DualModuleImpl::check_grow_state`]
+    DualModule { node_index: NodeIndex, message: String },
+}
+
+impl core::fmt::Display for GrowStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotInInterface { node_index } => write!(f, "dual node {node_index} doesn't belong to the interface"),
+            Self::InsideBlossom {
+                node_index,
+                blossom_index,
+            } => write!(
+                f,
+                "dual node {node_index} is inside blossom {blossom_index}, only outer nodes can change grow state"
+            ),
+            Self::NegativeDualVariable {
+                node_index,
+                dual_variable,
+            } => write!(
+                f,
+                "dual node {node_index} cannot shrink with negative dual variable {dual_variable}"
+            ),
+            Self::DualModule { node_index, message } => {
+                write!(f, "dual node {node_index} rejected by the dual module: {message}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for GrowStateError {}
+
+/// an illegal growth of a single dual node, see [`DualModuleImpl::grow_dual_node_checked`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrowthError {
+    /// the node doesn't belong to the interface
+    NotInInterface { node_index: NodeIndex },
+    /// only outer nodes can grow; a node inside a blossom always follows its outermost blossom
+    InsideBlossom {
+        node_index: NodeIndex,
+        blossom_index: NodeIndex,
+    },
+    /// the requested length exceeds what the node can grow (positive) or shrink (negative) alone without
+    /// over-growing an edge or shrinking below zero; `allowed` has the same sign as `requested`
+    ExceedsBound { requested: Weight, allowed: Weight },
+    /// the dual module implementation doesn't support growing a single dual node alone
+    Unsupported,
+}
+
+impl core::fmt::Display for GrowthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotInInterface { node_index } => write!(f, "dual node {node_index} doesn't belong to the interface"),
+            Self::InsideBlossom {
+                node_index,
+                blossom_index,
+            } => write!(
+                f,
+                "dual node {node_index} is inside blossom {blossom_index}, only outer nodes can grow"
+            ),
+            Self::ExceedsBound { requested, allowed } => {
+                write!(f, "cannot grow dual node by {requested}, at most {allowed}")
+            }
+            Self::Unsupported => write!(f, "the dual module implementation doesn't support growing a single dual node"),
+        }
+    }
+}
+
+impl core::error::Error for GrowthError {}
+
 impl DualNodeGrowState {
     pub fn is_against(&self, other: &Self) -> bool {
         matches!(
@@ -106,7 +189,7 @@ pub enum MaxUpdateLength {
 
 cfg_if::cfg_if! {
     if #[cfg(feature="ordered_conflicts")] {
-        use std::collections::BinaryHeap;
+        use alloc::collections::BinaryHeap;
         pub type ConflictList = BinaryHeap<MaxUpdateLength>;
     } else {
         pub type ConflictList = Vec<MaxUpdateLength>;
@@ -140,7 +223,7 @@ impl GroupMaxUpdateLength {
             for max_update_length in list.iter() {
                 max_update_length.update();
             }
-            for (_, max_update_length) in pending_stops.iter() {
+            for max_update_length in pending_stops.values() {
                 max_update_length.update();
             }
         }
@@ -179,7 +262,7 @@ impl GroupMaxUpdateLength {
         match self {
             Self::NonZeroGrow((current_length, current_has_empty_boundary_node)) => {
                 if let MaxUpdateLength::NonZeroGrow((length, has_empty_boundary_node)) = max_update_length {
-                    *current_length = std::cmp::min(*current_length, length);
+                    *current_length = core::cmp::min(*current_length, length);
                     *current_has_empty_boundary_node |= has_empty_boundary_node;
                 // or
                 } else {
@@ -210,13 +293,13 @@ impl GroupMaxUpdateLength {
         match self {
             Self::NonZeroGrow(current_length) => match other {
                 Self::NonZeroGrow(length) => {
-                    *current_length = std::cmp::min(*current_length, length);
+                    *current_length = core::cmp::min(*current_length, length);
                 }
                 Self::Conflicts((mut other_list, mut other_pending_stops)) => {
                     let mut list = ConflictList::new();
                     let mut pending_stops = BTreeMap::new();
-                    std::mem::swap(&mut list, &mut other_list);
-                    std::mem::swap(&mut pending_stops, &mut other_pending_stops);
+                    core::mem::swap(&mut list, &mut other_list);
+                    core::mem::swap(&mut pending_stops, &mut other_pending_stops);
                     *self = Self::Conflicts((list, pending_stops));
                 }
             },
@@ -306,7 +389,8 @@ pub struct DualNode {
     pub dual_variable_cache: (Weight, Weight),
     /// belonging of the dual module interface; a dual node is never standalone
     pub belonging: DualModuleInterfaceWeak,
-    /// how many defect vertices in this dual node
+    /// how many defect vertices in this dual node: 1 for a defect vertex, and the sum of its constituents for a blossom;
+    /// note that it counts defects, not dual nodes, so a blossom of 3 vertices and a blossom of 3 sub-blossoms differ
     pub defect_size: NonZeroUsize,
 }
 
@@ -328,6 +412,12 @@ impl DualNode {
 pub type DualNodePtr = ArcManualSafeLock<DualNode>;
 pub type DualNodeWeak = WeakManualSafeLock<DualNode>;
 
+impl PointerTag for DualNode {
+    fn pointer_tag(&self) -> String {
+        format!("DualNode {}", self.index)
+    }
+}
+
 impl Ord for DualNodePtr {
     // a consistent compare (during a single program)
     fn cmp(&self, other: &Self) -> Ordering {
@@ -359,16 +449,16 @@ impl PartialOrd for DualNodePtr {
     }
 }
 
-impl std::fmt::Debug for DualNodePtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for DualNodePtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.update(); // to make sure index is up-to-date
         let dual_node = self.read_recursive(); // reading index is consistent
         write!(f, "{}", dual_node.index)
     }
 }
 
-impl std::fmt::Debug for DualNodeWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for DualNodeWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
@@ -399,6 +489,12 @@ impl DualNodePtr {
         self.read_recursive().index
     }
 
+    /// the number of defect vertices inside this dual node, see [`DualNode::defect_size`]; the primal module adds them
+    /// up to get the size of an alternating tree, which is compared against `max_tree_size`
+    pub fn defect_size(&self) -> usize {
+        self.read_recursive().defect_size.get()
+    }
+
     /// helper function to set grow state with sanity check
     fn set_grow_state(&self, grow_state: DualNodeGrowState) {
         let mut dual_node = self.write();
@@ -507,15 +603,21 @@ pub struct DualModuleInterface {
 pub type DualModuleInterfacePtr = ArcManualSafeLock<DualModuleInterface>;
 pub type DualModuleInterfaceWeak = WeakManualSafeLock<DualModuleInterface>;
 
-impl std::fmt::Debug for DualModuleInterfacePtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for DualModuleInterface {
+    fn pointer_tag(&self) -> String {
+        format!("DualModuleInterface of unit {}", self.unit_index)
+    }
+}
+
+impl core::fmt::Debug for DualModuleInterfacePtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let interface = self.read_recursive();
         write!(f, "{}", interface.unit_index)
     }
 }
 
-impl std::fmt::Debug for DualModuleInterfaceWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for DualModuleInterfaceWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
@@ -544,6 +646,14 @@ pub trait DualModuleImpl {
         self.add_dual_node(dual_node_ptr)
     }
 
+    /// add many defect nodes at once, in the order of their indices; implementations can override it to avoid the
+    /// per-node overhead when loading a syndrome with many defects
+    fn add_defect_nodes_batch(&mut self, dual_node_ptrs: &[DualNodePtr]) {
+        for dual_node_ptr in dual_node_ptrs.iter() {
+            self.add_defect_node(dual_node_ptr);
+        }
+    }
+
     #[inline(always)]
     /// helper function to specifically add a blossom node
     fn add_blossom(&mut self, dual_node_ptr: &DualNodePtr) {
@@ -564,6 +674,11 @@ pub trait DualModuleImpl {
     /// update grow state
     fn set_grow_state(&mut self, dual_node_ptr: &DualNodePtr, grow_state: DualNodeGrowState);
 
+    /// optionally check that the dual module can take this grow state transition, see [`DualModuleInterfacePtr::check_grow_state`]
+    fn check_grow_state(&self, _dual_node_ptr: &DualNodePtr, _grow_state: DualNodeGrowState) -> Result<(), String> {
+        Ok(())
+    }
+
     /// An optional function that helps to break down the implementation of [`DualModuleImpl::compute_maximum_update_length`]
     /// check the maximum length to grow (shrink) specific dual node, if length is 0, give the reason of why it cannot further grow (shrink).
     /// if `is_grow` is false, return `length` <= 0, in any case |`length`| is maximized so that at least one edge becomes fully grown or fully not-grown.
@@ -581,9 +696,28 @@ pub trait DualModuleImpl {
     /// this number will be 0 if any conflicting reason presents
     fn compute_maximum_update_length(&mut self) -> GroupMaxUpdateLength;
 
-    /// An optional function that can manipulate individual dual node, not necessarily supported by all implementations
-    fn grow_dual_node(&mut self, _dual_node_ptr: &DualNodePtr, _length: Weight) {
-        panic!("the dual module implementation doesn't support this function, please use another dual module")
+    /// An optional function that can manipulate individual dual node, not necessarily supported by all implementations;
+    /// it panics whenever [`DualModuleImpl::grow_dual_node_checked`] returns an error
+    fn grow_dual_node(&mut self, dual_node_ptr: &DualNodePtr, length: Weight) {
+        if let Err(error) = self.grow_dual_node_checked(dual_node_ptr, length) {
+            panic!("{error}")
+        }
+    }
+
+    /// grow (positive length) or shrink (negative length) a single dual node alone, returning an error instead of
+    /// over-growing an edge or shrinking below zero; the state is not modified on error. The default implementation
+    /// reports [`GrowthError::Unsupported`]
+    fn grow_dual_node_checked(&mut self, _dual_node_ptr: &DualNodePtr, _length: Weight) -> Result<(), GrowthError> {
+        Err(GrowthError::Unsupported)
+    }
+
+    /// grow (positive length) or shrink (negative length) an arbitrary subset of dual nodes as a single step, so that
+    /// a shrinking node may give way to a growing node on a shared tight edge; the default implementation simply
+    /// grows the nodes one by one in the given order
+    fn grow_subset(&mut self, nodes: &[(DualNodePtr, Weight)]) {
+        for (dual_node_ptr, length) in nodes.iter() {
+            self.grow_dual_node(dual_node_ptr, *length);
+        }
     }
 
     /// grow a specific length globally, length must be positive.
@@ -609,6 +743,38 @@ pub trait DualModuleImpl {
         self.load_edge_modifier(&edge_modifier);
     }
 
+    /// optional support for listing all the virtual vertices that a growing dual node touches at the moment, each with
+    /// the touching dual node inside it, while [`MaxUpdateLength::TouchingVirtual`] only reports one of them; mirrored
+    /// vertices are excluded. An empty list means the dual module doesn't support it
+    fn touching_virtual_vertices(&mut self, _dual_node_ptr: &DualNodePtr) -> Vec<(DualNodePtr, VertexIndex)> {
+        vec![]
+    }
+
+    /// optional support for pre-reserving the growable structures to avoid reallocation in the middle of decoding
+    fn reserve_capacity(&mut self, _hints: &CapacityHints) {}
+
+    /// the high-water marks of the growable structures observed in all previous decodings, if supported
+    fn observed_capacities(&self) -> CapacityHints {
+        CapacityHints::default()
+    }
+
+    /// the maximum number of simultaneously active (growing or shrinking) dual nodes in the current decoding,
+    /// if supported
+    fn peak_active_nodes(&self) -> usize {
+        0
+    }
+
+    /// check that the dual module is clean after [`DualModuleImpl::clear`], e.g. without leftover erasures
+    fn check_clean(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// optional support for finding a minimum-weight path consisting of only tight edges between two vertices;
+    /// returns `None` if the dual module doesn't support it or if there is no such path
+    fn get_tight_path(&self, _vertex_1: VertexIndex, _vertex_2: VertexIndex) -> Option<Vec<EdgeIndex>> {
+        None
+    }
+
     /// prepare a list of nodes as shrinking state; useful in creating a blossom
     fn prepare_nodes_shrink(&mut self, _nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
         panic!("the dual module implementation doesn't support this function, please use another dual module")
@@ -626,7 +792,7 @@ pub trait DualModuleImpl {
     /// create a partitioned dual module (hosting only a subgraph and subset of dual nodes) to be used in the parallel dual module
     fn new_partitioned(_partitioned_initializer: &PartitionedSolverInitializer) -> Self
     where
-        Self: std::marker::Sized,
+        Self: core::marker::Sized,
     {
         panic!("the dual module implementation doesn't support this function, please use another dual module")
     }
@@ -805,9 +971,12 @@ impl DualModuleInterfacePtr {
     }
 
     pub fn load(&self, syndrome_pattern: &SyndromePattern, dual_module_impl: &mut impl DualModuleImpl) {
-        for vertex_idx in syndrome_pattern.defect_vertices.iter() {
-            self.create_defect_node(*vertex_idx, dual_module_impl);
-        }
+        log_span!(DEBUG, "load", defects = syndrome_pattern.defect_vertices.len());
+        assert!(
+            syndrome_pattern.erasure_regions.is_empty() && syndrome_pattern.uncertain_vertices.is_empty(),
+            "erasure regions and uncertain vertices must be expanded before loading, see `ErasureRegionIndex::expand`"
+        );
+        self.create_defect_nodes(&syndrome_pattern.defect_vertices, dual_module_impl);
         if !syndrome_pattern.erasures.is_empty() {
             assert!(
                 syndrome_pattern.dynamic_weights.is_empty(),
@@ -835,6 +1004,28 @@ impl DualModuleInterfacePtr {
         interface.children = None;
     }
 
+    /// check that the interface is clean after [`DualModuleInterfacePtr::clear`]
+    pub fn check_clean(&self) -> Result<(), String> {
+        let interface = self.read_recursive();
+        if interface.nodes_length != 0 {
+            return Err(format!("interface still has {} dual nodes", interface.nodes_length));
+        }
+        if interface.sum_dual_variables != 0 || interface.sum_grow_speed != 0 {
+            return Err(format!(
+                "interface has non-zero sum_dual_variables {} or sum_grow_speed {}",
+                interface.sum_dual_variables, interface.sum_grow_speed
+            ));
+        }
+        Ok(())
+    }
+
+    /// pre-reserve the node list to avoid reallocation in the middle of decoding
+    pub fn reserve_capacity(&self, max_nodes: usize) {
+        let mut interface = self.write();
+        let additional = max_nodes.saturating_sub(interface.nodes.len());
+        interface.nodes.reserve(additional);
+    }
+
     /// DFS flatten the nodes
     pub fn flatten_nodes(&self, flattened_nodes: &mut Vec<Option<DualNodePtr>>) {
         let interface = self.read_recursive();
@@ -858,8 +1049,33 @@ impl DualModuleInterfacePtr {
     }
 
     pub fn create_defect_node(&self, vertex_idx: VertexIndex, dual_module_impl: &mut impl DualModuleImpl) -> DualNodePtr {
-        let belonging = self.downgrade();
+        let node_ptr = self.new_defect_node(vertex_idx, &mut self.write());
+        dual_module_impl.add_defect_node(&node_ptr);
+        node_ptr
+    }
+
+    /// create the defect nodes of many vertices with a single lock on the interface, and add them to the dual module
+    /// in a batch, see [`DualModuleImpl::add_defect_nodes_batch`]
+    pub fn create_defect_nodes(
+        &self,
+        vertex_indices: &[VertexIndex],
+        dual_module_impl: &mut impl DualModuleImpl,
+    ) -> Vec<DualNodePtr> {
         let mut interface = self.write();
+        let additional = (interface.nodes_length + vertex_indices.len()).saturating_sub(interface.nodes.len());
+        interface.nodes.reserve(additional);
+        let node_ptrs: Vec<DualNodePtr> = vertex_indices
+            .iter()
+            .map(|&vertex_idx| self.new_defect_node(vertex_idx, &mut interface))
+            .collect();
+        drop(interface);
+        dual_module_impl.add_defect_nodes_batch(&node_ptrs);
+        node_ptrs
+    }
+
+    /// append a defect node to the interface without adding it to the dual module
+    fn new_defect_node(&self, vertex_idx: VertexIndex, interface: &mut DualModuleInterface) -> DualNodePtr {
+        let belonging = self.downgrade();
         interface.sum_grow_speed += 1;
         let local_node_index = interface.nodes_length;
         let node_index = interface.nodes_count();
@@ -900,8 +1116,6 @@ impl DualModuleInterfacePtr {
         }
         let cloned_node_ptr = node_ptr.clone();
         interface.nodes[local_node_index] = Some(node_ptr); // feature `dangerous_pointer`: must push the owner
-        drop(interface);
-        dual_module_impl.add_defect_node(&cloned_node_ptr);
         cloned_node_ptr
     }
 
@@ -1099,6 +1313,44 @@ impl DualModuleInterfacePtr {
         interface.remove_node(node_idx); // remove this blossom from root, feature `dangerous_pointer` requires running this at the end
     }
 
+    /// check that a grow state transition is legal before it's applied, to catch misuse of a primal module early instead
+    /// of as an over-grown panic later on: the node must be an outer node of this interface, a shrinking node must have
+    /// a non-negative dual variable, and the dual module must accept it; this is checked in [`Self::set_grow_state`]
+    /// in debug builds
+    pub fn check_grow_state(
+        &self,
+        dual_node_ptr: &DualNodePtr,
+        grow_state: DualNodeGrowState,
+        dual_module_impl: &impl DualModuleImpl,
+    ) -> Result<(), GrowStateError> {
+        let interface = self.read_recursive();
+        let dual_node = dual_node_ptr.read_recursive();
+        let node_index = dual_node.index;
+        if node_index >= interface.nodes_count() || interface.get_node(node_index).as_ref() != Some(dual_node_ptr) {
+            return Err(GrowStateError::NotInInterface { node_index });
+        }
+        if let Some(blossom_weak) = dual_node.parent_blossom.as_ref() {
+            return Err(GrowStateError::InsideBlossom {
+                node_index,
+                blossom_index: blossom_weak.upgrade_force().read_recursive().index,
+            });
+        }
+        if grow_state == DualNodeGrowState::Shrink {
+            let dual_variable = dual_node.get_dual_variable(&interface);
+            if dual_variable < 0 {
+                return Err(GrowStateError::NegativeDualVariable {
+                    node_index,
+                    dual_variable,
+                });
+            }
+        }
+        drop(dual_node);
+        drop(interface);
+        dual_module_impl
+            .check_grow_state(dual_node_ptr, grow_state)
+            .map_err(|message| GrowStateError::DualModule { node_index, message })
+    }
+
     /// a helper function to update grow state
     #[allow(clippy::needless_borrow)]
     pub fn set_grow_state(
@@ -1110,6 +1362,11 @@ impl DualModuleInterfacePtr {
         if self.read_recursive().is_fusion {
             dual_node_ptr.update(); // these dual node may not be update-to-date in fusion
         }
+        if cfg!(debug_assertions) {
+            if let Err(error) = self.check_grow_state(dual_node_ptr, grow_state, dual_module_impl) {
+                panic!("illegal grow state transition to {grow_state:?}: {error}");
+            }
+        }
         let mut interface = self.write();
         if interface.debug_print_actions {
             eprintln!("[set grow state] {:?} {:?}", dual_node_ptr, grow_state);
@@ -1144,8 +1401,35 @@ impl DualModuleInterfacePtr {
         dual_node_ptr.set_grow_state(grow_state);
     }
 
+    /// grow a single outer dual node by [`DualModuleImpl::grow_dual_node_checked`] and update its dual variable and
+    /// [`DualModuleInterface::sum_dual_variables`] accordingly; nothing changes on error
+    pub fn grow_dual_node_checked(
+        &self,
+        dual_node_ptr: &DualNodePtr,
+        length: Weight,
+        dual_module_impl: &mut impl DualModuleImpl,
+    ) -> Result<(), GrowthError> {
+        {
+            let node = dual_node_ptr.read_recursive();
+            if let Some(parent_blossom_weak) = node.parent_blossom.as_ref() {
+                return Err(GrowthError::InsideBlossom {
+                    node_index: node.index,
+                    blossom_index: parent_blossom_weak.upgrade_force().read_recursive().index,
+                });
+            }
+        }
+        dual_module_impl.grow_dual_node_checked(dual_node_ptr, length)?;
+        let mut interface = self.write();
+        let mut node = dual_node_ptr.write();
+        let current_dual_variable = node.get_dual_variable(&interface);
+        node.dual_variable_cache = (current_dual_variable + length, interface.dual_variable_global_progress);
+        interface.sum_dual_variables += length;
+        Ok(())
+    }
+
     /// grow the dual module and update [`DualModuleInterface::sum_`]
     pub fn grow(&self, length: Weight, dual_module_impl: &mut impl DualModuleImpl) {
+        log_span!(DEBUG, "grow", length);
         dual_module_impl.grow(length);
         self.notify_grown(length);
     }
@@ -1153,7 +1437,15 @@ impl DualModuleInterfacePtr {
     /// if a dual module spontaneously grow some value (e.g. with primal offloading), this function should be called
     pub fn notify_grown(&self, length: Weight) {
         let mut interface = self.write();
-        interface.sum_dual_variables += length * interface.sum_grow_speed;
+        interface.sum_dual_variables = length
+            .checked_mul(interface.sum_grow_speed)
+            .and_then(|delta| interface.sum_dual_variables.checked_add(delta))
+            .unwrap_or_else(|| {
+                panic!(
+                    "sum of dual variables overflows: {} + {} * {}",
+                    interface.sum_dual_variables, length, interface.sum_grow_speed
+                )
+            });
         interface.dual_variable_global_progress += length;
     }
 
@@ -1164,12 +1456,20 @@ impl DualModuleInterfacePtr {
             let safe_growth = max_update_length
                 .get_none_zero_growth()
                 .unwrap_or_else(|| panic!("iterative grow failed because of conflicts {max_update_length:?}"));
-            let growth = std::cmp::min(length, safe_growth);
+            let growth = core::cmp::min(length, safe_growth);
             self.grow(growth, dual_module_impl);
             length -= growth;
         }
     }
 
+    /// whether a primal-dual loop driven manually is done: [`DualModuleImpl::compute_maximum_update_length`] returns an
+    /// empty group, i.e. no node is growing or shrinking and there is no conflict left; with a correct primal module
+    /// this means every defect is matched. The solvers check [`GroupMaxUpdateLength::is_empty`] on the group they
+    /// compute anyway, which is the same condition without computing the maximum update length twice
+    pub fn is_converged(&self, dual_module_impl: &mut impl DualModuleImpl) -> bool {
+        dual_module_impl.compute_maximum_update_length().is_empty()
+    }
+
     /// fuse two interfaces by copying the nodes in `other` into myself
     #[allow(clippy::unnecessary_cast)]
     #[allow(clippy::needless_borrow)]
@@ -1197,7 +1497,10 @@ impl DualModuleInterfacePtr {
                 }
                 interface.nodes[(bias + other_node_index) as usize] = node_ptr.clone();
             }
-            interface.sum_dual_variables += other_interface.sum_dual_variables;
+            interface.sum_dual_variables = interface
+                .sum_dual_variables
+                .checked_add(other_interface.sum_dual_variables)
+                .expect("sum of dual variables overflows when fusing");
             interface.sum_grow_speed += other_interface.sum_grow_speed;
         }
     }
@@ -1226,7 +1529,10 @@ impl DualModuleInterfacePtr {
             (right_weak, right_interface.nodes_count()),
         ));
         for other_interface in [left_interface, right_interface] {
-            interface.sum_dual_variables += other_interface.sum_dual_variables;
+            interface.sum_dual_variables = interface
+                .sum_dual_variables
+                .checked_add(other_interface.sum_dual_variables)
+                .expect("sum of dual variables overflows when fusing");
             interface.sum_grow_speed += other_interface.sum_grow_speed;
         }
     }
@@ -1518,6 +1824,22 @@ impl MaxUpdateLength {
         }
     }
 
+    /// the two conflicting (outer) dual nodes of a [`MaxUpdateLength::Conflicting`] event, same as [`Self::get_conflicting`]
+    #[inline(always)]
+    pub fn conflicting_nodes(&self) -> Option<(DualNodePtr, DualNodePtr)> {
+        self.get_conflicting()
+    }
+
+    /// the two touching grandsons of a [`MaxUpdateLength::Conflicting`] event, i.e. the innermost dual nodes that
+    /// actually touch each other; they are in the same order as [`Self::conflicting_nodes`]
+    #[inline(always)]
+    pub fn conflicting_grandsons(&self) -> Option<(DualNodePtr, DualNodePtr)> {
+        match self {
+            Self::Conflicting((_, a), (_, b)) => Some((a.clone(), b.clone())),
+            _ => None,
+        }
+    }
+
     /// helper function that get values out of the enum
     #[allow(dead_code)]
     #[inline(always)]
@@ -1585,7 +1907,7 @@ impl EdgeWeightModifier {
     }
 }
 
-impl std::ops::Deref for EdgeWeightModifier {
+impl core::ops::Deref for EdgeWeightModifier {
     type Target = Vec<(EdgeIndex, Weight)>;
 
     fn deref(&self) -> &Self::Target {
@@ -23,7 +23,7 @@ use crate::rayon::prelude::*;
 use crate::serde_json;
 use crate::weak_table::PtrWeakHashSet;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::{Arc, Weak};
 
 pub struct DualModuleParallel<SerialModule: DualModuleImpl + Send + Sync> {
@@ -55,6 +55,9 @@ pub struct DualModuleParallelConfig {
     /// enable parallel execution of a fused dual module
     #[serde(default = "dual_module_parallel_default_configs::enable_parallel_execution")]
     pub enable_parallel_execution: bool,
+    /// debug mode: panic if a vertex is synced back and forth between different states more than this number of times while
+    /// preparing a single growth, which usually indicates a partition bug; disabled by default
+    pub sync_ping_pong_threshold: Option<usize>,
 }
 
 impl Default for DualModuleParallelConfig {
@@ -108,11 +111,91 @@ pub struct DualModuleParallelUnit<SerialModule: DualModuleImpl + Send + Sync> {
     pub enable_parallel_execution: bool,
     /// whether any descendant unit has active dual node
     pub has_active_node: bool,
+    /// see [`DualModuleParallelConfig::sync_ping_pong_threshold`]
+    pub sync_ping_pong_threshold: Option<usize>,
+    /// sync requests generated and dispatched by this unit since the last clear
+    pub sync_stats: SyncStats,
+}
+
+/// statistics of the sync requests between units, see [`DualModuleParallel::sync_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    /// the number of sync requests generated, keyed by (the unit that generates it, the unit that owns the vertex)
+    pub unit_pairs: BTreeMap<(usize, usize), usize>,
+    /// the number of sync requests dropped before dispatch because a later request in the same batch syncs the same vertex
+    pub duplicates: usize,
+}
+
+impl SyncStats {
+    pub fn add(&mut self, other: &Self) {
+        for (unit_pair, count) in other.unit_pairs.iter() {
+            *self.unit_pairs.entry(*unit_pair).or_insert(0) += count;
+        }
+        self.duplicates += other.duplicates;
+    }
+
+    /// the total number of sync requests generated
+    pub fn total(&self) -> usize {
+        self.unit_pairs.values().sum()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "unit_pairs": self
+                .unit_pairs
+                .iter()
+                .map(|((from_unit, owner_unit), count)| json!([from_unit, owner_unit, count]))
+                .collect::<Vec<_>>(),
+            "duplicates": self.duplicates,
+        })
+    }
+}
+
+/// count how many times each vertex is synced to a different state while preparing a single growth
+#[derive(Default)]
+pub struct SyncPingPongDetector {
+    /// the last synced state and the number of state changes of each vertex
+    states: HashMap<VertexIndex, (SyncState, usize)>,
+}
+
+/// the state carried by a sync request: the propagated dual node and grandson dual node
+type SyncState = (Option<DualNodeWeak>, Option<DualNodeWeak>);
+
+impl SyncPingPongDetector {
+    /// record a dispatched sync request and return the number of times this vertex has changed its synced state
+    pub fn observe(&mut self, sync_request: &SyncRequest) -> usize {
+        let state: SyncState = (
+            sync_request.propagated_dual_node.as_ref().map(|(weak, ..)| weak.clone()),
+            sync_request
+                .propagated_grandson_dual_node
+                .as_ref()
+                .map(|(weak, ..)| weak.clone()),
+        );
+        match self.states.get_mut(&sync_request.vertex_index) {
+            Some((last_state, changes)) => {
+                if *last_state != state {
+                    *last_state = state;
+                    *changes += 1;
+                }
+                *changes
+            }
+            None => {
+                self.states.insert(sync_request.vertex_index, (state, 0));
+                0
+            }
+        }
+    }
 }
 
 pub type DualModuleParallelUnitPtr<SerialModule> = ArcManualSafeLock<DualModuleParallelUnit<SerialModule>>;
 pub type DualModuleParallelUnitWeak<SerialModule> = WeakManualSafeLock<DualModuleParallelUnit<SerialModule>>;
 
+impl<SerialModule: DualModuleImpl + Send + Sync> PointerTag for DualModuleParallelUnit<SerialModule> {
+    fn pointer_tag(&self) -> String {
+        format!("DualModuleParallelUnit {}", self.unit_index)
+    }
+}
+
 impl<SerialModule: DualModuleImpl + Send + Sync> std::fmt::Debug for DualModuleParallelUnitPtr<SerialModule> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let unit = self.read_recursive();
@@ -142,7 +225,7 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
         let thread_pool = thread_pool_builder.build().expect("creating thread pool failed");
         let mut units = vec![];
         let unit_count = partition_info.units.len();
-        let complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges); // build the graph to construct the NN data structure
+        let complete_graph = CompleteGraph::from_initializer(initializer); // build the graph to construct the NN data structure
         let mut contained_vertices_vec: Vec<BTreeSet<VertexIndex>> = vec![]; // all vertices maintained by each unit
         let mut is_vertex_virtual: Vec<_> = (0..initializer.vertex_num).map(|_| false).collect();
         for virtual_vertex in initializer.virtual_vertices.iter() {
@@ -161,16 +244,13 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
                 let mut interfaces = vec![];
                 let mut current_index = unit_index;
                 let owning_range = &partition_info.units[unit_index].owning_range;
-                let mut contained_vertices = BTreeSet::new();
-                for vertex_index in owning_range.iter() {
-                    contained_vertices.insert(vertex_index);
-                }
+                let mut contained_vertices: BTreeSet<VertexIndex> = owning_range.into_iter().collect();
                 while let Some(parent_index) = &partition_info.units[current_index].parent {
                     let mut mirror_vertices = vec![];
                     if config.edges_in_fusion_unit {
                         for vertex_index in partition_info.units[*parent_index].owning_range.iter() {
                             let mut is_incident = false;
-                            for (peer_index, _) in complete_graph.vertices[vertex_index as usize].edges.iter() {
+                            for peer_index in complete_graph.vertices[vertex_index as usize].edges.keys() {
                                 if owning_range.contains(*peer_index) {
                                     is_incident = true;
                                     break;
@@ -185,7 +265,7 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
                         // first check if there EXISTS any vertex that's adjacent of it's contains vertex
                         let mut has_incident = false;
                         for vertex_index in partition_info.units[*parent_index].owning_range.iter() {
-                            for (peer_index, _) in complete_graph.vertices[vertex_index as usize].edges.iter() {
+                            for peer_index in complete_graph.vertices[vertex_index as usize].edges.keys() {
                                 if contained_vertices.contains(peer_index) {
                                     // important diff: as long as it has an edge with contained vertex, add it
                                     has_incident = true;
@@ -361,6 +441,7 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
             if let Some(parent_index) = &partition_info.units[unit_index].parent {
                 unit.parent = Some(units[*parent_index].downgrade());
             }
+            unit.sync_ping_pong_threshold = config.sync_ping_pong_threshold;
         }
         // fill in the extra_descendant_mirrored_vertices
         for unit_index in 0..unit_count {
@@ -431,6 +512,15 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
         Some(owning_unit_ptr)
     }
 
+    /// the sync requests between units since the last clear, summed over all units
+    pub fn sync_stats(&self) -> SyncStats {
+        let mut sync_stats = SyncStats::default();
+        for unit_ptr in self.units.iter() {
+            sync_stats.add(&unit_ptr.read_recursive().sync_stats);
+        }
+        sync_stats
+    }
+
     /// statically fuse them all, may be called at any state (meaning each unit may not necessarily be solved locally)
     pub fn static_fuse_all(&mut self) {
         for unit_ptr in self.units.iter() {
@@ -560,18 +650,72 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         })
     }
 
+    /// every unit holding a copy of the edge must be modified, including the inactive ones that are already fused into
+    /// an active ancestor, e.g. after [`Self::static_fuse_all`]
     fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
         self.thread_pool.scope(|_| {
             self.units.par_iter().for_each(|unit_ptr| {
                 lock_write!(unit, unit_ptr);
-                if !unit.is_active {
-                    return;
-                }
                 unit.load_edge_modifier(edge_modifier);
             });
         })
     }
 
+    /// split the hints to each unit proportionally to the number of owned vertices
+    fn reserve_capacity(&mut self, hints: &CapacityHints) {
+        for (unit_index, unit_ptr) in self.units.iter().enumerate() {
+            lock_write!(unit, unit_ptr);
+            unit.serial_module
+                .reserve_capacity(&hints.scaled(self.partition_info.owning_ratio(unit_index)));
+        }
+    }
+
+    /// the sum of the node high-water marks of all units, and the maximum boundary of a single node
+    fn observed_capacities(&self) -> CapacityHints {
+        let mut observed = CapacityHints::default();
+        for unit_ptr in self.units.iter() {
+            let unit_observed = unit_ptr.read_recursive().serial_module.observed_capacities();
+            observed.max_defects += unit_observed.max_defects;
+            observed.max_blossoms += unit_observed.max_blossoms;
+            observed.max_boundary_per_node =
+                std::cmp::max(observed.max_boundary_per_node, unit_observed.max_boundary_per_node);
+        }
+        observed
+    }
+
+    /// the sum of the peaks of all units, an upper bound of the working set because the units run concurrently
+    fn peak_active_nodes(&self) -> usize {
+        self.units
+            .iter()
+            .map(|unit_ptr| unit_ptr.read_recursive().serial_module.peak_active_nodes())
+            .sum()
+    }
+
+    fn generate_profiler_report(&self) -> serde_json::Value {
+        // summed from the reports of the serial modules, which may not record it
+        let stale_boundary_repairs: u64 = self
+            .units
+            .iter()
+            .filter_map(|unit_ptr| {
+                unit_ptr.read_recursive().serial_module.generate_profiler_report()["stale_boundary_repairs"].as_u64()
+            })
+            .sum();
+        json!({
+            "sync": self.sync_stats().to_json(),
+            "stale_boundary_repairs": stale_boundary_repairs,
+        })
+    }
+
+    fn check_clean(&self) -> Result<(), String> {
+        for (unit_index, unit_ptr) in self.units.iter().enumerate() {
+            let unit = unit_ptr.read_recursive();
+            unit.serial_module
+                .check_clean()
+                .map_err(|message| format!("unit {unit_index}: {message}"))?;
+        }
+        Ok(())
+    }
+
     fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
         let unit_ptr = self.find_active_ancestor(&nodes_circle[0]);
         self.thread_pool.scope(|_| {
@@ -694,15 +838,48 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallelUnit<SerialMo
         self.whole_range.contains(vertex_index) || self.extra_descendant_mirrored_vertices.contains(&vertex_index)
     }
 
-    /// no need to deduplicate the events: the result will always be consistent with the last one
-    fn execute_sync_events(&mut self, sync_requests: &[SyncRequest]) {
+    /// the result is always consistent with the last sync request of each vertex, so the earlier ones in the same batch are dropped
+    fn execute_sync_events(&mut self, sync_requests: &mut Vec<SyncRequest>, detector: &mut Option<SyncPingPongDetector>) {
         // println!("sync_requests: {sync_requests:?}");
+        let length = sync_requests.len();
+        let mut synced_vertices = HashSet::with_capacity(length);
+        let mut is_latest: Vec<bool> = sync_requests
+            .iter()
+            .rev()
+            .map(|sync_request| synced_vertices.insert(sync_request.vertex_index))
+            .collect();
+        is_latest.reverse();
+        let mut is_latest = is_latest.into_iter();
+        sync_requests.retain(|_| is_latest.next().unwrap());
+        self.sync_stats.duplicates += length - sync_requests.len();
         for sync_request in sync_requests.iter() {
+            if let Some(detector) = detector.as_mut() {
+                let changes = detector.observe(sync_request);
+                let threshold = self.sync_ping_pong_threshold.unwrap();
+                assert!(
+                    changes <= threshold,
+                    "ping-pong sync: vertex {} changed its synced state {} times while preparing a single growth, which usually indicates a partition bug",
+                    sync_request.vertex_index,
+                    changes
+                );
+            }
             sync_request.update();
             self.execute_sync_event(sync_request);
         }
     }
 
+    /// count the newly generated sync requests of my serial module
+    fn record_sync_requests(&mut self, sync_requests: &[SyncRequest]) {
+        for sync_request in sync_requests.iter() {
+            let owner_unit_index = sync_request.mirror_unit_weak.upgrade_force().read_recursive().unit_index;
+            *self
+                .sync_stats
+                .unit_pairs
+                .entry((self.unit_index, owner_unit_index))
+                .or_insert(0) += 1;
+        }
+    }
+
     /// iteratively prepare all growing and shrinking and append the sync requests
     fn iterative_prepare_all(&mut self, sync_requests: &mut Vec<SyncRequest>) {
         if !self.has_active_node {
@@ -730,8 +907,9 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallelUnit<SerialMo
             }
         }
         // my serial module
-        let local_sync_requests = self.serial_module.prepare_all();
-        sync_requests.append(local_sync_requests);
+        let generated_start = sync_requests.len();
+        sync_requests.append(self.serial_module.prepare_all());
+        self.record_sync_requests(&sync_requests[generated_start..]);
     }
 
     /// iteratively set grow state
@@ -820,8 +998,9 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallelUnit<SerialMo
                 );
             }
         }
-        let local_sync_requests = self.serial_module.prepare_nodes_shrink(nodes_circle);
-        sync_requests.append(local_sync_requests);
+        let generated_start = sync_requests.len();
+        sync_requests.append(self.serial_module.prepare_nodes_shrink(nodes_circle));
+        self.record_sync_requests(&sync_requests[generated_start..]);
     }
 
     fn iterative_add_blossom(
@@ -1102,6 +1281,8 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallelUnitPtr<Seria
             empty_sync_request: vec![],
             enable_parallel_execution,
             has_active_node: true, // by default to true, because children may have active nodes
+            sync_ping_pong_threshold: None,
+            sync_stats: SyncStats::default(),
         })
     }
 }
@@ -1116,6 +1297,7 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
     /// clear all growth and existing dual nodes
     fn clear(&mut self) {
         self.has_active_node = true;
+        self.sync_stats = SyncStats::default();
         self.serial_module.clear()
     }
 
@@ -1248,22 +1430,22 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         self.iterative_grow(length);
     }
 
+    /// only modifies the edges held by this unit, the edges in descendant units are modified by the [`DualModuleParallel`]
     fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
-        // TODO: split the edge modifier and then load them to individual descendant units
-        // hint: each edge could appear in any unit that mirrors the two vertices
         self.serial_module.load_edge_modifier(edge_modifier)
     }
 
     fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
         let nodes_circle_vertices: Vec<_> = nodes_circle.iter().map(|ptr| ptr.get_representative_vertex()).collect();
         let mut sync_requests = vec![];
+        let mut detector = self.sync_ping_pong_threshold.map(|_| SyncPingPongDetector::default());
         loop {
             self.iterative_prepare_nodes_shrink(nodes_circle, &nodes_circle_vertices, &mut sync_requests);
             if sync_requests.is_empty() {
                 break;
             }
             // println!("sync_requests: {sync_requests:?}");
-            self.execute_sync_events(&sync_requests);
+            self.execute_sync_events(&mut sync_requests, &mut detector);
             sync_requests.clear();
         }
         &mut self.empty_sync_request
@@ -1274,13 +1456,14 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
             // don't do anything, not even prepare the growth because it will be done in the serial module
         } else {
             let mut sync_requests = vec![];
+            let mut detector = self.sync_ping_pong_threshold.map(|_| SyncPingPongDetector::default());
             loop {
                 self.iterative_prepare_all(&mut sync_requests);
                 if sync_requests.is_empty() {
                     break;
                 }
                 // println!("sync_requests: {sync_requests:?}");
-                self.execute_sync_events(&sync_requests);
+                self.execute_sync_events(&mut sync_requests, &mut detector);
                 sync_requests.clear();
             }
         }
@@ -1671,7 +1854,7 @@ pub mod tests {
         final_dual: Weight,
     ) {
         let half_weight = 500;
-        let split_vertical = (d + 1) / 2;
+        let split_vertical = d.div_ceil(2);
         dual_module_parallel_standard_syndrome(
             CodeCapacityRepetitionCode::new(d, 0.1, half_weight),
             visualize_filename,
@@ -1757,7 +1940,7 @@ pub mod tests {
         final_dual: Weight,
     ) {
         let half_weight = 500;
-        let split_horizontal = (d + 1) / 2;
+        let split_horizontal = d.div_ceil(2);
         let row_count = d + 1;
         dual_module_parallel_standard_syndrome(
             CodeCapacityPlanarCode::new(d, 0.1, half_weight),
@@ -1920,4 +2103,156 @@ pub mod tests {
         });
         println!("results: {results:?}");
     }
+
+    /// sync traffic is counted per unit pair, duplicates are dropped without changing the result
+    #[test]
+    fn dual_module_parallel_sync_stats_1() {
+        // cargo test dual_module_parallel_sync_stats_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (7, 15);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let partition_config = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, 4).build_apply(&mut code);
+        let initializer = code.get_initializer();
+        let partition_info = partition_config.info();
+        let mut parallel_solver = SolverParallel::new(
+            &initializer,
+            &partition_info,
+            json!({ "dual": { "sync_ping_pong_threshold": 1 } }),
+        );
+        let mut serial_solver = SolverSerial::new(&initializer);
+        let mut total_stats = SyncStats::default();
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            parallel_solver.solve(&syndrome_pattern);
+            serial_solver.solve(&syndrome_pattern);
+            assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+            total_stats.add(&parallel_solver.dual_module.sync_stats());
+            parallel_solver.clear();
+            serial_solver.clear();
+            assert_eq!(parallel_solver.dual_module.sync_stats(), SyncStats::default());
+        }
+        println!("sync stats: {:?}", total_stats);
+        assert!(total_stats.total() > 0 && total_stats.duplicates > 0);
+        for &(from_unit, owner_unit) in total_stats.unit_pairs.keys() {
+            // only fusion units own mirrored vertices
+            assert!(owner_unit >= partition_config.partitions.len());
+            assert!(from_unit <= owner_unit);
+        }
+    }
+
+    /// the serial primal module with the parallel dual module must support erasures, which are loaded into every unit
+    /// holding a copy of the erased edges even after the units are statically fused
+    #[test]
+    fn dual_module_parallel_erasure_1() {
+        // cargo test dual_module_parallel_erasure_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let d = 11;
+        let mut code = CodeCapacityPlanarCode::new(d, 0.05, 500);
+        code.set_erasure_probability(0.05);
+        let partition_config = CodeCapacityPlanarCodeVerticalPartitionFour::new(d, d / 2, d / 2).build_apply(&mut code);
+        let initializer = code.get_initializer();
+        let partition_info = partition_config.info();
+        let mut dual_parallel_solver = SolverDualParallel::new(&initializer, &partition_info, json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for seed in 0..300 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            dual_parallel_solver.solve(&syndrome_pattern);
+            serial_solver.solve(&syndrome_pattern);
+            assert_eq!(
+                dual_parallel_solver.sum_dual_variables(),
+                serial_solver.sum_dual_variables(),
+                "seed {seed}"
+            );
+            dual_parallel_solver.clear();
+            serial_solver.clear();
+        }
+    }
+
+    /// a shrinking dual node may vacate both ends of an erased edge at once when one end is a mirrored vertex,
+    /// which must not leave the edge in its boundary
+    #[test]
+    fn dual_module_parallel_erasure_2() {
+        // cargo test dual_module_parallel_erasure_2 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let d = 11;
+        let mut code = CodeCapacityPlanarCode::new(d, 0.05, 500);
+        let partition_config = CodeCapacityPlanarCodeVerticalPartitionFour::new(d, d / 2, d / 2).build_apply(&mut code);
+        let initializer = code.get_initializer();
+        let partition_info = partition_config.info();
+        // edge 130 connects vertex 91 owned by a fusion unit and vertex 102 owned by a leaf unit
+        let syndrome_pattern = SyndromePattern::new(vec![93, 96, 103], vec![130]);
+        let mut dual_parallel_solver = SolverDualParallel::new(&initializer, &partition_info, json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        dual_parallel_solver.solve(&syndrome_pattern);
+        serial_solver.solve(&syndrome_pattern);
+        assert_eq!(dual_parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+    }
+
+    /// a vertex synced back and forth is reported in the debug mode
+    #[test]
+    #[should_panic(expected = "ping-pong sync")]
+    fn dual_module_parallel_sync_ping_pong_1() {
+        // cargo test dual_module_parallel_sync_ping_pong_1 -- --nocapture
+        let weighted_edges: Vec<_> = (0..11).map(|i| (i, i + 1, 100)).collect();
+        let initializer = SolverInitializer::new(12, weighted_edges, vec![0, 11]);
+        let mut partition_config = PartitionConfig::new(12);
+        partition_config.partitions = vec![VertexRange::new(0, 5), VertexRange::new(7, 12)];
+        partition_config.fusions = vec![(0, 1)];
+        let partition_info = partition_config.info();
+        let mut dual_module = DualModuleParallel::<DualModuleSerial>::new_config(
+            &initializer,
+            &partition_info,
+            serde_json::from_value(json!({ "sync_ping_pong_threshold": 2 })).unwrap(),
+        );
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        dual_module.static_fuse_all();
+        interface_ptr.load(&SyndromePattern::new_vertices(vec![4]), &mut dual_module);
+        let dual_node_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        // mirrored vertex 5 is deliberately synced between propagated and not propagated
+        let fusion_unit_ptr = dual_module.units[2].clone();
+        let mirror_unit_weak = fusion_unit_ptr.read_recursive().partition_unit.downgrade();
+        let mut detector = Some(SyncPingPongDetector::default());
+        for round in 0..4 {
+            let propagated = if round % 2 == 0 {
+                Some((dual_node_ptr.downgrade(), 0, 4))
+            } else {
+                None
+            };
+            let mut sync_requests = vec![SyncRequest {
+                mirror_unit_weak: mirror_unit_weak.clone(),
+                vertex_index: 5,
+                propagated_dual_node: propagated.clone(),
+                propagated_grandson_dual_node: propagated,
+            }];
+            fusion_unit_ptr.write().execute_sync_events(&mut sync_requests, &mut detector);
+        }
+    }
+
+    /// the checked single-node growth is not supported across units, which is reported instead of panicking
+    #[test]
+    fn dual_module_parallel_grow_dual_node_checked_1() {
+        // cargo test dual_module_parallel_grow_dual_node_checked_1 -- --nocapture
+        let weighted_edges: Vec<_> = (0..11).map(|i| (i, i + 1, 100)).collect();
+        let initializer = SolverInitializer::new(12, weighted_edges, vec![0, 11]);
+        let mut partition_config = PartitionConfig::new(12);
+        partition_config.partitions = vec![VertexRange::new(0, 5), VertexRange::new(7, 12)];
+        partition_config.fusions = vec![(0, 1)];
+        let partition_info = partition_config.info();
+        let mut dual_module = DualModuleParallel::<DualModuleSerial>::new_config(
+            &initializer,
+            &partition_info,
+            DualModuleParallelConfig::default(),
+        );
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        dual_module.static_fuse_all();
+        interface_ptr.load(&SyndromePattern::new_vertices(vec![4]), &mut dual_module);
+        let dual_node_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        assert_eq!(
+            interface_ptr.grow_dual_node_checked(&dual_node_ptr, 100, &mut dual_module),
+            Err(GrowthError::Unsupported)
+        );
+    }
 }
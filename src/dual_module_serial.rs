@@ -8,14 +8,17 @@
 //! operation is unfriendly to cache.
 //!
 
-#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references))]
+#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references, clippy::needless_borrow))]
 use super::dual_module::*;
 use super::pointers::*;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
+use crate::prelude::*;
+#[cfg(feature = "std")]
 use crate::weak_table::PtrWeakKeyHashMap;
-use std::collections::HashMap;
+use alloc::collections::BinaryHeap;
+use core::cmp::Reverse;
 
 pub struct DualModuleSerial {
     /// all vertices including virtual ones
@@ -26,6 +29,9 @@ pub struct DualModuleSerial {
     pub nodes_length: usize,
     /// keep edges, which can also be accessed in [`Self::vertices`]
     pub edges: Vec<EdgePtr>,
+    /// the edges around each vertex in a flat array, see [`Self::new_compact_adjacency`]; only possible when the
+    /// topology is fixed, i.e. not partitioned; if present, [`Vertex::edges`] is left empty
+    pub compact_adjacency: Option<CompactAdjacency>,
     /// current timestamp
     pub active_timestamp: FastClearTimestamp,
     /// the number of all vertices (including those partitioned into other serial modules)
@@ -51,8 +57,39 @@ pub struct DualModuleSerial {
     updated_boundary: Vec<(bool, EdgeWeak)>,
     /// temporary variable to reduce reallocation
     propagating_vertices: Vec<(VertexWeak, Option<DualNodeInternalWeak>)>,
+    /// the boundary capacity of newly created dual nodes, see [`DualModuleImpl::reserve_capacity`]
+    boundary_capacity: usize,
+    /// the number of defects and blossoms in the current decoding
+    current_capacities: CapacityHints,
+    /// the high-water marks in all previous decodings
+    observed_capacities: CapacityHints,
+    /// the maximum length of the deduplicated active list in the current decoding
+    peak_active_nodes: usize,
+    /// notified when a dual node touches a virtual vertex, see [`DualModuleSerial::set_virtual_touch_observer`]
+    pub virtual_touch_observer: Option<VirtualTouchObserver>,
+    /// the (node, virtual vertex) pairs already notified in the current decoding
+    virtual_touches: HashSet<(NodeIndex, VertexIndex)>,
+    /// the number of fully grown edges found in a boundary in the current decoding, which should have been removed
+    /// when preparing the growth but may be left behind by a sync event; they are repaired by preparing the node again
+    pub stale_boundary_repairs: usize,
+    /// remember the [`MaxUpdateLength::NonZeroGrow`] result of each active node in
+    /// [`DualModuleImpl::compute_maximum_update_length`] and reuse it in later rounds until the neighborhood of the node
+    /// changes; disabled in the units of a partitioned dual module, where the neighborhood is also changed by fusion
+    pub enable_max_update_length_cache: bool,
+    /// the total length of [`DualModuleImpl::grow`] since the last decoding, which shifts all the cached results
+    uniform_growth: Weight,
+    /// cached results of an older epoch are invalid, increased whenever a change is not tracked per node
+    max_update_length_epoch: usize,
+    /// the number of active nodes whose maximum update length is reused from the cache in the current decoding
+    pub max_update_length_cache_hits: usize,
+    /// the local index of each edge given in the global edge index, if the edges are reordered by
+    /// [`DualModuleSerial::new_reordered`]; [`Edge::edge_index`] is still the global index
+    pub local_edge_indices: Option<Vec<EdgeIndex>>,
 }
 
+/// a callback invoked with the dual node and the index of the virtual vertex it touches
+pub type VirtualTouchObserver = Box<dyn FnMut(&DualNodePtr, VertexIndex) + Send + Sync>;
+
 /// records information only available when used as a unit in the partitioned dual module
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -64,12 +101,31 @@ pub struct UnitModuleInfo {
     /// owned dual nodes range
     pub owning_dual_range: NodeRange,
     /// hash table for mapping [`DualNodePtr`] to internal [`DualNodeInternalPtr`]
-    pub dual_node_pointers: PtrWeakKeyHashMap<DualNodeWeak, usize>,
+    pub dual_node_pointers: DualNodePointers,
+    /// the local index in [`DualModuleSerial::edges`] of each global edge index that this unit holds;
+    /// an edge may be held by multiple units, each with its own copy
+    pub edge_indices: HashMap<EdgeIndex, EdgeIndex>,
 }
 
+/// the lookup table of [`UnitModuleInfo::dual_node_pointers`]; the weak-keyed table needs `std`, so without it the
+/// table holds the dual nodes until the next clear
+#[cfg(feature = "std")]
+pub type DualNodePointers = PtrWeakKeyHashMap<DualNodeWeak, usize>;
+#[cfg(not(feature = "std"))]
+pub type DualNodePointers = HashMap<DualNodePtr, usize>;
+
 pub type DualModuleSerialPtr = ArcManualSafeLock<DualModuleSerial>;
 pub type DualModuleSerialWeak = WeakManualSafeLock<DualModuleSerial>;
 
+impl PointerTag for DualModuleSerial {
+    fn pointer_tag(&self) -> String {
+        match self.unit_module_info.as_ref() {
+            Some(unit_module_info) => format!("DualModuleSerial of unit {}", unit_module_info.unit_index),
+            None => "DualModuleSerial".to_string(),
+        }
+    }
+}
+
 /// internal information of the dual node, added to the [`DualNode`]
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -87,21 +143,42 @@ pub struct DualNodeInternal {
     pub overgrown_stack: Vec<(VertexWeak, Weight)>,
     /// helps to prevent duplicate visit in a single cycle
     last_visit_cycle: usize,
+    /// the last [`MaxUpdateLength::NonZeroGrow`] result, see [`DualModuleSerial::enable_max_update_length_cache`]
+    max_update_length_cache: Option<MaxUpdateLengthCache>,
+}
+
+/// a [`MaxUpdateLength::NonZeroGrow`] result of a dual node, which remains valid after uniform growth: every
+/// constraint on the node shrinks by exactly the growth length as long as its neighborhood doesn't change
+#[derive(Debug, Clone, Copy)]
+struct MaxUpdateLengthCache {
+    is_grow: bool,
+    length: Weight,
+    is_boundary_empty: bool,
+    /// [`DualModuleSerial::uniform_growth`] when computed
+    uniform_growth: Weight,
+    /// [`DualModuleSerial::max_update_length_epoch`] when computed
+    epoch: usize,
 }
 
 // when using feature `dangerous_pointer`, it doesn't provide the `upgrade()` function, so we have to fall back to the safe solution
 pub type DualNodeInternalPtr = ArcManualSafeLock<DualNodeInternal>;
 pub type DualNodeInternalWeak = WeakManualSafeLock<DualNodeInternal>;
 
-impl std::fmt::Debug for DualNodeInternalPtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for DualNodeInternal {
+    fn pointer_tag(&self) -> String {
+        format!("DualNodeInternal {}", self.index)
+    }
+}
+
+impl core::fmt::Debug for DualNodeInternalPtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let dual_node_internal = self.read_recursive();
         write!(f, "{}", dual_node_internal.index)
     }
 }
 
-impl std::fmt::Debug for DualNodeInternalWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for DualNodeInternalWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
@@ -131,15 +208,21 @@ pub struct Vertex {
 pub type VertexPtr = FastClearArcManualSafeLockDangerous<Vertex>;
 pub type VertexWeak = FastClearWeakManualSafeLockDangerous<Vertex>;
 
-impl std::fmt::Debug for VertexPtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for Vertex {
+    fn pointer_tag(&self) -> String {
+        format!("Vertex {}", self.vertex_index)
+    }
+}
+
+impl core::fmt::Debug for VertexPtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let vertex = self.read_recursive_force();
         write!(f, "{}", vertex.vertex_index)
     }
 }
 
-impl std::fmt::Debug for VertexWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for VertexWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let vertex_ptr = self.upgrade_force();
         let vertex = vertex_ptr.read_recursive_force();
         write!(f, "{}", vertex.vertex_index)
@@ -177,28 +260,166 @@ pub struct Edge {
     pub dedup_timestamp: (FastClearTimestamp, FastClearTimestamp),
 }
 
+impl Edge {
+    /// the grown fraction `(left_growth + right_growth) / weight` of this edge, which is 1.0 when tight; a zero-weight
+    /// edge is 1.0 if it has any growth or a dual node reaches either vertex (it's tight right away while its growth
+    /// stays zero), and 0.0 otherwise
+    pub fn growth_fraction(&self) -> f64 {
+        let growth = self.left_growth + self.right_growth;
+        if self.weight == 0 {
+            if growth > 0 || self.left_dual_node.is_some() || self.right_dual_node.is_some() {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            growth as f64 / self.weight as f64
+        }
+    }
+}
+
 pub type EdgePtr = FastClearArcManualSafeLockDangerous<Edge>;
 pub type EdgeWeak = FastClearWeakManualSafeLockDangerous<Edge>;
 
-impl std::fmt::Debug for EdgePtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for Edge {
+    fn pointer_tag(&self) -> String {
+        format!("Edge {}", self.edge_index)
+    }
+}
+
+impl core::fmt::Debug for EdgePtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let edge = self.read_recursive_force();
         write!(f, "{}", edge.edge_index)
     }
 }
 
-impl std::fmt::Debug for EdgeWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for EdgeWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let edge_ptr = self.upgrade_force();
         let edge = edge_ptr.read_recursive_force();
         write!(f, "{}", edge.edge_index)
     }
 }
 
-impl DualModuleImpl for DualModuleSerial {
-    /// initialize the dual module, which is supposed to be reused for multiple decoding tasks with the same structure
+/// compressed-sparse-row adjacency of a decoding graph: the edges around vertex `v` are
+/// `edge_indices[offsets[v]..offsets[v + 1]]`, in the same order as they appear in the initializer
+#[derive(Debug, Clone)]
+pub struct CompactAdjacency {
+    pub offsets: Vec<usize>,
+    pub edge_indices: Vec<EdgeIndex>,
+}
+
+impl CompactAdjacency {
     #[allow(clippy::unnecessary_cast)]
-    fn new_empty(initializer: &SolverInitializer) -> Self {
+    pub fn new(vertex_num: VertexNum, weighted_edges: &[(VertexIndex, VertexIndex, Weight)]) -> Self {
+        let mut offsets = vec![0; vertex_num as usize + 1];
+        for &(i, j, _) in weighted_edges.iter() {
+            offsets[i as usize + 1] += 1;
+            offsets[j as usize + 1] += 1;
+        }
+        for vertex_index in 0..vertex_num as usize {
+            offsets[vertex_index + 1] += offsets[vertex_index];
+        }
+        let mut cursors = offsets.clone();
+        let mut edge_indices = vec![0; offsets[vertex_num as usize]];
+        for (edge_index, &(i, j, _)) in weighted_edges.iter().enumerate() {
+            for vertex_index in [i, j] {
+                edge_indices[cursors[vertex_index as usize]] = edge_index as EdgeIndex;
+                cursors[vertex_index as usize] += 1;
+            }
+        }
+        Self { offsets, edge_indices }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn edges_of(&self, vertex_index: VertexIndex) -> &[EdgeIndex] {
+        &self.edge_indices[self.offsets[vertex_index as usize]..self.offsets[vertex_index as usize + 1]]
+    }
+}
+
+/// iterate over the edges around a vertex, from [`DualModuleSerial::compact_adjacency`] if present, otherwise from
+/// [`Vertex::edges`]
+pub enum VertexEdgeIter<'a> {
+    Compact {
+        edge_indices: core::slice::Iter<'a, EdgeIndex>,
+        edges: &'a [EdgePtr],
+    },
+    Pointers(core::slice::Iter<'a, EdgeWeak>),
+}
+
+impl<'a> VertexEdgeIter<'a> {
+    /// the compact adjacency only exists in a non-partitioned module, where the vertex index is also the local index
+    pub fn new(compact_adjacency: &'a Option<CompactAdjacency>, edges: &'a [EdgePtr], vertex: &'a Vertex) -> Self {
+        match compact_adjacency {
+            Some(compact_adjacency) => Self::Compact {
+                edge_indices: compact_adjacency.edges_of(vertex.vertex_index).iter(),
+                edges,
+            },
+            None => Self::Pointers(vertex.edges.iter()),
+        }
+    }
+}
+
+impl Iterator for VertexEdgeIter<'_> {
+    type Item = VertexEdgePtr;
+    #[allow(clippy::unnecessary_cast)]
+    fn next(&mut self) -> Option<VertexEdgePtr> {
+        match self {
+            Self::Compact { edge_indices, edges } => edge_indices
+                .next()
+                .map(|edge_index| share_edge_ptr(&edges[*edge_index as usize])),
+            Self::Pointers(edge_weaks) => edge_weaks.next().map(|edge_weak| edge_weak.upgrade_force()),
+        }
+    }
+}
+
+impl DualModuleSerial {
+    /// like [`DualModuleImpl::new_empty`], but keeps the edges around each vertex in
+    /// [`DualModuleSerial::compact_adjacency`] instead of as pointers in [`Vertex::edges`]
+    pub fn new_compact_adjacency(initializer: &SolverInitializer) -> Self {
+        Self::new_with_adjacency(initializer, true)
+    }
+
+    /// like [`Self::new_compact_adjacency`], but the edges are stored in the order of their (smaller, larger) vertex
+    /// indices instead of the arbitrary order of the initializer, so that the edges around nearby vertices are close
+    /// in memory when growing dual nodes. All the edge indices in the interface (erasures, dynamic weights, snapshots,
+    /// tight paths) are still the indices of the initializer, translated by [`Self::local_edge_indices`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_reordered(initializer: &SolverInitializer) -> Self {
+        let mut order: Vec<EdgeIndex> = (0..initializer.weighted_edges.len() as EdgeIndex).collect();
+        order.sort_by_key(|&edge_index| {
+            let (i, j, _) = initializer.weighted_edges[edge_index as usize];
+            (i.min(j), i.max(j))
+        });
+        let mut reordered_initializer = initializer.clone();
+        reordered_initializer.weighted_edges = order
+            .iter()
+            .map(|&edge_index| initializer.weighted_edges[edge_index as usize])
+            .collect();
+        let mut dual_module = Self::new_with_adjacency(&reordered_initializer, true);
+        let mut local_edge_indices = vec![0; order.len()];
+        for (local_edge_index, &edge_index) in order.iter().enumerate() {
+            dual_module.edges[local_edge_index].write_force().edge_index = edge_index;
+            local_edge_indices[edge_index as usize] = local_edge_index as EdgeIndex;
+        }
+        dual_module.local_edge_indices = Some(local_edge_indices);
+        dual_module
+    }
+
+    /// the index in [`Self::edges`] of an edge given in the global edge index, or `None` if a partitioned unit doesn't
+    /// hold the edge
+    #[allow(clippy::unnecessary_cast)]
+    pub fn local_edge_index(&self, edge_index: EdgeIndex) -> Option<EdgeIndex> {
+        match (self.unit_module_info.as_ref(), self.local_edge_indices.as_ref()) {
+            (Some(unit_module_info), _) => unit_module_info.edge_indices.get(&edge_index).copied(),
+            (None, Some(local_edge_indices)) => Some(local_edge_indices[edge_index as usize]),
+            (None, None) => Some(edge_index),
+        }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn new_with_adjacency(initializer: &SolverInitializer, compact: bool) -> Self {
         let active_timestamp = 0;
         // create vertices
         let vertices: Vec<VertexPtr> = (0..initializer.vertex_num)
@@ -271,7 +492,7 @@ impl DualModuleImpl for DualModuleSerial {
                         let edge = edge_ptr.read_recursive(active_timestamp);
                         if edge.left == vertices[b as usize].downgrade() || edge.right == vertices[b as usize].downgrade() {
                             no_duplicate = false;
-                            eprintln!("duplicated edge between {} and {} with weight w1 = {} and w2 = {}, consider merge them into a single edge", i, j, weight, edge.weight);
+                            log_warn!(left = i, right = j, w1 = weight, w2 = edge.weight; "duplicated edge between {} and {} with weight w1 = {} and w2 = {}, consider merge them into a single edge", i, j, weight, edge.weight);
                             break;
                         }
                     }
@@ -281,11 +502,20 @@ impl DualModuleImpl for DualModuleSerial {
             }
             edges.push(edge_ptr);
         }
+        let compact_adjacency = if compact {
+            for vertex_ptr in vertices.iter() {
+                vertex_ptr.write(active_timestamp).edges = Vec::new();
+            }
+            Some(CompactAdjacency::new(initializer.vertex_num, &initializer.weighted_edges))
+        } else {
+            None
+        };
         Self {
             vertices,
             nodes: vec![],
             nodes_length: 0,
             edges,
+            compact_adjacency,
             active_timestamp: 0,
             vertex_num: initializer.vertex_num,
             edge_num: initializer.weighted_edges.len(),
@@ -298,8 +528,28 @@ impl DualModuleImpl for DualModuleSerial {
             sync_requests: vec![],
             updated_boundary: vec![],
             propagating_vertices: vec![],
+            boundary_capacity: 0,
+            current_capacities: CapacityHints::default(),
+            observed_capacities: CapacityHints::default(),
+            peak_active_nodes: 0,
+            virtual_touch_observer: None,
+            virtual_touches: HashSet::new(),
+            stale_boundary_repairs: 0,
+            enable_max_update_length_cache: true,
+            uniform_growth: 0,
+            max_update_length_epoch: 0,
+            max_update_length_cache_hits: 0,
+            local_edge_indices: None,
         }
     }
+}
+
+impl DualModuleImpl for DualModuleSerial {
+    /// initialize the dual module, which is supposed to be reused for multiple decoding tasks with the same structure
+    #[allow(clippy::unnecessary_cast)]
+    fn new_empty(initializer: &SolverInitializer) -> Self {
+        Self::new_with_adjacency(initializer, false)
+    }
 
     /// clear all growth and existing dual nodes
     #[allow(clippy::unnecessary_cast)]
@@ -315,39 +565,32 @@ impl DualModuleImpl for DualModuleSerial {
         self.nodes_length = 0; // without actually dropping all the nodes, to enable constant time clear
         if let Some(unit_module_info) = self.unit_module_info.as_mut() {
             unit_module_info.owning_dual_range = VertexRange::new(0, 0);
-            unit_module_info.dual_node_pointers = PtrWeakKeyHashMap::<DualNodeWeak, usize>::new();
+            unit_module_info.dual_node_pointers = DualNodePointers::new();
         }
         self.active_list.clear();
+        self.current_capacities = CapacityHints::default();
+        self.peak_active_nodes = 0;
+        self.virtual_touches.clear();
+        self.stale_boundary_repairs = 0;
+        self.uniform_growth = 0;
+        self.max_update_length_epoch += 1;
+        self.max_update_length_cache_hits = 0;
     }
 
     /// add a new dual node from dual module root
     #[allow(clippy::unnecessary_cast)]
     fn add_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
+        self.max_update_length_epoch += 1;
         self.register_dual_node_ptr(dual_node_ptr);
         let active_timestamp = self.active_timestamp;
         let node = dual_node_ptr.read_recursive();
         let node_index = self.nodes_length as NodeIndex;
-        let node_internal_ptr = if node_index < self.nodes.len() as NodeIndex && self.nodes[node_index as usize].is_some() {
-            let node_ptr = self.nodes[node_index as usize].take().unwrap();
-            let mut node = node_ptr.write();
-            node.origin = dual_node_ptr.downgrade();
-            node.index = node_index;
-            node.dual_variable = 0;
-            node.boundary.clear();
-            node.overgrown_stack.clear();
-            node.last_visit_cycle = 0;
-            drop(node);
-            node_ptr
-        } else {
-            DualNodeInternalPtr::new_value(DualNodeInternal {
-                origin: dual_node_ptr.downgrade(),
-                index: node_index,
-                dual_variable: 0,
-                boundary: Vec::new(),
-                overgrown_stack: Vec::new(),
-                last_visit_cycle: 0,
-            })
-        };
+        let node_internal_ptr = self.reuse_or_create_node_internal(dual_node_ptr, node_index);
+        match &node.class {
+            DualNodeClass::Blossom { .. } => self.current_capacities.max_blossoms += 1,
+            DualNodeClass::DefectVertex { .. } => self.current_capacities.max_defects += 1,
+        }
+        self.observed_capacities = self.observed_capacities.max(&self.current_capacities);
         {
             let boundary = &mut node_internal_ptr.write().boundary;
             match &node.class {
@@ -397,37 +640,7 @@ impl DualModuleImpl for DualModuleSerial {
                     }
                 }
                 DualNodeClass::DefectVertex { defect_index } => {
-                    let vertex_index = self
-                        .get_vertex_index(*defect_index)
-                        .expect("syndrome not belonging to this dual module");
-                    let vertex_ptr = &self.vertices[vertex_index];
-                    vertex_ptr.dynamic_clear(active_timestamp);
-                    let mut vertex = vertex_ptr.write(active_timestamp);
-                    vertex.propagated_dual_node = Some(node_internal_ptr.downgrade());
-                    vertex.propagated_grandson_dual_node = Some(node_internal_ptr.downgrade());
-                    vertex.is_defect = true;
-                    for edge_weak in vertex.edges.iter() {
-                        let edge_ptr = edge_weak.upgrade_force();
-                        edge_ptr.dynamic_clear(active_timestamp);
-                        let mut edge = edge_ptr.write(active_timestamp);
-                        let is_left = vertex_ptr.downgrade() == edge.left;
-                        debug_assert!(
-                            if is_left {
-                                edge.left_dual_node.is_none()
-                            } else {
-                                edge.right_dual_node.is_none()
-                            },
-                            "dual node of edge should be none"
-                        );
-                        if is_left {
-                            edge.left_dual_node = Some(node_internal_ptr.downgrade());
-                            edge.left_grandson_dual_node = Some(node_internal_ptr.downgrade());
-                        } else {
-                            edge.right_dual_node = Some(node_internal_ptr.downgrade());
-                            edge.right_grandson_dual_node = Some(node_internal_ptr.downgrade());
-                        }
-                        boundary.push((is_left, edge_weak.clone()));
-                    }
+                    self.attach_defect_vertex(*defect_index, &node_internal_ptr, boundary);
                 }
             }
         }
@@ -439,8 +652,46 @@ impl DualModuleImpl for DualModuleSerial {
         self.nodes[node_index as usize] = Some(node_internal_ptr);
     }
 
+    /// add many defect nodes at once, e.g. when loading a syndrome: the node list and the active list are grown and
+    /// the capacities are recorded once for the whole batch instead of once per node
+    #[allow(clippy::unnecessary_cast)]
+    fn add_defect_nodes_batch(&mut self, dual_node_ptrs: &[DualNodePtr]) {
+        self.max_update_length_epoch += 1;
+        if self.unit_module_info.is_some() {
+            // partitioned units keep a lookup table of the dual nodes they host, which is maintained one by one
+            for dual_node_ptr in dual_node_ptrs.iter() {
+                self.add_defect_node(dual_node_ptr);
+            }
+            return;
+        }
+        let new_nodes_length = self.nodes_length + dual_node_ptrs.len();
+        if self.nodes.len() < new_nodes_length {
+            self.nodes.resize(new_nodes_length, None);
+        }
+        self.active_list.reserve(dual_node_ptrs.len());
+        for dual_node_ptr in dual_node_ptrs.iter() {
+            let node_index = self.nodes_length as NodeIndex;
+            let defect_index = {
+                let node = dual_node_ptr.read_recursive();
+                debug_assert_eq!(node.index, node_index, "dual nodes must be added in a sequential manner");
+                match &node.class {
+                    DualNodeClass::DefectVertex { defect_index } => *defect_index,
+                    DualNodeClass::Blossom { .. } => unreachable!("node class mismatch"),
+                }
+            };
+            let node_internal_ptr = self.reuse_or_create_node_internal(dual_node_ptr, node_index);
+            self.attach_defect_vertex(defect_index, &node_internal_ptr, &mut node_internal_ptr.write().boundary);
+            self.active_list.push(node_internal_ptr.downgrade());
+            self.nodes[node_index as usize] = Some(node_internal_ptr);
+            self.nodes_length += 1;
+        }
+        self.current_capacities.max_defects += dual_node_ptrs.len();
+        self.observed_capacities = self.observed_capacities.max(&self.current_capacities);
+    }
+
     #[allow(clippy::unnecessary_cast)]
     fn remove_blossom(&mut self, dual_node_ptr: DualNodePtr) {
+        self.max_update_length_epoch += 1;
         let active_timestamp = self.active_timestamp;
         self.prepare_dual_node_growth(&dual_node_ptr, false); // prepare the blossom into shrinking
         let node = dual_node_ptr.read_recursive();
@@ -515,6 +766,13 @@ impl DualModuleImpl for DualModuleSerial {
     }
 
     fn set_grow_state(&mut self, dual_node_ptr: &DualNodePtr, grow_state: DualNodeGrowState) {
+        if self.enable_max_update_length_cache {
+            // the constraints of the peers depend on the grow state of this node
+            let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
+            dual_node_internal_ptr.write().max_update_length_cache = None;
+            let boundary = dual_node_internal_ptr.read_recursive().boundary.clone();
+            self.invalidate_max_update_length_around(&boundary);
+        }
         let dual_node = dual_node_ptr.read_recursive();
         if dual_node.grow_state == DualNodeGrowState::Stay && grow_state != DualNodeGrowState::Stay {
             let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
@@ -522,429 +780,359 @@ impl DualModuleImpl for DualModuleSerial {
         }
     }
 
-    #[allow(clippy::collapsible_else_if)]
+    fn check_grow_state(&self, dual_node_ptr: &DualNodePtr, grow_state: DualNodeGrowState) -> Result<(), String> {
+        let dual_node_index = self
+            .get_dual_node_index(dual_node_ptr)
+            .ok_or_else(|| "not hosted by this dual module".to_string())?;
+        let dual_node_internal_ptr = match self.nodes.get(dual_node_index) {
+            Some(Some(dual_node_internal_ptr)) => dual_node_internal_ptr,
+            _ => return Err(format!("internal node {dual_node_index} doesn't exist")),
+        };
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        if &dual_node_internal.origin.upgrade_force() != dual_node_ptr {
+            return Err(format!("internal node {dual_node_index} belongs to another dual node"));
+        }
+        if grow_state == DualNodeGrowState::Shrink {
+            // the overgrown vertices are restored first when shrinking, which must not take more than the dual variable
+            let overgrown: Weight = dual_node_internal
+                .overgrown_stack
+                .iter()
+                .map(|(_, overgrown)| overgrown)
+                .sum();
+            if overgrown > dual_node_internal.dual_variable {
+                return Err(format!(
+                    "overgrown length {overgrown} exceeds the dual variable {}",
+                    dual_node_internal.dual_variable
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn compute_maximum_update_length_dual_node(
         &mut self,
         dual_node_ptr: &DualNodePtr,
         is_grow: bool,
         simultaneous_update: bool,
     ) -> MaxUpdateLength {
+        self.compute_maximum_update_length_dual_node_repair(dual_node_ptr, is_grow, simultaneous_update, true)
+    }
+
+    fn compute_maximum_update_length(&mut self) -> GroupMaxUpdateLength {
+        // first prepare all nodes for individual grow or shrink; Stay nodes will be prepared to shrink in order to minimize effect on others
+        self.prepare_all();
+        // after preparing all the growth, there should be no sync requests
+        debug_assert!(
+            self.sync_requests.is_empty(),
+            "no sync requests should arise here; make sure to deal with all sync requests before growing"
+        );
+        let mut group_max_update_length = GroupMaxUpdateLength::new();
+        for i in 0..self.active_list.len() {
+            let dual_node_ptr = {
+                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
+                let dual_node_internal = internal_dual_node_ptr.read_recursive();
+                dual_node_internal.origin.upgrade_force()
+            };
+            let dual_node = dual_node_ptr.read_recursive();
+            let is_grow = match dual_node.grow_state {
+                DualNodeGrowState::Grow => true,
+                DualNodeGrowState::Shrink => false,
+                DualNodeGrowState::Stay => continue,
+            };
+            drop(dual_node); // unlock, otherwise it causes deadlock when updating the dual node
+            let max_update_length = if self.enable_max_update_length_cache {
+                self.cached_maximum_update_length_dual_node(&dual_node_ptr, is_grow)
+            } else {
+                self.compute_maximum_update_length_dual_node(&dual_node_ptr, is_grow, true)
+            };
+            group_max_update_length.add(max_update_length);
+        }
+        group_max_update_length
+    }
+
+    fn grow_dual_node_checked(&mut self, dual_node_ptr: &DualNodePtr, length: Weight) -> Result<(), GrowthError> {
+        if length == 0 {
+            return Ok(());
+        }
+        let allowed = self.maximum_dual_node_growth(dual_node_ptr, length > 0);
+        if length.abs() > allowed {
+            return Err(GrowthError::ExceedsBound {
+                requested: length,
+                allowed: allowed * length.signum(),
+            });
+        }
+        // growing a single node changes the constraints of its peers by a different length than the others
+        self.max_update_length_epoch += 1;
+        self.apply_dual_node_growth(dual_node_ptr, length);
+        Ok(())
+    }
+
+    fn grow(&mut self, length: Weight) {
+        debug_assert!(length > 0, "only positive growth is supported");
+        self.renew_active_list();
+        // first handle shrinks and then grow, to make sure they don't conflict
+        for i in 0..self.active_list.len() {
+            let dual_node_ptr = {
+                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
+                let dual_node_internal = internal_dual_node_ptr.read_recursive();
+                dual_node_internal.origin.upgrade_force()
+            };
+            let dual_node = dual_node_ptr.read_recursive();
+            if matches!(dual_node.grow_state, DualNodeGrowState::Shrink) {
+                self.apply_dual_node_growth(&dual_node_ptr, -length);
+            }
+        }
+        // then grow those needed
+        for i in 0..self.active_list.len() {
+            let dual_node_ptr = {
+                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
+                let dual_node_internal = internal_dual_node_ptr.read_recursive();
+                dual_node_internal.origin.upgrade_force()
+            };
+            let dual_node = dual_node_ptr.read_recursive();
+            if matches!(dual_node.grow_state, DualNodeGrowState::Grow) {
+                self.apply_dual_node_growth(&dual_node_ptr, length);
+            }
+        }
+        self.uniform_growth += length;
+    }
+
+    fn grow_subset(&mut self, nodes: &[(DualNodePtr, Weight)]) {
+        self.max_update_length_epoch += 1;
+        debug_assert!(
+            {
+                let mut node_indices: Vec<NodeIndex> = nodes.iter().map(|(ptr, _)| ptr.read_recursive().index).collect();
+                node_indices.sort_unstable();
+                node_indices.windows(2).all(|pair| pair[0] != pair[1])
+            },
+            "a dual node cannot be listed twice in the same subset growth"
+        );
+        // same as `grow`: first handle shrinks and then grow, to make sure they don't conflict
+        for (dual_node_ptr, length) in nodes.iter() {
+            if *length < 0 {
+                self.grow_dual_node(dual_node_ptr, *length);
+            }
+        }
+        for (dual_node_ptr, length) in nodes.iter() {
+            if *length > 0 {
+                self.grow_dual_node(dual_node_ptr, *length);
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
+        self.max_update_length_epoch += 1;
+        debug_assert!(
+            !self.edge_modifier.has_modified_edges(),
+            "the current erasure modifier is not clean, probably forget to clean the state?"
+        );
         let active_timestamp = self.active_timestamp;
-        if !simultaneous_update {
-            // when `simultaneous_update` is set, it's assumed that all nodes are prepared to grow or shrink
-            // this is because if we dynamically prepare them, it would be inefficient
-            self.prepare_dual_node_growth(dual_node_ptr, is_grow);
+        for (edge_index, target_weight) in edge_modifier.iter() {
+            // the modifier is given in global edge indices, while a partitioned module only holds some of the edges
+            let Some(local_edge_index) = self.local_edge_index(*edge_index) else {
+                continue;
+            };
+            let edge_ptr = &self.edges[local_edge_index as usize];
+            edge_ptr.dynamic_clear(active_timestamp); // may visit stale edges
+            let mut edge = edge_ptr.write(active_timestamp);
+            let original_weight = edge.weight;
+            edge.weight = *target_weight;
+            self.edge_modifier.push_modified_edge(local_edge_index, original_weight);
         }
-        let mut max_length_abs = Weight::MAX;
+    }
+
+    fn touching_virtual_vertices(&mut self, dual_node_ptr: &DualNodePtr) -> Vec<(DualNodePtr, VertexIndex)> {
+        let active_timestamp = self.active_timestamp;
         let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
         let dual_node_internal = dual_node_internal_ptr.read_recursive();
-        if !is_grow {
-            if dual_node_internal.dual_variable == 0 {
-                let dual_node = dual_node_ptr.read_recursive();
-                match dual_node.class {
-                    DualNodeClass::Blossom { .. } => return MaxUpdateLength::BlossomNeedExpand(dual_node_ptr.clone()),
-                    DualNodeClass::DefectVertex { defect_index } => {
-                        // try to report Conflicting event or give a VertexShrinkStop with potential conflicting node
-                        if let Some(vertex_index) = self.get_vertex_index(defect_index) {
-                            // since propagated node is never removed, this event could happen with no vertex
-                            let vertex_ptr = &self.vertices[vertex_index];
-                            let vertex = vertex_ptr.read_recursive(active_timestamp);
-                            let mut potential_conflict: Option<(DualNodePtr, DualNodePtr)> = None;
-                            for edge_weak in vertex.edges.iter() {
-                                let edge_ptr = edge_weak.upgrade_force();
-                                let edge = edge_ptr.read_recursive(active_timestamp);
-                                let is_left = vertex_ptr.downgrade() == edge.left;
-                                let remaining_length = edge.weight - edge.left_growth - edge.right_growth;
-                                if remaining_length == 0 {
-                                    let peer_dual_node = if is_left {
-                                        &edge.right_dual_node
-                                    } else {
-                                        &edge.left_dual_node
-                                    };
-                                    if let Some(peer_dual_node_ptr) = peer_dual_node {
-                                        let peer_grandson_dual_node = if is_left {
-                                            &edge.right_grandson_dual_node
-                                        } else {
-                                            &edge.left_grandson_dual_node
-                                        };
-                                        let peer_dual_node_ptr =
-                                            peer_dual_node_ptr.upgrade_force().read_recursive().origin.upgrade_force();
-                                        let peer_grandson_dual_node_ptr = peer_grandson_dual_node
-                                            .as_ref()
-                                            .unwrap()
-                                            .upgrade_force()
-                                            .read_recursive()
-                                            .origin
-                                            .upgrade_force();
-                                        if peer_dual_node_ptr.read_recursive().grow_state == DualNodeGrowState::Grow {
-                                            if let Some((other_dual_node_ptr, other_grandson_dual_node)) =
-                                                &potential_conflict
-                                            {
-                                                if &peer_dual_node_ptr != other_dual_node_ptr {
-                                                    return MaxUpdateLength::Conflicting(
-                                                        (other_dual_node_ptr.clone(), other_grandson_dual_node.clone()),
-                                                        (peer_dual_node_ptr, peer_grandson_dual_node_ptr),
-                                                    );
-                                                }
-                                            } else {
-                                                potential_conflict = Some((peer_dual_node_ptr, peer_grandson_dual_node_ptr));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            return MaxUpdateLength::VertexShrinkStop((dual_node_ptr.clone(), potential_conflict));
-                        } else {
-                            return MaxUpdateLength::VertexShrinkStop((dual_node_ptr.clone(), None));
-                        }
-                    }
-                }
-            }
-            if !dual_node_internal.overgrown_stack.is_empty() {
-                let last_index = dual_node_internal.overgrown_stack.len() - 1;
-                let (_, overgrown) = &dual_node_internal.overgrown_stack[last_index];
-                max_length_abs = std::cmp::min(max_length_abs, *overgrown);
-            }
-            max_length_abs = std::cmp::min(max_length_abs, dual_node_internal.dual_variable);
-        }
+        let mut touching_virtual_vertices = vec![];
         for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
             let edge_ptr = edge_weak.upgrade_force();
-            let is_left = *is_left;
             let edge = edge_ptr.read_recursive(active_timestamp);
-            if is_grow {
-                // first check if both side belongs to the same tree node, if so, no constraint on this edge
-                let peer_dual_node_internal_ptr: Option<DualNodeInternalPtr> = if is_left {
-                    edge.right_dual_node.as_ref().map(|ptr| ptr.upgrade_force())
-                } else {
-                    edge.left_dual_node.as_ref().map(|ptr| ptr.upgrade_force())
-                };
-                match peer_dual_node_internal_ptr {
-                    Some(peer_dual_node_internal_ptr) => {
-                        if peer_dual_node_internal_ptr == dual_node_internal_ptr {
-                            continue;
-                        } else {
-                            let peer_dual_node_internal = peer_dual_node_internal_ptr.read_recursive();
-                            let peer_dual_node_ptr = peer_dual_node_internal.origin.upgrade_force();
-                            let peer_dual_node = peer_dual_node_ptr.read_recursive();
-                            let remaining_length = edge.weight - edge.left_growth - edge.right_growth;
-                            let local_max_length_abs = match peer_dual_node.grow_state {
-                                DualNodeGrowState::Grow => {
-                                    debug_assert!(remaining_length % 2 == 0, "there is odd gap between two growing nodes, please make sure all weights are even numbers");
-                                    remaining_length / 2
-                                }
-                                DualNodeGrowState::Shrink => {
-                                    // Yue 2022.9.5: remove Conflicting event detection here, move it to the 0-dual syndrome node
-                                    continue;
-                                }
-                                DualNodeGrowState::Stay => remaining_length,
-                            };
-                            if local_max_length_abs == 0 {
-                                let peer_grandson_ptr = if is_left {
-                                    edge.right_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                } else {
-                                    edge.left_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                };
-                                let grandson_ptr = if is_left {
-                                    edge.left_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                } else {
-                                    edge.right_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                };
-                                return MaxUpdateLength::Conflicting(
-                                    (peer_dual_node_ptr.clone(), peer_grandson_ptr),
-                                    (dual_node_ptr.clone(), grandson_ptr),
-                                );
-                            }
-                            max_length_abs = std::cmp::min(max_length_abs, local_max_length_abs);
-                        }
-                    }
-                    None => {
-                        let local_max_length_abs = edge.weight - edge.left_growth - edge.right_growth;
-                        if local_max_length_abs == 0 {
-                            // check if peer is virtual node
-                            let peer_vertex_ptr = if is_left {
-                                edge.right.upgrade_force()
-                            } else {
-                                edge.left.upgrade_force()
-                            };
-                            let peer_vertex = peer_vertex_ptr.read_recursive(active_timestamp);
-                            if peer_vertex.is_virtual || peer_vertex.is_mirror_blocked() {
-                                let grandson_ptr = if is_left {
-                                    edge.left_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                } else {
-                                    edge.right_grandson_dual_node
-                                        .as_ref()
-                                        .map(|ptr| ptr.upgrade_force())
-                                        .unwrap()
-                                        .read_recursive()
-                                        .origin
-                                        .upgrade_force()
-                                };
-                                return MaxUpdateLength::TouchingVirtual(
-                                    (dual_node_ptr.clone(), grandson_ptr),
-                                    (peer_vertex.vertex_index, peer_vertex.is_mirror_blocked()),
-                                );
-                            } else {
-                                println!("edge: {edge_ptr:?}, peer_vertex_ptr: {peer_vertex_ptr:?}");
-                                unreachable!("this edge should've been removed from boundary because it's already fully grown, and it's peer vertex is not virtual")
-                            }
-                        }
-                        max_length_abs = std::cmp::min(max_length_abs, local_max_length_abs);
-                    }
-                }
+            let (peer_dual_node, peer_weak, grandson_dual_node) = if *is_left {
+                (&edge.right_dual_node, &edge.right, &edge.left_grandson_dual_node)
             } else {
-                if is_left {
-                    if edge.left_growth == 0 {
-                        unreachable!()
-                    }
-                    max_length_abs = std::cmp::min(max_length_abs, edge.left_growth);
-                } else {
-                    if edge.right_growth == 0 {
-                        unreachable!()
+                (&edge.left_dual_node, &edge.left, &edge.right_grandson_dual_node)
+            };
+            if peer_dual_node.is_some() || edge.weight - edge.left_growth - edge.right_growth != 0 {
+                continue;
+            }
+            let peer_vertex_ptr = peer_weak.upgrade_force();
+            let peer_vertex = peer_vertex_ptr.read_recursive(active_timestamp);
+            if !peer_vertex.is_virtual {
+                continue;
+            }
+            let touching_ptr = grandson_dual_node
+                .as_ref()
+                .map(|ptr| ptr.upgrade_force())
+                .unwrap()
+                .read_recursive()
+                .origin
+                .upgrade_force();
+            touching_virtual_vertices.push((touching_ptr, peer_vertex.vertex_index));
+        }
+        touching_virtual_vertices
+    }
+
+    fn reserve_capacity(&mut self, hints: &CapacityHints) {
+        let max_nodes = hints.max_nodes();
+        self.nodes.reserve(max_nodes.saturating_sub(self.nodes.len()));
+        self.active_list.reserve(max_nodes.saturating_sub(self.active_list.len()));
+        self.updated_boundary
+            .reserve(hints.max_boundary_per_node.saturating_sub(self.updated_boundary.len()));
+        for node_ptr in self.nodes.iter().flatten() {
+            let mut node = node_ptr.write();
+            let additional = hints.max_boundary_per_node.saturating_sub(node.boundary.len());
+            node.boundary.reserve(additional);
+        }
+        self.boundary_capacity = hints.max_boundary_per_node;
+    }
+
+    fn observed_capacities(&self) -> CapacityHints {
+        self.observed_capacities
+    }
+
+    fn peak_active_nodes(&self) -> usize {
+        self.peak_active_nodes
+    }
+
+    fn check_clean(&self) -> Result<(), String> {
+        if self.edge_modifier.has_modified_edges() {
+            return Err("leftover erasures or dynamic weights in the dual module".to_string());
+        }
+        if self.nodes_length != 0 || !self.active_list.is_empty() {
+            return Err(format!(
+                "dual module still has {} dual nodes and {} active nodes",
+                self.nodes_length,
+                self.active_list.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Dijkstra's algorithm restricted to tight edges; it uses the current (possibly modified) edge weights
+    fn get_tight_path(&self, vertex_1: VertexIndex, vertex_2: VertexIndex) -> Option<Vec<EdgeIndex>> {
+        let source = self.get_vertex_index(vertex_1)?;
+        let target = self.get_vertex_index(vertex_2)?;
+        let active_timestamp = self.active_timestamp;
+        // { local vertex index: (distance, previous local vertex index, edge index) }
+        let mut visited = HashMap::<usize, (Weight, usize, EdgeIndex)>::new();
+        // ties in distance are broken by the number of edges: otherwise every query sweeps the whole zero-weight
+        // region around the source (e.g. erasures) in the order of vertex index, which is quadratic over all pairs
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0, 0, source, source, EdgeIndex::MAX)));
+        while let Some(Reverse((distance, hops, local_index, previous, via_edge))) = heap.pop() {
+            if visited.contains_key(&local_index) {
+                continue;
+            }
+            visited.insert(local_index, (distance, previous, via_edge));
+            if local_index == target {
+                break;
+            }
+            let vertex_ptr = &self.vertices[local_index];
+            vertex_ptr.dynamic_clear(active_timestamp);
+            let (vertex_index, edges) = {
+                let vertex = vertex_ptr.read_recursive(active_timestamp);
+                let edges: Vec<VertexEdgePtr> = VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex).collect();
+                (vertex.vertex_index, edges)
+            };
+            for edge_ptr in edges.iter() {
+                edge_ptr.dynamic_clear(active_timestamp);
+                let edge = edge_ptr.read_recursive(active_timestamp);
+                if edge.left_growth + edge.right_growth < edge.weight {
+                    continue;
+                }
+                let left_index = edge.left.upgrade_force().read_recursive_force().vertex_index;
+                let right_index = edge.right.upgrade_force().read_recursive_force().vertex_index;
+                let peer_index = if left_index == vertex_index { right_index } else { left_index };
+                if let Some(peer_local_index) = self.get_vertex_index(peer_index) {
+                    if !visited.contains_key(&peer_local_index) {
+                        heap.push(Reverse((
+                            distance + edge.weight,
+                            hops + 1,
+                            peer_local_index,
+                            local_index,
+                            edge.edge_index,
+                        )));
                     }
-                    max_length_abs = std::cmp::min(max_length_abs, edge.right_growth);
                 }
             }
         }
-        MaxUpdateLength::NonZeroGrow((max_length_abs, dual_node_internal.boundary.is_empty()))
+        visited.get(&target)?;
+        let mut path = vec![];
+        let mut local_index = target;
+        while local_index != source {
+            let (_, previous, via_edge) = visited[&local_index];
+            path.push(via_edge);
+            local_index = previous;
+        }
+        path.reverse();
+        Some(path)
     }
 
-    fn compute_maximum_update_length(&mut self) -> GroupMaxUpdateLength {
-        // first prepare all nodes for individual grow or shrink; Stay nodes will be prepared to shrink in order to minimize effect on others
-        self.prepare_all();
-        // after preparing all the growth, there should be no sync requests
+    fn prepare_all(&mut self) -> &mut Vec<SyncRequest> {
         debug_assert!(
             self.sync_requests.is_empty(),
-            "no sync requests should arise here; make sure to deal with all sync requests before growing"
+            "make sure to remove all sync requests before prepare to avoid out-dated requests"
         );
-        let mut group_max_update_length = GroupMaxUpdateLength::new();
+        self.renew_active_list();
         for i in 0..self.active_list.len() {
             let dual_node_ptr = {
-                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
-                let dual_node_internal = internal_dual_node_ptr.read_recursive();
-                dual_node_internal.origin.upgrade_force()
+                if let Some(internal_dual_node_ptr) = self.active_list[i].upgrade() {
+                    let dual_node_internal = internal_dual_node_ptr.read_recursive();
+                    dual_node_internal.origin.upgrade_force()
+                } else {
+                    continue; // a blossom could be in the active list even after it's been removed
+                }
             };
             let dual_node = dual_node_ptr.read_recursive();
-            let is_grow = match dual_node.grow_state {
-                DualNodeGrowState::Grow => true,
-                DualNodeGrowState::Shrink => false,
-                DualNodeGrowState::Stay => continue,
+            match dual_node.grow_state {
+                DualNodeGrowState::Grow => {}
+                DualNodeGrowState::Shrink => {
+                    self.prepare_dual_node_growth(&dual_node_ptr, false);
+                }
+                DualNodeGrowState::Stay => {} // do not touch, Stay nodes might have become a part of a blossom, so it's not safe to change the boundary
             };
-            drop(dual_node); // unlock, otherwise it causes deadlock when updating the dual node
-            let max_update_length = self.compute_maximum_update_length_dual_node(&dual_node_ptr, is_grow, true);
-            group_max_update_length.add(max_update_length);
         }
-        group_max_update_length
+        for i in 0..self.active_list.len() {
+            let dual_node_ptr = {
+                if let Some(internal_dual_node_ptr) = self.active_list[i].upgrade() {
+                    let dual_node_internal = internal_dual_node_ptr.read_recursive();
+                    dual_node_internal.origin.upgrade_force()
+                } else {
+                    continue; // a blossom could be in the active list even after it's been removed
+                }
+            };
+            let dual_node = dual_node_ptr.read_recursive();
+            match dual_node.grow_state {
+                DualNodeGrowState::Grow => {
+                    self.prepare_dual_node_growth(&dual_node_ptr, true);
+                }
+                DualNodeGrowState::Shrink => {}
+                DualNodeGrowState::Stay => {} // do not touch, Stay nodes might have become a part of a blossom, so it's not safe to change the boundary
+            };
+        }
+        &mut self.sync_requests
     }
 
-    fn grow_dual_node(&mut self, dual_node_ptr: &DualNodePtr, length: Weight) {
-        let active_timestamp = self.active_timestamp;
-        if length == 0 {
-            eprintln!("[warning] calling `grow_dual_node` with zero length, nothing to do");
-            return;
-        }
-        self.prepare_dual_node_growth(dual_node_ptr, length > 0);
-        let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
-        {
-            // update node dual variable and do sanity check
-            let mut dual_node_internal = dual_node_internal_ptr.write();
-            dual_node_internal.dual_variable += length;
-            debug_assert!(
-                dual_node_internal.dual_variable >= 0,
-                "shrinking to negative dual variable is forbidden"
-            );
-            // update over-grown vertices
-            if !dual_node_internal.overgrown_stack.is_empty() {
-                let last_index = dual_node_internal.overgrown_stack.len() - 1;
-                let (_, overgrown) = &mut dual_node_internal.overgrown_stack[last_index];
-                if length < 0 {
-                    debug_assert!(*overgrown >= -length, "overgrown vertex cannot shrink so much");
-                }
-                *overgrown += length;
-            }
-        }
-        let dual_node_internal = dual_node_internal_ptr.read_recursive();
-        for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
-            let edge_ptr = edge_weak.upgrade_force();
-            let is_left = *is_left;
-            let (growth, weight) = {
-                // minimize writer lock acquisition
-                let mut edge = edge_ptr.write(active_timestamp);
-                if is_left {
-                    edge.left_growth += length;
-                    debug_assert!(edge.left_growth >= 0, "negative growth forbidden");
-                } else {
-                    edge.right_growth += length;
-                    debug_assert!(edge.right_growth >= 0, "negative growth forbidden");
-                }
-                (edge.left_growth + edge.right_growth, edge.weight)
-            };
-            let edge = edge_ptr.read_recursive(active_timestamp);
-            if growth > weight {
-                // first check for if both side belongs to the same dual node, if so, it's ok
-                let dual_node_internal_ptr_2: &Option<DualNodeInternalWeak> = if is_left {
-                    &edge.right_dual_node
-                } else {
-                    &edge.left_dual_node
-                };
-                if dual_node_internal_ptr_2.is_none()
-                    || dual_node_internal_ptr_2.as_ref().unwrap() != &dual_node_internal_ptr.downgrade()
-                {
-                    let left_ptr = edge.left.upgrade_force();
-                    let right_ptr = edge.right.upgrade_force();
-                    panic!(
-                        "over-grown edge ({},{}): {}/{}",
-                        left_ptr.read_recursive(active_timestamp).vertex_index,
-                        right_ptr.read_recursive(active_timestamp).vertex_index,
-                        growth,
-                        weight
-                    );
-                }
-            } else if growth < 0 {
-                let left_ptr = edge.left.upgrade_force();
-                let right_ptr = edge.right.upgrade_force();
-                panic!(
-                    "under-grown edge ({},{}): {}/{}",
-                    left_ptr.read_recursive(active_timestamp).vertex_index,
-                    right_ptr.read_recursive(active_timestamp).vertex_index,
-                    growth,
-                    weight
-                );
-            }
-        }
-    }
-
-    fn grow(&mut self, length: Weight) {
-        debug_assert!(length > 0, "only positive growth is supported");
-        self.renew_active_list();
-        // first handle shrinks and then grow, to make sure they don't conflict
-        for i in 0..self.active_list.len() {
-            let dual_node_ptr = {
-                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
-                let dual_node_internal = internal_dual_node_ptr.read_recursive();
-                dual_node_internal.origin.upgrade_force()
-            };
-            let dual_node = dual_node_ptr.read_recursive();
-            if matches!(dual_node.grow_state, DualNodeGrowState::Shrink) {
-                self.grow_dual_node(&dual_node_ptr, -length);
-            }
-        }
-        // then grow those needed
-        for i in 0..self.active_list.len() {
-            let dual_node_ptr = {
-                let internal_dual_node_ptr = self.active_list[i].upgrade_force();
-                let dual_node_internal = internal_dual_node_ptr.read_recursive();
-                dual_node_internal.origin.upgrade_force()
-            };
-            let dual_node = dual_node_ptr.read_recursive();
-            if matches!(dual_node.grow_state, DualNodeGrowState::Grow) {
-                self.grow_dual_node(&dual_node_ptr, length);
-            }
-        }
-    }
-
-    #[allow(clippy::unnecessary_cast)]
-    fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
-        debug_assert!(
-            !self.edge_modifier.has_modified_edges(),
-            "the current erasure modifier is not clean, probably forget to clean the state?"
-        );
-        let active_timestamp = self.active_timestamp;
-        for (edge_index, target_weight) in edge_modifier.iter() {
-            let edge_ptr = &self.edges[*edge_index as usize];
-            edge_ptr.dynamic_clear(active_timestamp); // may visit stale edges
-            let mut edge = edge_ptr.write(active_timestamp);
-            let original_weight = edge.weight;
-            edge.weight = *target_weight;
-            self.edge_modifier.push_modified_edge(*edge_index, original_weight);
-        }
-    }
-
-    fn prepare_all(&mut self) -> &mut Vec<SyncRequest> {
-        debug_assert!(
-            self.sync_requests.is_empty(),
-            "make sure to remove all sync requests before prepare to avoid out-dated requests"
-        );
-        self.renew_active_list();
-        for i in 0..self.active_list.len() {
-            let dual_node_ptr = {
-                if let Some(internal_dual_node_ptr) = self.active_list[i].upgrade() {
-                    let dual_node_internal = internal_dual_node_ptr.read_recursive();
-                    dual_node_internal.origin.upgrade_force()
-                } else {
-                    continue; // a blossom could be in the active list even after it's been removed
-                }
-            };
-            let dual_node = dual_node_ptr.read_recursive();
-            match dual_node.grow_state {
-                DualNodeGrowState::Grow => {}
-                DualNodeGrowState::Shrink => {
-                    self.prepare_dual_node_growth(&dual_node_ptr, false);
-                }
-                DualNodeGrowState::Stay => {} // do not touch, Stay nodes might have become a part of a blossom, so it's not safe to change the boundary
-            };
-        }
-        for i in 0..self.active_list.len() {
-            let dual_node_ptr = {
-                if let Some(internal_dual_node_ptr) = self.active_list[i].upgrade() {
-                    let dual_node_internal = internal_dual_node_ptr.read_recursive();
-                    dual_node_internal.origin.upgrade_force()
-                } else {
-                    continue; // a blossom could be in the active list even after it's been removed
-                }
-            };
-            let dual_node = dual_node_ptr.read_recursive();
-            match dual_node.grow_state {
-                DualNodeGrowState::Grow => {
-                    self.prepare_dual_node_growth(&dual_node_ptr, true);
-                }
-                DualNodeGrowState::Shrink => {}
-                DualNodeGrowState::Stay => {} // do not touch, Stay nodes might have become a part of a blossom, so it's not safe to change the boundary
-            };
+    fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
+        debug_assert!(
+            self.sync_requests.is_empty(),
+            "make sure to remove all sync requests before prepare to avoid out-dated requests"
+        );
+        for dual_node_ptr in nodes_circle.iter() {
+            if self.contains_dual_node(dual_node_ptr) {
+                self.prepare_dual_node_growth(dual_node_ptr, false); // prepare to shrink
+            }
         }
         &mut self.sync_requests
     }
 
-    fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
-        debug_assert!(
-            self.sync_requests.is_empty(),
-            "make sure to remove all sync requests before prepare to avoid out-dated requests"
-        );
-        for dual_node_ptr in nodes_circle.iter() {
-            if self.contains_dual_node(dual_node_ptr) {
-                self.prepare_dual_node_growth(dual_node_ptr, false); // prepare to shrink
-            }
-        }
-        &mut self.sync_requests
+    fn generate_profiler_report(&self) -> serde_json::Value {
+        json!({
+            "stale_boundary_repairs": self.stale_boundary_repairs,
+        })
     }
 
     fn contains_dual_node(&self, dual_node_ptr: &DualNodePtr) -> bool {
@@ -996,6 +1184,7 @@ impl DualModuleImpl for DualModuleSerial {
         }
         // set edges
         let mut edges = Vec::<EdgePtr>::new();
+        let mut edge_indices = HashMap::with_capacity(partitioned_initializer.weighted_edges.len());
         for &(i, j, weight, edge_index) in partitioned_initializer.weighted_edges.iter() {
             assert_ne!(i, j, "invalid edge from and to the same vertex {}", i);
             assert!(
@@ -1055,7 +1244,7 @@ impl DualModuleImpl for DualModuleSerial {
                         let edge = edge_ptr.read_recursive(active_timestamp);
                         if edge.left == vertices[b as usize].downgrade() || edge.right == vertices[b as usize].downgrade() {
                             no_duplicate = false;
-                            eprintln!("duplicated edge between {} and {} with weight w1 = {} and w2 = {}, consider merge them into a single edge", i, j, weight, edge.weight);
+                            log_warn!(left = i, right = j, w1 = weight, w2 = edge.weight; "duplicated edge between {} and {} with weight w1 = {} and w2 = {}, consider merge them into a single edge", i, j, weight, edge.weight);
                             break;
                         }
                     }
@@ -1063,6 +1252,7 @@ impl DualModuleImpl for DualModuleSerial {
                 });
                 vertex.edges.push(edge_ptr.downgrade());
             }
+            edge_indices.insert(edge_index, edges.len() as EdgeIndex);
             edges.push(edge_ptr);
         }
         Self {
@@ -1070,6 +1260,7 @@ impl DualModuleImpl for DualModuleSerial {
             nodes: vec![],
             nodes_length: 0,
             edges,
+            compact_adjacency: None, // the topology changes when fusing units
             active_timestamp: 0,
             vertex_num: partitioned_initializer.vertex_num,
             edge_num: partitioned_initializer.edge_num,
@@ -1078,7 +1269,8 @@ impl DualModuleImpl for DualModuleSerial {
                 unit_index: partitioned_initializer.unit_index,
                 mirrored_vertices,
                 owning_dual_range: VertexRange::new(0, 0),
-                dual_node_pointers: PtrWeakKeyHashMap::<DualNodeWeak, usize>::new(),
+                dual_node_pointers: DualNodePointers::new(),
+                edge_indices,
             }),
             active_list: vec![],
             current_cycle: 0,
@@ -1087,6 +1279,18 @@ impl DualModuleImpl for DualModuleSerial {
             sync_requests: vec![],
             updated_boundary: vec![],
             propagating_vertices: vec![],
+            boundary_capacity: 0,
+            current_capacities: CapacityHints::default(),
+            observed_capacities: CapacityHints::default(),
+            peak_active_nodes: 0,
+            virtual_touch_observer: None,
+            virtual_touches: HashSet::new(),
+            stale_boundary_repairs: 0,
+            enable_max_update_length_cache: false,
+            uniform_growth: 0,
+            max_update_length_epoch: 0,
+            max_update_length_cache_hits: 0,
+            local_edge_indices: None,
         }
     }
 
@@ -1099,6 +1303,7 @@ impl DualModuleImpl for DualModuleSerial {
     }
 
     fn execute_sync_event(&mut self, sync_event: &SyncRequest) {
+        self.max_update_length_epoch += 1;
         let active_timestamp = self.active_timestamp;
         debug_assert!(self.contains_vertex(sync_event.vertex_index));
         let propagated_dual_node_internal_ptr =
@@ -1177,8 +1382,7 @@ impl DualModuleImpl for DualModuleSerial {
                     }
                 }
                 // iterate over the edges around the vertex to add edges to the boundary
-                for edge_weak in vertex.edges.iter() {
-                    let edge_ptr = edge_weak.upgrade_force();
+                for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
                     edge_ptr.dynamic_clear(active_timestamp);
                     let mut edge = edge_ptr.write(active_timestamp);
                     let is_left = vertex_ptr.downgrade() == edge.left;
@@ -1198,11 +1402,11 @@ impl DualModuleImpl for DualModuleSerial {
                             edge.right_dual_node = None;
                             edge.right_grandson_dual_node = None;
                         };
-                        updated_boundary.push((!is_left, edge_weak.clone()));
+                        updated_boundary.push((!is_left, edge_ptr.downgrade()));
                     }
                 }
                 // update the boundary
-                std::mem::swap(&mut updated_boundary, &mut dual_node_internal.boundary);
+                core::mem::swap(&mut updated_boundary, &mut dual_node_internal.boundary);
             }
             // then update the vertex to the dual node
             if let Some(dual_node_internal_ptr) = propagated_dual_node_internal_ptr.as_ref() {
@@ -1211,8 +1415,7 @@ impl DualModuleImpl for DualModuleSerial {
                 vertex.propagated_dual_node = Some(dual_node_internal_ptr.downgrade());
                 vertex.propagated_grandson_dual_node = Some(grandson_dual_node_internal_ptr.downgrade());
                 lock_write!(dual_node_internal, dual_node_internal_ptr);
-                for edge_weak in vertex.edges.iter() {
-                    let edge_ptr = edge_weak.upgrade_force();
+                for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
                     edge_ptr.dynamic_clear(active_timestamp);
                     let mut edge = edge_ptr.write(active_timestamp);
                     let is_left = vertex_ptr.downgrade() == edge.left;
@@ -1231,7 +1434,7 @@ impl DualModuleImpl for DualModuleSerial {
                         edge.right_dual_node = Some(dual_node_internal_ptr.downgrade());
                         edge.right_grandson_dual_node = Some(grandson_dual_node_internal_ptr.downgrade());
                     }
-                    dual_node_internal.boundary.push((is_left, edge_weak.clone()));
+                    dual_node_internal.boundary.push((is_left, edge_ptr.downgrade()));
                 }
                 self.active_list.push(dual_node_internal_ptr.downgrade());
             }
@@ -1353,7 +1556,7 @@ impl DualModuleSerial {
         }
         self.current_cycle += 1;
         // renew the active_list
-        let mut updated_active_list = Vec::with_capacity(self.active_list.len());
+        let mut updated_active_list = Vec::with_capacity(self.active_list.capacity()); // keep reserved capacity
         for i in 0..self.active_list.len() {
             let (dual_node_ptr, internal_dual_node_ptr) = {
                 match self.active_list[i].upgrade() {
@@ -1380,6 +1583,7 @@ impl DualModuleSerial {
             };
         }
         self.active_list = updated_active_list;
+        self.peak_active_nodes = core::cmp::max(self.peak_active_nodes, self.active_list.len());
     }
 
     fn sanity_check_grandson(
@@ -1427,9 +1631,88 @@ impl DualModuleSerial {
         Ok(())
     }
 
+    /// scan all the weak pointers stored in vertices, edges and dual nodes and report the dead ones, which would otherwise
+    /// panic somewhere in `upgrade_force` without telling who holds them; vertices and edges cannot be checked with the
+    /// feature `dangerous_pointer` because their weak pointers are raw pointers
+    pub fn sanity_check_pointers(&self) -> Result<(), String> {
+        let active_timestamp = self.active_timestamp;
+        let mut dead_pointers = vec![];
+        let mut check = |is_alive: bool, holder: String| {
+            if !is_alive {
+                dead_pointers.push(holder);
+            }
+        };
+        for vertex_ptr in self.vertices.iter() {
+            vertex_ptr.dynamic_clear(active_timestamp);
+            let vertex = vertex_ptr.read_recursive(active_timestamp);
+            let vertex_index = vertex.vertex_index;
+            if let Some(mirror_unit) = vertex.mirror_unit.as_ref() {
+                check(mirror_unit.upgrade().is_some(), format!("vertex {vertex_index}: mirror_unit"));
+            }
+            for (i, edge_weak) in vertex.edges.iter().enumerate() {
+                check(is_edge_alive(edge_weak), format!("vertex {vertex_index}: edges[{i}]"));
+            }
+            if let Some(node_weak) = vertex.propagated_dual_node.as_ref() {
+                check(
+                    node_weak.upgrade().is_some(),
+                    format!("vertex {vertex_index}: propagated_dual_node"),
+                );
+            }
+            if let Some(node_weak) = vertex.propagated_grandson_dual_node.as_ref() {
+                check(
+                    node_weak.upgrade().is_some(),
+                    format!("vertex {vertex_index}: propagated_grandson_dual_node"),
+                );
+            }
+        }
+        for edge_ptr in self.edges.iter() {
+            edge_ptr.dynamic_clear(active_timestamp);
+            let edge = edge_ptr.read_recursive(active_timestamp);
+            let edge_index = edge.edge_index;
+            check(is_vertex_alive(&edge.left), format!("edge {edge_index}: left"));
+            check(is_vertex_alive(&edge.right), format!("edge {edge_index}: right"));
+            for (name, node_weak) in [
+                ("left_dual_node", &edge.left_dual_node),
+                ("left_grandson_dual_node", &edge.left_grandson_dual_node),
+                ("right_dual_node", &edge.right_dual_node),
+                ("right_grandson_dual_node", &edge.right_grandson_dual_node),
+            ] {
+                if let Some(node_weak) = node_weak.as_ref() {
+                    check(node_weak.upgrade().is_some(), format!("edge {edge_index}: {name}"));
+                }
+            }
+        }
+        for node_ptr in self.nodes.iter().take(self.nodes_length).flatten() {
+            let dual_node_internal = node_ptr.read_recursive();
+            let node_index = dual_node_internal.index;
+            check(
+                dual_node_internal.origin.upgrade().is_some(),
+                format!("dual node {node_index}: origin"),
+            );
+            for (i, (_, edge_weak)) in dual_node_internal.boundary.iter().enumerate() {
+                check(is_edge_alive(edge_weak), format!("dual node {node_index}: boundary[{i}]"));
+            }
+            for (i, (vertex_weak, _)) in dual_node_internal.overgrown_stack.iter().enumerate() {
+                check(
+                    is_vertex_alive(vertex_weak),
+                    format!("dual node {node_index}: overgrown_stack[{i}]"),
+                );
+            }
+        }
+        for (i, node_weak) in self.active_list.iter().enumerate() {
+            check(node_weak.upgrade().is_some(), format!("active_list[{i}]"));
+        }
+        if dead_pointers.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("dead weak pointers held by {}", dead_pointers.join(", ")))
+        }
+    }
+
     /// do a sanity check of if all the nodes are in consistent state
     #[allow(clippy::unnecessary_cast)]
     pub fn sanity_check(&self) -> Result<(), String> {
+        self.sanity_check_pointers()?;
         let active_timestamp = self.active_timestamp;
         for vertex_ptr in self.vertices.iter() {
             vertex_ptr.dynamic_clear(active_timestamp);
@@ -1647,6 +1930,117 @@ Implement internal helper functions that maintains the state of dual clusters
 */
 
 impl DualModuleSerial {
+    /// observe every dual node that touches a virtual vertex, e.g. to track logical observables or to detect a cluster
+    /// reaching the boundary; each (node, virtual vertex) pair is reported once per decoding, when the dual module
+    /// first reports it as [`MaxUpdateLength::TouchingVirtual`]. Mirrored vertices that only block the growth in a
+    /// partitioned unit are not virtual and thus not reported
+    pub fn set_virtual_touch_observer(&mut self, observer: impl FnMut(&DualNodePtr, VertexIndex) + Send + Sync + 'static) {
+        self.virtual_touch_observer = Some(Box::new(observer));
+    }
+
+    /// the grown fraction of an edge given in the global edge index, see [`Edge::growth_fraction`]; this is what the
+    /// visualizer draws from `left_growth` and `right_growth`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn edge_growth_fraction(&self, edge_index: EdgeIndex) -> f64 {
+        let local_edge_index = self
+            .local_edge_index(edge_index)
+            .unwrap_or_else(|| panic!("edge {edge_index} doesn't belong to this unit"));
+        let edge_ptr = &self.edges[local_edge_index as usize];
+        edge_ptr.dynamic_clear(self.active_timestamp);
+        let edge = edge_ptr.read_recursive(self.active_timestamp);
+        edge.growth_fraction()
+    }
+
+    /// the grown fractions of all the edges indexed by the global edge index, see [`Self::edge_growth_fraction`];
+    /// the edges that don't belong to this unit are 0.0
+    #[allow(clippy::unnecessary_cast)]
+    pub fn all_edge_growth_fractions(&self) -> Vec<f64> {
+        let mut fractions = vec![0.0; self.edge_num];
+        for edge_ptr in self.edges.iter() {
+            edge_ptr.dynamic_clear(self.active_timestamp);
+            let edge = edge_ptr.read_recursive(self.active_timestamp);
+            fractions[edge.edge_index as usize] = edge.growth_fraction();
+        }
+        fractions
+    }
+
+    fn notify_virtual_touch(&mut self, dual_node_ptr: &DualNodePtr, virtual_vertex_index: VertexIndex) {
+        if let Some(observer) = self.virtual_touch_observer.as_mut() {
+            let node_index = dual_node_ptr.read_recursive().index;
+            if self.virtual_touches.insert((node_index, virtual_vertex_index)) {
+                observer(dual_node_ptr, virtual_vertex_index);
+            }
+        }
+    }
+
+    /// reuse the internal node at `node_index` from a previous decoding if possible, otherwise allocate a new one
+    #[allow(clippy::unnecessary_cast)]
+    fn reuse_or_create_node_internal(&mut self, dual_node_ptr: &DualNodePtr, node_index: NodeIndex) -> DualNodeInternalPtr {
+        if node_index < self.nodes.len() as NodeIndex && self.nodes[node_index as usize].is_some() {
+            let node_ptr = self.nodes[node_index as usize].take().unwrap();
+            let mut node = node_ptr.write();
+            node.origin = dual_node_ptr.downgrade();
+            node.index = node_index;
+            node.dual_variable = 0;
+            node.boundary.clear();
+            node.overgrown_stack.clear();
+            node.last_visit_cycle = 0;
+            node.max_update_length_cache = None;
+            drop(node);
+            node_ptr
+        } else {
+            DualNodeInternalPtr::new_value(DualNodeInternal {
+                origin: dual_node_ptr.downgrade(),
+                index: node_index,
+                dual_variable: 0,
+                boundary: Vec::with_capacity(self.boundary_capacity),
+                overgrown_stack: Vec::new(),
+                last_visit_cycle: 0,
+                max_update_length_cache: None,
+            })
+        }
+    }
+
+    /// let a new defect node own its vertex and put all the edges around the vertex on its boundary
+    fn attach_defect_vertex(
+        &self,
+        defect_index: VertexIndex,
+        node_internal_ptr: &DualNodeInternalPtr,
+        boundary: &mut Vec<(bool, EdgeWeak)>,
+    ) {
+        let active_timestamp = self.active_timestamp;
+        let vertex_index = self
+            .get_vertex_index(defect_index)
+            .expect("syndrome not belonging to this dual module");
+        let vertex_ptr = &self.vertices[vertex_index];
+        vertex_ptr.dynamic_clear(active_timestamp);
+        let mut vertex = vertex_ptr.write(active_timestamp);
+        vertex.propagated_dual_node = Some(node_internal_ptr.downgrade());
+        vertex.propagated_grandson_dual_node = Some(node_internal_ptr.downgrade());
+        vertex.is_defect = true;
+        for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
+            edge_ptr.dynamic_clear(active_timestamp);
+            let mut edge = edge_ptr.write(active_timestamp);
+            let is_left = vertex_ptr.downgrade() == edge.left;
+            debug_assert!(
+                if is_left {
+                    edge.left_dual_node.is_none()
+                } else {
+                    edge.right_dual_node.is_none()
+                },
+                "dual node of edge should be none"
+            );
+            if is_left {
+                edge.left_dual_node = Some(node_internal_ptr.downgrade());
+                edge.left_grandson_dual_node = Some(node_internal_ptr.downgrade());
+            } else {
+                edge.right_dual_node = Some(node_internal_ptr.downgrade());
+                edge.right_grandson_dual_node = Some(node_internal_ptr.downgrade());
+            }
+            boundary.push((is_left, edge_ptr.downgrade()));
+        }
+    }
+
     /// register a new dual node ptr, but not creating the internal dual node
     fn register_dual_node_ptr(&mut self, dual_node_ptr: &DualNodePtr) {
         // println!("unit {:?}, register_dual_node_ptr: {:?}", self.unit_module_info, dual_node_ptr);
@@ -1726,6 +2120,15 @@ impl DualModuleSerial {
         })
     }
 
+    /// the number of over-grown vertices of a dual node, see [`DualNodeInternal::overgrown_stack`]; it's non-zero only when
+    /// the node has grown over some vertices whose incident edges are all fully grown by the node itself, e.g. in a dense
+    /// cluster; returns 0 if the dual node is not held in this partitioned unit
+    pub fn overgrown_stack_depth(&self, dual_node_ptr: &DualNodePtr) -> usize {
+        self.get_dual_node_internal_ptr_optional(dual_node_ptr)
+            .map(|dual_node_internal_ptr| dual_node_internal_ptr.read_recursive().overgrown_stack.len())
+            .unwrap_or(0)
+    }
+
     /// possibly add dual node only when sync_event is provided
     #[allow(clippy::unnecessary_cast)]
     pub fn get_otherwise_add_dual_node(
@@ -1733,6 +2136,7 @@ impl DualModuleSerial {
         dual_node_ptr: &DualNodePtr,
         dual_variable: Weight,
     ) -> DualNodeInternalPtr {
+        self.max_update_length_epoch += 1;
         let dual_node_index = self.get_dual_node_index(dual_node_ptr).unwrap_or_else(|| {
             // add a new internal dual node corresponding to the dual_node_ptr
             self.register_dual_node_ptr(dual_node_ptr);
@@ -1747,32 +2151,399 @@ impl DualModuleSerial {
                     node.boundary.clear();
                     node.overgrown_stack.clear();
                     node.last_visit_cycle = 0;
+                    node.max_update_length_cache = None;
                     drop(node);
                     node_ptr
                 } else {
-                    DualNodeInternalPtr::new_value(DualNodeInternal {
-                        origin: dual_node_ptr.downgrade(),
-                        index: node_index,
-                        dual_variable,
-                        boundary: Vec::new(),
-                        overgrown_stack: Vec::new(),
-                        last_visit_cycle: 0,
-                    })
-                };
-            self.active_list.push(node_internal_ptr.downgrade());
-            self.nodes_length += 1;
-            if self.nodes.len() < self.nodes_length {
-                self.nodes.push(None);
+                    DualNodeInternalPtr::new_value(DualNodeInternal {
+                        origin: dual_node_ptr.downgrade(),
+                        index: node_index,
+                        dual_variable,
+                        boundary: Vec::new(),
+                        overgrown_stack: Vec::new(),
+                        last_visit_cycle: 0,
+                        max_update_length_cache: None,
+                    })
+                };
+            self.active_list.push(node_internal_ptr.downgrade());
+            self.nodes_length += 1;
+            if self.nodes.len() < self.nodes_length {
+                self.nodes.push(None);
+            }
+            self.nodes[node_index as usize] = Some(node_internal_ptr);
+            node_index as usize
+        });
+        let dual_node_internal_ptr = self.nodes[dual_node_index].as_ref().expect("internal dual node must exists");
+        debug_assert!(
+            dual_node_ptr == &dual_node_internal_ptr.read_recursive().origin.upgrade_force(),
+            "dual node and dual internal node must corresponds to each other"
+        );
+        dual_node_internal_ptr.clone()
+    }
+
+    /// grow a single dual node by `length` (negative to shrink) without invalidating the cached maximum update lengths,
+    /// which is only valid when all the active nodes grow together, see [`DualModuleImpl::grow`]
+    fn apply_dual_node_growth(&mut self, dual_node_ptr: &DualNodePtr, length: Weight) {
+        let active_timestamp = self.active_timestamp;
+        if length == 0 {
+            log_warn!(; "[warning] calling `grow_dual_node` with zero length, nothing to do");
+            return;
+        }
+        self.prepare_dual_node_growth(dual_node_ptr, length > 0);
+        let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
+        {
+            // update node dual variable and do sanity check
+            let mut dual_node_internal = dual_node_internal_ptr.write();
+            dual_node_internal.dual_variable += length;
+            debug_assert!(
+                dual_node_internal.dual_variable >= 0,
+                "shrinking to negative dual variable is forbidden"
+            );
+            // update over-grown vertices
+            if !dual_node_internal.overgrown_stack.is_empty() {
+                let last_index = dual_node_internal.overgrown_stack.len() - 1;
+                let (_, overgrown) = &mut dual_node_internal.overgrown_stack[last_index];
+                if length < 0 {
+                    debug_assert!(*overgrown >= -length, "overgrown vertex cannot shrink so much");
+                }
+                *overgrown += length;
+            }
+        }
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
+            let edge_ptr = edge_weak.upgrade_force();
+            let is_left = *is_left;
+            let (growth, weight) = {
+                // minimize writer lock acquisition
+                let mut edge = edge_ptr.write(active_timestamp);
+                if is_left {
+                    edge.left_growth += length;
+                    debug_assert!(edge.left_growth >= 0, "negative growth forbidden");
+                } else {
+                    edge.right_growth += length;
+                    debug_assert!(edge.right_growth >= 0, "negative growth forbidden");
+                }
+                (edge.left_growth + edge.right_growth, edge.weight)
+            };
+            let edge = edge_ptr.read_recursive(active_timestamp);
+            if growth > weight {
+                // first check for if both side belongs to the same dual node, if so, it's ok
+                let dual_node_internal_ptr_2: &Option<DualNodeInternalWeak> = if is_left {
+                    &edge.right_dual_node
+                } else {
+                    &edge.left_dual_node
+                };
+                if dual_node_internal_ptr_2.is_none()
+                    || dual_node_internal_ptr_2.as_ref().unwrap() != &dual_node_internal_ptr.downgrade()
+                {
+                    let left_ptr = edge.left.upgrade_force();
+                    let right_ptr = edge.right.upgrade_force();
+                    panic!(
+                        "over-grown edge ({},{}): {}/{}",
+                        left_ptr.read_recursive(active_timestamp).vertex_index,
+                        right_ptr.read_recursive(active_timestamp).vertex_index,
+                        growth,
+                        weight
+                    );
+                }
+            } else if growth < 0 {
+                let left_ptr = edge.left.upgrade_force();
+                let right_ptr = edge.right.upgrade_force();
+                panic!(
+                    "under-grown edge ({},{}): {}/{}",
+                    left_ptr.read_recursive(active_timestamp).vertex_index,
+                    right_ptr.read_recursive(active_timestamp).vertex_index,
+                    growth,
+                    weight
+                );
+            }
+        }
+    }
+
+    /// invalidate the cached maximum update length of the dual nodes on both sides of the edges
+    fn invalidate_max_update_length_around(&self, boundary: &[(bool, EdgeWeak)]) {
+        let active_timestamp = self.active_timestamp;
+        for (_, edge_weak) in boundary.iter() {
+            let edge_ptr = edge_weak.upgrade_force();
+            let edge = edge_ptr.read_recursive(active_timestamp);
+            for dual_node_internal_weak in [&edge.left_dual_node, &edge.right_dual_node].into_iter().flatten() {
+                if is_dual_node_internal_alive(dual_node_internal_weak) {
+                    dual_node_internal_weak.upgrade_force().write().max_update_length_cache = None;
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::collapsible_else_if)]
+    /// [`DualModuleImpl::compute_maximum_update_length_dual_node`] that repairs a stale boundary at most once if
+    /// `repair_stale_boundary` is set: preparing the growth removes every fully grown edge from the boundary, so a stale
+    /// edge found again right after the repair is a bug and panics instead of preparing the node over and over
+    fn compute_maximum_update_length_dual_node_repair(
+        &mut self,
+        dual_node_ptr: &DualNodePtr,
+        is_grow: bool,
+        simultaneous_update: bool,
+        repair_stale_boundary: bool,
+    ) -> MaxUpdateLength {
+        let active_timestamp = self.active_timestamp;
+        if !simultaneous_update {
+            // when `simultaneous_update` is set, it's assumed that all nodes are prepared to grow or shrink
+            // this is because if we dynamically prepare them, it would be inefficient
+            self.prepare_dual_node_growth(dual_node_ptr, is_grow);
+        }
+        let mut max_length_abs = Weight::MAX;
+        let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        if !is_grow {
+            if dual_node_internal.dual_variable == 0 {
+                let dual_node = dual_node_ptr.read_recursive();
+                match dual_node.class {
+                    DualNodeClass::Blossom { .. } => return MaxUpdateLength::BlossomNeedExpand(dual_node_ptr.clone()),
+                    DualNodeClass::DefectVertex { defect_index } => {
+                        // try to report Conflicting event or give a VertexShrinkStop with potential conflicting node
+                        if let Some(vertex_index) = self.get_vertex_index(defect_index) {
+                            // since propagated node is never removed, this event could happen with no vertex
+                            let vertex_ptr = &self.vertices[vertex_index];
+                            let vertex = vertex_ptr.read_recursive(active_timestamp);
+                            let mut potential_conflict: Option<(DualNodePtr, DualNodePtr)> = None;
+                            for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
+                                let edge = edge_ptr.read_recursive(active_timestamp);
+                                let is_left = vertex_ptr.downgrade() == edge.left;
+                                let remaining_length = edge.weight - edge.left_growth - edge.right_growth;
+                                if remaining_length == 0 {
+                                    let peer_dual_node = if is_left {
+                                        &edge.right_dual_node
+                                    } else {
+                                        &edge.left_dual_node
+                                    };
+                                    if let Some(peer_dual_node_ptr) = peer_dual_node {
+                                        let peer_grandson_dual_node = if is_left {
+                                            &edge.right_grandson_dual_node
+                                        } else {
+                                            &edge.left_grandson_dual_node
+                                        };
+                                        let peer_dual_node_ptr =
+                                            peer_dual_node_ptr.upgrade_force().read_recursive().origin.upgrade_force();
+                                        let peer_grandson_dual_node_ptr = peer_grandson_dual_node
+                                            .as_ref()
+                                            .unwrap()
+                                            .upgrade_force()
+                                            .read_recursive()
+                                            .origin
+                                            .upgrade_force();
+                                        if peer_dual_node_ptr.read_recursive().grow_state == DualNodeGrowState::Grow {
+                                            if let Some((other_dual_node_ptr, other_grandson_dual_node)) =
+                                                &potential_conflict
+                                            {
+                                                if &peer_dual_node_ptr != other_dual_node_ptr {
+                                                    return MaxUpdateLength::Conflicting(
+                                                        (other_dual_node_ptr.clone(), other_grandson_dual_node.clone()),
+                                                        (peer_dual_node_ptr, peer_grandson_dual_node_ptr),
+                                                    );
+                                                }
+                                            } else {
+                                                potential_conflict = Some((peer_dual_node_ptr, peer_grandson_dual_node_ptr));
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            return MaxUpdateLength::VertexShrinkStop((dual_node_ptr.clone(), potential_conflict));
+                        } else {
+                            return MaxUpdateLength::VertexShrinkStop((dual_node_ptr.clone(), None));
+                        }
+                    }
+                }
+            }
+            if !dual_node_internal.overgrown_stack.is_empty() {
+                let last_index = dual_node_internal.overgrown_stack.len() - 1;
+                let (_, overgrown) = &dual_node_internal.overgrown_stack[last_index];
+                max_length_abs = core::cmp::min(max_length_abs, *overgrown);
+            }
+            max_length_abs = core::cmp::min(max_length_abs, dual_node_internal.dual_variable);
+        }
+        let mut has_stale_boundary = false;
+        for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
+            let edge_ptr = edge_weak.upgrade_force();
+            let is_left = *is_left;
+            let edge = edge_ptr.read_recursive(active_timestamp);
+            if is_grow {
+                // first check if both side belongs to the same tree node, if so, no constraint on this edge
+                let peer_dual_node_internal_ptr: Option<DualNodeInternalPtr> = if is_left {
+                    edge.right_dual_node.as_ref().map(|ptr| ptr.upgrade_force())
+                } else {
+                    edge.left_dual_node.as_ref().map(|ptr| ptr.upgrade_force())
+                };
+                match peer_dual_node_internal_ptr {
+                    Some(peer_dual_node_internal_ptr) => {
+                        if peer_dual_node_internal_ptr == dual_node_internal_ptr {
+                            continue;
+                        } else {
+                            let peer_dual_node_internal = peer_dual_node_internal_ptr.read_recursive();
+                            let peer_dual_node_ptr = peer_dual_node_internal.origin.upgrade_force();
+                            let peer_dual_node = peer_dual_node_ptr.read_recursive();
+                            let remaining_length = edge.weight - edge.left_growth - edge.right_growth;
+                            let local_max_length_abs = match peer_dual_node.grow_state {
+                                DualNodeGrowState::Grow => {
+                                    debug_assert!(remaining_length % 2 == 0, "there is odd gap between two growing nodes, please make sure all weights are even numbers");
+                                    remaining_length / 2
+                                }
+                                DualNodeGrowState::Shrink => {
+                                    // Yue 2022.9.5: remove Conflicting event detection here, move it to the 0-dual syndrome node
+                                    continue;
+                                }
+                                DualNodeGrowState::Stay => remaining_length,
+                            };
+                            if local_max_length_abs == 0 {
+                                let peer_grandson_ptr = if is_left {
+                                    edge.right_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                } else {
+                                    edge.left_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                };
+                                let grandson_ptr = if is_left {
+                                    edge.left_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                } else {
+                                    edge.right_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                };
+                                return MaxUpdateLength::Conflicting(
+                                    (peer_dual_node_ptr.clone(), peer_grandson_ptr),
+                                    (dual_node_ptr.clone(), grandson_ptr),
+                                );
+                            }
+                            max_length_abs = core::cmp::min(max_length_abs, local_max_length_abs);
+                        }
+                    }
+                    None => {
+                        let local_max_length_abs = edge.weight - edge.left_growth - edge.right_growth;
+                        if local_max_length_abs == 0 {
+                            // check if peer is virtual node
+                            let peer_vertex_ptr = if is_left {
+                                edge.right.upgrade_force()
+                            } else {
+                                edge.left.upgrade_force()
+                            };
+                            let peer_vertex = peer_vertex_ptr.read_recursive(active_timestamp);
+                            if peer_vertex.is_virtual || peer_vertex.is_mirror_blocked() {
+                                let grandson_ptr = if is_left {
+                                    edge.left_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                } else {
+                                    edge.right_grandson_dual_node
+                                        .as_ref()
+                                        .map(|ptr| ptr.upgrade_force())
+                                        .unwrap()
+                                        .read_recursive()
+                                        .origin
+                                        .upgrade_force()
+                                };
+                                // a mirrored vertex that is virtual in the whole graph stays virtual after fusion,
+                                // so the primal module doesn't need to break this match
+                                let virtual_vertex_index = peer_vertex.vertex_index;
+                                let is_mirror = !peer_vertex.is_virtual && peer_vertex.is_mirror_blocked();
+                                if !is_mirror {
+                                    self.notify_virtual_touch(dual_node_ptr, virtual_vertex_index);
+                                }
+                                return MaxUpdateLength::TouchingVirtual(
+                                    (dual_node_ptr.clone(), grandson_ptr),
+                                    (virtual_vertex_index, is_mirror),
+                                );
+                            } else {
+                                // this edge should've been removed from boundary because it's already fully grown and
+                                // its peer vertex is not virtual, e.g. when a sync event grows it after the preparation
+                                has_stale_boundary = true;
+                                continue;
+                            }
+                        }
+                        max_length_abs = core::cmp::min(max_length_abs, local_max_length_abs);
+                    }
+                }
+            } else {
+                if is_left {
+                    if edge.left_growth == 0 {
+                        unreachable!()
+                    }
+                    max_length_abs = core::cmp::min(max_length_abs, edge.left_growth);
+                } else {
+                    if edge.right_growth == 0 {
+                        unreachable!()
+                    }
+                    max_length_abs = core::cmp::min(max_length_abs, edge.right_growth);
+                }
             }
-            self.nodes[node_index as usize] = Some(node_internal_ptr);
-            node_index as usize
-        });
-        let dual_node_internal_ptr = self.nodes[dual_node_index].as_ref().expect("internal dual node must exists");
-        debug_assert!(
-            dual_node_ptr == &dual_node_internal_ptr.read_recursive().origin.upgrade_force(),
-            "dual node and dual internal node must corresponds to each other"
-        );
-        dual_node_internal_ptr.clone()
+        }
+        if has_stale_boundary {
+            assert!(
+                repair_stale_boundary,
+                "fully grown edge left in the boundary of {dual_node_ptr:?} after re-preparing its growth"
+            );
+            drop(dual_node_internal);
+            self.stale_boundary_repairs += 1;
+            log_warn!(repairs = self.stale_boundary_repairs; "[warning] repairing a stale boundary of {:?}, which should've been removed when preparing the growth", dual_node_ptr);
+            // preparing the growth again propagates the node to the peer vertices of the fully grown edges
+            self.prepare_dual_node_growth(dual_node_ptr, true);
+            return self.compute_maximum_update_length_dual_node_repair(dual_node_ptr, is_grow, true, false);
+        }
+        MaxUpdateLength::NonZeroGrow((max_length_abs, dual_node_internal.boundary.is_empty()))
+    }
+
+    /// [`DualModuleImpl::compute_maximum_update_length_dual_node`] of a prepared node that reuses the result of a previous
+    /// round if the neighborhood of the node hasn't changed since, see [`DualModuleSerial::enable_max_update_length_cache`]
+    fn cached_maximum_update_length_dual_node(&mut self, dual_node_ptr: &DualNodePtr, is_grow: bool) -> MaxUpdateLength {
+        let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
+        let cache = dual_node_internal_ptr.read_recursive().max_update_length_cache;
+        if let Some(cache) = cache {
+            if cache.epoch == self.max_update_length_epoch && cache.is_grow == is_grow {
+                let length = cache.length - (self.uniform_growth - cache.uniform_growth);
+                // a node limiting the growth may report another event when reaching 0, so it's always recomputed
+                if length > 0 {
+                    self.max_update_length_cache_hits += 1;
+                    return MaxUpdateLength::NonZeroGrow((length, cache.is_boundary_empty));
+                }
+            }
+        }
+        let max_update_length = self.compute_maximum_update_length_dual_node(dual_node_ptr, is_grow, true);
+        dual_node_internal_ptr.write().max_update_length_cache = match max_update_length {
+            MaxUpdateLength::NonZeroGrow((length, is_boundary_empty)) if length != Weight::MAX => {
+                Some(MaxUpdateLengthCache {
+                    is_grow,
+                    length,
+                    is_boundary_empty,
+                    uniform_growth: self.uniform_growth,
+                    epoch: self.max_update_length_epoch,
+                })
+            }
+            _ => None,
+        };
+        max_update_length
     }
 
     /// this is equivalent to [`DualModuleSerial::prepare_dual_node_growth`] when there are no 0 weight edges, but when it encounters zero-weight edges, it will report `true`
@@ -1782,6 +2553,7 @@ impl DualModuleSerial {
         self.propagating_vertices.clear();
         let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
         let mut newly_propagated_edge_has_zero_weight = false;
+        let mut overgrown_popped = false;
         if is_grow {
             // gracefully update the boundary to ease growing
             let dual_node_internal = dual_node_internal_ptr.read_recursive();
@@ -1876,8 +2648,7 @@ impl DualModuleSerial {
                         });
                     }
                     let mut count_newly_propagated_edge = 0;
-                    for edge_weak in vertex.edges.iter() {
-                        let edge_ptr = edge_weak.upgrade_force();
+                    for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
                         let (is_left, newly_propagated_edge) = {
                             edge_ptr.dynamic_clear(active_timestamp);
                             let edge = edge_ptr.read_recursive(active_timestamp);
@@ -1891,7 +2662,7 @@ impl DualModuleSerial {
                         };
                         if newly_propagated_edge {
                             count_newly_propagated_edge += 1;
-                            self.updated_boundary.push((is_left, edge_weak.clone()));
+                            self.updated_boundary.push((is_left, edge_ptr.downgrade()));
                             let mut edge = edge_ptr.write(active_timestamp);
                             if edge.weight == 0 {
                                 newly_propagated_edge_has_zero_weight = true;
@@ -1920,6 +2691,7 @@ impl DualModuleSerial {
                     let last_index = dual_node_internal.overgrown_stack.len() - 1;
                     let (_, overgrown) = &dual_node_internal.overgrown_stack[last_index];
                     if *overgrown == 0 {
+                        overgrown_popped = true;
                         let (vertex_weak, _) = dual_node_internal.overgrown_stack.pop().unwrap();
                         let vertex_ptr = vertex_weak.upgrade_force();
                         // push the surrounding edges back to the boundary
@@ -1936,8 +2708,7 @@ impl DualModuleSerial {
                                     propagated_grandson_dual_node: None,
                                 });
                             }
-                            for edge_weak in vertex.edges.iter() {
-                                let edge_ptr = edge_weak.upgrade_force();
+                            for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
                                 let mut edge = edge_ptr.write(active_timestamp);
                                 let is_left = vertex_ptr.downgrade() == edge.left;
                                 if self.unit_module_info.is_none() {
@@ -1970,7 +2741,7 @@ impl DualModuleSerial {
                                     } else {
                                         edge.dedup_timestamp.1 = self.edge_dedup_timestamp;
                                     }
-                                    self.updated_boundary.push((!is_left, edge_weak.clone()));
+                                    self.updated_boundary.push((!is_left, edge_ptr.downgrade()));
                                     // boundary has the opposite end
                                 }
                             }
@@ -2057,8 +2828,7 @@ impl DualModuleSerial {
                             propagated_grandson_dual_node: None,
                         });
                     }
-                    for edge_weak in vertex.edges.iter() {
-                        let edge_ptr = edge_weak.upgrade_force();
+                    for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
                         let (is_left, newly_propagated_edge) = {
                             let edge = edge_ptr.read_recursive(active_timestamp);
                             let is_left = vertex_ptr.downgrade() == edge.left;
@@ -2098,7 +2868,7 @@ impl DualModuleSerial {
                                 } else {
                                     edge.dedup_timestamp.1 = self.edge_dedup_timestamp;
                                 }
-                                self.updated_boundary.push((!is_left, edge_weak.clone()));
+                                self.updated_boundary.push((!is_left, edge_ptr.downgrade()));
                             } // otherwise it's duplicate and should not be added to the boundary list
                             if edge.weight == 0 {
                                 newly_propagated_edge_has_zero_weight = true;
@@ -2133,10 +2903,28 @@ impl DualModuleSerial {
                     }
                 }
             }
+            // when both ends of a 0-weight edge are vacated in the same round (e.g. one of them is a mirrored vertex),
+            // the first one pushes the edge to the boundary and the second one then removes it from this dual node
+            let dual_node_internal_weak = dual_node_internal_ptr.downgrade();
+            self.updated_boundary.retain(|(is_left, edge_weak)| {
+                let edge_ptr = edge_weak.upgrade_force();
+                let edge = edge_ptr.read_recursive(active_timestamp);
+                let this_dual_node = if *is_left {
+                    &edge.left_dual_node
+                } else {
+                    &edge.right_dual_node
+                };
+                this_dual_node.as_ref() == Some(&dual_node_internal_weak)
+            });
         }
         // update the boundary
         lock_write!(dual_node_internal, dual_node_internal_ptr);
-        std::mem::swap(&mut self.updated_boundary, &mut dual_node_internal.boundary);
+        core::mem::swap(&mut self.updated_boundary, &mut dual_node_internal.boundary);
+        self.observed_capacities.max_boundary_per_node = core::cmp::max(
+            self.observed_capacities.max_boundary_per_node,
+            dual_node_internal.boundary.len(),
+        );
+
         // println!("{} boundary: {:?}", tree_node.boundary.len(), tree_node.boundary);
         if self.unit_module_info.is_none() {
             debug_assert!(
@@ -2144,95 +2932,638 @@ impl DualModuleSerial {
                 "the boundary of a dual cluster is never empty"
             );
         }
-        newly_propagated_edge_has_zero_weight
+        if self.enable_max_update_length_cache && (overgrown_popped || !self.propagating_vertices.is_empty()) {
+            // vertices are taken or vacated: invalidate the node itself and the peers across the old and new boundary
+            let boundary = core::mem::take(&mut dual_node_internal.boundary);
+            drop(dual_node_internal);
+            self.invalidate_max_update_length_around(&boundary);
+            self.invalidate_max_update_length_around(&self.updated_boundary);
+            lock_write!(dual_node_internal, dual_node_internal_ptr);
+            dual_node_internal.boundary = boundary;
+            dual_node_internal.max_update_length_cache = None;
+        }
+        newly_propagated_edge_has_zero_weight
+    }
+
+    /// adjust the boundary of each dual node to fit into the need of growing (`length` > 0) or shrinking (`length` < 0)
+    pub fn prepare_dual_node_growth(&mut self, dual_node_ptr: &DualNodePtr, is_grow: bool) {
+        let mut need_another = self.prepare_dual_node_growth_single(dual_node_ptr, is_grow);
+        while need_another {
+            // when there are 0 weight edges, one may need to run multiple iterations to get it prepared in a proper state
+            need_another = self.prepare_dual_node_growth_single(dual_node_ptr, is_grow);
+        }
+    }
+
+    /// the maximum length that a single dual node can grow (`is_grow`) or shrink alone while all the other nodes stay,
+    /// as a non-negative number; this is the bound of [`DualModuleImpl::compute_maximum_update_length_dual_node`]
+    /// with every peer staying, evaluated on the boundary that [`DualModuleSerial::prepare_dual_node_growth`] would
+    /// produce but without preparing it, so that nothing is modified
+    pub fn maximum_dual_node_growth(&self, dual_node_ptr: &DualNodePtr, is_grow: bool) -> Weight {
+        let active_timestamp = self.active_timestamp;
+        let dual_node_internal_ptr = self.get_dual_node_internal_ptr(dual_node_ptr);
+        let dual_node_internal_weak = dual_node_internal_ptr.downgrade();
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        // objects untouched in this decoding are logically cleared, see [`FastClear`]
+        let is_fresh_edge = |edge: &Edge| edge.timestamp == active_timestamp;
+        let is_fresh_vertex = |vertex: &Vertex| vertex.timestamp == active_timestamp;
+        let side_dual_node = |edge: &Edge, is_left: bool| -> Option<DualNodeInternalWeak> {
+            if !is_fresh_edge(edge) {
+                None
+            } else if is_left {
+                edge.left_dual_node.clone()
+            } else {
+                edge.right_dual_node.clone()
+            }
+        };
+        let mut max_length_abs = Weight::MAX;
+        // the vertices that the preparation would propagate to (grow) or vacate (shrink)
+        let mut touched_vertices: HashSet<VertexIndex> = HashSet::new();
+        let mut boundary: Vec<(bool, EdgeWeak)> = dual_node_internal.boundary.clone();
+        if is_grow {
+            while let Some((is_left, edge_weak)) = boundary.pop() {
+                let edge_ptr = edge_weak.upgrade_force();
+                let edge = edge_ptr.read_recursive_force();
+                let peer_dual_node = side_dual_node(&edge, !is_left);
+                let peer_vertex_ptr = if is_left {
+                    edge.right.upgrade_force()
+                } else {
+                    edge.left.upgrade_force()
+                };
+                let peer_vertex = peer_vertex_ptr.read_recursive_force();
+                if peer_dual_node.as_ref() == Some(&dual_node_internal_weak)
+                    || touched_vertices.contains(&peer_vertex.vertex_index)
+                {
+                    continue; // both sides belong to this node
+                }
+                let remaining = if is_fresh_edge(&edge) {
+                    edge.weight - edge.left_growth - edge.right_growth
+                } else {
+                    edge.weight
+                };
+                // unlike in `compute_maximum_update_length_dual_node`, the peer stays no matter its grow state
+                if remaining > 0 || peer_dual_node.is_some() {
+                    max_length_abs = core::cmp::min(max_length_abs, remaining);
+                    continue;
+                }
+                if peer_vertex.is_virtual
+                    || peer_vertex.is_mirror_blocked()
+                    || (is_fresh_vertex(&peer_vertex) && peer_vertex.propagated_dual_node.is_some())
+                {
+                    return 0; // touching a virtual vertex or another node
+                }
+                // the preparation propagates to the peer vertex and the node then grows from its unclaimed edges
+                touched_vertices.insert(peer_vertex.vertex_index);
+                for peer_edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &peer_vertex) {
+                    let peer_edge = peer_edge_ptr.read_recursive_force();
+                    let peer_is_left = peer_edge.left == peer_vertex_ptr.downgrade();
+                    if side_dual_node(&peer_edge, peer_is_left).is_none() {
+                        boundary.push((peer_is_left, peer_edge_ptr.downgrade()));
+                    }
+                }
+            }
+        } else {
+            max_length_abs = dual_node_internal.dual_variable;
+            for (vertex_weak, overgrown) in dual_node_internal.overgrown_stack.iter().rev() {
+                if *overgrown > 0 {
+                    max_length_abs = core::cmp::min(max_length_abs, *overgrown);
+                    break;
+                }
+                // the preparation pops this vertex and the node then shrinks from the edges around it
+                let vertex_ptr = vertex_weak.upgrade_force();
+                let vertex = vertex_ptr.read_recursive_force();
+                touched_vertices.insert(vertex.vertex_index);
+                for edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &vertex) {
+                    let is_left = edge_ptr.read_recursive_force().left == vertex_ptr.downgrade();
+                    boundary.push((!is_left, edge_ptr.downgrade()));
+                }
+            }
+            while let Some((is_left, edge_weak)) = boundary.pop() {
+                let edge_ptr = edge_weak.upgrade_force();
+                let edge = edge_ptr.read_recursive_force();
+                let this_vertex_ptr = if is_left {
+                    edge.left.upgrade_force()
+                } else {
+                    edge.right.upgrade_force()
+                };
+                let this_vertex = this_vertex_ptr.read_recursive_force();
+                if side_dual_node(&edge, is_left).as_ref() != Some(&dual_node_internal_weak)
+                    || touched_vertices.contains(&this_vertex.vertex_index)
+                {
+                    continue; // this side has left the node
+                }
+                let this_growth = if is_left { edge.left_growth } else { edge.right_growth };
+                if this_growth > 0 {
+                    max_length_abs = core::cmp::min(max_length_abs, this_growth);
+                    continue;
+                }
+                if this_vertex.is_defect {
+                    return 0; // never shrink from the defect itself
+                }
+                // the preparation vacates this vertex and the node then shrinks from its fully grown edges
+                touched_vertices.insert(this_vertex.vertex_index);
+                for this_edge_ptr in VertexEdgeIter::new(&self.compact_adjacency, &self.edges, &this_vertex) {
+                    let this_edge = this_edge_ptr.read_recursive_force();
+                    let this_is_left = this_edge.left == this_vertex_ptr.downgrade();
+                    if side_dual_node(&this_edge, !this_is_left).as_ref() == Some(&dual_node_internal_weak)
+                        && this_edge.left_growth + this_edge.right_growth >= this_edge.weight
+                    {
+                        boundary.push((!this_is_left, this_edge_ptr.downgrade()));
+                    }
+                }
+            }
+        }
+        max_length_abs
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature="dangerous_pointer")] {
+        fn is_vertex_alive(_vertex_weak: &VertexWeak) -> bool { true }
+        fn is_dual_node_internal_alive(_dual_node_internal_weak: &DualNodeInternalWeak) -> bool { true }
+        fn is_edge_alive(_edge_weak: &EdgeWeak) -> bool { true }
+        /// the edge pointer given by [`VertexEdgeIter`], the same as upgrading an [`EdgeWeak`]
+        pub type VertexEdgePtr = EdgeWeak;
+        fn share_edge_ptr(edge_ptr: &EdgePtr) -> VertexEdgePtr { edge_ptr.downgrade() }
+    } else {
+        fn is_vertex_alive(vertex_weak: &VertexWeak) -> bool { vertex_weak.upgrade().is_some() }
+        fn is_dual_node_internal_alive(dual_node_internal_weak: &DualNodeInternalWeak) -> bool { dual_node_internal_weak.upgrade().is_some() }
+        fn is_edge_alive(edge_weak: &EdgeWeak) -> bool { edge_weak.upgrade().is_some() }
+        /// the edge pointer given by [`VertexEdgeIter`], the same as upgrading an [`EdgeWeak`]
+        pub type VertexEdgePtr = EdgePtr;
+        fn share_edge_ptr(edge_ptr: &EdgePtr) -> VertexEdgePtr { edge_ptr.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::super::primal_module::PrimalModuleImpl;
+    use super::super::primal_module_serial::tests::*;
+    use super::super::primal_module_serial::PrimalModuleSerialPtr;
+    use super::*;
+
+    #[allow(dead_code)]
+    fn debug_print_dual_node(dual_module: &DualModuleSerial, dual_node_ptr: &DualNodePtr) {
+        println!("boundary:");
+        let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(dual_node_ptr);
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
+            let edge_ptr = edge_weak.upgrade_force();
+            let edge = edge_ptr.read_recursive_force();
+            println!("    {} {:?}", if *is_left { " left" } else { "right" }, edge);
+        }
+    }
+
+    #[test]
+    fn dual_module_serial_basics() {
+        // cargo test dual_module_serial_basics -- --nocapture
+        let visualize_filename = "dual_module_serial_basics.json".to_string();
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let mut visualizer = Visualizer::new(
+            Some(visualize_data_folder() + visualize_filename.as_str()),
+            code.get_positions(),
+            true,
+        )
+        .unwrap();
+        print_visualize_link(visualize_filename.clone());
+        // create dual module
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        // try to work on a simple syndrome
+        code.vertices[19].is_defect = true;
+        code.vertices[25].is_defect = true;
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        visualizer
+            .snapshot_combined("syndrome".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        // create dual nodes and grow them by half length
+        let dual_node_19_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        let dual_node_25_ptr = interface_ptr.read_recursive().nodes[1].clone().unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
+        visualizer
+            .snapshot_combined("grow to 0.5".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
+        visualizer
+            .snapshot_combined("grow to 1".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
+        visualizer
+            .snapshot_combined("grow to 1.5".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
+        visualizer
+            .snapshot_combined("shrink to 1".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
+        visualizer
+            .snapshot_combined("shrink to 0.5".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
+        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
+        visualizer
+            .snapshot_combined("shrink to 0".to_string(), vec![&interface_ptr, &dual_module])
+            .unwrap();
+    }
+
+    /// two neighboring defects whose shared edge is already tight
+    fn dual_module_serial_tight_pair() -> (DualModuleSerial, DualModuleInterfacePtr, DualNodePtr, DualNodePtr) {
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        code.vertices[19].is_defect = true;
+        code.vertices[20].is_defect = true;
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        let dual_node_19_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        let dual_node_20_ptr = interface_ptr.read_recursive().nodes[1].clone().unwrap();
+        dual_module.grow_subset(&[
+            (dual_node_19_ptr.clone(), half_weight),
+            (dual_node_20_ptr.clone(), half_weight),
+        ]);
+        (dual_module, interface_ptr, dual_node_19_ptr, dual_node_20_ptr)
+    }
+
+    #[test]
+    fn dual_module_serial_grow_subset_1() {
+        // cargo test dual_module_serial_grow_subset_1 -- --nocapture
+        let (mut dual_module, _interface_ptr, dual_node_19_ptr, dual_node_20_ptr) = dual_module_serial_tight_pair();
+        // 19 takes over the tight edge from 20, which only works if 20 shrinks first
+        dual_module.grow_subset(&[(dual_node_19_ptr.clone(), 300), (dual_node_20_ptr.clone(), -300)]);
+        assert_eq!(
+            dual_module
+                .get_dual_node_internal_ptr(&dual_node_19_ptr)
+                .read_recursive()
+                .dual_variable,
+            800
+        );
+        assert_eq!(
+            dual_module
+                .get_dual_node_internal_ptr(&dual_node_20_ptr)
+                .read_recursive()
+                .dual_variable,
+            200
+        );
+        // conflict detection still sees the edge as tight afterwards, both nodes are still growing
+        let group_max_update_length = dual_module.compute_maximum_update_length();
+        assert!(
+            group_max_update_length.get_none_zero_growth().is_none(),
+            "{group_max_update_length:?}"
+        );
+        dual_module.grow_subset(&[(dual_node_19_ptr.clone(), -800), (dual_node_20_ptr.clone(), -200)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot grow dual node by 300, at most 0")]
+    fn dual_module_serial_grow_subset_2() {
+        // cargo test dual_module_serial_grow_subset_2 -- --nocapture
+        let (mut dual_module, _interface_ptr, dual_node_19_ptr, dual_node_20_ptr) = dual_module_serial_tight_pair();
+        // the same request with naive sequential growth over-grows the shared edge
+        dual_module.grow_dual_node(&dual_node_19_ptr, 300);
+        dual_module.grow_dual_node(&dual_node_20_ptr, -300);
+    }
+
+    /// a fully grown edge left in the boundary, e.g. by a sync event after the preparation, is repaired in place
+    #[test]
+    fn dual_module_serial_stale_boundary_1() {
+        // cargo test dual_module_serial_stale_boundary_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        code.vertices[19].is_defect = true;
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        let dual_node_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        dual_module.grow_dual_node(&dual_node_ptr, half_weight);
+        // fully grow one boundary edge behind the back of the dual module
+        let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(&dual_node_ptr);
+        let (is_left, edge_weak) = dual_node_internal_ptr.read_recursive().boundary[0].clone();
+        let edge_ptr = edge_weak.upgrade_force();
+        let peer_vertex_ptr = {
+            let mut edge = edge_ptr.write(dual_module.active_timestamp);
+            if is_left {
+                edge.right_growth = edge.weight - edge.left_growth;
+                edge.right.upgrade_force()
+            } else {
+                edge.left_growth = edge.weight - edge.right_growth;
+                edge.left.upgrade_force()
+            }
+        };
+        assert!(!peer_vertex_ptr.read_recursive(dual_module.active_timestamp).is_virtual);
+        let max_update_length = dual_module.compute_maximum_update_length_dual_node(&dual_node_ptr, true, true);
+        assert_eq!(max_update_length, MaxUpdateLength::NonZeroGrow((half_weight, false)));
+        assert_eq!(dual_module.stale_boundary_repairs, 1);
+        assert_eq!(dual_module.generate_profiler_report(), json!({ "stale_boundary_repairs": 1 }));
+        // the peer vertex is claimed by the node as if the growth had propagated
+        let peer_vertex = peer_vertex_ptr.read_recursive(dual_module.active_timestamp);
+        assert!(peer_vertex.propagated_dual_node.as_ref() == Some(&dual_node_internal_ptr.downgrade()));
+        drop(peer_vertex);
+        dual_module.clear();
+        assert_eq!(dual_module.stale_boundary_repairs, 0);
+    }
+
+    #[test]
+    fn dual_module_serial_sanity_check_pointers() {
+        // cargo test dual_module_serial_sanity_check_pointers -- --nocapture
+        let (dual_module, interface_ptr, dual_node_19_ptr, dual_node_20_ptr) = dual_module_serial_tight_pair();
+        dual_module.sanity_check_pointers().unwrap();
+        // the dual nodes are owned by the interface: dropping it leaves the internal nodes pointing to nothing
+        drop((interface_ptr, dual_node_19_ptr, dual_node_20_ptr));
+        let message = dual_module.sanity_check_pointers().unwrap_err();
+        assert!(message.contains("dual node 0: origin"), "{message}");
+        assert!(message.contains("dual node 1: origin"), "{message}");
+    }
+
+    /// the observer is notified once for each dual node that reaches a virtual vertex
+    #[test]
+    fn dual_module_serial_virtual_touch_observer_1() {
+        // cargo test dual_module_serial_virtual_touch_observer_1 -- --nocapture
+        let code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let touches = alloc::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let observed_touches = touches.clone();
+        dual_module.set_virtual_touch_observer(move |dual_node_ptr, virtual_vertex_index| {
+            let node_index = dual_node_ptr.read_recursive().index;
+            observed_touches.lock().unwrap().push((node_index, virtual_vertex_index));
+        });
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        for _ in 0..2 {
+            // vertex 8 is next to the virtual vertex 15 and vertex 13 is next to the virtual vertex 14
+            let syndrome_pattern = SyndromePattern::new_vertices(vec![8, 13]);
+            let interface_ptr = DualModuleInterfacePtr::new_empty();
+            primal_module.solve(&interface_ptr, &syndrome_pattern, &mut dual_module);
+            assert_eq!(interface_ptr.sum_dual_variables(), 2000);
+            let mut touches = core::mem::take(&mut *touches.lock().unwrap());
+            touches.sort();
+            assert_eq!(touches, vec![(0, 15), (1, 14)]);
+            primal_module.clear();
+            dual_module.clear();
+        }
+    }
+
+    /// loading a syndrome in a batch gives exactly the same state as adding the defect nodes one by one
+    #[test]
+    fn dual_module_serial_add_defect_nodes_batch_1() {
+        // cargo test dual_module_serial_add_defect_nodes_batch_1 -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, 500);
+        let initializer = code.get_initializer();
+        let mut batch_dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut single_dual_module = DualModuleSerial::new_empty(&initializer);
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            // reuse the internal nodes of the previous round, which may have more or less defects
+            batch_dual_module.clear();
+            single_dual_module.clear();
+            let batch_interface_ptr = DualModuleInterfacePtr::new_load(&syndrome_pattern, &mut batch_dual_module);
+            let single_interface_ptr = DualModuleInterfacePtr::new_empty();
+            for &vertex_index in syndrome_pattern.defect_vertices.iter() {
+                single_interface_ptr.create_defect_node(vertex_index, &mut single_dual_module);
+            }
+            assert_eq!(batch_dual_module.snapshot(false), single_dual_module.snapshot(false));
+            assert_eq!(batch_interface_ptr.snapshot(false), single_interface_ptr.snapshot(false));
+            assert_eq!(batch_dual_module.active_list.len(), single_dual_module.active_list.len());
+            assert_eq!(batch_dual_module.observed_capacities, single_dual_module.observed_capacities);
+            batch_interface_ptr.grow(100, &mut batch_dual_module);
+            single_interface_ptr.grow(100, &mut single_dual_module);
+            assert_eq!(batch_dual_module.snapshot(false), single_dual_module.snapshot(false));
+        }
+    }
+
+    /// an excessive single-node growth is rejected with the safe bound and leaves everything untouched
+    #[test]
+    fn dual_module_serial_grow_dual_node_checked_1() {
+        // cargo test dual_module_serial_grow_dual_node_checked_1 -- --nocapture
+        let (mut dual_module, interface_ptr, dual_node_19_ptr, dual_node_20_ptr) = dual_module_serial_tight_pair();
+        let dual_snapshot = dual_module.snapshot(false);
+        let interface_snapshot = interface_ptr.snapshot(false);
+        let sum_dual_variables = interface_ptr.sum_dual_variables();
+        // the shared edge is tight, so neither can grow alone
+        assert_eq!(
+            dual_module.grow_dual_node_checked(&dual_node_19_ptr, 1),
+            Err(GrowthError::ExceedsBound {
+                requested: 1,
+                allowed: 0
+            })
+        );
+        assert_eq!(
+            dual_module.grow_dual_node_checked(&dual_node_20_ptr, 1),
+            Err(GrowthError::ExceedsBound {
+                requested: 1,
+                allowed: 0
+            })
+        );
+        // a dual variable never goes below zero
+        assert_eq!(
+            dual_module.grow_dual_node_checked(&dual_node_19_ptr, -600),
+            Err(GrowthError::ExceedsBound {
+                requested: -600,
+                allowed: -500
+            })
+        );
+        assert_eq!(dual_module.snapshot(false), dual_snapshot);
+        assert_eq!(interface_ptr.snapshot(false), interface_snapshot);
+        // within the bounds, the interface follows
+        interface_ptr
+            .grow_dual_node_checked(&dual_node_19_ptr, -200, &mut dual_module)
+            .unwrap();
+        assert_eq!(dual_module.maximum_dual_node_growth(&dual_node_20_ptr, true), 200);
+        interface_ptr
+            .grow_dual_node_checked(&dual_node_20_ptr, 200, &mut dual_module)
+            .unwrap();
+        // the fixture grows the dual module directly, so only the changes are seen by the interface
+        assert_eq!(interface_ptr.sum_dual_variables(), sum_dual_variables);
+        for (dual_node_ptr, expected) in [(&dual_node_19_ptr, 300), (&dual_node_20_ptr, 700)] {
+            let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(dual_node_ptr);
+            assert_eq!(dual_node_internal_ptr.read_recursive().dual_variable, expected);
+        }
+        assert_eq!(
+            dual_node_20_ptr
+                .read_recursive()
+                .get_dual_variable(&interface_ptr.read_recursive()),
+            200
+        );
+        let dual_snapshot = dual_module.snapshot(false);
+        assert!(interface_ptr
+            .grow_dual_node_checked(&dual_node_20_ptr, 1, &mut dual_module)
+            .is_err());
+        assert_eq!(dual_module.snapshot(false), dual_snapshot);
     }
 
-    /// adjust the boundary of each dual node to fit into the need of growing (`length` > 0) or shrinking (`length` < 0)
-    pub fn prepare_dual_node_growth(&mut self, dual_node_ptr: &DualNodePtr, is_grow: bool) {
-        let mut need_another = self.prepare_dual_node_growth_single(dual_node_ptr, is_grow);
-        while need_another {
-            // when there are 0 weight edges, one may need to run multiple iterations to get it prepared in a proper state
-            need_another = self.prepare_dual_node_growth_single(dual_node_ptr, is_grow);
+    /// the grown fraction of each edge, including the zero-weight edges of erasures
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_serial_edge_growth_fraction_1() {
+        // cargo test dual_module_serial_edge_growth_fraction_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let edge_between = |vertex_1: VertexIndex, vertex_2: VertexIndex| {
+            initializer
+                .weighted_edges
+                .iter()
+                .position(|&(left, right, _)| (left, right) == (vertex_1, vertex_2) || (left, right) == (vertex_2, vertex_1))
+                .unwrap() as EdgeIndex
+        };
+        let (edge_19_20, edge_18_19, edge_11_12) = (edge_between(19, 20), edge_between(18, 19), edge_between(11, 12));
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        code.vertices[19].is_defect = true;
+        code.vertices[20].is_defect = true;
+        let mut syndrome_pattern = code.get_syndrome();
+        syndrome_pattern.erasures = vec![edge_18_19, edge_11_12];
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        interface_ptr.load(&syndrome_pattern, &mut dual_module);
+        interface_ptr.grow(half_weight / 2, &mut dual_module);
+        let fractions = dual_module.all_edge_growth_fractions();
+        assert_eq!(fractions.len(), initializer.weighted_edges.len());
+        for (edge_index, fraction) in fractions.iter().enumerate() {
+            assert_eq!(dual_module.edge_growth_fraction(edge_index as EdgeIndex), *fraction);
         }
+        assert_eq!(fractions[edge_19_20 as usize], 0.5);
+        assert_eq!(fractions[edge_18_19 as usize], 1.0); // reached by the dual node of 19
+        assert_eq!(fractions[edge_11_12 as usize], 0.0); // no dual node around
+        interface_ptr.grow(half_weight / 2, &mut dual_module);
+        assert_eq!(dual_module.edge_growth_fraction(edge_19_20), 1.0);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::super::example_codes::*;
-    use super::super::primal_module_serial::tests::*;
-    use super::*;
 
-    #[allow(dead_code)]
-    fn debug_print_dual_node(dual_module: &DualModuleSerial, dual_node_ptr: &DualNodePtr) {
-        println!("boundary:");
-        let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(dual_node_ptr);
-        let dual_node_internal = dual_node_internal_ptr.read_recursive();
-        for (is_left, edge_weak) in dual_node_internal.boundary.iter() {
-            let edge_ptr = edge_weak.upgrade_force();
-            let edge = edge_ptr.read_recursive_force();
-            println!("    {} {:?}", if *is_left { " left" } else { "right" }, edge);
+    /// a manually driven primal-dual loop terminates on the convergence predicate
+    #[test]
+    fn dual_module_serial_is_converged_1() {
+        // cargo test dual_module_serial_is_converged_1 -- --nocapture
+        use super::super::primal_module::SubGraphBuilder;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        // nothing to decode
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        assert!(interface_ptr.is_converged(&mut dual_module));
+        dual_module.clear();
+        // drive the loop until convergence
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        code.set_defect_vertices(&[16, 17, 23, 25, 37]);
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        primal_module.load(&interface_ptr);
+        assert!(!interface_ptr.is_converged(&mut dual_module));
+        while !interface_ptr.is_converged(&mut dual_module) {
+            let group_max_update_length = dual_module.compute_maximum_update_length();
+            if let Some(length) = group_max_update_length.get_none_zero_growth() {
+                interface_ptr.grow(length, &mut dual_module);
+            } else {
+                primal_module.resolve(group_max_update_length, &interface_ptr, &mut dual_module);
+            }
         }
+        let perfect_matching = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
+        let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+        subgraph_builder.load_perfect_matching(&perfect_matching);
+        assert_eq!(subgraph_builder.total_weight(), interface_ptr.sum_dual_variables());
     }
 
+    /// each illegal grow state transition is reported with the rule it violates
     #[test]
-    fn dual_module_serial_basics() {
-        // cargo test dual_module_serial_basics -- --nocapture
-        let visualize_filename = "dual_module_serial_basics.json".to_string();
+    fn dual_module_serial_check_grow_state_1() {
+        // cargo test dual_module_serial_check_grow_state_1 -- --nocapture
         let half_weight = 500;
         let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
-        let mut visualizer = Visualizer::new(
-            Some(visualize_data_folder() + visualize_filename.as_str()),
-            code.get_positions(),
-            true,
-        )
-        .unwrap();
-        print_visualize_link(visualize_filename.clone());
-        // create dual module
         let initializer = code.get_initializer();
         let mut dual_module = DualModuleSerial::new_empty(&initializer);
-        // try to work on a simple syndrome
         code.vertices[19].is_defect = true;
-        code.vertices[25].is_defect = true;
+        code.vertices[26].is_defect = true;
+        code.vertices[35].is_defect = true;
         let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
-        visualizer
-            .snapshot_combined("syndrome".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        // create dual nodes and grow them by half length
-        let dual_node_19_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
-        let dual_node_25_ptr = interface_ptr.read_recursive().nodes[1].clone().unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
-        visualizer
-            .snapshot_combined("grow to 0.5".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
-        visualizer
-            .snapshot_combined("grow to 1".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, half_weight);
-        visualizer
-            .snapshot_combined("grow to 1.5".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
-        visualizer
-            .snapshot_combined("shrink to 1".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
-        visualizer
-            .snapshot_combined("shrink to 0.5".to_string(), vec![&interface_ptr, &dual_module])
-            .unwrap();
-        dual_module.grow_dual_node(&dual_node_19_ptr, -half_weight);
-        dual_module.grow_dual_node(&dual_node_25_ptr, -half_weight);
-        visualizer
-            .snapshot_combined("shrink to 0".to_string(), vec![&interface_ptr, &dual_module])
+        let dual_node_ptrs: Vec<_> = (0..3)
+            .map(|i| interface_ptr.read_recursive().nodes[i].clone().unwrap())
+            .collect();
+        interface_ptr.grow(2 * half_weight, &mut dual_module);
+        for grow_state in [DualNodeGrowState::Grow, DualNodeGrowState::Stay, DualNodeGrowState::Shrink] {
+            interface_ptr
+                .check_grow_state(&dual_node_ptrs[0], grow_state, &dual_module)
+                .unwrap();
+        }
+        // a node of another decoding
+        let mut other_dual_module = DualModuleSerial::new_empty(&initializer);
+        let other_interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut other_dual_module);
+        assert_eq!(
+            other_interface_ptr.check_grow_state(&dual_node_ptrs[0], DualNodeGrowState::Grow, &other_dual_module),
+            Err(GrowStateError::NotInInterface { node_index: 0 })
+        );
+        // the dual module doesn't host the node
+        let empty_dual_module = DualModuleSerial::new_empty(&initializer);
+        assert!(matches!(
+            interface_ptr.check_grow_state(&dual_node_ptrs[1], DualNodeGrowState::Grow, &empty_dual_module),
+            Err(GrowStateError::DualModule { node_index: 1, .. })
+        ));
+        // an overgrown stack that cannot be restored by shrinking
+        let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(&dual_node_ptrs[2]);
+        let vertex_weak = dual_module.vertices[35].downgrade();
+        dual_node_internal_ptr
+            .write()
+            .overgrown_stack
+            .push((vertex_weak, 3 * half_weight));
+        let error = interface_ptr
+            .check_grow_state(&dual_node_ptrs[2], DualNodeGrowState::Shrink, &dual_module)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("overgrown length 1500 exceeds the dual variable 1000"),
+            "{error}"
+        );
+        dual_node_internal_ptr.write().overgrown_stack.clear();
+        // a corrupted dual variable
+        dual_node_ptrs[2].write().dual_variable_cache.0 -= 2 * half_weight + 1;
+        assert_eq!(
+            interface_ptr.check_grow_state(&dual_node_ptrs[2], DualNodeGrowState::Shrink, &dual_module),
+            Err(GrowStateError::NegativeDualVariable {
+                node_index: 2,
+                dual_variable: -1
+            })
+        );
+        dual_node_ptrs[2].write().dual_variable_cache.0 += 2 * half_weight + 1;
+        // nodes inside a blossom follow the blossom
+        interface_ptr.set_grow_state(&dual_node_ptrs[1], DualNodeGrowState::Shrink, &mut dual_module);
+        let blossom_ptr = interface_ptr.create_blossom(dual_node_ptrs.clone(), vec![], &mut dual_module);
+        let blossom_index = blossom_ptr.read_recursive().index;
+        for grow_state in [DualNodeGrowState::Grow, DualNodeGrowState::Stay, DualNodeGrowState::Shrink] {
+            assert_eq!(
+                interface_ptr.check_grow_state(&dual_node_ptrs[0], grow_state, &dual_module),
+                Err(GrowStateError::InsideBlossom {
+                    node_index: 0,
+                    blossom_index
+                })
+            );
+        }
+        // a blossom with zero dual variable can shrink, which expands it
+        interface_ptr
+            .check_grow_state(&blossom_ptr, DualNodeGrowState::Shrink, &dual_module)
             .unwrap();
     }
 
+    #[test]
+    fn dual_module_serial_conflicting_accessors() {
+        // cargo test dual_module_serial_conflicting_accessors -- --nocapture
+        let (mut dual_module, _interface_ptr, dual_node_19_ptr, dual_node_20_ptr) = dual_module_serial_tight_pair();
+        let group_max_update_length = dual_module.compute_maximum_update_length();
+        let conflict = group_max_update_length.peek().unwrap();
+        assert!(conflict.is_conflicting(&dual_node_19_ptr, &dual_node_20_ptr));
+        let (node_1, node_2) = conflict.conflicting_nodes().unwrap();
+        // syndrome nodes are their own grandsons
+        assert_eq!(conflict.conflicting_grandsons().unwrap(), (node_1.clone(), node_2.clone()));
+        assert!(
+            (node_1 == dual_node_19_ptr && node_2 == dual_node_20_ptr)
+                || (node_1 == dual_node_20_ptr && node_2 == dual_node_19_ptr)
+        );
+        let not_conflicting = MaxUpdateLength::BlossomNeedExpand(dual_node_19_ptr.clone());
+        assert!(not_conflicting.conflicting_nodes().is_none());
+        assert!(not_conflicting.conflicting_grandsons().is_none());
+    }
+
     #[test]
     fn dual_module_serial_blossom_basics() {
         // cargo test dual_module_serial_blossom_basics -- --nocapture
@@ -2854,4 +4185,632 @@ mod tests {
                 .unwrap();
         }
     }
+
+    /// a dense erasure mask is equivalent to the list of erased edges
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_erasure_mask_1() {
+        // cargo test dual_module_erasure_mask_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, half_weight);
+        code.set_erasure_probability(0.1);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let mut erasure_mask = vec![false; initializer.weighted_edges.len()];
+            for &edge_index in syndrome_pattern.erasures.iter() {
+                erasure_mask[edge_index as usize] = true;
+            }
+            solver.solve(&syndrome_pattern);
+            let expected_subgraph = solver.subgraph();
+            solver.clear();
+            solver
+                .solve_with_erasure_mask(syndrome_pattern.defect_vertices.clone(), &erasure_mask, None)
+                .unwrap();
+            assert_eq!(solver.subgraph(), expected_subgraph);
+            solver.clear();
+        }
+        assert!(solver.solve_with_erasure_mask(vec![], &[false], None).is_err());
+    }
+
+    /// erasing whole measurement rounds by region decodes the same as listing all their time-like edges
+    #[cfg(feature = "cli")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_erasure_regions_1() {
+        // cargo test dual_module_erasure_regions_1 -- --nocapture
+        use crate::cli::*;
+        use crate::mwpm_solver::*;
+        let half_weight = 500;
+        let (d, noisy_measurements) = (5, 4);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.02, half_weight);
+        let initializer = code.get_initializer();
+        let edge_rounds = initializer.edge_rounds.as_ref().unwrap();
+        let round_edges = |round: usize| -> Vec<EdgeIndex> {
+            (0..edge_rounds.len() as EdgeIndex)
+                .filter(|&edge_index| edge_rounds[edge_index as usize] == Some(round))
+                .collect()
+        };
+        for round in 0..noisy_measurements as usize {
+            assert_eq!(round_edges(round).len(), (d * (d - 1)) as usize);
+        }
+        let mut solver: Box<dyn PrimalDualSolver> = Box::new(SolverSerial::new(&initializer));
+        let mut verifier = VerifierFusionSerial::new(&initializer);
+        for seed in 0..10 {
+            let defect_vertices = code.generate_random_errors(seed).defect_vertices;
+            let lost_rounds = [seed as usize % 4, (seed as usize + 1) % 4];
+            let region_syndrome = SyndromePattern::new_erasure_regions(
+                defect_vertices.clone(),
+                lost_rounds.iter().map(|round| ErasureRegion::TimeRound(*round)).collect(),
+            );
+            let edge_syndrome = SyndromePattern::new(
+                defect_vertices,
+                lost_rounds.iter().flat_map(|round| round_edges(*round)).collect(),
+            );
+            solver.solve(&region_syndrome);
+            verifier.verify(&mut solver, &region_syndrome, None);
+            let region_subgraph = solver.subgraph();
+            let region_weight = solver.sum_dual_variables();
+            solver.clear();
+            solver.solve(&edge_syndrome);
+            assert_eq!(solver.sum_dual_variables(), region_weight, "seed {seed}");
+            assert_eq!(solver.subgraph(), region_subgraph, "seed {seed}");
+            solver.clear();
+            solver.check_clean().unwrap();
+            // the per-shot input only grows with the number of lost rounds, not with the size of the code
+            let region_size = serde_json::to_string(&region_syndrome).unwrap().len();
+            let edge_size = serde_json::to_string(&edge_syndrome).unwrap().len();
+            assert!(region_size < edge_size, "seed {seed}: {region_size} >= {edge_size}");
+        }
+    }
+
+    /// reset solvers between decodings with erasures, and detect leftover state
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn dual_module_erasure_reset_1() {
+        // cargo test dual_module_erasure_reset_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, half_weight);
+        code.set_erasure_probability(0.1);
+        let initializer = code.get_initializer();
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        // parallel solvers don't support erasures yet, so they only decode the plain syndrome
+        let mut solvers: Vec<(Box<dyn PrimalDualSolver>, bool)> = vec![
+            (Box::new(SolverSerial::new(&initializer)), true),
+            (
+                Box::new(SolverDualParallel::new(&initializer, &partition_info, json!({}))),
+                false,
+            ),
+            (Box::new(SolverParallel::new(&initializer, &partition_info, json!({}))), false),
+        ];
+        for (solver, with_erasures) in solvers.iter_mut() {
+            for seed in 0..10 {
+                let mut syndrome_pattern = code.generate_random_errors(seed);
+                if !*with_erasures {
+                    syndrome_pattern.erasures.clear();
+                }
+                solver.solve(&syndrome_pattern);
+                solver.subgraph();
+                assert!(solver.check_clean().is_err(), "a solved state is not clean");
+                solver.reset().unwrap();
+            }
+        }
+        // erasures loaded by an external caller are reported if the solver doesn't clear them
+        let mut solver = SolverSerial::new(&initializer);
+        solver.subgraph_builder.load_erasures(&[0, 1]);
+        assert!(solver.check_clean().is_err());
+        solver.reset().unwrap();
+    }
+
+    /// malformed syndromes are reported as errors instead of panicking, and the solver stays usable afterwards
+    #[test]
+    fn dual_module_serial_try_solve_1() {
+        // cargo test dual_module_serial_try_solve_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let vertex_num = initializer.vertex_num;
+        let edge_num = initializer.weighted_edges.len() as EdgeIndex;
+        let syndrome_pattern = code.generate_random_errors(1);
+        let mut reference_solver = SolverSerial::new(&initializer);
+        reference_solver.solve(&syndrome_pattern);
+        let expected_weight = reference_solver.sum_dual_variables();
+        let mut solver = SolverSerial::new(&initializer);
+        for (bad_syndrome, is_invalid) in [
+            (SyndromePattern::new_vertices(vec![vertex_num]), true),
+            (SyndromePattern::new_vertices(vec![0, 0]), true),
+            (SyndromePattern::new(vec![0], vec![edge_num]), true),
+            (SyndromePattern::new_dynamic_weights(vec![0], vec![], vec![(0, 3)]), true),
+            (SyndromePattern::new_dynamic_weights(vec![0], vec![1], vec![(0, 2)]), true),
+            // the code doesn't provide `edge_rounds`
+            (
+                SyndromePattern::new_erasure_regions(vec![0], vec![ErasureRegion::TimeRound(0)]),
+                false,
+            ),
+        ] {
+            match solver.try_solve(&bad_syndrome) {
+                Err(SolveError::InvalidSyndrome(message)) => assert!(is_invalid, "{message}"),
+                Err(SolveError::Panicked { message, cleared }) => {
+                    assert!(!is_invalid, "{message}");
+                    assert!(cleared, "{message}");
+                }
+                Err(error) => panic!("unexpected error: {error}"),
+                Ok(()) => panic!("{bad_syndrome:?} should be rejected"),
+            }
+            solver.check_clean().unwrap();
+            solver.try_solve(&syndrome_pattern).unwrap();
+            assert_eq!(solver.sum_dual_variables(), expected_weight);
+            solver.reset().unwrap();
+        }
+    }
+
+    #[test]
+    fn dual_module_serial_max_update_length_cache_1() {
+        // cargo test dual_module_serial_max_update_length_cache_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        let codes: Vec<Box<dyn ExampleCode>> = vec![
+            Box::new(CodeCapacityPlanarCode::new(11, 0.1, 500)),
+            Box::new(PhenomenologicalPlanarCode::new(7, 7, 0.03, 500)),
+            Box::new(CircuitLevelPlanarCode::new(5, 5, 0.01, 500)),
+        ];
+        for mut code in codes {
+            let initializer = code.get_initializer();
+            let mut solver = SolverSerial::new(&initializer);
+            let mut reference_solver = SolverSerial::new(&initializer);
+            reference_solver.dual_module.enable_max_update_length_cache = false;
+            let mut cache_hits = 0;
+            for seed in 0..30 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                solver.solve(&syndrome_pattern);
+                reference_solver.solve(&syndrome_pattern);
+                // the cache is transparent to the primal module, so the result is exactly the same
+                assert_eq!(solver.sum_dual_variables(), reference_solver.sum_dual_variables());
+                assert_eq!(solver.subgraph(), reference_solver.subgraph());
+                cache_hits += solver.dual_module.max_update_length_cache_hits;
+                assert_eq!(reference_solver.dual_module.max_update_length_cache_hits, 0);
+                solver.clear();
+                reference_solver.clear();
+            }
+            assert!(cache_hits > 0, "most nodes are not limiting the growth in each round");
+        }
+    }
+
+    /// the compressed-sparse-row adjacency gives the same results as the pointer-based one
+    #[test]
+    fn dual_module_serial_compact_adjacency_1() {
+        // cargo test dual_module_serial_compact_adjacency_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let compact_adjacency = CompactAdjacency::new(4, &[(0, 1, 2), (1, 2, 2), (1, 3, 2)]);
+        assert_eq!(compact_adjacency.offsets, vec![0, 1, 4, 5, 6]);
+        assert_eq!(compact_adjacency.edges_of(1), &[0, 1, 2]);
+        assert_eq!(compact_adjacency.edges_of(3), &[2]);
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let compact = DualModuleSerial::new_compact_adjacency(&initializer);
+        assert!(compact
+            .vertices
+            .iter()
+            .all(|vertex_ptr| vertex_ptr.read_recursive_force().edges.is_empty()));
+        assert!(DualModuleSerial::new_empty(&initializer).compact_adjacency.is_none());
+        let mut compact_solver = SolverSerial::new_config(&initializer, json!({"compact_adjacency": true}));
+        assert!(compact_solver.dual_module.compact_adjacency.is_some());
+        let mut pointer_solver = SolverSerial::new(&initializer);
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            compact_solver.solve(&syndrome_pattern);
+            pointer_solver.solve(&syndrome_pattern);
+            assert_eq!(compact_solver.sum_dual_variables(), pointer_solver.sum_dual_variables());
+            assert_eq!(compact_solver.subgraph(), pointer_solver.subgraph());
+            compact_solver.clear();
+            pointer_solver.clear();
+        }
+    }
+
+    /// reordered edges keep the global edge indices in the interface, so that erasures, dynamic weights, snapshots and
+    /// the subgraph round-trip
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_serial_reorder_edges_1() {
+        // cargo test --release dual_module_serial_reorder_edges_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CircuitLevelPlanarCode::new(7, 7, 0.005, half_weight);
+        code.set_erasure_probability(0.01);
+        let initializer = code.get_initializer();
+        let reordered = DualModuleSerial::new_reordered(&initializer);
+        let local_edge_indices = reordered.local_edge_indices.as_ref().unwrap();
+        assert!(local_edge_indices
+            .iter()
+            .enumerate()
+            .any(|(edge_index, &local_edge_index)| edge_index != local_edge_index as usize));
+        for (edge_index, &(i, j, weight)) in initializer.weighted_edges.iter().enumerate() {
+            let local_edge_index = reordered.local_edge_index(edge_index as EdgeIndex).unwrap();
+            let edge = reordered.edges[local_edge_index as usize].read_recursive_force();
+            assert_eq!(edge.edge_index, edge_index as EdgeIndex);
+            assert_eq!(edge.weight, weight);
+            assert_eq!(edge.left.upgrade_force().read_recursive_force().vertex_index, i.min(j));
+            assert_eq!(edge.right.upgrade_force().read_recursive_force().vertex_index, i.max(j));
+        }
+        // the erasures and dynamic weights are loaded to the same edges, and restored after clear
+        let mut reordered = reordered;
+        let mut original = DualModuleSerial::new_empty(&initializer);
+        let syndrome_patterns: Vec<_> = (0..50).map(|seed| code.generate_random_errors(seed)).collect();
+        let dynamic_patterns: Vec<_> = syndrome_patterns
+            .iter()
+            .map(|syndrome_pattern| {
+                let dynamic_weights = syndrome_pattern
+                    .erasures
+                    .iter()
+                    .map(|&edge_index| (edge_index, initializer.weighted_edges[edge_index as usize].2 / 2))
+                    .collect();
+                SyndromePattern::new_dynamic_weights(syndrome_pattern.defect_vertices.clone(), vec![], dynamic_weights)
+            })
+            .collect();
+        assert!(syndrome_patterns
+            .iter()
+            .take(5)
+            .any(|syndrome_pattern| !syndrome_pattern.erasures.is_empty()));
+        for (syndrome_pattern, dynamic_pattern) in syndrome_patterns.iter().zip(dynamic_patterns.iter()).take(5) {
+            let erased_weights: Vec<_> = syndrome_pattern.erasures.iter().map(|&edge_index| (edge_index, 0)).collect();
+            for (is_erasure, modified_weights) in [(true, &erased_weights), (false, &dynamic_pattern.dynamic_weights)] {
+                for dual_module in [&mut reordered, &mut original] {
+                    if is_erasure {
+                        dual_module.load_erasures(&syndrome_pattern.erasures);
+                    } else {
+                        dual_module.load_dynamic_weights(modified_weights);
+                    }
+                }
+                for &(edge_index, weight) in modified_weights.iter() {
+                    let local_edge_index = reordered.local_edge_index(edge_index).unwrap();
+                    let edge = reordered.edges[local_edge_index as usize].read_recursive_force();
+                    assert_eq!(edge.edge_index, edge_index);
+                    assert_eq!(edge.weight, weight);
+                }
+                assert_eq!(reordered.snapshot(false), original.snapshot(false));
+                for dual_module in [&mut reordered, &mut original] {
+                    dual_module.clear();
+                }
+                assert_eq!(reordered.snapshot(false), original.snapshot(false));
+            }
+        }
+        // the solver reports the same subgraph in the global edge indices
+        let mut reordered_solver = SolverSerial::new_config(&initializer, json!({"reorder_edges": true}));
+        let mut original_solver = SolverSerial::new(&initializer);
+        for syndrome_pattern in syndrome_patterns.iter().chain(dynamic_patterns.iter()) {
+            reordered_solver.solve(syndrome_pattern);
+            original_solver.solve(syndrome_pattern);
+            assert_eq!(reordered_solver.sum_dual_variables(), original_solver.sum_dual_variables());
+            assert_eq!(reordered_solver.subgraph(), original_solver.subgraph());
+            reordered_solver.clear();
+            original_solver.clear();
+        }
+    }
+
+    /// warm a solver to observe the capacities; reserving them pre-allocates the structures, and the same decoding
+    /// problems reach the same high-water marks
+    #[test]
+    fn dual_module_serial_capacity_hints_1() {
+        // cargo test dual_module_serial_capacity_hints_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let syndrome_patterns: Vec<_> = (0..200).map(|seed| code.generate_random_errors(seed)).collect();
+        let hints = observe_capacity_hints(&initializer, &syndrome_patterns);
+        assert!(hints.max_defects > 0 && hints.max_blossoms > 0 && hints.max_boundary_per_node > 0);
+        let mut solver = SolverSerial::new(&initializer);
+        solver.reserve_capacity(hints);
+        assert!(solver.dual_module.nodes.capacity() >= hints.max_nodes());
+        assert!(solver.dual_module.active_list.capacity() >= hints.max_nodes());
+        assert!(solver.interface_ptr.read_recursive().nodes.capacity() >= hints.max_nodes());
+        assert!(solver.primal_module.read_recursive().nodes.capacity() >= hints.max_nodes());
+        for syndrome_pattern in syndrome_patterns.iter() {
+            solver.solve(syndrome_pattern);
+            solver.clear();
+        }
+        assert_eq!(solver.observed_capacities(), hints);
+    }
+
+    fn observe_capacity_hints(initializer: &SolverInitializer, syndrome_patterns: &[SyndromePattern]) -> CapacityHints {
+        use super::super::mwpm_solver::*;
+        let mut solver = SolverSerial::new(initializer);
+        for syndrome_pattern in syndrome_patterns.iter() {
+            solver.solve(syndrome_pattern);
+            solver.clear();
+        }
+        solver.observed_capacities()
+    }
+
+    /// the tail latency with and without the capacity hints; too slow for the default test suite
+    #[test]
+    #[ignore]
+    fn dual_module_serial_capacity_hints_latency() {
+        // cargo test --release dual_module_serial_capacity_hints_latency -- --ignored --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let syndrome_patterns: Vec<_> = (0..10000).map(|seed| code.generate_random_errors(seed)).collect();
+        let hints = observe_capacity_hints(&initializer, &syndrome_patterns);
+        let latency_percentiles = |reserve: bool| {
+            let mut solver = SolverSerial::new(&initializer);
+            if reserve {
+                solver.reserve_capacity(hints);
+            }
+            let mut latencies = Vec::with_capacity(syndrome_patterns.len());
+            for syndrome_pattern in syndrome_patterns.iter() {
+                let begin = std::time::Instant::now();
+                solver.solve(syndrome_pattern);
+                latencies.push(begin.elapsed().as_secs_f64());
+                solver.clear();
+            }
+            latencies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let percentile = |p: f64| latencies[((latencies.len() - 1) as f64 * p).round() as usize];
+            (percentile(0.5), percentile(0.99), percentile(0.999))
+        };
+        let (p50, p99, p999) = latency_percentiles(false);
+        println!("without hints: p50 = {p50:.3e}s, p99 = {p99:.3e}s, p999 = {p999:.3e}s");
+        let (p50, p99, hinted_p999) = latency_percentiles(true);
+        println!("with hints: p50 = {p50:.3e}s, p99 = {p99:.3e}s, p999 = {hinted_p999:.3e}s");
+        println!("p999 reduction: {:.1}%", (1. - hinted_p999 / p999) * 100.);
+    }
+
+    /// with dense erasures, the zero-weight regions are large; searching a tight path or a complete-graph path must not
+    /// sweep the whole region for every matched pair, which used to make recovering the subgraph quadratic; the
+    /// search itself is checked by `complete_graph_erased_region_path_1`, while this test keeps the graph small
+    /// enough for the default test suite (d = 21 with ~10^4 erasures took seconds even after the fix)
+    #[test]
+    fn dual_module_serial_dense_erasures_1() {
+        // cargo test --release dual_module_serial_dense_erasures_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(11, 11, 0.001, half_weight);
+        code.set_erasure_probability(0.5);
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(0);
+        println!(
+            "erasures: {}, defects: {}",
+            syndrome_pattern.erasures.len(),
+            syndrome_pattern.defect_vertices.len()
+        );
+        assert!(syndrome_pattern.erasures.len() >= 1000);
+        let mut solver = SolverSerial::new_config(&initializer, json!({ "realized_paths": true }));
+        // applying and restoring the erasures is proportional to the number of erasures
+        let begin = std::time::Instant::now();
+        solver.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
+        solver.subgraph_builder.clear();
+        let erasure_time = begin.elapsed().as_secs_f64();
+        let begin = std::time::Instant::now();
+        solver.solve(&syndrome_pattern);
+        let solve_time = begin.elapsed().as_secs_f64();
+        let begin = std::time::Instant::now();
+        let mut perfect_matching = solver.perfect_matching();
+        let perfect_matching_time = begin.elapsed().as_secs_f64();
+        assert!(perfect_matching.realized_paths.is_some());
+        solver.subgraph_builder.load_perfect_matching(&perfect_matching);
+        let realized_weight = solver.subgraph_builder.total_weight();
+        // recover the same subgraph weight from the complete graph instead of the realized tight paths
+        perfect_matching.realized_paths = None;
+        let begin = std::time::Instant::now();
+        solver.subgraph_builder.load_perfect_matching(&perfect_matching);
+        let complete_graph_time = begin.elapsed().as_secs_f64();
+        let complete_graph_weight = solver.subgraph_builder.total_weight();
+        println!(
+            "erasures: {erasure_time:.3e}s, solve: {solve_time:.3e}s, perfect matching: {perfect_matching_time:.3e}s, complete graph subgraph: {complete_graph_time:.3e}s"
+        );
+        // verify the result is still a minimum-weight perfect matching
+        assert_eq!(realized_weight, solver.sum_dual_variables());
+        assert_eq!(complete_graph_weight, solver.sum_dual_variables());
+        // generous bound even for debug builds; the quadratic sweep took several seconds in release mode
+        assert!(
+            perfect_matching_time + complete_graph_time < 30.,
+            "subgraph recovery is too slow"
+        );
+    }
+
+    #[test]
+    fn dual_module_serial_sync_event_1() {
+        // cargo test dual_module_serial_sync_event_1 -- --nocapture
+        let fusion_unit_ptr = PartitionUnitPtr::new_value(PartitionUnit {
+            unit_index: 2,
+            enabled: true,
+        });
+        let (mut left, mut right) = new_mirrored_pair(&fusion_unit_ptr);
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        let dual_node_0 = interface_ptr.create_defect_node(0, &mut left);
+        let dual_node_1 = interface_ptr.create_defect_node(1, &mut left);
+        let dual_node_6 = interface_ptr.create_defect_node(6, &mut right);
+        assert_eq!(boundary_edges(&right, &dual_node_6), vec![(false, 5)]);
+        // (a) propagate into the empty mirrored vertex
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, Some((&dual_node_0, &dual_node_0))));
+        let snapshot = right.snapshot(false);
+        assert_eq!(snapshot["vertices"][4]["propagated_dual_node"], json!(0));
+        assert_eq!(snapshot["vertices"][4]["propagated_grandson_dual_node"], json!(0));
+        assert_eq!(snapshot["edges"][4]["left_dual_node"], json!(0));
+        assert_eq!(snapshot["edges"][4]["left_grandson_dual_node"], json!(0));
+        assert_eq!(boundary_edges(&right, &dual_node_0), vec![(true, 4)]);
+        assert_eq!(boundary_edges(&right, &dual_node_6), vec![(false, 5)]);
+        assert_eq!(right.nodes_length, 2);
+        // (b) a conflicting dual node vacates the vertex and replaces the previous one
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, Some((&dual_node_1, &dual_node_1))));
+        let snapshot = right.snapshot(false);
+        assert_eq!(snapshot["vertices"][4]["propagated_dual_node"], json!(1));
+        assert_eq!(snapshot["vertices"][4]["propagated_grandson_dual_node"], json!(1));
+        assert_eq!(snapshot["edges"][4]["left_dual_node"], json!(1));
+        assert_eq!(snapshot["edges"][4]["left_grandson_dual_node"], json!(1));
+        assert_eq!(boundary_edges(&right, &dual_node_0), vec![]);
+        assert_eq!(boundary_edges(&right, &dual_node_1), vec![(true, 4)]);
+        assert_eq!(boundary_edges(&right, &dual_node_6), vec![(false, 5)]);
+        assert_eq!(right.nodes_length, 3);
+        // (c) vacate the vertex back to None
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, None));
+        let snapshot = right.snapshot(false);
+        assert!(snapshot["vertices"][4].get("propagated_dual_node").is_none());
+        assert!(snapshot["vertices"][4].get("propagated_grandson_dual_node").is_none());
+        assert!(snapshot["edges"][4].get("left_dual_node").is_none());
+        assert!(snapshot["edges"][4].get("left_grandson_dual_node").is_none());
+        assert_eq!(boundary_edges(&right, &dual_node_1), vec![]);
+        assert_eq!(boundary_edges(&right, &dual_node_6), vec![(false, 5)]);
+        assert_eq!(right.nodes_length, 3);
+        // vacating an empty vertex is a no-op
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, None));
+        assert!(right.snapshot(false)["vertices"][4].get("propagated_dual_node").is_none());
+    }
+
+    #[test]
+    fn dual_module_serial_sync_event_2() {
+        // cargo test dual_module_serial_sync_event_2 -- --nocapture
+        let fusion_unit_ptr = PartitionUnitPtr::new_value(PartitionUnit {
+            unit_index: 2,
+            enabled: true,
+        });
+        let (mut left, mut right) = new_mirrored_pair(&fusion_unit_ptr);
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        let dual_node_0 = interface_ptr.create_defect_node(0, &mut left);
+        let dual_node_1 = interface_ptr.create_defect_node(1, &mut left);
+        let dual_node_2 = interface_ptr.create_defect_node(2, &mut left);
+        let blossom =
+            interface_ptr.create_blossom(vec![dual_node_0.clone(), dual_node_1, dual_node_2.clone()], vec![], &mut left);
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, Some((&blossom, &dual_node_0))));
+        let snapshot = right.snapshot(false);
+        assert_eq!(snapshot["vertices"][4]["propagated_dual_node"], json!(3));
+        assert_eq!(snapshot["vertices"][4]["propagated_grandson_dual_node"], json!(0));
+        assert_eq!(boundary_edges(&right, &blossom), vec![(true, 4)]);
+        assert_eq!(right.nodes_length, 2);
+        // (d) the same vertex propagated from another unit with a different grandson of the same blossom: benign conflict,
+        // only the grandson of the vertex is updated and the boundary is not touched
+        right.execute_sync_event(&new_sync_request(&fusion_unit_ptr, Some((&blossom, &dual_node_2))));
+        let snapshot = right.snapshot(false);
+        assert_eq!(snapshot["vertices"][4]["propagated_dual_node"], json!(3));
+        assert_eq!(snapshot["vertices"][4]["propagated_grandson_dual_node"], json!(2));
+        assert_eq!(snapshot["edges"][4]["left_dual_node"], json!(3));
+        assert_eq!(snapshot["edges"][4]["left_grandson_dual_node"], json!(0)); // edges keep the grandson at propagation
+        assert_eq!(boundary_edges(&right, &blossom), vec![(true, 4)]);
+        assert_eq!(right.nodes_length, 3); // only the new grandson is registered
+    }
+
+    /// regression test of the overgrown stack: the leaves 1, 2 and 3 are enclosed by the node once their only edge is fully
+    /// grown, so they would be lost from the boundary without the stack when shrinking back
+    #[test]
+    fn dual_module_serial_overgrown_stack_1() {
+        // cargo test dual_module_serial_overgrown_stack_1 -- --nocapture
+        let initializer = SolverInitializer::new(5, vec![(0, 1, 100), (0, 2, 200), (0, 3, 300), (0, 4, 1000)], vec![4]);
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let interface_ptr = DualModuleInterfacePtr::new_load(&SyndromePattern::new_vertices(vec![0]), &mut dual_module);
+        let dual_node_ptr = interface_ptr.read_recursive().nodes[0].clone().unwrap();
+        let all_boundary = vec![(true, 0), (true, 1), (true, 2), (true, 3)];
+        assert_eq!(dual_module.overgrown_stack_depth(&dual_node_ptr), 0);
+        assert_eq!(boundary_edges(&dual_module, &dual_node_ptr), all_boundary);
+        // (dual variable, overgrown stack depth, boundary): a vertex is pushed when the boundary is prepared for the next growth
+        let grow_expected = [
+            (50, 0, vec![0, 1, 2, 3]),
+            (100, 0, vec![0, 1, 2, 3]),
+            (150, 1, vec![1, 2, 3]),
+            (200, 1, vec![1, 2, 3]),
+            (250, 2, vec![2, 3]),
+            (300, 2, vec![2, 3]),
+            (350, 3, vec![3]),
+        ];
+        let shrink_expected = [
+            (300, 3, vec![3]),
+            (250, 2, vec![2, 3]),
+            (200, 2, vec![2, 3]),
+            (150, 1, vec![1, 2, 3]),
+            (100, 1, vec![1, 2, 3]),
+            (50, 0, vec![0, 1, 2, 3]),
+            (0, 0, vec![0, 1, 2, 3]),
+        ];
+        for (length, expected) in [(50, grow_expected), (-50, shrink_expected)] {
+            if length < 0 {
+                interface_ptr.set_grow_state(&dual_node_ptr, DualNodeGrowState::Shrink, &mut dual_module);
+            }
+            for (dual_variable, depth, boundary) in expected {
+                interface_ptr.grow(50, &mut dual_module);
+                dual_module.sanity_check().unwrap();
+                assert_eq!(interface_ptr.sum_dual_variables(), dual_variable);
+                assert_eq!(
+                    dual_module.overgrown_stack_depth(&dual_node_ptr),
+                    depth,
+                    "dual variable {dual_variable}"
+                );
+                let boundary: Vec<_> = boundary.into_iter().map(|edge_index| (true, edge_index)).collect();
+                assert_eq!(
+                    boundary_edges(&dual_module, &dual_node_ptr),
+                    boundary,
+                    "dual variable {dual_variable}"
+                );
+            }
+        }
+        // the enclosed leaves are vacated and the node stops shrinking at the defect
+        let snapshot = dual_module.snapshot(false);
+        for vertex_index in 1..4 {
+            assert!(snapshot["vertices"][vertex_index].get("propagated_dual_node").is_none());
+        }
+        for edge_index in 0..4 {
+            assert_eq!(snapshot["edges"][edge_index]["left_growth"], json!(0));
+        }
+        assert_eq!(boundary_edges(&dual_module, &dual_node_ptr), all_boundary);
+        let group_max_update_length = dual_module.compute_maximum_update_length();
+        assert!(matches!(
+            group_max_update_length.peek(),
+            Some(MaxUpdateLength::VertexShrinkStop((_, None)))
+        ));
+    }
+
+    /// build two partitioned units of a 7-vertex chain by hand, mirroring vertex 4 which belongs to a fusion unit:
+    /// unit 0 owns [0, 4) with edges 0-3 and unit 1 owns [5, 7) with edges 4-5
+    fn new_mirrored_pair(fusion_unit_ptr: &PartitionUnitPtr) -> (DualModuleSerial, DualModuleSerial) {
+        let interfaces = vec![(fusion_unit_ptr.downgrade(), vec![(4, false)])];
+        let left = DualModuleSerial::new_partitioned(&PartitionedSolverInitializer {
+            unit_index: 0,
+            vertex_num: 7,
+            edge_num: 6,
+            owning_range: VertexRange::new(0, 4),
+            owning_interface: None,
+            interfaces: interfaces.clone(),
+            weighted_edges: vec![(0, 1, 100, 0), (1, 2, 100, 1), (2, 3, 100, 2), (3, 4, 100, 3)],
+            virtual_vertices: vec![],
+        });
+        let right = DualModuleSerial::new_partitioned(&PartitionedSolverInitializer {
+            unit_index: 1,
+            vertex_num: 7,
+            edge_num: 6,
+            owning_range: VertexRange::new(5, 7),
+            owning_interface: None,
+            interfaces,
+            weighted_edges: vec![(4, 5, 100, 4), (5, 6, 100, 5)],
+            virtual_vertices: vec![],
+        });
+        (left, right)
+    }
+
+    /// craft a sync request at the mirrored vertex 4, with the grandson given explicitly
+    fn new_sync_request(
+        fusion_unit_ptr: &PartitionUnitPtr,
+        propagated: Option<(&DualNodePtr, &DualNodePtr)>,
+    ) -> SyncRequest {
+        // nothing has grown in these tests, so the dual variables are all zero
+        let to_weak = |node_ptr: &DualNodePtr| (node_ptr.downgrade(), 0, node_ptr.get_representative_vertex());
+        SyncRequest {
+            mirror_unit_weak: fusion_unit_ptr.downgrade(),
+            vertex_index: 4,
+            propagated_dual_node: propagated.map(|(node_ptr, _)| to_weak(node_ptr)),
+            propagated_grandson_dual_node: propagated.map(|(_, grandson_ptr)| to_weak(grandson_ptr)),
+        }
+    }
+
+    fn boundary_edges(dual_module: &DualModuleSerial, dual_node_ptr: &DualNodePtr) -> Vec<(bool, EdgeIndex)> {
+        let dual_node_internal_ptr = dual_module.get_dual_node_internal_ptr(dual_node_ptr);
+        let dual_node_internal = dual_node_internal_ptr.read_recursive();
+        dual_node_internal
+            .boundary
+            .iter()
+            .map(|(is_left, edge_weak)| (*is_left, edge_weak.upgrade_force().read_recursive_force().edge_index))
+            .collect()
+    }
 }
@@ -8,23 +8,27 @@
 //! To maximize code efficiency, user should design how to group vertices such that memory speed is constant for arbitrary large code distance.
 //!
 
+#[cfg(feature = "parallel")]
 use super::pointers::*;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
 use crate::rand_xoshiro::rand_core::SeedableRng;
+#[cfg(feature = "parallel")]
 use crate::rayon::prelude::*;
 use crate::serde_json;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+#[cfg(feature = "qecp_integrate")]
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, BufRead};
+use std::sync::{Arc, Mutex};
 
 /// Vertex corresponds to a stabilizer measurement bit
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CodeVertex {
     /// position helps to visualize
@@ -41,7 +45,7 @@ pub struct CodeVertex {
     pub is_defect: bool,
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeVertex {
     #[cfg(feature = "python_binding")]
@@ -51,9 +55,9 @@ impl CodeVertex {
 }
 
 /// Edge flips the measurement result of two vertices
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CodeEdge {
     /// the two vertices incident to this edge
@@ -73,7 +77,7 @@ pub struct CodeEdge {
     pub is_erasure: bool,
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeEdge {
     #[cfg_attr(feature = "python_binding", new)]
@@ -92,11 +96,87 @@ impl CodeEdge {
     }
 }
 
-/// default function for computing (pre-scaled) weight from probability
-#[cfg_attr(feature = "python_binding", pyfunction)]
-pub fn weight_of_p(p: f64) -> f64 {
-    assert!((0. ..=0.5).contains(&p), "p must be a reasonable value between 0 and 50%");
-    ((1. - p) / p).ln()
+/// the vertices of each measurement round share the same `t` position, so the layers are the distinct values of `t`
+/// in increasing order; an edge connecting two layers belongs to the earlier one, whose measurement result is
+/// compared by both of its vertices
+#[allow(clippy::unnecessary_cast)]
+pub fn edge_rounds_by_layer(vertices: &[CodeVertex], edges: &[CodeEdge]) -> Vec<Option<usize>> {
+    let mut layers: Vec<f64> = vertices.iter().map(|vertex| vertex.position.t).collect();
+    layers.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    layers.dedup();
+    let vertex_layers: Vec<usize> = vertices
+        .iter()
+        .map(|vertex| {
+            let t = vertex.position.t;
+            layers.binary_search_by(|layer| layer.partial_cmp(&t).unwrap()).unwrap()
+        })
+        .collect();
+    edges
+        .iter()
+        .map(|edge| {
+            let (layer_1, layer_2) = (
+                vertex_layers[edge.vertices.0 as usize],
+                vertex_layers[edge.vertices.1 as usize],
+            );
+            if layer_1 != layer_2 {
+                Some(std::cmp::min(layer_1, layer_2))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// the initializers memoized by [`ExampleCode::get_initializer_cached`], keyed by [`initializer_fingerprint`] and the
+/// type name of the code
+static INITIALIZER_CACHE: Mutex<Vec<(u64, &'static str, Arc<SolverInitializer>)>> = Mutex::new(Vec::new());
+
+/// the maximum number of memoized initializers, the least recently inserted is evicted first
+const INITIALIZER_CACHE_SIZE: usize = 8;
+
+/// a fingerprint of everything that [`ExampleCode::get_initializer`] depends on: the code type (which determines the
+/// edge rounds), the virtual vertices and layers of vertices and the weighted edges; it's computed in a single pass of
+/// integer mixing, which is much cheaper than building the initializer
+#[allow(clippy::unnecessary_cast)]
+fn initializer_fingerprint(type_name: &str, vertices: &[CodeVertex], edges: &[CodeEdge]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |value: u64| {
+        hash = (hash.rotate_left(5) ^ value).wrapping_mul(0x517cc1b727220a95);
+    };
+    for byte in type_name.bytes() {
+        feed(byte as u64);
+    }
+    feed(vertices.len() as u64);
+    for vertex in vertices.iter() {
+        feed(vertex.is_virtual as u64);
+        feed(vertex.position.t.to_bits());
+    }
+    feed(edges.len() as u64);
+    for edge in edges.iter() {
+        feed(edge.vertices.0 as u64);
+        feed(edge.vertices.1 as u64);
+        feed(edge.half_weight as u64);
+    }
+    hash
+}
+
+/// whether a cached initializer matches the decoding graph, to rule out a collision of [`initializer_fingerprint`]; the
+/// edge rounds are not compared because they follow from the layers of the vertices and computing them is as
+/// expensive as building the initializer
+#[allow(clippy::unnecessary_cast)]
+fn initializer_matches(initializer: &SolverInitializer, vertices: &[CodeVertex], edges: &[CodeEdge]) -> bool {
+    initializer.vertex_num as usize == vertices.len()
+        && initializer.weighted_edges.len() == edges.len()
+        && edges
+            .iter()
+            .zip(initializer.weighted_edges.iter())
+            .all(|(edge, &(left, right, weight))| edge.vertices == (left, right) && edge.half_weight * 2 == weight)
+        && vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, vertex)| vertex.is_virtual)
+            .map(|(vertex_index, _)| vertex_index as VertexIndex)
+            .eq(initializer.virtual_vertices.iter().cloned())
 }
 
 pub trait ExampleCode {
@@ -110,22 +190,14 @@ pub trait ExampleCode {
     }
 
     /// generic method that automatically computes integer weights from probabilities,
-    /// scales such that the maximum integer weight is 10000 and the minimum is 1
+    /// scaled such that the maximum half weight is `max_half_weight`, see [`normalized_weights_of_p`];
+    /// an edge with `p = 0.5` has weight 0, while it used to get the minimum weight 2 like the other unlikely edges
     fn compute_weights(&mut self, max_half_weight: Weight) {
         let (_vertices, edges) = self.vertices_edges();
-        let mut max_weight = 0.;
-        for edge in edges.iter() {
-            let weight = weight_of_p(edge.p);
-            if weight > max_weight {
-                max_weight = weight;
-            }
-        }
-        assert!(max_weight > 0., "max weight is not expected to be 0.");
-        // scale all weights but set the smallest to 1
-        for edge in edges.iter_mut() {
-            let weight = weight_of_p(edge.p);
-            let half_weight: Weight = ((max_half_weight as f64) * weight / max_weight).round() as Weight;
-            edge.half_weight = if half_weight == 0 { 1 } else { half_weight }; // weight is required to be even
+        let probabilities: Vec<f64> = edges.iter().map(|edge| edge.p).collect();
+        let weights = normalized_weights_of_p(&probabilities, max_half_weight);
+        for (edge, weight) in edges.iter_mut().zip(weights) {
+            edge.half_weight = weight / 2;
         }
     }
 
@@ -136,31 +208,40 @@ pub trait ExampleCode {
         if vertices.is_empty() || edges.is_empty() {
             return Err("empty graph".to_string());
         }
-        // check duplicated edges
-        let mut existing_edges = HashMap::<(VertexIndex, VertexIndex), EdgeIndex>::with_capacity(edges.len() * 2);
-        for (edge_idx, edge) in edges.iter().enumerate() {
-            let (v1, v2) = edge.vertices;
-            let unique_edge = if v1 < v2 { (v1, v2) } else { (v2, v1) };
-            if existing_edges.contains_key(&unique_edge) {
-                let previous_idx = existing_edges[&unique_edge];
-                return Err(format!(
-                    "duplicate edge {} and {} with incident vertices {} and {}",
-                    previous_idx, edge_idx, v1, v2
-                ));
-            }
-            existing_edges.insert(unique_edge, edge_idx as EdgeIndex);
+        // check duplicated edges by sorting them, which is much faster than hashing for large graphs
+        let mut unique_edges: Vec<(VertexIndex, VertexIndex, usize)> = edges
+            .iter()
+            .enumerate()
+            .map(|(edge_idx, edge)| {
+                let (v1, v2) = edge.vertices;
+                (v1.min(v2), v1.max(v2), edge_idx)
+            })
+            .collect();
+        unique_edges.sort_unstable();
+        // report the earliest edge that duplicates a previous one
+        if let Some((previous_idx, edge_idx)) = unique_edges
+            .windows(2)
+            .filter(|pair| (pair[0].0, pair[0].1) == (pair[1].0, pair[1].1))
+            .map(|pair| (pair[0].2, pair[1].2))
+            .min_by_key(|&(_, edge_idx)| edge_idx)
+        {
+            let (v1, v2) = edges[edge_idx].vertices;
+            return Err(format!(
+                "duplicate edge {} and {} with incident vertices {} and {}",
+                previous_idx, edge_idx, v1, v2
+            ));
         }
         // check duplicated referenced edge from each vertex
+        let mut neighbor_edges = Vec::<EdgeIndex>::new();
         for (vertex_idx, vertex) in vertices.iter().enumerate() {
-            let mut existing_edges = HashMap::<EdgeIndex, ()>::new();
             if vertex.neighbor_edges.is_empty() {
                 return Err(format!("vertex {} do not have any neighbor edges", vertex_idx));
             }
-            for edge_idx in vertex.neighbor_edges.iter() {
-                if existing_edges.contains_key(edge_idx) {
-                    return Err(format!("duplicate referred edge {} from vertex {}", edge_idx, vertex_idx));
-                }
-                existing_edges.insert(*edge_idx, ());
+            neighbor_edges.clear();
+            neighbor_edges.extend_from_slice(&vertex.neighbor_edges);
+            neighbor_edges.sort_unstable();
+            if let Some(pair) = neighbor_edges.windows(2).find(|pair| pair[0] == pair[1]) {
+                return Err(format!("duplicate referred edge {} from vertex {}", pair[0], vertex_idx));
             }
         }
         Ok(())
@@ -186,12 +267,17 @@ pub trait ExampleCode {
     #[allow(clippy::unnecessary_cast)]
     fn fill_vertices(&mut self, vertex_num: VertexNum) {
         let (vertices, edges) = self.vertices_edges();
+        let mut degrees = vec![0usize; vertex_num as usize];
+        for edge in edges.iter() {
+            degrees[edge.vertices.0 as usize] += 1;
+            degrees[edge.vertices.1 as usize] += 1;
+        }
         vertices.clear();
-        vertices.reserve(vertex_num as usize);
-        for _ in 0..vertex_num {
+        vertices.reserve_exact(vertex_num as usize);
+        for degree in degrees {
             vertices.push(CodeVertex {
                 position: VisualizePosition::new(0., 0., 0.),
-                neighbor_edges: Vec::new(),
+                neighbor_edges: Vec::with_capacity(degree),
                 is_virtual: false,
                 is_defect: false,
             });
@@ -232,7 +318,43 @@ pub trait ExampleCode {
             vertex_num,
             weighted_edges,
             virtual_vertices,
+            edge_rounds: self.get_edge_rounds(),
+        }
+    }
+
+    /// memoized [`ExampleCode::get_initializer`] for repeated calls, e.g. within the benchmark loop; the cache is keyed by
+    /// a fingerprint of the decoding graph and a hit is checked against the vertices and edges, so it's always
+    /// consistent with the current decoding graph
+    fn get_initializer_cached(&self) -> Arc<SolverInitializer> {
+        let (vertices, edges) = self.immutable_vertices_edges();
+        let type_name = std::any::type_name::<Self>();
+        let fingerprint = initializer_fingerprint(type_name, vertices, edges);
+        if let Some((_, _, initializer)) =
+            INITIALIZER_CACHE
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(cached_fingerprint, cached_type_name, initializer)| {
+                    *cached_fingerprint == fingerprint
+                        && *cached_type_name == type_name
+                        && initializer_matches(initializer, vertices, edges)
+                })
+        {
+            return initializer.clone();
         }
+        let initializer = Arc::new(self.get_initializer());
+        let mut cache = INITIALIZER_CACHE.lock().unwrap();
+        if cache.len() >= INITIALIZER_CACHE_SIZE {
+            cache.remove(0);
+        }
+        cache.push((fingerprint, type_name, initializer.clone()));
+        initializer
+    }
+
+    /// the measurement round of each edge, see [`SolverInitializer::edge_rounds`]; `None` for codes with a single
+    /// round of measurement
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        None
     }
 
     /// set defect vertices (non-trivial measurement result in case of single round of measurement,
@@ -298,14 +420,24 @@ pub trait ExampleCode {
     }
 
     /// generate random errors based on the edge probabilities and a seed for pseudo number generator
-    #[allow(clippy::unnecessary_cast)]
     fn generate_random_errors(&mut self, seed: u64) -> SyndromePattern {
+        self.generate_random_errors_with_edges(seed)
+            .expect("the default error generator always knows the error edges")
+            .0
+    }
+
+    /// same as [`ExampleCode::generate_random_errors`] but also returns the edges with errors, which is needed to tell
+    /// logical errors; codes that override the error generator and don't know the errors (e.g. reading syndromes from
+    /// a file) should return `None`
+    #[allow(clippy::unnecessary_cast)]
+    fn generate_random_errors_with_edges(&mut self, seed: u64) -> Option<(SyndromePattern, Vec<EdgeIndex>)> {
         let mut rng = DeterministicRng::seed_from_u64(seed);
         let (vertices, edges) = self.vertices_edges();
         for vertex in vertices.iter_mut() {
             vertex.is_defect = false;
         }
-        for edge in edges.iter_mut() {
+        let mut error_edges = vec![];
+        for (edge_index, edge) in edges.iter_mut().enumerate() {
             let p = if rng.next_f64() < edge.pe {
                 edge.is_erasure = true;
                 0.5 // when erasure happens, there are 50% chance of error
@@ -314,6 +446,7 @@ pub trait ExampleCode {
                 edge.p
             };
             if rng.next_f64() < p {
+                error_edges.push(edge_index as EdgeIndex);
                 let (v1, v2) = edge.vertices;
                 let vertex_1 = &mut vertices[v1 as usize];
                 if !vertex_1.is_virtual {
@@ -325,7 +458,46 @@ pub trait ExampleCode {
                 }
             }
         }
-        self.get_syndrome()
+        Some((self.get_syndrome(), error_edges))
+    }
+
+    /// virtual vertices on one side of the code: the errors together with the correction form a logical error if and
+    /// only if they connect this side to the other side an odd number of times; by default it's the virtual vertices
+    /// on the left half (smaller `j`) of all the virtual vertices, which matches the left and right boundaries of the
+    /// example codes
+    #[allow(clippy::unnecessary_cast)]
+    fn logical_boundary_vertices(&self) -> Vec<VertexIndex> {
+        let (vertices, _edges) = self.immutable_vertices_edges();
+        let virtual_j = vertices
+            .iter()
+            .filter(|vertex| vertex.is_virtual)
+            .map(|vertex| vertex.position.j);
+        let min_j = virtual_j.clone().fold(f64::INFINITY, f64::min);
+        let max_j = virtual_j.fold(f64::NEG_INFINITY, f64::max);
+        let center_j = (min_j + max_j) / 2.;
+        (0..vertices.len())
+            .filter(|&vertex_index| vertices[vertex_index].is_virtual && vertices[vertex_index].position.j < center_j)
+            .map(|vertex_index| vertex_index as VertexIndex)
+            .collect()
+    }
+
+    /// whether the correction leaves a logical error given the actual error edges, see
+    /// [`ExampleCode::logical_boundary_vertices`]; every edge touching the boundary flips the parity once
+    #[allow(clippy::unnecessary_cast)]
+    fn is_logical_error(&self, error_edges: &[EdgeIndex], correction_edges: &[EdgeIndex]) -> bool {
+        let (vertices, edges) = self.immutable_vertices_edges();
+        let mut is_boundary = vec![false; vertices.len()];
+        for vertex_index in self.logical_boundary_vertices() {
+            is_boundary[vertex_index as usize] = true;
+        }
+        let mut parity = false;
+        for edge_index in error_edges.iter().chain(correction_edges.iter()) {
+            let (v1, v2) = edges[*edge_index as usize].vertices;
+            if is_boundary[v1 as usize] != is_boundary[v2 as usize] {
+                parity = !parity;
+            }
+        }
+        parity
     }
 
     #[allow(clippy::unnecessary_cast)]
@@ -515,8 +687,8 @@ where
 }
 
 /// perfect quantum repetition code
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CodeCapacityRepetitionCode {
     /// vertices in the code
@@ -539,7 +711,7 @@ impl ExampleCode for CodeCapacityRepetitionCode {
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityRepetitionCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityRepetitionCode {
     #[cfg_attr(feature = "python_binding", new)]
@@ -582,10 +754,224 @@ impl CodeCapacityRepetitionCode {
     }
 }
 
+/// quantum repetition code with phenomenological noise model: `noisy_measurements` rounds of noisy stabilizer measurement
+/// capped by a perfect one, where each round is a [`CodeCapacityRepetitionCode`] and a measurement error connects the
+/// same stabilizer in two consecutive rounds
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct PhenomenologicalRepetitionCode {
+    /// vertices in the code
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edges: Vec<CodeEdge>,
+}
+
+impl ExampleCode for PhenomenologicalRepetitionCode {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        Some(edge_rounds_by_layer(&self.vertices, &self.edges))
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_example_code! {PhenomenologicalRepetitionCode}
+
+/// a small code for quick experiments: d = 3 with 3 noisy measurement rounds at p = 0.01
+impl Default for PhenomenologicalRepetitionCode {
+    fn default() -> Self {
+        Self::new(3, 3, 0.01, 500)
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl PhenomenologicalRepetitionCode {
+    #[cfg_attr(feature = "python_binding", new)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
+    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        Self::from_probabilities(d, noisy_measurements + 1, p, p, max_half_weight)
+    }
+
+    /// `rounds` measurement rounds in total (the last one is perfect), with data errors of probability `p_data` on the
+    /// space-like edges and measurement errors of probability `p_meas` on the time-like edges
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, rounds, p_data, p_meas, max_half_weight = 500)))]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_probabilities(d: VertexNum, rounds: VertexNum, p_data: f64, p_meas: f64, max_half_weight: Weight) -> Self {
+        assert!(rounds >= 1, "at least the perfect measurement round is required");
+        let mut code = Self::create_code(d, rounds - 1);
+        let (vertices, edges) = code.vertices_edges();
+        for edge in edges.iter_mut() {
+            let (v1, v2) = edge.vertices;
+            let is_time_like = vertices[v1 as usize].position.t != vertices[v2 as usize].position.t;
+            edge.p = if is_time_like { p_meas } else { p_data };
+        }
+        code.compute_weights(max_half_weight);
+        code
+    }
+
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
+        let mut code = Self {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+        };
+        let vertex_num = create_repetition_code_rounds(&mut code, d, noisy_measurements, false);
+        debug_assert_eq!(code.vertices.len(), vertex_num as usize);
+        code
+    }
+}
+
+/// (not accurate) quantum repetition code with circuit-level noise model: a [`PhenomenologicalRepetitionCode`] with
+/// additional diagonal edges between neighboring stabilizers in consecutive rounds, from the errors between the two
+/// CNOT gates of a stabilizer measurement
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct CircuitLevelRepetitionCode {
+    /// vertices in the code
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edges: Vec<CodeEdge>,
+}
+
+impl ExampleCode for CircuitLevelRepetitionCode {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        Some(edge_rounds_by_layer(&self.vertices, &self.edges))
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_example_code! {CircuitLevelRepetitionCode}
+
+/// a small code for quick experiments: d = 3 with 3 noisy measurement rounds at p = 0.01
+impl Default for CircuitLevelRepetitionCode {
+    fn default() -> Self {
+        Self::new(3, 3, 0.01, 500)
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl CircuitLevelRepetitionCode {
+    /// by default diagonal edge has error rate p/3 to mimic the behavior of unequal weights
+    #[cfg_attr(feature = "python_binding", new)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
+    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        Self::from_probabilities(d, noisy_measurements + 1, p, p, p / 3., max_half_weight)
+    }
+
+    /// `rounds` measurement rounds in total (the last one is perfect), with data errors of probability `p_data` on the
+    /// space-like edges, measurement errors of probability `p_meas` on the time-like edges and `p_diagonal` on the
+    /// diagonal edges
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, rounds, p_data, p_meas, p_diagonal, max_half_weight = 500)))]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_probabilities(
+        d: VertexNum,
+        rounds: VertexNum,
+        p_data: f64,
+        p_meas: f64,
+        p_diagonal: f64,
+        max_half_weight: Weight,
+    ) -> Self {
+        assert!(rounds >= 1, "at least the perfect measurement round is required");
+        let mut code = Self::create_code(d, rounds - 1);
+        let (vertices, edges) = code.vertices_edges();
+        for edge in edges.iter_mut() {
+            let (v1p, v2p) = (
+                &vertices[edge.vertices.0 as usize].position,
+                &vertices[edge.vertices.1 as usize].position,
+            );
+            edge.p = match (v1p.t != v2p.t, v1p.j != v2p.j) {
+                (true, true) => p_diagonal,
+                (true, false) => p_meas,
+                _ => p_data,
+            };
+        }
+        code.compute_weights(max_half_weight);
+        code
+    }
+
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
+        let mut code = Self {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+        };
+        let vertex_num = create_repetition_code_rounds(&mut code, d, noisy_measurements, true);
+        debug_assert_eq!(code.vertices.len(), vertex_num as usize);
+        code
+    }
+}
+
+/// build the decoding graph of `noisy_measurements + 1` rounds of a repetition code, each laid out like
+/// [`CodeCapacityRepetitionCode`]: the `d - 1` stabilizers followed by the right and the left virtual vertices; if
+/// `diagonal`, each stabilizer also connects to its right neighbor in the next round. Returns the number of vertices
+#[allow(clippy::unnecessary_cast)]
+fn create_repetition_code_rounds(
+    code: &mut impl ExampleCode,
+    d: VertexNum,
+    noisy_measurements: VertexNum,
+    diagonal: bool,
+) -> VertexNum {
+    assert!(d >= 3 && d % 2 == 1, "d must be odd integer >= 3");
+    let t_vertex_num = (d - 1) + 2; // two virtual vertices at left and right
+    let td = noisy_measurements + 1; // a perfect measurement round is capped at the end
+    let vertex_num = t_vertex_num * td;
+    let (_, edges) = code.vertices_edges();
+    edges.clear();
+    for t in 0..td {
+        let bias = t * t_vertex_num;
+        for i in 0..d - 1 {
+            edges.push(CodeEdge::new(bias + i, bias + i + 1));
+        }
+        edges.push(CodeEdge::new(bias, bias + d)); // the left-most edge
+        if t + 1 < td {
+            for i in 0..d - 1 {
+                edges.push(CodeEdge::new(bias + i, bias + i + t_vertex_num));
+                if diagonal && i + 1 < d - 1 {
+                    edges.push(CodeEdge::new(bias + i, bias + i + 1 + t_vertex_num));
+                }
+            }
+        }
+    }
+    code.fill_vertices(vertex_num);
+    let (vertices, _) = code.vertices_edges();
+    for t in 0..td {
+        let bias = t * t_vertex_num;
+        vertices[(bias + d - 1) as usize].is_virtual = true;
+        vertices[(bias + d) as usize].is_virtual = true;
+        for i in 0..d {
+            vertices[(bias + i) as usize].position = VisualizePosition::new(0., i as f64, t as f64);
+        }
+        vertices[(bias + d) as usize].position = VisualizePosition::new(0., -1., t as f64);
+    }
+    vertex_num
+}
+
 /// code capacity noise model is a single measurement round with perfect stabilizer measurements;
 /// e.g. this is the decoding graph of a CSS surface code (standard one, not rotated one) with X-type stabilizers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CodeCapacityPlanarCode {
     /// vertices in the code
@@ -608,7 +994,7 @@ impl ExampleCode for CodeCapacityPlanarCode {
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityPlanarCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityPlanarCode {
     #[cfg_attr(feature = "python_binding", new)]
@@ -668,8 +1054,8 @@ impl CodeCapacityPlanarCode {
 
 /// phenomenological noise model is multiple measurement rounds adding only measurement errors
 /// e.g. this is the decoding graph of a CSS surface code (standard one, not rotated one) with X-type stabilizers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PhenomenologicalPlanarCode {
     /// vertices in the code
@@ -687,12 +1073,15 @@ impl ExampleCode for PhenomenologicalPlanarCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        Some(edge_rounds_by_layer(&self.vertices, &self.edges))
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {PhenomenologicalPlanarCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PhenomenologicalPlanarCode {
     #[cfg_attr(feature = "python_binding", new)]
@@ -713,7 +1102,10 @@ impl PhenomenologicalPlanarCode {
         let td = noisy_measurements + 1; // a perfect measurement round is capped at the end
         let vertex_num = t_vertex_num * td; // `td` layers
                                             // create edges
-        let mut edges = Vec::new();
+        let layer_edge_num = d * (d - 1) + d + (d - 1) * (d - 1);
+        let inter_layer_edge_num = d * (d - 1);
+        let edge_num = layer_edge_num * td + inter_layer_edge_num * (td - 1);
+        let mut edges = Vec::with_capacity(edge_num as usize);
         for t in 0..td {
             let t_bias = t * t_vertex_num;
             for row in 0..d {
@@ -738,6 +1130,7 @@ impl PhenomenologicalPlanarCode {
                 }
             }
         }
+        debug_assert_eq!(edges.len(), edge_num as usize);
         let mut code = Self {
             vertices: Vec::new(),
             edges,
@@ -752,7 +1145,7 @@ impl PhenomenologicalPlanarCode {
                 code.vertices[(bias + d) as usize].is_virtual = true;
             }
         }
-        let mut positions = Vec::new();
+        let mut positions = Vec::with_capacity(vertex_num as usize);
         for t in 0..td {
             let pos_t = t as f64;
             for row in 0..d {
@@ -772,8 +1165,8 @@ impl PhenomenologicalPlanarCode {
 
 /// (not accurate) circuit-level noise model is multiple measurement rounds with errors between each two-qubit gates
 /// e.g. this is the decoding graph of a CSS surface code (standard one, not rotated one) with X-type stabilizers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CircuitLevelPlanarCode {
     /// vertices in the code
@@ -791,12 +1184,15 @@ impl ExampleCode for CircuitLevelPlanarCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        Some(edge_rounds_by_layer(&self.vertices, &self.edges))
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CircuitLevelPlanarCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CircuitLevelPlanarCode {
     /// by default diagonal edge has error rate p/3 to mimic the behavior of unequal weights
@@ -843,7 +1239,11 @@ impl CircuitLevelPlanarCode {
         let td = noisy_measurements + 1; // a perfect measurement round is capped at the end
         let vertex_num = t_vertex_num * td; // `td` layers
                                             // create edges
-        let mut edges = Vec::new();
+        let layer_edge_num = d * (d - 1) + d + (d - 1) * (d - 1);
+        // a vertical edge and up to 3 diagonal edges from each real vertex
+        let inter_layer_edge_num = d * (d - 1) + d * (d - 2) + (d - 1) * (d - 1) + (d - 1) * (d - 2);
+        let edge_num = layer_edge_num * td + inter_layer_edge_num * (td - 1);
+        let mut edges = Vec::with_capacity(edge_num as usize);
         for t in 0..td {
             let t_bias = t * t_vertex_num;
             for row in 0..d {
@@ -864,8 +1264,7 @@ impl CircuitLevelPlanarCode {
                     let bias = t_bias + row * row_vertex_num;
                     for i in 0..d - 1 {
                         edges.push(CodeEdge::new(bias + i, bias + i + t_vertex_num));
-                        let diagonal_diffs: Vec<(isize, isize)> = vec![(0, 1), (1, 0), (1, 1)];
-                        for (di, dj) in diagonal_diffs {
+                        for (di, dj) in [(0isize, 1isize), (1, 0), (1, 1)] {
                             let new_row = row as isize + di; // row corresponds to `i`
                             let new_i = i as isize + dj; // i corresponds to `j`
                             if new_row >= 0 && new_i >= 0 && new_row < d as isize && new_i < (d - 1) as isize {
@@ -877,6 +1276,7 @@ impl CircuitLevelPlanarCode {
                 }
             }
         }
+        debug_assert_eq!(edges.len(), edge_num as usize);
         let mut code = Self {
             vertices: Vec::new(),
             edges,
@@ -891,7 +1291,7 @@ impl CircuitLevelPlanarCode {
                 code.vertices[(bias + d) as usize].is_virtual = true;
             }
         }
-        let mut positions = Vec::new();
+        let mut positions = Vec::with_capacity(vertex_num as usize);
         for t in 0..td {
             let pos_t = t as f64;
             for row in 0..d {
@@ -910,8 +1310,8 @@ impl CircuitLevelPlanarCode {
 }
 
 /// CSS surface code (the rotated one) with X-type stabilizers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct CodeCapacityRotatedCode {
     /// vertices in the code
@@ -934,7 +1334,7 @@ impl ExampleCode for CodeCapacityRotatedCode {
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityRotatedCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityRotatedCode {
     #[cfg_attr(feature = "python_binding", new)]
@@ -961,7 +1361,7 @@ impl CodeCapacityRotatedCode {
                     if i % 2 == 0 {
                         edges.push(CodeEdge::new(bias + i / 2, bias + row_vertex_num + i / 2));
                     } else {
-                        edges.push(CodeEdge::new(bias + (i - 1) / 2, bias + row_vertex_num + (i + 1) / 2));
+                        edges.push(CodeEdge::new(bias + (i - 1) / 2, bias + row_vertex_num + i.div_ceil(2)));
                     }
                 }
             } else {
@@ -969,7 +1369,7 @@ impl CodeCapacityRotatedCode {
                     if i % 2 == 0 {
                         edges.push(CodeEdge::new(bias + i / 2, bias + row_vertex_num + i / 2));
                     } else {
-                        edges.push(CodeEdge::new(bias + (i + 1) / 2, bias + row_vertex_num + (i - 1) / 2));
+                        edges.push(CodeEdge::new(bias + i.div_ceil(2), bias + row_vertex_num + (i - 1) / 2));
                     }
                 }
             }
@@ -1004,8 +1404,8 @@ impl CodeCapacityRotatedCode {
 }
 
 /// CSS surface code (the rotated one) with X-type stabilizers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PhenomenologicalRotatedCode {
     /// vertices in the code
@@ -1023,12 +1423,15 @@ impl ExampleCode for PhenomenologicalRotatedCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        Some(edge_rounds_by_layer(&self.vertices, &self.edges))
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {PhenomenologicalRotatedCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PhenomenologicalRotatedCode {
     #[cfg_attr(feature = "python_binding", new)]
@@ -1059,7 +1462,7 @@ impl PhenomenologicalRotatedCode {
                         if i % 2 == 0 {
                             edges.push(CodeEdge::new(bias + i / 2, bias + row_vertex_num + i / 2));
                         } else {
-                            edges.push(CodeEdge::new(bias + (i - 1) / 2, bias + row_vertex_num + (i + 1) / 2));
+                            edges.push(CodeEdge::new(bias + (i - 1) / 2, bias + row_vertex_num + i.div_ceil(2)));
                         }
                     }
                 } else {
@@ -1067,7 +1470,7 @@ impl PhenomenologicalRotatedCode {
                         if i % 2 == 0 {
                             edges.push(CodeEdge::new(bias + i / 2, bias + row_vertex_num + i / 2));
                         } else {
-                            edges.push(CodeEdge::new(bias + (i + 1) / 2, bias + row_vertex_num + (i - 1) / 2));
+                            edges.push(CodeEdge::new(bias + i.div_ceil(2), bias + row_vertex_num + (i - 1) / 2));
                         }
                     }
                 }
@@ -1118,8 +1521,8 @@ impl PhenomenologicalRotatedCode {
 }
 
 /// example code with QEC-Playground as simulator
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg(feature = "qecp_integrate")]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct QECPlaygroundCode {
     simulator: qecp::simulator::Simulator,
@@ -1143,6 +1546,9 @@ impl ExampleCode for QECPlaygroundCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn generate_random_errors_with_edges(&mut self, _seed: u64) -> Option<(SyndromePattern, Vec<EdgeIndex>)> {
+        None // the errors are simulated by QEC-Playground on the circuit, not on the edges of the decoding graph
+    }
     // override simulation function
     #[allow(clippy::unnecessary_cast)]
     fn generate_random_errors(&mut self, seed: u64) -> SyndromePattern {
@@ -1351,8 +1757,8 @@ impl QECPlaygroundCode {
 /// the point is to avoid bad cache performance, because generating random error requires iterating over a large memory space,
 /// invalidating all cache. also, this can reduce the time of decoding by prepare the data before hand and could be shared between
 /// different partition configurations
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct ErrorPatternReader {
     /// vertices in the code
@@ -1369,6 +1775,9 @@ pub struct ErrorPatternReader {
     pub defect_index: usize,
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     pub cyclic_syndrome: bool,
+    /// the measurement rounds of the edges recorded in the file, if any
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edge_rounds: Option<Vec<Option<usize>>>,
 }
 
 impl ExampleCode for ErrorPatternReader {
@@ -1378,6 +1787,12 @@ impl ExampleCode for ErrorPatternReader {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn get_edge_rounds(&self) -> Option<Vec<Option<usize>>> {
+        self.edge_rounds.clone()
+    }
+    fn generate_random_errors_with_edges(&mut self, _seed: u64) -> Option<(SyndromePattern, Vec<EdgeIndex>)> {
+        None // the file only records the syndrome
+    }
     fn generate_random_errors(&mut self, _seed: u64) -> SyndromePattern {
         if self.cyclic_syndrome {
             if self.defect_index >= self.syndrome_patterns.len() {
@@ -1395,7 +1810,7 @@ impl ExampleCode for ErrorPatternReader {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl ErrorPatternReader {
     #[allow(clippy::unnecessary_cast)]
@@ -1432,17 +1847,24 @@ impl ErrorPatternReader {
         let mut syndrome_patterns = vec![];
         let mut initializer: Option<SolverInitializer> = None;
         let mut positions: Option<Vec<VisualizePosition>> = None;
+        let mut structural_hash: Option<u64> = None;
         for (line_index, line) in io::BufReader::new(file).lines().enumerate() {
             if let Ok(value) = line {
                 match line_index {
                     0 => {
                         assert!(value.starts_with("Syndrome Pattern v1.0 "), "incompatible file version");
+                        // files written before the structural hash was introduced don't have it
+                        if let Some(index) = value.find("structural_hash=") {
+                            let hex = value[index + "structural_hash=".len()..].trim();
+                            structural_hash = Some(u64::from_str_radix(hex, 16).expect("invalid structural hash"));
+                        }
                     }
                     1 => {
                         initializer = Some(serde_json::from_str(&value).unwrap());
                     }
-                    2 => {
-                        positions = Some(serde_json::from_str(&value).unwrap());
+                    2 if !value.trim_start().starts_with('{') => {
+                        // positions are optional: `null` or a file that directly continues with syndrome patterns
+                        positions = serde_json::from_str(&value).unwrap();
                     }
                     _ => {
                         let syndrome_pattern: SyndromePattern = serde_json::from_str(&value).unwrap();
@@ -1452,7 +1874,15 @@ impl ErrorPatternReader {
             }
         }
         let initializer = initializer.expect("initializer not present in file");
-        let positions = positions.expect("positions not present in file");
+        if let Some(structural_hash) = structural_hash {
+            let actual_hash = initializer.structural_hash();
+            assert_eq!(
+                structural_hash, actual_hash,
+                "structural hash mismatch: the file header records {structural_hash:016x} but the embedded initializer \
+                hashes to {actual_hash:016x}, the file is corrupted or was edited after generation"
+            );
+        }
+        let positions = positions.unwrap_or_else(|| auto_layout_positions(&initializer, 0));
         assert_eq!(positions.len(), initializer.vertex_num as usize);
         let mut code = Self {
             vertices: Vec::with_capacity(initializer.vertex_num as usize),
@@ -1460,6 +1890,7 @@ impl ErrorPatternReader {
             syndrome_patterns,
             defect_index: 0,
             cyclic_syndrome,
+            edge_rounds: initializer.edge_rounds.clone(),
         };
         for (left_vertex, right_vertex, weight) in initializer.weighted_edges.iter() {
             assert!(weight % 2 == 0, "weight must be even number");
@@ -1482,13 +1913,150 @@ impl ErrorPatternReader {
         }
         code
     }
+
+    /// check that the syndromes of this file were recorded on the given decoding graph, e.g. the one of the code a
+    /// benchmark decodes them with, by comparing [`SolverInitializer::structural_hash`]
+    pub fn check_structural_hash(&self, initializer: &SolverInitializer) -> Result<(), String> {
+        let (recorded_hash, expected_hash) = (self.get_initializer().structural_hash(), initializer.structural_hash());
+        if recorded_hash != expected_hash {
+            return Err(format!(
+                "structural hash mismatch: the syndromes were recorded on a decoding graph hashing to {recorded_hash:016x} \
+                but the decoding graph hashes to {expected_hash:016x}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// generate new shots from the error model of a recorded decoding graph, e.g. for data augmentation of a problem file
+/// recorded from a real device: each edge flips independently with its own probability, and optionally it's also
+/// erased (heralded) with a second probability, in which case it flips with probability 0.5 instead
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct ResampledCode {
+    /// vertices in the code
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edges: Vec<CodeEdge>,
+}
+
+impl ExampleCode for ResampledCode {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_example_code! {ResampledCode}
+
+impl ResampledCode {
+    /// the decoding graph and positions are read from an error pattern file; the config takes
+    /// - `filename`: the error pattern file, see [`ErrorPatternReader`]
+    /// - `probabilities`: the error probability of each edge; if absent, they are recovered from the weights by
+    ///   [`p_of_normalized_weights`], which then requires `max_weight_p`: the probability of the largest weight, e.g.
+    ///   the `p` of the example code that recorded the file
+    /// - `erasure_probabilities`: the erasure probability of each edge, no erasure by default
+    /// - `pe_scale`: scale all the erasure probabilities, 1 by default
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(mut config: serde_json::Value) -> Self {
+        let config = config.as_object_mut().expect("config must be JSON object");
+        let filename = config
+            .remove("filename")
+            .expect("filename is required")
+            .as_str()
+            .expect("filename string")
+            .to_string();
+        let probabilities: Option<Vec<f64>> = config
+            .remove("probabilities")
+            .map(|value| serde_json::from_value(value).expect("probabilities: list of numbers"));
+        let max_weight_p: Option<f64> = config
+            .remove("max_weight_p")
+            .map(|value| value.as_f64().expect("max_weight_p: number"));
+        let erasure_probabilities: Vec<f64> = config
+            .remove("erasure_probabilities")
+            .map(|value| serde_json::from_value(value).expect("erasure_probabilities: list of numbers"))
+            .unwrap_or_default();
+        let pe_scale = config
+            .remove("pe_scale")
+            .map(|value| value.as_f64().expect("pe_scale: number"))
+            .unwrap_or(1.);
+        if !config.is_empty() {
+            panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+        }
+        let reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        let initializer = reader.get_initializer();
+        let probabilities = probabilities.unwrap_or_else(|| {
+            let max_weight_p =
+                max_weight_p.expect("either probabilities or max_weight_p is required to recover the probabilities");
+            let weights: Vec<Weight> = initializer.weighted_edges.iter().map(|edge| edge.2).collect();
+            p_of_normalized_weights(&weights, max_weight_p)
+        });
+        let erasure_probabilities: Vec<f64> = erasure_probabilities.iter().map(|pe| (pe * pe_scale).min(1.)).collect();
+        let positions = reader.vertices.iter().map(|vertex| vertex.position.clone()).collect();
+        Self::from_initializer(&initializer, positions, &probabilities, &erasure_probabilities)
+    }
+
+    /// `probabilities` and (if not empty) `erasure_probabilities` give the probabilities of each edge in
+    /// `initializer.weighted_edges`; the weights of the code are the same as the initializer
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_initializer(
+        initializer: &SolverInitializer,
+        positions: Vec<VisualizePosition>,
+        probabilities: &[f64],
+        erasure_probabilities: &[f64],
+    ) -> Self {
+        let edge_num = initializer.weighted_edges.len();
+        assert_eq!(probabilities.len(), edge_num, "one probability per edge");
+        assert!(
+            erasure_probabilities.is_empty() || erasure_probabilities.len() == edge_num,
+            "one erasure probability per edge"
+        );
+        assert_eq!(positions.len(), initializer.vertex_num as usize);
+        let mut code = Self {
+            vertices: Vec::with_capacity(initializer.vertex_num as usize),
+            edges: Vec::with_capacity(edge_num),
+        };
+        for (edge_index, &(left_vertex, right_vertex, weight)) in initializer.weighted_edges.iter().enumerate() {
+            assert!(weight % 2 == 0, "weight must be even number");
+            let p = probabilities[edge_index];
+            let pe = erasure_probabilities.get(edge_index).copied().unwrap_or(0.);
+            assert!((0. ..=0.5).contains(&p), "edge {edge_index} has invalid probability {p}");
+            assert!(
+                (0. ..=1.).contains(&pe),
+                "edge {edge_index} has invalid erasure probability {pe}"
+            );
+            code.edges.push(CodeEdge {
+                vertices: (left_vertex, right_vertex),
+                p,
+                pe,
+                half_weight: weight / 2,
+                is_erasure: false,
+            });
+        }
+        code.fill_vertices(initializer.vertex_num);
+        for (vertex_index, position) in positions.into_iter().enumerate() {
+            code.vertices[vertex_index].position = position;
+        }
+        for &vertex_index in initializer.virtual_vertices.iter() {
+            code.vertices[vertex_index as usize].is_virtual = true;
+        }
+        code
+    }
 }
 
 /// generate error patterns in parallel by hold multiple instances of the same code type
+#[cfg(feature = "parallel")]
 pub struct ExampleCodeParallel<CodeType: ExampleCode + Sync + Send + Clone> {
     /// used to provide graph
     pub example: CodeType,
-    /// list of codes
+    /// list of codes; the syndrome patterns of `codes.len()` consecutive rounds are generated together from the seed
+    /// of the first round, where code `i` uses an independent generator seeded by [`child_seed`]`(seed, i)`
     pub codes: Vec<ArcRwLock<CodeType>>,
     /// syndrome patterns generated by individual code
     pub syndrome_patterns: Vec<SyndromePattern>,
@@ -1496,6 +2064,7 @@ pub struct ExampleCodeParallel<CodeType: ExampleCode + Sync + Send + Clone> {
     pub code_index: usize,
 }
 
+#[cfg(feature = "parallel")]
 impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCodeParallel<CodeType> {
     pub fn new(example: CodeType, code_count: usize) -> Self {
         let mut codes = vec![];
@@ -1511,6 +2080,7 @@ impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCodeParallel<CodeType>
     }
 }
 
+#[cfg(feature = "parallel")]
 impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCode for ExampleCodeParallel<CodeType> {
     fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
         self.example.vertices_edges()
@@ -1518,6 +2088,9 @@ impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCode for ExampleCodePar
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         self.example.immutable_vertices_edges()
     }
+    fn generate_random_errors_with_edges(&mut self, _seed: u64) -> Option<(SyndromePattern, Vec<EdgeIndex>)> {
+        None // the syndrome patterns are generated in batch
+    }
     fn generate_random_errors(&mut self, seed: u64) -> SyndromePattern {
         if self.code_index == 0 {
             // run generator in parallel
@@ -1526,7 +2099,7 @@ impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCode for ExampleCodePar
                 .map(|code_index| {
                     self.codes[code_index]
                         .write()
-                        .generate_random_errors(seed + (code_index * 1_000_000_000) as u64)
+                        .generate_random_errors(child_seed(seed, code_index))
                 })
                 .collect_into_vec(&mut self.syndrome_patterns);
         }
@@ -1541,14 +2114,16 @@ impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCode for ExampleCodePar
 pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<CodeVertex>()?;
     m.add_class::<CodeEdge>()?;
-    m.add_function(wrap_pyfunction!(weight_of_p, m)?)?;
     m.add_class::<CodeCapacityRepetitionCode>()?;
+    m.add_class::<PhenomenologicalRepetitionCode>()?;
+    m.add_class::<CircuitLevelRepetitionCode>()?;
     m.add_class::<CodeCapacityPlanarCode>()?;
     m.add_class::<PhenomenologicalPlanarCode>()?;
     m.add_class::<CircuitLevelPlanarCode>()?;
     m.add_class::<CodeCapacityRotatedCode>()?;
     m.add_class::<PhenomenologicalRotatedCode>()?;
     m.add_class::<ErrorPatternReader>()?;
+    m.add_class::<ResampledCode>()?;
     Ok(())
 }
 
@@ -1571,6 +2146,101 @@ pub fn visualize_code(code: &mut impl ExampleCode, visualize_filename: String) {
 mod tests {
     use super::*;
 
+    /// the units of a parallel example code generate independent errors, reproducible from the round seeds
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn example_code_parallel_independent_units_1() {
+        // cargo test example_code_parallel_independent_units_1 -- --nocapture
+        let code_count = 4;
+        let batch_count = 1000;
+        let example = CodeCapacityPlanarCode::new(7, 0.05, 500);
+        let mut code = ExampleCodeParallel::new(example.clone(), code_count);
+        let mut another_code = ExampleCodeParallel::new(example.clone(), code_count);
+        let mut defect_counts = vec![vec![]; code_count];
+        for batch in 0..batch_count {
+            for (code_index, unit_defect_counts) in defect_counts.iter_mut().enumerate() {
+                let seed = (batch * code_count + code_index) as u64; // as `--use-deterministic-seed` does
+                let syndrome_pattern = code.generate_random_errors(seed);
+                let another_syndrome_pattern = another_code.generate_random_errors(seed);
+                assert_eq!(syndrome_pattern.defect_vertices, another_syndrome_pattern.defect_vertices);
+                // each unit is an ordinary code seeded by a child of the first seed in the batch
+                let batch_seed = (batch * code_count) as u64;
+                let expected = example.clone().generate_random_errors(child_seed(batch_seed, code_index));
+                assert_eq!(syndrome_pattern.defect_vertices, expected.defect_vertices);
+                unit_defect_counts.push(syndrome_pattern.defect_vertices.len());
+            }
+        }
+        // chi-squared test of independence between every two units, on whether the defect count exceeds the median
+        let mut all_defect_counts = defect_counts.concat();
+        all_defect_counts.sort();
+        let median = all_defect_counts[all_defect_counts.len() / 2];
+        for unit_1 in 0..code_count {
+            for unit_2 in unit_1 + 1..code_count {
+                let mut table = [[0.; 2]; 2];
+                for (&defect_count_1, &defect_count_2) in defect_counts[unit_1].iter().zip(defect_counts[unit_2].iter()) {
+                    table[(defect_count_1 > median) as usize][(defect_count_2 > median) as usize] += 1.;
+                }
+                let total = batch_count as f64;
+                let mut chi_squared = 0.;
+                for row in 0..2 {
+                    for column in 0..2 {
+                        let row_sum: f64 = table[row].iter().sum();
+                        let column_sum = table[0][column] + table[1][column];
+                        let expected = row_sum * column_sum / total;
+                        chi_squared += (table[row][column] - expected).powi(2) / expected;
+                    }
+                }
+                println!("units {unit_1} and {unit_2}: chi-squared = {chi_squared:.3}");
+                // 1 degree of freedom at the significance level of 0.001
+                assert!(chi_squared < 10.828, "units {unit_1} and {unit_2} are correlated: {table:?}");
+            }
+        }
+    }
+
+    /// the cached initializer of a code is shared until its weights change, and a fingerprint collision is detected
+    #[test]
+    fn example_code_initializer_cache_1() {
+        // cargo test example_code_initializer_cache_1 -- --nocapture
+        let mut code = CircuitLevelPlanarCode::new(5, 5, 0.001, 500);
+        let initializer = code.get_initializer();
+        code.sanity_check().unwrap();
+        assert_eq!(initializer.weighted_edges.len(), 561);
+        // the cached initializer is reused as long as the decoding graph doesn't change
+        let cached = code.get_initializer_cached();
+        assert_eq!(cached.weighted_edges, initializer.weighted_edges);
+        assert_eq!(cached.edge_rounds, initializer.edge_rounds);
+        code.generate_random_errors(0);
+        assert!(Arc::ptr_eq(&cached, &code.get_initializer_cached()));
+        code.set_probability(0.01);
+        code.edges[0].p = 0.1;
+        code.compute_weights(500);
+        let recomputed = code.get_initializer_cached();
+        assert!(!Arc::ptr_eq(&cached, &recomputed));
+        assert_eq!(recomputed.weighted_edges, code.get_initializer().weighted_edges);
+        // a fingerprint collision is detected rather than served from the cache
+        let (vertices, edges) = code.immutable_vertices_edges();
+        let fingerprint = initializer_fingerprint(std::any::type_name::<CircuitLevelPlanarCode>(), vertices, edges);
+        let colliding = Arc::new(SolverInitializer::new(2, vec![(0, 1, 2)], vec![]));
+        INITIALIZER_CACHE.lock().unwrap().insert(
+            0,
+            (
+                fingerprint,
+                std::any::type_name::<CircuitLevelPlanarCode>(),
+                colliding.clone(),
+            ),
+        );
+        let checked = code.get_initializer_cached();
+        assert!(!Arc::ptr_eq(&colliding, &checked));
+        assert_eq!(checked.weighted_edges, recomputed.weighted_edges);
+        // duplicate edges are still reported
+        let (v1, v2) = code.edges[3].vertices;
+        code.edges[5].vertices = (v2, v1);
+        assert_eq!(
+            code.sanity_check(),
+            Err(format!("duplicate edge 3 and 5 with incident vertices {v2} and {v1}"))
+        );
+    }
+
     #[test]
     fn example_code_capacity_repetition_code() {
         // cargo test example_code_capacity_repetition_code -- --nocapture
@@ -1611,6 +2281,45 @@ mod tests {
         visualize_code(&mut code, "example_circuit_level_planar_code.json".to_string());
     }
 
+    #[test]
+    fn example_phenomenological_repetition_code() {
+        // cargo test example_phenomenological_repetition_code -- --nocapture
+        let mut code = PhenomenologicalRepetitionCode::new(7, 7, 0.01, 500);
+        code.sanity_check().unwrap();
+        let initializer = code.get_initializer();
+        assert_eq!(initializer.vertex_num, 8 * 8);
+        assert_eq!(initializer.virtual_vertices.len(), 2 * 8);
+        assert_eq!(initializer.weighted_edges.len(), 7 * 8 + 6 * 7);
+        visualize_code(&mut code, "example_phenomenological_repetition_code.json".to_string());
+        // more likely measurement errors make the time-like edges cheaper
+        let code = PhenomenologicalRepetitionCode::from_probabilities(5, 3, 0.01, 0.1, 500);
+        let initializer = code.get_initializer();
+        let weight_between = |vertex_1, vertex_2| {
+            initializer
+                .weighted_edges
+                .iter()
+                .find(|(left, right, _)| (*left, *right) == (vertex_1, vertex_2))
+                .unwrap()
+                .2
+        };
+        assert!(weight_between(0, 6) < weight_between(0, 1));
+        assert_eq!(weight_between(0, 1), weight_between(6, 7));
+        // `rounds` counts the perfect measurement round as well
+        let same_graph = PhenomenologicalRepetitionCode::new(5, 2, 0.01, 500);
+        assert_eq!(same_graph.get_initializer().vertex_num, initializer.vertex_num);
+    }
+
+    #[test]
+    fn example_circuit_level_repetition_code() {
+        // cargo test example_circuit_level_repetition_code -- --nocapture
+        let mut code = CircuitLevelRepetitionCode::new(7, 7, 0.01, 500);
+        code.sanity_check().unwrap();
+        let initializer = code.get_initializer();
+        assert_eq!(initializer.vertex_num, 8 * 8);
+        assert_eq!(initializer.weighted_edges.len(), 7 * 8 + (6 + 5) * 7);
+        visualize_code(&mut code, "example_circuit_level_repetition_code.json".to_string());
+    }
+
     #[test]
     fn example_code_capacity_rotated_code() {
         // cargo test example_code_capacity_rotated_code -- --nocapture
@@ -1627,6 +2336,147 @@ mod tests {
         visualize_code(&mut code, "example_code_phenomenological_rotated_code.json".to_string());
     }
 
+    /// the error pattern file records the structural hash of its initializer and rejects a tampered graph
+    #[test]
+    fn example_error_pattern_structural_hash() {
+        // cargo test example_error_pattern_structural_hash -- --nocapture
+        use crate::mwpm_solver::*;
+        std::fs::create_dir_all("tmp").unwrap();
+        let filename = "tmp/example_error_pattern_structural_hash.txt".to_string();
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        {
+            let mut logger =
+                SolverErrorPatternLogger::new(&initializer, &code.get_positions(), json!({ "filename": filename }));
+            logger.solve(&code.generate_random_errors(0));
+        }
+        let content = std::fs::read_to_string(&filename).unwrap();
+        assert!(content
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with(&format!("structural_hash={:016x}", initializer.structural_hash())));
+        let reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        assert_eq!(reader.get_initializer().structural_hash(), initializer.structural_hash());
+        // the syndromes only fit the decoding graph they were recorded on
+        reader.check_structural_hash(&initializer).unwrap();
+        let other_initializer = CodeCapacityPlanarCode::new(7, 0.1, 500).get_initializer();
+        assert!(reader.check_structural_hash(&other_initializer).is_err());
+        // changing a weight in the embedded initializer must be detected at load time
+        let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+        let mut tampered: SolverInitializer = serde_json::from_str(&lines[1]).unwrap();
+        tampered.weighted_edges[0].2 += 2;
+        lines[1] = serde_json::to_string(&tampered).unwrap();
+        std::fs::write(&filename, lines.join("\n") + "\n").unwrap();
+        let result = std::panic::catch_unwind(|| ErrorPatternReader::new(json!({ "filename": filename })));
+        assert!(result.is_err(), "a structural hash mismatch should be rejected");
+        std::fs::remove_file(filename).unwrap();
+    }
+
+    /// error patterns logged from several threads into a shared file are read back in the order of their shots
+    #[test]
+    fn example_error_pattern_shared_logger() {
+        // cargo test example_error_pattern_shared_logger -- --nocapture
+        use crate::mwpm_solver::*;
+        std::fs::create_dir_all("tmp").unwrap();
+        let filename = "tmp/example_error_pattern_shared_logger.txt".to_string();
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let shot_num = 40;
+        let thread_num = 4;
+        let syndrome_patterns: Vec<SyndromePattern> =
+            (0..shot_num).map(|seed| code.generate_random_errors(seed as u64)).collect();
+        {
+            let logger = SharedErrorPatternLogger::new(&initializer, &code.get_positions(), json!({ "filename": filename }));
+            std::thread::scope(|scope| {
+                for thread_index in 0..thread_num {
+                    let logger = logger.clone();
+                    let syndrome_patterns = &syndrome_patterns;
+                    // each thread logs its shots backwards so that most records have to wait for earlier shots
+                    scope.spawn(move || {
+                        for shot_index in (thread_index..shot_num).step_by(thread_num).rev() {
+                            logger.log(shot_index, &syndrome_patterns[shot_index]);
+                        }
+                    });
+                }
+            });
+            assert_eq!(logger.pending_count(), 0);
+            logger.flush();
+        }
+        let reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        assert_eq!(reader.get_initializer().structural_hash(), initializer.structural_hash());
+        assert_eq!(reader.syndrome_patterns.len(), shot_num);
+        for (read, expected) in reader.syndrome_patterns.iter().zip(syndrome_patterns.iter()) {
+            assert_eq!(read.defect_vertices, expected.defect_vertices);
+        }
+        std::fs::remove_file(filename).unwrap();
+    }
+
+    /// the resampled shots follow the analytic defect and erasure densities of the given error model
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_resampled_code_defect_density() {
+        // cargo test example_resampled_code_defect_density -- --nocapture
+        use crate::mwpm_solver::*;
+        let d = 11;
+        let (p, pe) = (0.1, 0.2);
+        let shot_num = 2000;
+        let mut code = CodeCapacityRepetitionCode::new(d, p, 500);
+        let initializer = code.get_initializer();
+        let edge_num = initializer.weighted_edges.len();
+        let real_vertex_num = initializer.vertex_num as usize - initializer.virtual_vertices.len();
+        let measure = |code: &mut ResampledCode| {
+            let (mut defect_count, mut erasure_count) = (0, 0);
+            for seed in 0..shot_num {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                defect_count += syndrome_pattern.defect_vertices.len();
+                erasure_count += syndrome_pattern.erasures.len();
+            }
+            (
+                defect_count as f64 / (shot_num as usize * real_vertex_num) as f64,
+                erasure_count as f64 / (shot_num as usize * edge_num) as f64,
+            )
+        };
+        // every real vertex of a repetition code has two incident edges
+        let mut resampled = ResampledCode::from_initializer(&initializer, code.get_positions(), &vec![p; edge_num], &[]);
+        let (defect_density, erasure_density) = measure(&mut resampled);
+        assert!((defect_density - 2. * p * (1. - p)).abs() < 0.01, "{defect_density}");
+        assert_eq!(erasure_density, 0.);
+        // an erased edge flips with probability 0.5
+        let q = pe * 0.5 + (1. - pe) * p;
+        let mut resampled =
+            ResampledCode::from_initializer(&initializer, code.get_positions(), &vec![p; edge_num], &vec![pe; edge_num]);
+        let (defect_density, erasure_density) = measure(&mut resampled);
+        assert!((defect_density - 2. * q * (1. - q)).abs() < 0.01, "{defect_density}");
+        assert!((erasure_density - pe).abs() < 0.01, "{erasure_density}");
+        // the same error model from a recorded problem file, with the erasure probabilities scaled
+        std::fs::create_dir_all("tmp").unwrap();
+        let filename = "tmp/example_resampled_code_defect_density.txt".to_string();
+        {
+            let mut logger =
+                SolverErrorPatternLogger::new(&initializer, &code.get_positions(), json!({ "filename": filename }));
+            logger.solve(&code.generate_random_errors(0));
+        }
+        let mut resampled = ResampledCode::new(json!({
+            "filename": filename,
+            "probabilities": vec![p; edge_num],
+            "erasure_probabilities": vec![pe * 2.; edge_num],
+            "pe_scale": 0.5,
+        }));
+        assert_eq!(resampled.get_initializer().structural_hash(), initializer.structural_hash());
+        let (defect_density, erasure_density) = measure(&mut resampled);
+        assert!((defect_density - 2. * q * (1. - q)).abs() < 0.01, "{defect_density}");
+        assert!((erasure_density - pe).abs() < 0.01, "{erasure_density}");
+        // the probabilities are recovered from the normalized weights of the file
+        let mut resampled = ResampledCode::new(json!({
+            "filename": filename,
+            "max_weight_p": p,
+        }));
+        let (defect_density, _) = measure(&mut resampled);
+        assert!((defect_density - 2. * p * (1. - p)).abs() < 0.01, "{defect_density}");
+        std::fs::remove_file(filename).unwrap();
+    }
+
     #[cfg(feature = "qecp_integrate")]
     #[test]
     fn example_qec_playground_code() {
@@ -1639,4 +2489,59 @@ mod tests {
         code.sanity_check().unwrap();
         visualize_code(&mut code, "example_qec_playground_code.json".to_string());
     }
+
+    #[cfg(feature = "qecp_integrate")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_qec_playground_model_graph() {
+        // cargo test example_qec_playground_model_graph --features qecp_integrate -- --nocapture
+        use crate::mwpm_solver::*;
+        use crate::primal_module::*;
+        use qecp::simulator::SimulatorGenerics;
+        let d = 5;
+        let mut simulator = qecp::simulator::Simulator::new(
+            qecp::code_builder::CodeType::StandardPlanarCode,
+            qecp::code_builder::CodeSize::new(d, d, d),
+        );
+        let mut noise_model = qecp::noise_model::NoiseModel::new(&simulator);
+        simulator.set_error_rates(&mut noise_model, 0.005, 0.005, 0.005, 0.);
+        simulator.compress_error_rates(&mut noise_model);
+        let noise_model = std::sync::Arc::new(noise_model);
+        let mut model_graph = qecp::model_graph::ModelGraph::new(&simulator);
+        model_graph.build(
+            &mut simulator,
+            noise_model.clone(),
+            &qecp::model_graph::WeightFunction::AutotuneImproved,
+            1,
+            true,
+            false,
+        );
+        let model_graph = &model_graph;
+        let vertex_map = QecpVertexMap::new(model_graph);
+        let initializer = SolverInitializer::from_qecp_model(model_graph, 500);
+        assert_eq!(vertex_map.visualize_positions().len(), initializer.vertex_num as usize);
+        let mut solver = SolverSerial::new(&initializer);
+        let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+        for seed in 0..20 {
+            let rng = qecp::reproducible_rand::Xoroshiro128StarStar::seed_from_u64(seed);
+            simulator.set_rng(rng);
+            simulator.generate_random_errors(&noise_model);
+            let sparse_measurement = simulator.generate_sparse_measurement();
+            let syndrome_pattern = SyndromePattern::from_qecp_measurement(&vertex_map, &sparse_measurement);
+            solver.solve(&syndrome_pattern);
+            subgraph_builder.clear();
+            subgraph_builder.load_perfect_matching(&solver.perfect_matching());
+            assert_eq!(subgraph_builder.total_weight(), solver.sum_dual_variables());
+            if cfg!(feature = "blossom_v") {
+                let blossom_mwpm_result = crate::blossom_v_mwpm(&initializer, &syndrome_pattern.defect_vertices);
+                let blossom_total_weight: Weight =
+                    crate::detailed_matching(&initializer, &syndrome_pattern.defect_vertices, &blossom_mwpm_result)
+                        .iter()
+                        .map(|detail| detail.weight)
+                        .sum();
+                assert_eq!(solver.sum_dual_variables(), blossom_total_weight);
+            }
+            solver.clear();
+        }
+    }
 }
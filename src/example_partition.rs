@@ -5,6 +5,7 @@
 
 use super::example_codes::*;
 use super::util::*;
+#[cfg(feature = "cli")]
 use clap::Parser;
 use serde::Serialize;
 use std::collections::VecDeque;
@@ -246,7 +247,7 @@ impl ExamplePartition for CodeCapacityRepetitionCodePartitionHalf {
     }
 }
 
-/// evenly partition along the time axis
+/// evenly partition along the time axis, or by the given number of rounds in each block
 pub struct PhenomenologicalPlanarCodeTimePartition {
     d: VertexNum,
     noisy_measurements: VertexNum,
@@ -257,6 +258,8 @@ pub struct PhenomenologicalPlanarCodeTimePartition {
     /// maximum amount of tree leaf; if the total partition is greater than this, it will be cut into multiple regions and each region is a separate tree;
     /// those trees are then fused sequentially
     maximum_tree_leaf_size: usize,
+    /// the number of measurement rounds in each block, see [`time_partition_config_with_block_rounds`]; evenly partition if not provided
+    block_rounds: Option<Vec<VertexNum>>,
 }
 
 impl PhenomenologicalPlanarCodeTimePartition {
@@ -273,11 +276,34 @@ impl PhenomenologicalPlanarCodeTimePartition {
             partition_num,
             enable_tree_fusion,
             maximum_tree_leaf_size,
+            block_rounds: None,
         }
     }
     pub fn new(d: VertexNum, noisy_measurements: VertexNum, partition_num: usize) -> Self {
         Self::new_tree(d, noisy_measurements, partition_num, false, usize::MAX)
     }
+    /// uneven partition, e.g. to give longer blocks to faster cores; `block_rounds` must sum up to `noisy_measurements + 1`
+    pub fn new_block_rounds(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        block_rounds: Vec<VertexNum>,
+        enable_tree_fusion: bool,
+        maximum_tree_leaf_size: usize,
+    ) -> Self {
+        assert_eq!(
+            block_rounds.iter().sum::<VertexNum>(),
+            noisy_measurements + 1,
+            "block rounds must sum up to the total number of measurement rounds"
+        );
+        Self {
+            d,
+            noisy_measurements,
+            partition_num: block_rounds.len(),
+            enable_tree_fusion,
+            maximum_tree_leaf_size,
+            block_rounds: Some(block_rounds),
+        }
+    }
 }
 
 impl ExamplePartition for PhenomenologicalPlanarCodeTimePartition {
@@ -287,126 +313,231 @@ impl ExamplePartition for PhenomenologicalPlanarCodeTimePartition {
         let round_vertex_num = d * (d + 1);
         let vertex_num = round_vertex_num * (noisy_measurements + 1);
         assert_eq!(code.vertex_num(), vertex_num, "code size incompatible");
-        assert!(partition_num >= 1 && partition_num <= noisy_measurements as usize + 1);
-        // do not use fixed partition_length, because it would introduce super long partition; do it on the fly
-        let mut config = PartitionConfig::new(vertex_num);
-        config.partitions.clear();
-        for partition_index in 0..partition_num as VertexIndex {
-            let start_round_index = partition_index * (noisy_measurements + 1) / partition_num as VertexNum;
-            let end_round_index = (partition_index + 1) * (noisy_measurements + 1) / partition_num as VertexNum;
-            assert!(end_round_index > start_round_index, "empty partition occurs");
-            if partition_index == 0 {
-                config.partitions.push(VertexRange::new(
-                    start_round_index * round_vertex_num,
-                    end_round_index * round_vertex_num,
-                ));
-            } else {
-                config.partitions.push(VertexRange::new(
-                    (start_round_index + 1) * round_vertex_num,
-                    end_round_index * round_vertex_num,
-                ));
-            }
+        match &self.block_rounds {
+            Some(block_rounds) => time_partition_config_with_block_rounds(
+                round_vertex_num,
+                block_rounds,
+                self.enable_tree_fusion,
+                self.maximum_tree_leaf_size,
+            ),
+            None => time_partition_config(
+                round_vertex_num,
+                noisy_measurements + 1,
+                partition_num,
+                self.enable_tree_fusion,
+                self.maximum_tree_leaf_size,
+            ),
         }
-        config.fusions.clear();
-        if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
-            for unit_index in partition_num..(2 * partition_num - 1) {
-                if unit_index == partition_num {
-                    config.fusions.push((0, 1));
+    }
+}
+
+/// two-level partition: first evenly along the time axis, and then each time slice into `space_partitions` blocks of columns;
+/// the columns between neighboring blocks are the spatial interfaces. The fusion tree fuses the blocks of each time slice first
+/// (left to right), and then fuses the time slices sequentially, just like [`PhenomenologicalPlanarCodeTimePartition`]
+pub struct PhenomenologicalPlanarCodeSpaceTimePartition {
+    d: VertexNum,
+    noisy_measurements: VertexNum,
+    /// the number of time slices
+    time_partitions: usize,
+    /// the number of blocks in each time slice
+    space_partitions: usize,
+}
+
+impl PhenomenologicalPlanarCodeSpaceTimePartition {
+    pub fn new(d: VertexNum, noisy_measurements: VertexNum, time_partitions: usize, space_partitions: usize) -> Self {
+        Self {
+            d,
+            noisy_measurements,
+            time_partitions,
+            space_partitions,
+        }
+    }
+
+    /// the round ranges `[start, end)` of each time slice, where the first round of every slice except for the first one
+    /// is the temporal interface
+    #[allow(clippy::unnecessary_cast)]
+    fn time_slice_rounds(&self) -> Vec<(VertexNum, VertexNum)> {
+        let (noisy_measurements, time_partitions) = (self.noisy_measurements, self.time_partitions);
+        assert!(time_partitions >= 1 && time_partitions <= noisy_measurements as usize + 1);
+        (0..time_partitions as VertexNum)
+            .map(|partition_index| {
+                let start_round_index = partition_index * (noisy_measurements + 1) / time_partitions as VertexNum;
+                let end_round_index = (partition_index + 1) * (noisy_measurements + 1) / time_partitions as VertexNum;
+                let first_owned_round = if partition_index == 0 {
+                    start_round_index
                 } else {
-                    config.fusions.push((unit_index as usize - 1, unit_index - partition_num + 1));
+                    start_round_index + 1
+                };
+                assert!(end_round_index > first_owned_round, "empty partition occurs");
+                (start_round_index, end_round_index)
+            })
+            .collect()
+    }
+
+    /// the spatial interface columns; the `d - 1` real columns are split into `space_partitions` blocks separated by them
+    #[allow(clippy::unnecessary_cast)]
+    fn interface_columns(&self) -> Vec<VertexNum> {
+        let (d, space_partitions) = (self.d, self.space_partitions);
+        assert!(space_partitions >= 1);
+        let columns: Vec<VertexNum> = (1..space_partitions as VertexNum)
+            .map(|block_index| block_index * (d - 1) / space_partitions as VertexNum)
+            .collect();
+        let mut last_column: isize = -1;
+        for &column in columns.iter().chain(std::iter::once(&(d - 1))) {
+            assert!(
+                column as isize - last_column >= 2,
+                "empty spatial block occurs, consider larger d"
+            );
+            last_column = column as isize;
+        }
+        columns
+    }
+
+    /// the block index of a column, or `None` if it's an interface column; the left virtual vertex (column `d`) belongs to
+    /// the first block and the right virtual vertex (column `d - 1`) belongs to the last block
+    fn column_block(&self, column: VertexNum, interface_columns: &[VertexNum]) -> Option<usize> {
+        if column == self.d {
+            return Some(0);
+        }
+        if interface_columns.contains(&column) {
+            return None;
+        }
+        Some(
+            interface_columns
+                .iter()
+                .filter(|&&interface_column| interface_column < column)
+                .count(),
+        )
+    }
+}
+
+impl ExamplePartition for PhenomenologicalPlanarCodeSpaceTimePartition {
+    #[allow(clippy::unnecessary_cast)]
+    fn build_reordered_vertices(&mut self, code: &dyn ExampleCode) -> Option<Vec<VertexIndex>> {
+        let d = self.d;
+        let row_vertex_num = d + 1;
+        let round_vertex_num = d * row_vertex_num;
+        assert_eq!(
+            code.vertex_num(),
+            round_vertex_num * (self.noisy_measurements + 1),
+            "code size incompatible"
+        );
+        let interface_columns = self.interface_columns();
+        let mut reordered_vertices = vec![];
+        // push the vertices of a single round whose column satisfies the filter
+        let push_round =
+            |reordered_vertices: &mut Vec<VertexIndex>, round: VertexNum, column_filter: &dyn Fn(VertexNum) -> bool| {
+                for row in 0..d {
+                    for column in 0..row_vertex_num {
+                        if column_filter(column) {
+                            reordered_vertices.push(round * round_vertex_num + row * row_vertex_num + column);
+                        }
+                    }
                 }
-            }
-        } else {
-            let mut whole_ranges = vec![];
-            let mut left_right_leaf = vec![];
-            for (unit_index, partition) in config.partitions.iter().enumerate() {
-                assert!(
-                    partition.end() <= vertex_num,
-                    "invalid vertex index {} in partitions",
-                    partition.end()
-                );
-                whole_ranges.push(*partition);
-                left_right_leaf.push((unit_index, unit_index));
-            }
-            // first cut into multiple regions
-            let region_count = if config.partitions.len() <= self.maximum_tree_leaf_size {
-                1
-            } else {
-                (config.partitions.len() + self.maximum_tree_leaf_size - 1) / self.maximum_tree_leaf_size
             };
-            let mut last_sequential_unit: Option<usize> = None;
-            for region_index in 0..region_count {
-                let region_start = region_index * self.maximum_tree_leaf_size;
-                let region_end = std::cmp::min((region_index + 1) * self.maximum_tree_leaf_size, config.partitions.len());
-                // build the local tree
-                let mut pending_fusion = VecDeque::new();
-                for unit_index in region_start..region_end {
-                    pending_fusion.push_back(unit_index);
+        for (slice_index, (start_round, end_round)) in self.time_slice_rounds().into_iter().enumerate() {
+            let mut first_owned_round = start_round;
+            if slice_index > 0 {
+                // the temporal interface
+                push_round(&mut reordered_vertices, start_round, &|_| true);
+                first_owned_round += 1;
+            }
+            for block_index in 0..self.space_partitions {
+                // the spatial block, followed by the spatial interface on its right
+                for round in first_owned_round..end_round {
+                    push_round(&mut reordered_vertices, round, &|column| {
+                        self.column_block(column, &interface_columns) == Some(block_index)
+                    });
                 }
-                let local_fusion_start_index = whole_ranges.len();
-                for unit_index in local_fusion_start_index..(local_fusion_start_index + region_end - region_start - 1) {
-                    let mut unit_index_1 = pending_fusion.pop_front().unwrap();
-                    // iterate over all pending fusions to find a neighboring one
-                    for i in 0..pending_fusion.len() {
-                        let mut unit_index_2 = pending_fusion[i];
-                        let is_neighbor = left_right_leaf[unit_index_1].0 == left_right_leaf[unit_index_2].1 + 1
-                            || left_right_leaf[unit_index_2].0 == left_right_leaf[unit_index_1].1 + 1;
-                        if is_neighbor {
-                            pending_fusion.remove(i);
-                            if whole_ranges[unit_index_1].start() > whole_ranges[unit_index_2].start() {
-                                (unit_index_1, unit_index_2) = (unit_index_2, unit_index_1);
-                                // only lower range can fuse higher range
-                            }
-                            config.fusions.push((unit_index_1, unit_index_2));
-                            pending_fusion.push_back(unit_index);
-                            // println!("unit_index_1: {unit_index_1} {:?}, unit_index_2: {unit_index_2} {:?}", whole_ranges[unit_index_1], whole_ranges[unit_index_2]);
-                            let (whole_range, _) = whole_ranges[unit_index_1].fuse(&whole_ranges[unit_index_2]);
-                            whole_ranges.push(whole_range);
-                            left_right_leaf.push((left_right_leaf[unit_index_1].0, left_right_leaf[unit_index_2].1));
-                            break;
-                        }
-                        assert!(i != pending_fusion.len() - 1, "unreachable: cannot find a neighbor");
+                if block_index < interface_columns.len() {
+                    let interface_column = interface_columns[block_index];
+                    for round in first_owned_round..end_round {
+                        push_round(&mut reordered_vertices, round, &|column| column == interface_column);
                     }
                 }
-                assert!(pending_fusion.len() == 1, "only the final unit is left");
-                let tree_root_unit_index = pending_fusion.pop_front().unwrap();
-                if let Some(last_sequential_unit) = last_sequential_unit.as_mut() {
-                    config.fusions.push((*last_sequential_unit, tree_root_unit_index));
-                    let (whole_range, _) = whole_ranges[*last_sequential_unit].fuse(&whole_ranges[tree_root_unit_index]);
-                    whole_ranges.push(whole_range);
-                    left_right_leaf.push((
-                        left_right_leaf[*last_sequential_unit].0,
-                        left_right_leaf[tree_root_unit_index].1,
-                    ));
-                    *last_sequential_unit = tree_root_unit_index + 1;
-                } else {
-                    last_sequential_unit = Some(tree_root_unit_index);
+            }
+        }
+        Some(reordered_vertices)
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn build_partition(&mut self, code: &dyn ExampleCode) -> PartitionConfig {
+        let d = self.d;
+        let row_vertex_num = d + 1;
+        let round_vertex_num = d * row_vertex_num;
+        let interface_columns = self.interface_columns();
+        let mut config = PartitionConfig::new(code.vertex_num());
+        config.partitions.clear();
+        let mut cursor: VertexIndex = 0;
+        let time_slice_rounds = self.time_slice_rounds();
+        for (slice_index, &(start_round, end_round)) in time_slice_rounds.iter().enumerate() {
+            let mut first_owned_round = start_round;
+            if slice_index > 0 {
+                cursor += round_vertex_num; // skip the temporal interface
+                first_owned_round += 1;
+            }
+            let slice_rounds = end_round - first_owned_round;
+            for block_index in 0..self.space_partitions {
+                let block_columns = (0..row_vertex_num)
+                    .filter(|&column| self.column_block(column, &interface_columns) == Some(block_index))
+                    .count() as VertexNum;
+                let block_length = slice_rounds * d * block_columns;
+                config.partitions.push(VertexRange::new_length(cursor, block_length));
+                cursor += block_length;
+                if block_index < interface_columns.len() {
+                    cursor += slice_rounds * d; // skip the spatial interface
                 }
             }
         }
+        assert_eq!(cursor, code.vertex_num(), "partition doesn't cover all vertices");
+        // fuse the blocks of each time slice first, and then fuse the time slices sequentially
+        let (time_partitions, space_partitions) = (time_slice_rounds.len(), self.space_partitions);
+        let mut unit_index = config.partitions.len();
+        let mut slice_units = vec![];
+        for slice_index in 0..time_partitions {
+            let mut current_unit = slice_index * space_partitions;
+            for block_index in 1..space_partitions {
+                config
+                    .fusions
+                    .push((current_unit, slice_index * space_partitions + block_index));
+                current_unit = unit_index;
+                unit_index += 1;
+            }
+            slice_units.push(current_unit);
+        }
+        let mut current_unit = slice_units[0];
+        for &slice_unit in slice_units.iter().skip(1) {
+            config.fusions.push((current_unit, slice_unit));
+            current_unit = unit_index;
+            unit_index += 1;
+        }
         config
     }
 }
 
 /// evenly partition along the time axis
-#[derive(Parser, Clone, Serialize)]
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "cli", derive(Parser))]
 pub struct PhenomenologicalRotatedCodeTimePartition {
     /// code distance
-    #[clap(value_parser)]
+    #[cfg_attr(feature = "cli", clap(value_parser))]
     pub d: VertexNum,
     /// rounds of noisy measurement, valid only when multiple rounds
-    #[clap(value_parser)]
+    #[cfg_attr(feature = "cli", clap(value_parser))]
     pub noisy_measurements: VertexNum,
     /// the number of partition
-    #[clap(value_parser)]
+    #[cfg_attr(feature = "cli", clap(value_parser))]
     pub partition_num: usize,
     /// enable tree fusion (to minimize latency but incur log(partition_num) more memory copy)
-    #[clap(short = 't', long, default_value_t = false)]
+    #[cfg_attr(feature = "cli", clap(short = 't', long, default_value_t = false))]
     pub enable_tree_fusion: bool,
     /// maximum amount of tree leaf; if the total partition is greater than this, it will be cut into multiple regions and each region is a separate tree;
     /// those trees are then fused sequentially
-    #[clap(short = 'l', long, default_value_t = usize::MAX)]
+    #[cfg_attr(feature = "cli", clap(short = 'l', long, default_value_t = usize::MAX))]
     pub maximum_tree_leaf_size: usize,
+    /// the number of measurement rounds in each block, overriding `partition_num`; evenly partition if not provided
+    #[cfg_attr(feature = "cli", clap(long, value_delimiter = ','))]
+    pub block_rounds: Option<Vec<VertexNum>>,
 }
 
 impl PhenomenologicalRotatedCodeTimePartition {
@@ -423,11 +554,34 @@ impl PhenomenologicalRotatedCodeTimePartition {
             partition_num,
             enable_tree_fusion,
             maximum_tree_leaf_size,
+            block_rounds: None,
         }
     }
     pub fn new(d: VertexNum, noisy_measurements: VertexNum, partition_num: usize) -> Self {
         Self::new_tree(d, noisy_measurements, partition_num, false, usize::MAX)
     }
+    /// uneven partition, e.g. to give longer blocks to faster cores; `block_rounds` must sum up to `noisy_measurements + 1`
+    pub fn new_block_rounds(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        block_rounds: Vec<VertexNum>,
+        enable_tree_fusion: bool,
+        maximum_tree_leaf_size: usize,
+    ) -> Self {
+        assert_eq!(
+            block_rounds.iter().sum::<VertexNum>(),
+            noisy_measurements + 1,
+            "block rounds must sum up to the total number of measurement rounds"
+        );
+        Self {
+            d,
+            noisy_measurements,
+            partition_num: block_rounds.len(),
+            enable_tree_fusion,
+            maximum_tree_leaf_size,
+            block_rounds: Some(block_rounds),
+        }
+    }
 }
 
 impl ExamplePartition for PhenomenologicalRotatedCodeTimePartition {
@@ -438,108 +592,244 @@ impl ExamplePartition for PhenomenologicalRotatedCodeTimePartition {
         let round_vertex_num = row_vertex_num * (d + 1);
         let vertex_num = round_vertex_num * (noisy_measurements + 1);
         assert_eq!(code.vertex_num(), vertex_num, "code size incompatible");
-        assert!(partition_num >= 1 && partition_num <= noisy_measurements as usize + 1);
-        // do not use fixed partition_length, because it would introduce super long partition; do it on the fly
+        match &self.block_rounds {
+            Some(block_rounds) => time_partition_config_with_block_rounds(
+                round_vertex_num,
+                block_rounds,
+                self.enable_tree_fusion,
+                self.maximum_tree_leaf_size,
+            ),
+            None => time_partition_config(
+                round_vertex_num,
+                noisy_measurements + 1,
+                partition_num,
+                self.enable_tree_fusion,
+                self.maximum_tree_leaf_size,
+            ),
+        }
+    }
+}
+
+/// how the time blocks of [`TimePartitionBuilder`] are fused together
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum FusionKind {
+    /// fuse the blocks one by one from the earliest to the latest, i.e. the standard sliding-window layout
+    Sequential,
+    /// fuse neighboring blocks in trees of at most `maximum_tree_leaf_size` leaves, and then fuse the trees sequentially
+    Tree { maximum_tree_leaf_size: usize },
+}
+
+/// partition the time axis into blocks of `block_rounds` measurement rounds, separated by `overlap_rounds` rounds of buffer region;
+/// the buffer region is owned by the fusion unit of its two neighboring blocks, and the boundary vertices of the buffer region are
+/// automatically mirrored into both neighbors (the whole buffer region is mirrored if `edges_in_fusion_unit` is disabled in the
+/// dual module), so that the fusion reconciles the dual variables of the two neighbors consistently.
+/// It applies to any code whose vertices are ordered round by round, with `round_vertex_num` vertices each round
+#[derive(Debug, Clone, Serialize)]
+pub struct TimePartitionBuilder {
+    /// the number of vertices in a single measurement round
+    pub round_vertex_num: VertexNum,
+    /// the number of measurement rounds in each block; the last block may be longer to absorb the remaining rounds
+    pub block_rounds: VertexNum,
+    /// the number of measurement rounds in the buffer region between consecutive blocks, must be at least 1
+    pub overlap_rounds: VertexNum,
+    /// how blocks are fused
+    pub fusion: FusionKind,
+}
+
+impl TimePartitionBuilder {
+    pub fn new(round_vertex_num: VertexNum, block_rounds: VertexNum, overlap_rounds: VertexNum, fusion: FusionKind) -> Self {
+        Self {
+            round_vertex_num,
+            block_rounds,
+            overlap_rounds,
+            fusion,
+        }
+    }
+
+    /// the round ranges `[start, end)` of each block given the total number of measurement rounds
+    pub fn block_round_ranges(&self, total_rounds: VertexNum) -> Vec<(VertexNum, VertexNum)> {
+        assert!(self.block_rounds >= 1, "each block must contain at least 1 round");
+        assert!(
+            self.overlap_rounds >= 1,
+            "at least 1 round of buffer region is required between blocks"
+        );
+        let mut ranges = vec![];
+        let mut start_round = 0;
+        while start_round < total_rounds {
+            let mut end_round = std::cmp::min(start_round + self.block_rounds, total_rounds);
+            // the next block must contain at least 1 round after the buffer region, otherwise absorb the remaining rounds
+            if end_round + self.overlap_rounds >= total_rounds {
+                end_round = total_rounds;
+            }
+            ranges.push((start_round, end_round));
+            start_round = end_round + self.overlap_rounds;
+        }
+        ranges
+    }
+}
+
+impl ExamplePartition for TimePartitionBuilder {
+    #[allow(clippy::unnecessary_cast)]
+    fn build_partition(&mut self, code: &dyn ExampleCode) -> PartitionConfig {
+        let round_vertex_num = self.round_vertex_num;
+        let vertex_num = code.vertex_num();
+        assert!(round_vertex_num > 0, "round_vertex_num must be positive");
+        assert_eq!(vertex_num % round_vertex_num, 0, "code size incompatible");
         let mut config = PartitionConfig::new(vertex_num);
-        config.partitions.clear();
-        for partition_index in 0..partition_num as VertexIndex {
-            let start_round_index = partition_index * (noisy_measurements + 1) / partition_num as VertexNum;
-            let end_round_index = (partition_index + 1) * (noisy_measurements + 1) / partition_num as VertexNum;
-            assert!(end_round_index > start_round_index, "empty partition occurs");
-            if partition_index == 0 {
-                config.partitions.push(VertexRange::new(
-                    start_round_index * round_vertex_num,
-                    end_round_index * round_vertex_num,
-                ));
-            } else {
-                config.partitions.push(VertexRange::new(
-                    (start_round_index + 1) * round_vertex_num,
-                    end_round_index * round_vertex_num,
-                ));
+        config.partitions = self
+            .block_round_ranges(vertex_num / round_vertex_num)
+            .into_iter()
+            .map(|(start_round, end_round)| VertexRange::new(start_round * round_vertex_num, end_round * round_vertex_num))
+            .collect();
+        match self.fusion {
+            FusionKind::Sequential => build_time_partition_fusions(&mut config, false, usize::MAX),
+            FusionKind::Tree { maximum_tree_leaf_size } => {
+                build_time_partition_fusions(&mut config, true, maximum_tree_leaf_size)
             }
         }
-        config.fusions.clear();
-        if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
-            for unit_index in partition_num..(2 * partition_num - 1) {
-                if unit_index == partition_num {
-                    config.fusions.push((0, 1));
-                } else {
-                    config.fusions.push((unit_index as usize - 1, unit_index - partition_num + 1));
-                }
+        config
+    }
+}
+
+/// evenly cut `rounds` measurement rounds of `round_vertex_num` vertices each into `partition_num` blocks, leaving the first round of
+/// every later block as the interface owned by the fusion unit; the blocks are fused as in [`build_time_partition_fusions`]
+#[allow(clippy::unnecessary_cast)]
+pub fn time_partition_config(
+    round_vertex_num: VertexNum,
+    rounds: VertexNum,
+    partition_num: usize,
+    enable_tree_fusion: bool,
+    maximum_tree_leaf_size: usize,
+) -> PartitionConfig {
+    assert!(partition_num >= 1 && partition_num <= rounds as usize);
+    // do not use fixed partition_length, because it would introduce super long partition; do it on the fly
+    let block_rounds: Vec<VertexNum> = (0..partition_num as VertexNum)
+        .map(|partition_index| {
+            (partition_index + 1) * rounds / partition_num as VertexNum
+                - partition_index * rounds / partition_num as VertexNum
+        })
+        .collect();
+    time_partition_config_with_block_rounds(round_vertex_num, &block_rounds, enable_tree_fusion, maximum_tree_leaf_size)
+}
+
+/// cut the measurement rounds into consecutive blocks of `block_rounds[i]` rounds each, leaving the first round of every later
+/// block as the interface owned by the fusion unit; the blocks are fused as in [`build_time_partition_fusions`]
+pub fn time_partition_config_with_block_rounds(
+    round_vertex_num: VertexNum,
+    block_rounds: &[VertexNum],
+    enable_tree_fusion: bool,
+    maximum_tree_leaf_size: usize,
+) -> PartitionConfig {
+    assert!(!block_rounds.is_empty(), "at least one block is required");
+    let rounds: VertexNum = block_rounds.iter().sum();
+    let mut config = PartitionConfig::new(round_vertex_num * rounds);
+    config.partitions.clear();
+    let mut start_round_index = 0;
+    for (partition_index, &rounds_in_block) in block_rounds.iter().enumerate() {
+        assert!(rounds_in_block > 0, "empty partition occurs");
+        let end_round_index = start_round_index + rounds_in_block;
+        if partition_index == 0 {
+            config.partitions.push(VertexRange::new(
+                start_round_index * round_vertex_num,
+                end_round_index * round_vertex_num,
+            ));
+        } else {
+            config.partitions.push(VertexRange::new(
+                (start_round_index + 1) * round_vertex_num,
+                end_round_index * round_vertex_num,
+            ));
+        }
+        start_round_index = end_round_index;
+    }
+    build_time_partition_fusions(&mut config, enable_tree_fusion, maximum_tree_leaf_size);
+    config
+}
+
+/// fuse the time partitions in `config` either sequentially or, if `enable_tree_fusion`, in trees of at most `maximum_tree_leaf_size` leaves
+/// which are then fused sequentially
+pub fn build_time_partition_fusions(config: &mut PartitionConfig, enable_tree_fusion: bool, maximum_tree_leaf_size: usize) {
+    let partition_num = config.partitions.len();
+    let vertex_num = config.vertex_num;
+    config.fusions.clear();
+    if !enable_tree_fusion || maximum_tree_leaf_size == 1 {
+        for unit_index in partition_num..(2 * partition_num - 1) {
+            if unit_index == partition_num {
+                config.fusions.push((0, 1));
+            } else {
+                config.fusions.push((unit_index - 1, unit_index - partition_num + 1));
             }
+        }
+    } else {
+        let mut whole_ranges = vec![];
+        let mut left_right_leaf = vec![];
+        for (unit_index, partition) in config.partitions.iter().enumerate() {
+            assert!(
+                VertexRange::new(0, vertex_num).contains_range(partition),
+                "invalid vertex index {} in partitions",
+                partition.end()
+            );
+            whole_ranges.push(*partition);
+            left_right_leaf.push((unit_index, unit_index));
+        }
+        // first cut into multiple regions
+        let region_count = if config.partitions.len() <= maximum_tree_leaf_size {
+            1
         } else {
-            let mut whole_ranges = vec![];
-            let mut left_right_leaf = vec![];
-            for (unit_index, partition) in config.partitions.iter().enumerate() {
-                assert!(
-                    partition.end() <= vertex_num,
-                    "invalid vertex index {} in partitions",
-                    partition.end()
-                );
-                whole_ranges.push(*partition);
-                left_right_leaf.push((unit_index, unit_index));
+            config.partitions.len().div_ceil(maximum_tree_leaf_size)
+        };
+        let mut last_sequential_unit: Option<usize> = None;
+        for region_index in 0..region_count {
+            let region_start = region_index * maximum_tree_leaf_size;
+            let region_end = std::cmp::min((region_index + 1) * maximum_tree_leaf_size, config.partitions.len());
+            // build the local tree
+            let mut pending_fusion = VecDeque::new();
+            for unit_index in region_start..region_end {
+                pending_fusion.push_back(unit_index);
             }
-            // first cut into multiple regions
-            let region_count = if config.partitions.len() <= self.maximum_tree_leaf_size {
-                1
-            } else {
-                (config.partitions.len() + self.maximum_tree_leaf_size - 1) / self.maximum_tree_leaf_size
-            };
-            let mut last_sequential_unit: Option<usize> = None;
-            for region_index in 0..region_count {
-                let region_start = region_index * self.maximum_tree_leaf_size;
-                let region_end = std::cmp::min((region_index + 1) * self.maximum_tree_leaf_size, config.partitions.len());
-                // build the local tree
-                let mut pending_fusion = VecDeque::new();
-                for unit_index in region_start..region_end {
-                    pending_fusion.push_back(unit_index);
-                }
-                let local_fusion_start_index = whole_ranges.len();
-                for unit_index in local_fusion_start_index..(local_fusion_start_index + region_end - region_start - 1) {
-                    let mut unit_index_1 = pending_fusion.pop_front().unwrap();
-                    // iterate over all pending fusions to find a neighboring one
-                    for i in 0..pending_fusion.len() {
-                        let mut unit_index_2 = pending_fusion[i];
-                        let is_neighbor = left_right_leaf[unit_index_1].0 == left_right_leaf[unit_index_2].1 + 1
-                            || left_right_leaf[unit_index_2].0 == left_right_leaf[unit_index_1].1 + 1;
-                        if is_neighbor {
-                            pending_fusion.remove(i);
-                            if whole_ranges[unit_index_1].start() > whole_ranges[unit_index_2].start() {
-                                (unit_index_1, unit_index_2) = (unit_index_2, unit_index_1);
-                                // only lower range can fuse higher range
-                            }
-                            config.fusions.push((unit_index_1, unit_index_2));
-                            pending_fusion.push_back(unit_index);
-                            // println!("unit_index_1: {unit_index_1} {:?}, unit_index_2: {unit_index_2} {:?}", whole_ranges[unit_index_1], whole_ranges[unit_index_2]);
-                            let (whole_range, _) = whole_ranges[unit_index_1].fuse(&whole_ranges[unit_index_2]);
-                            whole_ranges.push(whole_range);
-                            left_right_leaf.push((left_right_leaf[unit_index_1].0, left_right_leaf[unit_index_2].1));
-                            break;
+            let local_fusion_start_index = whole_ranges.len();
+            for unit_index in local_fusion_start_index..(local_fusion_start_index + region_end - region_start - 1) {
+                let mut unit_index_1 = pending_fusion.pop_front().unwrap();
+                // iterate over all pending fusions to find a neighboring one
+                for i in 0..pending_fusion.len() {
+                    let mut unit_index_2 = pending_fusion[i];
+                    let is_neighbor = left_right_leaf[unit_index_1].0 == left_right_leaf[unit_index_2].1 + 1
+                        || left_right_leaf[unit_index_2].0 == left_right_leaf[unit_index_1].1 + 1;
+                    if is_neighbor {
+                        pending_fusion.remove(i);
+                        if whole_ranges[unit_index_1].start() > whole_ranges[unit_index_2].start() {
+                            (unit_index_1, unit_index_2) = (unit_index_2, unit_index_1);
+                            // only lower range can fuse higher range
                         }
-                        assert!(i != pending_fusion.len() - 1, "unreachable: cannot find a neighbor");
+                        config.fusions.push((unit_index_1, unit_index_2));
+                        pending_fusion.push_back(unit_index);
+                        // println!("unit_index_1: {unit_index_1} {:?}, unit_index_2: {unit_index_2} {:?}", whole_ranges[unit_index_1], whole_ranges[unit_index_2]);
+                        let (whole_range, _) = whole_ranges[unit_index_1].fuse(&whole_ranges[unit_index_2]);
+                        whole_ranges.push(whole_range);
+                        left_right_leaf.push((left_right_leaf[unit_index_1].0, left_right_leaf[unit_index_2].1));
+                        break;
                     }
-                }
-                assert!(pending_fusion.len() == 1, "only the final unit is left");
-                let tree_root_unit_index = pending_fusion.pop_front().unwrap();
-                if let Some(last_sequential_unit) = last_sequential_unit.as_mut() {
-                    config.fusions.push((*last_sequential_unit, tree_root_unit_index));
-                    let (whole_range, _) = whole_ranges[*last_sequential_unit].fuse(&whole_ranges[tree_root_unit_index]);
-                    whole_ranges.push(whole_range);
-                    left_right_leaf.push((
-                        left_right_leaf[*last_sequential_unit].0,
-                        left_right_leaf[tree_root_unit_index].1,
-                    ));
-                    *last_sequential_unit = tree_root_unit_index + 1;
-                } else {
-                    last_sequential_unit = Some(tree_root_unit_index);
+                    assert!(i != pending_fusion.len() - 1, "unreachable: cannot find a neighbor");
                 }
             }
+            assert!(pending_fusion.len() == 1, "only the final unit is left");
+            let tree_root_unit_index = pending_fusion.pop_front().unwrap();
+            if let Some(last_sequential_unit) = last_sequential_unit.as_mut() {
+                config.fusions.push((*last_sequential_unit, tree_root_unit_index));
+                let (whole_range, _) = whole_ranges[*last_sequential_unit].fuse(&whole_ranges[tree_root_unit_index]);
+                whole_ranges.push(whole_range);
+                left_right_leaf.push((
+                    left_right_leaf[*last_sequential_unit].0,
+                    left_right_leaf[tree_root_unit_index].1,
+                ));
+                *last_sequential_unit = tree_root_unit_index + 1;
+            } else {
+                last_sequential_unit = Some(tree_root_unit_index);
+            }
         }
-        config
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "parallel"))]
 pub mod tests {
     use super::super::dual_module::*;
     use super::super::dual_module_parallel::*;
@@ -833,6 +1123,84 @@ pub mod tests {
         );
     }
 
+    /// custom time partition with buffer regions between blocks
+    #[test]
+    fn example_partition_time_partition_builder_1() {
+        // cargo test example_partition_time_partition_builder_1 -- --nocapture
+        let d = 7;
+        let noisy_measurements = 51;
+        let builder = TimePartitionBuilder::new(d * (d + 1), 10, 3, FusionKind::Sequential);
+        assert_eq!(
+            builder.block_round_ranges(noisy_measurements + 1),
+            vec![(0, 10), (13, 23), (26, 36), (39, 52)]
+        );
+        let defect_vertices = vec![
+            57, 113, 289, 304, 305, 331, 345, 387, 485, 493, 528, 536, 569, 570, 587, 588, 696, 745, 801, 833, 834, 884,
+            904, 940, 1152, 1184, 1208, 1258, 1266, 1344, 1413, 1421, 1481, 1489, 1490, 1546, 1690, 1733, 1740, 1746, 1796,
+            1825, 1826, 1856, 1857, 1996, 2004, 2020, 2028, 2140, 2196, 2306, 2307, 2394, 2395, 2413, 2417, 2425, 2496,
+            2497, 2731, 2739, 2818, 2874,
+        ];
+        let half_weight = 500;
+        for (index, fusion) in [
+            FusionKind::Sequential,
+            FusionKind::Tree {
+                maximum_tree_leaf_size: 2,
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut builder = builder.clone();
+            builder.fusion = fusion;
+            example_partition_standard_syndrome(
+                &mut PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.005, half_weight),
+                format!("example_partition_time_partition_builder_1_{index}.json"),
+                defect_vertices.clone(),
+                true,
+                35 * half_weight,
+                builder,
+            );
+        }
+    }
+
+    /// uneven time partition, e.g. to give longer blocks to faster cores
+    #[test]
+    fn example_partition_block_rounds_1() {
+        // cargo test example_partition_block_rounds_1 -- --nocapture
+        let d = 7;
+        let noisy_measurements = 51;
+        let round_vertex_num = d * (d + 1);
+        let block_rounds = vec![20, 4, 12, 16];
+        let config = time_partition_config_with_block_rounds(round_vertex_num, &block_rounds, true, usize::MAX);
+        let partitions: Vec<_> = config
+            .partitions
+            .iter()
+            .map(|range| (range.start() / round_vertex_num, range.end() / round_vertex_num))
+            .collect();
+        assert_eq!(partitions, vec![(0, 20), (21, 24), (25, 36), (37, 52)]);
+        assert_eq!(config.fusions, vec![(0, 1), (2, 3), (4, 5)]);
+        // the even partition is a special case
+        assert_eq!(
+            time_partition_config(round_vertex_num, noisy_measurements + 1, 3, false, usize::MAX).partitions,
+            time_partition_config_with_block_rounds(round_vertex_num, &[17, 17, 18], false, usize::MAX).partitions
+        );
+        let defect_vertices = vec![
+            57, 113, 289, 304, 305, 331, 345, 387, 485, 493, 528, 536, 569, 570, 587, 588, 696, 745, 801, 833, 834, 884,
+            904, 940, 1152, 1184, 1208, 1258, 1266, 1344, 1413, 1421, 1481, 1489, 1490, 1546, 1690, 1733, 1740, 1746, 1796,
+            1825, 1826, 1856, 1857, 1996, 2004, 2020, 2028, 2140, 2196, 2306, 2307, 2394, 2395, 2413, 2417, 2425, 2496,
+            2497, 2731, 2739, 2818, 2874,
+        ];
+        let half_weight = 500;
+        example_partition_standard_syndrome(
+            &mut PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.005, half_weight),
+            "example_partition_block_rounds_1.json".to_string(),
+            defect_vertices,
+            true,
+            35 * half_weight,
+            PhenomenologicalPlanarCodeTimePartition::new_block_rounds(d, noisy_measurements, block_rounds, true, usize::MAX),
+        );
+    }
+
     /// demo of tree + sequential fuse
     #[test]
     fn example_partition_demo_5() {
@@ -856,4 +1224,181 @@ pub mod tests {
             PhenomenologicalPlanarCodeTimePartition::new_tree(7, noisy_measurements, 8, true, 3),
         );
     }
+
+    /// two-level partition: 4 time slices, each split into left and right halves
+    #[test]
+    fn example_partition_space_time_1() {
+        // cargo test example_partition_space_time_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (15, 15);
+        let half_weight = 500;
+        let codes: Vec<Box<dyn ExampleCode>> = vec![
+            Box::new(PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.02, half_weight)),
+            Box::new(CircuitLevelPlanarCode::new(d, noisy_measurements, 0.005, half_weight)),
+        ];
+        for mut code in codes.into_iter() {
+            let mut partition = PhenomenologicalPlanarCodeSpaceTimePartition::new(d, noisy_measurements, 4, 2);
+            let mut reordered_vertices = partition.build_reordered_vertices(code.as_ref()).unwrap();
+            reordered_vertices.sort();
+            assert_eq!(
+                reordered_vertices,
+                (0..code.vertex_num()).collect::<Vec<_>>(),
+                "must be a permutation"
+            );
+            let partition_config = partition.build_apply(code.as_mut());
+            let partition_info = partition_config.info();
+            assert_eq!(partition_info.units.len(), 8 + 7);
+            for unit_index in 8..12 {
+                // spatial fusion units own the interface column of a time slice
+                assert!(!partition_info.units[unit_index].owning_range.is_empty());
+            }
+            let initializer = code.get_initializer();
+            let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+            let mut serial_solver = SolverSerial::new(&initializer);
+            for seed in 0..20 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                parallel_solver.solve(&syndrome_pattern);
+                serial_solver.solve(&syndrome_pattern);
+                assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+                let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+                subgraph_builder.load_subgraph(&parallel_solver.subgraph());
+                assert_eq!(subgraph_builder.total_weight(), serial_solver.sum_dual_variables());
+                parallel_solver.clear();
+                serial_solver.clear();
+            }
+        }
+    }
+
+    /// the number of rounds spent in fusion units is reported per unit, and fusing doesn't change the result
+    #[test]
+    fn example_partition_fusion_rounds_1() {
+        // cargo test example_partition_fusion_rounds_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements, partition_num) = (11, 39, 4);
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.02, half_weight);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, partition_num);
+        let partition_config = partition.build_apply(&mut code);
+        let partition_info = partition_config.info();
+        let initializer = code.get_initializer();
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        let mut fusion_rounds = 0;
+        let mut broken_matches = 0;
+        for seed in 0..100 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            parallel_solver.solve(&syndrome_pattern);
+            serial_solver.solve(&syndrome_pattern);
+            assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+            let unit_rounds = parallel_solver.primal_module.unit_rounds();
+            fusion_rounds += unit_rounds[partition_num..].iter().sum::<usize>();
+            broken_matches += parallel_solver.primal_module.unit_broken_matches().iter().sum::<usize>();
+            parallel_solver.clear();
+            serial_solver.clear();
+        }
+        println!("fusion_rounds: {fusion_rounds}, broken_matches: {broken_matches}");
+        assert!(fusion_rounds > 0);
+        assert!(broken_matches > 0);
+        assert_eq!(
+            parallel_solver.primal_module.unit_rounds(),
+            vec![0; partition_info.units.len()]
+        );
+    }
+
+    /// a defect matched to a virtual vertex that is mirrored from the interface keeps its match after fusion,
+    /// because the vertex is still virtual in the fused unit
+    #[test]
+    fn example_partition_fusion_rounds_2() {
+        // cargo test example_partition_fusion_rounds_2 -- --nocapture
+        use super::super::mwpm_solver::*;
+        // a chain of 12 vertices, where vertex 0, 5 and 11 are virtual and vertex 5 is the interface
+        let initializer = SolverInitializer::new(12, (0..11).map(|i| (i, i + 1, 100)).collect(), vec![0, 5, 11]);
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = vec![VertexRange::new(0, 5), VertexRange::new(6, 12)];
+        partition_config.fusions = vec![(0, 1)];
+        let partition_info = partition_config.info();
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+        parallel_solver.solve(&SyndromePattern::new_vertices(vec![4, 6]));
+        assert_eq!(parallel_solver.sum_dual_variables(), 200);
+        assert_eq!(
+            parallel_solver.primal_module.unit_rounds()[2],
+            0,
+            "fusion shouldn't break the matches"
+        );
+        assert_eq!(parallel_solver.primal_module.unit_broken_matches()[2], 0);
+        // when the interface vertex is not virtual, both matches are broken but the nodes keep their dual variables,
+        // so they meet at the interface vertex and a single round resolves the conflict without growing any further
+        let initializer = SolverInitializer::new(12, (0..11).map(|i| (i, i + 1, 100)).collect(), vec![0, 11]);
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+        parallel_solver.solve(&SyndromePattern::new_vertices(vec![4, 6]));
+        assert_eq!(parallel_solver.sum_dual_variables(), 200);
+        assert_eq!(parallel_solver.primal_module.unit_broken_matches(), vec![0, 0, 2]);
+        assert_eq!(parallel_solver.primal_module.unit_rounds()[2], 1);
+    }
+
+    /// the same partitions fused by a sequential plan and a balanced plan decode identically, while the timeline of each
+    /// fusion unit follows its own plan
+    #[test]
+    fn example_partition_fusion_plan_1() {
+        // cargo test example_partition_fusion_plan_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (5, 7);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.02, 500);
+        let initializer = code.get_initializer();
+        let sequential_config = time_partition_config(d * (d + 1), noisy_measurements + 1, 4, false, usize::MAX);
+        assert_eq!(sequential_config.fusions, vec![(0, 1), (4, 2), (5, 3)]);
+        let balanced_plan = vec![(0, 1), (2, 3), (4, 5)];
+        let balanced_config = sequential_config.clone().with_fusion_plan(balanced_plan.clone()).unwrap();
+        let sequential_info = sequential_config.info();
+        let balanced_info = balanced_config.info();
+        assert_eq!(balanced_info.fusion_plan(), balanced_plan);
+        let mut sequential_solver = SolverParallel::new(&initializer, &sequential_info, json!({}));
+        let mut balanced_solver = SolverParallel::new(&initializer, &balanced_info, json!({}));
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            sequential_solver.solve(&syndrome_pattern);
+            balanced_solver.solve(&syndrome_pattern);
+            assert_eq!(
+                sequential_solver.sum_dual_variables(),
+                balanced_solver.sum_dual_variables(),
+                "seed {seed}"
+            );
+            assert_eq!(sequential_solver.subgraph(), balanced_solver.subgraph(), "seed {seed}");
+            for (solver, partition_info) in [(&sequential_solver, &sequential_info), (&balanced_solver, &balanced_info)] {
+                let report = solver.generate_profiler_report();
+                assert_eq!(report["primal"]["fusion_plan"], json!(partition_info.fusion_plan()));
+                let event_time_vec = report["primal"]["event_time_vec"].as_array().unwrap();
+                for (fusion_index, (left_index, right_index)) in partition_info.fusion_plan().into_iter().enumerate() {
+                    let start = event_time_vec[4 + fusion_index]["start"].as_f64().unwrap();
+                    for child in [left_index, right_index] {
+                        assert!(start >= event_time_vec[child]["end"].as_f64().unwrap(), "seed {seed}");
+                    }
+                }
+            }
+            sequential_solver.clear();
+            balanced_solver.clear();
+        }
+    }
+
+    /// a fusion plan that doesn't form a single tree is rejected with the reasons
+    #[test]
+    fn example_partition_fusion_plan_2() {
+        // cargo test example_partition_fusion_plan_2 -- --nocapture
+        let config = time_partition_config(30, 8, 4, false, usize::MAX);
+        assert!(config
+            .clone()
+            .with_fusion_plan(vec![(0, 1), (1, 2), (4, 3)])
+            .unwrap_err()
+            .contains(&PartitionIssue::FusedTwice { unit_index: 1 }));
+        assert!(config
+            .clone()
+            .with_fusion_plan(vec![(0, 1), (2, 3)])
+            .unwrap_err()
+            .contains(&PartitionIssue::NotFused { unit_index: 4 }));
+        assert!(matches!(
+            config.clone().with_fusion_plan(vec![(1, 0), (4, 2), (5, 3)]).unwrap_err()[0],
+            PartitionIssue::MisorderedFusion { fusion_index: 0, .. }
+        ));
+        assert!(config.with_fusion_plan(vec![(0, 1), (4, 2), (5, 3)]).is_ok());
+    }
 }
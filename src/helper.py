@@ -25,9 +25,21 @@ def serve(host='localhost', port=51665, data_folder=".", return_server=False, qu
             response.content_type = 'text/javascript; charset=utf8'
         elif filename.endswith("json"):
             response.content_type = 'application/json; charset=utf8'
+    def guess_encoding(filename):
+        if filename.endswith(".json.gz"):
+            return "gzip"
+        elif filename.endswith(".json.zst"):
+            return "zstd"
+        return None
     @route('/data/<filename:path>')
     def send_data(filename):
-        return static_file(filename, root=data_folder)
+        file_response = static_file(filename, root=data_folder)
+        encoding = guess_encoding(filename)
+        if encoding is not None and file_response.status_code == 200:
+            # let the browser decompress the visualizer data transparently
+            file_response.set_header("Content-Type", "application/json; charset=utf8")
+            file_response.set_header("Content-Encoding", encoding)
+        return file_response
     @route('/')
     @route('/<filename:path>')
     def send_root(filename=""):
@@ -1,51 +1,165 @@
-#![cfg_attr(feature = "unsafe_pointer", feature(get_mut_unchecked))]
 #![cfg_attr(feature = "unsafe_pointer", allow(unused_mut))]
-#![cfg_attr(feature = "python_binding", feature(cfg_eval))]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
 extern crate cfg_if;
+#[cfg(not(feature = "std"))]
+extern crate hashbrown;
 extern crate libc;
+#[cfg(not(feature = "std"))]
+extern crate libm;
+#[cfg(feature = "std")]
 extern crate parking_lot;
 extern crate priority_queue;
 extern crate rand_xoshiro;
 extern crate serde;
 #[macro_use]
 extern crate serde_json;
+#[cfg(feature = "std")]
 extern crate chrono;
+#[cfg(feature = "cli")]
 extern crate clap;
+#[cfg(feature = "parallel")]
 extern crate core_affinity;
 extern crate derivative;
-#[cfg(test)]
+#[cfg(any(test, feature = "petgraph"))]
 extern crate petgraph;
 #[cfg(feature = "python_binding")]
 extern crate pyo3;
 #[cfg(feature = "qecp_integrate")]
 pub extern crate qecp;
+#[cfg(feature = "std")]
 extern crate rand;
+#[cfg(feature = "parallel")]
 extern crate rayon;
+#[cfg(feature = "logging")]
+extern crate tracing;
+#[cfg(feature = "logging")]
+extern crate tracing_subscriber;
+#[cfg(feature = "std")]
 extern crate urlencoding;
 #[cfg(feature = "wasm_binding")]
 extern crate wasm_bindgen;
+#[cfg(feature = "std")]
 extern crate weak_table;
 
+/// the `std` items that the core uses, taken from `alloc` (and `hashbrown` for the hash tables) so that the same imports
+/// work without `std`; every module of the core starts with `use crate::prelude::*`
+mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+    #[cfg(not(feature = "std"))]
+    pub use hashbrown::{HashMap, HashSet};
+    #[cfg(feature = "std")]
+    pub use std::collections::{HashMap, HashSet};
+
+    /// the `f64` methods that only `std` provides, computed by `libm` without it; the inherent methods still take
+    /// precedence when a dependency links `std` anyway, e.g. on a hosted target
+    #[cfg(not(feature = "std"))]
+    #[allow(dead_code)]
+    pub trait FloatMath {
+        fn round(self) -> f64;
+        fn ceil(self) -> f64;
+        fn sqrt(self) -> f64;
+        fn ln(self) -> f64;
+        fn exp(self) -> f64;
+    }
+
+    #[cfg(not(feature = "std"))]
+    impl FloatMath for f64 {
+        fn round(self) -> f64 {
+            libm::round(self)
+        }
+        fn ceil(self) -> f64 {
+            libm::ceil(self)
+        }
+        fn sqrt(self) -> f64 {
+            libm::sqrt(self)
+        }
+        fn ln(self) -> f64 {
+            libm::log(self)
+        }
+        fn exp(self) -> f64 {
+            libm::exp(self)
+        }
+    }
+}
+
+/// without `std` there is no standard output, the debug prints of the core are only formatted to check the arguments
+#[cfg(not(feature = "std"))]
+#[allow(unused_macros)]
+macro_rules! println {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
+#[cfg(not(feature = "std"))]
+macro_rules! eprintln {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+/// report a warning with structured fields, e.g. `log_warn!(vertex_index = 3; "vertex {} is isolated", 3)`; it's a
+/// `tracing::warn!` event with the `logging` feature and a plain `eprintln!` of the message otherwise
+#[cfg(feature = "logging")]
+macro_rules! log_warn {
+    ($($field:ident = $value:expr),* ; $($arg:tt)+) => {
+        tracing::warn!($($field = $value,)* $($arg)+)
+    };
+}
+#[cfg(not(feature = "logging"))]
+macro_rules! log_warn {
+    ($($field:ident = $value:expr),* ; $($arg:tt)+) => {
+        eprintln!($($arg)+)
+    };
+}
+
+/// enter a `tracing` span until the end of the current block, e.g. `log_span!(DEBUG, "grow", length)`; it expands to
+/// nothing without the `logging` feature, so the field expressions are not even evaluated
+#[cfg(feature = "logging")]
+macro_rules! log_span {
+    ($level:ident, $name:expr $(, $($field:tt)+)?) => {
+        let _log_span_guard = tracing::span!(tracing::Level::$level, $name $(, $($field)+)?).entered();
+    };
+}
+#[cfg(not(feature = "logging"))]
+macro_rules! log_span {
+    ($level:ident, $name:expr $(, $($field:tt)+)?) => {};
+}
+
 pub mod blossom_v;
+pub mod certificate;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod complete_graph;
 pub mod dual_module;
+#[cfg(feature = "parallel")]
 pub mod dual_module_parallel;
 pub mod dual_module_serial;
+#[cfg(feature = "std")]
 pub mod example_codes;
+#[cfg(feature = "std")]
 pub mod example_partition;
 pub mod mwpm_solver;
 pub mod pointers;
 pub mod primal_module;
+#[cfg(feature = "parallel")]
 pub mod primal_module_parallel;
 pub mod primal_module_serial;
+#[cfg(feature = "stim_dem")]
+pub mod stim_dem;
 pub mod util;
 pub mod visualize;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
 
 use complete_graph::*;
+use prelude::*;
 use util::*;
 
 #[cfg(feature = "python_binding")]
@@ -56,6 +170,7 @@ fn fusion_blossom(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     example_codes::register(py, m)?;
     visualize::register(py, m)?;
     primal_module::register(py, m)?;
+    stim_dem::register(py, m)?;
     let helper_code = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/helper.py"));
     let helper_module = PyModule::from_code(py, helper_code, "helper", "helper")?;
     helper_module.add("visualizer_website", generate_visualizer_website(py))?;
@@ -72,7 +187,7 @@ fn fusion_blossom(py: Python<'_>, m: &PyModule) -> PyResult<()> {
 pub fn fusion_mwpm(initializer: &SolverInitializer, syndrome_pattern: &SyndromePattern) -> Vec<VertexIndex> {
     // sanity check
     assert!(initializer.vertex_num > 1, "at least one vertex required");
-    let max_safe_weight = ((Weight::MAX as usize) / initializer.vertex_num as usize) as Weight;
+    let max_safe_weight = max_safe_weight(Weight::MAX as i128, initializer.vertex_num);
     for (i, j, weight) in initializer.weighted_edges.iter() {
         if weight > &max_safe_weight {
             panic!(
@@ -85,6 +200,20 @@ pub fn fusion_mwpm(initializer: &SolverInitializer, syndrome_pattern: &SyndromeP
     mwpm_solver::LegacySolverSerial::mwpm_solve(initializer, syndrome_pattern)
 }
 
+/// decode with fusion blossom and also compute the greedy nearest-neighbor baseline of
+/// [`util::SolverInitializer::greedy_weight_bound`], returning `(mwpm_weight, greedy_weight)`; the ratio
+/// `greedy_weight / mwpm_weight` is a cheap diagnostic of the syndrome: close to 1 means the defects are isolated and easy
+/// to match, while a large gap flags complex clusters. Note that the greedy baseline ignores erasures and is
+/// [`Weight::MAX`] if the greedy assignment fails to match all the defects
+pub fn decode_with_greedy_baseline(initializer: &SolverInitializer, syndrome_pattern: &SyndromePattern) -> (Weight, Weight) {
+    use mwpm_solver::PrimalDualSolver;
+    let mut solver = mwpm_solver::SolverSerial::new(initializer);
+    solver.solve(syndrome_pattern);
+    let mwpm_weight = solver.sum_dual_variables();
+    let greedy_weight = initializer.greedy_weight_bound(&syndrome_pattern.defect_vertices);
+    (mwpm_weight, greedy_weight)
+}
+
 /// fall back to use blossom V library to solve MWPM (install blossom V required)
 #[allow(clippy::unnecessary_cast)]
 pub fn blossom_v_mwpm(initializer: &SolverInitializer, defect_vertices: &[VertexIndex]) -> Vec<VertexIndex> {
@@ -94,25 +223,53 @@ pub fn blossom_v_mwpm(initializer: &SolverInitializer, defect_vertices: &[Vertex
     }
     // sanity check
     assert!(initializer.vertex_num > 1, "at least one vertex required");
-    let max_safe_weight = ((i32::MAX as usize) / initializer.vertex_num as usize) as Weight;
+    let max_safe_weight = max_safe_weight(i32::MAX as i128, initializer.vertex_num);
     for (i, j, weight) in initializer.weighted_edges.iter() {
-        if weight > &max_safe_weight {
+        // with `wide_weights`, large weights are scaled down to fit into blossom V, see [`blossom_v::legacy_weighted_edges`]
+        if cfg!(not(feature = "wide_weights")) && weight > &max_safe_weight {
             panic!(
                 "edge {}-{} has weight {} > max safe weight {}, it may cause blossom V library to overflow",
                 i, j, weight, max_safe_weight
             );
         }
     }
-    let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+    let mut complete_graph = CompleteGraph::from_initializer(initializer);
     blossom_v_mwpm_reuse(&mut complete_graph, initializer, defect_vertices)
 }
 
+/// same as [`blossom_v_mwpm`] but reusing the complete graph; the edges are sorted before calling blossom V so that the
+/// result only depends on the defect order. When the MWPM is degenerate, the matched pairs may still differ from
+/// those of another matcher (e.g. fusion blossom) of the same total weight, so only the weight should be compared
 #[allow(clippy::unnecessary_cast)]
 pub fn blossom_v_mwpm_reuse(
     complete_graph: &mut CompleteGraph,
     initializer: &SolverInitializer,
     defect_vertices: &[VertexIndex],
 ) -> Vec<VertexIndex> {
+    let (legacy_vertex_num, mut legacy_weighted_edges, boundaries) =
+        to_legacy_matching_problem(complete_graph, initializer, defect_vertices);
+    legacy_weighted_edges.sort_unstable();
+    // run blossom V to get matchings
+    let legacy_weighted_edges = blossom_v::legacy_weighted_edges(legacy_vertex_num, &legacy_weighted_edges);
+    let matchings = blossom_v::safe_minimum_weight_perfect_matching(legacy_vertex_num, &legacy_weighted_edges);
+    from_legacy_matching(defect_vertices, &boundaries, &matchings)
+}
+
+/// the nearest virtual vertex of each defect and the weight to reach it, `None` if the defect cannot reach any virtual
+/// vertex; used to translate the result of [`to_legacy_matching_problem`] back, see [`from_legacy_matching`]
+pub type LegacyBoundaryMap = Vec<Option<(VertexIndex, Weight)>>;
+
+/// transform the decoding problem into a minimum-weight perfect matching problem on a complete graph that any general
+/// MWPM library (e.g. blossom V) can solve: the `i`-th defect becomes vertex `i`, and it has a mirror virtual vertex
+/// `i + defect_num` connected with the weight to its nearest virtual vertex; defects are connected by their shortest
+/// path weights, and the mirror virtual vertices are fully connected with weight 0 so that they can pair up freely.
+/// Returns the number of vertices, the weighted edges and the boundary map to translate the matching back
+#[allow(clippy::unnecessary_cast)]
+pub fn to_legacy_matching_problem(
+    complete_graph: &mut CompleteGraph,
+    initializer: &SolverInitializer,
+    defect_vertices: &[VertexIndex],
+) -> (usize, Vec<(usize, usize, Weight)>, LegacyBoundaryMap) {
     // first collect virtual vertices and real vertices
     let mut is_virtual: Vec<bool> = (0..initializer.vertex_num).map(|_| false).collect();
     let mut is_defect: Vec<bool> = (0..initializer.vertex_num).map(|_| false).collect();
@@ -132,7 +289,7 @@ pub fn blossom_v_mwpm_reuse(
     // for each real vertex, add a corresponding virtual vertex to be matched
     let defect_num = defect_vertices.len();
     let legacy_vertex_num = defect_num * 2;
-    let mut legacy_weighted_edges = Vec::<(usize, usize, u32)>::new();
+    let mut legacy_weighted_edges = Vec::<(usize, usize, Weight)>::new();
     let mut boundaries = Vec::<Option<(VertexIndex, Weight)>>::new();
     for (i, &defect_vertex) in defect_vertices.iter().enumerate() {
         let complete_graph_edges = complete_graph.all_edges(defect_vertex);
@@ -144,7 +301,7 @@ pub fn blossom_v_mwpm_reuse(
         }
         if let Some((_, weight)) = boundary {
             // connect this real vertex to it's corresponding virtual vertex
-            legacy_weighted_edges.push((i, i + defect_num, weight as u32));
+            legacy_weighted_edges.push((i, i + defect_num, weight));
         }
         boundaries.push(boundary); // save for later resolve legacy matchings
         for (&peer, &(_, weight)) in complete_graph_edges.iter() {
@@ -152,7 +309,7 @@ pub fn blossom_v_mwpm_reuse(
                 let j = mapping_to_defect_vertices[peer as usize];
                 if i < j {
                     // remove duplicated edges
-                    legacy_weighted_edges.push((i, j, weight as u32));
+                    legacy_weighted_edges.push((i, j, weight));
                     // println!{"edge {} {} {} ", i, j, weight};
                 }
             }
@@ -162,10 +319,18 @@ pub fn blossom_v_mwpm_reuse(
             legacy_weighted_edges.push((i + defect_num, j + defect_num, 0));
         }
     }
-    // run blossom V to get matchings
-    // println!("[debug] legacy_vertex_num: {:?}", legacy_vertex_num);
-    // println!("[debug] legacy_weighted_edges: {:?}", legacy_weighted_edges);
-    let matchings = blossom_v::safe_minimum_weight_perfect_matching(legacy_vertex_num, &legacy_weighted_edges);
+    (legacy_vertex_num, legacy_weighted_edges, boundaries)
+}
+
+/// translate the perfect matching of the problem given by [`to_legacy_matching_problem`] back to the decoding graph,
+/// where `matchings[i]` is the vertex matched to vertex `i`; the result is the peer or virtual vertex of each defect,
+/// in the same format as [`blossom_v_mwpm`]
+pub fn from_legacy_matching(
+    defect_vertices: &[VertexIndex],
+    boundaries: &LegacyBoundaryMap,
+    matchings: &[usize],
+) -> Vec<VertexIndex> {
+    let defect_num = defect_vertices.len();
     let mut mwpm_result = Vec::new();
     for i in 0..defect_num {
         let j = matchings[i];
@@ -217,7 +382,7 @@ pub fn detailed_matching(
         is_defect[defect_vertex as usize] = true;
     }
     assert_eq!(defect_num, mwpm_result.len(), "invalid mwpm result");
-    let mut complete_graph = complete_graph::CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+    let mut complete_graph = complete_graph::CompleteGraph::from_initializer(initializer);
     let mut details = Vec::new();
     for i in 0..defect_num {
         let a = defect_vertices[i];
@@ -231,6 +396,28 @@ pub fn detailed_matching(
     details
 }
 
+/// the edges of the original decoding graph that realize a matching, i.e. the correction to apply to the physical qubits:
+/// the two vertices of each matched pair are connected by a minimum-weight path in the original graph, and the paths
+/// are XOR-accumulated, so that an edge used by an even number of paths cancels out. The pairs are either defect pairs
+/// or a defect and a virtual vertex, e.g. from [`primal_module::PerfectMatching::matched_pairs`] or
+/// [`primal_module::MatchingDiff::legacy_matched_pairs`]; panics if a pair is not connected. This is what
+/// [`primal_module::SubGraphBuilder`] does for the solvers, without erasures or dynamic weights
+pub fn reconstruct_error_edges(
+    initializer: &SolverInitializer,
+    matched_pairs: &[(VertexIndex, VertexIndex)],
+) -> Vec<EdgeIndex> {
+    let mut subgraph_builder = primal_module::SubGraphBuilder::new(initializer);
+    for &(vertex_1, vertex_2) in matched_pairs.iter() {
+        subgraph_builder.add_matching(vertex_1, vertex_2);
+    }
+    subgraph_builder.get_subgraph()
+}
+
+/// the total weight of the detailed matchings, or an error if it overflows `Weight`
+pub fn detailed_matching_total_weight(details: &[DetailedMatching]) -> Result<Weight, String> {
+    checked_weight_sum(details.iter().map(|detail| detail.weight))
+}
+
 #[cfg(feature = "python_binding")]
 macro_rules! include_visualize_file {
     ($mapping:ident, $filepath:expr) => {
@@ -245,7 +432,7 @@ macro_rules! include_visualize_file {
 #[cfg(feature = "python_binding")]
 fn generate_visualizer_website(py: Python<'_>) -> &pyo3::types::PyDict {
     use pyo3::types::IntoPyDict;
-    let mut mapping = std::collections::BTreeMap::<String, String>::new();
+    let mut mapping = alloc::collections::BTreeMap::<String, String>::new();
     include_visualize_file!(
         mapping,
         "gui3d.js",
@@ -267,3 +454,135 @@ use wasm_bindgen::prelude::*;
 pub fn get_version() -> String {
     "hello world".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::example_codes::*;
+    use super::mwpm_solver::*;
+    use super::*;
+
+    /// an exhaustive minimum-weight perfect matching for tiny graphs, standing in for an external library
+    fn exhaustive_minimum_weight_perfect_matching(
+        vertex_num: usize,
+        weighted_edges: &[(usize, usize, Weight)],
+    ) -> (Weight, Vec<usize>) {
+        let mut weights = vec![vec![None; vertex_num]; vertex_num];
+        for &(i, j, weight) in weighted_edges.iter() {
+            weights[i][j] = Some(weight);
+            weights[j][i] = Some(weight);
+        }
+        fn search(weights: &[Vec<Option<Weight>>], matchings: &mut Vec<usize>) -> Option<(Weight, Vec<usize>)> {
+            let Some(i) = matchings.iter().position(|&peer| peer == usize::MAX) else {
+                return Some((0, matchings.clone()));
+            };
+            let mut best: Option<(Weight, Vec<usize>)> = None;
+            for j in (i + 1)..matchings.len() {
+                if let (usize::MAX, Some(weight)) = (matchings[j], weights[i][j]) {
+                    matchings[i] = j;
+                    matchings[j] = i;
+                    if let Some((total, result)) = search(weights, matchings) {
+                        if best.as_ref().is_none_or(|(best_total, _)| total + weight < *best_total) {
+                            best = Some((total + weight, result));
+                        }
+                    }
+                    matchings[i] = usize::MAX;
+                    matchings[j] = usize::MAX;
+                }
+            }
+            best
+        }
+        search(&weights, &mut vec![usize::MAX; vertex_num]).expect("no perfect matching")
+    }
+
+    #[test]
+    fn lib_legacy_matching_problem_1() {
+        // cargo test lib_legacy_matching_problem_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut complete_graph = CompleteGraph::from_initializer(&initializer);
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..20 {
+            let mut syndrome_pattern = code.generate_random_errors(seed);
+            syndrome_pattern.defect_vertices.truncate(5);
+            let defect_vertices = &syndrome_pattern.defect_vertices;
+            let (vertex_num, weighted_edges, boundaries) =
+                to_legacy_matching_problem(&mut complete_graph, &initializer, defect_vertices);
+            assert_eq!(vertex_num, 2 * defect_vertices.len());
+            let (weight, matchings) = exhaustive_minimum_weight_perfect_matching(vertex_num, &weighted_edges);
+            solver.solve(&syndrome_pattern);
+            assert_eq!(weight, solver.sum_dual_variables(), "seed {seed}");
+            solver.clear();
+            // every defect is translated to its peer defect or the nearest virtual vertex
+            let mwpm_result = from_legacy_matching(defect_vertices, &boundaries, &matchings);
+            for (i, &matched) in mwpm_result.iter().enumerate() {
+                match defect_vertices.iter().position(|&vertex| vertex == matched) {
+                    Some(j) => assert_eq!(mwpm_result[j], defect_vertices[i]),
+                    None => assert_eq!(Some(matched), boundaries[i].map(|(virtual_vertex, _)| virtual_vertex)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn lib_reconstruct_error_edges_1() {
+        // cargo test lib_reconstruct_error_edges_1 -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(7, 3, 0.03, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let matched_pairs = solver.perfect_matching().matched_pairs();
+            let error_edges = reconstruct_error_edges(&initializer, &matched_pairs);
+            // the solver may realize a pair by another path of the same weight
+            let weight_of = |edges: &[EdgeIndex]| -> Weight {
+                edges
+                    .iter()
+                    .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                    .sum()
+            };
+            assert_eq!(weight_of(&error_edges), weight_of(&solver.subgraph()));
+            let defect_vertices: alloc::collections::BTreeSet<_> =
+                syndrome_pattern.defect_vertices.iter().copied().collect();
+            assert_eq!(initializer.syndrome_of(&error_edges), defect_vertices);
+            // the legacy format gives the same pairs
+            let mwpm_result = fusion_mwpm(&initializer, &syndrome_pattern);
+            let legacy_pairs =
+                primal_module::MatchingDiff::legacy_matched_pairs(&syndrome_pattern.defect_vertices, &mwpm_result);
+            let legacy_error_edges = reconstruct_error_edges(&initializer, &legacy_pairs);
+            assert_eq!(initializer.syndrome_of(&legacy_error_edges), defect_vertices);
+            solver.clear();
+        }
+        // two paths sharing an edge cancel it out: 0 - 1 - 2 - 3 with pairs (0, 2) and (1, 3)
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 2), (1, 2, 2), (2, 3, 2)], vec![]);
+        assert_eq!(reconstruct_error_edges(&initializer, &[(0, 2), (1, 3)]), vec![0, 2]);
+    }
+
+    #[test]
+    fn lib_decode_with_greedy_baseline_1() {
+        // cargo test lib_decode_with_greedy_baseline_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(11, 0.05, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let (mwpm_weight, greedy_weight) = decode_with_greedy_baseline(&initializer, &syndrome_pattern);
+            solver.solve(&syndrome_pattern);
+            assert_eq!(mwpm_weight, solver.sum_dual_variables(), "seed {seed}");
+            solver.clear();
+            assert!(mwpm_weight <= greedy_weight, "seed {seed}");
+        }
+        // an isolated pair is easy: greedy is optimal
+        let (mwpm_weight, greedy_weight) =
+            decode_with_greedy_baseline(&initializer, &SyndromePattern::new_vertices(vec![13, 14]));
+        assert_eq!(mwpm_weight, 1000);
+        assert_eq!(greedy_weight, 1000);
+        // greedy takes the cheap middle pair of 0 - 1 - 2 - 3 and then pays for the far pair
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 10), (1, 2, 8), (2, 3, 10)], vec![]);
+        let (mwpm_weight, greedy_weight) =
+            decode_with_greedy_baseline(&initializer, &SyndromePattern::new_vertices(vec![0, 1, 2, 3]));
+        assert_eq!(mwpm_weight, 20);
+        assert_eq!(greedy_weight, 36);
+    }
+}
@@ -4,34 +4,55 @@
 //! Note that you can call different primal and dual modules, even interchangeably, by following the examples in this file
 //!
 
-use std::collections::{BTreeMap, BTreeSet};
+#![cfg_attr(feature = "unsafe_pointer", allow(clippy::needless_borrow))]
+
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use alloc::collections::BTreeSet;
+#[cfg(feature = "parallel")]
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::prelude::*;
+#[cfg(feature = "std")]
 use std::io::BufWriter;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "std")]
 use nonzero::nonzero as nz;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "std")]
 use crate::blossom_v;
+#[cfg(feature = "std")]
 use crate::complete_graph::*;
 use crate::derivative::Derivative;
 use crate::dual_module::*;
 
+use super::certificate::Certificate;
 use super::dual_module::{DualModuleImpl, DualModuleInterfacePtr};
+#[cfg(feature = "parallel")]
 use super::dual_module_parallel::*;
 use super::dual_module_serial::DualModuleSerial;
 use super::pointers::*;
-use super::primal_module::{PerfectMatching, PrimalModuleImpl, SubGraphBuilder, VisualizeSubgraph};
+use super::primal_module::{
+    DecodeStats, ErasurePeeling, MatchingError, PerfectMatching, PrimalModuleImpl, SubGraphBuilder, VisualizeSubgraph,
+};
+#[cfg(feature = "parallel")]
 use super::primal_module_parallel::*;
 use super::primal_module_serial::PrimalModuleSerialPtr;
 use super::util::*;
 use super::visualize::*;
 
 /// a serial solver
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Derivative)]
 #[derivative(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct LegacySolverSerial {
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
@@ -138,8 +159,154 @@ impl LegacySolverSerial {
     }
 }
 
+/// the error returned by [`PrimalDualSolver::try_solve`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// the syndrome pattern doesn't fit the decoding graph; the solver is untouched
+    InvalidSyndrome(String),
+    /// a connected component (labeled by its smallest vertex index) has no virtual vertex but an odd number of
+    /// defects, so no perfect matching exists; see [`OddParityPolicy`] to repair it instead
+    OddParity { component: VertexIndex, defect_count: usize },
+    /// the solver panicked with this message; if `cleared`, the solver has been reset and can be reused
+    Panicked { message: String, cleared: bool },
+}
+
+impl core::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidSyndrome(message) => write!(f, "invalid syndrome: {message}"),
+            Self::OddParity { component, defect_count } => write!(
+                f,
+                "component {component} has no virtual vertex but an odd number of defects ({defect_count})"
+            ),
+            Self::Panicked { message, cleared } => {
+                write!(f, "solver panicked: {message}")?;
+                if !cleared {
+                    write!(f, " (the solver could not be cleared)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for SolveError {}
+
+/// the error returned by [`SolverSerial::load_external_matching`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// a vertex index is not in the decoding graph
+    VertexOutOfRange { vertex: VertexIndex, vertex_num: VertexNum },
+    /// the pairs don't form a perfect matching of the loaded syndrome
+    Matching(MatchingError),
+    /// the two vertices of a pair are not connected in the decoding graph
+    Disconnected { vertex_1: VertexIndex, vertex_2: VertexIndex },
+}
+
+impl core::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::VertexOutOfRange { vertex, vertex_num } => {
+                write!(
+                    f,
+                    "vertex {vertex} is out of range, the decoding graph has {vertex_num} vertices"
+                )
+            }
+            Self::Matching(error) => write!(f, "not a perfect matching: {error}"),
+            Self::Disconnected { vertex_1, vertex_2 } => {
+                write!(f, "vertices {vertex_1} and {vertex_2} are not connected")
+            }
+        }
+    }
+}
+
+impl core::error::Error for LoadError {}
+
+impl From<MatchingError> for LoadError {
+    fn from(error: MatchingError) -> Self {
+        Self::Matching(error)
+    }
+}
+
+/// an owned summary of a decoding returned by [`PrimalDualSolver::solve_owned`]; unlike the pointer-based
+/// [`PerfectMatching`], it stays valid after [`PrimalDualSolver::clear`] and can be stored or sent to other threads.
+/// New fields may be added in the future, so it can only be constructed by the solvers
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct SolveResult {
+    /// the matched pairs (defect_vertex, peer_or_virtual_vertex), see [`PerfectMatching::matched_pairs`], including the
+    /// defects matched to a far virtual vertex by [`OddParityPolicy::MatchToNearestVirtualEvenIfFar`]
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub matched_pairs: Vec<(DefectIndex, VertexIndex)>,
+    /// the total weight of the minimum-weight perfect matching, i.e. the sum of dual variables; if
+    /// [`Self::defect_count_fallback`] applies, it is only a lower bound of that weight because the matching is approximate
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub weight: Weight,
+    /// the edges of the correction, see [`PrimalDualSolver::subgraph`]
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub subgraph: Vec<EdgeIndex>,
+    /// the defects removed to repair components with odd parity; the result is not the MWPM of the given syndrome
+    /// if this is not empty, see [`OddParityPolicy`]
+    pub odd_parity_repairs: Vec<OddParityRepair>,
+    /// whether the syndrome was decoded approximately because it has too many defects, see [`DefectCountFallback`]
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub defect_count_fallback: bool,
+    /// the time of [`PrimalDualSolver::solve`] in seconds, excluding the time to extract the result; always 0 without
+    /// `std`, which has no clock
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub solve_time: f64,
+}
+
+impl SolveResult {
+    /// whether the result is not the MWPM of the given syndrome, because the syndrome was modified before decoding
+    /// (e.g. by [`OddParityPolicy`]) or decoded approximately (e.g. by [`DefectCountFallback`])
+    pub fn is_degraded(&self) -> bool {
+        !self.odd_parity_repairs.is_empty() || self.defect_count_fallback
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl SolveResult {
+    #[getter]
+    fn get_odd_parity_repairs(&self) -> PyObject {
+        json_to_pyobject(serde_json::to_value(&self.odd_parity_repairs).unwrap())
+    }
+    #[pyo3(name = "is_degraded")]
+    fn py_is_degraded(&self) -> bool {
+        self.is_degraded()
+    }
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// the message of a caught panic
+#[cfg(feature = "std")]
+fn panic_message(payload: Box<dyn core::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
 pub trait PrimalDualSolver {
     fn clear(&mut self);
+    /// check that the solver is clean after [`PrimalDualSolver::clear`], e.g. without leftover erasures
+    fn check_clean(&self) -> Result<(), String> {
+        Ok(())
+    }
+    /// the preferred way to clear the solver between decodings: it clears the dual module (recovering the erasures first),
+    /// the primal module and the subgraph builder in the correct order, and then checks that no state is left behind
+    fn reset(&mut self) -> Result<(), String> {
+        self.clear();
+        self.check_clean()
+    }
     fn reset_profiler(&mut self) {} // only if profiler records some information that needs to be cleared, e.g. vec![]
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>);
     fn solve(&mut self, syndrome_pattern: &SyndromePattern) {
@@ -155,6 +322,101 @@ pub trait PrimalDualSolver {
     }
     fn sum_dual_variables(&self) -> Weight;
     fn generate_profiler_report(&self) -> serde_json::Value;
+    /// the number of edges in the decoding graph, if known; used to validate dense per-edge inputs
+    fn edge_num(&self) -> Option<usize> {
+        None
+    }
+    /// the number of vertices in the decoding graph, if known; used to validate the syndrome in [`PrimalDualSolver::try_solve`]
+    fn vertex_num(&self) -> Option<VertexNum> {
+        None
+    }
+    /// check the syndrome before solving in [`PrimalDualSolver::try_solve`]; by default only the indices are checked,
+    /// if the solver knows the size of the decoding graph
+    fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        if let (Some(vertex_num), Some(edge_num)) = (self.vertex_num(), self.edge_num()) {
+            syndrome_pattern
+                .check_indices(vertex_num, edge_num)
+                .map_err(SolveError::InvalidSyndrome)?;
+        }
+        Ok(())
+    }
+    /// the defects removed from the syndrome to repair components with odd parity, see [`OddParityPolicy`]
+    fn odd_parity_repairs(&self) -> Vec<OddParityRepair> {
+        vec![]
+    }
+    /// whether the last syndrome was decoded approximately because it has too many defects, see [`DefectCountFallback`]
+    fn defect_count_fallback_applied(&self) -> bool {
+        false
+    }
+    /// the dual variables and the correction after solving, which prove the optimality of the result without trusting
+    /// the solver, see [`crate::certificate::verify_certificate`]; `None` if the solver doesn't have dual variables
+    fn optimality_certificate(&mut self) -> Option<Certificate> {
+        None
+    }
+    /// solve the syndrome and collect the result into an owned [`SolveResult`], which is the recommended way to use the
+    /// solver as a library: the result remains valid after [`PrimalDualSolver::clear`] and doesn't depend on the
+    /// internal pointer graph; to also get the optimality certificate, see [`SolverSerial::solve_full`]
+    fn solve_owned(&mut self, syndrome_pattern: &SyndromePattern) -> SolveResult {
+        #[cfg(feature = "std")]
+        let begin = std::time::Instant::now();
+        self.solve(syndrome_pattern);
+        #[cfg(feature = "std")]
+        let solve_time = begin.elapsed().as_secs_f64();
+        #[cfg(not(feature = "std"))]
+        let solve_time = 0.;
+        let mut matched_pairs = self.perfect_matching().matched_pairs();
+        let subgraph = self.subgraph();
+        let odd_parity_repairs = self.odd_parity_repairs();
+        // the defects matched to a far virtual vertex are not in the perfect matching of the repaired syndrome
+        matched_pairs.extend(odd_parity_repairs.iter().filter_map(|repair| {
+            repair
+                .matched_virtual
+                .map(|virtual_vertex| (repair.removed_defect, virtual_vertex))
+        }));
+        matched_pairs.sort();
+        SolveResult {
+            matched_pairs,
+            weight: self.sum_dual_variables(),
+            subgraph,
+            odd_parity_repairs,
+            defect_count_fallback: self.defect_count_fallback_applied(),
+            solve_time,
+        }
+    }
+    /// like [`PrimalDualSolver::solve`] but returns an error instead of panicking, for services where one malformed
+    /// shot must not bring down the process: the syndrome is first checked against the decoding graph (if the solver
+    /// knows its size), and any panic during solving is caught, after which the solver is reset so it can be reused;
+    /// catching a panic requires `std`
+    #[cfg(feature = "std")]
+    fn try_solve(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        self.check_syndrome_pattern(syndrome_pattern)?;
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.solve(syndrome_pattern))) {
+            Ok(()) => Ok(()),
+            Err(payload) => {
+                let message = panic_message(payload);
+                let cleared = matches!(
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.reset())),
+                    Ok(Ok(()))
+                );
+                Err(SolveError::Panicked { message, cleared })
+            }
+        }
+    }
+    /// solve with erasures given as a dense per-edge mask instead of a list of edge indices
+    fn solve_with_erasure_mask(
+        &mut self,
+        defect_vertices: Vec<VertexIndex>,
+        erasure_mask: &[bool],
+        visualizer: Option<&mut Visualizer>,
+    ) -> Result<(), String> {
+        let edge_num = self
+            .edge_num()
+            .ok_or_else(|| "the solver doesn't know the number of edges".to_string())?;
+        let syndrome_pattern = SyndromePattern::new_erasure_mask(defect_vertices, erasure_mask, edge_num)?;
+        self.solve_visualizer(&syndrome_pattern, visualizer);
+        Ok(())
+    }
+    #[cfg(feature = "std")]
     #[allow(clippy::unnecessary_cast)]
     fn stim_integration_predict_bit_packed_data(
         &mut self,
@@ -167,10 +429,10 @@ pub trait PrimalDualSolver {
     ) {
         let mut in_reader = std::io::BufReader::new(File::open(in_file).expect("in_file not found"));
         let mut out_writer = std::io::BufWriter::new(File::create(out_file).expect("out_file not found"));
-        let num_det_bytes = (num_dets + 7) / 8; // ceil
+        let num_det_bytes = num_dets.div_ceil(8); // ceil
         let mut dets_bit_packed = vec![0; num_det_bytes];
         assert!(num_obs <= 64, "too many observables");
-        let prediction_bytes = (num_obs + 7) / 8; // ceil
+        let prediction_bytes = num_obs.div_ceil(8); // ceil
         for _ in 0..num_shots {
             in_reader.read_exact(&mut dets_bit_packed).expect("read success");
             let mut defect_vertices = vec![];
@@ -210,6 +472,10 @@ macro_rules! bind_trait_primal_dual_solver {
             fn trait_clear(&mut self) {
                 self.clear()
             }
+            #[pyo3(name = "reset")]
+            fn trait_reset(&mut self) -> PyResult<()> {
+                self.reset().map_err(pyo3::exceptions::PyRuntimeError::new_err)
+            }
             #[pyo3(name = "solve_visualizer")]
             fn trait_solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
                 self.solve_visualizer(syndrome_pattern, visualizer)
@@ -218,6 +484,16 @@ macro_rules! bind_trait_primal_dual_solver {
             fn trait_solve(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
                 self.solve_visualizer(syndrome_pattern, visualizer)
             }
+            #[pyo3(name = "solve_with_erasure_mask")]
+            fn trait_solve_with_erasure_mask(
+                &mut self,
+                defect_vertices: Vec<VertexIndex>,
+                erasure_mask: Vec<bool>,
+                visualizer: Option<&mut Visualizer>,
+            ) -> PyResult<()> {
+                self.solve_with_erasure_mask(defect_vertices, &erasure_mask, visualizer)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)
+            }
             #[pyo3(name = "perfect_matching_visualizer")]
             fn trait_perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
                 self.perfect_matching_visualizer(visualizer)
@@ -234,6 +510,10 @@ macro_rules! bind_trait_primal_dual_solver {
             fn trait_subgraph(&mut self, visualizer: Option<&mut Visualizer>) -> Vec<EdgeIndex> {
                 self.subgraph_visualizer(visualizer)
             }
+            #[pyo3(name = "solve_owned")]
+            fn trait_solve_owned(&mut self, syndrome_pattern: &SyndromePattern) -> SolveResult {
+                self.solve_owned(syndrome_pattern)
+            }
             #[pyo3(name = "sum_dual_variables")]
             fn trait_sum_dual_variables(&self) -> Weight {
                 self.sum_dual_variables()
@@ -258,15 +538,62 @@ macro_rules! bind_trait_primal_dual_solver {
     };
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+/// the serial solver; like all solvers it's `Send` under every pointer feature (see the compile-time checks below), so
+/// it can be moved into another thread or shared as `Arc<Mutex<SolverSerial>>`
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverSerial {
     pub dual_module: DualModuleSerial,
     pub primal_module: PrimalModuleSerialPtr,
     pub interface_ptr: DualModuleInterfacePtr,
     pub subgraph_builder: SubGraphBuilder,
+    /// try [`SolverSerial::decode_with_erasures_only`] first when solving, configured by `{"erasure_peeling": true}`
+    pub erasure_peeling: bool,
+    /// the result of [`SolverSerial::decode_with_erasures_only`] since the last clear, in which case the primal module
+    /// is bypassed
+    pub erasure_peeling_result: Option<ErasurePeeling>,
+    /// record the tight-edge path of every matched pair in the perfect matching, which the subgraph then follows
+    /// instead of the shortest paths of the complete graph, configured by `{"realized_paths": true}`; see
+    /// [`PerfectMatching::record_realized_paths`]
+    pub realized_paths: bool,
+    /// the bit budget of the largest weight in [`SolverSerial::update_weights_normalized`], configured by
+    /// `{"weight_bits": 24}`
+    pub weight_bits: u32,
+    /// the weight per unit of log-likelihood ratio of the last [`SolverSerial::update_weights_normalized`]
+    pub weight_scale: Option<f64>,
+    /// the weights of all edges set by [`SolverSerial::update_weights_normalized`], loaded as dynamic weights
+    pub normalized_weights: Vec<(EdgeIndex, Weight)>,
 }
 
+/// the default [`SolverSerial::weight_bits`]
+pub const DEFAULT_WEIGHT_BITS: u32 = 24;
+
+/// pin the auto traits of the solvers and the types crossing their API at compile time, so that a change that makes
+/// them lose `Send` or `Sync` (e.g. an `Rc` pointer) fails here instead of deep in a user's async runtime.
+///
+/// A solver is `Send`: every pointer inside it is an [`alloc::sync::Arc`] (or a raw pointer into one), and all the
+/// objects they point to are owned by the solver, so moving the solver moves the whole object graph to the other
+/// thread and nothing is left behind. A solver is only `Sync` with the lock-based pointers: with `unsafe_pointer`,
+/// `write(&self)` hands out `&mut` from a shared reference, so two threads holding `&SolverSerial` could alias through
+/// the public `interface_ptr`. The auto trait is still implemented in that mode but not promised, hence the `Sync`
+/// check is gated; sharing a solver goes through `Arc<Mutex<_>>`, which only needs `Send`
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    #[cfg(not(feature = "unsafe_pointer"))]
+    const fn assert_sync<T: Sync>() {}
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send::<SolverSerial>();
+    #[cfg(feature = "parallel")]
+    assert_send::<SolverParallel>();
+    #[cfg(not(feature = "unsafe_pointer"))]
+    assert_sync::<SolverSerial>();
+    #[cfg(all(feature = "parallel", not(feature = "unsafe_pointer")))]
+    assert_sync::<SolverParallel>();
+    assert_send_sync::<SolverInitializer>();
+    assert_send_sync::<SyndromePattern>();
+    assert_send_sync::<SolveResult>();
+};
+
 bind_trait_fusion_visualizer!(SolverSerial);
 impl FusionVisualizer for SolverSerial {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
@@ -280,18 +607,135 @@ impl FusionVisualizer for SolverSerial {
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverSerial}
 
+/// everything about a decoding returned by [`SolverSerial::solve_full`]: the matching, the correction and the proof that
+/// the correction has minimum weight, which can be checked by [`crate::certificate::verify_certificate`] without
+/// trusting the solver. Note that [`Self::perfect_matching`] refers to the dual nodes of the solver and is invalidated
+/// by [`PrimalDualSolver::clear`], while the other fields are owned
+#[derive(Debug)]
+pub struct FullResult {
+    /// the minimum-weight perfect matching, see [`PrimalDualSolver::perfect_matching`]
+    pub perfect_matching: PerfectMatching,
+    /// the edges of the correction, see [`PrimalDualSolver::subgraph`]
+    pub subgraph: Vec<EdgeIndex>,
+    /// the total weight of the minimum-weight perfect matching, i.e. the sum of dual variables
+    pub weight: Weight,
+    /// the dual variables and the correction, see [`PrimalDualSolver::optimality_certificate`]
+    pub certificate: Certificate,
+}
+
 #[cfg(feature = "python_binding")]
 #[pymethods]
 impl SolverSerial {
     #[new]
-    #[pyo3(signature = (initializer, *, max_tree_size = None))]
-    pub fn new_python(initializer: &SolverInitializer, max_tree_size: Option<usize>) -> Self {
+    #[pyo3(signature = (initializer, *, max_tree_size = None, erasure_peeling = false))]
+    pub fn new_python(initializer: &SolverInitializer, max_tree_size: Option<usize>, erasure_peeling: bool) -> Self {
         let mut solver = Self::new(initializer);
         if let Some(max_tree_size) = max_tree_size {
             solver.primal_module.write().max_tree_size = max_tree_size;
         }
+        solver.erasure_peeling = erasure_peeling;
         solver
     }
+    /// see [`SolverSerial::update_weights_normalized`]
+    #[pyo3(name = "update_weights_normalized")]
+    fn py_update_weights_normalized(&mut self, log_likelihood_ratios: Vec<f64>) -> f64 {
+        self.update_weights_normalized(&log_likelihood_ratios)
+    }
+    #[pyo3(name = "sum_dual_variables_llr")]
+    fn py_sum_dual_variables_llr(&self) -> f64 {
+        self.sum_dual_variables_llr()
+    }
+    /// load a syndrome without solving it, see [`SolverSerial::load_syndrome`]
+    #[pyo3(name = "load_syndrome")]
+    fn py_load_syndrome(&mut self, syndrome_pattern: &SyndromePattern) {
+        self.load_syndrome(syndrome_pattern)
+    }
+    #[pyo3(name = "dual_state")]
+    fn py_dual_state(&self) -> PyObject {
+        json_to_pyobject(self.dual_state())
+    }
+    /// `grow_state` is one of "grow", "stay" or "shrink"
+    #[pyo3(name = "set_grow_state")]
+    fn py_set_grow_state(&mut self, node_index: NodeIndex, grow_state: &str) -> PyResult<()> {
+        let grow_state = match grow_state {
+            "grow" => DualNodeGrowState::Grow,
+            "stay" => DualNodeGrowState::Stay,
+            "shrink" => DualNodeGrowState::Shrink,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown grow state {grow_state:?}, expecting \"grow\", \"stay\" or \"shrink\""
+                )))
+            }
+        };
+        self.set_grow_state(node_index, grow_state)
+            .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))
+    }
+    #[pyo3(name = "grow")]
+    fn py_grow(&mut self, length: Weight) -> PyResult<()> {
+        self.grow(length).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+    /// grow (or shrink, if negative) a single outer dual node, failing without any change if it's unsafe
+    #[pyo3(name = "grow_dual_node")]
+    fn py_grow_dual_node(&mut self, node_index: NodeIndex, length: Weight) -> PyResult<()> {
+        self.grow_dual_node(node_index, length)
+            .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))
+    }
+    /// verify the matching of another decoder against the loaded syndrome and return its weight
+    #[pyo3(name = "load_external_matching")]
+    fn py_load_external_matching(&mut self, pairs: Vec<(VertexIndex, VertexIndex)>) -> PyResult<Weight> {
+        self.load_external_matching(&pairs)
+            .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))
+    }
+    #[pyo3(name = "solve_local")]
+    fn py_solve_local(
+        &mut self,
+        syndrome_pattern: &SyndromePattern,
+        seed_defects: Vec<VertexIndex>,
+        radius: Weight,
+    ) -> Vec<EdgeIndex> {
+        self.solve_local(syndrome_pattern, &seed_defects, radius)
+    }
+    #[pyo3(name = "is_converged")]
+    fn py_is_converged(&mut self) -> bool {
+        self.is_converged()
+    }
+    /// `None` when nothing can grow, the maximum length to grow, or a list of conflicts each as a dict
+    #[pyo3(name = "compute_maximum_update_length")]
+    fn py_compute_maximum_update_length(&mut self) -> PyObject {
+        let node_index = |dual_node_ptr: &DualNodePtr| dual_node_ptr.read_recursive().index;
+        let value = match self.compute_maximum_update_length() {
+            group_max_update_length if group_max_update_length.is_empty() => json!(null),
+            GroupMaxUpdateLength::NonZeroGrow((length, _)) => json!(length),
+            GroupMaxUpdateLength::Conflicts((conflicts, _)) => json!(conflicts
+                .iter()
+                .map(|conflict| match conflict {
+                    MaxUpdateLength::NonZeroGrow(_) => unreachable!(),
+                    MaxUpdateLength::Conflicting((node_1, touching_1), (node_2, touching_2)) => json!({
+                        "type": "conflicting",
+                        "node_1": node_index(node_1),
+                        "touching_1": node_index(touching_1),
+                        "node_2": node_index(node_2),
+                        "touching_2": node_index(touching_2),
+                    }),
+                    MaxUpdateLength::TouchingVirtual((node, touching), (virtual_vertex, _)) => json!({
+                        "type": "touching_virtual",
+                        "node": node_index(node),
+                        "touching": node_index(touching),
+                        "virtual_vertex": virtual_vertex,
+                    }),
+                    MaxUpdateLength::BlossomNeedExpand(node) => json!({
+                        "type": "blossom_need_expand",
+                        "node": node_index(node),
+                    }),
+                    MaxUpdateLength::VertexShrinkStop((node, _)) => json!({
+                        "type": "vertex_shrink_stop",
+                        "node": node_index(node),
+                    }),
+                })
+                .collect::<Vec<_>>()),
+        };
+        json_to_pyobject(value)
+    }
 }
 
 impl SolverSerial {
@@ -301,18 +745,459 @@ impl SolverSerial {
             primal_module: PrimalModuleSerialPtr::new_empty(initializer),
             interface_ptr: DualModuleInterfacePtr::new_empty(),
             subgraph_builder: SubGraphBuilder::new(initializer),
+            erasure_peeling: false,
+            erasure_peeling_result: None,
+            realized_paths: false,
+            weight_bits: DEFAULT_WEIGHT_BITS,
+            weight_scale: None,
+            normalized_weights: vec![],
+        }
+    }
+
+    /// the options are `{"erasure_peeling": true}`, see [`Self::erasure_peeling`], `{"realized_paths": true}`, see
+    /// [`Self::realized_paths`], `{"compact_adjacency": true}` to store the edges around each vertex in a flat array,
+    /// see [`DualModuleSerial::new_compact_adjacency`], `{"reorder_edges": true}` to also store the edges in a
+    /// cache-friendly order, see [`DualModuleSerial::new_reordered`], `{"weight_bits": 24}`, see [`Self::update_weights_normalized`], and
+    /// `{"defect_count_fallback": {"threshold": 1000}}`, see [`DefectCountFallback`]
+    pub fn new_config(initializer: &SolverInitializer, mut config: serde_json::Value) -> Self {
+        let mut solver = Self::new(initializer);
+        let config = config.as_object_mut().expect("config must be JSON object");
+        if let Some(value) = config.remove("compact_adjacency") {
+            if value.as_bool().expect("compact_adjacency must be a boolean") {
+                solver.dual_module = DualModuleSerial::new_compact_adjacency(initializer);
+            }
+        }
+        if let Some(value) = config.remove("reorder_edges") {
+            if value.as_bool().expect("reorder_edges must be a boolean") {
+                solver.dual_module = DualModuleSerial::new_reordered(initializer);
+            }
+        }
+        if let Some(value) = config.remove("erasure_peeling") {
+            solver.erasure_peeling = value.as_bool().expect("erasure_peeling must be a boolean");
+        }
+        if let Some(value) = config.remove("realized_paths") {
+            solver.realized_paths = value.as_bool().expect("realized_paths must be a boolean");
+        }
+        if let Some(value) = config.remove("weight_bits") {
+            let weight_bits = value.as_u64().expect("weight_bits must be a positive integer");
+            assert!(
+                (2..=62).contains(&weight_bits),
+                "weight_bits must be between 2 and 62, got {weight_bits}"
+            );
+            solver.weight_bits = weight_bits as u32;
+        }
+        if let Some(value) = config.remove("defect_count_fallback") {
+            solver.subgraph_builder.defect_count_fallback = serde_json::from_value(value).unwrap();
+        }
+        if !config.is_empty() {
+            panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+        }
+        solver
+    }
+
+    /// solve the syndrome and return the matching, the correction, its weight and the optimality certificate together;
+    /// this is the recommended entry point if one wants to both apply the correction and verify its optimality, as
+    /// the perfect matching is computed only once for the subgraph and the certificate is built from the same state.
+    /// The solver is not cleared afterwards
+    pub fn solve_full(&mut self, syndrome_pattern: &SyndromePattern) -> FullResult {
+        self.solve(syndrome_pattern);
+        let perfect_matching = self.perfect_matching();
+        if self.erasure_peeling_result.is_none() {
+            self.subgraph_builder.load_perfect_matching(&perfect_matching);
+        }
+        let subgraph = self.subgraph_builder.get_subgraph();
+        let certificate = Certificate::from_interface(&self.interface_ptr, subgraph.clone());
+        FullResult {
+            perfect_matching,
+            subgraph,
+            weight: self.sum_dual_variables(),
+            certificate,
+        }
+    }
+
+    /// decode the syndrome by peeling if all its defects are explained by its erasures, see
+    /// [`SubGraphBuilder::peel_erasures`], and return the correction; otherwise return `None` and leave the solver
+    /// cleared, so that the caller may fall back to [`PrimalDualSolver::solve`]. The defects are still loaded into the
+    /// interface to report [`PrimalDualSolver::perfect_matching`], but the dual variables stay 0, which is optimal
+    /// because the matching has 0 weight
+    pub fn decode_with_erasures_only(&mut self, syndrome_pattern: &SyndromePattern) -> Option<Vec<EdgeIndex>> {
+        self.clear();
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        self.peel_erasures_visualizer(syndrome_pattern, None)
+    }
+
+    /// [`Self::decode_with_erasures_only`] on a clean solver and a syndrome whose erasure regions are already expanded
+    fn peel_erasures_visualizer(
+        &mut self,
+        syndrome_pattern: &SyndromePattern,
+        visualizer: Option<&mut Visualizer>,
+    ) -> Option<Vec<EdgeIndex>> {
+        let erasure_peeling = self.subgraph_builder.peel_erasures(syndrome_pattern)?;
+        self.interface_ptr.load(syndrome_pattern, &mut self.dual_module);
+        self.subgraph_builder.load_subgraph(&erasure_peeling.subgraph);
+        let subgraph = erasure_peeling.subgraph.clone();
+        self.erasure_peeling_result = Some(erasure_peeling);
+        if let Some(visualizer) = visualizer {
+            visualizer
+                .snapshot_combined(
+                    "erasure peeling".to_string(),
+                    vec![&self.interface_ptr, &self.dual_module, &VisualizeSubgraph::new(&subgraph)],
+                )
+                .unwrap();
+        }
+        Some(subgraph)
+    }
+
+    /// set the weights of all edges from real-valued log-likelihood ratios, e.g. regenerated by belief propagation for
+    /// every shot. The ratios are scaled by the largest factor such that the largest weight fits in
+    /// [`Self::weight_bits`] bits and a path through all the vertices doesn't overflow `Weight`, the same bound checked
+    /// by [`crate::fusion_mwpm`], and then rounded to even weights where any positive ratio gets at least weight 2, like
+    /// [`normalized_weights_of_p`]. The weights replace those of the initializer in the following decodings until the
+    /// next call; the erasures of a syndrome still set their edges to 0, but it must not have dynamic weights.
+    /// Returns the scale, i.e. the weight per unit of log-likelihood ratio, see [`Self::sum_dual_variables_llr`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn update_weights_normalized(&mut self, log_likelihood_ratios: &[f64]) -> f64 {
+        assert_eq!(
+            log_likelihood_ratios.len(),
+            self.dual_module.edges.len(),
+            "one log-likelihood ratio is required for each edge"
+        );
+        let mut max_ratio: f64 = 0.;
+        for (edge_index, &ratio) in log_likelihood_ratios.iter().enumerate() {
+            assert!(
+                ratio.is_finite() && ratio >= 0.,
+                "log-likelihood ratio of edge {edge_index} must be finite and non-negative, got {ratio}"
+            );
+            max_ratio = max_ratio.max(ratio);
+        }
+        let max_weight = core::cmp::min(
+            max_safe_weight(Weight::MAX as i128, self.dual_module.vertex_num) as i128,
+            1i128 << self.weight_bits,
+        );
+        let max_half_weight = (max_weight / 2) as f64;
+        assert!(
+            max_half_weight >= 1.,
+            "the decoding graph is too large for any positive weight"
+        );
+        let half_scale = if max_ratio == 0. {
+            max_half_weight
+        } else {
+            max_half_weight / max_ratio
+        };
+        self.normalized_weights = log_likelihood_ratios
+            .iter()
+            .enumerate()
+            .map(|(edge_index, &ratio)| {
+                let half_weight = if ratio == 0. {
+                    0
+                } else {
+                    core::cmp::max((ratio * half_scale).round() as Weight, 1)
+                };
+                (edge_index as EdgeIndex, 2 * half_weight)
+            })
+            .collect();
+        self.weight_scale = Some(2. * half_scale);
+        2. * half_scale
+    }
+
+    /// [`PrimalDualSolver::sum_dual_variables`] in units of log-likelihood ratio given the scale of the last
+    /// [`Self::update_weights_normalized`], or the integer weights as they are if it's never called
+    #[allow(clippy::unnecessary_cast)]
+    pub fn sum_dual_variables_llr(&self) -> f64 {
+        self.sum_dual_variables() as f64 / self.weight_scale.unwrap_or(1.)
+    }
+
+    /// the syndrome with the normalized weights as its dynamic weights, where the erased edges are set to 0
+    #[allow(clippy::unnecessary_cast)]
+    fn with_normalized_weights(&self, syndrome_pattern: &SyndromePattern) -> SyndromePattern {
+        assert!(
+            syndrome_pattern.dynamic_weights.is_empty(),
+            "dynamic_weights cannot be provided together with `SolverSerial::update_weights_normalized`"
+        );
+        let mut dynamic_weights = self.normalized_weights.clone();
+        for &edge_index in syndrome_pattern.erasures.iter() {
+            dynamic_weights[edge_index as usize].1 = 0;
+        }
+        SyndromePattern {
+            erasures: vec![],
+            dynamic_weights,
+            ..syndrome_pattern.clone()
+        }
+    }
+
+    /// pre-reserve all growable structures to avoid reallocation in the middle of decoding
+    pub fn reserve_capacity(&mut self, hints: CapacityHints) {
+        self.dual_module.reserve_capacity(&hints);
+        self.primal_module.reserve_capacity(&hints);
+        self.interface_ptr.reserve_capacity(hints.max_nodes());
+    }
+
+    /// statistics of the last decoding; the blossom statistics are recorded only when `primal_module.thrash_threshold`
+    /// is set
+    pub fn decode_stats(&self) -> DecodeStats {
+        let mut decode_stats = self.primal_module.read_recursive().decode_stats.clone();
+        decode_stats.peak_active_nodes = self.dual_module.peak_active_nodes();
+        decode_stats
+    }
+
+    /// the high-water marks of all previous decodings, useful to derive [`CapacityHints`] empirically
+    pub fn observed_capacities(&self) -> CapacityHints {
+        self.dual_module.observed_capacities()
+    }
+
+    /// load the defects of a syndrome into a cleared solver without solving it, so that the primal steps can be driven
+    /// manually by [`Self::compute_maximum_update_length`], [`Self::set_grow_state`] and [`Self::grow`]; this is slow
+    /// and meant for prototyping primal heuristics on small instances only: the primal module is bypassed, so
+    /// [`PrimalDualSolver::perfect_matching`] is not available afterwards
+    pub fn load_syndrome(&mut self, syndrome_pattern: &SyndromePattern) {
+        self.clear();
+        self.interface_ptr.load(syndrome_pattern, &mut self.dual_module);
+    }
+
+    /// a snapshot of the dual state, i.e. [`FusionVisualizer::snapshot`] with the list of outer dual nodes under
+    /// `"interface_nodes"`, each with its index, grow state, dual variable and either the defect vertex or the blossom
+    pub fn dual_state(&self) -> serde_json::Value {
+        let mut value = self.snapshot(false);
+        let interface = self.interface_ptr.read_recursive();
+        let mut interface_nodes = vec![];
+        for node_index in 0..interface.nodes_count() {
+            let Some(dual_node_ptr) = interface.get_node(node_index) else {
+                continue;
+            };
+            let dual_node = dual_node_ptr.read_recursive();
+            if dual_node.parent_blossom.is_some() {
+                continue; // only outer nodes can be controlled
+            }
+            interface_nodes.push(json!({
+                "index": dual_node.index,
+                "grow_state": match dual_node.grow_state {
+                    DualNodeGrowState::Grow => "grow",
+                    DualNodeGrowState::Shrink => "shrink",
+                    DualNodeGrowState::Stay => "stay",
+                },
+                "dual_variable": dual_node.get_dual_variable(&interface),
+                "defect_vertex": match &dual_node.class {
+                    DualNodeClass::DefectVertex { defect_index } => Some(*defect_index),
+                    _ => None,
+                },
+                "blossom": match &dual_node.class {
+                    DualNodeClass::Blossom { nodes_circle, .. } => Some(
+                        nodes_circle
+                            .iter()
+                            .map(|node_weak| node_weak.upgrade_force().read_recursive().index)
+                            .collect::<Vec<NodeIndex>>()
+                    ),
+                    _ => None,
+                },
+            }));
+        }
+        value
+            .as_object_mut()
+            .unwrap()
+            .insert("interface_nodes".to_string(), json!(interface_nodes));
+        value
+    }
+
+    /// set the grow state of an outer dual node, checking the transition with
+    /// [`DualModuleInterfacePtr::check_grow_state`] first
+    pub fn set_grow_state(&mut self, node_index: NodeIndex, grow_state: DualNodeGrowState) -> Result<(), GrowStateError> {
+        let dual_node_ptr = {
+            let interface = self.interface_ptr.read_recursive();
+            if node_index >= interface.nodes_count() {
+                return Err(GrowStateError::NotInInterface { node_index });
+            }
+            interface
+                .get_node(node_index)
+                .ok_or(GrowStateError::NotInInterface { node_index })?
+        };
+        self.interface_ptr
+            .check_grow_state(&dual_node_ptr, grow_state, &self.dual_module)?;
+        self.interface_ptr
+            .set_grow_state(&dual_node_ptr, grow_state, &mut self.dual_module);
+        Ok(())
+    }
+
+    /// grow all the dual nodes by `length` according to their grow states; the length must not exceed the one given by
+    /// [`Self::compute_maximum_update_length`], otherwise the dual variables would become infeasible
+    pub fn grow(&mut self, length: Weight) -> Result<(), String> {
+        match self.dual_module.compute_maximum_update_length().get_none_zero_growth() {
+            Some(max_length) if length <= max_length => {}
+            Some(max_length) => return Err(format!("cannot grow {length}, at most {max_length}")),
+            None => return Err(format!("cannot grow {length}, conflicts must be resolved first")),
+        }
+        self.interface_ptr.grow(length, &mut self.dual_module);
+        Ok(())
+    }
+
+    /// grow (or shrink, if `length` is negative) a single outer dual node while all the others stay, see
+    /// [`DualModuleInterfacePtr::grow_dual_node_checked`]; an excessive length is rejected with
+    /// [`GrowthError::ExceedsBound`] and nothing is modified
+    pub fn grow_dual_node(&mut self, node_index: NodeIndex, length: Weight) -> Result<(), GrowthError> {
+        let dual_node_ptr = {
+            let interface = self.interface_ptr.read_recursive();
+            if node_index >= interface.nodes_count() {
+                return Err(GrowthError::NotInInterface { node_index });
+            }
+            interface
+                .get_node(node_index)
+                .ok_or(GrowthError::NotInInterface { node_index })?
+        };
+        self.interface_ptr
+            .grow_dual_node_checked(&dual_node_ptr, length, &mut self.dual_module)
+    }
+
+    /// load the matching of another decoder for the syndrome currently in the solver (by [`Self::load_syndrome`] or
+    /// [`PrimalDualSolver::solve`]) and return its total weight, i.e. the sum of the minimum-weight path of every pair.
+    /// Each pair is either two defects or a defect and a virtual vertex, in any order; the pairs are validated to be a
+    /// perfect matching like [`PerfectMatching::validate`], in which case its correction is loaded into
+    /// [`Self::subgraph_builder`] (see [`SubGraphBuilder::get_subgraph`]) and nothing is changed otherwise.
+    /// After solving, the matching is optimal if and only if the weight equals [`PrimalDualSolver::sum_dual_variables`].
+    /// No primal or dual state is built from the matching, so it cannot warm start a re-optimization: the primal module
+    /// only matches nodes through tight edges, and dual variables that make every matched path tight without
+    /// over-growing any other edge exist only if the matching is already optimal. To re-optimize, solve the syndrome
+    /// and compare the weights
+    #[allow(clippy::unnecessary_cast)]
+    pub fn load_external_matching(&mut self, pairs: &[(VertexIndex, VertexIndex)]) -> Result<Weight, LoadError> {
+        let vertex_num = self.subgraph_builder.vertex_num;
+        // whether each defect has been matched
+        let mut is_matched: BTreeMap<VertexIndex, bool> = BTreeMap::new();
+        {
+            let interface = self.interface_ptr.read_recursive();
+            for node_index in 0..interface.nodes_count() {
+                if let Some(dual_node_ptr) = interface.get_node(node_index) {
+                    if let DualNodeClass::DefectVertex { defect_index } = &dual_node_ptr.read_recursive().class {
+                        is_matched.insert(*defect_index, false);
+                    }
+                }
+            }
+        }
+        let mark_matched =
+            |is_matched: &mut BTreeMap<VertexIndex, bool>, defect_vertex: VertexIndex| -> Result<(), LoadError> {
+                match is_matched.get_mut(&defect_vertex) {
+                    Some(true) => Err(MatchingError::DuplicateDefect(defect_vertex).into()),
+                    Some(matched) => {
+                        *matched = true;
+                        Ok(())
+                    }
+                    None => Err(MatchingError::UnknownDefect(defect_vertex).into()),
+                }
+            };
+        let complete_graph = &mut self.subgraph_builder.complete_graph;
+        let mut weight = 0;
+        for &(vertex_1, vertex_2) in pairs.iter() {
+            for vertex in [vertex_1, vertex_2] {
+                if vertex >= vertex_num {
+                    return Err(LoadError::VertexOutOfRange { vertex, vertex_num });
+                }
+            }
+            // a defect matched to the boundary may be given on either side
+            let (defect_vertex, peer_vertex) = if complete_graph.is_virtual[vertex_1 as usize] {
+                (vertex_2, vertex_1)
+            } else {
+                (vertex_1, vertex_2)
+            };
+            mark_matched(&mut is_matched, defect_vertex)?;
+            if !complete_graph.is_virtual[peer_vertex as usize] {
+                if is_matched.contains_key(&peer_vertex) {
+                    mark_matched(&mut is_matched, peer_vertex)?;
+                } else {
+                    return Err(MatchingError::NotVirtual {
+                        defect_vertex,
+                        vertex: peer_vertex,
+                    }
+                    .into());
+                }
+            }
+            let edges = complete_graph.all_edges_with_terminate(defect_vertex, peer_vertex);
+            match edges.get(&peer_vertex) {
+                Some((_, path_weight)) => weight += path_weight,
+                None => return Err(LoadError::Disconnected { vertex_1, vertex_2 }),
+            }
+        }
+        if let Some((&defect_vertex, _)) = is_matched.iter().find(|(_, matched)| !**matched) {
+            return Err(MatchingError::UnmatchedDefect(defect_vertex).into());
+        }
+        self.subgraph_builder.subgraph.clear();
+        for &(vertex_1, vertex_2) in pairs.iter() {
+            self.subgraph_builder.add_matching(vertex_1, vertex_2);
         }
+        Ok(weight)
+    }
+
+    /// decode only the defects within `radius` weight of `seed_defects`, ignoring the rest of the decoding graph, and
+    /// return the subgraph in the original edge indices. The region is found by a bounded Dijkstra search, see
+    /// [`SubGraphBuilder::local_region`], and the vertices just outside it are treated as virtual, so a defect may be
+    /// matched out of the region through any cut edge. The result is exact only if no cluster crosses the radius;
+    /// otherwise a defect may be matched out of the region where the global decoding would pair it with a defect
+    /// outside. This solver is not modified, and the erasures and weights of the syndrome pattern are ignored
+    #[allow(clippy::unnecessary_cast)]
+    pub fn solve_local(
+        &mut self,
+        syndrome_pattern: &SyndromePattern,
+        seed_defects: &[VertexIndex],
+        radius: Weight,
+    ) -> Vec<EdgeIndex> {
+        let region = self.subgraph_builder.local_region(seed_defects, radius);
+        let defect_vertices = syndrome_pattern
+            .defect_vertices
+            .iter()
+            .filter_map(|&vertex_index| region.local_vertex(vertex_index))
+            .collect();
+        let mut solver = SolverSerial::new(&region.initializer);
+        solver.primal_module.write().max_tree_size = self.primal_module.read_recursive().max_tree_size;
+        solver.subgraph_builder.odd_parity_policy = self.subgraph_builder.odd_parity_policy;
+        solver.solve(&SyndromePattern::new_vertices(defect_vertices));
+        solver
+            .subgraph()
+            .into_iter()
+            .map(|edge_index| region.edges[edge_index as usize])
+            .collect()
+    }
+
+    /// the next step of a primal loop: either the maximum length to grow or the conflicts that the caller must resolve
+    /// by changing grow states, since nothing is resolved here; it's empty when no node is growing or shrinking
+    pub fn compute_maximum_update_length(&mut self) -> GroupMaxUpdateLength {
+        self.dual_module.compute_maximum_update_length()
+    }
+
+    /// whether the primal loop is done, see [`DualModuleInterfacePtr::is_converged`]
+    pub fn is_converged(&mut self) -> bool {
+        self.interface_ptr.is_converged(&mut self.dual_module)
     }
 }
 
 impl PrimalDualSolver for SolverSerial {
     fn clear(&mut self) {
-        self.primal_module.clear();
         self.dual_module.clear();
+        self.primal_module.clear();
         self.interface_ptr.clear();
         self.subgraph_builder.clear();
+        self.erasure_peeling_result = None;
     }
-    fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
+    fn check_clean(&self) -> Result<(), String> {
+        self.dual_module.check_clean()?;
+        self.primal_module.check_clean()?;
+        self.interface_ptr.check_clean()?;
+        self.subgraph_builder.check_clean()
+    }
+    fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, mut visualizer: Option<&mut Visualizer>) {
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        if self.erasure_peeling
+            && self
+                .peel_erasures_visualizer(syndrome_pattern, visualizer.as_deref_mut())
+                .is_some()
+        {
+            return;
+        }
+        let repaired_syndrome_pattern = self.subgraph_builder.repair_odd_parity(syndrome_pattern);
+        let syndrome_pattern = repaired_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        let normalized_syndrome_pattern = self
+            .weight_scale
+            .is_some()
+            .then(|| self.with_normalized_weights(syndrome_pattern));
+        let syndrome_pattern = normalized_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         if !syndrome_pattern.erasures.is_empty() {
             assert!(
                 syndrome_pattern.dynamic_weights.is_empty(),
@@ -323,13 +1208,50 @@ impl PrimalDualSolver for SolverSerial {
         if !syndrome_pattern.dynamic_weights.is_empty() {
             self.subgraph_builder.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
         }
+        let fallback_max_tree_size = self.subgraph_builder.check_defect_count_fallback(syndrome_pattern);
+        let configured_max_tree_size = fallback_max_tree_size
+            .map(|max_tree_size| core::mem::replace(&mut self.primal_module.write().max_tree_size, max_tree_size));
         self.primal_module
             .solve_visualizer(&self.interface_ptr, syndrome_pattern, &mut self.dual_module, visualizer);
+        if let Some(max_tree_size) = configured_max_tree_size {
+            self.primal_module.write().max_tree_size = max_tree_size;
+        }
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
-        let perfect_matching = self
-            .primal_module
-            .perfect_matching(&self.interface_ptr, &mut self.dual_module);
+        let mut perfect_matching = match &self.erasure_peeling_result {
+            Some(erasure_peeling) => {
+                let interface = self.interface_ptr.read_recursive();
+                let mut defect_nodes = BTreeMap::new();
+                for node_index in 0..interface.nodes_count() {
+                    let dual_node_ptr = interface.get_node(node_index).unwrap();
+                    let defect_index = match &dual_node_ptr.read_recursive().class {
+                        DualNodeClass::DefectVertex { defect_index } => *defect_index,
+                        _ => continue,
+                    };
+                    defect_nodes.insert(defect_index, dual_node_ptr);
+                }
+                let mut perfect_matching = PerfectMatching::new();
+                for (vertex_1, vertex_2) in erasure_peeling.peer_matchings.iter() {
+                    let pair = (defect_nodes[vertex_1].clone(), defect_nodes[vertex_2].clone());
+                    perfect_matching.peer_matchings.push(pair);
+                }
+                for (defect_vertex, virtual_vertex) in erasure_peeling.virtual_matchings.iter() {
+                    let pair = (defect_nodes[defect_vertex].clone(), *virtual_vertex);
+                    perfect_matching.virtual_matchings.push(pair);
+                }
+                perfect_matching
+            }
+            None => {
+                let mut perfect_matching = self
+                    .primal_module
+                    .perfect_matching(&self.interface_ptr, &mut self.dual_module);
+                if self.realized_paths {
+                    perfect_matching.record_realized_paths(&self.dual_module);
+                }
+                perfect_matching
+            }
+        };
+        self.subgraph_builder.attach_boundary_alternatives(&mut perfect_matching);
         if let Some(visualizer) = visualizer {
             visualizer
                 .snapshot_combined(
@@ -342,7 +1264,9 @@ impl PrimalDualSolver for SolverSerial {
     }
     fn subgraph_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> Vec<EdgeIndex> {
         let perfect_matching = self.perfect_matching();
-        self.subgraph_builder.load_perfect_matching(&perfect_matching);
+        if self.erasure_peeling_result.is_none() {
+            self.subgraph_builder.load_perfect_matching(&perfect_matching);
+        }
         let subgraph = self.subgraph_builder.get_subgraph();
         if let Some(visualizer) = visualizer {
             visualizer
@@ -362,6 +1286,25 @@ impl PrimalDualSolver for SolverSerial {
     fn sum_dual_variables(&self) -> Weight {
         self.interface_ptr.read_recursive().sum_dual_variables
     }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.edge_num())
+    }
+    fn vertex_num(&self) -> Option<VertexNum> {
+        Some(self.subgraph_builder.vertex_num)
+    }
+    fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        self.subgraph_builder.check_syndrome_pattern(syndrome_pattern)
+    }
+    fn odd_parity_repairs(&self) -> Vec<OddParityRepair> {
+        self.subgraph_builder.odd_parity_repairs.clone()
+    }
+    fn defect_count_fallback_applied(&self) -> bool {
+        self.subgraph_builder.defect_count_fallback_applied
+    }
+    fn optimality_certificate(&mut self) -> Option<Certificate> {
+        let subgraph = self.subgraph();
+        Some(Certificate::from_interface(&self.interface_ptr, subgraph))
+    }
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({
             "dual": self.dual_module.generate_profiler_report(),
@@ -370,7 +1313,8 @@ impl PrimalDualSolver for SolverSerial {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[cfg(feature = "parallel")]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverDualParallel {
     pub dual_module: DualModuleParallel<DualModuleSerial>,
@@ -379,7 +1323,9 @@ pub struct SolverDualParallel {
     pub subgraph_builder: SubGraphBuilder,
 }
 
+#[cfg(feature = "parallel")]
 bind_trait_fusion_visualizer!(SolverDualParallel);
+#[cfg(feature = "parallel")]
 impl FusionVisualizer for SolverDualParallel {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         let mut value = self.primal_module.snapshot(abbrev);
@@ -396,32 +1342,72 @@ bind_trait_primal_dual_solver! {SolverDualParallel}
 #[pymethods]
 impl SolverDualParallel {
     #[new]
+    #[pyo3(signature = (initializer, partition_info, primal_dual_config = None, *, thread_pool_size = None))]
     pub fn new_python(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        primal_dual_config: PyObject,
-    ) -> Self {
-        let primal_dual_config = pyobject_to_json(primal_dual_config);
-        Self::new(initializer, partition_info, primal_dual_config)
+        primal_dual_config: Option<PyObject>,
+        thread_pool_size: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut primal_dual_config = primal_dual_config.map_or(json!({}), pyobject_to_json);
+        if let Some(thread_pool_size) = thread_pool_size {
+            primal_dual_config["thread_pool_size"] = json!(thread_pool_size);
+        }
+        Self::try_new(initializer, partition_info, primal_dual_config)
+            .map_err(|issues| pyo3::exceptions::PyValueError::new_err(format!("invalid partition config: {:?}", issues)))
     }
 }
 
+#[cfg(feature = "parallel")]
 impl SolverDualParallel {
     pub fn new(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
         primal_dual_config: serde_json::Value,
     ) -> Self {
+        Self::try_new(initializer, partition_info, primal_dual_config)
+            .unwrap_or_else(|issues| panic!("invalid partition config: {:?}", issues))
+    }
+
+    /// construct the solver only if the partition config passes [`PartitionConfig::validate`]; the config is a
+    /// [`DualModuleParallelConfig`] with an optional `"defect_count_fallback"`, see [`DefectCountFallback`]
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        mut primal_dual_config: serde_json::Value,
+    ) -> Result<Self, Vec<PartitionIssue>> {
+        partition_info.config.validate(initializer)?;
+        let mut subgraph_builder = SubGraphBuilder::new(initializer);
+        if let Some(value) = primal_dual_config
+            .as_object_mut()
+            .expect("config must be JSON object")
+            .remove("defect_count_fallback")
+        {
+            subgraph_builder.defect_count_fallback = serde_json::from_value(value).unwrap();
+        }
         let config: DualModuleParallelConfig = serde_json::from_value(primal_dual_config).unwrap();
-        Self {
+        Ok(Self {
             dual_module: DualModuleParallel::new_config(initializer, partition_info, config),
             primal_module: PrimalModuleSerialPtr::new_empty(initializer),
             interface_ptr: DualModuleInterfacePtr::new_empty(),
-            subgraph_builder: SubGraphBuilder::new(initializer),
-        }
+            subgraph_builder,
+        })
+    }
+
+    /// pre-reserve all growable structures, the dual module splits the hints to its units by the owned vertex count
+    pub fn reserve_capacity(&mut self, hints: CapacityHints) {
+        self.dual_module.reserve_capacity(&hints);
+        self.primal_module.reserve_capacity(&hints);
+        self.interface_ptr.reserve_capacity(hints.max_nodes());
+    }
+
+    /// the high-water marks of all previous decodings, useful to derive [`CapacityHints`] empirically
+    pub fn observed_capacities(&self) -> CapacityHints {
+        self.dual_module.observed_capacities()
     }
 }
 
+#[cfg(feature = "parallel")]
 impl PrimalDualSolver for SolverDualParallel {
     fn clear(&mut self) {
         self.dual_module.clear();
@@ -429,7 +1415,17 @@ impl PrimalDualSolver for SolverDualParallel {
         self.interface_ptr.clear();
         self.subgraph_builder.clear();
     }
+    fn check_clean(&self) -> Result<(), String> {
+        self.dual_module.check_clean()?;
+        self.primal_module.check_clean()?;
+        self.interface_ptr.check_clean()?;
+        self.subgraph_builder.check_clean()
+    }
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        let repaired_syndrome_pattern = self.subgraph_builder.repair_odd_parity(syndrome_pattern);
+        let syndrome_pattern = repaired_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         if !syndrome_pattern.erasures.is_empty() {
             assert!(
                 syndrome_pattern.dynamic_weights.is_empty(),
@@ -441,13 +1437,20 @@ impl PrimalDualSolver for SolverDualParallel {
             self.subgraph_builder.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
         }
         self.dual_module.static_fuse_all();
+        let fallback_max_tree_size = self.subgraph_builder.check_defect_count_fallback(syndrome_pattern);
+        let configured_max_tree_size = fallback_max_tree_size
+            .map(|max_tree_size| core::mem::replace(&mut self.primal_module.write().max_tree_size, max_tree_size));
         self.primal_module
             .solve_visualizer(&self.interface_ptr, syndrome_pattern, &mut self.dual_module, visualizer);
+        if let Some(max_tree_size) = configured_max_tree_size {
+            self.primal_module.write().max_tree_size = max_tree_size;
+        }
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
-        let perfect_matching = self
+        let mut perfect_matching = self
             .primal_module
             .perfect_matching(&self.interface_ptr, &mut self.dual_module);
+        self.subgraph_builder.attach_boundary_alternatives(&mut perfect_matching);
         if let Some(visualizer) = visualizer {
             visualizer
                 .snapshot_combined(
@@ -480,6 +1483,25 @@ impl PrimalDualSolver for SolverDualParallel {
     fn sum_dual_variables(&self) -> Weight {
         self.interface_ptr.read_recursive().sum_dual_variables
     }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.edge_num())
+    }
+    fn vertex_num(&self) -> Option<VertexNum> {
+        Some(self.subgraph_builder.vertex_num)
+    }
+    fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        self.subgraph_builder.check_syndrome_pattern(syndrome_pattern)
+    }
+    fn odd_parity_repairs(&self) -> Vec<OddParityRepair> {
+        self.subgraph_builder.odd_parity_repairs.clone()
+    }
+    fn defect_count_fallback_applied(&self) -> bool {
+        self.subgraph_builder.defect_count_fallback_applied
+    }
+    fn optimality_certificate(&mut self) -> Option<Certificate> {
+        let subgraph = self.subgraph();
+        Some(Certificate::from_interface(&self.interface_ptr, subgraph))
+    }
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({
             "dual": self.dual_module.generate_profiler_report(),
@@ -488,7 +1510,8 @@ impl PrimalDualSolver for SolverDualParallel {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[cfg(feature = "parallel")]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverParallel {
     pub dual_module: DualModuleParallel<DualModuleSerial>,
@@ -496,7 +1519,9 @@ pub struct SolverParallel {
     pub subgraph_builder: SubGraphBuilder,
 }
 
+#[cfg(feature = "parallel")]
 bind_trait_fusion_visualizer!(SolverParallel);
+#[cfg(feature = "parallel")]
 impl FusionVisualizer for SolverParallel {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         let mut value = self.primal_module.snapshot(abbrev);
@@ -511,14 +1536,22 @@ bind_trait_primal_dual_solver! {SolverParallel}
 #[cfg(feature = "python_binding")]
 #[pymethods]
 impl SolverParallel {
+    /// `thread_pool_size` applies to both the primal and the dual module, overwriting the one in `primal_dual_config`
     #[new]
+    #[pyo3(signature = (initializer, partition_info, primal_dual_config = None, *, thread_pool_size = None))]
     pub fn new_python(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        primal_dual_config: PyObject,
-    ) -> Self {
-        let primal_dual_config = pyobject_to_json(primal_dual_config);
-        Self::new(initializer, partition_info, primal_dual_config)
+        primal_dual_config: Option<PyObject>,
+        thread_pool_size: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut primal_dual_config = primal_dual_config.map_or(json!({}), pyobject_to_json);
+        if let Some(thread_pool_size) = thread_pool_size {
+            primal_dual_config["dual"]["thread_pool_size"] = json!(thread_pool_size);
+            primal_dual_config["primal"]["thread_pool_size"] = json!(thread_pool_size);
+        }
+        Self::try_new(initializer, partition_info, primal_dual_config)
+            .map_err(|issues| pyo3::exceptions::PyValueError::new_err(format!("invalid partition config: {:?}", issues)))
     }
 
     #[pyo3(name = "defect_perfect_matching")]
@@ -555,12 +1588,24 @@ impl SolverParallel {
     }
 }
 
+#[cfg(feature = "parallel")]
 impl SolverParallel {
     pub fn new(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        mut primal_dual_config: serde_json::Value,
+        primal_dual_config: serde_json::Value,
     ) -> Self {
+        Self::try_new(initializer, partition_info, primal_dual_config)
+            .unwrap_or_else(|issues| panic!("invalid partition config: {:?}", issues))
+    }
+
+    /// construct the solver only if the partition config passes [`PartitionConfig::validate`]
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        mut primal_dual_config: serde_json::Value,
+    ) -> Result<Self, Vec<PartitionIssue>> {
+        partition_info.config.validate(initializer)?;
         let primal_dual_config = primal_dual_config.as_object_mut().expect("config must be JSON object");
         let mut dual_config = DualModuleParallelConfig::default();
         let mut primal_config = PrimalModuleParallelConfig::default();
@@ -570,38 +1615,78 @@ impl SolverParallel {
         if let Some(value) = primal_dual_config.remove("primal") {
             primal_config = serde_json::from_value(value).unwrap();
         }
+        let mut subgraph_builder = SubGraphBuilder::new(initializer);
+        if let Some(value) = primal_dual_config.remove("defect_count_fallback") {
+            subgraph_builder.defect_count_fallback = serde_json::from_value(value).unwrap();
+        }
         if !primal_dual_config.is_empty() {
             panic!(
                 "unknown primal_dual_config keys: {:?}",
                 primal_dual_config.keys().collect::<Vec<&String>>()
             );
         }
-        Self {
+        Ok(Self {
             dual_module: DualModuleParallel::new_config(initializer, partition_info, dual_config),
             primal_module: PrimalModuleParallel::new_config(initializer, partition_info, primal_config),
-            subgraph_builder: SubGraphBuilder::new(initializer),
-        }
+            subgraph_builder,
+        })
+    }
+
+    /// pre-reserve all growable structures, splitting the hints to each unit by the owned vertex count
+    pub fn reserve_capacity(&mut self, hints: CapacityHints) {
+        self.dual_module.reserve_capacity(&hints);
+        self.primal_module.reserve_capacity(&hints);
+    }
+
+    /// the high-water marks of all previous decodings, useful to derive [`CapacityHints`] empirically
+    pub fn observed_capacities(&self) -> CapacityHints {
+        self.dual_module.observed_capacities()
+    }
+
+    /// statistics of the last decoding merged from all units; the blossom statistics are recorded only when
+    /// `thrash_threshold` is configured
+    pub fn decode_stats(&self) -> DecodeStats {
+        let mut decode_stats = self.primal_module.decode_stats();
+        decode_stats.peak_active_nodes = self.dual_module.peak_active_nodes();
+        decode_stats
     }
 }
 
+#[cfg(feature = "parallel")]
 impl PrimalDualSolver for SolverParallel {
     fn clear(&mut self) {
         self.dual_module.clear();
         self.primal_module.clear();
         self.subgraph_builder.clear();
     }
+    fn check_clean(&self) -> Result<(), String> {
+        self.dual_module.check_clean()?;
+        self.primal_module.check_clean()?;
+        self.subgraph_builder.check_clean()
+    }
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
+        let expanded_syndrome_pattern = self.subgraph_builder.expand_erasure_regions(syndrome_pattern);
+        let syndrome_pattern = expanded_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        let repaired_syndrome_pattern = self.subgraph_builder.repair_odd_parity(syndrome_pattern);
+        let syndrome_pattern = repaired_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         if !syndrome_pattern.erasures.is_empty() {
             self.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
         }
+        let fallback_max_tree_size = self.subgraph_builder.check_defect_count_fallback(syndrome_pattern);
+        let configured_max_tree_size =
+            fallback_max_tree_size.map(|max_tree_size| self.primal_module.set_max_tree_size(max_tree_size));
         self.primal_module
             .parallel_solve_visualizer(syndrome_pattern, &self.dual_module, visualizer);
+        if let Some(max_tree_size) = configured_max_tree_size {
+            self.primal_module.set_max_tree_size(max_tree_size);
+        }
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
         let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
-        let perfect_matching = self
+        let mut perfect_matching = self
             .primal_module
             .perfect_matching(&useless_interface_ptr, &mut self.dual_module);
+        self.subgraph_builder.attach_boundary_alternatives(&mut perfect_matching);
         if let Some(visualizer) = visualizer {
             let last_interface_ptr = &self.primal_module.units.last().unwrap().read_recursive().interface_ptr;
             visualizer
@@ -638,6 +1723,33 @@ impl PrimalDualSolver for SolverParallel {
         let sum_dual_variables = last_unit.interface_ptr.read_recursive().sum_dual_variables;
         sum_dual_variables
     }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.edge_num())
+    }
+    fn vertex_num(&self) -> Option<VertexNum> {
+        Some(self.subgraph_builder.vertex_num)
+    }
+    fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        self.subgraph_builder.check_syndrome_pattern(syndrome_pattern)
+    }
+    fn odd_parity_repairs(&self) -> Vec<OddParityRepair> {
+        self.subgraph_builder.odd_parity_repairs.clone()
+    }
+    fn defect_count_fallback_applied(&self) -> bool {
+        self.subgraph_builder.defect_count_fallback_applied
+    }
+    fn optimality_certificate(&mut self) -> Option<Certificate> {
+        let subgraph = self.subgraph();
+        let interface_ptr = self
+            .primal_module
+            .units
+            .last()
+            .unwrap()
+            .read_recursive()
+            .interface_ptr
+            .clone();
+        Some(Certificate::from_interface(&interface_ptr, subgraph))
+    }
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({
             "dual": self.dual_module.generate_profiler_report(),
@@ -646,37 +1758,284 @@ impl PrimalDualSolver for SolverParallel {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+/// decode an unbounded stream of measurement rounds with a sliding window of time-partitioned units of `unit_rounds`
+/// rounds each, solved by a [`SolverParallel`] whose units are fused sequentially. Each unit is loaded once its rounds
+/// are complete: only its own defects are solved and then fused into the solved units before it, keeping their state.
+/// Once `lookahead_units` units follow the oldest unit, its matchings are finalized: they are emitted through the
+/// callback and the unit falls out of the window. A defect in a later unit that is matched to a finalized defect is
+/// consumed and never emitted again. The finalized matchings are the same as an offline solve of the whole history if
+/// `lookahead_units` units are longer than the maximum time extent of a cluster.
+///
+/// When all the `window_units` units of the solver are loaded, the solver is cleared by [`PrimalDualSolver::clear`]
+/// and its units are recycled for the next rounds: the units that are not finalized yet are loaded again into its first
+/// units. With the default `lookahead_units` of half the window, each round is solved at most twice.
+///
+/// The decoding graph must be invariant under time translation by a round, e.g. [`crate::example_codes::PhenomenologicalPlanarCode`],
+/// with vertex `round * round_vertex_num + i` being vertex `i` of the round; the emitted vertex indices follow the same
+/// convention in the whole history
+#[cfg(feature = "parallel")]
+pub struct StreamingDecoder {
+    /// the solver of the window, whose units are recycled every time they are all loaded
+    pub solver: SolverParallel,
+    /// the number of vertices in each measurement round
+    pub round_vertex_num: VertexNum,
+    /// the number of measurement rounds in each unit
+    pub unit_rounds: VertexNum,
+    /// the number of units in the window
+    pub window_units: usize,
+    /// the number of loaded units that must follow a unit before it's finalized, at most `window_units - 1`
+    pub lookahead_units: usize,
+    /// the index of the first round that is not finalized, i.e. the number of finalized rounds
+    first_round: VertexNum,
+    /// the index of the round loaded into the first unit of the solver
+    window_first_round: VertexNum,
+    /// the number of units of the solver that are loaded
+    loaded_units: usize,
+    /// the defects of each loaded unit that is not finalized, in the vertex indices of the history
+    pending_units: VecDeque<Vec<VertexIndex>>,
+    /// the defects of each round of the unit that is not complete yet, in the vertex indices of a single round
+    pending_rounds: Vec<Vec<VertexIndex>>,
+    /// the defects that are already matched to a finalized defect, in the vertex indices of the history
+    consumed_defects: BTreeSet<VertexIndex>,
+}
+
+#[cfg(feature = "parallel")]
+impl StreamingDecoder {
+    /// `window_initializer` is the decoding graph of `window_units * unit_rounds` rounds, which is partitioned in time
+    /// by [`crate::example_partition::time_partition_config_with_block_rounds`] and fused sequentially
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(
+        window_initializer: &SolverInitializer,
+        round_vertex_num: VertexNum,
+        unit_rounds: VertexNum,
+        primal_dual_config: serde_json::Value,
+    ) -> Self {
+        assert!(round_vertex_num > 0 && unit_rounds > 0, "rounds must not be empty");
+        assert_eq!(
+            window_initializer.vertex_num % (round_vertex_num * unit_rounds),
+            0,
+            "the window must consist of whole units"
+        );
+        let window_units = (window_initializer.vertex_num / (round_vertex_num * unit_rounds)) as usize;
+        assert!(
+            window_units >= 2,
+            "the window must have at least 2 units to finalize one at a time"
+        );
+        let partition_config = crate::example_partition::time_partition_config_with_block_rounds(
+            round_vertex_num,
+            &vec![unit_rounds; window_units],
+            false,
+            usize::MAX,
+        );
+        Self {
+            solver: SolverParallel::new(window_initializer, &partition_config.info(), primal_dual_config),
+            round_vertex_num,
+            unit_rounds,
+            window_units,
+            lookahead_units: window_units / 2,
+            first_round: 0,
+            window_first_round: 0,
+            loaded_units: 0,
+            pending_units: VecDeque::new(),
+            pending_rounds: vec![],
+            consumed_defects: BTreeSet::new(),
+        }
+    }
+
+    /// the number of rounds whose matchings have been finalized
+    pub fn finalized_rounds(&self) -> VertexNum {
+        self.first_round
+    }
+
+    /// accept the defects of a new round, in the vertex indices of a single round; if it completes a unit, the unit is
+    /// loaded and the matchings `(defect_vertex, peer_or_virtual_vertex)` of the oldest unit may be finalized and passed
+    /// to `callback`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn push_round<F>(&mut self, defects_in_round: &[VertexIndex], mut callback: F)
+    where
+        F: FnMut(VertexIndex, VertexIndex),
+    {
+        for &defect_vertex in defects_in_round.iter() {
+            assert!(
+                defect_vertex < self.round_vertex_num,
+                "defect vertex {defect_vertex} is out of the round of {} vertices",
+                self.round_vertex_num
+            );
+        }
+        self.pending_rounds.push(defects_in_round.to_vec());
+        if self.pending_rounds.len() as VertexNum == self.unit_rounds {
+            self.load_pending_rounds();
+            if self.pending_units.len() > self.lookahead_units {
+                self.finalize_units(1, &mut callback);
+            }
+        }
+    }
+
+    /// end the stream by finalizing all the remaining rounds; the decoder can then start a new stream from round 0
+    pub fn finish<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(VertexIndex, VertexIndex),
+    {
+        if !self.pending_rounds.is_empty() {
+            // the rounds after the end of the stream have no defects
+            self.load_pending_rounds();
+        }
+        if !self.pending_units.is_empty() {
+            // fill the window with empty units, otherwise a defect in the last loaded unit may be matched to the mirrored
+            // vertices of the next unit as if they were virtual
+            while self.loaded_units < self.window_units {
+                self.pending_units.push_back(vec![]);
+                self.solve_unit(self.pending_units.len() - 1);
+            }
+            self.finalize_units(self.pending_units.len(), &mut callback);
+        }
+        self.solver.clear();
+        self.first_round = 0;
+        self.window_first_round = 0;
+        self.loaded_units = 0;
+        self.consumed_defects.clear();
+    }
+
+    /// load the pending rounds as a new unit, recycling the units of the solver if they are all loaded
+    #[allow(clippy::unnecessary_cast)]
+    fn load_pending_rounds(&mut self) {
+        assert!(
+            self.lookahead_units < self.window_units,
+            "the lookahead must leave room for a new unit in the window"
+        );
+        let first_vertex =
+            (self.first_round + self.pending_units.len() as VertexNum * self.unit_rounds) * self.round_vertex_num;
+        let mut defect_vertices = vec![];
+        for (round, defects_in_round) in self.pending_rounds.drain(..).enumerate() {
+            let round_vertex = first_vertex + round as VertexIndex * self.round_vertex_num;
+            defect_vertices.extend(defects_in_round.into_iter().map(|defect_vertex| round_vertex + defect_vertex));
+        }
+        defect_vertices.sort_unstable();
+        defect_vertices.dedup();
+        if self.loaded_units == self.window_units {
+            self.solver.clear();
+            self.window_first_round = self.first_round;
+            self.loaded_units = 0;
+            for unit_index in 0..self.pending_units.len() {
+                self.solve_unit(unit_index);
+            }
+        }
+        self.pending_units.push_back(defect_vertices);
+        self.solve_unit(self.pending_units.len() - 1);
+    }
+
+    /// load the pending unit into the next unit of the solver and fuse it into the units before it
+    #[allow(clippy::unnecessary_cast)]
+    fn solve_unit(&mut self, pending_index: usize) {
+        let offset = self.window_first_round * self.round_vertex_num;
+        let defect_vertices: Vec<VertexIndex> = self.pending_units[pending_index]
+            .iter()
+            .filter(|defect_vertex| !self.consumed_defects.contains(defect_vertex))
+            .map(|&defect_vertex| defect_vertex - offset)
+            .collect();
+        let syndrome_pattern = SyndromePattern::new_vertices(defect_vertices);
+        let unit_index = self.loaded_units;
+        let primal_module = &self.solver.primal_module;
+        primal_module.solve_unit(unit_index, &syndrome_pattern, &self.solver.dual_module);
+        if unit_index > 0 {
+            // the fusion unit owns the first round of the new unit
+            primal_module.solve_unit(
+                self.window_units + unit_index - 1,
+                &syndrome_pattern,
+                &self.solver.dual_module,
+            );
+        }
+        self.loaded_units += 1;
+    }
+
+    /// finalize the matchings of the oldest `finalize_units` pending units
+    #[allow(clippy::unnecessary_cast)]
+    fn finalize_units<F>(&mut self, finalize_units: usize, callback: &mut F)
+    where
+        F: FnMut(VertexIndex, VertexIndex),
+    {
+        let offset = self.window_first_round * self.round_vertex_num;
+        let finalize_start = self.first_round * self.round_vertex_num - offset;
+        let finalize_rounds = finalize_units as VertexNum * self.unit_rounds;
+        let finalize_end = finalize_start + finalize_rounds * self.round_vertex_num;
+        let matched_pairs = self.solver.perfect_matching().matched_pairs();
+        let is_virtual = &self.solver.subgraph_builder.complete_graph.is_virtual;
+        // peer matchings are ordered by vertex index, so the defect in the finalized rounds always comes first
+        for (defect_vertex, peer_vertex) in matched_pairs {
+            if defect_vertex < finalize_start
+                || defect_vertex >= finalize_end
+                || self.consumed_defects.contains(&(offset + defect_vertex))
+            {
+                continue;
+            }
+            callback(offset + defect_vertex, offset + peer_vertex);
+            if peer_vertex >= finalize_end && !is_virtual[peer_vertex as usize] {
+                self.consumed_defects.insert(offset + peer_vertex);
+            }
+        }
+        self.pending_units.drain(..finalize_units);
+        self.first_round += finalize_rounds;
+        let first_vertex = self.first_round * self.round_vertex_num;
+        self.consumed_defects.retain(|&vertex| vertex >= first_vertex);
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverErrorPatternLogger {
     pub file: BufWriter<File>,
+    pub edge_num: usize,
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverErrorPatternLogger}
 
+#[cfg(feature = "std")]
 impl SolverErrorPatternLogger {
-    pub fn new(initializer: &SolverInitializer, positions: &Vec<VisualizePosition>, mut config: serde_json::Value) -> Self {
-        let mut filename = "tmp/syndrome_patterns.txt".to_string();
-        let config = config.as_object_mut().expect("config must be JSON object");
-        if let Some(value) = config.remove("filename") {
-            filename = value.as_str().expect("filename string").to_string();
-        }
-        if !config.is_empty() {
-            panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+    pub fn new(initializer: &SolverInitializer, positions: &Vec<VisualizePosition>, config: serde_json::Value) -> Self {
+        Self {
+            file: create_error_pattern_file(initializer, positions, config),
+            edge_num: initializer.weighted_edges.len(),
         }
-        let file = File::create(filename).unwrap();
-        let mut file = BufWriter::new(file);
-        file.write_all(b"Syndrome Pattern v1.0   <initializer> <positions> <syndrome_pattern>*\n")
-            .unwrap();
-        serde_json::to_writer(&mut file, &initializer).unwrap(); // large object write to file directly
-        file.write_all(b"\n").unwrap();
-        serde_json::to_writer(&mut file, &positions).unwrap();
-        file.write_all(b"\n").unwrap();
-        Self { file }
     }
 }
 
+/// create the error pattern file with its header, the initializer and the positions, to which syndrome patterns are
+/// then appended one per line; see [`crate::example_codes::ErrorPatternReader`]
+#[cfg(feature = "std")]
+fn create_error_pattern_file(
+    initializer: &SolverInitializer,
+    positions: &Vec<VisualizePosition>,
+    mut config: serde_json::Value,
+) -> BufWriter<File> {
+    let mut filename = "tmp/syndrome_patterns.txt".to_string();
+    let config = config.as_object_mut().expect("config must be JSON object");
+    if let Some(value) = config.remove("filename") {
+        filename = value.as_str().expect("filename string").to_string();
+    }
+    if !config.is_empty() {
+        panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+    }
+    let file = File::create(filename).unwrap();
+    let mut file = BufWriter::new(file);
+    // the structural hash lets the reader detect a file generated for a different decoding graph
+    file.write_all(
+        format!(
+            "Syndrome Pattern v1.0   <initializer> <positions> <syndrome_pattern>*   structural_hash={:016x}\n",
+            initializer.structural_hash()
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    serde_json::to_writer(&mut file, &initializer).unwrap(); // large object write to file directly
+    file.write_all(b"\n").unwrap();
+    serde_json::to_writer(&mut file, &positions).unwrap();
+    file.write_all(b"\n").unwrap();
+    file
+}
+
+#[cfg(feature = "std")]
 impl PrimalDualSolver for SolverErrorPatternLogger {
     fn clear(&mut self) {}
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, _visualizer: Option<&mut Visualizer>) {
@@ -699,12 +2058,137 @@ impl PrimalDualSolver for SolverErrorPatternLogger {
     fn sum_dual_variables(&self) -> Weight {
         panic!("error pattern logger do not actually solve the problem")
     }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.edge_num)
+    }
+    fn generate_profiler_report(&self) -> serde_json::Value {
+        json!({})
+    }
+}
+
+/// a thread-safe version of [`SolverErrorPatternLogger`] that logs the syndrome patterns of many threads into a single
+/// file: clone one handle for each thread, and all of them share the same file.
+/// Each record is written as a whole line under the lock so that the file remains readable by
+/// [`crate::example_codes::ErrorPatternReader`], and records are written in the order of their shot index
+/// regardless of which thread finishes first: a record logged by [`SharedErrorPatternLogger::log`] waits until all
+/// the shots before it are logged.
+/// Solving through [`PrimalDualSolver`] assigns the next shot index instead, i.e., the records are in the order of
+/// arrival; don't mix the two in the same file.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SharedErrorPatternLogger {
+    shared: Arc<Mutex<SharedErrorPatternFile>>,
+    pub edge_num: usize,
+}
+
+#[cfg(feature = "std")]
+struct SharedErrorPatternFile {
+    file: BufWriter<File>,
+    /// the shot index of the next record to be written to the file
+    next_shot_index: usize,
+    /// records waiting for an earlier shot to be logged
+    pending: BTreeMap<usize, String>,
+}
+
+#[cfg(feature = "std")]
+impl SharedErrorPatternFile {
+    fn write_record(&mut self, record: &str) {
+        self.file.write_all(record.as_bytes()).unwrap();
+        self.file.write_all(b"\n").unwrap();
+    }
+}
+
+#[cfg(feature = "std")]
+impl SharedErrorPatternLogger {
+    pub fn new(initializer: &SolverInitializer, positions: &Vec<VisualizePosition>, config: serde_json::Value) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(SharedErrorPatternFile {
+                file: create_error_pattern_file(initializer, positions, config),
+                next_shot_index: 0,
+                pending: BTreeMap::new(),
+            })),
+            edge_num: initializer.weighted_edges.len(),
+        }
+    }
+
+    /// log the syndrome pattern of the shot `shot_index`, counting from 0; each shot index should be logged exactly once
+    pub fn log(&self, shot_index: usize, syndrome_pattern: &SyndromePattern) {
+        let record = serde_json::to_string(syndrome_pattern).unwrap();
+        let mut shared = self.shared.lock().unwrap();
+        assert!(
+            shot_index >= shared.next_shot_index && !shared.pending.contains_key(&shot_index),
+            "shot {shot_index} is logged twice"
+        );
+        if shot_index != shared.next_shot_index {
+            shared.pending.insert(shot_index, record);
+            return;
+        }
+        shared.write_record(&record);
+        shared.next_shot_index += 1;
+        let shared = &mut *shared;
+        while let Some(record) = shared.pending.remove(&shared.next_shot_index) {
+            shared.write_record(&record);
+            shared.next_shot_index += 1;
+        }
+    }
+
+    /// the number of records waiting for an earlier shot to be logged
+    pub fn pending_count(&self) -> usize {
+        self.shared.lock().unwrap().pending.len()
+    }
+
+    /// write all the records to the file, including the pending ones in the order of their shot index if some shots
+    /// are never logged, e.g. when the run is interrupted
+    pub fn flush(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        let pending = core::mem::take(&mut shared.pending);
+        for (shot_index, record) in pending {
+            shared.write_record(&record);
+            shared.next_shot_index = shot_index + 1;
+        }
+        shared.file.flush().unwrap();
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for SharedErrorPatternFile {
+    fn drop(&mut self) {
+        let pending = core::mem::take(&mut self.pending);
+        for record in pending.into_values() {
+            self.write_record(&record);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl PrimalDualSolver for SharedErrorPatternLogger {
+    fn clear(&mut self) {}
+    fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, _visualizer: Option<&mut Visualizer>) {
+        let record = serde_json::to_string(syndrome_pattern).unwrap();
+        let mut shared = self.shared.lock().unwrap();
+        shared.write_record(&record);
+        shared.next_shot_index += 1;
+    }
+    fn perfect_matching_visualizer(&mut self, _visualizer: Option<&mut Visualizer>) -> PerfectMatching {
+        panic!("error pattern logger do not actually solve the problem, please use Verifier::None by `--verifier none`")
+    }
+    fn subgraph_visualizer(&mut self, _visualizer: Option<&mut Visualizer>) -> Vec<EdgeIndex> {
+        vec![]
+    }
+    fn sum_dual_variables(&self) -> Weight {
+        panic!("error pattern logger do not actually solve the problem")
+    }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.edge_num)
+    }
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({})
     }
 }
 
-/// an exact solver calling blossom V library for benchmarking comparison
+/// an exact solver calling blossom V library for benchmarking comparison; it has no tree size to bound, so
+/// [`DefectCountFallback`] never applies to it
+#[cfg(feature = "std")]
 #[derive(Clone)]
 pub struct SolverBlossomV {
     pub initializer: SolverInitializer,
@@ -713,6 +2197,7 @@ pub struct SolverBlossomV {
     pub matched_pairs: Vec<(VertexIndex, VertexIndex)>,
 }
 
+#[cfg(feature = "std")]
 impl SolverBlossomV {
     pub fn new(initializer: &SolverInitializer) -> Self {
         Self {
@@ -724,14 +2209,25 @@ impl SolverBlossomV {
     }
 }
 
+#[cfg(feature = "std")]
 impl PrimalDualSolver for SolverBlossomV {
     fn clear(&mut self) {
         self.matched_pairs.clear();
         self.subgraph_builder.clear();
     }
+    fn check_clean(&self) -> Result<(), String> {
+        self.subgraph_builder.check_clean()
+    }
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
         assert!(visualizer.is_none(), "not supported");
-        assert!(syndrome_pattern.erasures.is_empty(), "doesn't support erasure for now");
+        assert!(
+            syndrome_pattern.erasures.is_empty()
+                && syndrome_pattern.erasure_regions.is_empty()
+                && syndrome_pattern.uncertain_vertices.is_empty(),
+            "doesn't support erasure for now"
+        );
+        let repaired_syndrome_pattern = self.subgraph_builder.repair_odd_parity(syndrome_pattern);
+        let syndrome_pattern = repaired_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
         let defect_vertices = &syndrome_pattern.defect_vertices;
         if defect_vertices.is_empty() {
             return;
@@ -743,21 +2239,21 @@ impl PrimalDualSolver for SolverBlossomV {
         // for each real vertex, add a corresponding virtual vertex to be matched
         let defect_num = defect_vertices.len();
         let legacy_vertex_num = defect_num * 2;
-        let mut legacy_weighted_edges = Vec::<(usize, usize, u32)>::new();
+        let mut legacy_weighted_edges = Vec::<(usize, usize, Weight)>::new();
         for i in 0..defect_num - 1 {
             for j in i + 1..defect_num {
                 if let Some(weight) = self
                     .prebuilt_complete_graph
                     .get_edge_weight(defect_vertices[i], defect_vertices[j])
                 {
-                    legacy_weighted_edges.push((i, j, weight as u32));
+                    legacy_weighted_edges.push((i, j, weight));
                 }
             }
         }
         for (i, &defect_vertex) in defect_vertices.iter().enumerate() {
             if let Some((_, weight)) = self.prebuilt_complete_graph.get_boundary_weight(defect_vertex) {
                 // connect this real vertex to it's corresponding virtual vertex
-                legacy_weighted_edges.push((i, i + defect_num, weight as u32));
+                legacy_weighted_edges.push((i, i + defect_num, weight));
             }
         }
         for i in 0..defect_num - 1 {
@@ -769,6 +2265,7 @@ impl PrimalDualSolver for SolverBlossomV {
         // run blossom V to get matchings
         // println!("[debug] legacy_vertex_num: {:?}", legacy_vertex_num);
         // println!("[debug] legacy_weighted_edges: {:?}", legacy_weighted_edges);
+        let legacy_weighted_edges = blossom_v::legacy_weighted_edges(legacy_vertex_num, &legacy_weighted_edges);
         let matchings = blossom_v::safe_minimum_weight_perfect_matching(legacy_vertex_num, &legacy_weighted_edges);
         let mut matched_pairs = Vec::new();
         for i in 0..defect_num {
@@ -829,6 +2326,7 @@ impl PrimalDualSolver for SolverBlossomV {
             }
             self.subgraph_builder.add_matching(vertex_1, vertex_2);
         }
+        self.subgraph_builder.attach_boundary_alternatives(&mut perfect_matching);
         perfect_matching
     }
     fn subgraph_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> Vec<EdgeIndex> {
@@ -853,6 +2351,21 @@ impl PrimalDualSolver for SolverBlossomV {
         }
         weight
     }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.initializer.weighted_edges.len())
+    }
+    fn vertex_num(&self) -> Option<VertexNum> {
+        Some(self.initializer.vertex_num)
+    }
+    fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        self.subgraph_builder.check_syndrome_pattern(syndrome_pattern)
+    }
+    fn odd_parity_repairs(&self) -> Vec<OddParityRepair> {
+        self.subgraph_builder.odd_parity_repairs.clone()
+    }
+    fn defect_count_fallback_applied(&self) -> bool {
+        self.subgraph_builder.defect_count_fallback_applied
+    }
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({})
     }
@@ -866,5 +2379,737 @@ pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<SolverDualParallel>()?;
     m.add_class::<SolverParallel>()?;
     m.add_class::<SolverErrorPatternLogger>()?;
+    m.add_class::<SolveResult>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::*;
+
+    /// erasures crossing the interface of a 2-partition planar code decode the same as the serial solver; a stale
+    /// boundary left by a sync event is repaired and counted instead of panicking
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_dual_parallel_erasure_stale_boundary_1() {
+        // cargo test solver_dual_parallel_erasure_stale_boundary_1 -- --nocapture
+        use super::super::example_partition::*;
+        let d = 11;
+        let mut code = CodeCapacityPlanarCode::new(d, 0.05, 500);
+        code.set_erasure_probability(0.05);
+        let partition_config = CodeCapacityPlanarCodeVerticalPartitionHalf::new(d, d / 2).build_apply(&mut code);
+        let initializer = code.get_initializer();
+        let partition_info = partition_config.info();
+        let mut dual_parallel_solver = SolverDualParallel::new(&initializer, &partition_info, json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        // the erased edges with one end on each side of the interface
+        let owning_unit = |vertex_index: VertexIndex| partition_info.vertex_to_owning_unit[vertex_index as usize];
+        let interface_edges: Vec<EdgeIndex> = (0..initializer.weighted_edges.len() as EdgeIndex)
+            .filter(|&edge_index| {
+                let (left, right, _) = initializer.weighted_edges[edge_index as usize];
+                owning_unit(left) != owning_unit(right)
+            })
+            .collect();
+        assert!(!interface_edges.is_empty());
+        let mut stale_boundary_repairs = 0;
+        for seed in 0..20 {
+            let mut syndrome_pattern = code.generate_random_errors(seed);
+            // erase a third of the interface edges on top of the random erasures
+            syndrome_pattern
+                .erasures
+                .extend(interface_edges.iter().skip(seed as usize % 3).step_by(3));
+            dual_parallel_solver.solve(&syndrome_pattern);
+            serial_solver.solve(&syndrome_pattern);
+            assert_eq!(
+                dual_parallel_solver.sum_dual_variables(),
+                serial_solver.sum_dual_variables(),
+                "seed {seed}"
+            );
+            stale_boundary_repairs += dual_parallel_solver.generate_profiler_report()["dual"]["stale_boundary_repairs"]
+                .as_u64()
+                .unwrap();
+            dual_parallel_solver.clear();
+            serial_solver.clear();
+        }
+        println!("stale_boundary_repairs: {stale_boundary_repairs}");
+    }
+
+    /// the matchings finalized by a sliding window over a stream of rounds are the same as an offline solve of the history,
+    /// while the units of the window are recycled several times
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn streaming_decoder_1() {
+        // cargo test streaming_decoder_1 -- --nocapture
+        let (d, history_rounds, unit_rounds, window_units) = (5, 41, 2, 6);
+        let half_weight = 500;
+        let round_vertex_num = d * (d + 1);
+        let window_code = PhenomenologicalPlanarCode::new(d, unit_rounds * window_units - 1, 0.02, half_weight);
+        let mut decoder = StreamingDecoder::new(
+            &window_code.get_initializer(),
+            round_vertex_num,
+            unit_rounds,
+            serde_json::json!({}),
+        );
+        assert_eq!(decoder.window_units, window_units as usize);
+        let mut code = PhenomenologicalPlanarCode::new(d, history_rounds - 1, 0.02, half_weight);
+        let mut offline_solver = SolverSerial::new(&code.get_initializer());
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let mut rounds = vec![vec![]; history_rounds as usize];
+            for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+                rounds[(defect_vertex / round_vertex_num) as usize].push(defect_vertex % round_vertex_num);
+            }
+            let mut pairs = vec![];
+            for defects_in_round in rounds.iter() {
+                decoder.push_round(defects_in_round, |vertex_1, vertex_2| pairs.push((vertex_1, vertex_2)));
+            }
+            assert_eq!(
+                decoder.finalized_rounds(),
+                history_rounds - history_rounds % unit_rounds - unit_rounds * decoder.lookahead_units as VertexNum
+            );
+            decoder.finish(|vertex_1, vertex_2| pairs.push((vertex_1, vertex_2)));
+            offline_solver.solve(&syndrome_pattern);
+            assert_eq!(
+                offline_solver.load_external_matching(&pairs),
+                Ok(offline_solver.sum_dual_variables())
+            );
+            offline_solver.clear();
+        }
+    }
+
+    /// the matching of another decoder is verified against the loaded syndrome, and its weight is compared with the
+    /// sum of dual variables
+    #[test]
+    fn solver_serial_load_external_matching_1() {
+        // cargo test solver_serial_load_external_matching_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        code.set_defect_vertices(&[16, 17, 37]);
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&code.get_syndrome());
+        let subgraph = solver.subgraph();
+        let pairs = solver.perfect_matching().matched_pairs();
+        assert_eq!(solver.load_external_matching(&pairs), Ok(solver.sum_dual_variables()));
+        assert_eq!(solver.subgraph_builder.get_subgraph(), subgraph);
+        // a suboptimal matching: everything to the nearest boundary, given in any order
+        let nearest_virtual = |solver: &mut SolverSerial, vertex| {
+            solver.subgraph_builder.complete_graph.nearby_virtual(vertex, Weight::MAX)[0]
+        };
+        let mut boundary_pairs = vec![];
+        let mut boundary_weight = 0;
+        for vertex in [16, 17, 37] {
+            let (virtual_vertex, weight) = nearest_virtual(&mut solver, vertex);
+            boundary_pairs.push((virtual_vertex, vertex));
+            boundary_weight += weight;
+        }
+        assert_eq!(solver.load_external_matching(&boundary_pairs), Ok(boundary_weight));
+        assert!(boundary_weight > solver.sum_dual_variables());
+        // illegal matchings are rejected without changing the loaded correction
+        let boundary_subgraph = solver.subgraph_builder.get_subgraph();
+        let (virtual_37, _) = nearest_virtual(&mut solver, 37);
+        assert_eq!(
+            solver.load_external_matching(&[(16, 17)]),
+            Err(LoadError::Matching(MatchingError::UnmatchedDefect(37)))
+        );
+        assert_eq!(
+            solver.load_external_matching(&[(16, 17), (37, virtual_37), (17, virtual_37)]),
+            Err(LoadError::Matching(MatchingError::DuplicateDefect(17)))
+        );
+        assert_eq!(
+            solver.load_external_matching(&[(16, 18), (17, 37)]),
+            Err(LoadError::Matching(MatchingError::NotVirtual {
+                defect_vertex: 16,
+                vertex: 18
+            }))
+        );
+        assert_eq!(
+            solver.load_external_matching(&[(16, 17), (37, 1000)]),
+            Err(LoadError::VertexOutOfRange {
+                vertex: 1000,
+                vertex_num: initializer.vertex_num
+            })
+        );
+        assert_eq!(solver.subgraph_builder.get_subgraph(), boundary_subgraph);
+    }
+
+    /// the defect left over by [`OddParityPolicy::MatchToNearestVirtualEvenIfFar`] is matched to the same virtual vertex,
+    /// see [`ConnectedComponents::fallback_virtual_by_index`], in the result of every solver
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn solver_odd_parity_match_to_virtual_1() {
+        // cargo test solver_odd_parity_match_to_virtual_1 -- --nocapture
+        // component 0: a chain 0-1-2-3 with virtual vertices 0 and 3; component 4: a chain 4-5-6 without virtual vertex
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 2), (2, 3, 6), (4, 5, 2), (5, 6, 8)];
+        let initializer = SolverInitializer::new(7, weighted_edges, vec![0, 3]);
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let policy = OddParityPolicy::MatchToNearestVirtualEvenIfFar;
+        let mut serial_solver = SolverSerial::new(&initializer);
+        serial_solver.subgraph_builder.odd_parity_policy = policy;
+        let mut dual_parallel_solver = SolverDualParallel::new(&initializer, &partition_info, json!({}));
+        dual_parallel_solver.subgraph_builder.odd_parity_policy = policy;
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        parallel_solver.subgraph_builder.odd_parity_policy = policy;
+        let mut solvers: Vec<Box<dyn PrimalDualSolver>> = vec![
+            Box::new(serial_solver),
+            Box::new(dual_parallel_solver),
+            Box::new(parallel_solver),
+        ];
+        for solver in solvers.iter_mut() {
+            // 4 and 5 are paired first, leaving 6, which is closer to virtual vertex 3 than to 0
+            let result = solver.solve_owned(&SyndromePattern::new_vertices(vec![2, 4, 5, 6]));
+            solver.reset().unwrap();
+            assert_eq!(result.matched_pairs, vec![(2, 0), (4, 5), (6, 3)]);
+            assert_eq!(result.weight, 4 + 2);
+            assert_eq!(result.odd_parity_repairs[0].removed_defect, 6);
+            assert_eq!(result.odd_parity_repairs[0].matched_virtual, Some(3));
+            assert!(result.is_degraded());
+        }
+    }
+
+    /// the owned result of all solvers stays valid after clearing the solver
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_solve_owned_1() {
+        // cargo test solver_solve_owned_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let mut solvers: Vec<Box<dyn PrimalDualSolver>> = vec![
+            Box::new(SolverSerial::new(&initializer)),
+            Box::new(SolverDualParallel::new(&initializer, &partition_info, json!({}))),
+            Box::new(SolverParallel::new(&initializer, &partition_info, json!({}))),
+        ];
+        let mut reference_solver = SolverSerial::new(&initializer);
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            reference_solver.solve(&syndrome_pattern);
+            let expected_weight = reference_solver.sum_dual_variables();
+            reference_solver.clear();
+            for solver in solvers.iter_mut() {
+                let result = solver.solve_owned(&syndrome_pattern);
+                solver.reset().unwrap();
+                assert_eq!(result.weight, expected_weight);
+                // every defect is matched exactly once
+                let mut matched_defects: Vec<VertexIndex> = result
+                    .matched_pairs
+                    .iter()
+                    .flat_map(|&(vertex_1, vertex_2)| [vertex_1, vertex_2])
+                    .filter(|vertex| syndrome_pattern.defect_vertices.contains(vertex))
+                    .collect();
+                matched_defects.sort();
+                let mut defect_vertices = syndrome_pattern.defect_vertices.clone();
+                defect_vertices.sort();
+                assert_eq!(matched_defects, defect_vertices);
+                let subgraph_weight: Weight = result
+                    .subgraph
+                    .iter()
+                    .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                    .sum();
+                assert_eq!(subgraph_weight, expected_weight);
+                assert!(!result.is_degraded());
+                let json = serde_json::to_value(&result).unwrap();
+                assert_eq!(serde_json::from_value::<SolveResult>(json).unwrap(), result);
+            }
+        }
+    }
+
+    /// an uncertain vertex is a free pass-through between its neighbors, restored when the solver is cleared
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_uncertain_vertices_1() {
+        // cargo test solver_uncertain_vertices_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        // the defects 19 and 21 are connected through the uncertain vertex 20 at no cost
+        let syndrome_pattern = SyndromePattern::new_uncertain_vertices(vec![19, 21], vec![20]);
+        solver.try_solve(&syndrome_pattern).unwrap();
+        assert_eq!(solver.sum_dual_variables(), 0);
+        solver.reset().unwrap();
+        solver.solve(&SyndromePattern::new_vertices(vec![19, 21]));
+        assert_eq!(solver.sum_dual_variables(), 4 * half_weight);
+        solver.reset().unwrap();
+        // an uncertain vertex is equivalent to erasing all its incident edges
+        let incident_edges = |vertex_index: VertexIndex| -> Vec<EdgeIndex> {
+            (0..initializer.weighted_edges.len())
+                .filter(|&edge_index| {
+                    let (i, j, _) = initializer.weighted_edges[edge_index];
+                    i == vertex_index || j == vertex_index
+                })
+                .map(|edge_index| edge_index as EdgeIndex)
+                .collect()
+        };
+        for seed in 0..10 {
+            let mut defect_vertices = code.generate_random_errors(seed).defect_vertices;
+            let uncertain_vertices: Vec<VertexIndex> = (0..initializer.vertex_num)
+                .filter(|vertex_index| {
+                    vertex_index % 7 == (seed % 7) as VertexIndex && !defect_vertices.contains(vertex_index)
+                })
+                .collect();
+            defect_vertices.retain(|vertex_index| !uncertain_vertices.contains(vertex_index));
+            let uncertain_syndrome =
+                SyndromePattern::new_uncertain_vertices(defect_vertices.clone(), uncertain_vertices.clone());
+            let mut erasures: Vec<EdgeIndex> = uncertain_vertices
+                .iter()
+                .flat_map(|&vertex_index| incident_edges(vertex_index))
+                .collect();
+            erasures.sort();
+            erasures.dedup();
+            solver.solve(&uncertain_syndrome);
+            let uncertain_weight = solver.sum_dual_variables();
+            solver.reset().unwrap();
+            solver.solve(&SyndromePattern::new(defect_vertices, erasures));
+            assert_eq!(solver.sum_dual_variables(), uncertain_weight, "seed {seed}");
+            solver.reset().unwrap();
+        }
+        // an uncertain vertex cannot be a defect
+        assert!(matches!(
+            solver.try_solve(&SyndromePattern::new_uncertain_vertices(vec![19, 20], vec![20])),
+            Err(SolveError::InvalidSyndrome(_))
+        ));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn solver_defect_count_fallback_1() {
+        // cargo test solver_defect_count_fallback_1 -- --nocapture
+        let half_weight = 500;
+        let threshold = 8;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        // all the solvers take the same config
+        let config = json!({"defect_count_fallback": {"threshold": threshold, "mode": "union_find"}});
+        let mut solvers: Vec<Box<dyn PrimalDualSolver>> = vec![
+            Box::new(SolverSerial::new_config(&initializer, config.clone())),
+            Box::new(SolverDualParallel::new(&initializer, &partition_info, config.clone())),
+            Box::new(SolverParallel::new(&initializer, &partition_info, config)),
+        ];
+        let mut reference_solver = SolverSerial::new(&initializer);
+        let (mut burst_count, mut normal_count) = (0, 0);
+        // bursts and normal shots interleave, so the solvers must switch back after each burst
+        for seed in 0..40 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let is_burst = syndrome_pattern.defect_vertices.len() > threshold;
+            if is_burst {
+                burst_count += 1;
+            } else {
+                normal_count += 1;
+            }
+            reference_solver.solve(&syndrome_pattern);
+            let expected_weight = reference_solver.sum_dual_variables();
+            reference_solver.clear();
+            for solver in solvers.iter_mut() {
+                let result = solver.solve_owned(&syndrome_pattern);
+                solver.reset().unwrap();
+                assert_eq!(result.defect_count_fallback, is_burst);
+                assert_eq!(result.is_degraded(), is_burst);
+                let mut matched_defects: Vec<VertexIndex> = result
+                    .matched_pairs
+                    .iter()
+                    .flat_map(|&(vertex_1, vertex_2)| [vertex_1, vertex_2])
+                    .filter(|vertex| syndrome_pattern.defect_vertices.contains(vertex))
+                    .collect();
+                matched_defects.sort();
+                let mut defect_vertices = syndrome_pattern.defect_vertices.clone();
+                defect_vertices.sort();
+                assert_eq!(matched_defects, defect_vertices);
+                if is_burst {
+                    // the dual variables are still feasible, so their sum bounds the optimal weight
+                    assert!(result.weight <= expected_weight);
+                } else {
+                    assert_eq!(result.weight, expected_weight);
+                }
+            }
+        }
+        assert!(burst_count > 0 && normal_count > 0);
+    }
+
+    #[test]
+    fn solver_serial_defect_count_fallback_2() {
+        // cargo test solver_serial_defect_count_fallback_2 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.subgraph_builder.defect_count_fallback = Some(serde_json::from_value(json!({"threshold": 0})).unwrap());
+        code.vertices[8].is_defect = true;
+        code.vertices[13].is_defect = true;
+        solver.solve(&code.get_syndrome());
+        assert!(solver.defect_count_fallback_applied());
+        // the configured `max_tree_size` is restored after the fallback
+        assert_eq!(solver.primal_module.read_recursive().max_tree_size, usize::MAX);
+        solver.clear();
+        assert!(!solver.defect_count_fallback_applied());
+    }
+
+    /// decoding a cluster locally gives the same result as the global decoding as long as the radius covers it
+    #[test]
+    fn solver_serial_solve_local_1() {
+        // cargo test solver_serial_solve_local_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(15, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let edge_weight = initializer.weighted_edges[0].2;
+        // a cluster of two defects 3 edges apart in the middle, and a far-away pair
+        let cluster = [117, 120];
+        code.set_defect_vertices(&[38, 39, 117, 120]);
+        let syndrome_pattern = code.get_syndrome();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&syndrome_pattern);
+        let global_subgraph: BTreeSet<_> = solver.subgraph().into_iter().collect();
+        solver.clear();
+        let local_subgraph: BTreeSet<_> = solver
+            .solve_local(&syndrome_pattern, &cluster, 4 * edge_weight)
+            .into_iter()
+            .collect();
+        assert_eq!(local_subgraph.len(), 3);
+        assert!(local_subgraph.is_subset(&global_subgraph));
+        assert_eq!(
+            initializer.syndrome_of(&local_subgraph.iter().cloned().collect::<Vec<_>>()),
+            cluster.iter().cloned().collect()
+        );
+        // with a too small radius, both defects are matched out of the region through the cut edges
+        let local_subgraph = solver.solve_local(&syndrome_pattern, &cluster, 0);
+        assert_eq!(local_subgraph.len(), 2);
+        assert_eq!(solver.subgraph_builder.local_region(&cluster, 0).region_vertex_num, 2);
+    }
+
+    /// erasure-only syndromes are peeled to a minimum-weight correction, and the others fall back to the blossom algorithm
+    #[cfg(feature = "cli")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_serial_erasure_peeling_1() {
+        // cargo test solver_serial_erasure_peeling_1 -- --nocapture
+        use crate::cli::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.05, half_weight);
+        code.set_erasure_probability(0.05);
+        let initializer = code.get_initializer();
+        let mut solver: Box<dyn PrimalDualSolver> =
+            Box::new(SolverSerial::new_config(&initializer, json!({"erasure_peeling": true})));
+        let mut verifier = VerifierFusionSerial::new(&initializer);
+        let mut peeling_solver = SolverSerial::new(&initializer);
+        let check_correction = |syndrome_pattern: &SyndromePattern, subgraph: &[EdgeIndex]| {
+            let mut is_defect = vec![false; initializer.vertex_num as usize];
+            for &edge_index in subgraph.iter() {
+                let (i, j, _) = initializer.weighted_edges[edge_index as usize];
+                is_defect[i as usize] ^= true;
+                is_defect[j as usize] ^= true;
+            }
+            for &vertex_index in initializer.virtual_vertices.iter() {
+                is_defect[vertex_index as usize] = false;
+            }
+            let mut defect_vertices: Vec<VertexIndex> = (0..initializer.vertex_num)
+                .filter(|&vertex_index| is_defect[vertex_index as usize])
+                .collect();
+            defect_vertices.sort();
+            let mut expected_defect_vertices = syndrome_pattern.defect_vertices.clone();
+            expected_defect_vertices.sort();
+            assert_eq!(defect_vertices, expected_defect_vertices);
+        };
+        for (p, erasure_only) in [(0., true), (0.05, false)] {
+            code.set_probability(p);
+            let mut peeled_count = 0;
+            for seed in 0..50 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                solver.solve(&syndrome_pattern);
+                verifier.verify(&mut solver, &syndrome_pattern, None);
+                solver.perfect_matching().validate(&syndrome_pattern, &initializer).unwrap();
+                check_correction(&syndrome_pattern, &solver.subgraph());
+                solver.reset().unwrap();
+                if let Some(subgraph) = peeling_solver.decode_with_erasures_only(&syndrome_pattern) {
+                    peeled_count += 1;
+                    check_correction(&syndrome_pattern, &subgraph);
+                    assert_eq!(peeling_solver.sum_dual_variables(), 0);
+                    assert_eq!(peeling_solver.subgraph(), subgraph);
+                }
+            }
+            if erasure_only {
+                assert_eq!(
+                    peeled_count, 50,
+                    "erasure-only syndromes are always explained by the erasures"
+                );
+            } else {
+                assert!(peeled_count < 50);
+            }
+        }
+        // the peeled correction is recorded by the visualizer as well
+        code.set_probability(0.);
+        let syndrome_pattern = code.generate_random_errors(0);
+        let visualize_filename = "solver_serial_erasure_peeling_1.json".to_string();
+        let mut visualizer = Visualizer::new(
+            Some(visualize_data_folder() + visualize_filename.as_str()),
+            code.get_positions(),
+            true,
+        )
+        .unwrap();
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        assert_eq!(visualizer.snapshots, vec!["erasure peeling".to_string()]);
+        solver.reset().unwrap();
+        // an error outside the erasures is not explained by them
+        let syndrome_pattern = SyndromePattern::new(vec![0, 1], vec![]);
+        assert!(peeling_solver.decode_with_erasures_only(&syndrome_pattern).is_none());
+        assert!(peeling_solver.erasure_peeling_result.is_none());
+    }
+
+    /// decode with per-shot real-valued weights spanning very different ranges and compare with an exact real-valued
+    /// matching, up to the rounding of the integer weights
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_serial_normalized_weights_1() {
+        // cargo test solver_serial_normalized_weights_1 -- --nocapture
+        use crate::rand_xoshiro::rand_core::SeedableRng;
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let edge_num = initializer.weighted_edges.len();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut rng = DeterministicRng::seed_from_u64(123);
+        let mut compared = 0;
+        for shot in 0..60 {
+            // the typical ratio ranges from 1e-6 to 1e6, and every other shot also spreads over 6 orders of magnitude
+            let range = 10f64.powi(shot % 13 - 6);
+            let spread = if shot % 2 == 0 { 0. } else { 6. };
+            let weights: Vec<f64> = (0..edge_num)
+                .map(|_| {
+                    if rng.next_f64() < 0.05 {
+                        0.
+                    } else {
+                        range * 10f64.powf(spread * (rng.next_f64() - 0.5))
+                    }
+                })
+                .collect();
+            let scale = solver.update_weights_normalized(&weights);
+            let max_weight = solver.normalized_weights.iter().map(|&(_, weight)| weight).max().unwrap();
+            assert_eq!(max_weight, 1 << DEFAULT_WEIGHT_BITS);
+            let syndrome_pattern = code.generate_random_errors(shot as u64);
+            if syndrome_pattern.defect_vertices.len() > 12 {
+                continue;
+            }
+            solver.solve(&syndrome_pattern);
+            let subgraph = solver.subgraph();
+            let defect_vertices: alloc::collections::BTreeSet<VertexIndex> =
+                syndrome_pattern.defect_vertices.iter().cloned().collect();
+            assert_eq!(initializer.syndrome_of(&subgraph), defect_vertices);
+            let subgraph_weight: f64 = subgraph.iter().map(|&edge_index| weights[edge_index as usize]).sum();
+            let optimal_weight = brute_force_matching_weight(&initializer, &weights, &syndrome_pattern.defect_vertices);
+            // each edge weight is off by at most 2 after rounding, and both corrections have at most `edge_num` edges
+            let tolerance = 4. * edge_num as f64 / scale;
+            assert!(
+                subgraph_weight >= optimal_weight - 1e-9 * range && subgraph_weight <= optimal_weight + tolerance,
+                "shot {shot}: weight {subgraph_weight} is not optimal {optimal_weight}"
+            );
+            assert!((solver.sum_dual_variables_llr() - optimal_weight).abs() <= tolerance);
+            solver.clear();
+            compared += 1;
+        }
+        assert!(compared > 30);
+    }
+
+    /// the minimum total real-valued weight of matching the defects with each other or to the boundary, by dynamic
+    /// programming over the subsets of defects
+    #[allow(clippy::unnecessary_cast)]
+    fn brute_force_matching_weight(
+        initializer: &SolverInitializer,
+        weights: &[f64],
+        defect_vertices: &[VertexIndex],
+    ) -> f64 {
+        let vertex_num = initializer.vertex_num as usize;
+        let mut distances = vec![vec![f64::INFINITY; vertex_num]; vertex_num];
+        for (vertex_index, row) in distances.iter_mut().enumerate() {
+            row[vertex_index] = 0.;
+        }
+        for (&(i, j, _), &weight) in initializer.weighted_edges.iter().zip(weights.iter()) {
+            let (i, j) = (i as usize, j as usize);
+            distances[i][j] = distances[i][j].min(weight);
+            distances[j][i] = distances[j][i].min(weight);
+        }
+        for k in 0..vertex_num {
+            for i in 0..vertex_num {
+                for j in 0..vertex_num {
+                    distances[i][j] = distances[i][j].min(distances[i][k] + distances[k][j]);
+                }
+            }
+        }
+        let boundary_distances: Vec<f64> = defect_vertices
+            .iter()
+            .map(|&defect| {
+                initializer
+                    .virtual_vertices
+                    .iter()
+                    .fold(f64::INFINITY, |distance, &virtual_vertex| {
+                        distance.min(distances[defect as usize][virtual_vertex as usize])
+                    })
+            })
+            .collect();
+        let defect_num = defect_vertices.len();
+        let mut best = vec![f64::INFINITY; 1 << defect_num];
+        best[0] = 0.;
+        for mask in 1..(1usize << defect_num) {
+            let first = mask.trailing_zeros() as usize;
+            let rest = mask & !(1 << first);
+            let mut weight = best[rest] + boundary_distances[first];
+            for second in (first + 1)..defect_num {
+                if rest & (1 << second) != 0 {
+                    let distance = distances[defect_vertices[first] as usize][defect_vertices[second] as usize];
+                    weight = weight.min(best[rest & !(1 << second)] + distance);
+                }
+            }
+            best[mask] = weight;
+        }
+        best[(1 << defect_num) - 1]
+    }
+
+    /// drive the primal loop manually with a trivial greedy primal that matches a node to whatever it touches first
+    #[test]
+    fn solver_serial_manual_primal_1() {
+        // cargo test solver_serial_manual_primal_1 -- --nocapture
+        let half_weight = 500;
+        let code = CodeCapacityRepetitionCode::new(15, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let defect_vertices = vec![1, 2, 6, 7, 13];
+        let syndrome_pattern = SyndromePattern::new_vertices(defect_vertices.clone());
+        let mut solver = SolverSerial::new(&initializer);
+        solver.load_syndrome(&syndrome_pattern);
+        let node_index = |dual_node_ptr: &DualNodePtr| dual_node_ptr.read_recursive().index;
+        let mut matched: BTreeMap<NodeIndex, Option<NodeIndex>> = BTreeMap::new();
+        while !solver.is_converged() {
+            let conflicts = match solver.compute_maximum_update_length() {
+                GroupMaxUpdateLength::NonZeroGrow((length, _)) => {
+                    solver.grow(length).unwrap();
+                    continue;
+                }
+                GroupMaxUpdateLength::Conflicts((conflicts, _)) => conflicts,
+            };
+            for conflict in conflicts.iter() {
+                match conflict {
+                    MaxUpdateLength::Conflicting((node_1, _), (node_2, _)) => {
+                        let (node_1, node_2) = (node_index(node_1), node_index(node_2));
+                        if matched.contains_key(&node_1) && matched.contains_key(&node_2) {
+                            continue; // the same conflict may be reported from both sides
+                        }
+                        assert!(!matched.contains_key(&node_1) && !matched.contains_key(&node_2));
+                        matched.insert(node_1, Some(node_2));
+                        matched.insert(node_2, Some(node_1));
+                        solver.set_grow_state(node_1, DualNodeGrowState::Stay).unwrap();
+                        solver.set_grow_state(node_2, DualNodeGrowState::Stay).unwrap();
+                    }
+                    MaxUpdateLength::TouchingVirtual((node, _), _) => {
+                        let node = node_index(node);
+                        if let alloc::collections::btree_map::Entry::Vacant(entry) = matched.entry(node) {
+                            entry.insert(None);
+                            solver.set_grow_state(node, DualNodeGrowState::Stay).unwrap();
+                        }
+                    }
+                    _ => unreachable!("greedy primal cannot resolve {conflict:?}"),
+                }
+            }
+        }
+        // every defect is matched exactly once, either to another defect or to a virtual vertex
+        let dual_state = solver.dual_state();
+        let interface_nodes = dual_state["interface_nodes"].as_array().unwrap();
+        let mut node_defects: Vec<VertexIndex> = interface_nodes
+            .iter()
+            .map(|node| node["defect_vertex"].as_u64().unwrap() as VertexIndex)
+            .collect();
+        node_defects.sort();
+        assert_eq!(node_defects, defect_vertices);
+        assert_eq!(matched.len(), interface_nodes.len());
+        for (node, peer) in matched.iter() {
+            assert!(peer.is_none_or(|peer| matched[&peer] == Some(*node)));
+        }
+        assert!(interface_nodes.iter().all(|node| node["grow_state"] == "stay"));
+        // the dual variables remain feasible, so their sum is a lower bound of the minimum weight
+        let mut reference = SolverSerial::new(&initializer);
+        reference.solve(&syndrome_pattern);
+        assert!(solver.sum_dual_variables() <= reference.sum_dual_variables());
+    }
+
+    /// the manual primal steps reject illegal nodes and growths without modifying the dual state
+    #[test]
+    fn solver_serial_manual_primal_2() {
+        // cargo test solver_serial_manual_primal_2 -- --nocapture
+        let half_weight = 500;
+        let code = CodeCapacityRepetitionCode::new(5, 0.1, half_weight);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        solver.load_syndrome(&SyndromePattern::new_vertices(vec![1, 2]));
+        assert_eq!(
+            solver.set_grow_state(5, DualNodeGrowState::Stay),
+            Err(GrowStateError::NotInInterface { node_index: 5 })
+        );
+        let dual_state = solver.dual_state();
+        let length = solver.compute_maximum_update_length().get_none_zero_growth().unwrap();
+        assert_eq!(length, half_weight);
+        assert!(solver.grow(length + 1).is_err());
+        assert_eq!(solver.dual_state(), dual_state);
+        solver.grow(length).unwrap();
+        assert_eq!(solver.sum_dual_variables(), 2 * length);
+        // the two nodes touch each other, so nothing can grow until the conflict is resolved
+        assert!(matches!(
+            solver.compute_maximum_update_length(),
+            GroupMaxUpdateLength::Conflicts(_)
+        ));
+        assert!(solver.grow(1).is_err());
+        solver.set_grow_state(0, DualNodeGrowState::Stay).unwrap();
+        solver.set_grow_state(1, DualNodeGrowState::Stay).unwrap();
+        assert!(solver.is_converged());
+    }
+
+    /// a component without virtual vertices and an odd number of defects is reported or repaired by the policy
+    #[test]
+    fn solver_serial_odd_parity_1() {
+        // cargo test solver_serial_odd_parity_1 -- --nocapture
+        // component 0: a chain 0-1-2-3 with virtual vertex 0; component 4: a chain 4-5-6-7-8 without virtual vertex
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 2), (2, 3, 2), (4, 5, 12), (5, 6, 2), (6, 7, 12), (7, 8, 14)];
+        let initializer = SolverInitializer::new(9, weighted_edges, vec![0]);
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![2, 4, 5, 6, 7, 8]);
+        let mut solver = SolverSerial::new(&initializer);
+        assert_eq!(
+            solver.try_solve(&syndrome_pattern),
+            Err(SolveError::OddParity {
+                component: 4,
+                defect_count: 5
+            })
+        );
+        solver.check_clean().unwrap();
+        // an even number of defects in the component is fine
+        solver.try_solve(&SyndromePattern::new_vertices(vec![2, 4, 5])).unwrap();
+        assert_eq!(solver.sum_dual_variables(), 4 + 12);
+        assert!(solver.odd_parity_repairs().is_empty());
+        solver.reset().unwrap();
+        // vertex 8 is the most isolated: its nearest defect is 14 away, while the others have one within 12;
+        // the remaining defects are matched as (4, 5) and (6, 7)
+        // pairing the closest defects first gives (5, 6) and (7, 8), leaving vertex 4 alone
+        // the vertex left alone is matched to virtual vertex 0 in the other component
+        for (policy, removed_defect, matched_virtual, expected_weight) in [
+            (OddParityPolicy::DropFarthest, 8, None, 4 + 12 + 12),
+            (OddParityPolicy::MatchToNearestVirtualEvenIfFar, 4, Some(0), 4 + 2 + 14),
+        ] {
+            assert_eq!(serde_json::from_value::<OddParityPolicy>(json!(policy)).unwrap(), policy);
+            solver.subgraph_builder.odd_parity_policy = policy;
+            solver.try_solve(&syndrome_pattern).unwrap();
+            assert_eq!(solver.sum_dual_variables(), expected_weight);
+            assert_eq!(
+                solver.odd_parity_repairs(),
+                vec![OddParityRepair {
+                    component: 4,
+                    defect_count: 5,
+                    removed_defect,
+                    policy,
+                    matched_virtual,
+                }]
+            );
+            solver.reset().unwrap();
+        }
+        assert_eq!(
+            serde_json::from_value::<OddParityPolicy>(json!("match_to_nearest_virtual_even_if_far")).unwrap(),
+            OddParityPolicy::MatchToNearestVirtualEvenIfFar
+        );
+    }
+}
@@ -5,11 +5,20 @@
 //! The solution is to write everything in safe Rust, and debug them.
 //! After this, one can enable the feature `unsafe_pointer` to remove the unnecessary locks, thus improving the performance.
 //!
+//! All the pointers are built on [`Arc`] and [`Weak`] (raw pointers into an [`Arc`] with `dangerous_pointer`), never on
+//! `Rc`, so every object holding them is `Send` as long as the pointed objects are; the solvers are checked at compile
+//! time to stay so, see `mwpm_solver.rs`. The unsafe pointers are only sound under the single-writer discipline of the
+//! algorithm, so a solver is not meant to be `Sync` with `unsafe_pointer` and sharing it across threads requires
+//! external synchronization like a `Mutex`.
+//!
 
 use super::util::*;
+#[cfg(feature = "std")]
 use crate::parking_lot::lock_api::{RwLockReadGuard, RwLockWriteGuard};
+#[cfg(feature = "std")]
 use crate::parking_lot::{RawRwLock, RwLock};
-use std::sync::{Arc, Weak};
+use crate::prelude::*;
+use alloc::sync::{Arc, Weak};
 
 /// allows fast reset of vector of objects without iterating over all objects each time: dynamically clear it
 pub trait FastClear {
@@ -43,6 +52,34 @@ pub trait FastClear {
     }
 }
 
+/// a short description of an object, e.g. its type and index, that the weak pointers record at downgrade time when the
+/// feature `pointer_diagnostics` is enabled, so that upgrading a dead weak pointer tells which object it pointed to
+pub trait PointerTag {
+    fn pointer_tag(&self) -> String {
+        core::any::type_name::<Self>().to_string()
+    }
+}
+
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+fn pointer_tag_of<T: PointerTag>(ptr: &Arc<RwLock<T>>) -> Arc<str> {
+    // the object may be locked by the caller of `downgrade`, in which case only the type is known
+    match ptr.try_read_recursive() {
+        Some(obj) => obj.pointer_tag().into(),
+        None => format!("{} (locked at downgrade)", core::any::type_name::<T>()).into(),
+    }
+}
+
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+#[track_caller]
+fn dead_pointer_panic(tag: &str) -> ! {
+    panic!(
+        "upgrading a dead weak pointer to {} at {}",
+        tag,
+        core::panic::Location::caller()
+    )
+}
+
+#[cfg(feature = "std")]
 pub trait FastClearRwLockPtr<ObjType>
 where
     ObjType: FastClear,
@@ -56,7 +93,7 @@ where
     fn ptr_mut(&mut self) -> &mut Arc<RwLock<ObjType>>;
 
     #[inline(always)]
-    fn read_recursive(&self, active_timestamp: FastClearTimestamp) -> RwLockReadGuard<RawRwLock, ObjType> {
+    fn read_recursive(&self, active_timestamp: FastClearTimestamp) -> RwLockReadGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().read_recursive();
         ret.debug_assert_dynamic_cleared(active_timestamp); // only assert during debug modes
         ret
@@ -64,13 +101,13 @@ where
 
     /// without sanity check: this data might be outdated, so only use when you're read those immutable fields
     #[inline(always)]
-    fn read_recursive_force(&self) -> RwLockReadGuard<RawRwLock, ObjType> {
+    fn read_recursive_force(&self) -> RwLockReadGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().read_recursive();
         ret
     }
 
     #[inline(always)]
-    fn write(&self, active_timestamp: FastClearTimestamp) -> RwLockWriteGuard<RawRwLock, ObjType> {
+    fn write(&self, active_timestamp: FastClearTimestamp) -> RwLockWriteGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().write();
         ret.debug_assert_dynamic_cleared(active_timestamp); // only assert during debug modes
         ret
@@ -78,7 +115,7 @@ where
 
     /// without sanity check: useful only in implementing hard_clear
     #[inline(always)]
-    fn write_force(&self) -> RwLockWriteGuard<RawRwLock, ObjType> {
+    fn write_force(&self) -> RwLockWriteGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().write();
         ret
     }
@@ -95,6 +132,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 pub trait RwLockPtr<ObjType> {
     fn new_ptr(ptr: Arc<RwLock<ObjType>>) -> Self;
 
@@ -105,13 +143,13 @@ pub trait RwLockPtr<ObjType> {
     fn ptr_mut(&mut self) -> &mut Arc<RwLock<ObjType>>;
 
     #[inline(always)]
-    fn read_recursive(&self) -> RwLockReadGuard<RawRwLock, ObjType> {
+    fn read_recursive(&self) -> RwLockReadGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().read_recursive();
         ret
     }
 
     #[inline(always)]
-    fn write(&self) -> RwLockWriteGuard<RawRwLock, ObjType> {
+    fn write(&self) -> RwLockWriteGuard<'_, RawRwLock, ObjType> {
         let ret = self.ptr().write();
         ret
     }
@@ -121,14 +159,19 @@ pub trait RwLockPtr<ObjType> {
     }
 }
 
+#[cfg(feature = "std")]
 pub struct ArcRwLock<T> {
     ptr: Arc<RwLock<T>>,
 }
 
+#[cfg(feature = "std")]
 pub struct WeakRwLock<T> {
     ptr: Weak<RwLock<T>>,
+    #[cfg(feature = "pointer_diagnostics")]
+    tag: Arc<str>,
 }
 
+#[cfg(all(feature = "std", not(feature = "pointer_diagnostics")))]
 impl<T> ArcRwLock<T> {
     pub fn downgrade(&self) -> WeakRwLock<T> {
         WeakRwLock::<T> {
@@ -137,23 +180,39 @@ impl<T> ArcRwLock<T> {
     }
 }
 
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+impl<T: PointerTag> ArcRwLock<T> {
+    pub fn downgrade(&self) -> WeakRwLock<T> {
+        WeakRwLock::<T> {
+            ptr: Arc::downgrade(&self.ptr),
+            tag: pointer_tag_of(&self.ptr),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl<T> WeakRwLock<T> {
+    #[cfg_attr(feature = "pointer_diagnostics", track_caller)]
     pub fn upgrade_force(&self) -> ArcRwLock<T> {
-        ArcRwLock::<T> {
-            ptr: self.ptr.upgrade().unwrap(),
-        }
+        #[cfg(feature = "pointer_diagnostics")]
+        let ptr = self.ptr.upgrade().unwrap_or_else(|| dead_pointer_panic(&self.tag));
+        #[cfg(not(feature = "pointer_diagnostics"))]
+        let ptr = self.ptr.upgrade().unwrap();
+        ArcRwLock::<T> { ptr }
     }
     pub fn upgrade(&self) -> Option<ArcRwLock<T>> {
         self.ptr.upgrade().map(|x| ArcRwLock::<T> { ptr: x })
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> Clone for ArcRwLock<T> {
     fn clone(&self) -> Self {
         Self::new_ptr(Arc::clone(self.ptr()))
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> RwLockPtr<T> for ArcRwLock<T> {
     fn new_ptr(ptr: Arc<RwLock<T>>) -> Self {
         Self { ptr }
@@ -171,35 +230,46 @@ impl<T> RwLockPtr<T> for ArcRwLock<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> PartialEq for ArcRwLock<T> {
     fn eq(&self, other: &Self) -> bool {
         self.ptr_eq(other)
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> Eq for ArcRwLock<T> {}
 
+#[cfg(feature = "std")]
 impl<T> Clone for WeakRwLock<T> {
     fn clone(&self) -> Self {
-        Self { ptr: self.ptr.clone() }
+        Self {
+            ptr: self.ptr.clone(),
+            #[cfg(feature = "pointer_diagnostics")]
+            tag: self.tag.clone(),
+        }
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> PartialEq for WeakRwLock<T> {
     fn eq(&self, other: &Self) -> bool {
         self.ptr.ptr_eq(&other.ptr)
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> Eq for WeakRwLock<T> {}
 
-impl<T> std::ops::Deref for ArcRwLock<T> {
+#[cfg(feature = "std")]
+impl<T> core::ops::Deref for ArcRwLock<T> {
     type Target = RwLock<T>;
     fn deref(&self) -> &Self::Target {
         &self.ptr
     }
 }
 
+#[cfg(all(feature = "std", not(feature = "pointer_diagnostics")))]
 impl<T> weak_table::traits::WeakElement for WeakRwLock<T> {
     type Strong = ArcRwLock<T>;
     fn new(view: &Self::Strong) -> Self {
@@ -213,14 +283,33 @@ impl<T> weak_table::traits::WeakElement for WeakRwLock<T> {
     }
 }
 
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+impl<T: PointerTag> weak_table::traits::WeakElement for WeakRwLock<T> {
+    type Strong = ArcRwLock<T>;
+    fn new(view: &Self::Strong) -> Self {
+        view.downgrade()
+    }
+    fn view(&self) -> Option<Self::Strong> {
+        self.upgrade()
+    }
+    fn clone(view: &Self::Strong) -> Self::Strong {
+        view.clone()
+    }
+}
+
+#[cfg(feature = "std")]
 pub struct FastClearArcRwLock<T: FastClear> {
     ptr: Arc<RwLock<T>>,
 }
 
+#[cfg(feature = "std")]
 pub struct FastClearWeakRwLock<T: FastClear> {
     ptr: Weak<RwLock<T>>,
+    #[cfg(feature = "pointer_diagnostics")]
+    tag: Arc<str>,
 }
 
+#[cfg(all(feature = "std", not(feature = "pointer_diagnostics")))]
 impl<T: FastClear> FastClearArcRwLock<T> {
     pub fn downgrade(&self) -> FastClearWeakRwLock<T> {
         FastClearWeakRwLock::<T> {
@@ -229,23 +318,39 @@ impl<T: FastClear> FastClearArcRwLock<T> {
     }
 }
 
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+impl<T: FastClear + PointerTag> FastClearArcRwLock<T> {
+    pub fn downgrade(&self) -> FastClearWeakRwLock<T> {
+        FastClearWeakRwLock::<T> {
+            ptr: Arc::downgrade(&self.ptr),
+            tag: pointer_tag_of(&self.ptr),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl<T: FastClear> FastClearWeakRwLock<T> {
+    #[cfg_attr(feature = "pointer_diagnostics", track_caller)]
     pub fn upgrade_force(&self) -> FastClearArcRwLock<T> {
-        FastClearArcRwLock::<T> {
-            ptr: self.ptr.upgrade().unwrap(),
-        }
+        #[cfg(feature = "pointer_diagnostics")]
+        let ptr = self.ptr.upgrade().unwrap_or_else(|| dead_pointer_panic(&self.tag));
+        #[cfg(not(feature = "pointer_diagnostics"))]
+        let ptr = self.ptr.upgrade().unwrap();
+        FastClearArcRwLock::<T> { ptr }
     }
     pub fn upgrade(&self) -> Option<FastClearArcRwLock<T>> {
         self.ptr.upgrade().map(|x| FastClearArcRwLock::<T> { ptr: x })
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> Clone for FastClearArcRwLock<T> {
     fn clone(&self) -> Self {
         Self::new_ptr(Arc::clone(self.ptr()))
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> FastClearRwLockPtr<T> for FastClearArcRwLock<T> {
     fn new_ptr(ptr: Arc<RwLock<T>>) -> Self {
         Self { ptr }
@@ -263,35 +368,46 @@ impl<T: FastClear> FastClearRwLockPtr<T> for FastClearArcRwLock<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> PartialEq for FastClearArcRwLock<T> {
     fn eq(&self, other: &Self) -> bool {
         self.ptr_eq(other)
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> Eq for FastClearArcRwLock<T> {}
 
+#[cfg(feature = "std")]
 impl<T: FastClear> Clone for FastClearWeakRwLock<T> {
     fn clone(&self) -> Self {
-        Self { ptr: self.ptr.clone() }
+        Self {
+            ptr: self.ptr.clone(),
+            #[cfg(feature = "pointer_diagnostics")]
+            tag: self.tag.clone(),
+        }
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> PartialEq for FastClearWeakRwLock<T> {
     fn eq(&self, other: &Self) -> bool {
         self.ptr.ptr_eq(&other.ptr)
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastClear> Eq for FastClearWeakRwLock<T> {}
 
-impl<T: FastClear> std::ops::Deref for FastClearArcRwLock<T> {
+#[cfg(feature = "std")]
+impl<T: FastClear> core::ops::Deref for FastClearArcRwLock<T> {
     type Target = RwLock<T>;
     fn deref(&self) -> &Self::Target {
         &self.ptr
     }
 }
 
+#[cfg(all(feature = "std", not(feature = "pointer_diagnostics")))]
 impl<T: FastClear> weak_table::traits::WeakElement for FastClearWeakRwLock<T> {
     type Strong = FastClearArcRwLock<T>;
     fn new(view: &Self::Strong) -> Self {
@@ -305,14 +421,45 @@ impl<T: FastClear> weak_table::traits::WeakElement for FastClearWeakRwLock<T> {
     }
 }
 
+#[cfg(all(feature = "std", feature = "pointer_diagnostics"))]
+impl<T: FastClear + PointerTag> weak_table::traits::WeakElement for FastClearWeakRwLock<T> {
+    type Strong = FastClearArcRwLock<T>;
+    fn new(view: &Self::Strong) -> Self {
+        view.downgrade()
+    }
+    fn view(&self) -> Option<Self::Strong> {
+        self.upgrade()
+    }
+    fn clone(view: &Self::Strong) -> Self::Strong {
+        view.clone()
+    }
+}
+
 /*
  * unsafe APIs, used for production environment where speed matters
  */
 
 cfg_if::cfg_if! {
     if #[cfg(feature="unsafe_pointer")] {
-        use std::cell::UnsafeCell;
 
+        /// the same as the nightly `Arc::get_mut_unchecked` but available on stable Rust, without touching the reference
+        /// count; the caller must guarantee that there is a single writer at any time, which the algorithm ensures as in
+        /// the [`RwLock`] implementation
+        #[inline(always)]
+        #[allow(clippy::mut_from_ref)]
+        unsafe fn arc_get_mut_unchecked<T>(ptr: &Arc<T>) -> &mut T {
+            // SAFETY: `Arc::as_ptr` derives the pointer from the heap allocation owned by the `Arc`, not from the
+            // shared `&Arc<T>`, so writing through it is allowed as long as the allocation is alive, which the borrow
+            // of `ptr` guarantees for the returned lifetime. This is exactly what `Arc::get_mut_unchecked` does. The
+            // remaining requirement is the single-writer invariant: no other `&T` or `&mut T` to the same object is
+            // used while the returned reference is alive. The primal and dual modules keep it the same way they
+            // would hold a write lock: an object is only written by the module that owns it, or by a fusion step
+            // that runs after its children have finished, which is why the lock-free build gives the same results
+            // as the [`RwLock`] build in every test.
+            &mut *(Arc::as_ptr(ptr) as *mut T)
+        }
+
+        #[allow(clippy::mut_from_ref)]  // a writer is unique by the single-writer invariant, see `arc_get_mut_unchecked`
         pub trait FastClearUnsafePtr<ObjType> where ObjType: FastClear {
 
             fn new_ptr(ptr: Arc<ObjType>) -> Self;
@@ -339,8 +486,7 @@ cfg_if::cfg_if! {
             #[inline(always)]
             fn write(&self, active_timestamp: FastClearTimestamp) -> &mut ObjType {
                 unsafe {
-                    let ptr = UnsafeCell::new(self.ptr().clone());
-                    let ret = Arc::get_mut_unchecked(&mut *ptr.get());
+                    let ret = arc_get_mut_unchecked(self.ptr());
                     ret.debug_assert_dynamic_cleared(active_timestamp);  // only assert during debug modes
                     ret
                 }
@@ -355,8 +501,7 @@ cfg_if::cfg_if! {
             #[inline(always)]
             fn write_force(&self) -> &mut ObjType {
                 unsafe {
-                    let ptr = UnsafeCell::new(self.ptr().clone());
-                    Arc::get_mut_unchecked(&mut *ptr.get())
+                    arc_get_mut_unchecked(self.ptr())
                 }
             }
 
@@ -373,6 +518,7 @@ cfg_if::cfg_if! {
 
         }
 
+        #[allow(clippy::mut_from_ref)]  // a writer is unique by the single-writer invariant, see `arc_get_mut_unchecked`
         pub trait UnsafePtr<ObjType> {
 
             fn new_ptr(ptr: Arc<ObjType>) -> Self;
@@ -391,8 +537,7 @@ cfg_if::cfg_if! {
             #[inline(always)]
             fn write(&self) -> &mut ObjType {
                 unsafe {
-                    let ptr = UnsafeCell::new(self.ptr().clone());
-                    Arc::get_mut_unchecked(&mut *ptr.get())
+                    arc_get_mut_unchecked(self.ptr())
                 }
             }
 
@@ -453,6 +598,11 @@ cfg_if::cfg_if! {
 
         impl<T> Eq for ArcUnsafe<T> { }
 
+        /// hashes the address, consistent with [`PartialEq`]; used to look up dual nodes without the weak tables of `std`
+        impl<T> core::hash::Hash for ArcUnsafe<T> {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) { Arc::as_ptr(&self.ptr).hash(state) }
+        }
+
         impl<T> Clone for WeakUnsafe<T> {
             fn clone(&self) -> Self {
                 Self { ptr: self.ptr.clone() }
@@ -465,13 +615,14 @@ cfg_if::cfg_if! {
 
         impl<T> Eq for WeakUnsafe<T> { }
 
-        impl<T> std::ops::Deref for ArcUnsafe<T> {
+        impl<T> core::ops::Deref for ArcUnsafe<T> {
             type Target = T;
             fn deref(&self) -> &Self::Target {
                 &self.ptr
             }
         }
 
+        #[cfg(feature = "std")]
         impl<T> weak_table::traits::WeakElement for WeakUnsafe<T> {
             type Strong = ArcUnsafe<T>;
             fn new(view: &Self::Strong) -> Self {
@@ -543,13 +694,14 @@ cfg_if::cfg_if! {
 
         impl<T: FastClear> Eq for FastClearWeakUnsafe<T> { }
 
-        impl<T: FastClear> std::ops::Deref for FastClearArcUnsafe<T> {
+        impl<T: FastClear> core::ops::Deref for FastClearArcUnsafe<T> {
             type Target = T;
             fn deref(&self) -> &Self::Target {
                 &self.ptr
             }
         }
 
+        #[cfg(feature = "std")]
         impl<T: FastClear> weak_table::traits::WeakElement for FastClearWeakUnsafe<T> {
             type Strong = FastClearArcUnsafe<T>;
             fn new(view: &Self::Strong) -> Self {
@@ -570,6 +722,7 @@ cfg_if::cfg_if! {
 cfg_if::cfg_if! {
     if #[cfg(feature="dangerous_pointer")] {
 
+        #[allow(clippy::mut_from_ref)]  // a writer is unique by the single-writer invariant, see `arc_get_mut_unchecked`
         pub trait FastClearUnsafePtrDangerous<ObjType> where ObjType: FastClear {
 
             fn new_ptr(ptr: Arc<ObjType>) -> Self;
@@ -631,7 +784,7 @@ cfg_if::cfg_if! {
 
             #[inline(always)]
             fn ptr_eq(&self, other: &Self) -> bool {
-                std::ptr::eq(self.ptr(), other.ptr())
+                core::ptr::eq(self.ptr(), other.ptr())
             }
 
         }
@@ -644,11 +797,14 @@ cfg_if::cfg_if! {
             raw_ptr: *const T,
         }
 
-        unsafe impl<T: FastClear> Send for FastClearArcUnsafeDangerous<T> {}
-        unsafe impl<T: FastClear> Sync for FastClearArcUnsafeDangerous<T> {}
+        // SAFETY: the strong pointer is an `Arc` and the weak pointer only points into one that the owner keeps alive,
+        // so both are exactly as thread-safe as `Arc<T>`, which requires `T: Send + Sync`; without this bound a
+        // non-thread-safe object could be smuggled across threads through the raw pointer
+        unsafe impl<T: FastClear + Send + Sync> Send for FastClearArcUnsafeDangerous<T> {}
+        unsafe impl<T: FastClear + Send + Sync> Sync for FastClearArcUnsafeDangerous<T> {}
 
-        unsafe impl<T: FastClear> Send for FastClearWeakUnsafeDangerous<T> {}
-        unsafe impl<T: FastClear> Sync for FastClearWeakUnsafeDangerous<T> {}
+        unsafe impl<T: FastClear + Send + Sync> Send for FastClearWeakUnsafeDangerous<T> {}
+        unsafe impl<T: FastClear + Send + Sync> Sync for FastClearWeakUnsafeDangerous<T> {}
 
         impl<T: FastClear> FastClearArcUnsafeDangerous<T> {
             #[inline(always)]
@@ -716,12 +872,12 @@ cfg_if::cfg_if! {
 
         impl<T: FastClear> PartialEq for FastClearWeakUnsafeDangerous<T> {
             #[inline(always)]
-            fn eq(&self, other: &Self) -> bool { std::ptr::eq(self.ptr(), other.ptr()) }
+            fn eq(&self, other: &Self) -> bool { core::ptr::eq(self.ptr(), other.ptr()) }
         }
 
         impl<T: FastClear> Eq for FastClearWeakUnsafeDangerous<T> { }
 
-        impl<T: FastClear> std::ops::Deref for FastClearArcUnsafeDangerous<T> {
+        impl<T: FastClear> core::ops::Deref for FastClearArcUnsafeDangerous<T> {
             type Target = T;
             #[inline(always)]
             fn deref(&self) -> &Self::Target {
@@ -729,6 +885,7 @@ cfg_if::cfg_if! {
             }
         }
 
+        #[cfg(feature = "std")]
         impl<T: FastClear> weak_table::traits::WeakElement for FastClearWeakUnsafeDangerous<T> {
             type Strong = FastClearWeakUnsafeDangerous<T>;
             #[inline(always)]
@@ -797,15 +954,21 @@ mod tests {
     type TesterPtr = ArcRwLock<Tester>;
     type TesterWeak = WeakRwLock<Tester>;
 
-    impl std::fmt::Debug for TesterPtr {
-        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    impl PointerTag for Tester {
+        fn pointer_tag(&self) -> String {
+            format!("Tester {}", self.idx)
+        }
+    }
+
+    impl core::fmt::Debug for TesterPtr {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
             let value = self.read_recursive();
             write!(f, "{:?}", value)
         }
     }
 
-    impl std::fmt::Debug for TesterWeak {
-        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    impl core::fmt::Debug for TesterWeak {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
             self.upgrade_force().fmt(f)
         }
     }
@@ -821,6 +984,17 @@ mod tests {
         assert_eq!(ptr.read_recursive().idx, 2);
     }
 
+    #[cfg(feature = "pointer_diagnostics")]
+    #[test]
+    #[should_panic(expected = "upgrading a dead weak pointer to Tester 3 at src/pointers.rs")]
+    fn pointers_diagnostics_1() {
+        // cargo test pointers_diagnostics_1 --features pointer_diagnostics -- --nocapture
+        let ptr = TesterPtr::new_value(Tester { idx: 3 });
+        let weak = ptr.downgrade();
+        drop(ptr);
+        weak.upgrade_force();
+    }
+
     cfg_if::cfg_if! {
         if #[cfg(feature="unsafe_pointer")] {
 
@@ -838,9 +1012,9 @@ mod tests {
 
             #[test]
             fn pointers_test_3() {  // cargo test pointers_test_3 --features dangerous_pointer -- --nocapture
-                println!("{}", std::mem::size_of::<ArcManualSafeLock<Tester>>());
-                println!("{}", std::mem::size_of::<Arc<Tester>>());
-                println!("{}", std::mem::size_of::<*const Tester>());
+                println!("{}", core::mem::size_of::<ArcManualSafeLock<Tester>>());
+                println!("{}", core::mem::size_of::<Arc<Tester>>());
+                println!("{}", core::mem::size_of::<*const Tester>());
             }
 
         }
@@ -6,17 +6,20 @@
 #![cfg_attr(feature = "unsafe_pointer", allow(dropping_references))]
 use super::complete_graph::*;
 use super::dual_module::*;
+use super::mwpm_solver::SolveError;
 use super::pointers::*;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
+use crate::prelude::*;
+use crate::serde::{Deserialize, Serialize};
+use alloc::collections::{BTreeMap, BTreeSet};
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Derivative)]
 #[derivative(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct IntermediateMatching {
     /// matched pairs; note that each pair will only appear once. (node_1, touching_1), (node_2, touching_2)
@@ -25,15 +28,218 @@ pub struct IntermediateMatching {
     pub virtual_matchings: Vec<((DualNodePtr, DualNodeWeak), VertexIndex)>,
 }
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Derivative)]
 #[derivative(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PerfectMatching {
     /// matched pairs; note that each pair will only appear once. (defect_node_1, defect_node_2)
     pub peer_matchings: Vec<(DualNodePtr, DualNodePtr)>,
     /// those nodes matched to the boundary. (syndrome node, virtual_vertex)
     pub virtual_matchings: Vec<(DualNodePtr, VertexIndex)>,
+    /// the tight-edge path realizing each matched pair, only recorded on request by
+    /// [`PerfectMatching::record_realized_paths`] because it costs a search per pair; peer matchings come first and
+    /// then virtual matchings, in the same order
+    pub realized_paths: Option<Vec<(DefectIndex, VertexIndex, Vec<EdgeIndex>)>>,
+    /// the lowest-weight alternative boundaries of each boundary-matched defect, only computed when
+    /// [`SubGraphBuilder::boundary_alternatives_k`] is set; see [`BoundaryAlternatives`]
+    pub boundary_alternatives: Option<Vec<BoundaryAlternatives>>,
+}
+
+/// the next-best virtual vertices that a boundary-matched defect could have matched to.
+/// note that this is an approximation based on local alternatives: each alternative only replaces the virtual
+/// vertex of this single defect while keeping the rest of the matching, rather than re-optimizing globally
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoundaryAlternatives {
+    /// the defect vertex matched to the boundary
+    pub defect_vertex: VertexIndex,
+    /// the virtual vertex it's matched to and the weight of the minimum-weight path
+    pub matched: (VertexIndex, Weight),
+    /// at most k other virtual vertices, sorted by weight: (virtual_vertex, weight, weight difference to the matched one)
+    pub alternatives: Vec<(VertexIndex, Weight, Weight)>,
+}
+
+/// the matched pairs present in one matching but not the other, in the format of [`PerfectMatching::matched_pairs`].
+/// when both matchings have the same weight, the differing pairs characterize the degeneracy of the MWPM;
+/// otherwise they localize the discrepancy, e.g. when investigating a verifier mismatch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchingDiff {
+    /// pairs only present in the first matching
+    pub only_in_self: Vec<(DefectIndex, VertexIndex)>,
+    /// pairs only present in the second matching
+    pub only_in_other: Vec<(DefectIndex, VertexIndex)>,
+}
+
+impl MatchingDiff {
+    pub fn new(pairs_1: &[(DefectIndex, VertexIndex)], pairs_2: &[(DefectIndex, VertexIndex)]) -> Self {
+        let set_1: BTreeSet<_> = pairs_1.iter().copied().collect();
+        let set_2: BTreeSet<_> = pairs_2.iter().copied().collect();
+        Self {
+            only_in_self: set_1.difference(&set_2).copied().collect(),
+            only_in_other: set_2.difference(&set_1).copied().collect(),
+        }
+    }
+
+    /// the matched pairs of a legacy matching result, e.g. from the blossom V library, see [`crate::blossom_v_mwpm`]
+    pub fn legacy_matched_pairs(
+        defect_vertices: &[VertexIndex],
+        mwpm_result: &[DefectIndex],
+    ) -> Vec<(DefectIndex, VertexIndex)> {
+        assert_eq!(defect_vertices.len(), mwpm_result.len(), "invalid mwpm result");
+        let is_defect: BTreeSet<VertexIndex> = defect_vertices.iter().copied().collect();
+        let mut matched_pairs: Vec<_> = defect_vertices
+            .iter()
+            .zip(mwpm_result.iter())
+            .filter(|(a, b)| !is_defect.contains(b) || a < b)
+            .map(|(a, b)| (*a, *b))
+            .collect();
+        matched_pairs.sort();
+        matched_pairs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty()
+    }
+
+    /// the correction subgraphs of the differing pairs of each side, computed using the given builder which is cleared
+    pub fn subgraphs(&self, subgraph_builder: &mut SubGraphBuilder) -> ((Vec<EdgeIndex>, Weight), (Vec<EdgeIndex>, Weight)) {
+        let mut subgraph_of = |pairs: &[(DefectIndex, VertexIndex)]| {
+            subgraph_builder.clear();
+            for &(vertex_1, vertex_2) in pairs.iter() {
+                subgraph_builder.add_matching(vertex_1, vertex_2);
+            }
+            (subgraph_builder.get_subgraph(), subgraph_builder.total_weight())
+        };
+        let subgraph_self = subgraph_of(&self.only_in_self);
+        let subgraph_other = subgraph_of(&self.only_in_other);
+        subgraph_builder.clear();
+        (subgraph_self, subgraph_other)
+    }
+}
+
+/// the defects that resolved together in a [`PerfectMatching`], see [`PerfectMatching::by_cluster`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClusterResult {
+    /// the defect vertices in this cluster, sorted
+    pub defect_vertices: Vec<VertexIndex>,
+    /// matched pairs of defects inside this cluster, in the format of [`PerfectMatching::matched_pairs`]
+    pub peer_matchings: Vec<(DefectIndex, VertexIndex)>,
+    /// defects of this cluster matched to the boundary: (defect_vertex, virtual_vertex), sorted
+    pub virtual_matchings: Vec<(DefectIndex, VertexIndex)>,
+}
+
+/// per-vertex aggregation of [`PerfectMatching::weight_attribution`] across many shots, useful for spotting
+/// detectors with systematically large weights, e.g. due to miscalibrated error rates
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeightAttributionStats {
+    /// the number of shots added
+    pub shots: usize,
+    /// (the number of times the vertex is a defect, the sum of its attributed weights); the sum is widened to
+    /// avoid overflow when aggregating a long benchmark
+    pub vertices: BTreeMap<VertexIndex, (usize, i128)>,
+}
+
+impl WeightAttributionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add the attribution of a single shot
+    pub fn add(&mut self, attribution: &[(DefectIndex, Weight)]) {
+        self.shots += 1;
+        for &(vertex_index, weight) in attribution.iter() {
+            let (count, sum) = self.vertices.entry(vertex_index).or_insert((0, 0));
+            *count += 1;
+            *sum += weight as i128;
+        }
+    }
+
+    /// the mean attributed weight of a vertex when it's a defect
+    pub fn mean(&self, vertex_index: VertexIndex) -> Option<f64> {
+        self.vertices
+            .get(&vertex_index)
+            .map(|&(count, sum)| sum as f64 / count as f64)
+    }
+
+    /// `{ shots, vertices: [{ vertex, count, mean }] }`
+    pub fn to_json(&self) -> serde_json::Value {
+        let vertices: Vec<serde_json::Value> = self
+            .vertices
+            .iter()
+            .map(|(&vertex_index, &(count, sum))| {
+                json!({
+                    "vertex": vertex_index,
+                    "count": count,
+                    "mean": sum as f64 / count as f64,
+                })
+            })
+            .collect();
+        json!({
+            "shots": self.shots,
+            "vertices": vertices,
+        })
+    }
+}
+
+/// statistics of a single decoding; the blossom statistics are only recorded when thrashing detection is enabled,
+/// see [`crate::primal_module_serial::PrimalModuleSerial::thrash_threshold`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecodeStats {
+    /// the number of blossoms created in `resolve`
+    pub blossoms_formed: usize,
+    /// the number of blossoms expanded in `resolve`
+    pub blossoms_expanded: usize,
+    /// blossoms that are formed and expanded more than the threshold, typically caused by pathological weights
+    pub thrash_events: Vec<ThrashEvent>,
+    /// the maximum number of simultaneously active (growing or shrinking) dual nodes, which indicates the working set
+    /// of a decoding; always recorded, see [`crate::dual_module::DualModuleImpl::peak_active_nodes`]
+    pub peak_active_nodes: usize,
+    /// the CPU time of each unit of the parallel solver, only recorded when `thread_accounting` is enabled
+    pub unit_cpu_time: Vec<f64>,
+    /// the CPU time each thread spent on the units of the parallel solver, only recorded when `thread_accounting` is enabled
+    pub thread_busy_time: Vec<f64>,
+}
+
+/// a blossom that is repeatedly formed and expanded, identified by the indices of its children nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThrashEvent {
+    /// the sorted node indices of the children of the blossom
+    pub nodes: Vec<NodeIndex>,
+    /// how many times the blossom has been expanded
+    pub cycles: usize,
+}
+
+impl DecodeStats {
+    /// merge the statistics of another module, e.g. from another unit of the parallel solver
+    pub fn merge(&mut self, other: &Self) {
+        self.blossoms_formed += other.blossoms_formed;
+        self.blossoms_expanded += other.blossoms_expanded;
+        self.thrash_events.extend(other.thrash_events.iter().cloned());
+        self.peak_active_nodes += other.peak_active_nodes;
+        self.unit_cpu_time.extend(other.unit_cpu_time.iter().cloned());
+        if self.thread_busy_time.len() < other.thread_busy_time.len() {
+            self.thread_busy_time.resize(other.thread_busy_time.len(), 0.);
+        }
+        for (busy_time, other_busy_time) in self.thread_busy_time.iter_mut().zip(other.thread_busy_time.iter()) {
+            *busy_time += other_busy_time;
+        }
+    }
+
+    /// the maximum over the mean busy time of the threads, see [`balance_factor`]
+    pub fn balance_factor(&self) -> Option<f64> {
+        balance_factor(&self.thread_busy_time)
+    }
+}
+
+/// the ratio of the maximum over the mean busy time, which is 1 if the work is perfectly balanced among the threads and
+/// equals the number of threads if a single thread does all the work; `None` if there is no busy time recorded
+pub fn balance_factor(busy_time: &[f64]) -> Option<f64> {
+    let total: f64 = busy_time.iter().sum();
+    if busy_time.is_empty() || total <= 0. {
+        return None;
+    }
+    let max = busy_time.iter().cloned().fold(0., f64::max);
+    Some(max * busy_time.len() as f64 / total)
 }
 
 /// common trait that must be implemented for each implementation of primal module
@@ -175,6 +381,7 @@ pub trait PrimalModuleImpl {
     ) where
         F: FnMut(&DualModuleInterfacePtr, &mut D, &mut Self, &GroupMaxUpdateLength),
     {
+        log_span!(INFO, "solve", defects = syndrome_pattern.defect_vertices.len());
         interface.load(syndrome_pattern, dual_module);
         self.load(interface);
         self.solve_step_callback_interface_loaded(interface, dual_module, callback);
@@ -194,6 +401,7 @@ pub trait PrimalModuleImpl {
             if let Some(length) = group_max_update_length.get_none_zero_growth() {
                 interface.grow(length, dual_module);
             } else {
+                log_span!(DEBUG, "resolve");
                 self.resolve(group_max_update_length, interface, dual_module);
             }
             group_max_update_length = dual_module.compute_maximum_update_length();
@@ -212,7 +420,7 @@ impl Default for IntermediateMatching {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl IntermediateMatching {
     #[cfg_attr(feature = "python_binding", new)]
@@ -228,7 +436,8 @@ impl IntermediateMatching {
         self.virtual_matchings.append(&mut other.virtual_matchings);
     }
 
-    /// expand the intermediate matching into a perfect matching with only syndrome nodes
+    /// expand the intermediate matching into a perfect matching with only syndrome nodes; this is read-only on both the
+    /// primal and the dual module: the pairs inside a blossom are emitted from its stored odd cycle, see [`Self::expand_blossom`]
     pub fn get_perfect_matching(&self) -> PerfectMatching {
         let mut perfect_matching = PerfectMatching::new();
         // handle peer matchings
@@ -300,6 +509,8 @@ impl IntermediateMatching {
     }
 
     /// expand blossom iteratively into matched pairs, note that this will NOT change the structure of the primal module;
+    /// only the blossoms on the way from `touching_ptr` up to `blossom_ptr` are walked, and every other child is either
+    /// a defect vertex or a blossom expanded in the same way from its own touching child
     pub fn expand_blossom(blossom_ptr: &DualNodePtr, touching_ptr: &DualNodePtr) -> Vec<(DualNodePtr, DualNodePtr)> {
         // println!("expand_blossom ({:?}, {:?}) {{", blossom_ptr, touching_ptr);
         let mut perfect_matching = vec![];
@@ -355,7 +566,7 @@ impl Default for PerfectMatching {
     }
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PerfectMatching {
     #[cfg_attr(feature = "python_binding", new)]
@@ -363,7 +574,86 @@ impl PerfectMatching {
         Self {
             peer_matchings: vec![],
             virtual_matchings: vec![],
+            realized_paths: None,
+            boundary_alternatives: None,
+        }
+    }
+
+    /// the number of defects matched to a virtual vertex, i.e. to the boundary
+    pub fn boundary_match_count(&self) -> usize {
+        self.virtual_matchings.len()
+    }
+
+    /// the number of matched pairs between two defects; each pair is counted once and covers two defects
+    pub fn peer_match_count(&self) -> usize {
+        self.peer_matchings.len()
+    }
+
+    /// the tight-edge path of each matched pair (defect_vertex, peer_or_virtual_vertex, edges);
+    /// empty if the dual module didn't provide them
+    pub fn realized_paths(&self) -> Vec<(DefectIndex, VertexIndex, Vec<EdgeIndex>)> {
+        self.realized_paths.clone().unwrap_or_default()
+    }
+
+    /// split the weight of each matched pair equally between its two defects, or fully to the defect if it's matched
+    /// to a virtual vertex; the weights are taken from the realized paths when available and otherwise from the
+    /// minimum-weight paths in the decoding graph, both using the static weights of the initializer.
+    /// the result is sorted by defect vertex
+    #[allow(clippy::unnecessary_cast)]
+    pub fn weight_attribution(&self, initializer: &SolverInitializer) -> Vec<(DefectIndex, Weight)> {
+        let mut attribution = Vec::with_capacity(2 * self.peer_matchings.len() + self.virtual_matchings.len());
+        let mut attribute = |vertex_1: DefectIndex, vertex_2: VertexIndex, weight: Weight, is_peer: bool| {
+            if is_peer {
+                let half = weight / 2;
+                attribution.push((vertex_1, weight - half));
+                attribution.push((vertex_2, half));
+            } else {
+                attribution.push((vertex_1, weight));
+            }
+        };
+        if let Some(realized_paths) = &self.realized_paths {
+            for (index, (vertex_1, vertex_2, path)) in realized_paths.iter().enumerate() {
+                let weight = path
+                    .iter()
+                    .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                    .sum();
+                attribute(*vertex_1, *vertex_2, weight, index < self.peer_matchings.len());
+            }
+        } else {
+            let mut complete_graph = CompleteGraph::from_initializer(initializer);
+            for (ptr_1, ptr_2) in self.peer_matchings.iter() {
+                let (vertex_1, vertex_2) = (Self::defect_index_of(ptr_1), Self::defect_index_of(ptr_2));
+                let (_, weight) = complete_graph.get_path(vertex_1, vertex_2);
+                attribute(vertex_1, vertex_2, weight, true);
+            }
+            for (ptr, virtual_vertex) in self.virtual_matchings.iter() {
+                let vertex = Self::defect_index_of(ptr);
+                let (_, weight) = complete_graph.get_path(vertex, *virtual_vertex);
+                attribute(vertex, *virtual_vertex, weight, false);
+            }
         }
+        attribution.sort();
+        attribution
+    }
+
+    /// all matched pairs (defect_vertex, peer_or_virtual_vertex) sorted, where a peer pair always puts the smaller
+    /// vertex first so that two matchings of the same syndrome can be compared
+    pub fn matched_pairs(&self) -> Vec<(DefectIndex, VertexIndex)> {
+        let mut matched_pairs: Vec<_> = self
+            .peer_matchings
+            .iter()
+            .map(|(ptr_1, ptr_2)| {
+                let (vertex_1, vertex_2) = (Self::defect_index_of(ptr_1), Self::defect_index_of(ptr_2));
+                (core::cmp::min(vertex_1, vertex_2), core::cmp::max(vertex_1, vertex_2))
+            })
+            .chain(
+                self.virtual_matchings
+                    .iter()
+                    .map(|(ptr, virtual_vertex)| (Self::defect_index_of(ptr), *virtual_vertex)),
+            )
+            .collect();
+        matched_pairs.sort();
+        matched_pairs
     }
 
     /// this interface is not very optimized, but is compatible with blossom V algorithm's result
@@ -433,6 +723,223 @@ impl PerfectMatching {
     pub fn get_virtual_matchings(&self) -> Vec<(NodeIndex, VertexIndex)> {
         self.virtual_matchings.iter().map(|(a, b)| (a.updated_index(), *b)).collect()
     }
+
+    /// (defect_vertex, (matched_virtual_vertex, weight), [(virtual_vertex, weight, weight_difference)])
+    #[cfg(feature = "python_binding")]
+    #[getter]
+    #[allow(clippy::type_complexity)]
+    pub fn get_boundary_alternatives(
+        &self,
+    ) -> Option<Vec<(VertexIndex, (VertexIndex, Weight), Vec<(VertexIndex, Weight, Weight)>)>> {
+        self.boundary_alternatives.as_ref().map(|boundary_alternatives| {
+            boundary_alternatives
+                .iter()
+                .map(|x| (x.defect_vertex, x.matched, x.alternatives.clone()))
+                .collect()
+        })
+    }
+}
+
+/// the reason why a [`PerfectMatching`] is not a perfect matching of a syndrome, see [`PerfectMatching::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchingError {
+    /// a matched dual node is not a defect vertex, e.g. a blossom that is not expanded
+    NotDefectNode,
+    /// a matched vertex is not a defect of the syndrome
+    UnknownDefect(VertexIndex),
+    /// a defect is matched more than once
+    DuplicateDefect(VertexIndex),
+    /// a defect is not matched
+    UnmatchedDefect(VertexIndex),
+    /// a defect is matched to the boundary through a vertex that is not virtual
+    NotVirtual {
+        defect_vertex: VertexIndex,
+        vertex: VertexIndex,
+    },
+}
+
+impl core::fmt::Display for MatchingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotDefectNode => write!(f, "a matched dual node is not a defect vertex"),
+            Self::UnknownDefect(vertex) => write!(f, "vertex {vertex} is matched but is not a defect"),
+            Self::DuplicateDefect(vertex) => write!(f, "defect {vertex} is matched more than once"),
+            Self::UnmatchedDefect(vertex) => write!(f, "defect {vertex} is not matched"),
+            Self::NotVirtual { defect_vertex, vertex } => {
+                write!(f, "defect {defect_vertex} is matched to vertex {vertex} which is not virtual")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MatchingError {}
+
+impl PerfectMatching {
+    /// check that this is a perfect matching of the syndrome: every defect is matched exactly once, either to another
+    /// defect or to a virtual vertex, and nothing else is matched. This is the primal-side analog of
+    /// [`crate::dual_module_serial::DualModuleSerial::sanity_check`] and only takes time linear in the number of defects.
+    /// Note that the defects removed by [`OddParityPolicy`] are not in the matching, so validate against the repaired syndrome
+    pub fn validate(
+        &self,
+        syndrome_pattern: &SyndromePattern,
+        initializer: &SolverInitializer,
+    ) -> Result<(), MatchingError> {
+        let defect_index_of = |dual_node_ptr: &DualNodePtr| -> Result<VertexIndex, MatchingError> {
+            match &dual_node_ptr.read_recursive().class {
+                DualNodeClass::DefectVertex { defect_index } => Ok(*defect_index),
+                _ => Err(MatchingError::NotDefectNode),
+            }
+        };
+        // whether each defect has been matched
+        let mut is_matched: HashMap<VertexIndex, bool> = syndrome_pattern
+            .defect_vertices
+            .iter()
+            .map(|&defect_vertex| (defect_vertex, false))
+            .collect();
+        let mut mark_matched = |defect_vertex: VertexIndex| -> Result<(), MatchingError> {
+            match is_matched.get_mut(&defect_vertex) {
+                None => Err(MatchingError::UnknownDefect(defect_vertex)),
+                Some(true) => Err(MatchingError::DuplicateDefect(defect_vertex)),
+                Some(matched) => {
+                    *matched = true;
+                    Ok(())
+                }
+            }
+        };
+        for (dual_node_ptr_1, dual_node_ptr_2) in self.peer_matchings.iter() {
+            mark_matched(defect_index_of(dual_node_ptr_1)?)?;
+            mark_matched(defect_index_of(dual_node_ptr_2)?)?;
+        }
+        let virtual_vertices: BTreeSet<VertexIndex> = self
+            .virtual_matchings
+            .iter()
+            .map(|(_, virtual_vertex)| *virtual_vertex)
+            .collect();
+        let is_virtual: BTreeSet<VertexIndex> = initializer
+            .virtual_vertices
+            .iter()
+            .filter(|vertex| virtual_vertices.contains(vertex))
+            .copied()
+            .collect();
+        for (dual_node_ptr, virtual_vertex) in self.virtual_matchings.iter() {
+            let defect_vertex = defect_index_of(dual_node_ptr)?;
+            mark_matched(defect_vertex)?;
+            if !is_virtual.contains(virtual_vertex) {
+                return Err(MatchingError::NotVirtual {
+                    defect_vertex,
+                    vertex: *virtual_vertex,
+                });
+            }
+        }
+        if let Some(&defect_vertex) = syndrome_pattern
+            .defect_vertices
+            .iter()
+            .find(|&defect_vertex| !is_matched[defect_vertex])
+        {
+            return Err(MatchingError::UnmatchedDefect(defect_vertex));
+        }
+        Ok(())
+    }
+
+    /// the matched pairs that differ from another matching of the same syndrome, see [`MatchingDiff`]
+    pub fn diff(&self, other: &PerfectMatching) -> MatchingDiff {
+        MatchingDiff::new(&self.matched_pairs(), &other.matched_pairs())
+    }
+
+    /// group the matching by the clusters that resolved together, sorted by their smallest defect vertex: a cluster is
+    /// an outermost dual node (a defect or a blossom of defects) together with the outermost node it's matched to, if
+    /// it's not matched to the boundary. This reads the blossom structure from the dual nodes, so it must be called
+    /// before the solver is cleared
+    pub fn by_cluster(&self) -> Vec<ClusterResult> {
+        let outermost_index_of = |dual_node_ptr: &DualNodePtr| -> NodeIndex {
+            let mut dual_node_ptr = dual_node_ptr.clone();
+            loop {
+                let parent_blossom = dual_node_ptr.read_recursive().parent_blossom.clone();
+                match parent_blossom {
+                    Some(parent_blossom_weak) => dual_node_ptr = parent_blossom_weak.upgrade_force(),
+                    None => break dual_node_ptr.updated_index(),
+                }
+            }
+        };
+        // union the outermost nodes of each matched pair, which are the same node if the pair is inside a blossom
+        let mut parents = BTreeMap::<NodeIndex, NodeIndex>::new();
+        let find = |parents: &BTreeMap<NodeIndex, NodeIndex>, index: NodeIndex| -> NodeIndex {
+            let mut root = index;
+            while let Some(&parent) = parents.get(&root).filter(|&&parent| parent != root) {
+                root = parent;
+            }
+            root
+        };
+        let peer_roots: Vec<_> = self
+            .peer_matchings
+            .iter()
+            .map(|(ptr_1, ptr_2)| {
+                let root_1 = find(&parents, outermost_index_of(ptr_1));
+                let root_2 = find(&parents, outermost_index_of(ptr_2));
+                let root = core::cmp::min(root_1, root_2);
+                parents.insert(root_1, root);
+                parents.insert(root_2, root);
+                (root_1, root_2)
+            })
+            .collect();
+        let mut clusters = BTreeMap::<NodeIndex, ClusterResult>::new();
+        for ((ptr_1, ptr_2), (root_1, _)) in self.peer_matchings.iter().zip(peer_roots) {
+            let cluster = clusters.entry(find(&parents, root_1)).or_default();
+            let (vertex_1, vertex_2) = (Self::defect_index_of(ptr_1), Self::defect_index_of(ptr_2));
+            cluster.defect_vertices.extend([vertex_1, vertex_2]);
+            cluster
+                .peer_matchings
+                .push((core::cmp::min(vertex_1, vertex_2), core::cmp::max(vertex_1, vertex_2)));
+        }
+        for (ptr, virtual_vertex) in self.virtual_matchings.iter() {
+            let cluster = clusters.entry(find(&parents, outermost_index_of(ptr))).or_default();
+            let defect_vertex = Self::defect_index_of(ptr);
+            cluster.defect_vertices.push(defect_vertex);
+            cluster.virtual_matchings.push((defect_vertex, *virtual_vertex));
+        }
+        let mut clusters: Vec<ClusterResult> = clusters.into_values().collect();
+        for cluster in clusters.iter_mut() {
+            cluster.defect_vertices.sort();
+            cluster.peer_matchings.sort();
+            cluster.virtual_matchings.sort();
+        }
+        clusters.sort_by_key(|cluster| cluster.defect_vertices[0]);
+        clusters
+    }
+
+    fn defect_index_of(dual_node_ptr: &DualNodePtr) -> DefectIndex {
+        let node = dual_node_ptr.read_recursive();
+        if let DualNodeClass::DefectVertex { defect_index } = &node.class {
+            *defect_index
+        } else {
+            unreachable!("can only be syndrome")
+        }
+    }
+
+    /// ask the dual module for the tight-edge path of every matched pair, see [`DualModuleImpl::get_tight_path`]; the
+    /// paths are only recorded if all of them are available, otherwise `realized_paths` is left as `None`
+    pub fn record_realized_paths<D: DualModuleImpl>(&mut self, dual_module: &D) {
+        let mut realized_paths = Vec::with_capacity(self.peer_matchings.len() + self.virtual_matchings.len());
+        let pairs = self
+            .peer_matchings
+            .iter()
+            .map(|(ptr_1, ptr_2)| (Self::defect_index_of(ptr_1), Self::defect_index_of(ptr_2)))
+            .chain(
+                self.virtual_matchings
+                    .iter()
+                    .map(|(ptr, virtual_vertex)| (Self::defect_index_of(ptr), *virtual_vertex)),
+            );
+        for (vertex_1, vertex_2) in pairs {
+            match dual_module.get_tight_path(vertex_1, vertex_2) {
+                Some(path) => realized_paths.push((vertex_1, vertex_2, path)),
+                None => {
+                    self.realized_paths = None;
+                    return;
+                }
+            }
+        }
+        self.realized_paths = Some(realized_paths);
+    }
 }
 
 impl FusionVisualizer for PerfectMatching {
@@ -443,11 +950,11 @@ impl FusionVisualizer for PerfectMatching {
         } else {
             let mut maximum_node_index = 0;
             for (ptr_1, ptr_2) in self.peer_matchings.iter() {
-                maximum_node_index = std::cmp::max(maximum_node_index, ptr_1.get_ancestor_blossom().read_recursive().index);
-                maximum_node_index = std::cmp::max(maximum_node_index, ptr_2.get_ancestor_blossom().read_recursive().index);
+                maximum_node_index = core::cmp::max(maximum_node_index, ptr_1.get_ancestor_blossom().read_recursive().index);
+                maximum_node_index = core::cmp::max(maximum_node_index, ptr_2.get_ancestor_blossom().read_recursive().index);
             }
             for (ptr, _virtual_vertex) in self.virtual_matchings.iter() {
-                maximum_node_index = std::cmp::max(maximum_node_index, ptr.get_ancestor_blossom().read_recursive().index);
+                maximum_node_index = core::cmp::max(maximum_node_index, ptr.get_ancestor_blossom().read_recursive().index);
             }
             let mut primal_nodes = vec![json!(null); maximum_node_index as usize + 1];
             for (ptr_1, ptr_2) in self.peer_matchings.iter() {
@@ -484,6 +991,43 @@ impl FusionVisualizer for PerfectMatching {
     }
 }
 
+/// the decoding of a syndrome explained by its erasures alone, see [`SubGraphBuilder::peel_erasures`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErasurePeeling {
+    /// the correction, consisting of erased edges only
+    pub subgraph: Vec<EdgeIndex>,
+    /// defects paired within the same component of erased edges
+    pub peer_matchings: Vec<(VertexIndex, VertexIndex)>,
+    /// defects matched to a virtual vertex in their component of erased edges, as `(defect, virtual_vertex)`
+    pub virtual_matchings: Vec<(VertexIndex, VertexIndex)>,
+}
+
+/// the induced subgraph within some radius of a few seed vertices, see [`SubGraphBuilder::local_region`]
+#[derive(Debug, Clone)]
+pub struct LocalRegion {
+    /// the decoding graph of the region, in which the vertices just outside the region are virtual
+    pub initializer: SolverInitializer,
+    /// the original index of each vertex: first the vertices in the region, and then those just outside it
+    pub vertices: Vec<VertexIndex>,
+    /// the number of vertices in the region, excluding those just outside it
+    pub region_vertex_num: usize,
+    /// the original index of each edge
+    pub edges: Vec<EdgeIndex>,
+    /// the local index of each original vertex
+    local_indices: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl LocalRegion {
+    /// the local index of an original vertex in the region, `None` if it's outside the region
+    #[allow(clippy::unnecessary_cast)]
+    pub fn local_vertex(&self, vertex_index: VertexIndex) -> Option<VertexIndex> {
+        self.local_indices
+            .get(&vertex_index)
+            .cloned()
+            .filter(|&local_index| (local_index as usize) < self.region_vertex_num)
+    }
+}
+
 /// build a subgraph based on minimum-weight paths between matched pairs
 #[derive(Debug, Clone)]
 pub struct SubGraphBuilder {
@@ -495,6 +1039,20 @@ pub struct SubGraphBuilder {
     pub complete_graph: CompleteGraph,
     /// current subgraph, assuming edges are not very much
     pub subgraph: BTreeSet<EdgeIndex>,
+    /// if non-zero, solvers attach at most this number of alternative boundaries to each boundary-matched defect
+    pub boundary_alternatives_k: usize,
+    /// the edges of each erasure region, see [`SubGraphBuilder::expand_erasure_regions`]
+    pub erasure_region_index: ErasureRegionIndex,
+    /// the connected components of the decoding graph, to check the defect parity of each component
+    pub connected_components: ConnectedComponents,
+    /// what solvers do with a component that has no virtual vertex but an odd number of defects
+    pub odd_parity_policy: OddParityPolicy,
+    /// the defects removed by [`SubGraphBuilder::repair_odd_parity`] since the last clear
+    pub odd_parity_repairs: Vec<OddParityRepair>,
+    /// decode syndromes with too many defects approximately, see [`DefectCountFallback`]
+    pub defect_count_fallback: Option<DefectCountFallback>,
+    /// whether [`Self::defect_count_fallback`] applied since the last clear
+    pub defect_count_fallback_applied: bool,
 }
 
 impl SubGraphBuilder {
@@ -507,14 +1065,137 @@ impl SubGraphBuilder {
         Self {
             vertex_num: initializer.vertex_num,
             vertex_pair_edges,
-            complete_graph: CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges),
+            complete_graph: CompleteGraph::from_initializer(initializer),
             subgraph: BTreeSet::new(),
+            boundary_alternatives_k: 0,
+            erasure_region_index: ErasureRegionIndex::new(initializer),
+            connected_components: ConnectedComponents::new(initializer),
+            odd_parity_policy: OddParityPolicy::default(),
+            odd_parity_repairs: vec![],
+            defect_count_fallback: None,
+            defect_count_fallback_applied: false,
         }
     }
 
+    /// the syndrome pattern with erasure regions and uncertain vertices expanded into erasures, or `None` if there is none to expand;
+    /// solvers call this before loading the syndrome so that the rest of the pipeline only sees edge indices
+    pub fn expand_erasure_regions(&self, syndrome_pattern: &SyndromePattern) -> Option<SyndromePattern> {
+        self.erasure_region_index.expand(syndrome_pattern)
+    }
+
+    /// check the syndrome pattern against the decoding graph before solving, see [`crate::mwpm_solver::PrimalDualSolver::try_solve`];
+    /// odd components are only reported when [`Self::odd_parity_policy`] doesn't repair them
+    pub fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), SolveError> {
+        syndrome_pattern
+            .check_indices(self.vertex_num, self.edge_num())
+            .map_err(SolveError::InvalidSyndrome)?;
+        if self.odd_parity_policy == OddParityPolicy::Error {
+            let odd_components = self.connected_components.odd_components(&syndrome_pattern.defect_vertices);
+            if let Some((component, defects)) = odd_components.into_iter().next() {
+                return Err(SolveError::OddParity {
+                    component,
+                    defect_count: defects.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// the syndrome pattern with one defect removed from each component that has no virtual vertex but an odd number
+    /// of defects, chosen by [`Self::odd_parity_policy`], or `None` if no repair is needed; the removed defects are
+    /// recorded in [`Self::odd_parity_repairs`]. Panics with the [`OddParityPolicy::Error`] policy
+    pub fn repair_odd_parity(&mut self, syndrome_pattern: &SyndromePattern) -> Option<SyndromePattern> {
+        let odd_components = self.connected_components.odd_components(&syndrome_pattern.defect_vertices);
+        if odd_components.is_empty() {
+            return None;
+        }
+        let mut removed_defects = BTreeSet::new();
+        for (component, defects) in odd_components {
+            let distances = self.defect_distances(&defects);
+            let removed_index = match self.odd_parity_policy {
+                OddParityPolicy::Error => panic!(
+                    "{}",
+                    SolveError::OddParity {
+                        component,
+                        defect_count: defects.len()
+                    }
+                ),
+                OddParityPolicy::DropFarthest => most_isolated_defect(&distances),
+                OddParityPolicy::MatchToNearestVirtualEvenIfFar => greedy_leftover_defect(&distances),
+            };
+            let matched_virtual = match self.odd_parity_policy {
+                OddParityPolicy::MatchToNearestVirtualEvenIfFar => {
+                    self.connected_components.fallback_virtual_by_index(defects[removed_index])
+                }
+                _ => None,
+            };
+            removed_defects.insert(defects[removed_index]);
+            self.odd_parity_repairs.push(OddParityRepair {
+                component,
+                defect_count: defects.len(),
+                removed_defect: defects[removed_index],
+                policy: self.odd_parity_policy,
+                matched_virtual,
+            });
+        }
+        let mut repaired = syndrome_pattern.clone();
+        repaired
+            .defect_vertices
+            .retain(|vertex_index| !removed_defects.contains(vertex_index));
+        Some(repaired)
+    }
+
+    /// the pairwise distances between defects of the same component
+    fn defect_distances(&mut self, defects: &[VertexIndex]) -> Vec<Vec<Weight>> {
+        defects
+            .iter()
+            .map(|&defect_1| {
+                let edges = self.complete_graph.all_edges(defect_1);
+                defects
+                    .iter()
+                    .map(|defect_2| match edges.get(defect_2) {
+                        Some((_, weight)) => *weight,
+                        None => 0, // itself
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     pub fn clear(&mut self) {
         self.subgraph.clear();
         self.complete_graph.reset();
+        self.odd_parity_repairs.clear();
+        self.defect_count_fallback_applied = false;
+    }
+
+    /// the `max_tree_size` to decode this syndrome with if [`Self::defect_count_fallback`] applies, which is then
+    /// recorded in [`Self::defect_count_fallback_applied`]; solvers restore their own `max_tree_size` afterwards
+    pub fn check_defect_count_fallback(&mut self, syndrome_pattern: &SyndromePattern) -> Option<usize> {
+        let max_tree_size = self
+            .defect_count_fallback
+            .and_then(|fallback| fallback.max_tree_size(syndrome_pattern));
+        if max_tree_size.is_some() {
+            self.defect_count_fallback_applied = true;
+        }
+        max_tree_size
+    }
+
+    /// check that the builder is clean after [`SubGraphBuilder::clear`], e.g. without leftover erasures
+    pub fn check_clean(&self) -> Result<(), String> {
+        if !self.subgraph.is_empty() {
+            return Err(format!("subgraph builder still has {} edges", self.subgraph.len()));
+        }
+        if self.complete_graph.edge_modifier.has_modified_edges() {
+            return Err("leftover erasures or dynamic weights in the subgraph builder".to_string());
+        }
+        if !self.odd_parity_repairs.is_empty() {
+            return Err(format!(
+                "subgraph builder still has {} odd parity repairs",
+                self.odd_parity_repairs.len()
+            ));
+        }
+        Ok(())
     }
 
     /// temporarily set some edges to 0 weight, and when it resets, those edges will be reverted back to the original weight
@@ -526,9 +1207,82 @@ impl SubGraphBuilder {
         self.complete_graph.load_dynamic_weights(dynamic_weights);
     }
 
-    /// load perfect matching to the subgraph builder
+    /// the subgraph induced by the vertices within `radius` weight of any seed vertex, using the current edge weights;
+    /// every vertex just outside the region becomes a virtual vertex connected by the original cut edges, so the cost
+    /// is proportional to the size of the region rather than the decoding graph
+    #[allow(clippy::unnecessary_cast)]
+    pub fn local_region(&mut self, seed_vertices: &[VertexIndex], radius: Weight) -> LocalRegion {
+        let mut region = BTreeSet::new();
+        for &seed_vertex in seed_vertices.iter() {
+            region.insert(seed_vertex);
+            region.extend(
+                self.complete_graph
+                    .all_edges_within(seed_vertex, VertexIndex::MAX, radius)
+                    .into_keys(),
+            );
+        }
+        let mut vertices: Vec<VertexIndex> = region.into_iter().collect();
+        let region_vertex_num = vertices.len();
+        let mut local_indices: HashMap<VertexIndex, VertexIndex> = vertices
+            .iter()
+            .enumerate()
+            .map(|(local_index, &vertex_index)| (vertex_index, local_index as VertexIndex))
+            .collect();
+        let mut virtual_vertices: Vec<VertexIndex> = (0..region_vertex_num)
+            .filter(|&local_index| self.complete_graph.is_virtual[vertices[local_index] as usize])
+            .map(|local_index| local_index as VertexIndex)
+            .collect();
+        let mut weighted_edges = vec![];
+        let mut edges = vec![];
+        for local_1 in 0..region_vertex_num {
+            let vertex_1 = vertices[local_1];
+            for (&vertex_2, &weight) in self.complete_graph.vertices[vertex_1 as usize].edges.iter() {
+                let local_2 = match local_indices.get(&vertex_2) {
+                    Some(&local_2) if (local_2 as usize) < region_vertex_num => {
+                        if vertex_2 < vertex_1 {
+                            continue; // each edge inside the region is added once
+                        }
+                        local_2
+                    }
+                    Some(&local_2) => local_2,
+                    None => {
+                        let local_2 = vertices.len() as VertexIndex;
+                        vertices.push(vertex_2);
+                        local_indices.insert(vertex_2, local_2);
+                        virtual_vertices.push(local_2);
+                        local_2
+                    }
+                };
+                weighted_edges.push((local_1 as VertexIndex, local_2, weight));
+                let id = if vertex_1 < vertex_2 {
+                    (vertex_1, vertex_2)
+                } else {
+                    (vertex_2, vertex_1)
+                };
+                edges.push(self.vertex_pair_edges[&id]);
+            }
+        }
+        LocalRegion {
+            initializer: SolverInitializer::new(vertices.len() as VertexNum, weighted_edges, virtual_vertices),
+            vertices,
+            region_vertex_num,
+            edges,
+            local_indices,
+        }
+    }
+
+    /// load perfect matching to the subgraph builder; the realized tight-edge paths are used directly if
+    /// available, otherwise the minimum-weight paths are computed from the complete graph
     pub fn load_perfect_matching(&mut self, perfect_matching: &PerfectMatching) {
         self.subgraph.clear();
+        if let Some(realized_paths) = perfect_matching.realized_paths.as_ref() {
+            for (_, _, path) in realized_paths.iter() {
+                for edge_index in path.iter() {
+                    self.toggle_edge(*edge_index);
+                }
+            }
+            return;
+        }
         for (ptr_1, ptr_2) in perfect_matching.peer_matchings.iter() {
             let a_vid = {
                 let node = ptr_1.read_recursive();
@@ -561,6 +1315,39 @@ impl SubGraphBuilder {
         }
     }
 
+    /// find the `k` lowest-weight alternative virtual vertices of each defect matched to the boundary
+    pub fn boundary_alternatives(&mut self, perfect_matching: &PerfectMatching, k: usize) -> Vec<BoundaryAlternatives> {
+        let mut boundary_alternatives = Vec::with_capacity(perfect_matching.virtual_matchings.len());
+        for (ptr, virtual_vertex) in perfect_matching.virtual_matchings.iter() {
+            let defect_vertex = PerfectMatching::defect_index_of(ptr);
+            let nearby = self.complete_graph.nearby_virtual(defect_vertex, Weight::MAX);
+            let matched_weight = match nearby.iter().find(|(vertex, _)| vertex == virtual_vertex) {
+                Some((_, weight)) => *weight,
+                None => self.complete_graph.get_path(defect_vertex, *virtual_vertex).1,
+            };
+            let alternatives = nearby
+                .into_iter()
+                .filter(|(vertex, _)| vertex != virtual_vertex)
+                .take(k)
+                .map(|(vertex, weight)| (vertex, weight, weight - matched_weight))
+                .collect();
+            boundary_alternatives.push(BoundaryAlternatives {
+                defect_vertex,
+                matched: (*virtual_vertex, matched_weight),
+                alternatives,
+            });
+        }
+        boundary_alternatives
+    }
+
+    /// attach boundary alternatives to the perfect matching if [`Self::boundary_alternatives_k`] is set
+    pub fn attach_boundary_alternatives(&mut self, perfect_matching: &mut PerfectMatching) {
+        if self.boundary_alternatives_k > 0 {
+            perfect_matching.boundary_alternatives =
+                Some(self.boundary_alternatives(perfect_matching, self.boundary_alternatives_k));
+        }
+    }
+
     pub fn load_subgraph(&mut self, subgraph: &[EdgeIndex]) {
         self.subgraph.clear();
         self.subgraph.extend(subgraph);
@@ -574,29 +1361,202 @@ impl SubGraphBuilder {
             let b = *vertex;
             let id = if a < b { (a, b) } else { (b, a) };
             let edge_index = *self.vertex_pair_edges.get(&id).expect("edge should exist");
-            if self.subgraph.contains(&edge_index) {
-                self.subgraph.remove(&edge_index);
-            } else {
-                self.subgraph.insert(edge_index);
-            }
+            self.toggle_edge(edge_index);
             a = b;
         }
     }
 
-    /// get the total weight of the subgraph
-    #[allow(clippy::unnecessary_cast)]
-    pub fn total_weight(&self) -> Weight {
-        let mut weight = 0;
-        for edge_index in self.subgraph.iter() {
-            weight += self.complete_graph.weighted_edges[*edge_index as usize].2;
+    /// XOR a single edge into the subgraph
+    fn toggle_edge(&mut self, edge_index: EdgeIndex) {
+        if !self.subgraph.remove(&edge_index) {
+            self.subgraph.insert(edge_index);
         }
-        weight
+    }
+
+    /// get the total weight of the subgraph, panics if it overflows `Weight`
+    pub fn total_weight(&self) -> Weight {
+        self.checked_total_weight().unwrap()
+    }
+
+    /// the number of edges in the decoding graph
+    pub fn edge_num(&self) -> usize {
+        self.complete_graph.weighted_edges.len()
+    }
+
+    /// get the total weight of the subgraph, or an error if it overflows `Weight`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn checked_total_weight(&self) -> Result<Weight, String> {
+        checked_weight_sum(
+            self.subgraph
+                .iter()
+                .map(|edge_index| self.complete_graph.weighted_edges[*edge_index as usize].2),
+        )
     }
 
     /// get subgraph as a vec
     pub fn get_subgraph(&self) -> Vec<EdgeIndex> {
         self.subgraph.iter().copied().collect()
     }
+
+    /// decode a syndrome whose defects are all explained by its erasures without any blossom: every defect must lie in
+    /// a connected component of the erased edges which has either an even number of defects or a virtual vertex. The
+    /// components are found by union-find, whose merging edges form a spanning forest, and the correction is built by
+    /// peeling the leaves of the forest towards its roots (a virtual vertex if there is one); it takes time linear in
+    /// the number of erasures. Since erased edges have 0 weight, the correction is a minimum-weight one. Returns `None`
+    /// if the syndrome has no erasures, has dynamic weights or is not explained by its erasures
+    #[allow(clippy::unnecessary_cast)]
+    pub fn peel_erasures(&self, syndrome_pattern: &SyndromePattern) -> Option<ErasurePeeling> {
+        if syndrome_pattern.erasures.is_empty() || !syndrome_pattern.dynamic_weights.is_empty() {
+            return None;
+        }
+        fn find(parents: &mut [usize], mut vertex: usize) -> usize {
+            while parents[vertex] != vertex {
+                parents[vertex] = parents[parents[vertex]];
+                vertex = parents[vertex];
+            }
+            vertex
+        }
+        // only the vertices touched by erasures are indexed, so that the cost doesn't depend on the decoding graph
+        let mut local_indices: HashMap<VertexIndex, usize> = HashMap::with_capacity(2 * syndrome_pattern.erasures.len());
+        let mut vertices: Vec<VertexIndex> = vec![];
+        let mut parents: Vec<usize> = vec![];
+        let mut forest_edges: Vec<(usize, usize, EdgeIndex)> = vec![];
+        for &edge_index in syndrome_pattern.erasures.iter() {
+            let (i, j, _) = self.complete_graph.weighted_edges[edge_index as usize];
+            let [local_i, local_j] = [i, j].map(|vertex| {
+                *local_indices.entry(vertex).or_insert_with(|| {
+                    vertices.push(vertex);
+                    parents.push(vertices.len() - 1);
+                    vertices.len() - 1
+                })
+            });
+            let (root_i, root_j) = (find(&mut parents, local_i), find(&mut parents, local_j));
+            if root_i != root_j {
+                parents[root_i.max(root_j)] = root_i.min(root_j);
+                forest_edges.push((local_i, local_j, edge_index));
+            }
+        }
+        let local_num = vertices.len();
+        let mut is_defect = vec![false; local_num];
+        for defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            is_defect[*local_indices.get(defect_vertex)?] = true;
+        }
+        // the root of each component, preferably a virtual vertex so that it can absorb an odd parity
+        let mut tree_roots: Vec<usize> = (0..local_num).collect();
+        let mut odd_parity = vec![false; local_num];
+        for local in 0..local_num {
+            let root = find(&mut parents, local);
+            if self.complete_graph.is_virtual[vertices[local] as usize] && tree_roots[root] == root {
+                tree_roots[root] = local;
+            }
+            odd_parity[root] ^= is_defect[local];
+        }
+        for root in 0..local_num {
+            if parents[root] == root
+                && odd_parity[root]
+                && !self.complete_graph.is_virtual[vertices[tree_roots[root]] as usize]
+            {
+                return None;
+            }
+        }
+        // visit the spanning forest from its roots, and then peel it in the reverse order
+        let mut adjacency: Vec<Vec<(usize, EdgeIndex)>> = vec![vec![]; local_num];
+        for &(local_i, local_j, edge_index) in forest_edges.iter() {
+            adjacency[local_i].push((local_j, edge_index));
+            adjacency[local_j].push((local_i, edge_index));
+        }
+        let mut parent_edges: Vec<Option<(usize, EdgeIndex)>> = vec![None; local_num];
+        let mut visited = vec![false; local_num];
+        let mut order: Vec<usize> = Vec::with_capacity(local_num);
+        for root in 0..local_num {
+            if parents[root] != root {
+                continue;
+            }
+            visited[tree_roots[root]] = true;
+            order.push(tree_roots[root]);
+            let mut head = order.len() - 1;
+            while head < order.len() {
+                let local = order[head];
+                head += 1;
+                for &(peer, edge_index) in adjacency[local].iter() {
+                    if !visited[peer] {
+                        visited[peer] = true;
+                        parent_edges[peer] = Some((local, edge_index));
+                        order.push(peer);
+                    }
+                }
+            }
+        }
+        let mut parity = is_defect;
+        let mut subgraph = vec![];
+        for &local in order.iter().rev() {
+            if !parity[local] {
+                continue;
+            }
+            if let Some((parent, edge_index)) = parent_edges[local] {
+                subgraph.push(edge_index);
+                parity[parent] ^= true;
+            }
+        }
+        subgraph.sort();
+        // pair the defects of each component in order, and the one left over with the virtual root
+        let mut peeling = ErasurePeeling {
+            subgraph,
+            ..Default::default()
+        };
+        let mut pending: Vec<Option<VertexIndex>> = vec![None; local_num];
+        for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            let root = find(&mut parents, local_indices[&defect_vertex]);
+            match pending[root].take() {
+                Some(peer_vertex) => peeling.peer_matchings.push((peer_vertex, defect_vertex)),
+                None => pending[root] = Some(defect_vertex),
+            }
+        }
+        for root in 0..local_num {
+            if let Some(defect_vertex) = pending[root] {
+                peeling.virtual_matchings.push((defect_vertex, vertices[tree_roots[root]]));
+            }
+        }
+        Some(peeling)
+    }
+}
+
+/// the index of the defect whose nearest other defect is the farthest, given the pairwise distances
+fn most_isolated_defect(distances: &[Vec<Weight>]) -> usize {
+    let nearest = |index: usize| -> Weight {
+        (0..distances.len())
+            .filter(|&other| other != index)
+            .map(|other| distances[index][other])
+            .min()
+            .unwrap_or(Weight::MAX)
+    };
+    let mut most_isolated = 0;
+    for index in 1..distances.len() {
+        if nearest(index) > nearest(most_isolated) {
+            most_isolated = index;
+        }
+    }
+    most_isolated
+}
+
+/// the index of the defect left over after greedily pairing the closest defects, given the pairwise distances of
+/// an odd number of defects
+fn greedy_leftover_defect(distances: &[Vec<Weight>]) -> usize {
+    let mut pairs: Vec<(Weight, usize, usize)> = vec![];
+    for (index_1, row) in distances.iter().enumerate() {
+        for (index_2, &distance) in row.iter().enumerate().skip(index_1 + 1) {
+            pairs.push((distance, index_1, index_2));
+        }
+    }
+    pairs.sort_unstable();
+    let mut is_paired = vec![false; distances.len()];
+    for (_, index_1, index_2) in pairs {
+        if !is_paired[index_1] && !is_paired[index_2] {
+            is_paired[index_1] = true;
+            is_paired[index_2] = true;
+        }
+    }
+    is_paired.iter().position(|is_paired| !is_paired).unwrap()
 }
 
 /// to visualize subgraph
@@ -618,6 +1578,43 @@ impl FusionVisualizer for VisualizeSubgraph<'_> {
     }
 }
 
+/// to visualize the differing pairs of two matchings; the edges used by both sides are not highlighted
+pub struct VisualizeMatchingDiff<'a> {
+    pub matching_diff: &'a MatchingDiff,
+    pub edges_self: Vec<EdgeIndex>,
+    pub edges_other: Vec<EdgeIndex>,
+}
+
+impl<'a> VisualizeMatchingDiff<'a> {
+    pub fn new(matching_diff: &'a MatchingDiff, subgraph_builder: &mut SubGraphBuilder) -> Self {
+        let ((subgraph_self, _), (subgraph_other, _)) = matching_diff.subgraphs(subgraph_builder);
+        let set_self: BTreeSet<_> = subgraph_self.iter().copied().collect();
+        let set_other: BTreeSet<_> = subgraph_other.iter().copied().collect();
+        Self {
+            matching_diff,
+            edges_self: set_self.difference(&set_other).copied().collect(),
+            edges_other: set_other.difference(&set_self).copied().collect(),
+        }
+    }
+}
+
+impl FusionVisualizer for VisualizeMatchingDiff<'_> {
+    fn snapshot(&self, _abbrev: bool) -> serde_json::Value {
+        json!({
+            "matching_diff": {
+                "only_in_self": {
+                    "pairs": self.matching_diff.only_in_self,
+                    "edges": self.edges_self,
+                },
+                "only_in_other": {
+                    "pairs": self.matching_diff.only_in_other,
+                    "edges": self.edges_other,
+                },
+            },
+        })
+    }
+}
+
 #[cfg(feature = "python_binding")]
 #[pyfunction]
 pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
@@ -47,6 +47,12 @@ pub struct PrimalModuleParallelUnit {
     pub parent: Option<PrimalModuleParallelUnitWeak>,
     /// record the time of events
     pub event_time: Option<PrimalModuleParallelUnitEventTime>,
+    /// the number of primal-dual rounds (either growing or resolving conflicts) of the last decoding in this unit;
+    /// for a fusion unit, this quantifies the extra work of fusing its children
+    pub rounds: usize,
+    /// the number of matches to mirrored vertices broken when this unit fused its children in the last decoding; the
+    /// broken nodes keep their dual variables and only start growing again
+    pub broken_matches: usize,
     /// streaming decode mocker, if exists, base partition will wait until specified time and then start decoding
     pub streaming_decode_mocker: Option<StreamingDecodeMocker>,
 }
@@ -54,6 +60,12 @@ pub struct PrimalModuleParallelUnit {
 pub type PrimalModuleParallelUnitPtr = ArcManualSafeLock<PrimalModuleParallelUnit>;
 pub type PrimalModuleParallelUnitWeak = WeakManualSafeLock<PrimalModuleParallelUnit>;
 
+impl PointerTag for PrimalModuleParallelUnit {
+    fn pointer_tag(&self) -> String {
+        format!("PrimalModuleParallelUnit {}", self.unit_index)
+    }
+}
+
 impl std::fmt::Debug for PrimalModuleParallelUnitPtr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let unit = self.read_recursive();
@@ -76,6 +88,8 @@ pub struct PrimalModuleParallelUnitEventTime {
     pub end: f64,
     /// thread index
     pub thread_index: usize,
+    /// CPU time spent by the thread on this unit, only recorded when `thread_accounting` is enabled
+    pub cpu_time: Option<f64>,
 }
 
 impl Default for PrimalModuleParallelUnitEventTime {
@@ -90,6 +104,47 @@ impl PrimalModuleParallelUnitEventTime {
             start: 0.,
             end: 0.,
             thread_index: rayon::current_thread_index().unwrap_or(0),
+            cpu_time: None,
+        }
+    }
+}
+
+/// measures the CPU time of the current thread, which unlike the wall time excludes the time the thread is descheduled;
+/// falls back to the wall time on platforms other than unix
+pub struct ThreadCpuTimer {
+    #[cfg(unix)]
+    start: f64,
+    #[cfg(not(unix))]
+    start: Instant,
+}
+
+impl ThreadCpuTimer {
+    #[cfg(unix)]
+    fn now() -> f64 {
+        let mut time = crate::libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        let result = unsafe { crate::libc::clock_gettime(crate::libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+        assert_eq!(result, 0, "clock_gettime failed");
+        time.tv_sec as f64 + time.tv_nsec as f64 * 1e-9
+    }
+
+    pub fn start() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(unix)] {
+                Self { start: Self::now() }
+            } else {
+                Self { start: Instant::now() }
+            }
+        }
+    }
+
+    /// the CPU time in seconds since [`ThreadCpuTimer::start`]; must be called on the same thread
+    pub fn elapsed(&self) -> f64 {
+        cfg_if::cfg_if! {
+            if #[cfg(unix)] {
+                Self::now() - self.start
+            } else {
+                self.start.elapsed().as_secs_f64()
+            }
         }
     }
 }
@@ -119,6 +174,15 @@ pub struct PrimalModuleParallelConfig {
     /// max tree size for the serial modules, for faster speed at the cost of less accuracy
     #[serde(default = "primal_module_parallel_default_configs::max_tree_size")]
     pub max_tree_size: usize,
+    /// detect blossoms that are repeatedly formed and expanded in the serial modules, see [`DecodeStats`]
+    pub thrash_threshold: Option<usize>,
+    /// the order of resolving conflicts in the serial modules
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// measure the CPU time of each unit and the busy time of each thread, see [`DecodeStats::balance_factor`];
+    /// disabled by default to avoid the extra syscalls
+    #[serde(default)]
+    pub thread_accounting: bool,
 }
 
 impl Default for PrimalModuleParallelConfig {
@@ -189,6 +253,8 @@ impl PrimalModuleParallel {
                     // println!("unit_index: {unit_index}");
                     let primal_module = PrimalModuleSerialPtr::new_empty(initializer);
                     primal_module.write().max_tree_size = config.max_tree_size;
+                    primal_module.write().thrash_threshold = config.thrash_threshold;
+                    primal_module.write().conflict_policy = config.conflict_policy;
                     PrimalModuleParallelUnitPtr::new_wrapper(primal_module, unit_index, Arc::clone(&partition_info))
                 })
                 .collect_into_vec(&mut units);
@@ -277,13 +343,114 @@ impl PrimalModuleImpl for PrimalModuleParallel {
 
     fn generate_profiler_report(&self) -> serde_json::Value {
         let event_time_vec: Vec<_> = self.units.iter().map(|ptr| ptr.read_recursive().event_time.clone()).collect();
-        json!({
+        let mut report = json!({
             "event_time_vec": event_time_vec,
-        })
+            "rounds_vec": self.unit_rounds(),
+            "broken_matches_vec": self.unit_broken_matches(),
+            "fusion_plan": self.partition_info.fusion_plan(),
+        });
+        if self.config.thread_accounting {
+            let thread_busy_time = self.thread_busy_time();
+            let report = report.as_object_mut().unwrap();
+            report.insert("balance_factor".to_string(), json!(balance_factor(&thread_busy_time)));
+            report.insert("thread_busy_time".to_string(), json!(thread_busy_time));
+        }
+        report
     }
 }
 
 impl PrimalModuleParallel {
+    /// split the hints to each unit proportionally to the number of owned vertices
+    pub fn reserve_capacity(&self, hints: &CapacityHints) {
+        for (unit_index, unit_ptr) in self.units.iter().enumerate() {
+            let unit = unit_ptr.read_recursive();
+            let unit_hints = hints.scaled(self.partition_info.owning_ratio(unit_index));
+            unit.serial_module.reserve_capacity(&unit_hints);
+            unit.interface_ptr.reserve_capacity(unit_hints.max_nodes());
+        }
+    }
+
+    /// the number of primal-dual rounds of each unit in the last decoding, see [`PrimalModuleParallelUnit::rounds`]
+    pub fn unit_rounds(&self) -> Vec<usize> {
+        self.units.iter().map(|ptr| ptr.read_recursive().rounds).collect()
+    }
+
+    /// the number of broken matches of each unit in the last decoding, see [`PrimalModuleParallelUnit::broken_matches`]
+    pub fn unit_broken_matches(&self) -> Vec<usize> {
+        self.units.iter().map(|ptr| ptr.read_recursive().broken_matches).collect()
+    }
+
+    /// check that every unit is clean after clear
+    pub fn check_clean(&self) -> Result<(), String> {
+        for (unit_index, unit_ptr) in self.units.iter().enumerate() {
+            let unit = unit_ptr.read_recursive();
+            unit.serial_module
+                .check_clean()
+                .and_then(|_| unit.interface_ptr.check_clean())
+                .map_err(|message| format!("unit {unit_index}: {message}"))?;
+        }
+        Ok(())
+    }
+
+    /// set the `max_tree_size` of the serial modules of all units, e.g. for [`DefectCountFallback`], and return the
+    /// previous value of the first unit so that it can be restored
+    pub fn set_max_tree_size(&self, max_tree_size: usize) -> usize {
+        let mut previous = max_tree_size;
+        for (unit_index, unit_ptr) in self.units.iter().enumerate() {
+            let unit = unit_ptr.read_recursive();
+            let unit_previous = std::mem::replace(&mut unit.serial_module.write().max_tree_size, max_tree_size);
+            if unit_index == 0 {
+                previous = unit_previous;
+            }
+        }
+        previous
+    }
+
+    /// the CPU time of each unit in the last decoding, 0 if not executed or `thread_accounting` is disabled
+    pub fn unit_cpu_time(&self) -> Vec<f64> {
+        self.units
+            .iter()
+            .map(|ptr| {
+                let unit = ptr.read_recursive();
+                unit.event_time
+                    .as_ref()
+                    .and_then(|event_time| event_time.cpu_time)
+                    .unwrap_or(0.)
+            })
+            .collect()
+    }
+
+    /// the total CPU time of each thread in the pool spent on the units in the last decoding
+    pub fn thread_busy_time(&self) -> Vec<f64> {
+        let mut thread_busy_time = vec![0.; self.thread_pool.current_num_threads()];
+        for unit_ptr in self.units.iter() {
+            let unit = unit_ptr.read_recursive();
+            if let Some(event_time) = unit.event_time.as_ref() {
+                if let Some(cpu_time) = event_time.cpu_time {
+                    if event_time.thread_index >= thread_busy_time.len() {
+                        thread_busy_time.resize(event_time.thread_index + 1, 0.);
+                    }
+                    thread_busy_time[event_time.thread_index] += cpu_time;
+                }
+            }
+        }
+        thread_busy_time
+    }
+
+    /// merge the decoding statistics of all units
+    pub fn decode_stats(&self) -> DecodeStats {
+        let mut decode_stats = DecodeStats::default();
+        for unit_ptr in self.units.iter() {
+            let unit = unit_ptr.read_recursive();
+            decode_stats.merge(&unit.serial_module.read_recursive().decode_stats);
+        }
+        if self.config.thread_accounting {
+            decode_stats.unit_cpu_time = self.unit_cpu_time();
+            decode_stats.thread_busy_time = self.thread_busy_time();
+        }
+        decode_stats
+    }
+
     pub fn parallel_solve<DualSerialModule: DualModuleImpl + Send + Sync>(
         &mut self,
         syndrome_pattern: &SyndromePattern,
@@ -339,6 +506,30 @@ impl PrimalModuleParallel {
         }
     }
 
+    /// solve a single unit whose children, if any, are already solved, leaving the other units untouched; e.g. load the
+    /// time partitions of a stream one at a time and fuse each of them into the solved units before it
+    pub fn solve_unit<DualSerialModule: DualModuleImpl + Send + Sync>(
+        &self,
+        unit_index: usize,
+        syndrome_pattern: &SyndromePattern,
+        parallel_dual_module: &DualModuleParallel<DualSerialModule>,
+    ) {
+        *self.last_solve_start_time.write() = Instant::now();
+        self.units[unit_index].children_ready_solve(
+            self,
+            PartitionedSyndromePattern::new(syndrome_pattern),
+            parallel_dual_module,
+            &mut None::<
+                &mut fn(
+                    &DualModuleInterfacePtr,
+                    &DualModuleParallelUnit<DualSerialModule>,
+                    &PrimalModuleSerialPtr,
+                    Option<&GroupMaxUpdateLength>,
+                ),
+            >,
+        );
+    }
+
     pub fn parallel_solve_step_callback<DualSerialModule: DualModuleImpl + Send + Sync, F>(
         &mut self,
         syndrome_pattern: &SyndromePattern,
@@ -353,6 +544,7 @@ impl PrimalModuleParallel {
             ) + Send
             + Sync,
     {
+        log_span!(INFO, "solve", defects = syndrome_pattern.defect_vertices.len());
         let thread_pool = Arc::clone(&self.thread_pool);
         *self.last_solve_start_time.write() = Instant::now();
         if self.config.prioritize_base_partition {
@@ -507,6 +699,8 @@ impl PrimalModuleParallelUnitPtr {
             children: None, // to be filled later
             parent: None,   // to be filled later
             event_time: None,
+            rounds: 0,
+            broken_matches: 0,
             streaming_decode_mocker: None,
         })
     }
@@ -543,6 +737,7 @@ impl PrimalModuleParallelUnitPtr {
                 }
             }
         }
+        let cpu_timer = primal_module_parallel.config.thread_accounting.then(ThreadCpuTimer::start);
         let mut event_time = PrimalModuleParallelUnitEventTime::new();
         event_time.start = primal_module_parallel
             .last_solve_start_time
@@ -569,7 +764,8 @@ impl PrimalModuleParallelUnitPtr {
                 // do callback before actually breaking the matched pairs, for ease of visualization
                 callback(&primal_unit.interface_ptr, &dual_unit, &primal_unit.serial_module, None);
             }
-            primal_unit.break_matching_with_mirror(dual_unit.deref_mut());
+            primal_unit.broken_matches = primal_unit.break_matching_with_mirror(dual_unit.deref_mut());
+            let mut rounds = 0;
             for defect_index in owned_defect_range.whole_defect_range.iter() {
                 let defect_vertex = partitioned_syndrome_pattern.syndrome_pattern.defect_vertices[defect_index as usize];
                 primal_unit
@@ -580,6 +776,7 @@ impl PrimalModuleParallelUnitPtr {
                 &interface_ptr,
                 dual_unit.deref_mut(),
                 |interface, dual_module, primal_module, group_max_update_length| {
+                    rounds += 1;
                     if let Some(callback) = callback.as_mut() {
                         callback(interface, dual_module, primal_module, Some(group_max_update_length));
                     }
@@ -588,14 +785,17 @@ impl PrimalModuleParallelUnitPtr {
             if let Some(callback) = callback.as_mut() {
                 callback(&primal_unit.interface_ptr, &dual_unit, &primal_unit.serial_module, None);
             }
+            primal_unit.rounds = rounds;
         } else {
             debug_assert!(primal_unit.is_active, "leaf must be active to be solved");
             let syndrome_pattern = owned_defect_range.expand();
+            let mut rounds = 0;
             primal_unit.serial_module.solve_step_callback(
                 &interface_ptr,
                 &syndrome_pattern,
                 dual_unit.deref_mut(),
                 |interface, dual_module, primal_module, group_max_update_length| {
+                    rounds += 1;
                     if let Some(callback) = callback.as_mut() {
                         callback(interface, dual_module, primal_module, Some(group_max_update_length));
                     }
@@ -604,6 +804,7 @@ impl PrimalModuleParallelUnitPtr {
             if let Some(callback) = callback.as_mut() {
                 callback(&primal_unit.interface_ptr, &dual_unit, &primal_unit.serial_module, None);
             }
+            primal_unit.rounds = rounds;
         }
         primal_unit.is_active = true;
         event_time.end = primal_module_parallel
@@ -611,6 +812,7 @@ impl PrimalModuleParallelUnitPtr {
             .read_recursive()
             .elapsed()
             .as_secs_f64();
+        event_time.cpu_time = cpu_timer.map(|cpu_timer| cpu_timer.elapsed());
         primal_unit.event_time = Some(event_time);
     }
 
@@ -696,6 +898,7 @@ impl PrimalModuleParallelUnit {
         &mut self,
         dual_unit: &mut DualModuleParallelUnit<DualSerialModule>,
     ) {
+        log_span!(DEBUG, "fuse", unit_index = self.unit_index);
         let (left_child_ptr, right_child_ptr) = (
             self.children.as_ref().unwrap().0.upgrade_force(),
             self.children.as_ref().unwrap().1.upgrade_force(),
@@ -706,11 +909,12 @@ impl PrimalModuleParallelUnit {
         self.serial_module.fuse(&left_child.serial_module, &right_child.serial_module);
     }
 
-    /// break the matched pairs of interface vertices
+    /// break the matched pairs of interface vertices, returning the number of broken matches
     #[allow(clippy::unnecessary_cast)]
-    pub fn break_matching_with_mirror(&mut self, dual_module: &mut impl DualModuleImpl) {
+    pub fn break_matching_with_mirror(&mut self, dual_module: &mut impl DualModuleImpl) -> usize {
         // use `possible_break` to efficiently break those
         let mut possible_break = vec![];
+        let mut broken_matches = 0;
         let module = self.serial_module.read_recursive();
         for node_index in module.possible_break.iter() {
             let primal_node_ptr = module.get_node(*node_index);
@@ -719,6 +923,7 @@ impl PrimalModuleParallelUnit {
                 if let Some((MatchTarget::VirtualVertex(vertex_index), _)) = &primal_node.temporary_match {
                     if self.partition_info.vertex_to_owning_unit[*vertex_index as usize] == self.unit_index {
                         primal_node.temporary_match = None;
+                        broken_matches += 1;
                         self.interface_ptr.set_grow_state(
                             &primal_node.origin.upgrade_force(),
                             DualNodeGrowState::Grow,
@@ -733,6 +938,7 @@ impl PrimalModuleParallelUnit {
         }
         drop(module);
         self.serial_module.write().possible_break = possible_break;
+        broken_matches
     }
 }
 
@@ -744,6 +950,8 @@ impl PrimalModuleImpl for PrimalModuleParallelUnit {
     fn clear(&mut self) {
         self.serial_module.clear();
         self.interface_ptr.clear();
+        self.rounds = 0;
+        self.broken_matches = 0;
     }
 
     fn load(&mut self, interface_ptr: &DualModuleInterfacePtr) {
@@ -1128,7 +1336,7 @@ pub mod tests {
         final_dual: Weight,
     ) {
         let half_weight = 500;
-        let split_horizontal = (d + 1) / 2;
+        let split_horizontal = d.div_ceil(2);
         let row_count = d + 1;
         primal_module_parallel_standard_syndrome(
             CodeCapacityPlanarCode::new(d, 0.1, half_weight),
@@ -1184,4 +1392,67 @@ pub mod tests {
             Some(json!({ "max_tree_size": 0, "debug_sequential": true })),
         );
     }
+
+    #[test]
+    fn primal_module_parallel_thread_accounting_1() {
+        // cargo test primal_module_parallel_thread_accounting_1 -- --nocapture
+        let noisy_measurements = 20;
+        let mut code = PhenomenologicalPlanarCode::new(11, noisy_measurements, 0.05, 500);
+        let initializer = code.get_initializer();
+        // deliberately imbalanced: a single layer against all the rest
+        let layer_vertex_num = initializer.vertex_num / (noisy_measurements + 1);
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = vec![
+            VertexRange::new(0, layer_vertex_num),
+            VertexRange::new(2 * layer_vertex_num, initializer.vertex_num),
+        ];
+        partition_config.fusions = vec![(0, 1)];
+        let partition_info = partition_config.info();
+        let mut dual_module: DualModuleParallel<DualModuleSerial> =
+            DualModuleParallel::new_config(&initializer, &partition_info, DualModuleParallelConfig::default());
+        let primal_config: PrimalModuleParallelConfig = serde_json::from_value(json!({ "thread_pool_size": 2 })).unwrap();
+        let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
+        // disabled by default
+        primal_module.parallel_solve(&code.generate_random_errors(0), &dual_module);
+        let decode_stats = primal_module.decode_stats();
+        assert!(decode_stats.unit_cpu_time.is_empty() && decode_stats.thread_busy_time.is_empty());
+        assert_eq!(decode_stats.balance_factor(), None);
+        assert!(primal_module.generate_profiler_report().get("balance_factor").is_none());
+        primal_module.clear();
+        dual_module.clear();
+        // enabled: the large unit occupies a single thread while the others are mostly idle
+        let primal_config: PrimalModuleParallelConfig =
+            serde_json::from_value(json!({ "thread_pool_size": 4, "thread_accounting": true })).unwrap();
+        let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
+        let mut decode_stats = DecodeStats::default();
+        let mut sum_balance_factor = 0.;
+        for seed in 0..10 {
+            primal_module.parallel_solve(&code.generate_random_errors(seed), &dual_module);
+            let shot_decode_stats = primal_module.decode_stats();
+            sum_balance_factor += shot_decode_stats.balance_factor().unwrap();
+            decode_stats.merge(&shot_decode_stats);
+            primal_module.clear();
+            dual_module.clear();
+        }
+        assert_eq!(decode_stats.thread_busy_time.len(), 4);
+        assert_eq!(decode_stats.unit_cpu_time.len(), 3 * 10);
+        let small_unit_time: f64 = decode_stats.unit_cpu_time.iter().step_by(3).sum();
+        let large_unit_time: f64 = decode_stats.unit_cpu_time.iter().skip(1).step_by(3).sum();
+        let total_busy_time: f64 = decode_stats.thread_busy_time.iter().sum();
+        let total_unit_time: f64 = decode_stats.unit_cpu_time.iter().sum();
+        println!("unit cpu time: {small_unit_time:.3e} vs {large_unit_time:.3e}");
+        println!("thread busy time: {:?}", decode_stats.thread_busy_time);
+        assert!(large_unit_time > 3. * small_unit_time);
+        assert!((total_busy_time - total_unit_time).abs() < 1e-9);
+        let balance_factor = sum_balance_factor / 10.;
+        println!("average balance factor: {balance_factor}");
+        assert!(balance_factor > 2., "imbalanced partition is not detected");
+        primal_module.parallel_solve(&code.generate_random_errors(0), &dual_module);
+        let report = primal_module.generate_profiler_report();
+        assert!(report["balance_factor"].as_f64().unwrap() >= 1.);
+        assert_eq!(
+            report["event_time_vec"][1]["cpu_time"],
+            json!(primal_module.unit_cpu_time()[1])
+        );
+    }
 }
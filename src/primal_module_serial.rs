@@ -4,18 +4,47 @@
 //! where traditional matching is too time-consuming because of their |E| = O(|V|^2) scaling.
 //!
 
-#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references))]
+#![cfg_attr(feature = "unsafe_pointer", allow(dropping_references, clippy::needless_borrow))]
 
-use std::cmp::Ordering;
-use std::num::NonZeroUsize;
+use crate::prelude::*;
+use alloc::collections::BTreeSet;
+use core::cmp::Ordering;
+use core::num::NonZeroUsize;
 
 use crate::derivative::Derivative;
 
+use super::complete_graph::CompleteGraph;
 use super::dual_module::*;
 use super::pointers::*;
 use super::primal_module::*;
 use super::util::*;
 use super::visualize::*;
+use serde::{Deserialize, Serialize};
+
+/// the order in which the conflicts reported together by the dual module are resolved; every policy yields a
+/// minimum-weight perfect matching, but the decoding time and the chosen matching among degenerate ones may differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// the order of [`GroupMaxUpdateLength::pop`], i.e. the last reported conflict first, or the priority order
+    /// with the `ordered_conflicts` feature; this is the only policy that doesn't reorder the conflicts
+    #[default]
+    Native,
+    /// the first reported conflict first; with the `ordered_conflicts` feature it's the reverse priority order
+    Fifo,
+    /// the priority order of [`MaxUpdateLength`] regardless of the `ordered_conflicts` feature: conflicts between
+    /// nodes first, then touching virtual vertices and then blossom expanding
+    Priority,
+    /// conflicts between two nodes of the same alternating tree, i.e. those forming a blossom, first
+    BlossomFirst,
+    /// conflicts touching virtual vertices last
+    BoundaryLast,
+    /// the convention of some reference decoders, to reproduce their choices among degenerate matchings for
+    /// cross-validation: shrinking blossoms hitting 0 dual variable first, then conflicts between nodes, then touching
+    /// virtual vertices in increasing index; a node touching several virtual vertices at the same time is matched to
+    /// the lowest index among them if the dual module supports [`DualModuleImpl::touching_virtual_vertices`]
+    ShrinkFirst,
+}
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -39,22 +68,34 @@ pub struct PrimalModuleSerial {
     /// the two children of this serial module, when fused; following the length of this child,
     /// given that fused children serial modules will not have new nodes anymore
     pub children: Option<((PrimalModuleSerialWeak, NodeNum), (PrimalModuleSerialWeak, NodeNum))>,
-    /// the maximum number of children in a tree before it collapses to a union-find decoder
+    /// the maximum size of an alternating tree before it collapses to a union-find decoder, where the size is the total
+    /// number of defect vertices in the tree (see [`DualNode::defect_size`]), not the number of tree nodes; a blossom
+    /// therefore counts as all the defects inside it. `usize::MAX` is the exact MWPM decoder and 0 is union-find
     pub max_tree_size: usize,
+    /// report a [`ThrashEvent`] when the same blossom is expanded more than this number of times; disabled by default
+    pub thrash_threshold: Option<usize>,
+    /// statistics of the current decoding, only recorded when `thrash_threshold` is set
+    pub decode_stats: DecodeStats,
+    /// the order of resolving the conflicts reported together
+    pub conflict_policy: ConflictPolicy,
+    /// the expand count of each blossom (identified by its sorted children) and its index in `decode_stats.thrash_events`
+    thrash_counter: HashMap<Vec<NodeIndex>, (usize, Option<usize>)>,
 }
 
 pub type PrimalModuleSerialPtr = ArcManualSafeLock<PrimalModuleSerial>;
 pub type PrimalModuleSerialWeak = WeakManualSafeLock<PrimalModuleSerial>;
 
-impl std::fmt::Debug for PrimalModuleSerialPtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for PrimalModuleSerial {}
+
+impl core::fmt::Debug for PrimalModuleSerialPtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let interface = self.read_recursive();
         write!(f, "{}", interface.unit_index)
     }
 }
 
-impl std::fmt::Debug for PrimalModuleSerialWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for PrimalModuleSerialWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
@@ -70,7 +111,8 @@ pub struct AlternatingTreeNode {
     pub children: Vec<(PrimalNodeInternalWeak, DualNodeWeak)>,
     /// the depth in the alternating tree, root has 0 depth
     pub depth: usize,
-    /// the number of children in the tree; only the root has this variable.
+    /// the total number of defect vertices in the tree, i.e. the sum of [`DualNode::defect_size`] of all its nodes;
+    /// only the root has this variable, see [`PrimalModuleSerialPtr::tree_size`]
     pub tree_size: Option<NonZeroUsize>,
 }
 
@@ -100,16 +142,22 @@ pub struct PrimalNodeInternal {
 pub type PrimalNodeInternalPtr = ArcManualSafeLock<PrimalNodeInternal>;
 pub type PrimalNodeInternalWeak = WeakManualSafeLock<PrimalNodeInternal>;
 
-impl std::fmt::Debug for PrimalNodeInternalPtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for PrimalNodeInternal {
+    fn pointer_tag(&self) -> String {
+        format!("PrimalNodeInternal {}", self.index)
+    }
+}
+
+impl core::fmt::Debug for PrimalNodeInternalPtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.update(); // to make sure index is up-to-date
         let primal_node_internal = self.read_recursive();
         write!(f, "{}", primal_node_internal.index)
     }
 }
 
-impl std::fmt::Debug for PrimalNodeInternalWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for PrimalNodeInternalWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
@@ -182,6 +230,10 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
             // max_tree_size: 0,
             // Minimum Weight Perfect Matching
             max_tree_size: usize::MAX,
+            thrash_threshold: None,
+            decode_stats: DecodeStats::default(),
+            conflict_policy: ConflictPolicy::default(),
+            thrash_counter: HashMap::new(),
         })
     }
 
@@ -193,6 +245,10 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
         module.parent = None;
         module.index_bias = 0;
         module.children = None;
+        if module.thrash_threshold.is_some() {
+            module.decode_stats = DecodeStats::default();
+            module.thrash_counter.clear();
+        }
     }
 
     fn load_defect_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
@@ -233,7 +289,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
         module.nodes[local_node_index] = Some(primal_node_internal_ptr);
     }
 
-    #[allow(clippy::collapsible_else_if)]
+    #[allow(clippy::collapsible_else_if, clippy::unnecessary_unwrap)]
     fn resolve<D: DualModuleImpl>(
         &mut self,
         mut group_max_update_length: GroupMaxUpdateLength,
@@ -244,7 +300,13 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
         let mut current_conflict_index = 0;
         let debug_resolve_only_one = self.read_recursive().debug_resolve_only_one;
         let max_tree_size = self.read_recursive().max_tree_size;
-        while let Some(conflict) = group_max_update_length.pop() {
+        let mut ordered_conflicts = self
+            .order_conflicts(&mut group_max_update_length)
+            .map(|conflicts| conflicts.into_iter());
+        while let Some(conflict) = match ordered_conflicts.as_mut() {
+            Some(conflicts) => conflicts.next(),
+            None => group_max_update_length.pop(),
+        } {
             current_conflict_index += 1;
             if debug_resolve_only_one && current_conflict_index > 1 {
                 // debug mode
@@ -567,6 +629,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                                 root_tree_node.tree_size = Some(tree_size);
                                 // update dual module interface
                                 if tree_size.get() > max_tree_size {
+                                    drop(root_node);
                                     drop(matched_node_internal);
                                     drop(leaf_node_internal);
                                     self.collapse_tree(root_node_ptr.clone(), interface_ptr, dual_module);
@@ -703,6 +766,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                                 }
                                 touching_children
                             };
+                            self.record_blossom_formed();
                             let blossom_node_ptr =
                                 interface_ptr.create_blossom(nodes_circle, touching_children, dual_module);
                             let primal_node_internal_blossom_ptr = {
@@ -853,7 +917,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                     }
                     unreachable!()
                 }
-                MaxUpdateLength::TouchingVirtual((node_ptr, touching_ptr), (virtual_vertex_index, is_mirror)) => {
+                MaxUpdateLength::TouchingVirtual((node_ptr, mut touching_ptr), (mut virtual_vertex_index, is_mirror)) => {
                     if self.get_primal_node_internal_ptr_option(&node_ptr).is_none() {
                         continue;
                     } // ignore out-of-date event
@@ -867,6 +931,16 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                         );
                         continue; // this is no longer a conflict
                     }
+                    let is_outer_node = primal_node_internal.origin.upgrade_force() == node_ptr;
+                    if !is_mirror && is_outer_node && self.read_recursive().conflict_policy == ConflictPolicy::ShrinkFirst {
+                        for (other_touching_ptr, other_virtual_vertex_index) in
+                            dual_module.touching_virtual_vertices(&node_ptr)
+                        {
+                            if other_virtual_vertex_index < virtual_vertex_index {
+                                (touching_ptr, virtual_vertex_index) = (other_touching_ptr, other_virtual_vertex_index);
+                            }
+                        }
+                    }
                     // this is the most probable case, so put it in the front
                     if primal_node_internal.is_free() {
                         primal_node_internal.temporary_match =
@@ -989,6 +1063,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                             child_touching_child_ptr,
                         )
                     };
+                    self.record_blossom_expanded(&nodes_circle);
                     interface_ptr.expand_blossom(node_ptr, dual_module);
                     // now we need to re-connect all the expanded nodes, by analyzing the relationship of nodes_circle, parent_touching_ptr and child_touching_ptr
                     let parent_touching_index = nodes_circle
@@ -1361,6 +1436,57 @@ impl PrimalModuleSerial {
 }
 
 impl PrimalModuleSerialPtr {
+    /// pre-reserve the node list to avoid reallocation in the middle of decoding
+    pub fn reserve_capacity(&self, hints: &CapacityHints) {
+        let mut module = self.write();
+        let additional = hints.max_nodes().saturating_sub(module.nodes.len());
+        module.nodes.reserve(additional);
+    }
+
+    /// check that the primal module is clean after clear
+    pub fn check_clean(&self) -> Result<(), String> {
+        let module = self.read_recursive();
+        if module.nodes_length != 0 || !module.possible_break.is_empty() {
+            return Err(format!("primal module still has {} nodes", module.nodes_length));
+        }
+        Ok(())
+    }
+
+    /// count blossom formation for [`DecodeStats`], if thrashing detection is enabled
+    pub fn record_blossom_formed(&self) {
+        let mut module = self.write();
+        if module.thrash_threshold.is_some() {
+            module.decode_stats.blossoms_formed += 1;
+        }
+    }
+
+    /// count blossom expansion and report a [`ThrashEvent`] when the same blossom is expanded more than the threshold
+    pub fn record_blossom_expanded(&self, nodes_circle: &[DualNodeWeak]) {
+        let mut module = self.write();
+        let thrash_threshold = match module.thrash_threshold {
+            Some(thrash_threshold) => thrash_threshold,
+            None => return,
+        };
+        module.decode_stats.blossoms_expanded += 1;
+        let mut nodes: Vec<NodeIndex> = nodes_circle
+            .iter()
+            .map(|node_weak| node_weak.upgrade_force().read_recursive().index)
+            .collect();
+        nodes.sort_unstable();
+        let module = &mut *module;
+        let (cycles, event_index) = module.thrash_counter.entry(nodes.clone()).or_insert((0, None));
+        *cycles += 1;
+        if *cycles > thrash_threshold {
+            match event_index {
+                Some(event_index) => module.decode_stats.thrash_events[*event_index].cycles = *cycles,
+                None => {
+                    *event_index = Some(module.decode_stats.thrash_events.len());
+                    module.decode_stats.thrash_events.push(ThrashEvent { nodes, cycles: *cycles });
+                }
+            }
+        }
+    }
+
     pub fn get_primal_node_internal_ptr_option(&self, dual_node_ptr: &DualNodePtr) -> Option<PrimalNodeInternalPtr> {
         let module = self.read_recursive();
         let dual_node = dual_node_ptr.read_recursive();
@@ -1382,6 +1508,72 @@ impl PrimalModuleSerialPtr {
             .expect("internal primal node must exists")
     }
 
+    /// the size of the alternating tree that the dual node belongs to, compared against `max_tree_size` whenever the
+    /// tree grows; `None` if the node is not in any alternating tree, e.g. when it's matched or inside a blossom
+    pub fn tree_size(&self, dual_node_ptr: &DualNodePtr) -> Option<usize> {
+        let primal_node_internal_ptr = self.get_primal_node_internal_ptr_option(dual_node_ptr)?;
+        let root_weak = primal_node_internal_ptr.read_recursive().tree_node.as_ref()?.root.clone();
+        let root_ptr = root_weak.upgrade_force();
+        let root = root_ptr.read_recursive();
+        root.tree_node.as_ref().unwrap().tree_size.map(|tree_size| tree_size.get())
+    }
+
+    /// drain the conflicts in the order of `conflict_policy`, or `None` if they should be popped in the native order;
+    /// `VertexShrinkStop` events always stay at the end, because they're solved automatically by other conflicts
+    pub fn order_conflicts(&self, group_max_update_length: &mut GroupMaxUpdateLength) -> Option<Vec<MaxUpdateLength>> {
+        let conflict_policy = self.read_recursive().conflict_policy;
+        if conflict_policy == ConflictPolicy::Native {
+            return None;
+        }
+        let mut conflicts = vec![];
+        while let Some(conflict) = group_max_update_length.pop() {
+            conflicts.push(conflict);
+        }
+        let is_vertex_shrink_stop = |conflict: &MaxUpdateLength| matches!(conflict, MaxUpdateLength::VertexShrinkStop(..));
+        match conflict_policy {
+            ConflictPolicy::Native => unreachable!(),
+            ConflictPolicy::Fifo => {
+                conflicts.reverse();
+                conflicts.sort_by_key(is_vertex_shrink_stop);
+            }
+            ConflictPolicy::Priority => conflicts.sort_by(|a, b| b.cmp(a)),
+            ConflictPolicy::BlossomFirst => {
+                conflicts.sort_by_cached_key(|conflict| (!self.forms_blossom(conflict), is_vertex_shrink_stop(conflict)));
+            }
+            ConflictPolicy::BoundaryLast => conflicts.sort_by_key(|conflict| match conflict {
+                MaxUpdateLength::TouchingVirtual(..) => 1,
+                MaxUpdateLength::VertexShrinkStop(..) => 2,
+                _ => 0,
+            }),
+            ConflictPolicy::ShrinkFirst => conflicts.sort_by_key(|conflict| match conflict {
+                MaxUpdateLength::BlossomNeedExpand(..) => (0, 0),
+                MaxUpdateLength::TouchingVirtual(_, (virtual_vertex_index, _)) => (2, *virtual_vertex_index),
+                MaxUpdateLength::VertexShrinkStop(..) => (3, 0),
+                _ => (1, 0),
+            }),
+        }
+        Some(conflicts)
+    }
+
+    /// whether the conflict is between two outer nodes of the same alternating tree, which forms a blossom
+    pub fn forms_blossom(&self, conflict: &MaxUpdateLength) -> bool {
+        let MaxUpdateLength::Conflicting((node_ptr_1, _), (node_ptr_2, _)) = conflict else {
+            return false;
+        };
+        let root_of = |node_ptr: &DualNodePtr| {
+            let primal_node_internal_ptr = self.get_outer_node(self.get_primal_node_internal_ptr_option(node_ptr)?);
+            let primal_node_internal = primal_node_internal_ptr.read_recursive();
+            primal_node_internal
+                .tree_node
+                .as_ref()
+                .map(|tree_node| tree_node.root.clone())
+        };
+        match (root_of(node_ptr_1), root_of(node_ptr_2)) {
+            (Some(root_1), Some(root_2)) => root_1 == root_2,
+            _ => false,
+        }
+    }
+
     /// get the outer node in the most up-to-date cache
     pub fn get_outer_node(&self, primal_node_internal_ptr: PrimalNodeInternalPtr) -> PrimalNodeInternalPtr {
         let node = primal_node_internal_ptr.read_recursive();
@@ -1647,6 +1839,89 @@ impl PrimalModuleSerialPtr {
         debug_assert_eq!(flattened_nodes.len() - flattened_nodes_length, module.nodes_count() as usize);
     }
 
+    /// report the newly final matched pairs since the last call, see [`FinalMatchingTracker`] for the finality condition
+    #[allow(clippy::unnecessary_cast)]
+    pub fn drain_final_matchings(
+        &self,
+        interface_ptr: &DualModuleInterfacePtr,
+        tracker: &mut FinalMatchingTracker,
+    ) -> PerfectMatching {
+        let mut final_matching = PerfectMatching::new();
+        if self.read_recursive().max_tree_size != usize::MAX {
+            return final_matching;
+        }
+        let mut flattened_nodes = vec![];
+        self.flatten_nodes(&mut flattened_nodes);
+        let defect_vertex_of = |dual_node_ptr: &DualNodePtr| -> Option<VertexIndex> {
+            match dual_node_ptr.read_recursive().class {
+                DualNodeClass::DefectVertex { defect_index } => Some(defect_index),
+                DualNodeClass::Blossom { .. } => None,
+            }
+        };
+        let defects: Vec<VertexIndex> = flattened_nodes
+            .iter()
+            .flatten()
+            .filter_map(|primal_node_internal_ptr| {
+                defect_vertex_of(&primal_node_internal_ptr.read_recursive().origin.upgrade_force())
+            })
+            .collect();
+        let interface = interface_ptr.read_recursive();
+        for primal_node_internal_ptr in flattened_nodes.iter().flatten() {
+            let primal_node_internal = primal_node_internal_ptr.read_recursive();
+            if primal_node_internal.tree_node.is_some() {
+                continue;
+            }
+            let match_target = match primal_node_internal.temporary_match.as_ref() {
+                Some((match_target, _)) => match_target.clone(),
+                None => continue,
+            };
+            let dual_node_ptr = primal_node_internal.origin.upgrade_force();
+            let vertex = match defect_vertex_of(&dual_node_ptr) {
+                Some(vertex) => vertex,
+                None => continue,
+            };
+            {
+                let dual_node = dual_node_ptr.read_recursive();
+                if tracker.reported.contains(&vertex)
+                    || dual_node.parent_blossom.is_some()
+                    || dual_node.grow_state != DualNodeGrowState::Stay
+                {
+                    continue;
+                }
+            }
+            let dual_variable = dual_node_ptr.read_recursive().get_dual_variable(&interface);
+            match match_target {
+                MatchTarget::Peer(peer_internal_weak) => {
+                    let peer_dual_node_ptr = peer_internal_weak.upgrade_force().read_recursive().origin.upgrade_force();
+                    let peer_vertex = match defect_vertex_of(&peer_dual_node_ptr) {
+                        Some(peer_vertex) => peer_vertex,
+                        None => continue,
+                    };
+                    if peer_vertex < vertex {
+                        continue; // handled by the peer
+                    }
+                    let peer_dual_variable = peer_dual_node_ptr.read_recursive().get_dual_variable(&interface);
+                    if tracker.is_untouchable(vertex, dual_variable, Some(peer_vertex), &defects)
+                        && tracker.is_untouchable(peer_vertex, peer_dual_variable, Some(vertex), &defects)
+                    {
+                        tracker.reported.insert(vertex);
+                        tracker.reported.insert(peer_vertex);
+                        final_matching.peer_matchings.push((dual_node_ptr, peer_dual_node_ptr));
+                    }
+                }
+                MatchTarget::VirtualVertex(virtual_vertex) => {
+                    if tracker.is_virtual[virtual_vertex as usize]
+                        && tracker.is_untouchable(vertex, dual_variable, None, &defects)
+                    {
+                        tracker.reported.insert(vertex);
+                        final_matching.virtual_matchings.push((dual_node_ptr, virtual_vertex));
+                    }
+                }
+            }
+        }
+        final_matching
+    }
+
     /// fuse two modules by copying the nodes in `other` into myself
     #[allow(clippy::unnecessary_cast)]
     pub fn slow_fuse(&self, left: &Self, right: &Self) {
@@ -1960,6 +2235,99 @@ impl PrimalModuleSerialPtr {
     }
 }
 
+/// reports the matched pairs that can no longer change during the rest of the decoding, so that their correction can be
+/// output early in latency-sensitive settings; call [`PrimalModuleSerialPtr::drain_final_matchings`] from the callback of
+/// [`PrimalModuleImpl::solve_step_callback`] and each final pair is reported exactly once.
+///
+/// Finality condition. Let `bd(v)` be the distance from vertex `v` to the nearest virtual vertex and `y_a` the dual variable of a
+/// defect vertex node `a`. A node that is temporarily matched (neither in an alternating tree nor inside a blossom) stays with
+/// `y_a` unchanged until another node touches it. Dual feasibility along the shortest path from any defect `v` to a virtual
+/// vertex bounds the sum of the dual variables of all the nodes containing `v` by `bd(v)`, so these nodes never cover any vertex
+/// further than `bd(v)` from `v`, no matter how the rest of the decoding goes. Thus `a` is never touched again if
+/// `d(v, a) > bd(v) + y_a` for every other loaded defect `v` except its peer. A peer match `(a, b)` is final if both `a` and `b`
+/// satisfy this condition, and a virtual match `(a, t)` is final if `a` satisfies it and `t` is a virtual vertex of the decoding
+/// graph rather than a mirrored vertex, which may be broken when fused.
+///
+/// Note that
+/// - only the defects loaded so far are considered: defects loaded afterwards, e.g. by fusing another unit, may touch a reported pair;
+/// - the distances are computed from the static weights, so it must not be used when erasures or dynamic weights are loaded;
+/// - only matches between defect vertex nodes are reported, pairs inside blossoms are left to the final perfect matching;
+/// - nothing is reported when `max_tree_size` is finite, because the union-find mode is not guaranteed to find the optimal matching anyway.
+#[derive(Debug, Clone)]
+pub struct FinalMatchingTracker {
+    /// the decoding graph to compute the distance between defects
+    complete_graph: CompleteGraph,
+    /// the distance of each vertex to the nearest virtual vertex, `Weight::MAX` if not connected to any
+    boundary_distances: Vec<Weight>,
+    /// the maximum finite value in `boundary_distances`
+    max_boundary_distance: Weight,
+    /// whether a vertex is a virtual vertex of the decoding graph
+    is_virtual: Vec<bool>,
+    /// defect vertices that are already reported in the current decoding
+    reported: BTreeSet<VertexIndex>,
+}
+
+impl FinalMatchingTracker {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(initializer: &SolverInitializer) -> Self {
+        // multi-source Dijkstra from all the virtual vertices by connecting them to an auxiliary vertex with 0 weight
+        let auxiliary_vertex = initializer.vertex_num as VertexIndex;
+        let mut weighted_edges = initializer.weighted_edges.clone();
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            weighted_edges.push((auxiliary_vertex, virtual_vertex, 0));
+        }
+        let mut auxiliary_graph = CompleteGraph::new(initializer.vertex_num + 1, &weighted_edges);
+        let mut boundary_distances = vec![Weight::MAX; initializer.vertex_num as usize];
+        for (vertex_index, (_, weight)) in auxiliary_graph.all_edges(auxiliary_vertex) {
+            boundary_distances[vertex_index as usize] = weight;
+        }
+        let max_boundary_distance = boundary_distances
+            .iter()
+            .copied()
+            .filter(|&distance| distance != Weight::MAX)
+            .max()
+            .unwrap_or(0);
+        let complete_graph = CompleteGraph::from_initializer(initializer);
+        Self {
+            is_virtual: complete_graph.is_virtual.clone(),
+            complete_graph,
+            boundary_distances,
+            max_boundary_distance,
+            reported: BTreeSet::new(),
+        }
+    }
+
+    /// forget the reported pairs, must be called before each decoding
+    pub fn clear(&mut self) {
+        self.reported.clear();
+    }
+
+    /// whether the matched defect vertex `vertex` with dual variable `dual_variable` can never be touched by any of `defects` other than `peer`
+    #[allow(clippy::unnecessary_cast)]
+    fn is_untouchable(
+        &mut self,
+        vertex: VertexIndex,
+        dual_variable: Weight,
+        peer: Option<VertexIndex>,
+        defects: &[VertexIndex],
+    ) -> bool {
+        let radius = dual_variable.saturating_add(self.max_boundary_distance);
+        let distances = self.complete_graph.all_edges_within(vertex, VertexIndex::MAX, radius);
+        for &defect in defects.iter() {
+            if defect == vertex || Some(defect) == peer {
+                continue;
+            }
+            if let Some(&(_, distance)) = distances.get(&defect) {
+                let reach = self.boundary_distances[defect as usize].saturating_add(dual_variable);
+                if distance <= reach {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
 impl PrimalNodeInternalPtr {
     /// DFS flatten the children of a tree
     pub fn flatten_tree(&self, flattened_nodes: &mut Vec<PrimalNodeInternalPtr>) {
@@ -2170,6 +2538,76 @@ pub mod tests {
         // func(11, Some(visualize_filename), defect_vertices, 9, 3);
     }
 
+    /// the root of an existing tree must be unlocked before it collapses, otherwise the union-find decoder deadlocks
+    #[test]
+    fn primal_module_union_find_basic_11() {
+        // cargo test primal_module_union_find_basic_11 -- --nocapture
+        use crate::mwpm_solver::{PrimalDualSolver, SolverSerial};
+        let initializer = CodeCapacityPlanarCode::new(5, 0.1, 500).get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.primal_module.write().max_tree_size = 3;
+        let defect_vertices = vec![7, 8, 19, 20, 21, 25, 27];
+        solver.solve(&SyndromePattern::new_vertices(defect_vertices.clone()));
+        let perfect_matching = solver.perfect_matching();
+        assert_eq!(
+            perfect_matching.peer_matchings.len() * 2 + perfect_matching.virtual_matchings.len(),
+            defect_vertices.len()
+        );
+    }
+
+    /// the size of an alternating tree is the number of defects in it, summing up the defects inside blossoms
+    #[test]
+    fn primal_module_serial_tree_size_1() {
+        // cargo test primal_module_serial_tree_size_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        let mut checked_trees = 0;
+        let mut checked_blossoms = 0;
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            primal_module.solve_step_callback(
+                &interface_ptr,
+                &syndrome_pattern,
+                &mut dual_module,
+                |interface_ptr, _, primal_module, _| {
+                    let interface = interface_ptr.read_recursive();
+                    for dual_node_ptr in interface.nodes[..interface.nodes_length].iter().flatten() {
+                        if let DualNodeClass::Blossom { nodes_circle, .. } = &dual_node_ptr.read_recursive().class {
+                            let sum: usize = nodes_circle.iter().map(|weak| weak.upgrade_force().defect_size()).sum();
+                            assert_eq!(dual_node_ptr.defect_size(), sum);
+                            checked_blossoms += 1;
+                        }
+                        let Some(tree_size) = primal_module.tree_size(dual_node_ptr) else {
+                            continue;
+                        };
+                        let primal_node_internal_ptr = primal_module.get_primal_node_internal_ptr(dual_node_ptr);
+                        let node = primal_node_internal_ptr.read_recursive();
+                        if node.tree_node.as_ref().unwrap().depth != 0 {
+                            continue; // only check each tree once, from its root
+                        }
+                        drop(node);
+                        let mut tree_nodes = vec![];
+                        primal_node_internal_ptr.flatten_tree(&mut tree_nodes);
+                        let defects: usize = tree_nodes
+                            .iter()
+                            .map(|ptr| ptr.read_recursive().origin.upgrade_force().defect_size())
+                            .sum();
+                        assert_eq!(tree_size, defects);
+                        checked_trees += 1;
+                    }
+                },
+            );
+            interface_ptr.clear();
+            primal_module.clear();
+            dual_module.clear();
+        }
+        println!("checked {checked_trees} trees and {checked_blossoms} blossoms");
+        assert!(checked_trees > 0 && checked_blossoms > 0);
+    }
+
     /// test the error pattern in the paper
     #[test]
     fn primal_module_serial_default_example() {
@@ -2618,4 +3056,617 @@ pub mod tests {
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     }
+
+    /// the realized tight-edge paths should reproduce the syndrome and sum up to the dual objective
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn primal_module_serial_realized_paths_1() {
+        // cargo test primal_module_serial_realized_paths_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(5, 5, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            dual_module.clear();
+            primal_module.clear();
+            let interface_ptr = DualModuleInterfacePtr::new_empty();
+            primal_module.solve(&interface_ptr, &syndrome_pattern, &mut dual_module);
+            let mut perfect_matching = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
+            assert!(perfect_matching.realized_paths.is_none(), "realized paths are opt-in");
+            perfect_matching.record_realized_paths(&dual_module);
+            assert!(
+                perfect_matching.realized_paths.is_some(),
+                "serial dual module should provide tight paths"
+            );
+            let realized_paths = perfect_matching.realized_paths();
+            let mut realized_weight = 0;
+            let mut defect_parity = vec![false; initializer.vertex_num as usize];
+            for (vertex_1, vertex_2, path) in realized_paths.iter() {
+                for &edge_index in path.iter() {
+                    let (left, right, weight) = initializer.weighted_edges[edge_index as usize];
+                    realized_weight += weight;
+                    defect_parity[left as usize] ^= true;
+                    defect_parity[right as usize] ^= true;
+                }
+                // each path must start and end at the matched vertices
+                defect_parity[*vertex_1 as usize] ^= true;
+                defect_parity[*vertex_2 as usize] ^= true;
+            }
+            assert!(
+                defect_parity.iter().all(|x| !x),
+                "realized paths don't connect the matched pairs"
+            );
+            assert_eq!(realized_weight, interface_ptr.sum_dual_variables(), "seed {seed}");
+            subgraph_builder.load_perfect_matching(&perfect_matching);
+            assert_eq!(
+                subgraph_builder.total_weight(),
+                interface_ptr.sum_dual_variables(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    /// extracting the perfect matching leaves the blossoms collapsed in both modules, and costs little compared to
+    /// solving even with many nested blossoms
+    #[test]
+    fn primal_module_serial_extraction_time_1() {
+        // cargo test --release primal_module_serial_extraction_time_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(21, 21, 0.02, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+        let (mut solve_time, mut extraction_time, mut realized_paths_time) = (0., 0., 0.);
+        for seed in 0..5 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            dual_module.clear();
+            primal_module.clear();
+            let interface_ptr = DualModuleInterfacePtr::new_empty();
+            let begin = std::time::Instant::now();
+            primal_module.solve(&interface_ptr, &syndrome_pattern, &mut dual_module);
+            solve_time += begin.elapsed().as_secs_f64();
+            let nodes_count = interface_ptr.read_recursive().nodes_count();
+            let sum_dual_variables = interface_ptr.sum_dual_variables();
+            let begin = std::time::Instant::now();
+            let intermediate_matching = primal_module.intermediate_matching(&interface_ptr, &mut dual_module);
+            let mut perfect_matching = intermediate_matching.get_perfect_matching();
+            extraction_time += begin.elapsed().as_secs_f64();
+            let begin = std::time::Instant::now();
+            perfect_matching.record_realized_paths(&dual_module);
+            realized_paths_time += begin.elapsed().as_secs_f64();
+            // no blossom is expanded in the modules
+            assert_eq!(interface_ptr.read_recursive().nodes_count(), nodes_count);
+            assert_eq!(interface_ptr.sum_dual_variables(), sum_dual_variables);
+            assert_eq!(
+                perfect_matching.peer_matchings.len() * 2 + perfect_matching.virtual_matchings.len(),
+                syndrome_pattern.defect_vertices.len()
+            );
+            subgraph_builder.load_perfect_matching(&perfect_matching);
+            assert_eq!(subgraph_builder.total_weight(), sum_dual_variables, "seed {seed}");
+        }
+        println!("solve: {solve_time:.3e}s, extraction: {extraction_time:.3e}s, realized paths: {realized_paths_time:.3e}s");
+    }
+
+    /// a defect matched to the boundary always chooses the nearest virtual vertex, so alternatives never weigh less
+    #[test]
+    fn primal_module_serial_boundary_alternatives_1() {
+        // cargo test primal_module_serial_boundary_alternatives_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.subgraph_builder.boundary_alternatives_k = 2;
+        let mut boundary_matched_count = 0;
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let perfect_matching = solver.perfect_matching();
+            let boundary_alternatives = perfect_matching.boundary_alternatives.as_ref().unwrap();
+            assert_eq!(boundary_alternatives.len(), perfect_matching.virtual_matchings.len());
+            for boundary_alternative in boundary_alternatives.iter() {
+                let (matched_virtual, matched_weight) = boundary_alternative.matched;
+                assert_eq!(
+                    boundary_alternative.alternatives.len(),
+                    2,
+                    "planar code has more than 2 virtual vertices"
+                );
+                for &(virtual_vertex, weight, weight_difference) in boundary_alternative.alternatives.iter() {
+                    assert_ne!(virtual_vertex, matched_virtual);
+                    assert_eq!(weight - matched_weight, weight_difference);
+                    assert!(weight_difference >= 0, "boundary matching is not the nearest one");
+                }
+                assert!(boundary_alternative.alternatives[0].1 <= boundary_alternative.alternatives[1].1);
+            }
+            boundary_matched_count += boundary_alternatives.len();
+            solver.clear();
+        }
+        assert!(boundary_matched_count > 0, "no boundary matching is tested");
+    }
+
+    /// every defect is counted exactly once, either in a peer matching or in a boundary matching
+    #[test]
+    fn primal_module_serial_match_counts() {
+        // cargo test primal_module_serial_match_counts -- --nocapture
+        use super::super::mwpm_solver::*;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let (mut total_peer, mut total_boundary) = (0, 0);
+        for seed in 0..30 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let perfect_matching = solver.perfect_matching();
+            assert_eq!(
+                2 * perfect_matching.peer_match_count() + perfect_matching.boundary_match_count(),
+                syndrome_pattern.defect_vertices.len(),
+                "seed {seed}"
+            );
+            total_peer += perfect_matching.peer_match_count();
+            total_boundary += perfect_matching.boundary_match_count();
+            solver.clear();
+        }
+        assert!(total_peer > 0 && total_boundary > 0);
+    }
+
+    /// the attributed weights cover every defect once and sum up to the matching weight, whether they're computed
+    /// from the realized paths or from the decoding graph
+    #[test]
+    fn primal_module_serial_weight_attribution_1() {
+        // cargo test primal_module_serial_weight_attribution_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(5, 5, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new_config(&initializer, json!({ "realized_paths": true }));
+        let mut stats = WeightAttributionStats::new();
+        for seed in 0..30 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let mut perfect_matching = solver.perfect_matching();
+            let attribution = perfect_matching.weight_attribution(&initializer);
+            let defects: Vec<VertexIndex> = attribution.iter().map(|(vertex_index, _)| *vertex_index).collect();
+            let mut defect_vertices = syndrome_pattern.defect_vertices.clone();
+            defect_vertices.sort();
+            assert_eq!(defects, defect_vertices, "seed {seed}");
+            let attributed_weight: Weight = attribution.iter().map(|(_, weight)| *weight).sum();
+            assert_eq!(attributed_weight, solver.sum_dual_variables(), "seed {seed}");
+            perfect_matching.realized_paths = None;
+            let fallback_weight: Weight = perfect_matching
+                .weight_attribution(&initializer)
+                .iter()
+                .map(|(_, weight)| *weight)
+                .sum();
+            assert_eq!(fallback_weight, attributed_weight, "seed {seed}");
+            stats.add(&attribution);
+            solver.clear();
+        }
+        assert_eq!(stats.shots, 30);
+        let (&vertex_index, &(count, sum)) = stats.vertices.iter().next().unwrap();
+        assert_eq!(stats.mean(vertex_index), Some(sum as f64 / count as f64));
+        assert_eq!(stats.to_json()["vertices"].as_array().unwrap().len(), stats.vertices.len());
+    }
+
+    /// two valid MWPMs of the same syndrome only differ by pairs of the same total weight
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn primal_module_serial_matching_diff_1() {
+        // cargo test primal_module_serial_matching_diff_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let visualize_filename = "primal_module_serial_matching_diff_1.json".to_string();
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, half_weight);
+        let mut partition = crate::example_partition::CodeCapacityPlanarCodeVerticalPartitionHalf::new(11, 6);
+        let partition_config = crate::example_partition::ExamplePartition::build_apply(&mut partition, &mut code);
+        let initializer = code.get_initializer();
+        let mut visualizer = Visualizer::new(
+            Some(visualize_data_folder() + visualize_filename.as_str()),
+            code.get_positions(),
+            true,
+        )
+        .unwrap();
+        print_visualize_link(visualize_filename);
+        let partition_info = partition_config.info();
+        let mut solver_serial = SolverSerial::new(&initializer);
+        let mut solver_parallel = SolverParallel::new(&initializer, &partition_info, json!({}));
+        let mut subgraph_builder = SubGraphBuilder::new(&initializer);
+        let mut degenerate_count = 0;
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver_serial.solve(&syndrome_pattern);
+            solver_parallel.solve(&syndrome_pattern);
+            let matching_serial = solver_serial.perfect_matching();
+            let matching_parallel = solver_parallel.perfect_matching();
+            assert!(matching_serial.diff(&matching_serial).is_empty());
+            let matching_diff = matching_serial.diff(&matching_parallel);
+            let legacy_result = matching_parallel.legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            assert_eq!(
+                MatchingDiff::legacy_matched_pairs(&syndrome_pattern.defect_vertices, &legacy_result),
+                matching_parallel.matched_pairs()
+            );
+            if !matching_diff.is_empty() {
+                let ((_, weight_self), (_, weight_other)) = matching_diff.subgraphs(&mut subgraph_builder);
+                assert_eq!(weight_self, weight_other, "seed {seed}: {matching_diff:?}");
+                let visualize_diff = VisualizeMatchingDiff::new(&matching_diff, &mut subgraph_builder);
+                assert!(!visualize_diff.edges_self.is_empty() && !visualize_diff.edges_other.is_empty());
+                visualizer
+                    .snapshot_combined(
+                        format!("diff {seed}"),
+                        vec![
+                            &solver_serial.interface_ptr,
+                            &solver_serial.dual_module,
+                            &matching_serial,
+                            &visualize_diff,
+                        ],
+                    )
+                    .unwrap();
+                degenerate_count += 1;
+            }
+            solver_serial.clear();
+            solver_parallel.clear();
+        }
+        assert!(degenerate_count > 0, "no degenerate matching is tested");
+    }
+
+    /// the matchings of both solvers are perfect matchings, and broken matchings are reported
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn primal_module_serial_validate_matching_1() {
+        // cargo test primal_module_serial_validate_matching_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, half_weight);
+        let mut partition = crate::example_partition::CodeCapacityPlanarCodeVerticalPartitionHalf::new(11, 6);
+        let partition_config = crate::example_partition::ExamplePartition::build_apply(&mut partition, &mut code);
+        let initializer = code.get_initializer();
+        let mut solver_serial = SolverSerial::new(&initializer);
+        let mut solver_parallel = SolverParallel::new(&initializer, &partition_config.info(), json!({}));
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            for solver in [&mut solver_serial as &mut dyn PrimalDualSolver, &mut solver_parallel] {
+                solver.solve(&syndrome_pattern);
+                let perfect_matching = solver.perfect_matching();
+                perfect_matching.validate(&syndrome_pattern, &initializer).unwrap();
+                solver.clear();
+            }
+        }
+        // vertex 0 is next to the left virtual vertex 11 and vertex 3 is in the middle of the same row
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![0, 3, 4]);
+        solver_serial.solve(&syndrome_pattern);
+        let mut perfect_matching = solver_serial.perfect_matching();
+        assert_eq!(perfect_matching.matched_pairs(), vec![(0, 11), (3, 4)]);
+        perfect_matching.validate(&syndrome_pattern, &initializer).unwrap();
+        assert_eq!(
+            perfect_matching.validate(&SyndromePattern::new_vertices(vec![0, 3, 4, 5]), &initializer),
+            Err(MatchingError::UnmatchedDefect(5))
+        );
+        assert_eq!(
+            perfect_matching.validate(&SyndromePattern::new_vertices(vec![0, 3]), &initializer),
+            Err(MatchingError::UnknownDefect(4))
+        );
+        perfect_matching.virtual_matchings[0].1 = 1;
+        assert_eq!(
+            perfect_matching.validate(&syndrome_pattern, &initializer),
+            Err(MatchingError::NotVirtual {
+                defect_vertex: 0,
+                vertex: 1
+            })
+        );
+        perfect_matching.virtual_matchings[0].1 = 11;
+        let duplicated = perfect_matching.virtual_matchings[0].clone();
+        perfect_matching.virtual_matchings.push(duplicated);
+        assert_eq!(
+            perfect_matching.validate(&syndrome_pattern, &initializer),
+            Err(MatchingError::DuplicateDefect(0))
+        );
+    }
+
+    #[test]
+    fn primal_module_serial_thrash_events_1() {
+        // cargo test primal_module_serial_thrash_events_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, half_weight);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        // disabled by default, except for the always recorded peak of active nodes
+        solver.solve(&code.generate_random_errors(0));
+        let decode_stats = solver.decode_stats();
+        assert!(decode_stats.peak_active_nodes > 0);
+        assert_eq!(
+            DecodeStats {
+                peak_active_nodes: 0,
+                ..decode_stats
+            },
+            DecodeStats::default()
+        );
+        solver.clear();
+        // with threshold 0, every expansion is reported
+        solver.primal_module.write().thrash_threshold = Some(0);
+        let mut total_expanded = 0;
+        for seed in 0..200 {
+            solver.solve(&code.generate_random_errors(seed));
+            let decode_stats = solver.decode_stats();
+            let reported_cycles: usize = decode_stats.thrash_events.iter().map(|event| event.cycles).sum();
+            assert_eq!(reported_cycles, decode_stats.blossoms_expanded);
+            assert!(decode_stats.blossoms_expanded <= decode_stats.blossoms_formed);
+            for event in decode_stats.thrash_events.iter() {
+                assert!(event.nodes.windows(2).all(|pair| pair[0] < pair[1]), "nodes should be sorted");
+            }
+            total_expanded += decode_stats.blossoms_expanded;
+            solver.clear();
+            assert_eq!(
+                solver.decode_stats(),
+                DecodeStats::default(),
+                "clear should reset the statistics"
+            );
+        }
+        println!("total_expanded: {total_expanded}");
+        assert!(total_expanded > 0, "no blossom expansion is tested");
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn primal_module_serial_peak_active_nodes_1() {
+        // cargo test primal_module_serial_peak_active_nodes_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut code = CodeCapacityRepetitionCode::new(15, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        // two far-apart pairs grow simultaneously, while the last defect keeps growing alone at the end
+        code.set_defect_vertices(&[1, 2, 6, 7, 13]);
+        solver.solve(&code.get_syndrome());
+        assert_eq!(solver.decode_stats().peak_active_nodes, 5);
+        solver.clear();
+        assert_eq!(solver.decode_stats().peak_active_nodes, 0);
+        // a larger syndrome never has more active nodes than dual nodes
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, half_weight);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let decode_stats = solver.decode_stats();
+            assert!(decode_stats.peak_active_nodes <= solver.interface_ptr.read_recursive().nodes_count() as usize);
+            assert_eq!(
+                decode_stats.peak_active_nodes > 0,
+                !syndrome_pattern.defect_vertices.is_empty()
+            );
+            solver.clear();
+        }
+    }
+
+    /// every conflict policy yields the minimum weight, while the decoding time and the matching may differ
+    #[test]
+    fn primal_module_serial_conflict_policy_1() {
+        // cargo test --release primal_module_serial_conflict_policy_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let half_weight = 500;
+        let mut codes: Vec<Box<dyn ExampleCode>> = vec![
+            Box::new(CodeCapacityPlanarCode::new(11, 0.1, half_weight)),
+            Box::new(PhenomenologicalPlanarCode::new(7, 7, 0.05, half_weight)),
+            Box::new(CircuitLevelPlanarCode::new(5, 5, 0.01, half_weight)),
+        ];
+        let policies = [
+            ConflictPolicy::Native,
+            ConflictPolicy::Fifo,
+            ConflictPolicy::Priority,
+            ConflictPolicy::BlossomFirst,
+            ConflictPolicy::BoundaryLast,
+            ConflictPolicy::ShrinkFirst,
+        ];
+        for code in codes.iter_mut() {
+            let initializer = code.get_initializer();
+            let syndrome_patterns: Vec<_> = (0..100).map(|seed| code.generate_random_errors(seed)).collect();
+            let mut native_results = vec![];
+            for &policy in policies.iter() {
+                let mut solver = SolverSerial::new(&initializer);
+                solver.primal_module.write().conflict_policy = policy;
+                let mut different_subgraphs = 0;
+                let begin = std::time::Instant::now();
+                for (index, syndrome_pattern) in syndrome_patterns.iter().enumerate() {
+                    solver.solve(syndrome_pattern);
+                    let subgraph = solver.subgraph();
+                    let weight = solver.sum_dual_variables();
+                    assert_eq!(solver.subgraph_builder.total_weight(), weight, "{policy:?} is not optimal");
+                    if policy == ConflictPolicy::Native {
+                        native_results.push((subgraph, weight));
+                    } else {
+                        assert_eq!(weight, native_results[index].1);
+                        different_subgraphs += (subgraph != native_results[index].0) as usize;
+                    }
+                    solver.clear();
+                }
+                let elapsed = begin.elapsed().as_secs_f64();
+                println!("{policy:?}: {elapsed:.3e}s, {different_subgraphs} different subgraphs");
+            }
+        }
+    }
+
+    /// a defect in the middle of two virtual vertices is matched to the one with the lower index regardless of the
+    /// order of the edges
+    #[test]
+    fn primal_module_serial_shrink_first_1() {
+        // cargo test primal_module_serial_shrink_first_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        for weighted_edges in [vec![(1, 2, 100), (0, 1, 100)], vec![(0, 1, 100), (1, 2, 100)]] {
+            let initializer = SolverInitializer::new(3, weighted_edges, vec![0, 2]);
+            let mut matched_virtual_vertices = vec![];
+            for policy in [ConflictPolicy::Native, ConflictPolicy::ShrinkFirst] {
+                let mut solver = SolverSerial::new(&initializer);
+                solver.primal_module.write().conflict_policy = policy;
+                solver.solve(&SyndromePattern::new_vertices(vec![1]));
+                assert_eq!(solver.sum_dual_variables(), 100);
+                let perfect_matching = solver.perfect_matching();
+                assert_eq!(perfect_matching.virtual_matchings.len(), 1);
+                matched_virtual_vertices.push(perfect_matching.virtual_matchings[0].1);
+            }
+            println!("matched virtual vertices with Native and ShrinkFirst: {matched_virtual_vertices:?}");
+            assert_eq!(matched_virtual_vertices[1], 0);
+        }
+    }
+
+    /// pairs reported final during the decoding must appear in the final perfect matching
+    #[test]
+    fn primal_module_serial_final_matchings_1() {
+        // cargo test primal_module_serial_final_matchings_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(7, 15, 0.02, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        let mut tracker = FinalMatchingTracker::new(&initializer);
+        let (mut total_pairs, mut early_pairs) = (0, 0);
+        for seed in 0..40 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let mut reported = vec![];
+            primal_module.solve_step_callback(
+                &interface_ptr,
+                &syndrome_pattern,
+                &mut dual_module,
+                |interface_ptr, _, primal_module, _| {
+                    reported.extend(
+                        primal_module
+                            .drain_final_matchings(interface_ptr, &mut tracker)
+                            .matched_pairs(),
+                    );
+                },
+            );
+            early_pairs += reported.len();
+            reported.extend(
+                primal_module
+                    .drain_final_matchings(&interface_ptr, &mut tracker)
+                    .matched_pairs(),
+            );
+            let perfect_matching = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
+            let matched_pairs = perfect_matching.matched_pairs();
+            total_pairs += matched_pairs.len();
+            for pair in reported.iter() {
+                assert!(matched_pairs.contains(pair), "reported pair {pair:?} is not final");
+            }
+            let mut deduplicated = reported.clone();
+            deduplicated.sort();
+            deduplicated.dedup();
+            assert_eq!(deduplicated.len(), reported.len(), "each pair should be reported once");
+            interface_ptr.clear();
+            primal_module.clear();
+            dual_module.clear();
+            tracker.clear();
+        }
+        println!("early_pairs: {early_pairs}, total_pairs: {total_pairs}");
+        assert!(early_pairs > 0, "no pair is reported before the decoding finishes");
+    }
+
+    #[cfg(feature = "logging")]
+    type RecordedSpans = alloc::sync::Arc<parking_lot::Mutex<Vec<(String, Option<String>)>>>;
+
+    /// records the name of every new span together with the name of its parent span
+    #[cfg(feature = "logging")]
+    struct SpanRecorder {
+        spans: RecordedSpans,
+    }
+
+    #[cfg(feature = "logging")]
+    impl<S> tracing_subscriber::Layer<S> for SpanRecorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            _attributes: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            context: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = context.span(id).unwrap();
+            let parent = span.parent().map(|parent| parent.name().to_string());
+            self.spans.lock().push((span.name().to_string(), parent));
+        }
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn primal_module_serial_logging_spans_1() {
+        // cargo test --features logging primal_module_serial_logging_spans_1 -- --nocapture
+        use tracing_subscriber::layer::SubscriberExt;
+        let spans = RecordedSpans::default();
+        let subscriber = tracing_subscriber::registry().with(SpanRecorder { spans: spans.clone() });
+        let code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
+        let interface_ptr = DualModuleInterfacePtr::new_empty();
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![16, 19, 29, 39]);
+        tracing::subscriber::with_default(subscriber, || {
+            primal_module.solve(&interface_ptr, &syndrome_pattern, &mut dual_module);
+        });
+        let spans = spans.lock();
+        println!("spans: {spans:?}");
+        assert_eq!(spans[0], ("solve".to_string(), None));
+        assert_eq!(spans.iter().filter(|(name, _)| name == "solve").count(), 1);
+        for name in ["load", "grow", "resolve"] {
+            assert!(spans.iter().any(|(span_name, _)| span_name == name), "missing span {name}");
+        }
+        for (name, parent) in spans.iter().skip(1) {
+            assert_eq!(parent.as_deref(), Some("solve"), "span {name} is not inside the solve span");
+        }
+    }
+
+    /// the clusters partition the matching, and the defects of a blossom resolve together with its matched partner
+    #[test]
+    fn primal_module_serial_matching_by_cluster_1() {
+        // cargo test primal_module_serial_matching_by_cluster_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        // three defects 2 edges apart form a blossom, which is then matched to the virtual vertex 71
+        solver.solve(&SyndromePattern::new_vertices(vec![39, 41, 63]));
+        let clusters = solver.perfect_matching().by_cluster();
+        assert_eq!(
+            clusters,
+            vec![ClusterResult {
+                defect_vertices: vec![39, 41, 63],
+                peer_matchings: vec![(39, 41)],
+                virtual_matchings: vec![(63, 71)],
+            }]
+        );
+        solver.clear();
+        // two pairs on the corners of a square resolve independently
+        solver.solve(&SyndromePattern::new_vertices(vec![40, 50, 52, 62]));
+        let clusters = solver.perfect_matching().by_cluster();
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].defect_vertices, vec![40, 52]);
+        assert_eq!(clusters[1].defect_vertices, vec![50, 62]);
+        solver.clear();
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let perfect_matching = solver.perfect_matching();
+            let clusters = perfect_matching.by_cluster();
+            let mut defect_vertices: Vec<_> = clusters
+                .iter()
+                .flat_map(|cluster| cluster.defect_vertices.iter().cloned())
+                .collect();
+            defect_vertices.sort();
+            let mut expected_defect_vertices = syndrome_pattern.defect_vertices.clone();
+            expected_defect_vertices.sort();
+            assert_eq!(defect_vertices, expected_defect_vertices);
+            let mut matched_pairs: Vec<_> = clusters
+                .iter()
+                .flat_map(|cluster| cluster.peer_matchings.iter().chain(cluster.virtual_matchings.iter()).cloned())
+                .collect();
+            matched_pairs.sort();
+            assert_eq!(matched_pairs, perfect_matching.matched_pairs());
+            for cluster in clusters.iter() {
+                let matched_count = 2 * cluster.peer_matchings.len() + cluster.virtual_matchings.len();
+                assert_eq!(matched_count, cluster.defect_vertices.len());
+            }
+            solver.clear();
+        }
+    }
 }
@@ -0,0 +1,481 @@
+//! Stim Detector Error Model
+//!
+//! Import a detector error model (DEM) generated by Stim, e.g. `circuit.detector_error_model(decompose_errors=True)`,
+//! as a decoding graph together with the logical observables flipped by each edge. Each detector `D<k>` becomes
+//! vertex `k` and a single virtual vertex (the last one) stands for the boundary. Every error mechanism must be
+//! graphlike, i.e. each of its components separated by `^` flips at most two detectors; Stim decomposes hyperedges
+//! this way when asked to. The supported instructions are `error`, `detector`, `logical_observable`,
+//! `shift_detectors` and (nested) `repeat` blocks.
+//!
+
+use super::util::*;
+#[cfg(feature = "python_binding")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// the logical observables flipped by each edge of a decoding graph imported from a detector error model
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct ObservableMap {
+    /// the number of logical observables
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub observable_num: usize,
+    /// for each edge, the bit mask of the observables it flips, in the format of
+    /// [`crate::mwpm_solver::PrimalDualSolver::stim_integration_predict_bit_packed_data`]
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub edge_masks: Vec<usize>,
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl ObservableMap {
+    /// the observables flipped by a correction subgraph, as a bit mask
+    #[allow(clippy::unnecessary_cast)]
+    pub fn observables_of(&self, subgraph: Vec<EdgeIndex>) -> usize {
+        subgraph
+            .iter()
+            .fold(0, |mask, &edge_index| mask ^ self.edge_masks[edge_index as usize])
+    }
+    #[cfg(feature = "python_binding")]
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// the error when importing a detector error model
+#[derive(Debug, Clone, PartialEq)]
+pub enum DemError {
+    /// the file cannot be read
+    Io(String),
+    /// a line is not valid DEM syntax or uses an unsupported instruction
+    Syntax { line: usize, message: String },
+    /// a component of an error mechanism flips more than two detectors, so it's not an edge
+    UndecomposableHyperedge { line: usize, detectors: Vec<usize> },
+    /// the error probability is not in `[0, 0.5]`, which MWPM cannot decode with non-negative weights
+    InvalidProbability { line: usize, probability: f64 },
+    /// the observable mask doesn't fit in `usize`
+    TooManyObservables { line: usize, observable: usize },
+}
+
+impl std::fmt::Display for DemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "cannot read the detector error model: {message}"),
+            Self::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            Self::UndecomposableHyperedge { line, detectors } => write!(
+                f,
+                "line {line}: the error flips {} detectors {detectors:?}, which is not an edge; generate the model \
+                with `decompose_errors=True` in Stim",
+                detectors.len()
+            ),
+            Self::InvalidProbability { line, probability } => {
+                write!(f, "line {line}: error probability {probability} is not in [0, 0.5]")
+            }
+            Self::TooManyObservables { line, observable } => write!(
+                f,
+                "line {line}: observable L{observable} exceeds the maximum of {} observables",
+                usize::BITS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DemError {}
+
+/// the default `max_half_weight` of the imported decoding graph, see [`normalized_weights_of_p`]
+pub const DEM_MAX_HALF_WEIGHT: Weight = 5000;
+
+enum DemInstruction {
+    /// the components separated by `^`, each with its detectors and observable mask
+    Error {
+        line: usize,
+        probability: f64,
+        components: Vec<(Vec<usize>, usize)>,
+    },
+    Detector(Vec<usize>),
+    LogicalObservable(usize),
+    ShiftDetectors(usize),
+    Repeat(usize, Vec<DemInstruction>),
+}
+
+/// parse the lines into instructions until the end of the current block
+fn parse_dem_block(
+    lines: &mut std::iter::Peekable<impl Iterator<Item = (usize, String)>>,
+    is_nested: bool,
+) -> Result<Vec<DemInstruction>, DemError> {
+    let mut instructions = vec![];
+    while let Some((line, text)) = lines.next() {
+        let syntax_error = |message: String| DemError::Syntax { line, message };
+        if text == "}" {
+            return if is_nested {
+                Ok(instructions)
+            } else {
+                Err(syntax_error("unmatched `}`".to_string()))
+            };
+        }
+        // `name[tag](arguments) targets`, where the tag and the arguments are optional
+        let name_end = text
+            .find(|c: char| c == '(' || c == '[' || c.is_whitespace())
+            .unwrap_or(text.len());
+        let name = &text[..name_end];
+        let mut rest = &text[name_end..];
+        if rest.starts_with('[') {
+            let tag_end = rest.find(']').ok_or_else(|| syntax_error("unclosed tag".to_string()))?;
+            rest = &rest[tag_end + 1..];
+        }
+        let mut arguments: Vec<f64> = vec![];
+        if rest.starts_with('(') {
+            let arguments_end = rest.find(')').ok_or_else(|| syntax_error("unclosed arguments".to_string()))?;
+            for argument in rest[1..arguments_end].split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                arguments.push(
+                    argument
+                        .parse()
+                        .map_err(|_| syntax_error(format!("invalid argument `{argument}`")))?,
+                );
+            }
+            rest = &rest[arguments_end + 1..];
+        }
+        let targets: Vec<&str> = rest.split_whitespace().collect();
+        let parse_index = |target: &str, prefix: char| -> Result<usize, DemError> {
+            target
+                .strip_prefix(prefix)
+                .and_then(|index| index.parse().ok())
+                .ok_or_else(|| syntax_error(format!("invalid target `{target}`")))
+        };
+        match name {
+            "error" => {
+                let probability = match arguments[..] {
+                    [probability] => probability,
+                    _ => return Err(syntax_error("`error` takes exactly one probability".to_string())),
+                };
+                if !(0. ..=0.5).contains(&probability) {
+                    return Err(DemError::InvalidProbability { line, probability });
+                }
+                let mut components = vec![(vec![], 0)];
+                for &target in targets.iter() {
+                    if target == "^" {
+                        components.push((vec![], 0));
+                        continue;
+                    }
+                    let (detectors, mask) = components.last_mut().unwrap();
+                    if target.starts_with('D') {
+                        let detector = parse_index(target, 'D')?;
+                        // a detector flipped twice by the same component is not flipped
+                        match detectors.iter().position(|&d| d == detector) {
+                            Some(index) => {
+                                detectors.swap_remove(index);
+                            }
+                            None => detectors.push(detector),
+                        }
+                    } else if target.starts_with('L') {
+                        let observable = parse_index(target, 'L')?;
+                        if observable >= usize::BITS as usize {
+                            return Err(DemError::TooManyObservables { line, observable });
+                        }
+                        *mask ^= 1 << observable;
+                    } else {
+                        return Err(syntax_error(format!("invalid target `{target}`")));
+                    }
+                }
+                instructions.push(DemInstruction::Error {
+                    line,
+                    probability,
+                    components,
+                });
+            }
+            "detector" => {
+                let detectors = targets
+                    .iter()
+                    .map(|target| parse_index(target, 'D'))
+                    .collect::<Result<_, _>>()?;
+                instructions.push(DemInstruction::Detector(detectors));
+            }
+            "logical_observable" => {
+                for target in targets.iter() {
+                    let observable = parse_index(target, 'L')?;
+                    if observable >= usize::BITS as usize {
+                        return Err(DemError::TooManyObservables { line, observable });
+                    }
+                    instructions.push(DemInstruction::LogicalObservable(observable));
+                }
+            }
+            "shift_detectors" => {
+                let shift = match targets[..] {
+                    [shift] => shift
+                        .parse()
+                        .map_err(|_| syntax_error(format!("invalid detector shift `{shift}`")))?,
+                    [] => 0,
+                    _ => return Err(syntax_error("`shift_detectors` takes one shift".to_string())),
+                };
+                instructions.push(DemInstruction::ShiftDetectors(shift));
+            }
+            "repeat" => {
+                let count = match targets[..] {
+                    [count, "{"] => count
+                        .parse()
+                        .map_err(|_| syntax_error(format!("invalid repeat count `{count}`")))?,
+                    _ => return Err(syntax_error("expecting `repeat <count> {`".to_string())),
+                };
+                let block = parse_dem_block(lines, true)?;
+                instructions.push(DemInstruction::Repeat(count, block));
+            }
+            _ => return Err(syntax_error(format!("unsupported instruction `{name}`"))),
+        }
+    }
+    if is_nested {
+        Err(DemError::Syntax {
+            line: 0,
+            message: "unclosed `repeat` block".to_string(),
+        })
+    } else {
+        Ok(instructions)
+    }
+}
+
+/// the state when executing the instructions
+#[derive(Default)]
+struct DemGraph {
+    detector_offset: usize,
+    detector_num: usize,
+    observable_num: usize,
+    /// `(detector, Some(detector))` for an edge between two detectors and `(detector, None)` for a boundary edge,
+    /// with the probability and the observable mask
+    edges: BTreeMap<(usize, Option<usize>), (f64, usize)>,
+}
+
+impl DemGraph {
+    fn execute(&mut self, instructions: &[DemInstruction]) -> Result<(), DemError> {
+        for instruction in instructions.iter() {
+            match instruction {
+                DemInstruction::Error {
+                    line,
+                    probability,
+                    components,
+                } => {
+                    for (detectors, mask) in components.iter() {
+                        let mut detectors: Vec<usize> = detectors.iter().map(|d| d + self.detector_offset).collect();
+                        detectors.sort_unstable();
+                        if let Some(&detector) = detectors.last() {
+                            self.detector_num = self.detector_num.max(detector + 1);
+                        }
+                        self.observable_num = self.observable_num.max(usize::BITS as usize - mask.leading_zeros() as usize);
+                        let key = match detectors[..] {
+                            // an undetectable error doesn't belong to the decoding graph
+                            [] => continue,
+                            [detector] => (detector, None),
+                            [detector_1, detector_2] => (detector_1, Some(detector_2)),
+                            _ => return Err(DemError::UndecomposableHyperedge { line: *line, detectors }),
+                        };
+                        if *probability == 0. {
+                            continue;
+                        }
+                        self.edges
+                            .entry(key)
+                            .and_modify(|(existing_probability, existing_mask)| {
+                                if existing_mask == mask {
+                                    // independent mechanisms with the same effect: either one but not both happens
+                                    *existing_probability = *existing_probability * (1. - probability)
+                                        + probability * (1. - *existing_probability);
+                                } else if probability > existing_probability {
+                                    // the matching cannot tell them apart, so keep the more likely one
+                                    *existing_probability = *probability;
+                                    *existing_mask = *mask;
+                                }
+                            })
+                            .or_insert((*probability, *mask));
+                    }
+                }
+                DemInstruction::Detector(detectors) => {
+                    for detector in detectors.iter() {
+                        self.detector_num = self.detector_num.max(detector + self.detector_offset + 1);
+                    }
+                }
+                DemInstruction::LogicalObservable(observable) => {
+                    self.observable_num = self.observable_num.max(observable + 1);
+                }
+                DemInstruction::ShiftDetectors(shift) => self.detector_offset += shift,
+                DemInstruction::Repeat(count, block) => {
+                    for _ in 0..*count {
+                        self.execute(block)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// import a detector error model with the given `max_half_weight`, see the module documentation
+#[allow(clippy::unnecessary_cast)]
+pub fn parse_dem(dem: &str, max_half_weight: Weight) -> Result<(SolverInitializer, ObservableMap), DemError> {
+    let mut lines = dem
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text.split('#').next().unwrap().trim().to_string()))
+        .filter(|(_, text)| !text.is_empty())
+        .flat_map(|(line, text)| {
+            // a `}` may close a block at the end of a line
+            match text.strip_suffix('}').map(str::trim_end) {
+                Some(instruction) if !instruction.is_empty() => {
+                    vec![(line, instruction.to_string()), (line, "}".to_string())]
+                }
+                _ => vec![(line, text)],
+            }
+        })
+        .peekable();
+    let instructions = parse_dem_block(&mut lines, false)?;
+    let mut graph = DemGraph::default();
+    graph.execute(&instructions)?;
+    let virtual_vertex = graph.detector_num as VertexIndex;
+    let probabilities: Vec<f64> = graph.edges.values().map(|&(probability, _)| probability).collect();
+    let weights = normalized_weights_of_p(&probabilities, max_half_weight);
+    let weighted_edges = graph
+        .edges
+        .keys()
+        .zip(weights)
+        .map(|(&(detector_1, detector_2), weight)| {
+            let vertex_2 = detector_2.map_or(virtual_vertex, |detector| detector as VertexIndex);
+            (detector_1 as VertexIndex, vertex_2, weight)
+        })
+        .collect();
+    let initializer = SolverInitializer::new(graph.detector_num as VertexNum + 1, weighted_edges, vec![virtual_vertex]);
+    let observable_map = ObservableMap {
+        observable_num: graph.observable_num,
+        edge_masks: graph.edges.values().map(|&(_, mask)| mask).collect(),
+    };
+    Ok((initializer, observable_map))
+}
+
+impl SolverInitializer {
+    /// import a detector error model generated by Stim, together with the observables flipped by each edge, see
+    /// [`crate::stim_dem`]
+    pub fn from_dem_str(dem: &str) -> Result<(Self, ObservableMap), DemError> {
+        parse_dem(dem, DEM_MAX_HALF_WEIGHT)
+    }
+
+    /// [`SolverInitializer::from_dem_str`] of a file
+    pub fn from_dem_file(path: impl AsRef<std::path::Path>) -> Result<(Self, ObservableMap), DemError> {
+        let dem = std::fs::read_to_string(path).map_err(|error| DemError::Io(error.to_string()))?;
+        Self::from_dem_str(&dem)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl SolverInitializer {
+    #[staticmethod]
+    #[pyo3(name = "from_dem_str")]
+    fn py_from_dem_str(dem: &str) -> PyResult<(Self, ObservableMap)> {
+        Self::from_dem_str(dem).map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))
+    }
+    #[staticmethod]
+    #[pyo3(name = "from_dem_file")]
+    fn py_from_dem_file(path: &str) -> PyResult<(Self, ObservableMap)> {
+        Self::from_dem_file(path).map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pyfunction]
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<ObservableMap>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mwpm_solver::*;
+
+    #[test]
+    fn stim_dem_repetition_code_1() {
+        // cargo test stim_dem_repetition_code_1 -- --nocapture
+        // a distance-3 repetition code with 2 rounds, written with a `repeat` block
+        let dem = "
+            error(0.1) D0 L0  # left boundary
+            error(0.1) D0 D1
+            error(0.1) D1
+            detector(0, 0) D0
+            detector(1, 0) D1
+            repeat 1 {
+                error(0.05) D0 D2
+                error(0.05) D1 D3
+                shift_detectors(0, 1) 2
+                error(0.1) D0 L0
+                error(0.1) D0 D1
+                error(0.1) D1
+            }
+            logical_observable L0
+        ";
+        let (initializer, observable_map) = SolverInitializer::from_dem_str(dem).unwrap();
+        assert_eq!(initializer.vertex_num, 5);
+        assert_eq!(initializer.virtual_vertices, vec![4]);
+        assert_eq!(initializer.weighted_edges.len(), 8);
+        assert_eq!(observable_map.observable_num, 1);
+        assert_eq!(observable_map.edge_masks.len(), 8);
+        // the time-like edges are less likely and thus heavier
+        let weight_of = |vertex_1, vertex_2| {
+            initializer
+                .weighted_edges
+                .iter()
+                .find(|&&(left, right, _)| (left, right) == (vertex_1, vertex_2))
+                .unwrap()
+                .2
+        };
+        assert!(weight_of(0, 2) > weight_of(0, 1));
+        assert_eq!(weight_of(0, 2), 2 * DEM_MAX_HALF_WEIGHT);
+        // a single error at the left boundary in the second round flips the observable
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&SyndromePattern::new_vertices(vec![2]));
+        assert_eq!(observable_map.observables_of(solver.subgraph()), 1);
+        solver.clear();
+        solver.solve(&SyndromePattern::new_vertices(vec![1, 3]));
+        assert_eq!(observable_map.observables_of(solver.subgraph()), 0);
+    }
+
+    #[test]
+    fn stim_dem_errors_1() {
+        // cargo test stim_dem_errors_1 -- --nocapture
+        // decomposed hyperedges are split into edges with the same probability
+        let (initializer, observable_map) = SolverInitializer::from_dem_str("error(0.01) D0 D1 ^ D2 L0").unwrap();
+        assert_eq!(initializer.vertex_num, 4);
+        assert_eq!(initializer.weighted_edges.len(), 2);
+        assert_eq!(observable_map.edge_masks, vec![0, 1]);
+        // parallel mechanisms with the same observables are merged
+        let (initializer, _) = SolverInitializer::from_dem_str("error(0.1) D0 D1\nerror(0.1) D1 D0").unwrap();
+        assert_eq!(initializer.weighted_edges.len(), 1);
+        assert_eq!(
+            SolverInitializer::from_dem_str("error(0.01) D0 D1 D2").err(),
+            Some(DemError::UndecomposableHyperedge {
+                line: 1,
+                detectors: vec![0, 1, 2]
+            })
+        );
+        assert_eq!(
+            SolverInitializer::from_dem_str("error(0.1) D0\nerror(0.7) D0 D1").err(),
+            Some(DemError::InvalidProbability {
+                line: 2,
+                probability: 0.7
+            })
+        );
+        assert!(matches!(
+            SolverInitializer::from_dem_str("error(0.1) D0\nrepeat 2 {\nerror(0.1) D0"),
+            Err(DemError::Syntax { .. })
+        ));
+        assert!(matches!(
+            SolverInitializer::from_dem_str("detector_separator 1"),
+            Err(DemError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            SolverInitializer::from_dem_file("tmp/non_existing_file.dem"),
+            Err(DemError::Io(_))
+        ));
+        // the error message points the user to the Stim option
+        let message = SolverInitializer::from_dem_str("error(0.01) D0 D1 D2")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("decompose_errors=True"));
+    }
+}
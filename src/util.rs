@@ -1,19 +1,30 @@
-use super::mwpm_solver::PrimalDualSolver;
+use super::complete_graph::CompleteGraph;
+use super::mwpm_solver::{PrimalDualSolver, SolverSerial};
 use super::pointers::*;
 use super::rand_xoshiro;
+use crate::prelude::*;
 use crate::rand_xoshiro::rand_core::RngCore;
+use alloc::collections::{BTreeMap, BTreeSet};
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::prelude::*;
+#[cfg(feature = "std")]
 use std::time::Instant;
 
+#[cfg(all(feature = "i32_weight", feature = "wide_weights"))]
+compile_error!("features `i32_weight` and `wide_weights` are mutually exclusive");
+
 cfg_if::cfg_if! {
     if #[cfg(feature="i32_weight")] {
         /// use i32 to store weight to be compatible with blossom V library (c_int)
         pub type Weight = i32;
+    } else if #[cfg(feature="wide_weights")] {
+        /// use i64 to store weight regardless of the pointer width (e.g. on wasm32), for fine-grained soft weights
+        pub type Weight = i64;
     } else {
         pub type Weight = isize;
     }
@@ -40,6 +51,21 @@ cfg_if::cfg_if! {
     }
 }
 
+/// sum up weights using a widened accumulator, so that an overflow is reported instead of silently wrapping around
+pub fn checked_weight_sum(weights: impl IntoIterator<Item = Weight>) -> Result<Weight, String> {
+    let mut sum: i128 = 0;
+    for weight in weights {
+        sum += weight as i128;
+    }
+    Weight::try_from(sum).map_err(|_| format!("sum of weights {sum} overflows `Weight`"))
+}
+
+/// the maximum edge weight such that the total weight of a path through all the vertices is no larger than `max_total`
+#[allow(clippy::unnecessary_cast)]
+pub fn max_safe_weight(max_total: i128, vertex_num: VertexNum) -> Weight {
+    (max_total / (vertex_num as i128).max(1)).min(Weight::MAX as i128) as Weight
+}
+
 #[cfg(feature = "python_binding")]
 macro_rules! bind_trait_python_json {
     ($struct_name:ident) => {
@@ -59,7 +85,7 @@ macro_rules! bind_trait_python_json {
     };
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolverInitializer {
@@ -72,13 +98,18 @@ pub struct SolverInitializer {
     /// the virtual vertices
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     pub virtual_vertices: Vec<VertexIndex>,
+    /// optionally, the measurement round of each edge: `Some(round)` for a time-like edge that is erased when the
+    /// measurement round is lost, and `None` otherwise; required by [`ErasureRegion::TimeRound`]
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edge_rounds: Option<Vec<Option<usize>>>,
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_python_json! {SolverInitializer}
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SyndromePattern {
     /// the vertices corresponding to defect measurements
@@ -94,6 +125,288 @@ pub struct SyndromePattern {
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     #[serde(default = "default_dynamic_weights")]
     pub dynamic_weights: Vec<(EdgeIndex, Weight)>,
+    /// erasures given as whole regions instead of edge indices, e.g. a lost measurement round; the solver expands them
+    /// into `erasures` using an [`ErasureRegionIndex`] built from the initializer; in Python they are given in the JSON
+    /// form, e.g. `[{"TimeRound": 0}, {"VertexRange": [0, 4]}]`
+    #[serde(default = "default_erasure_regions", skip_serializing_if = "Vec::is_empty")]
+    pub erasure_regions: Vec<ErasureRegion>,
+    /// the vertices whose measurement is lost, so that their parity is unknown; all the incident edges are erased so
+    /// that the vertex becomes a free pass-through between its neighbors. An uncertain vertex must not be a defect
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    #[serde(default = "default_uncertain_vertices", skip_serializing_if = "Vec::is_empty")]
+    pub uncertain_vertices: Vec<VertexIndex>,
+}
+
+/// a set of edges that are erased together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErasureRegion {
+    /// all the time-like edges of a measurement round, see [`SolverInitializer::edge_rounds`]
+    TimeRound(usize),
+    /// all the edges whose both vertices are in the range
+    VertexRange(VertexRange),
+}
+
+pub fn default_erasure_regions() -> Vec<ErasureRegion> {
+    vec![]
+}
+
+pub fn default_uncertain_vertices() -> Vec<VertexIndex> {
+    vec![]
+}
+
+/// the edges of each [`ErasureRegion`], precomputed from the initializer so that expanding a region doesn't
+/// require scanning the edges by their rounds
+#[derive(Debug, Clone)]
+pub struct ErasureRegionIndex {
+    /// the time-like edges of each measurement round; `None` if the initializer doesn't provide `edge_rounds`
+    pub round_edges: Option<Vec<Vec<EdgeIndex>>>,
+    /// the vertices of each edge
+    pub edge_vertices: Vec<(VertexIndex, VertexIndex)>,
+}
+
+impl ErasureRegionIndex {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(initializer: &SolverInitializer) -> Self {
+        let round_edges = initializer.edge_rounds.as_ref().map(|edge_rounds| {
+            assert_eq!(
+                edge_rounds.len(),
+                initializer.weighted_edges.len(),
+                "edge_rounds must have exactly one entry per edge"
+            );
+            let round_num = edge_rounds.iter().flatten().map(|round| round + 1).max().unwrap_or(0);
+            let mut round_edges = vec![vec![]; round_num];
+            for (edge_index, round) in edge_rounds.iter().enumerate() {
+                if let Some(round) = round {
+                    round_edges[*round].push(edge_index as EdgeIndex);
+                }
+            }
+            round_edges
+        });
+        Self {
+            round_edges,
+            edge_vertices: initializer.weighted_edges.iter().map(|(i, j, _)| (*i, *j)).collect(),
+        }
+    }
+
+    /// append the edges of a region to `erasures`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn expand_region(&self, region: &ErasureRegion, erasures: &mut Vec<EdgeIndex>) {
+        match region {
+            ErasureRegion::TimeRound(round) => {
+                let round_edges = self
+                    .round_edges
+                    .as_ref()
+                    .expect("time round erasure requires `edge_rounds` in the initializer");
+                assert!(
+                    *round < round_edges.len(),
+                    "measurement round {round} out of range, there are {} rounds",
+                    round_edges.len()
+                );
+                erasures.extend_from_slice(&round_edges[*round]);
+            }
+            ErasureRegion::VertexRange(vertex_range) => {
+                for (edge_index, (i, j)) in self.edge_vertices.iter().enumerate() {
+                    if vertex_range.contains(*i) && vertex_range.contains(*j) {
+                        erasures.push(edge_index as EdgeIndex);
+                    }
+                }
+            }
+        }
+    }
+
+    /// append the edges incident to any of the uncertain vertices to `erasures`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn expand_uncertain_vertices(&self, uncertain_vertices: &[VertexIndex], erasures: &mut Vec<EdgeIndex>) {
+        let is_uncertain: BTreeSet<VertexIndex> = uncertain_vertices.iter().copied().collect();
+        for (edge_index, (i, j)) in self.edge_vertices.iter().enumerate() {
+            if is_uncertain.contains(i) || is_uncertain.contains(j) {
+                erasures.push(edge_index as EdgeIndex);
+            }
+        }
+    }
+
+    /// the syndrome pattern with all erasure regions and uncertain vertices expanded into `erasures`, removing
+    /// duplicated edges; `None` if there is neither, in which case the original syndrome pattern should be used
+    #[allow(clippy::unnecessary_cast)]
+    pub fn expand(&self, syndrome_pattern: &SyndromePattern) -> Option<SyndromePattern> {
+        if syndrome_pattern.erasure_regions.is_empty() && syndrome_pattern.uncertain_vertices.is_empty() {
+            return None;
+        }
+        let mut erasures = syndrome_pattern.erasures.clone();
+        for region in syndrome_pattern.erasure_regions.iter() {
+            self.expand_region(region, &mut erasures);
+        }
+        if !syndrome_pattern.uncertain_vertices.is_empty() {
+            self.expand_uncertain_vertices(&syndrome_pattern.uncertain_vertices, &mut erasures);
+        }
+        let mut is_erased = vec![false; self.edge_vertices.len()];
+        erasures.retain(|edge_index| !core::mem::replace(&mut is_erased[*edge_index as usize], true));
+        Some(SyndromePattern::new_dynamic_weights(
+            syndrome_pattern.defect_vertices.clone(),
+            erasures,
+            syndrome_pattern.dynamic_weights.clone(),
+        ))
+    }
+}
+
+/// the connected components of the decoding graph; a component without virtual vertices must have an even number of
+/// defects, otherwise no perfect matching exists
+#[derive(Debug, Clone)]
+pub struct ConnectedComponents {
+    /// the component of each vertex, labeled by its smallest vertex index
+    pub vertex_components: Vec<VertexIndex>,
+    /// whether each component (indexed by its label) has a virtual vertex; only meaningful at the label indices
+    pub has_virtual: Vec<bool>,
+    /// whether every component has a virtual vertex, in which case any syndrome is feasible
+    pub all_have_virtual: bool,
+    /// the virtual vertices of the decoding graph in increasing order
+    pub virtual_vertices: Vec<VertexIndex>,
+}
+
+impl ConnectedComponents {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(initializer: &SolverInitializer) -> Self {
+        let vertex_num = initializer.vertex_num as usize;
+        let mut parents: Vec<usize> = (0..vertex_num).collect();
+        fn find(parents: &mut [usize], mut vertex: usize) -> usize {
+            while parents[vertex] != vertex {
+                parents[vertex] = parents[parents[vertex]];
+                vertex = parents[vertex];
+            }
+            vertex
+        }
+        for &(i, j, _) in initializer.weighted_edges.iter() {
+            let (root_i, root_j) = (find(&mut parents, i as usize), find(&mut parents, j as usize));
+            // always keep the smaller index as the root, so that the root is the label
+            parents[root_i.max(root_j)] = root_i.min(root_j);
+        }
+        let vertex_components: Vec<VertexIndex> = (0..vertex_num)
+            .map(|vertex| find(&mut parents, vertex) as VertexIndex)
+            .collect();
+        let mut has_virtual = vec![false; vertex_num];
+        for &vertex_index in initializer.virtual_vertices.iter() {
+            has_virtual[vertex_components[vertex_index as usize] as usize] = true;
+        }
+        let all_have_virtual =
+            (0..vertex_num).all(|vertex| vertex_components[vertex] as usize != vertex || has_virtual[vertex]);
+        let mut virtual_vertices = initializer.virtual_vertices.clone();
+        virtual_vertices.sort_unstable();
+        virtual_vertices.dedup();
+        Self {
+            vertex_components,
+            has_virtual,
+            all_have_virtual,
+            virtual_vertices,
+        }
+    }
+
+    /// an arbitrary virtual vertex to match a vertex whose component has no virtual vertex, or `None` if there is no
+    /// virtual vertex at all; the decoding graph gives no path, hence no weight, to any of them, so the choice is
+    /// arbitrary and the match is unweighted. The one closest by vertex index is picked, only to keep the choice
+    /// deterministic; a caller that knows the geometry should pick the vertex itself
+    pub fn fallback_virtual_by_index(&self, vertex_index: VertexIndex) -> Option<VertexIndex> {
+        let position = self
+            .virtual_vertices
+            .partition_point(|&virtual_vertex| virtual_vertex < vertex_index);
+        let before = position.checked_sub(1).map(|position| self.virtual_vertices[position]);
+        let after = self.virtual_vertices.get(position).cloned();
+        match (before, after) {
+            (Some(before), Some(after)) => Some(if vertex_index - before <= after - vertex_index {
+                before
+            } else {
+                after
+            }),
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// the components without virtual vertices that have an odd number of defects, as `(label, defects)`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn odd_components(&self, defect_vertices: &[VertexIndex]) -> Vec<(VertexIndex, Vec<VertexIndex>)> {
+        if self.all_have_virtual {
+            return vec![];
+        }
+        let mut component_defects = alloc::collections::BTreeMap::<VertexIndex, Vec<VertexIndex>>::new();
+        for &vertex_index in defect_vertices.iter() {
+            let component = self.vertex_components[vertex_index as usize];
+            if !self.has_virtual[component as usize] {
+                component_defects.entry(component).or_default().push(vertex_index);
+            }
+        }
+        component_defects
+            .into_iter()
+            .filter(|(_, defects)| defects.len() % 2 == 1)
+            .collect()
+    }
+}
+
+/// what the solver does when a component without virtual vertices has an odd number of defects, e.g. because of
+/// corrupted measurement data; the JSON form is `{"odd_parity": "error"}` etc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OddParityPolicy {
+    /// refuse to decode: `try_solve` returns an error naming the component, and `solve` panics
+    #[default]
+    Error,
+    /// drop the defect whose nearest other defect in the component is the farthest, i.e. the most isolated one
+    DropFarthest,
+    /// pair the closest defects of the component first; the one left over is removed from the syndrome and matched to
+    /// a virtual vertex outside the component. No path leads there, so the virtual vertex is an arbitrary one and the
+    /// match adds no weight, see [`ConnectedComponents::fallback_virtual_by_index`]; the match is reported in
+    /// [`OddParityRepair::matched_virtual`] and in the matched pairs of the solve result
+    MatchToNearestVirtualEvenIfFar,
+}
+
+/// a defect removed from the syndrome to restore the parity of a component, see [`OddParityPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OddParityRepair {
+    /// the component, labeled by its smallest vertex index
+    pub component: VertexIndex,
+    /// the number of defects in the component before the repair
+    pub defect_count: usize,
+    /// the removed defect
+    pub removed_defect: VertexIndex,
+    /// the policy that chose the defect
+    pub policy: OddParityPolicy,
+    /// the (arbitrary, unweighted) virtual vertex the removed defect is matched to with
+    /// [`OddParityPolicy::MatchToNearestVirtualEvenIfFar`], or `None` if the defect is dropped or the decoding graph has
+    /// no virtual vertex at all
+    pub matched_virtual: Option<VertexIndex>,
+}
+
+/// switch a shot with too many defects to a faster but approximate decoder, so that a burst of errors (e.g. a cosmic
+/// ray) gives a possibly poor correction quickly instead of stalling the decoder; the JSON form is
+/// `{"defect_count_fallback": {"threshold": 1000, "mode": "union_find"}}` in the config of [`crate::mwpm_solver::SolverSerial`],
+/// `SolverDualParallel` and `SolverParallel`, which all switch for the shot and restore their `max_tree_size`
+/// afterwards. Only the length of the defect list is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefectCountFallback {
+    /// the fallback applies to syndromes with more defects than this
+    pub threshold: usize,
+    /// the decoder to use instead
+    #[serde(default)]
+    pub mode: DefectCountFallbackMode,
+}
+
+/// the decoder used by [`DefectCountFallback`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefectCountFallbackMode {
+    /// the union-find decoder, i.e. the serial primal module with `max_tree_size = 0`
+    #[default]
+    UnionFind,
+}
+
+impl DefectCountFallback {
+    /// the `max_tree_size` of the primal module to decode this syndrome with, or `None` if the fallback doesn't apply
+    pub fn max_tree_size(&self, syndrome_pattern: &SyndromePattern) -> Option<usize> {
+        if syndrome_pattern.defect_vertices.len() <= self.threshold {
+            return None;
+        }
+        match self.mode {
+            DefectCountFallbackMode::UnionFind => Some(0),
+        }
+    }
 }
 
 pub fn default_dynamic_weights() -> Vec<(EdgeIndex, Weight)> {
@@ -110,6 +423,8 @@ impl SyndromePattern {
             defect_vertices,
             erasures,
             dynamic_weights: vec![],
+            erasure_regions: vec![],
+            uncertain_vertices: vec![],
         }
     }
     pub fn new_dynamic_weights(
@@ -121,11 +436,214 @@ impl SyndromePattern {
             defect_vertices,
             erasures,
             dynamic_weights,
+            erasure_regions: vec![],
+            uncertain_vertices: vec![],
+        }
+    }
+    /// construct with erasure regions, which are expanded into edges by the solver
+    pub fn new_erasure_regions(defect_vertices: Vec<VertexIndex>, erasure_regions: Vec<ErasureRegion>) -> Self {
+        Self {
+            defect_vertices,
+            erasures: vec![],
+            dynamic_weights: vec![],
+            erasure_regions,
+            uncertain_vertices: vec![],
+        }
+    }
+    /// construct with uncertain vertices, whose incident edges are erased by the solver
+    pub fn new_uncertain_vertices(defect_vertices: Vec<VertexIndex>, uncertain_vertices: Vec<VertexIndex>) -> Self {
+        Self {
+            defect_vertices,
+            erasures: vec![],
+            dynamic_weights: vec![],
+            erasure_regions: vec![],
+            uncertain_vertices,
+        }
+    }
+    /// construct from a dense per-edge erasure mask, e.g. heralded erasures from a simulator; the mask must have
+    /// exactly one entry per edge
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_erasure_mask(
+        defect_vertices: Vec<VertexIndex>,
+        erasure_mask: &[bool],
+        edge_num: usize,
+    ) -> Result<Self, String> {
+        if erasure_mask.len() != edge_num {
+            return Err(format!(
+                "erasure mask length {} doesn't match the number of edges {}",
+                erasure_mask.len(),
+                edge_num
+            ));
+        }
+        let erasures = erasure_mask
+            .iter()
+            .enumerate()
+            .filter(|(_, &is_erased)| is_erased)
+            .map(|(edge_index, _)| edge_index as EdgeIndex)
+            .collect();
+        Ok(Self::new(defect_vertices, erasures))
+    }
+    /// check that the indices fit in a decoding graph of `vertex_num` vertices and `edge_num` edges, that no defect is
+    /// repeated and that dynamic weights are even and non-negative, so that the solver doesn't panic on them
+    #[allow(clippy::unnecessary_cast)]
+    pub fn check_indices(&self, vertex_num: VertexNum, edge_num: usize) -> Result<(), String> {
+        let mut is_defect = vec![false; vertex_num as usize];
+        for &vertex_index in self.defect_vertices.iter() {
+            if vertex_index >= vertex_num {
+                return Err(format!(
+                    "defect vertex {vertex_index} out of range, there are {vertex_num} vertices"
+                ));
+            }
+            if is_defect[vertex_index as usize] {
+                return Err(format!("defect vertex {vertex_index} is repeated"));
+            }
+            is_defect[vertex_index as usize] = true;
+        }
+        for &edge_index in self.erasures.iter() {
+            if edge_index as usize >= edge_num {
+                return Err(format!("erasure edge {edge_index} out of range, there are {edge_num} edges"));
+            }
+        }
+        if !self.erasures.is_empty() && !self.dynamic_weights.is_empty() {
+            return Err("erasures and dynamic_weights cannot be provided at the same time".to_string());
+        }
+        for &(edge_index, weight) in self.dynamic_weights.iter() {
+            if edge_index as usize >= edge_num {
+                return Err(format!(
+                    "dynamic weight edge {edge_index} out of range, there are {edge_num} edges"
+                ));
+            }
+            if weight < 0 || weight % 2 != 0 {
+                return Err(format!(
+                    "dynamic weight {weight} of edge {edge_index} must be even and non-negative"
+                ));
+            }
+        }
+        for &vertex_index in self.uncertain_vertices.iter() {
+            if vertex_index >= vertex_num {
+                return Err(format!(
+                    "uncertain vertex {vertex_index} out of range, there are {vertex_num} vertices"
+                ));
+            }
+            if is_defect[vertex_index as usize] {
+                return Err(format!("uncertain vertex {vertex_index} cannot be a defect"));
+            }
+        }
+        for erasure_region in self.erasure_regions.iter() {
+            if let ErasureRegion::VertexRange(vertex_range) = erasure_region {
+                if vertex_range.end() > vertex_num {
+                    return Err(format!(
+                        "erasure region {vertex_range:?} out of range, there are {vertex_num} vertices"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// greedily shrink a syndrome pattern while `predicate` still holds, e.g. "the fusion solver and the ground truth disagree",
+/// yielding a minimal failing case that is much easier to debug than the original one; defects are removed one at a time
+/// and then in pairs (removing a single defect may break the parity of a graph without virtual vertices), followed by
+/// erasures, dynamic weights, erasure regions and uncertain vertices. The result is 1-minimal: removing any single element (or any pair of
+/// defects) no longer fails. The predicate is called on each candidate and treats the solvers as black boxes, so wrap
+/// the decoding in [`std::panic::catch_unwind`] if a panic should also count as a failure.
+pub fn minimize_failing_syndrome(
+    initializer: &SolverInitializer,
+    syndrome_pattern: &SyndromePattern,
+    mut predicate: impl FnMut(&SolverInitializer, &SyndromePattern) -> bool,
+) -> SyndromePattern {
+    assert!(
+        predicate(initializer, syndrome_pattern),
+        "the predicate must hold for the original syndrome pattern"
+    );
+    let mut minimized = syndrome_pattern.clone();
+    loop {
+        let mut changed = false;
+        // remove single defects
+        let mut i = 0;
+        while i < minimized.defect_vertices.len() {
+            let mut candidate = minimized.clone();
+            candidate.defect_vertices.remove(i);
+            if predicate(initializer, &candidate) {
+                minimized = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+        // remove pairs of defects
+        let mut i = 0;
+        while i < minimized.defect_vertices.len() {
+            let mut j = i + 1;
+            while j < minimized.defect_vertices.len() {
+                let mut candidate = minimized.clone();
+                candidate.defect_vertices.remove(j);
+                candidate.defect_vertices.remove(i);
+                if predicate(initializer, &candidate) {
+                    minimized = candidate;
+                    changed = true;
+                    j = i + 1;
+                } else {
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+        // remove erasures, dynamic weights, erasure regions and uncertain vertices
+        let mut i = 0;
+        while i < minimized.erasures.len() {
+            let mut candidate = minimized.clone();
+            candidate.erasures.remove(i);
+            if predicate(initializer, &candidate) {
+                minimized = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+        let mut i = 0;
+        while i < minimized.dynamic_weights.len() {
+            let mut candidate = minimized.clone();
+            candidate.dynamic_weights.remove(i);
+            if predicate(initializer, &candidate) {
+                minimized = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+        let mut i = 0;
+        while i < minimized.erasure_regions.len() {
+            let mut candidate = minimized.clone();
+            candidate.erasure_regions.remove(i);
+            if predicate(initializer, &candidate) {
+                minimized = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+        let mut i = 0;
+        while i < minimized.uncertain_vertices.len() {
+            let mut candidate = minimized.clone();
+            candidate.uncertain_vertices.remove(i);
+            if predicate(initializer, &candidate) {
+                minimized = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+        // removing an erasure may enable removing more defects
+        if !changed {
+            break;
         }
     }
+    minimized
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl SyndromePattern {
     #[cfg_attr(feature = "python_binding", new)]
@@ -158,11 +676,69 @@ impl SyndromePattern {
         Self::new(vec![], vec![])
     }
     #[cfg(feature = "python_binding")]
+    #[getter]
+    fn get_erasure_regions(&self) -> PyObject {
+        json_to_pyobject(serde_json::to_value(&self.erasure_regions).unwrap())
+    }
+    #[cfg(feature = "python_binding")]
+    #[setter]
+    fn set_erasure_regions(&mut self, erasure_regions: PyObject) -> PyResult<()> {
+        self.erasure_regions = serde_json::from_value(pyobject_to_json(erasure_regions))
+            .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))?;
+        Ok(())
+    }
+    #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
         format!("{:?}", self)
     }
 }
 
+/// sizes used to pre-reserve the growable structures of a solver, so that they don't reallocate in the middle of decoding;
+/// the same structure also reports the high-water marks observed in previous decodings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapacityHints {
+    /// the maximum number of defect vertices in a decoding problem
+    pub max_defects: usize,
+    /// the maximum number of blossoms created when solving a decoding problem
+    pub max_blossoms: usize,
+    /// the maximum number of boundary edges of a single dual node
+    pub max_boundary_per_node: usize,
+}
+
+impl CapacityHints {
+    pub fn new(max_defects: usize, max_blossoms: usize, max_boundary_per_node: usize) -> Self {
+        Self {
+            max_defects,
+            max_blossoms,
+            max_boundary_per_node,
+        }
+    }
+
+    /// the maximum number of dual nodes, including both defect vertices and blossoms
+    pub fn max_nodes(&self) -> usize {
+        self.max_defects + self.max_blossoms
+    }
+
+    /// scale the number of nodes by `ratio` (rounding up), used when a solver is split into units;
+    /// the boundary of a single node is not scaled
+    pub fn scaled(&self, ratio: f64) -> Self {
+        Self {
+            max_defects: (self.max_defects as f64 * ratio).ceil() as usize,
+            max_blossoms: (self.max_blossoms as f64 * ratio).ceil() as usize,
+            max_boundary_per_node: self.max_boundary_per_node,
+        }
+    }
+
+    /// element-wise maximum of the two
+    pub fn max(&self, other: &Self) -> Self {
+        Self {
+            max_defects: core::cmp::max(self.max_defects, other.max_defects),
+            max_blossoms: core::cmp::max(self.max_blossoms, other.max_blossoms),
+            max_boundary_per_node: core::cmp::max(self.max_boundary_per_node, other.max_boundary_per_node),
+        }
+    }
+}
+
 /// an efficient representation of partitioned vertices and erasures when they're ordered
 #[derive(Debug, Clone, Serialize)]
 pub struct PartitionedSyndromePattern<'a> {
@@ -187,9 +763,9 @@ impl<'a> PartitionedSyndromePattern<'a> {
     }
 }
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(transparent)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct IndexRange {
     pub range: [VertexNodeIndex; 2],
@@ -200,7 +776,7 @@ pub type VertexRange = IndexRange;
 pub type NodeRange = IndexRange;
 pub type DefectRange = IndexRange;
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl IndexRange {
     #[cfg_attr(feature = "python_binding", new)]
@@ -238,6 +814,25 @@ impl IndexRange {
     pub fn contains(&self, vertex_index: VertexNodeIndex) -> bool {
         vertex_index >= self.start() && vertex_index < self.end()
     }
+    /// whether `other` lies within this range; an empty range is contained if its position is within this range
+    pub fn contains_range(&self, other: &Self) -> bool {
+        other.start() >= self.start() && other.end() <= self.end()
+    }
+    /// whether the two ranges share any index; adjacent ranges like `[0, 3)` and `[3, 5)` don't intersect
+    pub fn intersects(&self, other: &Self) -> bool {
+        core::cmp::max(self.start(), other.start()) < core::cmp::min(self.end(), other.end())
+    }
+    /// the indices shared by the two ranges, or `None` if they don't intersect
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.intersects(other) {
+            Some(Self::new(
+                core::cmp::max(self.start(), other.start()),
+                core::cmp::min(self.end(), other.end()),
+            ))
+        } else {
+            None
+        }
+    }
     /// fuse two ranges together, returning (the whole range, the interfacing range)
     pub fn fuse(&self, other: &Self) -> (Self, Self) {
         self.sanity_check();
@@ -259,8 +854,24 @@ impl IndexRange {
     }
 }
 
+impl IntoIterator for IndexRange {
+    type Item = VertexNodeIndex;
+    type IntoIter = core::ops::Range<VertexNodeIndex>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &IndexRange {
+    type Item = VertexNodeIndex;
+    type IntoIter = core::ops::Range<VertexNodeIndex>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl IndexRange {
-    pub fn iter(&self) -> std::ops::Range<VertexNodeIndex> {
+    pub fn iter(&self) -> core::ops::Range<VertexNodeIndex> {
         self.range[0]..self.range[1]
     }
     pub fn contains_any(&self, vertex_indices: &[VertexNodeIndex]) -> bool {
@@ -285,8 +896,14 @@ pub struct PartitionUnit {
 pub type PartitionUnitPtr = ArcManualSafeLock<PartitionUnit>;
 pub type PartitionUnitWeak = WeakManualSafeLock<PartitionUnit>;
 
-impl std::fmt::Debug for PartitionUnitPtr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PointerTag for PartitionUnit {
+    fn pointer_tag(&self) -> String {
+        format!("PartitionUnit {}", self.unit_index)
+    }
+}
+
+impl core::fmt::Debug for PartitionUnitPtr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         let partition_unit = self.read_recursive();
         write!(
             f,
@@ -297,16 +914,55 @@ impl std::fmt::Debug for PartitionUnitPtr {
     }
 }
 
-impl std::fmt::Debug for PartitionUnitWeak {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for PartitionUnitWeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.upgrade_force().fmt(f)
     }
 }
 
+/// a problem found by [`PartitionConfig::validate`]; unit indices follow the same convention as [`PartitionInfo::units`],
+/// i.e. the partitions first and then the fusions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionIssue {
+    /// the config is built for a different decoding graph
+    VertexNumMismatch { config: VertexNum, initializer: VertexNum },
+    /// at least one partition must exist
+    NoPartition,
+    /// the range is reversed or exceeds `vertex_num`
+    InvalidRange { partition_index: usize, range: VertexRange },
+    /// a fusion must only depend on units created before it
+    InvalidFusionChild { fusion_index: usize, child: usize },
+    /// each unit can only be fused once
+    FusedTwice { unit_index: usize },
+    /// every unit except for the last one must be fused
+    NotFused { unit_index: usize },
+    /// the whole range of the left child must be lower than that of the right child
+    MisorderedFusion {
+        fusion_index: usize,
+        left: VertexRange,
+        right: VertexRange,
+    },
+    /// a vertex is owned by two units, e.g. overlapping partitions or a partition inside an interface
+    OverlappingUnits { unit_1: usize, unit_2: usize },
+    /// these vertices are not owned by any unit
+    NotCovered { range: VertexRange },
+    /// the edge connects to an invalid vertex or to itself
+    InvalidEdge { edge_index: EdgeIndex },
+    /// the edge connects two units that are not descendant of one another, e.g. an interface vertex is placed in the
+    /// wrong fusion unit so that it cannot be mirrored to both sides
+    CrossingEdge {
+        edge_index: EdgeIndex,
+        unit_1: usize,
+        unit_2: usize,
+    },
+    /// the virtual vertex doesn't exist or is listed twice, so it cannot be consistently mirrored
+    InvalidVirtualVertex { vertex_index: VertexIndex },
+}
+
 /// user input partition configuration
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PartitionConfig {
     /// the number of vertices
@@ -323,7 +979,7 @@ pub struct PartitionConfig {
 #[cfg(feature = "python_binding")]
 bind_trait_python_json! {PartitionConfig}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PartitionConfig {
     #[cfg_attr(feature = "python_binding", new)]
@@ -340,6 +996,65 @@ impl PartitionConfig {
         format!("{:?}", self)
     }
 
+    /// fuse the given partitions in their order, either sequentially or in trees of at most `maximum_tree_leaf_size` leaves;
+    /// the gap between neighboring partitions becomes the interface owned by their fusion unit
+    #[cfg(feature = "python_binding")]
+    #[staticmethod]
+    #[pyo3(signature = (vertex_num, partitions, *, enable_tree_fusion = false, maximum_tree_leaf_size = None))]
+    fn from_partitions(
+        vertex_num: VertexNum,
+        partitions: Vec<VertexRange>,
+        enable_tree_fusion: bool,
+        maximum_tree_leaf_size: Option<usize>,
+    ) -> Self {
+        let mut config = Self::new(vertex_num);
+        config.partitions = partitions;
+        crate::example_partition::build_time_partition_fusions(
+            &mut config,
+            enable_tree_fusion,
+            maximum_tree_leaf_size.unwrap_or(usize::MAX),
+        );
+        config
+    }
+
+    /// evenly cut `rounds` measurement rounds of `round_vertex_num` vertices each into `partition_num` blocks, the same as
+    /// the time partitions of the example codes
+    #[cfg(feature = "python_binding")]
+    #[staticmethod]
+    #[pyo3(signature = (round_vertex_num, rounds, partition_num, *, enable_tree_fusion = false, maximum_tree_leaf_size = None))]
+    fn time_partition(
+        round_vertex_num: VertexNum,
+        rounds: VertexNum,
+        partition_num: usize,
+        enable_tree_fusion: bool,
+        maximum_tree_leaf_size: Option<usize>,
+    ) -> Self {
+        crate::example_partition::time_partition_config(
+            round_vertex_num,
+            rounds,
+            partition_num,
+            enable_tree_fusion,
+            maximum_tree_leaf_size.unwrap_or(usize::MAX),
+        )
+    }
+
+    /// raise `ValueError` listing all the issues if the config doesn't fit the decoding graph
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "validate")]
+    fn validate_python(&self, initializer: &SolverInitializer) -> PyResult<()> {
+        self.validate(initializer)
+            .map_err(|issues| pyo3::exceptions::PyValueError::new_err(format!("invalid partition config: {:?}", issues)))
+    }
+
+    /// raise `ValueError` listing all the issues if the plan doesn't fuse the partitions into a single tree
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "with_fusion_plan")]
+    fn with_fusion_plan_python(&self, plan: Vec<(usize, usize)>) -> PyResult<Self> {
+        self.clone()
+            .with_fusion_plan(plan)
+            .map_err(|issues| pyo3::exceptions::PyValueError::new_err(format!("invalid fusion plan: {:?}", issues)))
+    }
+
     #[allow(clippy::unnecessary_cast)]
     pub fn info(&self) -> PartitionInfo {
         assert!(!self.partitions.is_empty(), "at least one partition must exist");
@@ -348,7 +1063,7 @@ impl PartitionConfig {
         for &partition in self.partitions.iter() {
             partition.sanity_check();
             assert!(
-                partition.end() <= self.vertex_num as VertexIndex,
+                VertexRange::new(0, self.vertex_num as VertexIndex).contains_range(&partition),
                 "invalid vertex index {} in partitions",
                 partition.end()
             );
@@ -439,8 +1154,181 @@ impl PartitionConfig {
     }
 }
 
+impl PartitionConfig {
+    /// check the partition against the decoding graph without panicking, so that a hand-written config can be
+    /// diagnosed before [`PartitionConfig::info`] or the parallel solvers panic on it; checks depending on the fusion
+    /// tree are skipped if the tree itself is broken
+    pub fn validate(&self, initializer: &SolverInitializer) -> Result<(), Vec<PartitionIssue>> {
+        self.validate_optional_graph(Some(initializer))
+    }
+
+    /// replace the fusions with a hand-written fusion plan, e.g. to experiment with fusion scheduling; the `i`-th pair
+    /// in the plan fuses two units created before it into the new unit `partitions.len() + i`, and the plan must fuse
+    /// all the partitions into a single tree covering all vertices. the parallel solvers execute a fusion once both of
+    /// its children finish, so the shape of the tree determines which fusions run last
+    pub fn with_fusion_plan(mut self, plan: Vec<(usize, usize)>) -> Result<Self, Vec<PartitionIssue>> {
+        self.fusions = plan;
+        self.validate_optional_graph(None)?;
+        Ok(self)
+    }
+
+    /// the checks of [`PartitionConfig::validate`], skipping those that depend on the decoding graph if it's not given
+    #[allow(clippy::unnecessary_cast)]
+    fn validate_optional_graph(&self, initializer: Option<&SolverInitializer>) -> Result<(), Vec<PartitionIssue>> {
+        let mut issues = vec![];
+        if let Some(initializer) = initializer {
+            if self.vertex_num != initializer.vertex_num {
+                issues.push(PartitionIssue::VertexNumMismatch {
+                    config: self.vertex_num,
+                    initializer: initializer.vertex_num,
+                });
+                return Err(issues);
+            }
+        }
+        if self.partitions.is_empty() {
+            issues.push(PartitionIssue::NoPartition);
+            return Err(issues);
+        }
+        let unit_count = self.partitions.len() + self.fusions.len();
+        let mut whole_ranges: Vec<Option<VertexRange>> = Vec::with_capacity(unit_count);
+        let mut owning_ranges: Vec<Option<VertexRange>> = Vec::with_capacity(unit_count);
+        for (partition_index, &range) in self.partitions.iter().enumerate() {
+            let is_valid =
+                range.start() <= range.end() && VertexRange::new(0, self.vertex_num as VertexIndex).contains_range(&range);
+            if !is_valid {
+                issues.push(PartitionIssue::InvalidRange { partition_index, range });
+            }
+            whole_ranges.push(if is_valid { Some(range) } else { None });
+            owning_ranges.push(if is_valid { Some(range) } else { None });
+        }
+        // fusion tree
+        let mut parents: Vec<Option<usize>> = vec![None; unit_count];
+        for (fusion_index, &(left_index, right_index)) in self.fusions.iter().enumerate() {
+            let unit_index = fusion_index + self.partitions.len();
+            let mut children_valid = true;
+            for child in [left_index, right_index] {
+                if child >= unit_index {
+                    issues.push(PartitionIssue::InvalidFusionChild { fusion_index, child });
+                    children_valid = false;
+                } else if parents[child].is_some() {
+                    issues.push(PartitionIssue::FusedTwice { unit_index: child });
+                    children_valid = false;
+                } else {
+                    parents[child] = Some(unit_index);
+                }
+            }
+            let fused = if children_valid {
+                match (whole_ranges[left_index], whole_ranges[right_index]) {
+                    (Some(left), Some(right)) => {
+                        if left.end() <= right.start() {
+                            Some(left.fuse(&right))
+                        } else {
+                            issues.push(PartitionIssue::MisorderedFusion {
+                                fusion_index,
+                                left,
+                                right,
+                            });
+                            None
+                        }
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            whole_ranges.push(fused.map(|(whole_range, _)| whole_range));
+            owning_ranges.push(fused.map(|(_, interface_range)| interface_range));
+        }
+        for (unit_index, parent) in parents.iter().enumerate().take(unit_count - 1) {
+            if parent.is_none() {
+                issues.push(PartitionIssue::NotFused { unit_index });
+            }
+        }
+        let tree_valid = issues.is_empty();
+        // vertex ownership
+        let mut vertex_to_owning_unit: Vec<Option<usize>> = vec![None; self.vertex_num as usize];
+        let mut overlapping_units = BTreeSet::new();
+        for (unit_index, owning_range) in owning_ranges.iter().enumerate() {
+            if let Some(owning_range) = owning_range {
+                for vertex_index in owning_range.iter() {
+                    match vertex_to_owning_unit[vertex_index as usize] {
+                        Some(other_unit_index) => {
+                            overlapping_units.insert((other_unit_index, unit_index));
+                        }
+                        None => vertex_to_owning_unit[vertex_index as usize] = Some(unit_index),
+                    }
+                }
+            }
+        }
+        for (unit_1, unit_2) in overlapping_units.into_iter() {
+            issues.push(PartitionIssue::OverlappingUnits { unit_1, unit_2 });
+        }
+        if !tree_valid {
+            return Err(issues);
+        }
+        let mut uncovered_start = None;
+        for vertex_index in 0..=self.vertex_num as VertexIndex {
+            let is_covered =
+                vertex_index == self.vertex_num as VertexIndex || vertex_to_owning_unit[vertex_index as usize].is_some();
+            match (uncovered_start, is_covered) {
+                (None, false) => uncovered_start = Some(vertex_index),
+                (Some(start), true) => {
+                    issues.push(PartitionIssue::NotCovered {
+                        range: VertexRange::new(start, vertex_index),
+                    });
+                    uncovered_start = None;
+                }
+                _ => {}
+            }
+        }
+        // edges and virtual vertices
+        let initializer = match initializer {
+            Some(initializer) => initializer,
+            None => return if issues.is_empty() { Ok(()) } else { Err(issues) },
+        };
+        let is_ancestor = |ancestor: usize, mut unit_index: usize| {
+            while let Some(parent) = parents[unit_index] {
+                if parent == ancestor {
+                    return true;
+                }
+                unit_index = parent;
+            }
+            false
+        };
+        for (edge_index, &(i, j, _)) in initializer.weighted_edges.iter().enumerate() {
+            let edge_index = edge_index as EdgeIndex;
+            if i == j || i >= self.vertex_num || j >= self.vertex_num {
+                issues.push(PartitionIssue::InvalidEdge { edge_index });
+                continue;
+            }
+            if let (Some(unit_1), Some(unit_2)) = (vertex_to_owning_unit[i as usize], vertex_to_owning_unit[j as usize]) {
+                if unit_1 != unit_2 && !is_ancestor(unit_1, unit_2) && !is_ancestor(unit_2, unit_1) {
+                    issues.push(PartitionIssue::CrossingEdge {
+                        edge_index,
+                        unit_1,
+                        unit_2,
+                    });
+                }
+            }
+        }
+        let mut is_virtual = vec![false; self.vertex_num as usize];
+        for &vertex_index in initializer.virtual_vertices.iter() {
+            if vertex_index >= self.vertex_num || is_virtual[vertex_index as usize] {
+                issues.push(PartitionIssue::InvalidVirtualVertex { vertex_index });
+            } else {
+                is_virtual[vertex_index as usize] = true;
+            }
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PartitionInfo {
     /// the initial configuration that creates this info
@@ -460,6 +1348,12 @@ bind_trait_python_json! {PartitionInfo}
 
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PartitionInfo {
+    /// the fusion pairs in the order they were given, see [`PartitionConfig::with_fusion_plan`]; the `i`-th pair creates
+    /// the unit `partitions.len() + i`
+    pub fn fusion_plan(&self) -> Vec<(usize, usize)> {
+        self.config.fusions.clone()
+    }
+
     /// split a sequence of syndrome into multiple parts, each corresponds to a unit;
     /// this is a slow method and should only be used when the syndrome pattern is not well-ordered
     #[allow(clippy::unnecessary_cast)]
@@ -473,6 +1367,11 @@ impl PartitionInfo {
         partitioned_syndrome
     }
 
+    /// the fraction of vertices owned by a unit, used to split the capacity hints proportionally
+    pub fn owning_ratio(&self, unit_index: usize) -> f64 {
+        self.units[unit_index].owning_range.len() as f64 / self.vertex_to_owning_unit.len() as f64
+    }
+
     #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
         format!("{:?}", self)
@@ -541,8 +1440,8 @@ impl<'a> PartitionedSyndromePattern<'a> {
     }
 }
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct PartitionUnitInfo {
     /// the whole range of units
@@ -623,7 +1522,7 @@ pub fn translated_defect_to_reordered(
         .collect()
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl SolverInitializer {
     #[cfg_attr(feature = "python_binding", new)]
@@ -636,17 +1535,191 @@ impl SolverInitializer {
             vertex_num,
             weighted_edges,
             virtual_vertices,
+            edge_rounds: None,
         }
     }
     #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
         format!("{:?}", self)
     }
+    /// a hash of the decoding graph structure that is stable across runs, platforms and index widths, used to check
+    /// that cached artifacts (e.g. syndrome pattern files) are loaded against the graph they were generated for;
+    /// it covers the vertex number, the set of virtual vertices and the set of weighted edges, but not the order of
+    /// edges, so two initializers with the same hash may still assign different edge indices
+    #[allow(clippy::unnecessary_cast)]
+    pub fn structural_hash(&self) -> u64 {
+        // FNV-1a 64-bit over little-endian u64 words, which does not depend on the Rust version like `DefaultHasher`
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+        feed(self.vertex_num as u64);
+        let mut virtual_vertices = self.virtual_vertices.clone();
+        virtual_vertices.sort_unstable();
+        virtual_vertices.dedup();
+        feed(virtual_vertices.len() as u64);
+        for &vertex_index in virtual_vertices.iter() {
+            feed(vertex_index as u64);
+        }
+        let mut weighted_edges: Vec<_> = self
+            .weighted_edges
+            .iter()
+            .map(|&(left, right, weight)| (left.min(right), left.max(right), weight))
+            .collect();
+        weighted_edges.sort_unstable();
+        feed(weighted_edges.len() as u64);
+        for &(left, right, weight) in weighted_edges.iter() {
+            feed(left as u64);
+            feed(right as u64);
+            feed(weight as i64 as u64);
+        }
+        hash
+    }
 }
 
-impl SolverInitializer {
-    #[allow(clippy::unnecessary_cast)]
-    pub fn syndrome_of(&self, subgraph: &[EdgeIndex]) -> BTreeSet<VertexIndex> {
+/// the difference between two decoding graphs over the same vertices, e.g. derived from calibrations on different days;
+/// edges are identified by their (sorted) endpoint pair, so reordering edges or swapping their endpoints is not a change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InitializerDiff {
+    /// the vertex number of both graphs, if they differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex_num_changed: Option<(VertexNum, VertexNum)>,
+    /// edges `(left, right, weight)` only present in the other graph
+    pub added_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    /// edges `(left, right, weight)` only present in this graph
+    pub removed_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    /// edges `(left, right, weight, other_weight)` present in both graphs with different weights
+    pub weight_changes: Vec<(VertexIndex, VertexIndex, Weight, Weight)>,
+    /// the maximum absolute weight change among `weight_changes`
+    pub max_weight_change: Weight,
+    /// the mean absolute weight change among `weight_changes`
+    pub mean_weight_change: f64,
+    /// the root mean square of the weight changes among `weight_changes`
+    pub rms_weight_change: f64,
+    pub added_virtual_vertices: Vec<VertexIndex>,
+    pub removed_virtual_vertices: Vec<VertexIndex>,
+}
+
+impl InitializerDiff {
+    pub fn is_empty(&self) -> bool {
+        self.vertex_num_changed.is_none()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.weight_changes.is_empty()
+            && self.added_virtual_vertices.is_empty()
+            && self.removed_virtual_vertices.is_empty()
+    }
+}
+
+impl SolverInitializer {
+    /// the edges keyed by their sorted endpoint pair; among parallel edges only the lightest one is kept, because it's
+    /// the only one that a minimum-weight matching would ever use
+    fn edges_by_endpoints(&self) -> BTreeMap<(VertexIndex, VertexIndex), Weight> {
+        let mut edges = BTreeMap::new();
+        for &(left, right, weight) in self.weighted_edges.iter() {
+            let entry = edges.entry((left.min(right), left.max(right))).or_insert(weight);
+            *entry = (*entry).min(weight);
+        }
+        edges
+    }
+
+    /// compare this decoding graph with `other`, see [`InitializerDiff`]; two graphs with the same
+    /// [`SolverInitializer::structural_hash`] are reported as identical without comparing edge by edge
+    pub fn diff(&self, other: &Self) -> InitializerDiff {
+        let mut diff = InitializerDiff::default();
+        if self.structural_hash() == other.structural_hash() {
+            return diff;
+        }
+        if self.vertex_num != other.vertex_num {
+            diff.vertex_num_changed = Some((self.vertex_num, other.vertex_num));
+        }
+        let edges = self.edges_by_endpoints();
+        let other_edges = other.edges_by_endpoints();
+        for (&(left, right), &weight) in edges.iter() {
+            match other_edges.get(&(left, right)) {
+                Some(&other_weight) if other_weight != weight => {
+                    diff.weight_changes.push((left, right, weight, other_weight));
+                }
+                Some(_) => {}
+                None => diff.removed_edges.push((left, right, weight)),
+            }
+        }
+        for (&(left, right), &other_weight) in other_edges.iter() {
+            if !edges.contains_key(&(left, right)) {
+                diff.added_edges.push((left, right, other_weight));
+            }
+        }
+        if !diff.weight_changes.is_empty() {
+            let changes: Vec<f64> = diff
+                .weight_changes
+                .iter()
+                .map(|&(_, _, weight, other_weight)| (other_weight - weight).abs() as f64)
+                .collect();
+            diff.max_weight_change = diff
+                .weight_changes
+                .iter()
+                .map(|&(_, _, weight, other_weight)| (other_weight - weight).abs())
+                .max()
+                .unwrap();
+            diff.mean_weight_change = changes.iter().sum::<f64>() / changes.len() as f64;
+            diff.rms_weight_change =
+                (changes.iter().map(|change| change * change).sum::<f64>() / changes.len() as f64).sqrt();
+        }
+        let virtual_vertices: BTreeSet<VertexIndex> = self.virtual_vertices.iter().cloned().collect();
+        let other_virtual_vertices: BTreeSet<VertexIndex> = other.virtual_vertices.iter().cloned().collect();
+        diff.added_virtual_vertices = other_virtual_vertices.difference(&virtual_vertices).cloned().collect();
+        diff.removed_virtual_vertices = virtual_vertices.difference(&other_virtual_vertices).cloned().collect();
+        diff
+    }
+
+    /// estimate the impact of replacing this decoding graph with `other`: decode every syndrome under both graphs and
+    /// count the syndromes whose correction changed, comparing corrections as sets of endpoint pairs because edge
+    /// indices are not comparable between the graphs; the syndromes can only be decoded on both graphs if they have the
+    /// same number of vertices, otherwise the reason is returned as an error
+    #[allow(clippy::unnecessary_cast)]
+    pub fn diff_impact(&self, other: &Self, syndrome_patterns: &[SyndromePattern]) -> Result<usize, String> {
+        if self.vertex_num != other.vertex_num {
+            return Err(format!(
+                "the graphs have different numbers of vertices ({} and {}), syndromes cannot be decoded on both",
+                self.vertex_num, other.vertex_num
+            ));
+        }
+        if self.structural_hash() == other.structural_hash() {
+            return Ok(0);
+        }
+        let correction_of =
+            |initializer: &SolverInitializer, solver: &mut SolverSerial, syndrome_pattern: &SyndromePattern| {
+                solver.clear();
+                solver.solve(syndrome_pattern);
+                let correction: BTreeSet<(VertexIndex, VertexIndex)> = solver
+                    .subgraph()
+                    .into_iter()
+                    .map(|edge_index| {
+                        let (left, right, _) = initializer.weighted_edges[edge_index as usize];
+                        (left.min(right), left.max(right))
+                    })
+                    .collect();
+                correction
+            };
+        let mut solver = SolverSerial::new(self);
+        let mut other_solver = SolverSerial::new(other);
+        let changed = syndrome_patterns
+            .iter()
+            .filter(|syndrome_pattern| {
+                correction_of(self, &mut solver, syndrome_pattern)
+                    != correction_of(other, &mut other_solver, syndrome_pattern)
+            })
+            .count();
+        Ok(changed)
+    }
+}
+
+impl SolverInitializer {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn syndrome_of(&self, subgraph: &[EdgeIndex]) -> BTreeSet<VertexIndex> {
         let mut defects = BTreeSet::new();
         for edge_index in subgraph {
             let (left, right, _weight) = self.weighted_edges[*edge_index as usize];
@@ -664,6 +1737,746 @@ impl SolverInitializer {
         }
         defects
     }
+
+    /// the minimum weight of a nontrivial logical operator, i.e. the effective code distance in weight units, useful to
+    /// catch decoding graphs built with wrong weights; each logical is given as the two sets of (virtual) vertices
+    /// on the opposite boundaries, and the distance is the shortest path between them minimized over all logicals.
+    /// returns `Weight::MAX` if no boundaries are connected
+    pub fn effective_distance(&self, logical_boundaries: &[(Vec<VertexIndex>, Vec<VertexIndex>)]) -> Weight {
+        let mut complete_graph = CompleteGraph::from_initializer(self);
+        let mut distance = Weight::MAX;
+        for (boundary_1, boundary_2) in logical_boundaries.iter() {
+            // run Dijkstra from the smaller side
+            let (sources, targets) = if boundary_1.len() <= boundary_2.len() {
+                (boundary_1, boundary_2)
+            } else {
+                (boundary_2, boundary_1)
+            };
+            let targets: BTreeSet<VertexIndex> = targets.iter().copied().collect();
+            for &source in sources.iter() {
+                if targets.contains(&source) {
+                    return 0;
+                }
+                let edges = complete_graph.all_edges(source);
+                for target in targets.iter() {
+                    if let Some(&(_, weight)) = edges.get(target) {
+                        distance = core::cmp::min(distance, weight);
+                    }
+                }
+            }
+        }
+        distance
+    }
+
+    /// a fast upper bound of the minimum-weight perfect matching of `defect_vertices`, computed by greedily taking the
+    /// cheapest defect pairs and nearest virtual vertices (nearest neighbor heuristic); the bound is also never larger
+    /// than matching every defect to its nearest virtual vertex. Returns [`Weight::MAX`] if the greedy assignment
+    /// cannot match all the defects, e.g. an odd number of defects without any reachable virtual vertex.
+    /// Note that erasures and other dynamic weights are not considered: the bound is for the static decoding graph.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn greedy_weight_bound(&self, defect_vertices: &[VertexIndex]) -> Weight {
+        let mut complete_graph = CompleteGraph::from_initializer(self);
+        let defect_num = defect_vertices.len();
+        // candidates `(weight, i, Some(j))` for defect pairs and `(weight, i, None)` for the nearest virtual vertex
+        let mut candidates: Vec<(Weight, usize, Option<usize>)> = vec![];
+        let mut boundary_sum: Option<Weight> = Some(0);
+        for i in 0..defect_num {
+            let edges = complete_graph.all_edges(defect_vertices[i]);
+            for j in (i + 1)..defect_num {
+                if defect_vertices[j] == defect_vertices[i] {
+                    candidates.push((0, i, Some(j)));
+                } else if let Some(&(_, weight)) = edges.get(&defect_vertices[j]) {
+                    candidates.push((weight, i, Some(j)));
+                }
+            }
+            let nearest_virtual = edges
+                .iter()
+                .filter(|(peer, _)| complete_graph.is_virtual[**peer as usize])
+                .map(|(_, &(_, weight))| weight)
+                .min();
+            match nearest_virtual {
+                Some(weight) => {
+                    candidates.push((weight, i, None));
+                    boundary_sum = boundary_sum.map(|sum| sum + weight);
+                }
+                None => boundary_sum = None,
+            }
+        }
+        candidates.sort_unstable();
+        let mut matched = vec![false; defect_num];
+        let mut matched_num = 0;
+        let mut greedy_sum: Weight = 0;
+        for (weight, i, j) in candidates {
+            if matched[i] {
+                continue;
+            }
+            match j {
+                Some(j) if !matched[j] => {
+                    matched[j] = true;
+                    matched_num += 2;
+                }
+                Some(_) => continue,
+                None => matched_num += 1,
+            }
+            matched[i] = true;
+            greedy_sum += weight;
+        }
+        let greedy_sum = if matched_num == defect_num { Some(greedy_sum) } else { None };
+        match (greedy_sum, boundary_sum) {
+            (Some(a), Some(b)) => core::cmp::min(a, b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => Weight::MAX,
+        }
+    }
+
+    /// the same decoding graph with the lexicographic objective (weight, then number of edges): among all the
+    /// minimum-weight corrections, decoding the returned graph gives one with the fewest edges, i.e. the shortest paths,
+    /// which is easier to interpret in visualization. Every weight `w` becomes `w * (edge_num + 1) + 2`; since the
+    /// original weights are even, any difference in the original total weight dominates the tie-breaking term, which
+    /// is at most `2 * edge_num`. The subgraph should be evaluated with the original weights, e.g. by summing
+    /// `self.weighted_edges`. Erasures set the transformed weight to 0, so erased edges are not counted.
+    /// Returns an error if the transformed weights overflow [`Weight`].
+    #[allow(clippy::unnecessary_cast)]
+    pub fn with_edge_count_tie_break(&self) -> Result<Self, String> {
+        let scale = self.weighted_edges.len() as Weight + 1;
+        let weighted_edges = self
+            .weighted_edges
+            .iter()
+            .map(|&(left, right, weight)| {
+                weight
+                    .checked_mul(scale)
+                    .and_then(|weight| weight.checked_add(2))
+                    .map(|weight| (left, right, weight))
+                    .ok_or_else(|| format!("weight {weight} overflows when scaled by {scale} for the tie-breaking"))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            weighted_edges,
+            ..self.clone()
+        })
+    }
+
+    /// build the decoding graph from real-valued weights that are only meaningful up to a common offset, e.g. affine
+    /// transformed log-likelihood ratios: every weight is shifted by `offset` and then quantized so that the largest
+    /// shifted weight becomes `2 * scale`. Returns an error if any shifted weight is negative or not finite.
+    ///
+    /// Shifting all edges by the same constant `c` changes the weight of a correction by `c` times its number of edges,
+    /// so the minimum-weight correction is preserved only if all the candidate corrections have the same number of
+    /// edges. This holds when every edge connects two possible defects without any virtual vertex and the syndrome is
+    /// decoded as a perfect matching of single edges (e.g. a complete graph of defects), where every matching has
+    /// exactly half as many edges as defects. In general decoding graphs a positive offset penalizes long paths and
+    /// boundary matchings, so the offset should only be used to correct the convention of the upstream weights
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_offset_weights(
+        vertex_num: VertexNum,
+        weighted_edges: Vec<(VertexIndex, VertexIndex, f64)>,
+        virtual_vertices: Vec<VertexIndex>,
+        offset: f64,
+        scale: Weight,
+    ) -> Result<Self, String> {
+        if scale <= 0 {
+            return Err(format!("scale must be positive, got {scale}"));
+        }
+        let mut maximum_weight: f64 = 0.;
+        for (edge_index, &(left, right, weight)) in weighted_edges.iter().enumerate() {
+            let shifted_weight = weight + offset;
+            if !shifted_weight.is_finite() || shifted_weight < 0. {
+                return Err(format!(
+                    "edge {edge_index} ({left}, {right}) has weight {weight}, which is {shifted_weight} after shifting by {offset}"
+                ));
+            }
+            maximum_weight = maximum_weight.max(shifted_weight);
+        }
+        let weighted_edges = weighted_edges
+            .into_iter()
+            .map(|(left, right, weight)| {
+                let half_weight = if maximum_weight == 0. {
+                    0
+                } else {
+                    ((weight + offset) / maximum_weight * scale as f64).round() as Weight
+                };
+                // weights must be even for the serial dual module
+                (left, right, 2 * half_weight)
+            })
+            .collect();
+        Ok(Self::new(vertex_num, weighted_edges, virtual_vertices))
+    }
+}
+
+/// the smallest probability distinguished by [`weight_of_p`]: it maps to the maximum weight `2 * max_half_weight`,
+/// and any smaller probability (including 0) is clamped to the same weight
+pub const MIN_WEIGHT_P: f64 = 1e-10;
+
+/// the log-likelihood ratio `ln((1 - p) / p)` of an error with probability `p`, clamped at [`MIN_WEIGHT_P`]
+fn clamped_log_likelihood_ratio(p: f64) -> f64 {
+    assert!(
+        (0. ..=0.5).contains(&p),
+        "p must be a reasonable value between 0 and 50%, got {p}"
+    );
+    let p = p.max(MIN_WEIGHT_P);
+    ((1. - p) / p).ln()
+}
+
+/// convert the probability of an edge to an integer weight with the conventions shared by all the integrations:
+/// the weight is proportional to the log-likelihood ratio `ln((1 - p) / p)` and always even (`2 * half_weight`);
+/// `p = 0.5` gives weight 0, and the weight increases monotonically as `p` decreases until it's clamped to
+/// `2 * max_half_weight` at [`MIN_WEIGHT_P`]. See [`normalized_weights_of_p`] for weights relative to the most
+/// unlikely edge instead of this fixed scale
+#[allow(clippy::unnecessary_cast)]
+#[cfg_attr(feature = "python_binding", pyfunction)]
+pub fn weight_of_p(p: f64, max_half_weight: Weight) -> Weight {
+    let ratio = clamped_log_likelihood_ratio(p) / clamped_log_likelihood_ratio(0.);
+    2 * ((max_half_weight as f64) * ratio).round() as Weight
+}
+
+/// the probability of an edge of `weight`, the inverse of [`weight_of_p`] up to the quantization error; the weight
+/// is clamped between 0 and `2 * max_half_weight`
+#[allow(clippy::unnecessary_cast)]
+#[cfg_attr(feature = "python_binding", pyfunction)]
+pub fn p_of_weight(weight: Weight, max_half_weight: Weight) -> f64 {
+    assert!(max_half_weight > 0, "max_half_weight must be positive");
+    let half_weight = (weight as f64 / 2.).clamp(0., max_half_weight as f64);
+    let log_likelihood_ratio = half_weight / max_half_weight as f64 * clamped_log_likelihood_ratio(0.);
+    1. / (1. + log_likelihood_ratio.exp())
+}
+
+/// [`weight_of_p`] of each probability; decoding graphs usually have only a few distinct probabilities,
+/// so the logarithm is computed once per distinct value
+pub fn weights_of_p(probabilities: &[f64], max_half_weight: Weight) -> Vec<Weight> {
+    let mut cache: HashMap<u64, Weight> = HashMap::new();
+    // consecutive edges usually share the same probability, which is cheaper to check than a hash lookup
+    let mut last: Option<(u64, Weight)> = None;
+    probabilities
+        .iter()
+        .map(|&p| match last {
+            Some((bits, weight)) if bits == p.to_bits() => weight,
+            _ => {
+                let weight = *cache.entry(p.to_bits()).or_insert_with(|| weight_of_p(p, max_half_weight));
+                last = Some((p.to_bits(), weight));
+                weight
+            }
+        })
+        .collect()
+}
+
+/// [`p_of_weight`] of each weight
+pub fn p_of_weights(weights: &[Weight], max_half_weight: Weight) -> Vec<f64> {
+    weights.iter().map(|&weight| p_of_weight(weight, max_half_weight)).collect()
+}
+
+/// the inverse of [`normalized_weights_of_p`] up to the quantization error, which is how the example codes (and thus
+/// the error pattern files they record) compute their weights: the largest weight belongs to the most unlikely
+/// probability `max_weight_p` and the other log-likelihood ratios are proportional to their weights
+#[allow(clippy::unnecessary_cast)]
+pub fn p_of_normalized_weights(weights: &[Weight], max_weight_p: f64) -> Vec<f64> {
+    let max_weight = weights.iter().cloned().max().unwrap_or(0);
+    let max_log_likelihood_ratio = clamped_log_likelihood_ratio(max_weight_p);
+    weights
+        .iter()
+        .map(|&weight| {
+            if max_weight <= 0 {
+                return 0.5;
+            }
+            let log_likelihood_ratio = weight.max(0) as f64 / max_weight as f64 * max_log_likelihood_ratio;
+            1. / (1. + log_likelihood_ratio.exp())
+        })
+        .collect()
+}
+
+/// the weights of the probabilities scaled so that the most unlikely one has weight `2 * max_half_weight`, which
+/// makes the best use of the integer range of a single decoding graph; this is how the example codes compute their
+/// weights. Like [`weight_of_p`] the weights are even and `p = 0.5` gives 0, but any other probability gets at
+/// least weight 2 so that only the real coin flips become free
+#[allow(clippy::unnecessary_cast)]
+pub fn normalized_weights_of_p(probabilities: &[f64], max_half_weight: Weight) -> Vec<Weight> {
+    let mut cache: HashMap<u64, f64> = HashMap::new();
+    // consecutive edges usually share the same probability, which is cheaper to check than a hash lookup
+    let mut last: Option<(u64, f64)> = None;
+    let log_likelihood_ratios: Vec<f64> = probabilities
+        .iter()
+        .map(|&p| match last {
+            Some((bits, ratio)) if bits == p.to_bits() => ratio,
+            _ => {
+                let ratio = *cache.entry(p.to_bits()).or_insert_with(|| clamped_log_likelihood_ratio(p));
+                last = Some((p.to_bits(), ratio));
+                ratio
+            }
+        })
+        .collect();
+    let max_log_likelihood_ratio = log_likelihood_ratios.iter().fold(0., |max: f64, ratio| max.max(*ratio));
+    log_likelihood_ratios
+        .iter()
+        .map(|&ratio| {
+            if ratio == 0. {
+                return 0;
+            }
+            let half_weight = ((max_half_weight as f64) * ratio / max_log_likelihood_ratio).round() as Weight;
+            2 * core::cmp::max(half_weight, 1)
+        })
+        .collect()
+}
+
+/// where a part of [`SolverInitializer::concat`] is placed in the combined decoding graph, to translate the syndromes
+/// and the results between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexOffsets {
+    /// the index of the first vertex of the part in the combined graph
+    pub vertex_offset: VertexIndex,
+    /// the number of vertices of the part
+    pub vertex_num: VertexNum,
+    /// the index of the first edge of the part in the combined graph
+    pub edge_offset: EdgeIndex,
+    /// the number of edges of the part
+    pub edge_num: usize,
+}
+
+impl IndexOffsets {
+    /// the index of a vertex of the part in the combined graph
+    pub fn vertex(&self, vertex_index: VertexIndex) -> VertexIndex {
+        debug_assert!(vertex_index < self.vertex_num, "vertex {vertex_index} out of range");
+        self.vertex_offset + vertex_index
+    }
+
+    /// the index of an edge of the part in the combined graph
+    #[allow(clippy::unnecessary_cast)]
+    pub fn edge(&self, edge_index: EdgeIndex) -> EdgeIndex {
+        debug_assert!((edge_index as usize) < self.edge_num, "edge {edge_index} out of range");
+        self.edge_offset + edge_index
+    }
+
+    /// the index of a combined vertex in the part, or `None` if it belongs to another part
+    pub fn local_vertex(&self, vertex_index: VertexIndex) -> Option<VertexIndex> {
+        (vertex_index >= self.vertex_offset && vertex_index - self.vertex_offset < self.vertex_num)
+            .then(|| vertex_index - self.vertex_offset)
+    }
+
+    /// the index of a combined edge in the part, or `None` if it belongs to another part
+    #[allow(clippy::unnecessary_cast)]
+    pub fn local_edge(&self, edge_index: EdgeIndex) -> Option<EdgeIndex> {
+        (edge_index >= self.edge_offset && ((edge_index - self.edge_offset) as usize) < self.edge_num)
+            .then(|| edge_index - self.edge_offset)
+    }
+
+    /// the edges of a combined subgraph (e.g. [`crate::mwpm_solver::PrimalDualSolver::subgraph`]) that are in the
+    /// part, in the indices of the part
+    pub fn local_subgraph(&self, subgraph: &[EdgeIndex]) -> Vec<EdgeIndex> {
+        subgraph
+            .iter()
+            .filter_map(|&edge_index| self.local_edge(edge_index))
+            .collect()
+    }
+
+    /// the syndrome of the part in the indices of the combined graph; [`ErasureRegion::TimeRound`] is not supported
+    /// because the rounds are shared by all parts, expand it with
+    /// [`crate::primal_module::SubGraphBuilder::expand_erasure_regions`] first
+    pub fn syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> SyndromePattern {
+        SyndromePattern {
+            defect_vertices: syndrome_pattern.defect_vertices.iter().map(|&v| self.vertex(v)).collect(),
+            erasures: syndrome_pattern.erasures.iter().map(|&e| self.edge(e)).collect(),
+            dynamic_weights: syndrome_pattern
+                .dynamic_weights
+                .iter()
+                .map(|&(edge_index, weight)| (self.edge(edge_index), weight))
+                .collect(),
+            erasure_regions: syndrome_pattern
+                .erasure_regions
+                .iter()
+                .map(|erasure_region| match erasure_region {
+                    ErasureRegion::TimeRound(_) => panic!("time round erasure regions cannot be translated to a part"),
+                    ErasureRegion::VertexRange(range) => ErasureRegion::VertexRange(VertexRange::new(
+                        self.vertex(range.start()),
+                        self.vertex_offset + range.end(),
+                    )),
+                })
+                .collect(),
+            uncertain_vertices: syndrome_pattern.uncertain_vertices.iter().map(|&v| self.vertex(v)).collect(),
+        }
+    }
+
+    /// the syndrome of the combined graph given the syndrome of each part
+    pub fn concat_syndrome_patterns(offsets: &[IndexOffsets], syndrome_patterns: &[SyndromePattern]) -> SyndromePattern {
+        assert_eq!(offsets.len(), syndrome_patterns.len(), "one syndrome pattern per part");
+        let mut combined = SyndromePattern::new_empty();
+        for (offsets, syndrome_pattern) in offsets.iter().zip(syndrome_patterns.iter()) {
+            let part = offsets.syndrome_pattern(syndrome_pattern);
+            combined.defect_vertices.extend(part.defect_vertices);
+            combined.erasures.extend(part.erasures);
+            combined.dynamic_weights.extend(part.dynamic_weights);
+            combined.erasure_regions.extend(part.erasure_regions);
+            combined.uncertain_vertices.extend(part.uncertain_vertices);
+        }
+        combined
+    }
+}
+
+/// a vertex of one of the decoding graphs joined by [`SolverInitializer::new_parity_linked`], as `(patch, vertex)`
+pub type PatchVertex = (usize, VertexIndex);
+
+/// several decoding graphs (patches) joined into a single one, see [`SolverInitializer::new_parity_linked`]
+#[derive(Debug, Clone)]
+pub struct ParityLinkedInitializer {
+    /// the joined decoding graph
+    pub initializer: SolverInitializer,
+    /// the index of the first vertex of each patch in the joined graph
+    pub vertex_offsets: Vec<VertexIndex>,
+    /// the index of the first edge of each patch in the joined graph; the gadget edges come after all patch edges
+    pub edge_offsets: Vec<EdgeIndex>,
+    /// the zero-weight gadget edges that link the boundaries
+    pub gadget_edges: Vec<EdgeIndex>,
+}
+
+impl ParityLinkedInitializer {
+    /// the index of a patch vertex in the joined graph
+    pub fn vertex(&self, (patch, vertex_index): PatchVertex) -> VertexIndex {
+        self.vertex_offsets[patch] + vertex_index
+    }
+
+    /// the syndrome of the joined graph given the defect vertices of each patch
+    pub fn syndrome_pattern(&self, patch_defect_vertices: &[Vec<VertexIndex>]) -> SyndromePattern {
+        assert_eq!(
+            patch_defect_vertices.len(),
+            self.vertex_offsets.len(),
+            "one defect list per patch"
+        );
+        let defect_vertices = patch_defect_vertices
+            .iter()
+            .enumerate()
+            .flat_map(|(patch, defect_vertices)| defect_vertices.iter().map(move |&vertex_index| (patch, vertex_index)))
+            .map(|patch_vertex| self.vertex(patch_vertex))
+            .collect();
+        SyndromePattern::new_vertices(defect_vertices)
+    }
+}
+
+impl SolverInitializer {
+    /// concatenate independent decoding graphs into one, e.g. to decode many small code patches with a single reusable
+    /// solver; the vertices and the edges of each part follow those of the previous parts, as given by the returned
+    /// [`IndexOffsets`]. Since the parts share no edges, the matching of the combined graph decomposes into the
+    /// matchings of each part. The edge rounds are kept only if every part has them
+    #[allow(clippy::unnecessary_cast)]
+    pub fn concat(parts: &[SolverInitializer]) -> (SolverInitializer, Vec<IndexOffsets>) {
+        let mut offsets = Vec::with_capacity(parts.len());
+        let mut vertex_num: VertexNum = 0;
+        let mut weighted_edges = Vec::with_capacity(parts.iter().map(|part| part.weighted_edges.len()).sum());
+        let mut virtual_vertices = vec![];
+        for part in parts.iter() {
+            offsets.push(IndexOffsets {
+                vertex_offset: vertex_num as VertexIndex,
+                vertex_num: part.vertex_num,
+                edge_offset: weighted_edges.len() as EdgeIndex,
+                edge_num: part.weighted_edges.len(),
+            });
+            weighted_edges.extend(
+                part.weighted_edges
+                    .iter()
+                    .map(|&(left, right, weight)| (left + vertex_num, right + vertex_num, weight)),
+            );
+            virtual_vertices.extend(part.virtual_vertices.iter().map(|&vertex_index| vertex_index + vertex_num));
+            vertex_num += part.vertex_num;
+        }
+        let mut initializer = SolverInitializer::new(vertex_num, weighted_edges, virtual_vertices);
+        if parts.iter().all(|part| part.edge_rounds.is_some()) {
+            initializer.edge_rounds = Some(parts.iter().flat_map(|part| part.edge_rounds.clone().unwrap()).collect());
+        }
+        (initializer, offsets)
+    }
+
+    /// join several decoding graphs into one where each group of linked virtual vertices shares a parity constraint,
+    /// e.g. the merged boundary in lattice surgery: the linked vertices are no longer virtual but connected by
+    /// zero-weight gadget edges, so a defect that matches into the group must be paired with another defect matching
+    /// into the same group, possibly from another patch. Each group needs at least two vertices, and at least one
+    /// unlinked virtual vertex should remain in the joined graph if the total number of defects can be odd
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_parity_linked(
+        patches: &[SolverInitializer],
+        linked_groups: &[Vec<PatchVertex>],
+    ) -> Result<ParityLinkedInitializer, String> {
+        let (joined, offsets) = Self::concat(patches);
+        let vertex_offsets: Vec<VertexIndex> = offsets.iter().map(|offsets| offsets.vertex_offset).collect();
+        let edge_offsets: Vec<EdgeIndex> = offsets.iter().map(|offsets| offsets.edge_offset).collect();
+        let SolverInitializer {
+            vertex_num,
+            mut weighted_edges,
+            virtual_vertices,
+            edge_rounds,
+        } = joined;
+        let mut is_virtual = vec![false; vertex_num as usize];
+        for &vertex_index in virtual_vertices.iter() {
+            is_virtual[vertex_index as usize] = true;
+        }
+        let mut is_linked = vec![false; vertex_num as usize];
+        let mut gadget_edges = vec![];
+        for (group_index, group) in linked_groups.iter().enumerate() {
+            if group.len() < 2 {
+                return Err(format!("linked group {group_index} must have at least two vertices"));
+            }
+            let mut previous: Option<VertexIndex> = None;
+            for &(patch, vertex_index) in group.iter() {
+                if patch >= patches.len() {
+                    return Err(format!("linked group {group_index}: patch {patch} out of range"));
+                }
+                if vertex_index >= patches[patch].vertex_num {
+                    return Err(format!(
+                        "linked group {group_index}: vertex {vertex_index} out of range in patch {patch}"
+                    ));
+                }
+                let joined_index = vertex_offsets[patch] + vertex_index;
+                if !is_virtual[joined_index as usize] {
+                    return Err(format!(
+                        "linked group {group_index}: vertex {vertex_index} in patch {patch} is not virtual"
+                    ));
+                }
+                if is_linked[joined_index as usize] {
+                    return Err(format!(
+                        "linked group {group_index}: vertex {vertex_index} in patch {patch} is linked more than once"
+                    ));
+                }
+                is_linked[joined_index as usize] = true;
+                if let Some(previous) = previous {
+                    gadget_edges.push(weighted_edges.len() as EdgeIndex);
+                    weighted_edges.push((previous, joined_index, 0));
+                }
+                previous = Some(joined_index);
+            }
+        }
+        let virtual_vertices = (0..vertex_num)
+            .filter(|&vertex_index| is_virtual[vertex_index as usize] && !is_linked[vertex_index as usize])
+            .collect();
+        let mut initializer = SolverInitializer::new(vertex_num, weighted_edges, virtual_vertices);
+        if let Some(mut edge_rounds) = edge_rounds {
+            edge_rounds.resize(initializer.weighted_edges.len(), None);
+            initializer.edge_rounds = Some(edge_rounds);
+        }
+        Ok(ParityLinkedInitializer {
+            initializer,
+            vertex_offsets,
+            edge_offsets,
+            gadget_edges,
+        })
+    }
+}
+
+/// the vertex ordering used when converting a QEC-Playground model graph into the decoding graph: real vertices are
+/// ordered by (t, i, j), followed by one virtual vertex for each real vertex that has a boundary edge
+#[cfg(feature = "qecp_integrate")]
+#[derive(Debug, Clone)]
+pub struct QecpVertexMap {
+    /// the qecp position of each real vertex
+    pub real_positions: Vec<qecp::simulator::Position>,
+    /// the real vertex that each virtual vertex is attached to, the virtual vertex index is `real_positions.len() + i`
+    pub virtual_owners: Vec<VertexIndex>,
+    /// reverse mapping from qecp position to real vertex
+    pub position_to_vertex: HashMap<qecp::simulator::Position, VertexIndex>,
+}
+
+#[cfg(feature = "qecp_integrate")]
+impl QecpVertexMap {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(model_graph: &qecp::model_graph::ModelGraph) -> Self {
+        let mut real_positions = vec![];
+        let mut position_to_vertex = HashMap::new();
+        for (t, layer) in model_graph.nodes.iter().enumerate() {
+            for (i, row) in layer.iter().enumerate() {
+                for (j, node) in row.iter().enumerate() {
+                    if node.is_some() {
+                        let position = qecp::simulator::Position::new(t, i, j);
+                        position_to_vertex.insert(position.clone(), real_positions.len() as VertexIndex);
+                        real_positions.push(position);
+                    }
+                }
+            }
+        }
+        let mut virtual_owners = vec![];
+        for (vertex_index, position) in real_positions.iter().enumerate() {
+            let node = model_graph.nodes[position.t][position.i][position.j].as_ref().unwrap();
+            if node.boundary.is_some() {
+                virtual_owners.push(vertex_index as VertexIndex);
+            }
+        }
+        Self {
+            real_positions,
+            virtual_owners,
+            position_to_vertex,
+        }
+    }
+
+    pub fn vertex_num(&self) -> VertexNum {
+        (self.real_positions.len() + self.virtual_owners.len()) as VertexNum
+    }
+
+    /// positions for the visualizer; a virtual vertex is drawn slightly aside the real vertex it's attached to
+    #[allow(clippy::unnecessary_cast)]
+    pub fn visualize_positions(&self) -> Vec<super::visualize::VisualizePosition> {
+        use super::visualize::VisualizePosition;
+        let mut positions: Vec<_> = self
+            .real_positions
+            .iter()
+            .map(|position| VisualizePosition::new(position.i as f64, position.j as f64, position.t as f64))
+            .collect();
+        for &owner in self.virtual_owners.iter() {
+            let position = &self.real_positions[owner as usize];
+            positions.push(VisualizePosition::new(
+                position.i as f64,
+                position.j as f64 + 0.5,
+                position.t as f64,
+            ));
+        }
+        positions
+    }
+}
+
+/// a vertex of the petgraph graph of a decoding graph, see [`SolverInitializer::to_petgraph`]
+#[cfg(feature = "petgraph")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VertexMeta {
+    pub is_virtual: bool,
+}
+
+#[cfg(feature = "petgraph")]
+impl SolverInitializer {
+    /// the decoding graph as an undirected petgraph graph with the same vertex and edge indices, e.g. to use the graph
+    /// algorithms of petgraph; the edge weights are the weights of the decoding graph
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_petgraph(&self) -> petgraph::graph::UnGraph<VertexMeta, Weight> {
+        let mut graph = petgraph::graph::UnGraph::with_capacity(self.vertex_num as usize, self.weighted_edges.len());
+        for _ in 0..self.vertex_num {
+            graph.add_node(VertexMeta::default());
+        }
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            graph[petgraph::graph::NodeIndex::new(virtual_vertex as usize)].is_virtual = true;
+        }
+        for &(i, j, weight) in self.weighted_edges.iter() {
+            graph.add_edge(
+                petgraph::graph::NodeIndex::new(i as usize),
+                petgraph::graph::NodeIndex::new(j as usize),
+                weight,
+            );
+        }
+        graph
+    }
+
+    /// build the decoding graph from an undirected petgraph graph with the same vertex and edge indices, checking that
+    /// the weights are even and non-negative and that there are no self loops or parallel edges.
+    ///
+    /// For example, solve the minimum-weight perfect matching of a complete graph built with petgraph:
+    ///
+    /// ```
+    /// use fusion_blossom::util::*;
+    /// use petgraph::graph::UnGraph;
+    /// let mut graph = UnGraph::<VertexMeta, Weight>::new_undirected();
+    /// let vertices: Vec<_> = (0..4).map(|_| graph.add_node(VertexMeta::default())).collect();
+    /// for i in 0..4 {
+    ///     for j in (i + 1)..4 {
+    ///         graph.add_edge(vertices[i], vertices[j], 2 * (i * j) as Weight);
+    ///     }
+    /// }
+    /// let initializer = SolverInitializer::from_petgraph(&graph).unwrap();
+    /// // every vertex is a defect, so each one is matched to another
+    /// let syndrome_pattern = SyndromePattern::new_vertices((0..4).collect());
+    /// let mwpm = fusion_blossom::fusion_mwpm(&initializer, &syndrome_pattern);
+    /// assert_eq!(mwpm, vec![3, 2, 1, 0]); // 0-3 and 1-2 with total weight 0 + 4
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_petgraph(graph: &petgraph::graph::UnGraph<VertexMeta, Weight>) -> Result<Self, String> {
+        use petgraph::visit::EdgeRef;
+        let mut vertex_pairs = BTreeSet::new();
+        let mut weighted_edges = Vec::with_capacity(graph.edge_count());
+        for edge in graph.edge_references() {
+            let (i, j, weight) = (edge.source().index(), edge.target().index(), *edge.weight());
+            let edge_index = edge.id().index();
+            if i == j {
+                return Err(format!("edge {edge_index} is a self loop on vertex {i}"));
+            }
+            if weight < 0 || weight % 2 != 0 {
+                return Err(format!("weight {weight} of edge {edge_index} must be even and non-negative"));
+            }
+            if !vertex_pairs.insert((i.min(j), i.max(j))) {
+                return Err(format!(
+                    "edge {edge_index} duplicates another edge between vertices {i} and {j}"
+                ));
+            }
+            weighted_edges.push((i as VertexIndex, j as VertexIndex, weight));
+        }
+        let virtual_vertices = graph
+            .node_indices()
+            .filter(|&node| graph[node].is_virtual)
+            .map(|node| node.index() as VertexIndex)
+            .collect();
+        Ok(Self::new(graph.node_count() as VertexNum, weighted_edges, virtual_vertices))
+    }
+}
+
+#[cfg(feature = "qecp_integrate")]
+impl SolverInitializer {
+    /// build the decoding graph from a QEC-Playground model graph, where the largest weight is mapped to `2 * scale`;
+    /// the vertex indices follow [`QecpVertexMap`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_qecp_model(model_graph: &qecp::model_graph::ModelGraph, scale: Weight) -> Self {
+        assert!(scale > 0, "scale must be positive");
+        let vertex_map = QecpVertexMap::new(model_graph);
+        let get_node =
+            |position: &qecp::simulator::Position| model_graph.nodes[position.t][position.i][position.j].as_ref().unwrap();
+        let mut maximum_weight: f64 = 0.;
+        for position in vertex_map.real_positions.iter() {
+            let node = get_node(position);
+            for edge in node.edges.values() {
+                maximum_weight = maximum_weight.max(edge.weight);
+            }
+            if let Some(boundary) = node.boundary.as_ref() {
+                maximum_weight = maximum_weight.max(boundary.weight);
+            }
+        }
+        let scale_weight = |weight: f64| -> Weight {
+            if maximum_weight == 0. {
+                return 0;
+            }
+            // weights must be even for the serial dual module
+            2 * ((weight / maximum_weight * scale as f64).round() as Weight).max(0)
+        };
+        let mut weighted_edges = vec![];
+        for (vertex_index, position) in vertex_map.real_positions.iter().enumerate() {
+            for (peer_position, edge) in get_node(position).edges.iter() {
+                let peer_index = vertex_map.position_to_vertex[peer_position];
+                if (vertex_index as VertexIndex) < peer_index {
+                    // each edge is recorded in both of its vertices
+                    weighted_edges.push((vertex_index as VertexIndex, peer_index, scale_weight(edge.weight)));
+                }
+            }
+        }
+        let real_vertex_num = vertex_map.real_positions.len() as VertexIndex;
+        let mut virtual_vertices = vec![];
+        for (i, &owner) in vertex_map.virtual_owners.iter().enumerate() {
+            let virtual_index = real_vertex_num + i as VertexIndex;
+            let boundary = get_node(&vertex_map.real_positions[owner as usize])
+                .boundary
+                .as_ref()
+                .unwrap();
+            weighted_edges.push((owner, virtual_index, scale_weight(boundary.weight)));
+            virtual_vertices.push(virtual_index);
+        }
+        Self::new(vertex_map.vertex_num(), weighted_edges, virtual_vertices)
+    }
+}
+
+#[cfg(feature = "qecp_integrate")]
+impl SyndromePattern {
+    /// translate the defect measurements of a QEC-Playground simulator using the same vertex ordering of
+    /// [`SolverInitializer::from_qecp_model`]
+    pub fn from_qecp_measurement(
+        vertex_map: &QecpVertexMap,
+        sparse_measurement: &qecp::simulator::SparseMeasurement,
+    ) -> Self {
+        let defect_vertices = sparse_measurement
+            .iter()
+            .map(|position| {
+                *vertex_map
+                    .position_to_vertex
+                    .get(position)
+                    .unwrap_or_else(|| panic!("defect measurement at {position:?} is not in the model graph"))
+            })
+            .collect();
+        Self::new_vertices(defect_vertices)
+    }
 }
 
 /// timestamp type determines how many fast clear before a hard clear is required, see [`FastClear`]
@@ -673,6 +2486,20 @@ pub type FastClearTimestamp = usize;
 /// use Xoshiro256StarStar for deterministic random number generator
 pub type DeterministicRng = rand_xoshiro::Xoshiro256StarStar;
 
+/// the SplitMix64 output function, a bijection on `u64` that scrambles nearby inputs into unrelated outputs
+pub fn splitmix64(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9E3779B97F4A7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+    value ^ (value >> 31)
+}
+
+/// derive the seed of an independent child generator from a parent seed and the child index, e.g. one per unit
+/// of a parallel generator; children of the same parent always have distinct seeds
+pub fn child_seed(seed: u64, index: usize) -> u64 {
+    splitmix64(splitmix64(seed) ^ index as u64)
+}
+
 pub trait F64Rng {
     fn next_f64(&mut self) -> f64;
 }
@@ -684,6 +2511,7 @@ impl F64Rng for DeterministicRng {
 }
 
 /// record the decoding time of multiple syndrome patterns
+#[cfg(feature = "std")]
 pub struct BenchmarkProfiler {
     /// each record corresponds to a different syndrome pattern
     pub records: Vec<BenchmarkProfilerEntry>,
@@ -695,23 +2523,32 @@ pub struct BenchmarkProfiler {
     pub noisy_measurements: VertexNum,
     /// the file to output the profiler results
     pub benchmark_profiler_output: Option<File>,
+    /// latency distributions of the whole round and of each phase, enabled by [`Self::enable_latency_histograms`]
+    pub latency_histograms: Option<BenchmarkLatencyHistograms>,
 }
 
+#[cfg(feature = "std")]
 impl BenchmarkProfiler {
     pub fn new(noisy_measurements: VertexNum, detail_log_file: Option<(String, &PartitionInfo)>) -> Self {
+        Self::new_with_header(noisy_measurements, detail_log_file, json!({}))
+    }
+    /// the same as [`Self::new`] but with additional fields in the second header line of the output file, e.g. to note
+    /// what is excluded from the timing
+    pub fn new_with_header(
+        noisy_measurements: VertexNum,
+        detail_log_file: Option<(String, &PartitionInfo)>,
+        mut header: serde_json::Value,
+    ) -> Self {
         let benchmark_profiler_output = detail_log_file.map(|(filename, partition_info)| {
             let mut file = File::create(filename).unwrap();
             file.write_all(serde_json::to_string(&partition_info.config).unwrap().as_bytes())
                 .unwrap();
             file.write_all(b"\n").unwrap();
-            file.write_all(
-                serde_json::to_string(&json!({
-                    "noisy_measurements": noisy_measurements,
-                }))
-                .unwrap()
-                .as_bytes(),
-            )
-            .unwrap();
+            header
+                .as_object_mut()
+                .expect("header must be JSON object")
+                .insert("noisy_measurements".to_string(), json!(noisy_measurements));
+            file.write_all(serde_json::to_string(&header).unwrap().as_bytes()).unwrap();
             file.write_all(b"\n").unwrap();
             file
         });
@@ -721,8 +2558,26 @@ impl BenchmarkProfiler {
             sum_syndrome: 0,
             noisy_measurements,
             benchmark_profiler_output,
+            latency_histograms: None,
         }
     }
+    /// record the latency of every round into histograms, see [`BenchmarkLatencyHistograms`]
+    pub fn enable_latency_histograms(&mut self) {
+        if self.latency_histograms.is_none() {
+            self.latency_histograms = Some(BenchmarkLatencyHistograms::new());
+        }
+    }
+    /// record the latency of every round into histograms with custom bucket edges in nanoseconds, e.g. around a
+    /// deadline, see [`LatencyHistogram::with_bucket_edges`]
+    pub fn enable_latency_histograms_with_bucket_edges(&mut self, bucket_edges: Vec<u64>) {
+        self.latency_histograms = Some(BenchmarkLatencyHistograms::with_bucket_edges(bucket_edges));
+    }
+    /// the tail latency of the rounds, only available when the latency histograms are enabled
+    pub fn summary(&self) -> Option<ProfilerSummary> {
+        self.latency_histograms
+            .as_ref()
+            .map(|latency_histograms| latency_histograms.round.summary())
+    }
     /// record the beginning of a decoding procedure
     pub fn begin(&mut self, syndrome_pattern: &SyndromePattern) {
         // sanity check last entry, if exists, is complete
@@ -752,6 +2607,9 @@ impl BenchmarkProfiler {
         last_entry.record_end();
         self.sum_round_time += last_entry.round_time.unwrap();
         self.sum_syndrome += last_entry.syndrome_pattern.defect_vertices.len();
+        if let Some(latency_histograms) = self.latency_histograms.as_mut() {
+            latency_histograms.add(last_entry);
+        }
         if let Some(file) = self.benchmark_profiler_output.as_mut() {
             let mut events = serde_json::Map::new();
             for (event_name, time) in last_entry.events.iter() {
@@ -778,10 +2636,15 @@ impl BenchmarkProfiler {
         let total = self.sum_round_time / (self.records.len() as f64);
         let per_round = total / (1. + self.noisy_measurements as f64);
         let per_defect = self.sum_round_time / (self.sum_syndrome as f64);
-        format!("total: {total:.3e}, round: {per_round:.3e}, defect: {per_defect:.3e},")
+        let mut brief = format!("total: {total:.3e}, round: {per_round:.3e}, defect: {per_defect:.3e},");
+        if let Some(summary) = self.summary() {
+            brief += &format!(" p99: {:.3e}, max: {:.3e},", summary.p99, summary.max);
+        }
+        brief
     }
 }
 
+#[cfg(feature = "std")]
 pub struct BenchmarkProfilerEntry {
     /// the syndrome pattern of this decoding problem
     pub syndrome_pattern: SyndromePattern,
@@ -793,6 +2656,7 @@ pub struct BenchmarkProfilerEntry {
     pub round_time: Option<f64>,
 }
 
+#[cfg(feature = "std")]
 impl BenchmarkProfilerEntry {
     pub fn new(syndrome_pattern: &SyndromePattern) -> Self {
         Self {
@@ -827,6 +2691,334 @@ impl BenchmarkProfilerEntry {
     }
 }
 
+/// sustained decoding throughput, which is the realistic deployment metric when a solver is reused across shots: the
+/// decoding graph is only loaded once and [`PrimalDualSolver::clear`] keeps the allocated structures for the next shot.
+/// Compare [`Self::reused`] with [`Self::cold`] to quantify the benefit of reusing a solver
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputSummary {
+    /// number of decoded shots
+    pub shots: usize,
+    /// total time of decoding all the shots, including the construction or clearing of the solver
+    pub total_time: f64,
+    /// `shots / total_time`
+    pub shots_per_second: f64,
+    /// number of heap allocations during decoding, if an allocation counter is provided
+    pub allocations: Option<u64>,
+}
+
+#[cfg(feature = "std")]
+impl ThroughputSummary {
+    /// decode all the syndrome patterns on the same solver, clearing it between shots; `allocation_count` reads a
+    /// monotonic allocation counter, e.g. from a counting global allocator, which is not provided by this library; see
+    /// `tests/streaming_throughput.rs` for an example
+    pub fn reused(
+        primal_dual_solver: &mut dyn PrimalDualSolver,
+        syndrome_patterns: &[SyndromePattern],
+        allocation_count: Option<&dyn Fn() -> u64>,
+    ) -> Self {
+        let allocations_begin = allocation_count.map(|count| count());
+        let begin = Instant::now();
+        for syndrome_pattern in syndrome_patterns.iter() {
+            primal_dual_solver.solve(syndrome_pattern);
+            primal_dual_solver.clear();
+        }
+        Self::new(syndrome_patterns.len(), begin, allocation_count, allocations_begin)
+    }
+
+    /// construct a new solver for every shot, i.e. the cold per-shot performance
+    pub fn cold<Solver: PrimalDualSolver>(
+        mut new_solver: impl FnMut() -> Solver,
+        syndrome_patterns: &[SyndromePattern],
+        allocation_count: Option<&dyn Fn() -> u64>,
+    ) -> Self {
+        let allocations_begin = allocation_count.map(|count| count());
+        let begin = Instant::now();
+        for syndrome_pattern in syndrome_patterns.iter() {
+            let mut primal_dual_solver = new_solver();
+            primal_dual_solver.solve(syndrome_pattern);
+        }
+        Self::new(syndrome_patterns.len(), begin, allocation_count, allocations_begin)
+    }
+
+    fn new(
+        shots: usize,
+        begin: Instant,
+        allocation_count: Option<&dyn Fn() -> u64>,
+        allocations_begin: Option<u64>,
+    ) -> Self {
+        let total_time = begin.elapsed().as_secs_f64();
+        Self {
+            shots,
+            total_time,
+            shots_per_second: shots as f64 / total_time,
+            allocations: allocation_count.map(|count| count() - allocations_begin.unwrap()),
+        }
+    }
+
+    /// the average number of heap allocations per shot
+    pub fn allocations_per_shot(&self) -> Option<f64> {
+        self.allocations
+            .map(|allocations| allocations as f64 / self.shots.max(1) as f64)
+    }
+}
+
+/// the tail latency of the benchmark rounds in seconds, see [`BenchmarkProfiler::summary`]; the percentiles are the
+/// upper bounds of their buckets, so a decoder meets a deadline in the worst case observed if `max` is below it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfilerSummary {
+    /// number of recorded rounds
+    pub round_count: u64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p99: f64,
+    pub p999: f64,
+    pub max: f64,
+}
+
+/// number of linear sub-buckets in each power-of-two range, giving a relative bucket width of at most 1/16
+const LATENCY_HISTOGRAM_SUB_BUCKET_BITS: u32 = 4;
+const LATENCY_HISTOGRAM_SUB_BUCKETS: u64 = 1 << LATENCY_HISTOGRAM_SUB_BUCKET_BITS;
+
+/// a log-bucketed histogram of latencies in nanoseconds with the same bucket layout as HdrHistogram:
+/// values below 16ns have their own buckets, and each power-of-two range above is split into 16 linear buckets,
+/// so that any recorded value is known within 6.25% regardless of its magnitude; alternatively the bucket edges can be
+/// configured, see [`Self::with_bucket_edges`]
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    /// the count of each bucket, only allocated up to the largest recorded value unless the bucket edges are configured
+    pub counts: Vec<u64>,
+    /// the configured bucket edges in nanoseconds, `None` for the log-scaled buckets
+    pub bucket_edges: Option<Vec<u64>>,
+    /// total number of recorded values
+    pub total_count: u64,
+    /// the smallest recorded value in nanoseconds
+    pub min: u64,
+    /// the largest recorded value in nanoseconds
+    pub max: u64,
+    /// the sum of all recorded values in nanoseconds
+    pub sum: u128,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![],
+            bucket_edges: None,
+            total_count: 0,
+            min: u64::MAX,
+            max: 0,
+            sum: 0,
+        }
+    }
+    /// buckets `[0, e0), [e0, e1), ..., [en, u64::MAX)` given strictly increasing edges in nanoseconds; all the buckets
+    /// are allocated here, so that recording never allocates
+    pub fn with_bucket_edges(bucket_edges: Vec<u64>) -> Self {
+        assert!(
+            bucket_edges.windows(2).all(|pair| pair[0] < pair[1]),
+            "bucket edges must be strictly increasing: {bucket_edges:?}"
+        );
+        Self {
+            counts: vec![0; bucket_edges.len() + 1],
+            bucket_edges: Some(bucket_edges),
+            ..Self::new()
+        }
+    }
+    /// the index of the bucket that contains `nanos` in the layout of this histogram
+    pub fn index_of(&self, nanos: u64) -> usize {
+        match &self.bucket_edges {
+            Some(bucket_edges) => bucket_edges.partition_point(|&edge| edge <= nanos),
+            None => Self::bucket_index(nanos),
+        }
+    }
+    /// the range `[lower, upper)` of the bucket in the layout of this histogram
+    pub fn range_of(&self, index: usize) -> (u64, u64) {
+        match &self.bucket_edges {
+            Some(bucket_edges) => (
+                if index == 0 { 0 } else { bucket_edges[index - 1] },
+                bucket_edges.get(index).cloned().unwrap_or(u64::MAX),
+            ),
+            None => Self::bucket_range(index),
+        }
+    }
+    /// the index of the bucket that contains `nanos` in the log-scaled layout
+    pub fn bucket_index(nanos: u64) -> usize {
+        if nanos < LATENCY_HISTOGRAM_SUB_BUCKETS {
+            return nanos as usize;
+        }
+        let shift = 63 - nanos.leading_zeros() - LATENCY_HISTOGRAM_SUB_BUCKET_BITS;
+        let sub_bucket = (nanos >> shift) - LATENCY_HISTOGRAM_SUB_BUCKETS;
+        (LATENCY_HISTOGRAM_SUB_BUCKETS * (shift as u64 + 1) + sub_bucket) as usize
+    }
+    /// the range `[lower, upper)` of values in nanoseconds that falls into the bucket of the log-scaled layout
+    pub fn bucket_range(index: usize) -> (u64, u64) {
+        let index = index as u64;
+        if index < LATENCY_HISTOGRAM_SUB_BUCKETS {
+            return (index, index + 1);
+        }
+        let shift = index / LATENCY_HISTOGRAM_SUB_BUCKETS - 1;
+        let sub_bucket = index % LATENCY_HISTOGRAM_SUB_BUCKETS;
+        let lower = (LATENCY_HISTOGRAM_SUB_BUCKETS + sub_bucket) << shift;
+        (lower, lower.saturating_add(1 << shift))
+    }
+    pub fn record_nanos(&mut self, nanos: u64) {
+        let index = self.index_of(nanos);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+        self.total_count += 1;
+        self.min = self.min.min(nanos);
+        self.max = self.max.max(nanos);
+        self.sum += nanos as u128;
+    }
+    /// record a latency in seconds, as measured by [`BenchmarkProfilerEntry`]
+    pub fn record(&mut self, seconds: f64) {
+        self.record_nanos((seconds.max(0.) * 1e9).round() as u64);
+    }
+    /// the upper bound of the bucket that contains the given percentile (0 to 100), clamped to the recorded range;
+    /// like HdrHistogram, this never under-reports a latency
+    pub fn value_at_percentile(&self, percentile: f64) -> u64 {
+        if self.total_count == 0 {
+            return 0;
+        }
+        let target = ((percentile.clamp(0., 100.) / 100. * self.total_count as f64).ceil() as u64).max(1);
+        let mut accumulated = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            accumulated += count;
+            if accumulated >= target {
+                let (_, upper) = self.range_of(index);
+                return (upper - 1).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+    pub fn mean(&self) -> f64 {
+        if self.total_count == 0 {
+            return 0.;
+        }
+        self.sum as f64 / self.total_count as f64
+    }
+    pub fn summary(&self) -> ProfilerSummary {
+        let seconds = |nanos: u64| nanos as f64 / 1e9;
+        ProfilerSummary {
+            round_count: self.total_count,
+            mean: self.mean() / 1e9,
+            p50: seconds(self.value_at_percentile(50.)),
+            p99: seconds(self.value_at_percentile(99.)),
+            p999: seconds(self.value_at_percentile(99.9)),
+            max: seconds(self.max),
+        }
+    }
+    /// the non-empty buckets and the summary percentiles, all in nanoseconds
+    pub fn to_json(&self) -> serde_json::Value {
+        let buckets: Vec<serde_json::Value> = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| {
+                let (lower, upper) = self.range_of(index);
+                json!([lower, upper, count])
+            })
+            .collect();
+        let mut percentiles = serde_json::Map::new();
+        for percentile in [50., 90., 99., 99.9, 99.99] {
+            percentiles.insert(format!("p{percentile}"), json!(self.value_at_percentile(percentile)));
+        }
+        json!({
+            "total_count": self.total_count,
+            "min": if self.total_count == 0 { 0 } else { self.min },
+            "max": self.max,
+            "mean": self.mean(),
+            "percentiles": percentiles,
+            "buckets": buckets,
+        })
+    }
+}
+
+/// latency histograms of the benchmark loop: the whole round, and each phase between consecutive events so that
+/// e.g. the decoding latency (`decoded`) is not polluted by the verifier (`verified`) or the clear (`cleared`)
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkLatencyHistograms {
+    /// from [`BenchmarkProfiler::begin`] to [`BenchmarkProfiler::end`]
+    pub round: LatencyHistogram,
+    /// the phase ending with each event, measured from the previous event (or the beginning of the round)
+    pub phases: Vec<(String, LatencyHistogram)>,
+    /// the configured bucket edges of all the histograms, see [`LatencyHistogram::with_bucket_edges`]
+    pub bucket_edges: Option<Vec<u64>>,
+}
+
+#[cfg(feature = "std")]
+impl BenchmarkLatencyHistograms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_bucket_edges(bucket_edges: Vec<u64>) -> Self {
+        Self {
+            round: LatencyHistogram::with_bucket_edges(bucket_edges.clone()),
+            phases: vec![],
+            bucket_edges: Some(bucket_edges),
+        }
+    }
+    fn new_histogram(&self) -> LatencyHistogram {
+        match &self.bucket_edges {
+            Some(bucket_edges) => LatencyHistogram::with_bucket_edges(bucket_edges.clone()),
+            None => LatencyHistogram::new(),
+        }
+    }
+    pub fn add(&mut self, entry: &BenchmarkProfilerEntry) {
+        self.round.record(entry.round_time.expect("the entry is not complete"));
+        let mut last_time = 0.;
+        for (event_name, time) in entry.events.iter() {
+            let phase_index = match self.phases.iter().position(|(name, _)| name == event_name) {
+                Some(phase_index) => phase_index,
+                None => {
+                    self.phases.push((event_name.clone(), self.new_histogram()));
+                    self.phases.len() - 1
+                }
+            };
+            self.phases[phase_index].1.record(time - last_time);
+            last_time = *time;
+        }
+    }
+    /// the output is self-describing: the `schema` field documents every other field for plotting scripts
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut phases = serde_json::Map::new();
+        for (event_name, histogram) in self.phases.iter() {
+            phases.insert(event_name.clone(), histogram.to_json());
+        }
+        json!({
+            "schema": {
+                "unit": "all latencies are integers in nanoseconds, except `mean` which is a float",
+                "round": "histogram of the latency of a whole benchmark round",
+                "phases": "histogram of each phase, named by the event that ends it and measured from the previous event: \
+                    `decoded` is the solve, `verified` the verifier, `analyzed` the optional statistics, `cleared` the clear",
+                "histogram": {
+                    "total_count": "number of recorded rounds",
+                    "min": "smallest recorded latency",
+                    "max": "largest recorded latency",
+                    "mean": "average latency",
+                    "percentiles": "`p<x>` is the latency below which x% of the rounds fall, reported as the \
+                        largest value of its bucket so it never under-reports",
+                    "buckets": "non-empty buckets as [lower, upper, count] covering latencies in [lower, upper); \
+                        unless the bucket edges are configured, buckets are log-scaled as in HdrHistogram, each power of \
+                        two split into 16 linear buckets",
+                },
+            },
+            "round": self.round.to_json(),
+            "phases": phases,
+        })
+    }
+}
+
 /**
  * If you want to modify a field of a Rust struct, it will return a copy of it to avoid memory unsafety.
  * Thus, typical way of modifying a python field doesn't work, e.g. `obj.a.b.c = 1` won't actually modify `obj`.
@@ -962,6 +3154,8 @@ pub(crate) fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PartitionInfo>()?;
     m.add_class::<PartitionConfig>()?;
     m.add_class::<SyndromePattern>()?;
+    m.add_function(wrap_pyfunction!(weight_of_p, m)?)?;
+    m.add_function(wrap_pyfunction!(p_of_weight, m)?)?;
     use crate::pyo3::PyTypeInfo;
     // m.add_class::<IndexRange>()?;
     m.add("VertexRange", VertexRange::type_object(py))?;
@@ -1006,4 +3200,705 @@ pub mod tests {
             assert_eq!(owned_partitioned.whole_defect_range, expected_defect_range);
         }
     }
+
+    /// the effective distance of a planar code is `d` times the uniform edge weight
+    #[test]
+    fn util_effective_distance_1() {
+        // cargo test util_effective_distance_1 -- --nocapture
+        use crate::example_codes::*;
+        let d = 7;
+        let code = CodeCapacityPlanarCode::new(d, 0.1, 500);
+        let mut initializer = code.get_initializer();
+        let weight = initializer.weighted_edges[0].2;
+        assert!(initializer.weighted_edges.iter().all(|(_, _, w)| *w == weight));
+        // each row has a left virtual vertex `bias + d` and a right virtual vertex `bias + d - 1`
+        let left: Vec<VertexIndex> = (0..d).map(|row| (row * (d + 1) + d) as VertexIndex).collect();
+        let right: Vec<VertexIndex> = (0..d).map(|row| (row * (d + 1) + d - 1) as VertexIndex).collect();
+        let logical_boundaries = vec![(left.clone(), right.clone())];
+        assert_eq!(initializer.effective_distance(&logical_boundaries), d as Weight * weight);
+        // a cheaper row reduces the effective distance
+        for (left_vertex, right_vertex, w) in initializer.weighted_edges.iter_mut() {
+            if *left_vertex < d as VertexIndex + 1 && *right_vertex < d as VertexIndex + 1 {
+                *w = 2;
+            }
+        }
+        assert_eq!(initializer.effective_distance(&logical_boundaries), d as Weight * 2);
+        // disconnected boundaries
+        initializer.weighted_edges.clear();
+        assert_eq!(initializer.effective_distance(&logical_boundaries), Weight::MAX);
+        assert_eq!(initializer.effective_distance(&[(vec![left[0]], vec![left[0]])]), 0);
+    }
+
+    /// the structural hash ignores the order of edges and virtual vertices but not the graph itself
+    #[test]
+    fn util_from_offset_weights_1() {
+        // cargo test util_from_offset_weights_1 -- --nocapture
+        let weighted_edges = vec![(0, 1, -1.5), (1, 2, 0.5), (2, 3, 2.5)];
+        let initializer = SolverInitializer::from_offset_weights(4, weighted_edges.clone(), vec![3], 2.5, 100).unwrap();
+        assert_eq!(initializer.weighted_edges, vec![(0, 1, 40), (1, 2, 120), (2, 3, 200)]);
+        assert_eq!(initializer.virtual_vertices, vec![3]);
+        // the offset is not enough to make all weights non-negative
+        assert!(SolverInitializer::from_offset_weights(4, weighted_edges.clone(), vec![3], 1., 100).is_err());
+        assert!(SolverInitializer::from_offset_weights(4, vec![(0, 1, f64::NAN)], vec![], 0., 100).is_err());
+        assert!(SolverInitializer::from_offset_weights(4, weighted_edges, vec![3], 2.5, 0).is_err());
+        // all weights become zero
+        let initializer = SolverInitializer::from_offset_weights(2, vec![(0, 1, -1.)], vec![], 1., 100).unwrap();
+        assert_eq!(initializer.weighted_edges, vec![(0, 1, 0)]);
+    }
+
+    #[test]
+    fn util_weight_of_p_1() {
+        // cargo test util_weight_of_p_1 -- --nocapture
+        let max_half_weight = 5000;
+        assert_eq!(weight_of_p(0.5, max_half_weight), 0);
+        assert_eq!(weight_of_p(MIN_WEIGHT_P, max_half_weight), 2 * max_half_weight);
+        assert_eq!(weight_of_p(1e-15, max_half_weight), 2 * max_half_weight);
+        assert_eq!(weight_of_p(0., max_half_weight), 2 * max_half_weight);
+        // probabilities from 0.5 down to 5e-10, above MIN_WEIGHT_P
+        let probabilities: Vec<f64> = (0..=180).map(|i| 0.5 * 10f64.powf(-(i as f64) / 20.)).collect();
+        let weights = weights_of_p(&probabilities, max_half_weight);
+        let log_likelihood_ratio = |p: f64| ((1. - p) / p).ln();
+        // the quantization error of the log-likelihood ratio
+        let tolerance = log_likelihood_ratio(MIN_WEIGHT_P) / (2. * max_half_weight as f64) + 1e-9;
+        for (i, (&p, &weight)) in probabilities.iter().zip(weights.iter()).enumerate() {
+            assert_eq!(weight, weight_of_p(p, max_half_weight));
+            assert_eq!(weight % 2, 0, "weight must be even");
+            if i > 0 {
+                assert!(weight >= weights[i - 1], "weight must not decrease as p decreases");
+            }
+            let round_trip = p_of_weight(weight, max_half_weight);
+            assert!(
+                (log_likelihood_ratio(round_trip) - log_likelihood_ratio(p)).abs() <= tolerance,
+                "p = {p}, round trip = {round_trip}"
+            );
+        }
+        assert_eq!(p_of_weights(&weights, max_half_weight)[0], 0.5);
+        assert_eq!(p_of_weight(-2, max_half_weight), 0.5);
+        assert_eq!(
+            p_of_weight(4 * max_half_weight, max_half_weight),
+            p_of_weight(2 * max_half_weight, max_half_weight)
+        );
+        // normalized weights scale the most unlikely edge to the maximum, but only p = 0.5 becomes free
+        assert_eq!(normalized_weights_of_p(&[0.1, 0.1], 500), vec![1000, 1000]);
+        assert_eq!(normalized_weights_of_p(&[0.01, 0.4999999, 0.5], 500), vec![1000, 2, 0]);
+        let normalized = normalized_weights_of_p(&[0.01, 0.1], 500);
+        assert_eq!(
+            normalized[1],
+            2 * (500. * log_likelihood_ratio(0.1) / log_likelihood_ratio(0.01)).round() as Weight
+        );
+        // the normalized weights are inverted with the probability of the largest weight, not with `max_half_weight`
+        let recovered = p_of_normalized_weights(&normalized, 0.01);
+        assert!((recovered[0] - 0.01).abs() < 1e-12, "{recovered:?}");
+        assert!((log_likelihood_ratio(recovered[1]) - log_likelihood_ratio(0.1)).abs() <= tolerance);
+        assert_eq!(p_of_normalized_weights(&[0, 0], 0.1), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn util_greedy_weight_bound_1() {
+        // cargo test util_greedy_weight_bound_1 -- --nocapture
+        use crate::example_codes::*;
+        use crate::mwpm_solver::*;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        assert_eq!(initializer.greedy_weight_bound(&[]), 0);
+        // vertex 8 is next to the left virtual vertex 15 and vertex 13 is next to the right virtual vertex 14
+        assert_eq!(initializer.greedy_weight_bound(&[8, 13]), 2000);
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..100 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let bound = initializer.greedy_weight_bound(&syndrome_pattern.defect_vertices);
+            assert!(
+                bound >= solver.sum_dual_variables(),
+                "the greedy bound must not be below the optimum"
+            );
+            solver.clear();
+        }
+        // without virtual vertices an odd number of defects cannot be matched
+        let initializer = SolverInitializer::new(3, vec![(0, 1, 2), (1, 2, 2)], vec![]);
+        assert_eq!(initializer.greedy_weight_bound(&[0]), Weight::MAX);
+        assert_eq!(initializer.greedy_weight_bound(&[0, 2]), 4);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn util_edge_count_tie_break_1() {
+        // cargo test util_edge_count_tie_break_1 -- --nocapture
+        use crate::example_codes::*;
+        use crate::mwpm_solver::*;
+        // defects 0 and 3 are connected by a path of 2 edges and a path of 4 edges with the same weight 8
+        let initializer = SolverInitializer::new(
+            6,
+            vec![(0, 1, 4), (1, 3, 4), (0, 2, 2), (2, 4, 2), (4, 5, 2), (5, 3, 2)],
+            vec![],
+        );
+        let tie_break_initializer = initializer.with_edge_count_tie_break().unwrap();
+        let mut solver = SolverSerial::new(&tie_break_initializer);
+        solver.solve(&SyndromePattern::new_vertices(vec![0, 3]));
+        let mut subgraph = solver.subgraph();
+        subgraph.sort();
+        assert_eq!(subgraph, vec![0, 1]);
+        // the weight stays optimal and the number of edges never increases
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut reference_solver = SolverSerial::new(&initializer);
+        let mut solver = SolverSerial::new(&initializer.with_edge_count_tie_break().unwrap());
+        for seed in 0..100 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            reference_solver.solve(&syndrome_pattern);
+            let reference_subgraph = reference_solver.subgraph();
+            solver.solve(&syndrome_pattern);
+            let subgraph = solver.subgraph();
+            let weight_of = |subgraph: &[EdgeIndex]| -> Weight {
+                subgraph
+                    .iter()
+                    .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                    .sum()
+            };
+            assert_eq!(weight_of(&subgraph), reference_solver.sum_dual_variables());
+            assert_eq!(weight_of(&reference_subgraph), reference_solver.sum_dual_variables());
+            assert!(subgraph.len() <= reference_subgraph.len());
+            reference_solver.clear();
+            solver.clear();
+        }
+        // the scaled weights must fit in `Weight`
+        let initializer = SolverInitializer::new(2, vec![(0, 1, Weight::MAX - 1)], vec![]);
+        assert!(initializer.with_edge_count_tie_break().is_err());
+    }
+
+    #[test]
+    fn util_structural_hash_1() {
+        // cargo test util_structural_hash_1 -- --nocapture
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        let hash = initializer.structural_hash();
+        // pinned value: the hash must not change across runs, platforms or Rust versions
+        assert_eq!(hash, 0x54b29e0822753308);
+        let reordered = SolverInitializer::new(4, vec![(3, 2, 100), (0, 1, 100), (2, 1, 200)], vec![3, 0]);
+        assert_eq!(reordered.structural_hash(), hash);
+        let reweighted = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 202), (2, 3, 100)], vec![0, 3]);
+        assert_ne!(reweighted.structural_hash(), hash);
+        let revirtualized = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0]);
+        assert_ne!(revirtualized.structural_hash(), hash);
+        let more_vertices = SolverInitializer::new(5, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        assert_ne!(more_vertices.structural_hash(), hash);
+    }
+
+    #[test]
+    fn util_initializer_diff_1() {
+        // cargo test util_initializer_diff_1 -- --nocapture
+        let initializer = SolverInitializer::new(5, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100), (3, 4, 100)], vec![0, 4]);
+        let reordered = SolverInitializer::new(5, vec![(4, 3, 100), (0, 1, 100), (2, 1, 200), (3, 2, 100)], vec![4, 0]);
+        assert!(initializer.diff(&reordered).is_empty());
+        assert_eq!(
+            initializer.diff_impact(&reordered, &[SyndromePattern::new_vertices(vec![1])]),
+            Ok(0)
+        );
+        let drifted = SolverInitializer::new(5, vec![(0, 1, 300), (2, 1, 220), (3, 4, 100), (0, 2, 500)], vec![0, 3]);
+        let diff = initializer.diff(&drifted);
+        assert_eq!(diff.vertex_num_changed, None);
+        assert_eq!(diff.added_edges, vec![(0, 2, 500)]);
+        assert_eq!(diff.removed_edges, vec![(2, 3, 100)]);
+        assert_eq!(diff.weight_changes, vec![(0, 1, 100, 300), (1, 2, 200, 220)]);
+        assert_eq!(diff.max_weight_change, 200);
+        assert_eq!(diff.mean_weight_change, 110.);
+        assert_eq!(diff.added_virtual_vertices, vec![3]);
+        assert_eq!(diff.removed_virtual_vertices, vec![4]);
+        // defect 1 is matched to virtual vertex 0 in both graphs, but defect 2 loses its path to virtual vertex 4
+        let syndrome_patterns = vec![SyndromePattern::new_vertices(vec![1]), SyndromePattern::new_vertices(vec![2])];
+        assert_eq!(initializer.diff_impact(&drifted, &syndrome_patterns), Ok(1));
+        let shrunk = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        assert_eq!(initializer.diff(&shrunk).vertex_num_changed, Some((5, 4)));
+        assert!(initializer.diff_impact(&shrunk, &syndrome_patterns).is_err());
+    }
+
+    /// erasure regions are expanded into deduplicated edges after the explicit erasures
+    #[test]
+    fn util_erasure_region_index_1() {
+        // cargo test util_erasure_region_index_1 -- --nocapture
+        // a chain 0 - 1 - 2 - 3 whose edges 0 and 2 belong to measurement rounds 0 and 1
+        let mut initializer = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        let syndrome_pattern = SyndromePattern::new_erasure_regions(
+            vec![1, 2],
+            vec![
+                ErasureRegion::TimeRound(1),
+                ErasureRegion::VertexRange(VertexRange::new(1, 4)),
+                ErasureRegion::TimeRound(1),
+            ],
+        );
+        assert!(ErasureRegionIndex::new(&initializer)
+            .expand(&SyndromePattern::new_vertices(vec![1, 2]))
+            .is_none());
+        initializer.edge_rounds = Some(vec![Some(0), None, Some(1)]);
+        let index = ErasureRegionIndex::new(&initializer);
+        assert_eq!(index.round_edges, Some(vec![vec![0], vec![2]]));
+        let mut with_erasures = syndrome_pattern.clone();
+        with_erasures.erasures = vec![0];
+        let expanded = index.expand(&with_erasures).unwrap();
+        assert_eq!(expanded.defect_vertices, vec![1, 2]);
+        assert_eq!(expanded.erasures, vec![0, 2, 1]);
+        assert!(expanded.erasure_regions.is_empty());
+        // the regions survive serialization, and are omitted when empty to keep the existing format
+        let json = serde_json::to_string(&syndrome_pattern).unwrap();
+        let deserialized: SyndromePattern = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.erasure_regions, syndrome_pattern.erasure_regions);
+        assert!(!serde_json::to_string(&expanded).unwrap().contains("erasure_regions"));
+    }
+
+    /// a time round erasure needs the rounds of the edges
+    #[test]
+    #[should_panic(expected = "requires `edge_rounds`")]
+    fn util_erasure_region_index_2() {
+        // cargo test util_erasure_region_index_2 -- --nocapture
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 200), (2, 3, 100)], vec![0, 3]);
+        let syndrome_pattern = SyndromePattern::new_erasure_regions(vec![], vec![ErasureRegion::TimeRound(0)]);
+        ErasureRegionIndex::new(&initializer).expand(&syndrome_pattern);
+    }
+
+    /// adjacent ranges don't intersect, overlapping ones intersect at the shared indices
+    #[test]
+    fn util_vertex_range_1() {
+        // cargo test util_vertex_range_1 -- --nocapture
+        let left = VertexRange::new(0, 3);
+        let right = VertexRange::new(3, 5);
+        assert!(!left.intersects(&right) && !right.intersects(&left));
+        assert_eq!(left.intersection(&right), None);
+        let overlapping = VertexRange::new(2, 4);
+        assert!(left.intersects(&overlapping) && overlapping.intersects(&right));
+        assert_eq!(left.intersection(&overlapping), Some(VertexRange::new(2, 3)));
+        assert_eq!(overlapping.intersection(&right), Some(VertexRange::new(3, 4)));
+        assert_eq!(overlapping.intersection(&VertexRange::new(0, 5)), Some(overlapping));
+        // empty ranges never intersect, but are contained at any position within the range
+        let empty = VertexRange::new(3, 3);
+        assert!(!empty.intersects(&VertexRange::new(0, 5)) && !empty.intersects(&empty));
+        assert!(left.contains_range(&empty) && right.contains_range(&empty));
+        assert!(!VertexRange::new(0, 2).contains_range(&empty));
+        let whole = VertexRange::new(0, 5);
+        assert!(whole.contains_range(&left) && whole.contains_range(&right) && whole.contains_range(&whole));
+        assert!(!left.contains_range(&overlapping) && !overlapping.contains_range(&whole));
+        // iterating yields the contained indices
+        assert_eq!(overlapping.into_iter().collect::<Vec<_>>(), vec![2, 3]);
+        let mut indices = vec![];
+        for vertex_index in &right {
+            indices.push(vertex_index);
+        }
+        assert_eq!(indices, vec![3, 4]);
+        assert_eq!(empty.into_iter().count(), 0);
+    }
+
+    /// each class of broken partition config is reported instead of panicking
+    #[test]
+    fn util_partition_config_validate_1() {
+        // cargo test util_partition_config_validate_1 -- --nocapture
+        #[cfg(feature = "parallel")]
+        use crate::mwpm_solver::*;
+        // a chain of 12 vertices, where vertex 0 and 11 are virtual
+        let initializer = SolverInitializer::new(12, (0..11).map(|i| (i, i + 1, 100)).collect(), vec![0, 11]);
+        let valid_config = || {
+            let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+            partition_config.partitions = vec![VertexRange::new(0, 5), VertexRange::new(6, 12)];
+            partition_config.fusions = vec![(0, 1)];
+            partition_config
+        };
+        assert_eq!(valid_config().validate(&initializer), Ok(()));
+        assert_eq!(PartitionConfig::new(initializer.vertex_num).validate(&initializer), Ok(()));
+        let check = |partition_config: PartitionConfig, expected: Vec<PartitionIssue>| {
+            assert_eq!(partition_config.validate(&initializer), Err(expected));
+        };
+        check(
+            PartitionConfig::new(5),
+            vec![PartitionIssue::VertexNumMismatch {
+                config: 5,
+                initializer: initializer.vertex_num,
+            }],
+        );
+        let mut partition_config = valid_config();
+        partition_config.partitions.clear();
+        partition_config.fusions.clear();
+        check(partition_config, vec![PartitionIssue::NoPartition]);
+        let mut partition_config = valid_config();
+        partition_config.partitions[1] = VertexRange::new(6, 13);
+        check(
+            partition_config,
+            vec![PartitionIssue::InvalidRange {
+                partition_index: 1,
+                range: VertexRange::new(6, 13),
+            }],
+        );
+        let mut partition_config = valid_config();
+        partition_config.fusions = vec![(0, 2)];
+        check(
+            partition_config,
+            vec![
+                PartitionIssue::InvalidFusionChild {
+                    fusion_index: 0,
+                    child: 2,
+                },
+                PartitionIssue::NotFused { unit_index: 1 },
+            ],
+        );
+        let mut partition_config = valid_config();
+        partition_config.partitions.push(VertexRange::new(12, 12));
+        partition_config.fusions = vec![(0, 1), (0, 2)];
+        check(
+            partition_config,
+            vec![
+                PartitionIssue::FusedTwice { unit_index: 0 },
+                PartitionIssue::NotFused { unit_index: 3 },
+            ],
+        );
+        let mut partition_config = valid_config();
+        partition_config.fusions = vec![(1, 0)];
+        check(
+            partition_config,
+            vec![PartitionIssue::MisorderedFusion {
+                fusion_index: 0,
+                left: VertexRange::new(6, 12),
+                right: VertexRange::new(0, 5),
+            }],
+        );
+        // a partition placed inside the interface of the fusion
+        let mut partition_config = valid_config();
+        partition_config.partitions = vec![VertexRange::new(0, 4), VertexRange::new(6, 12), VertexRange::new(4, 5)];
+        partition_config.fusions = vec![(0, 1), (3, 2)];
+        check(
+            partition_config,
+            vec![
+                PartitionIssue::MisorderedFusion {
+                    fusion_index: 1,
+                    left: VertexRange::new(0, 12),
+                    right: VertexRange::new(4, 5),
+                },
+                PartitionIssue::OverlappingUnits { unit_1: 2, unit_2: 3 },
+            ],
+        );
+        let mut partition_config = valid_config();
+        partition_config.partitions = vec![VertexRange::new(1, 5), VertexRange::new(6, 12)];
+        check(
+            partition_config,
+            vec![PartitionIssue::NotCovered {
+                range: VertexRange::new(0, 1),
+            }],
+        );
+        // two partitions without an interface in between: edge (4, 5) crosses them
+        let mut partition_config = valid_config();
+        partition_config.partitions = vec![VertexRange::new(0, 5), VertexRange::new(5, 12)];
+        let crossing_edge_index = initializer
+            .weighted_edges
+            .iter()
+            .position(|&(i, j, _)| (i, j) == (4, 5) || (i, j) == (5, 4))
+            .unwrap() as EdgeIndex;
+        let expected = vec![PartitionIssue::CrossingEdge {
+            edge_index: crossing_edge_index,
+            unit_1: 0,
+            unit_2: 1,
+        }];
+        check(partition_config.clone(), expected.clone());
+        #[cfg(feature = "parallel")]
+        {
+            let partition_info = partition_config.info(); // the tree itself is valid, only the edges are not
+            assert_eq!(
+                SolverParallel::try_new(&initializer, &partition_info, json!({})).err(),
+                Some(expected)
+            );
+        }
+        let mut broken_initializer = initializer.clone();
+        broken_initializer.virtual_vertices.push(12);
+        broken_initializer.weighted_edges.push((3, 3, 100));
+        assert_eq!(
+            valid_config().validate(&broken_initializer),
+            Err(vec![
+                PartitionIssue::InvalidEdge {
+                    edge_index: initializer.weighted_edges.len() as EdgeIndex
+                },
+                PartitionIssue::InvalidVirtualVertex { vertex_index: 12 },
+            ])
+        );
+        #[cfg(feature = "parallel")]
+        assert!(SolverDualParallel::try_new(&initializer, &valid_config().info(), json!({})).is_ok());
+    }
+
+    /// two repetition-code patches whose facing boundaries are linked, checked against hand-computed matchings
+    #[test]
+    fn util_parity_linked_initializer_1() {
+        // cargo test util_parity_linked_initializer_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        let weight = 100;
+        // 0(virtual) - 1 - 2 - 3 - 4(virtual)
+        let patch = SolverInitializer::new(5, (0..4).map(|i| (i, i + 1, weight)).collect(), vec![0, 4]);
+        let linked = SolverInitializer::new_parity_linked(&[patch.clone(), patch.clone()], &[vec![(0, 4), (1, 0)]]).unwrap();
+        assert_eq!(linked.vertex_offsets, vec![0, 5]);
+        assert_eq!(linked.edge_offsets, vec![0, 4]);
+        assert_eq!(linked.gadget_edges, vec![8]);
+        assert_eq!(linked.initializer.virtual_vertices, vec![0, 9]);
+        assert_eq!(linked.initializer.weighted_edges[8], (4, 5, 0));
+        let solve = |patch_defect_vertices: &[Vec<VertexIndex>]| -> (Weight, Vec<VertexIndex>) {
+            let mut solver = SolverSerial::new(&linked.initializer);
+            solver.solve(&linked.syndrome_pattern(patch_defect_vertices));
+            let mut subgraph_builder = crate::primal_module::SubGraphBuilder::new(&linked.initializer);
+            subgraph_builder.load_perfect_matching(&solver.perfect_matching());
+            let mut subgraph = subgraph_builder.get_subgraph();
+            subgraph.sort();
+            (subgraph_builder.total_weight(), subgraph)
+        };
+        // a single defect next to the linked boundary can no longer match into it alone
+        assert_eq!(solve(&[vec![3], vec![]]), (3 * weight, vec![0, 1, 2]));
+        // two defects facing each other across the linked boundary are paired through the gadget edge
+        assert_eq!(solve(&[vec![3], vec![1]]), (2 * weight, vec![3, 4, 8]));
+        // without the link, both would match to their own boundary at the same cost
+        let mut solver = SolverSerial::new(&patch);
+        solver.solve(&SyndromePattern::new_vertices(vec![3]));
+        assert_eq!(solver.sum_dual_variables(), weight);
+        // invalid links
+        let error =
+            SolverInitializer::new_parity_linked(&[patch.clone(), patch.clone()], &[vec![(0, 2), (1, 0)]]).unwrap_err();
+        assert!(error.contains("is not virtual"), "{error}");
+        let error =
+            SolverInitializer::new_parity_linked(core::slice::from_ref(&patch), &[vec![(0, 4), (1, 0)]]).unwrap_err();
+        assert!(error.contains("out of range"), "{error}");
+        let error = SolverInitializer::new_parity_linked(core::slice::from_ref(&patch), &[vec![(0, 4)]]).unwrap_err();
+        assert!(error.contains("at least two"), "{error}");
+        let error =
+            SolverInitializer::new_parity_linked(&[patch], &[vec![(0, 4), (0, 0)], vec![(0, 4), (0, 0)]]).unwrap_err();
+        assert!(error.contains("more than once"), "{error}");
+    }
+
+    /// independent decoding graphs decoded together give the same result as decoding each of them alone
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn util_concat_initializers_1() {
+        // cargo test util_concat_initializers_1 -- --nocapture
+        use crate::example_codes::*;
+        use crate::mwpm_solver::*;
+        let mut codes: Vec<Box<dyn ExampleCode>> = vec![
+            Box::new(CodeCapacityPlanarCode::new(7, 0.1, 500)),
+            Box::new(CodeCapacityRepetitionCode::new(9, 0.1, 500)),
+            Box::new(PhenomenologicalPlanarCode::new(5, 3, 0.05, 500)),
+        ];
+        let parts: Vec<SolverInitializer> = codes.iter().map(|code| code.get_initializer()).collect();
+        let (initializer, offsets) = SolverInitializer::concat(&parts);
+        assert_eq!(
+            initializer.vertex_num,
+            parts.iter().map(|part| part.vertex_num).sum::<VertexNum>()
+        );
+        assert_eq!(offsets[1].vertex_offset, parts[0].vertex_num);
+        assert_eq!(
+            offsets[2].edge_offset as usize,
+            parts[0].weighted_edges.len() + parts[1].weighted_edges.len()
+        );
+        assert_eq!(offsets[1].local_vertex(offsets[1].vertex(3)), Some(3));
+        assert_eq!(offsets[1].local_vertex(offsets[2].vertex(0)), None);
+        assert!(
+            initializer.edge_rounds.is_none(),
+            "only the phenomenological code has edge rounds"
+        );
+        codes[0].set_erasure_probability(0.05);
+        let mut solver = SolverSerial::new(&initializer);
+        let mut part_solvers: Vec<SolverSerial> = parts.iter().map(SolverSerial::new).collect();
+        for seed in 0..20 {
+            let syndrome_patterns: Vec<SyndromePattern> = codes
+                .iter_mut()
+                .enumerate()
+                .map(|(index, code)| code.generate_random_errors(seed * 3 + index as u64))
+                .collect();
+            solver.solve(&IndexOffsets::concat_syndrome_patterns(&offsets, &syndrome_patterns));
+            let subgraph = solver.subgraph();
+            let mut sum_dual_variables = 0;
+            for (index, part_solver) in part_solvers.iter_mut().enumerate() {
+                part_solver.solve(&syndrome_patterns[index]);
+                sum_dual_variables += part_solver.sum_dual_variables();
+                let local_subgraph = offsets[index].local_subgraph(&subgraph);
+                let defect_vertices: BTreeSet<VertexIndex> =
+                    syndrome_patterns[index].defect_vertices.iter().copied().collect();
+                assert_eq!(parts[index].syndrome_of(&local_subgraph), defect_vertices);
+                part_solver.clear();
+            }
+            assert_eq!(solver.sum_dual_variables(), sum_dual_variables);
+            solver.clear();
+        }
+    }
+
+    /// the histogram buckets cover every value within 1/16 relative precision, and the phases follow the events
+    #[test]
+    fn util_latency_histogram_1() {
+        // cargo test util_latency_histogram_1 -- --nocapture
+        for nanos in [0, 1, 15, 16, 17, 31, 32, 33, 1000, 123456789, u64::MAX] {
+            let (lower, upper) = LatencyHistogram::bucket_range(LatencyHistogram::bucket_index(nanos));
+            assert!(
+                lower <= nanos && (nanos < upper || upper == u64::MAX),
+                "{nanos} not in [{lower}, {upper})"
+            );
+            assert!((upper - lower) * 16 <= lower.max(16), "bucket [{lower}, {upper}) is too wide");
+        }
+        let mut histogram = LatencyHistogram::new();
+        for nanos in 1..=1000 {
+            histogram.record_nanos(nanos * 1000);
+        }
+        assert_eq!(histogram.total_count, 1000);
+        assert_eq!(histogram.value_at_percentile(0.), 1023); // the largest value of the bucket [992, 1024)
+        assert_eq!(histogram.value_at_percentile(100.), 1000000);
+        let p50 = histogram.value_at_percentile(50.);
+        assert!((500000..500000 + 500000 / 16).contains(&p50), "{p50}");
+        let p99 = histogram.value_at_percentile(99.);
+        assert!((990000..990000 + 990000 / 16).contains(&p99), "{p99}");
+        assert_eq!(histogram.mean(), 500500.);
+        let json = histogram.to_json();
+        let buckets = json["buckets"].as_array().unwrap();
+        let counted: u64 = buckets.iter().map(|bucket| bucket[2].as_u64().unwrap()).sum();
+        assert_eq!(counted, 1000);
+        // the phases are measured between consecutive events
+        let mut profiler = BenchmarkProfiler::new(0, None);
+        profiler.enable_latency_histograms();
+        for _ in 0..3 {
+            profiler.begin(&SyndromePattern::new_vertices(vec![0, 1]));
+            profiler.event("decoded".to_string());
+            profiler.event("cleared".to_string());
+            profiler.end(None);
+        }
+        let latency_histograms = profiler.latency_histograms.as_ref().unwrap();
+        assert_eq!(latency_histograms.round.total_count, 3);
+        let phase_names: Vec<_> = latency_histograms.phases.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(phase_names, vec!["decoded", "cleared"]);
+        let json = latency_histograms.to_json();
+        assert_eq!(json["phases"]["cleared"]["total_count"], 3);
+        assert!(json["schema"]["histogram"]["buckets"].is_string());
+    }
+
+    /// the conversion to petgraph is lossless, and petgraph agrees on the connected components
+    #[cfg(feature = "petgraph")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn util_petgraph_conversion_1() {
+        // cargo test util_petgraph_conversion_1 --features petgraph -- --nocapture
+        use crate::example_codes::*;
+        use petgraph::graph::{NodeIndex, UnGraph};
+        let initializer = PhenomenologicalPlanarCode::new(5, 3, 0.01, 500).get_initializer();
+        let graph = initializer.to_petgraph();
+        assert_eq!(graph.node_count(), initializer.vertex_num as usize);
+        let converted = SolverInitializer::from_petgraph(&graph).unwrap();
+        assert_eq!(converted.weighted_edges, initializer.weighted_edges);
+        let mut virtual_vertices = initializer.virtual_vertices.clone();
+        virtual_vertices.sort();
+        assert_eq!(converted.virtual_vertices, virtual_vertices);
+        // two separate components, only one of them has a virtual vertex
+        let initializer = SolverInitializer::new(5, vec![(0, 1, 2), (1, 2, 2), (3, 4, 2)], vec![4]);
+        let graph = initializer.to_petgraph();
+        assert_eq!(petgraph::algo::connected_components(&graph), 2);
+        let connected_components = ConnectedComponents::new(&initializer);
+        for (i, j, _) in initializer.weighted_edges.iter() {
+            assert_eq!(
+                connected_components.vertex_components[*i as usize],
+                connected_components.vertex_components[*j as usize]
+            );
+        }
+        assert!(!connected_components.all_have_virtual);
+        assert!(graph[NodeIndex::new(4)].is_virtual);
+        // the invariants of the crate are enforced
+        let mut graph = UnGraph::<VertexMeta, Weight>::new_undirected();
+        let (a, b) = (graph.add_node(VertexMeta::default()), graph.add_node(VertexMeta::default()));
+        let edge = graph.add_edge(a, b, 3);
+        assert!(SolverInitializer::from_petgraph(&graph).is_err());
+        graph[edge] = 2;
+        assert!(SolverInitializer::from_petgraph(&graph).is_ok());
+        graph.add_edge(b, a, 4);
+        assert!(SolverInitializer::from_petgraph(&graph).is_err());
+        let mut graph = UnGraph::<VertexMeta, Weight>::new_undirected();
+        let a = graph.add_node(VertexMeta::default());
+        graph.add_edge(a, a, 2);
+        assert!(SolverInitializer::from_petgraph(&graph).is_err());
+    }
+
+    /// configured bucket edges are allocated once, and the summary reports the tail latency in seconds
+    #[test]
+    fn util_latency_histogram_bucket_edges_1() {
+        // cargo test util_latency_histogram_bucket_edges_1 -- --nocapture
+        let mut histogram = LatencyHistogram::with_bucket_edges(vec![1000, 2000, 5000]);
+        assert_eq!(histogram.counts.len(), 4);
+        let counts_ptr = histogram.counts.as_ptr();
+        for nanos in [0, 999, 1000, 1500, 4999, 5000, 1_000_000] {
+            histogram.record_nanos(nanos);
+        }
+        assert_eq!(histogram.counts.as_ptr(), counts_ptr, "recording must not reallocate");
+        assert_eq!(histogram.counts, vec![2, 2, 1, 2]);
+        assert_eq!(histogram.range_of(0), (0, 1000));
+        assert_eq!(histogram.range_of(3), (5000, u64::MAX));
+        assert_eq!(histogram.value_at_percentile(50.), 1999);
+        assert_eq!(histogram.value_at_percentile(100.), 1_000_000); // clamped to the largest recorded value
+        let json = histogram.to_json();
+        assert_eq!(json["buckets"][3], json!([5000, u64::MAX, 2]));
+        // the profiler reports the summary of the whole rounds
+        let mut profiler = BenchmarkProfiler::new(0, None);
+        assert_eq!(profiler.summary(), None);
+        profiler.enable_latency_histograms_with_bucket_edges(vec![1_000_000_000]);
+        for _ in 0..10 {
+            profiler.begin(&SyndromePattern::new_vertices(vec![0, 1]));
+            profiler.event("decoded".to_string());
+            profiler.end(None);
+        }
+        let summary = profiler.summary().unwrap();
+        assert_eq!(summary.round_count, 10);
+        assert!(summary.p50 <= summary.p99 && summary.p99 <= summary.p999 && summary.p999 <= summary.max);
+        assert!(summary.max < 1., "all the rounds fall into the first bucket");
+        let latency_histograms = profiler.latency_histograms.as_ref().unwrap();
+        assert_eq!(latency_histograms.phases[0].1.counts.len(), 2);
+        assert!(profiler.brief().contains("p99"));
+    }
+
+    /// a large failing syndrome is reduced to the few defects that actually trigger the failure
+    #[test]
+    fn util_minimize_failing_syndrome_1() {
+        // cargo test util_minimize_failing_syndrome_1 -- --nocapture
+        use crate::example_codes::*;
+        use crate::mwpm_solver::*;
+        let d = 11;
+        let mut code = CodeCapacityPlanarCode::new(d, 0.1, 500);
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(0);
+        assert!(syndrome_pattern.defect_vertices.len() > 10);
+        let mut solver = SolverSerial::new(&initializer);
+        let mut decode_weight = |syndrome_pattern: &SyndromePattern| -> Weight {
+            solver.solve(syndrome_pattern);
+            let weight = solver.sum_dual_variables();
+            solver.clear();
+            weight
+        };
+        // a fake failure: any syndrome whose minimum-weight matching is at least 3 edges
+        let threshold = 3 * 500;
+        assert!(decode_weight(&syndrome_pattern) >= threshold);
+        let minimized = minimize_failing_syndrome(&initializer, &syndrome_pattern, |_, syndrome_pattern| {
+            decode_weight(syndrome_pattern) >= threshold
+        });
+        println!("minimized: {minimized:?}");
+        assert!(minimized.defect_vertices.len() < syndrome_pattern.defect_vertices.len());
+        assert!(decode_weight(&minimized) >= threshold);
+        // 1-minimal: removing any defect fixes the fake failure
+        for i in 0..minimized.defect_vertices.len() {
+            let mut candidate = minimized.clone();
+            candidate.defect_vertices.remove(i);
+            assert!(decode_weight(&candidate) < threshold);
+        }
+    }
+
+    /// test weight summation near the maximum safe weight
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn util_checked_weight_sum_1() {
+        // cargo test util_checked_weight_sum_1 -- --nocapture
+        use crate::mwpm_solver::*;
+        assert!(checked_weight_sum([Weight::MAX, 1]).is_err());
+        assert!(checked_weight_sum([Weight::MIN, -1]).is_err());
+        assert_eq!(checked_weight_sum([Weight::MAX, 1, -2]), Ok(Weight::MAX - 1));
+        // a chain with every vertex being defect, whose total weight is close to `Weight::MAX`
+        let vertex_num = 200;
+        let max_safe_weight = max_safe_weight(Weight::MAX as i128, vertex_num);
+        let weight = max_safe_weight - max_safe_weight % 2;
+        let weighted_edges = (0..vertex_num - 1).map(|i| (i, i + 1, weight)).collect();
+        let initializer = SolverInitializer::new(vertex_num, weighted_edges, vec![]);
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&SyndromePattern::new_vertices((0..vertex_num).collect()));
+        let expected_weight = (vertex_num / 2) as Weight * weight;
+        assert_eq!(solver.sum_dual_variables(), expected_weight);
+        let mut subgraph_builder = crate::primal_module::SubGraphBuilder::new(&initializer);
+        subgraph_builder.load_perfect_matching(&solver.perfect_matching());
+        assert_eq!(subgraph_builder.checked_total_weight(), Ok(expected_weight));
+    }
 }
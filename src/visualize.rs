@@ -3,17 +3,46 @@
 //! This module helps visualize the progress of a fusion blossom algorithm
 //!
 
+#[cfg(feature = "std")]
 use crate::chrono::Local;
+use crate::prelude::*;
+use crate::rand_xoshiro::rand_core::SeedableRng;
 use crate::serde::{Deserialize, Serialize};
 use crate::serde_json;
+#[cfg(feature = "std")]
 use crate::urlencoding;
 #[cfg(feature = "python_binding")]
 use crate::util::*;
+use crate::util::{DeterministicRng, F64Rng, SolverInitializer};
+#[cfg(feature = "visualizer_gzip")]
+use flate2::write::GzEncoder;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::{Seek, SeekFrom, Write};
 
+/// the result of writing a visualizer file
+#[cfg(feature = "std")]
+pub type VisualizerResult<T> = std::io::Result<T>;
+/// the result of the visualizer methods; there is no file system without `std`, so only a visualizer without a file can
+/// be created and the snapshots are not recorded
+#[cfg(not(feature = "std"))]
+pub type VisualizerResult<T> = Result<T, VisualizerUnsupported>;
+
+/// the error of creating a visualizer with a file without `std`
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisualizerUnsupported;
+
+#[cfg(not(feature = "std"))]
+impl core::fmt::Display for VisualizerUnsupported {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "writing a visualizer file requires the `std` feature")
+    }
+}
+
 pub trait FusionVisualizer {
     /// take a snapshot, set `abbrev` to true to save space
     fn snapshot(&self, abbrev: bool) -> serde_json::Value;
@@ -35,8 +64,8 @@ macro_rules! bind_trait_fusion_visualizer {
 #[allow(unused_imports)]
 pub use bind_trait_fusion_visualizer;
 
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct VisualizePosition {
     /// vertical axis, -i is up, +i is down (left-up corner is smallest i,j)
@@ -50,7 +79,7 @@ pub struct VisualizePosition {
     pub t: f64,
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl VisualizePosition {
     /// create a visualization position
@@ -64,15 +93,165 @@ impl VisualizePosition {
     }
 }
 
+/// the output file of the visualizer; a plain file is rewritten in place so that it's always a valid JSON,
+/// while a compressed file is streamed and only becomes complete when the visualizer finishes
+#[cfg(feature = "std")]
+pub enum VisualizerFile {
+    Plain(File),
+    #[cfg(feature = "visualizer_gzip")]
+    Gzip(GzEncoder<File>),
+    #[cfg(feature = "visualizer_zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, File>),
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for VisualizerFile {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Plain(file) => f.debug_tuple("Plain").field(file).finish(),
+            #[cfg(feature = "visualizer_gzip")]
+            Self::Gzip(encoder) => f.debug_tuple("Gzip").field(encoder.get_ref()).finish(),
+            #[cfg(feature = "visualizer_zstd")]
+            Self::Zstd(encoder) => f.debug_tuple("Zstd").field(encoder.get_ref()).finish(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[allow(irrefutable_let_patterns)] // only plain files exist without the compression features
+impl VisualizerFile {
+    /// choose the encoding by the extension of the file: `.json.gz` for gzip and `.json.zst` for zstd
+    pub fn create(filepath: &str) -> std::io::Result<Self> {
+        let file = File::create(filepath)?;
+        if filepath.ends_with(".gz") {
+            #[cfg(feature = "visualizer_gzip")]
+            return Ok(Self::Gzip(GzEncoder::new(file, flate2::Compression::default())));
+            #[cfg(not(feature = "visualizer_gzip"))]
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "gzip compression of visualizer file requires the `visualizer_gzip` feature",
+            ));
+        }
+        if filepath.ends_with(".zst") {
+            #[cfg(feature = "visualizer_zstd")]
+            return Ok(Self::Zstd(zstd::stream::write::Encoder::new(file, 0)?));
+            #[cfg(not(feature = "visualizer_zstd"))]
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "zstd compression of visualizer file requires the `visualizer_zstd` feature",
+            ));
+        }
+        Ok(Self::Plain(file))
+    }
+
+    /// move the cursor before the ending `]}` of a plain file; compressed files never write them until finished
+    fn begin_append(&mut self) -> std::io::Result<()> {
+        if let Self::Plain(file) = self {
+            file.seek(SeekFrom::End(-2))?;
+        }
+        Ok(())
+    }
+
+    /// keep a plain file as a valid JSON by writing the ending `]}`, or flush the compressed stream
+    fn end_append(&mut self) -> std::io::Result<()> {
+        if let Self::Plain(file) = self {
+            file.write_all(b"]}")?;
+            file.sync_all()
+        } else {
+            self.writer().flush()
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(file) => file,
+            #[cfg(feature = "visualizer_gzip")]
+            Self::Gzip(encoder) => encoder,
+            #[cfg(feature = "visualizer_zstd")]
+            Self::Zstd(encoder) => encoder,
+        }
+    }
+
+    /// write the closing brackets and the trailer of the compressed stream; `extra_fields` are appended to the root
+    /// object after the snapshots, e.g. `,"warning":"..."`
+    fn finish(self, extra_fields: &str) -> std::io::Result<()> {
+        match self {
+            Self::Plain(mut file) => {
+                if !extra_fields.is_empty() {
+                    file.seek(SeekFrom::End(-1))?; // move the cursor before the ending }
+                    file.write_all(extra_fields.as_bytes())?;
+                    file.write_all(b"}")?;
+                    file.sync_all()?;
+                }
+            }
+            #[cfg(feature = "visualizer_gzip")]
+            Self::Gzip(mut encoder) => {
+                encoder.write_all(format!("]{extra_fields}}}").as_bytes())?;
+                encoder.finish()?.sync_all()?;
+            }
+            #[cfg(feature = "visualizer_zstd")]
+            Self::Zstd(mut encoder) => {
+                encoder.write_all(format!("]{extra_fields}}}").as_bytes())?;
+                encoder.finish()?.sync_all()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// what a visualizer does when the current file reaches its limits, see [`Visualizer::set_limits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisualizerLimitPolicy {
+    /// stop recording and leave a `"warning"` field in the file
+    Stop,
+    /// continue in a new file `<name>.part2.json`, `<name>.part3.json`, ..., each of them a complete visualizer file;
+    /// every file links to the next one using the `"next_part"` field
+    RollOver,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+struct VisualizerLimits {
+    max_snapshots: usize,
+    max_bytes: usize,
+    policy: VisualizerLimitPolicy,
+}
+
+/// the path of the `part`-th file of a rolling-over visualizer, e.g. `visualizer.part2.json` or `visualizer.part2.json.gz`
+pub fn visualizer_part_filepath(filepath: &str, part: usize) -> String {
+    if part <= 1 {
+        return filepath.to_string();
+    }
+    let file_name_start = filepath.rfind('/').map_or(0, |index| index + 1);
+    match filepath[file_name_start..].find(".json") {
+        Some(index) => {
+            let (stem, extension) = filepath.split_at(file_name_start + index);
+            format!("{stem}.part{part}{extension}")
+        }
+        None => format!("{filepath}.part{part}"),
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[derive(Debug)]
-#[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct Visualizer {
     /// save to file if applicable
-    file: Option<File>,
-    /// if waiting for the first snapshot
-    empty_snapshot: bool,
-    /// names of the snapshots
+    #[cfg(feature = "std")]
+    file: Option<VisualizerFile>,
+    /// the path of the first file, used to name the following parts when rolling over
+    filepath: Option<String>,
+    /// the beginning of the file before the snapshots, repeated in every part
+    #[cfg(feature = "std")]
+    header: Vec<u8>,
+    /// the number of bytes written to the current file, before compression
+    #[cfg(feature = "std")]
+    file_bytes: usize,
+    /// the index of the current file, starting from 1
+    part: usize,
+    #[cfg(feature = "std")]
+    limits: Option<VisualizerLimits>,
+    /// names of the snapshots in the current file
     #[cfg_attr(feature = "python_binding", pyo3(get))]
     pub snapshots: Vec<String>,
 }
@@ -336,7 +515,7 @@ pub fn snapshot_combine_values(value: &mut serde_json::Value, mut value_2: serde
             // ideally, the two primal nodes should have the same length, but here we omit it
             // assert!(primal_nodes.len() == primal_nodes_2.len(), "primal_nodes must be compatible");
             if primal_nodes_2.len() > primal_nodes.len() {
-                std::mem::swap(primal_nodes, primal_nodes_2);
+                core::mem::swap(primal_nodes, primal_nodes_2);
             }
             debug_assert!(primal_nodes.len() >= primal_nodes_2.len());
             for (primal_node_idx, primal_node) in primal_nodes.iter_mut().enumerate() {
@@ -348,7 +527,7 @@ pub fn snapshot_combine_values(value: &mut serde_json::Value, mut value_2: serde
                     continue;
                 }
                 if primal_node.is_null() {
-                    std::mem::swap(primal_node, primal_node_2);
+                    core::mem::swap(primal_node, primal_node_2);
                     continue;
                 }
                 let primal_node = primal_node.as_object_mut().expect("each primal_node must be an object");
@@ -408,43 +587,155 @@ pub fn center_positions(mut positions: Vec<VisualizePosition>) -> Vec<VisualizeP
     positions
 }
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
+/// number of power iterations used by [`auto_layout_positions`]; fixed so that the layout is reproducible
+pub const AUTO_LAYOUT_ITERATIONS: usize = 300;
+
+/// compute a deterministic 2D layout for a decoding graph that comes without positions, e.g. imported from a file.
+/// each connected component is placed using a spectral layout (the two smallest non-trivial eigenvectors of its graph
+/// Laplacian, computed with a fixed number of power iterations from a seeded initial vector), and the components are
+/// then packed row by row from the largest to the smallest. The quality is only meant to be good enough to see clusters.
+#[allow(clippy::unnecessary_cast)]
+#[cfg_attr(feature = "python_binding", pyfunction)]
+#[cfg_attr(feature = "python_binding", pyo3(signature = (initializer, seed=0)))]
+pub fn auto_layout_positions(initializer: &SolverInitializer, seed: u64) -> Vec<VisualizePosition> {
+    let vertex_num = initializer.vertex_num as usize;
+    let mut neighbors: Vec<Vec<usize>> = vec![vec![]; vertex_num];
+    for &(left_vertex, right_vertex, _) in initializer.weighted_edges.iter() {
+        let (left_vertex, right_vertex) = (left_vertex as usize, right_vertex as usize);
+        if left_vertex != right_vertex {
+            neighbors[left_vertex].push(right_vertex);
+            neighbors[right_vertex].push(left_vertex);
+        }
+    }
+    // find connected components in a deterministic order
+    let mut component_of = vec![usize::MAX; vertex_num];
+    let mut components: Vec<Vec<usize>> = vec![];
+    for start in 0..vertex_num {
+        if component_of[start] != usize::MAX {
+            continue;
+        }
+        let component_index = components.len();
+        let mut component = vec![start];
+        component_of[start] = component_index;
+        let mut head = 0;
+        while head < component.len() {
+            let vertex = component[head];
+            head += 1;
+            for &neighbor in neighbors[vertex].iter() {
+                if component_of[neighbor] == usize::MAX {
+                    component_of[neighbor] = component_index;
+                    component.push(neighbor);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components.sort_by_key(|component| core::cmp::Reverse(component.len())); // stable, so ties keep vertex order
+    let mut rng = DeterministicRng::seed_from_u64(seed);
+    let mut positions = vec![VisualizePosition::new(0., 0., 0.); vertex_num];
+    let total_side = (vertex_num as f64).sqrt().ceil().max(1.);
+    let (mut cursor_i, mut cursor_j, mut row_height) = (0., 0., 0f64);
+    for component in components.iter() {
+        let side = (component.len() as f64).sqrt().ceil();
+        let local = spectral_layout(component, &neighbors, &mut rng);
+        if cursor_j > 0. && cursor_j + side > total_side {
+            cursor_i += row_height + 1.;
+            cursor_j = 0.;
+            row_height = 0.;
+        }
+        for (&vertex, &(i, j)) in component.iter().zip(local.iter()) {
+            positions[vertex] = VisualizePosition::new(cursor_i + i * side, cursor_j + j * side, 0.);
+        }
+        cursor_j += side + 1.;
+        row_height = row_height.max(side);
+    }
+    positions
+}
+
+/// spectral layout of a single connected component, returning coordinates normalized to `[0, 1]`
+fn spectral_layout(component: &[usize], neighbors: &[Vec<usize>], rng: &mut DeterministicRng) -> Vec<(f64, f64)> {
+    let n = component.len();
+    if n <= 2 {
+        return (0..n).map(|idx| (0., idx as f64)).collect();
+    }
+    let local_index: HashMap<usize, usize> = component.iter().enumerate().map(|(idx, &vertex)| (vertex, idx)).collect();
+    let local_neighbors: Vec<Vec<usize>> = component
+        .iter()
+        .map(|&vertex| neighbors[vertex].iter().map(|neighbor| local_index[neighbor]).collect())
+        .collect();
+    let max_degree = local_neighbors.iter().map(|list| list.len()).max().unwrap_or(0);
+    // power iteration on (c I - L) converges to the eigenvectors of the smallest eigenvalues of L
+    let shift = 2. * max_degree as f64 + 1.;
+    let deflate = |vector: &mut Vec<f64>, basis: &[Vec<f64>]| {
+        let mean = vector.iter().sum::<f64>() / n as f64;
+        vector.iter_mut().for_each(|x| *x -= mean);
+        for base in basis.iter() {
+            let projection: f64 = vector.iter().zip(base.iter()).map(|(x, y)| x * y).sum();
+            vector.iter_mut().zip(base.iter()).for_each(|(x, y)| *x -= projection * y);
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 0. {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+    };
+    let mut basis: Vec<Vec<f64>> = vec![];
+    for _ in 0..2 {
+        let mut vector: Vec<f64> = (0..n).map(|_| rng.next_f64() - 0.5).collect();
+        deflate(&mut vector, &basis);
+        for _ in 0..AUTO_LAYOUT_ITERATIONS {
+            let mut next: Vec<f64> = (0..n)
+                .map(|idx| {
+                    let neighbor_sum: f64 = local_neighbors[idx].iter().map(|&neighbor| vector[neighbor]).sum();
+                    (shift - local_neighbors[idx].len() as f64) * vector[idx] + neighbor_sum
+                })
+                .collect();
+            deflate(&mut next, &basis);
+            vector = next;
+        }
+        basis.push(vector);
+    }
+    let normalize = |vector: &[f64]| -> Vec<f64> {
+        let min = vector.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = vector.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+        vector
+            .iter()
+            .map(|x| if range > 0. { (x - min) / range } else { 0.5 })
+            .collect()
+    };
+    let (coordinate_j, coordinate_i) = (normalize(&basis[0]), normalize(&basis[1]));
+    coordinate_i.into_iter().zip(coordinate_j).collect()
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval::cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl Visualizer {
     /// create a new visualizer with target filename and node layout
     #[cfg_attr(feature = "python_binding", new)]
     #[cfg_attr(feature = "python_binding", pyo3(signature = (filepath, positions=vec![], center=true)))]
-    pub fn new(mut filepath: Option<String>, mut positions: Vec<VisualizePosition>, center: bool) -> std::io::Result<Self> {
-        if cfg!(feature = "disable_visualizer") {
-            filepath = None; // do not open file
-        }
-        if center {
-            positions = center_positions(positions);
-        }
-        let mut file = match filepath {
-            Some(filepath) => Some(File::create(filepath)?),
-            None => None,
-        };
-        if let Some(file) = file.as_mut() {
-            file.set_len(0)?; // truncate the file
-            file.seek(SeekFrom::Start(0))?; // move the cursor to the front
-            file.write_all(
-                format!(
-                    "{{\"format\":\"fusion_blossom\",\"version\":\"{}\"",
-                    env!("CARGO_PKG_VERSION")
-                )
-                .as_bytes(),
-            )?;
-            file.write_all(b",\"positions\":")?;
-            file.write_all(json!(positions).to_string().as_bytes())?;
-            file.write_all(b",\"snapshots\":[]}")?;
-            file.sync_all()?;
-        }
-        Ok(Self {
-            file,
-            empty_snapshot: true,
-            snapshots: vec![],
-        })
+    pub fn new(filepath: Option<String>, positions: Vec<VisualizePosition>, center: bool) -> VisualizerResult<Self> {
+        Self::new_optional_initializer(filepath, positions, center, None)
+    }
+
+    /// create a new visualizer that also embeds the decoding graph in the file header, so that the visualization
+    /// is self-contained: the edge weights and virtual vertices can be recovered without the code that generated them
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (filepath, positions, initializer, center=true)))]
+    pub fn new_with_initializer(
+        filepath: Option<String>,
+        positions: Vec<VisualizePosition>,
+        initializer: &SolverInitializer,
+        center: bool,
+    ) -> VisualizerResult<Self> {
+        Self::new_optional_initializer(filepath, positions, center, Some(initializer))
+    }
+
+    /// create a new visualizer for a decoding graph without positions, see [`auto_layout_positions`];
+    /// the initializer is embedded in the file header, see [`Visualizer::new_with_initializer`]
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (filepath, initializer, seed=0)))]
+    pub fn new_auto_layout(filepath: Option<String>, initializer: &SolverInitializer, seed: u64) -> VisualizerResult<Self> {
+        Self::new_with_initializer(filepath, auto_layout_positions(initializer, seed), initializer, true)
     }
 
     #[cfg(feature = "python_binding")]
@@ -489,26 +780,192 @@ impl Visualizer {
         let value = pyobject_to_json(value_py);
         self.snapshot_value(name, value)
     }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "set_limits", signature = (max_snapshots=None, max_bytes=None, roll_over=false))]
+    fn set_limits_py(&mut self, max_snapshots: Option<usize>, max_bytes: Option<usize>, roll_over: bool) {
+        let policy = if roll_over {
+            VisualizerLimitPolicy::RollOver
+        } else {
+            VisualizerLimitPolicy::Stop
+        };
+        self.set_limits(max_snapshots, max_bytes, policy)
+    }
 }
 
 impl Visualizer {
-    pub fn incremental_save(&mut self, name: String, value: serde_json::Value) -> std::io::Result<()> {
-        if let Some(file) = self.file.as_mut() {
-            self.snapshots.push(name.clone());
-            file.seek(SeekFrom::End(-2))?; // move the cursor before the ending ]}
-            if !self.empty_snapshot {
-                file.write_all(b",")?;
+    fn new_optional_initializer(
+        mut filepath: Option<String>,
+        mut positions: Vec<VisualizePosition>,
+        center: bool,
+        initializer: Option<&SolverInitializer>,
+    ) -> VisualizerResult<Self> {
+        if cfg!(feature = "disable_visualizer") {
+            filepath = None; // do not open file
+        }
+        if center {
+            positions = center_positions(positions);
+        }
+        let mut header = format!(
+            "{{\"format\":\"fusion_blossom\",\"version\":\"{}\"",
+            env!("CARGO_PKG_VERSION")
+        )
+        .into_bytes();
+        header.extend_from_slice(b",\"positions\":");
+        header.extend_from_slice(json!(positions).to_string().as_bytes());
+        if let Some(initializer) = initializer {
+            header.extend_from_slice(b",\"initializer\":");
+            header.extend_from_slice(json!(initializer).to_string().as_bytes());
+        }
+        header.extend_from_slice(b",\"snapshots\":[");
+        #[cfg(feature = "std")]
+        let file = match filepath.as_ref() {
+            Some(filepath) => Some(Self::create_file(filepath, &header)?),
+            None => None,
+        };
+        #[cfg(not(feature = "std"))]
+        if filepath.is_some() {
+            return Err(VisualizerUnsupported);
+        }
+        Ok(Self {
+            #[cfg(feature = "std")]
+            file,
+            filepath,
+            #[cfg(feature = "std")]
+            file_bytes: header.len(),
+            #[cfg(feature = "std")]
+            header,
+            part: 1,
+            #[cfg(feature = "std")]
+            limits: None,
+            snapshots: vec![],
+        })
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(irrefutable_let_patterns)] // only plain files exist without the compression features
+    fn create_file(filepath: &str, header: &[u8]) -> std::io::Result<VisualizerFile> {
+        let mut file = VisualizerFile::create(filepath)?;
+        if let VisualizerFile::Plain(file) = &mut file {
+            file.set_len(0)?; // truncate the file
+            file.seek(SeekFrom::Start(0))?; // move the cursor to the front
+        }
+        file.writer().write_all(header)?;
+        file.end_append()?;
+        Ok(file)
+    }
+
+    /// limit the number of snapshots and the (uncompressed) size of each file, so that a long run with the visualizer
+    /// enabled doesn't fill the disk; a file always accepts at least one snapshot even if it exceeds `max_bytes`
+    #[cfg(feature = "std")]
+    pub fn set_limits(&mut self, max_snapshots: Option<usize>, max_bytes: Option<usize>, policy: VisualizerLimitPolicy) {
+        assert!(
+            max_snapshots != Some(0),
+            "a visualizer file must be able to hold at least one snapshot"
+        );
+        self.limits = Some(VisualizerLimits {
+            max_snapshots: max_snapshots.unwrap_or(usize::MAX),
+            max_bytes: max_bytes.unwrap_or(usize::MAX),
+            policy,
+        });
+    }
+
+    /// see [`Visualizer::set_limits`]
+    #[cfg(feature = "std")]
+    pub fn with_limits(
+        mut self,
+        max_snapshots: Option<usize>,
+        max_bytes: Option<usize>,
+        policy: VisualizerLimitPolicy,
+    ) -> Self {
+        self.set_limits(max_snapshots, max_bytes, policy);
+        self
+    }
+
+    /// the paths of all the files written so far, more than one only when rolling over
+    pub fn part_filepaths(&self) -> Vec<String> {
+        match self.filepath.as_ref() {
+            Some(filepath) => (1..=self.part).map(|part| visualizer_part_filepath(filepath, part)).collect(),
+            None => vec![],
+        }
+    }
+
+    /// without `std` there is no file to save the snapshot to
+    #[cfg(not(feature = "std"))]
+    pub fn incremental_save(&mut self, _name: String, _value: serde_json::Value) -> VisualizerResult<()> {
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn incremental_save(&mut self, name: String, value: serde_json::Value) -> VisualizerResult<()> {
+        if self.file.is_none() {
+            return Ok(());
+        }
+        let snapshot = json!((&name, value)).to_string();
+        if let Some(limits) = self.limits.as_ref() {
+            let bytes = self.file_bytes + snapshot.len() + 1;
+            if !self.snapshots.is_empty() && (self.snapshots.len() >= limits.max_snapshots || bytes > limits.max_bytes) {
+                match limits.policy {
+                    VisualizerLimitPolicy::Stop => {
+                        let warning = format!(
+                            "recording stopped after {} snapshots because of the limits of {} snapshots and {} bytes",
+                            self.snapshots.len(),
+                            limits.max_snapshots,
+                            limits.max_bytes
+                        );
+                        log_warn!(snapshots = self.snapshots.len(); "[warning] visualizer {warning}");
+                        let file = self.file.take().unwrap();
+                        return file.finish(&format!(",\"warning\":{}", json!(warning)));
+                    }
+                    VisualizerLimitPolicy::RollOver => self.roll_over()?,
+                }
             }
-            self.empty_snapshot = false;
-            file.write_all(json!((name, value)).to_string().as_bytes())?;
-            file.write_all(b"]}")?;
-            file.sync_all()?;
+        }
+        let file = self.file.as_mut().unwrap();
+        file.begin_append()?; // move the cursor before the ending ]}
+        let writer = file.writer();
+        if !self.snapshots.is_empty() {
+            writer.write_all(b",")?;
+            self.file_bytes += 1;
+        }
+        writer.write_all(snapshot.as_bytes())?;
+        file.end_append()?;
+        self.file_bytes += snapshot.len();
+        self.snapshots.push(name);
+        Ok(())
+    }
+
+    /// complete the current file and continue in the next part
+    #[cfg(feature = "std")]
+    fn roll_over(&mut self) -> VisualizerResult<()> {
+        let filepath = self.filepath.as_ref().unwrap();
+        self.part += 1;
+        let next_filepath = visualizer_part_filepath(filepath, self.part);
+        let next_filename = std::path::Path::new(&next_filepath).file_name().unwrap().to_string_lossy();
+        let file = self.file.take().unwrap();
+        file.finish(&format!(",\"next_part\":{}", json!(next_filename)))?;
+        self.file = Some(Self::create_file(&next_filepath, &self.header)?);
+        self.file_bytes = self.header.len();
+        self.snapshots.clear();
+        Ok(())
+    }
+
+    /// complete the visualizer file; this is required for compressed files and is automatically called on drop.
+    /// any further snapshots will not be saved
+    pub fn finish(&mut self) -> VisualizerResult<()> {
+        #[cfg(feature = "std")]
+        if let Some(file) = self.file.take() {
+            file.finish("")?;
         }
         Ok(())
     }
 
     /// append another snapshot of the fusion type, and also update the file in case
-    pub fn snapshot_combined(&mut self, name: String, fusion_algorithms: Vec<&dyn FusionVisualizer>) -> std::io::Result<()> {
+    pub fn snapshot_combined(
+        &mut self,
+        name: String,
+        fusion_algorithms: Vec<&dyn FusionVisualizer>,
+    ) -> VisualizerResult<()> {
         if cfg!(feature = "disable_visualizer") {
             return Ok(());
         }
@@ -524,7 +981,7 @@ impl Visualizer {
     }
 
     /// append another snapshot of the fusion type, and also update the file in case
-    pub fn snapshot(&mut self, name: String, fusion_algorithm: &impl FusionVisualizer) -> std::io::Result<()> {
+    pub fn snapshot(&mut self, name: String, fusion_algorithm: &impl FusionVisualizer) -> VisualizerResult<()> {
         if cfg!(feature = "disable_visualizer") {
             return Ok(());
         }
@@ -535,7 +992,7 @@ impl Visualizer {
         Ok(())
     }
 
-    pub fn snapshot_combined_value(&mut self, name: String, values: Vec<serde_json::Value>) -> std::io::Result<()> {
+    pub fn snapshot_combined_value(&mut self, name: String, values: Vec<serde_json::Value>) -> VisualizerResult<()> {
         if cfg!(feature = "disable_visualizer") {
             return Ok(());
         }
@@ -549,7 +1006,7 @@ impl Visualizer {
         Ok(())
     }
 
-    pub fn snapshot_value(&mut self, name: String, mut value: serde_json::Value) -> std::io::Result<()> {
+    pub fn snapshot_value(&mut self, name: String, mut value: serde_json::Value) -> VisualizerResult<()> {
         if cfg!(feature = "disable_visualizer") {
             return Ok(());
         }
@@ -560,6 +1017,14 @@ impl Visualizer {
     }
 }
 
+impl Drop for Visualizer {
+    fn drop(&mut self) {
+        if let Err(error) = self.finish() {
+            log_warn!(error = error.to_string().as_str(); "[warning] failed to finish visualizer file: {error}");
+        }
+    }
+}
+
 const DEFAULT_VISUALIZE_DATA_FOLDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/visualize/data/");
 
 // only used locally, because this is compile time directory
@@ -572,11 +1037,13 @@ pub fn static_visualize_data_filename() -> String {
     "visualizer.json".to_string()
 }
 
+#[cfg(feature = "std")]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn auto_visualize_data_filename() -> String {
     format!("{}.json", Local::now().format("%Y%m%d-%H-%M-%S%.3f"))
 }
 
+#[cfg(feature = "std")]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn print_visualize_link_with_parameters(filename: String, parameters: Vec<(String, String)>) {
     let default_port = if cfg!(feature = "python_binding") { 51666 } else { 8066 };
@@ -597,6 +1064,7 @@ pub fn print_visualize_link_with_parameters(filename: String, parameters: Vec<(S
     }
 }
 
+#[cfg(feature = "std")]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn print_visualize_link(filename: String) {
     print_visualize_link_with_parameters(filename, Vec::new())
@@ -612,6 +1080,7 @@ pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(print_visualize_link_with_parameters, m)?)?;
     m.add_function(wrap_pyfunction!(print_visualize_link, m)?)?;
     m.add_function(wrap_pyfunction!(center_positions, m)?)?;
+    m.add_function(wrap_pyfunction!(auto_layout_positions, m)?)?;
     Ok(())
 }
 
@@ -915,7 +1384,7 @@ mod tests {
         //                        0   1   2   3   4   5   6   7   8    9
         //                        A  vA   B  vB   C  vC   D  vD   E   vE
         let kept_vertices = [39, 47, 52, 59, 63, 71, 90, 94, 100, 107]; // including some virtual vertices
-        let mut old_to_new = std::collections::BTreeMap::<DefectIndex, DefectIndex>::new();
+        let mut old_to_new = alloc::collections::BTreeMap::<DefectIndex, DefectIndex>::new();
         for (new_index, defect_vertex) in kept_vertices.iter().enumerate() {
             old_to_new.insert(*defect_vertex, new_index as DefectIndex);
         }
@@ -996,4 +1465,221 @@ mod tests {
                 .unwrap();
         }
     }
+
+    /// write the same decoding process into plain and compressed files, and compare their sizes
+    #[test]
+    fn visualize_compressed_data_file() {
+        // cargo test visualize_compressed_data_file -- --nocapture
+        use super::super::mwpm_solver::*;
+        use std::io::Read;
+        let half_weight = 500;
+        let mut code = PhenomenologicalPlanarCode::new(11, 11, 0.01, half_weight);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let initializer = code.get_initializer();
+        let mut extensions = vec!["json"];
+        if cfg!(feature = "visualizer_gzip") {
+            extensions.push("json.gz");
+        }
+        if cfg!(feature = "visualizer_zstd") {
+            extensions.push("json.zst");
+        }
+        let mut contents = vec![];
+        for extension in extensions.iter() {
+            let visualize_filename = format!("visualize_compressed_data_file.{extension}");
+            let filepath = visualize_data_folder() + visualize_filename.as_str();
+            let mut visualizer = Visualizer::new(Some(filepath.clone()), code.get_positions(), true).unwrap();
+            print_visualize_link(visualize_filename.clone());
+            let mut solver = SolverSerial::new(&initializer);
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            visualizer.finish().unwrap();
+            let file_size = std::fs::metadata(&filepath).unwrap().len();
+            println!("{visualize_filename}: {file_size} bytes");
+            let file = File::open(&filepath).unwrap();
+            let mut content = String::new();
+            match *extension {
+                "json" => std::io::BufReader::new(file).read_to_string(&mut content).unwrap(),
+                #[cfg(feature = "visualizer_gzip")]
+                "json.gz" => flate2::read::GzDecoder::new(file).read_to_string(&mut content).unwrap(),
+                #[cfg(feature = "visualizer_zstd")]
+                "json.zst" => zstd::stream::read::Decoder::new(file)
+                    .unwrap()
+                    .read_to_string(&mut content)
+                    .unwrap(),
+                _ => unreachable!(),
+            };
+            contents.push((file_size, serde_json::from_str::<serde_json::Value>(&content).unwrap()));
+        }
+        let (plain_size, plain_value) = &contents[0];
+        for (file_size, value) in contents.iter().skip(1) {
+            assert_eq!(value, plain_value, "compressed file must decode to the same data");
+            assert!(file_size < plain_size, "compression doesn't reduce the file size");
+            println!("compression ratio: {:.2}", *plain_size as f64 / *file_size as f64);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_auto_layout() {
+        // cargo test visualize_auto_layout -- --nocapture
+        use super::super::mwpm_solver::*;
+        let visualize_filename = "visualize_auto_layout.json".to_string();
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let syndrome_pattern = code.generate_random_errors(1);
+        // append a disconnected copy of the graph, which should be placed as a separate cluster
+        let mut initializer = code.get_initializer();
+        let vertex_num = initializer.vertex_num;
+        let copied_edges: Vec<_> = initializer
+            .weighted_edges
+            .iter()
+            .map(|&(a, b, w)| (a + vertex_num, b + vertex_num, w))
+            .collect();
+        initializer.weighted_edges.extend(copied_edges);
+        initializer.vertex_num *= 2;
+        let positions = auto_layout_positions(&initializer, 0);
+        assert_eq!(positions.len(), initializer.vertex_num as usize);
+        assert_eq!(
+            json!(positions),
+            json!(auto_layout_positions(&initializer, 0)),
+            "layout must be deterministic"
+        );
+        assert!(positions
+            .iter()
+            .all(|position| position.i.is_finite() && position.j.is_finite()));
+        let half = vertex_num as usize;
+        let bounding_box = |range: core::ops::Range<usize>, axis: fn(&VisualizePosition) -> f64| {
+            let values: Vec<f64> = positions[range].iter().map(axis).collect();
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            (min, max)
+        };
+        let separated = |axis: fn(&VisualizePosition) -> f64| {
+            let (min_1, max_1) = bounding_box(0..half, axis);
+            let (min_2, max_2) = bounding_box(half..2 * half, axis);
+            max_1 < min_2 || max_2 < min_1
+        };
+        assert!(
+            separated(|position| position.i) || separated(|position| position.j),
+            "disconnected components should not overlap"
+        );
+        let mut visualizer = Visualizer::new_auto_layout(
+            Some(visualize_data_folder() + visualize_filename.as_str()),
+            &code.get_initializer(),
+            0,
+        )
+        .unwrap();
+        print_visualize_link(visualize_filename);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+    }
+
+    /// the embedded initializer makes the visualization file self-contained
+    #[test]
+    fn visualize_embedded_initializer() {
+        // cargo test visualize_embedded_initializer -- --nocapture
+        use super::super::mwpm_solver::*;
+        let visualize_filename = "visualize_embedded_initializer.json".to_string();
+        let filepath = visualize_data_folder() + visualize_filename.as_str();
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, half_weight);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let initializer = code.get_initializer();
+        let mut visualizer =
+            Visualizer::new_with_initializer(Some(filepath.clone()), code.get_positions(), &initializer, true).unwrap();
+        print_visualize_link(visualize_filename);
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        visualizer.finish().unwrap();
+        let content: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&filepath).unwrap()).unwrap();
+        let embedded: SolverInitializer = serde_json::from_value(content["initializer"].clone()).unwrap();
+        assert_eq!(embedded.vertex_num, initializer.vertex_num);
+        assert_eq!(embedded.weighted_edges, initializer.weighted_edges);
+        assert_eq!(embedded.virtual_vertices, initializer.virtual_vertices);
+        assert!(!content["snapshots"].as_array().unwrap().is_empty());
+        // the plain constructor does not embed the initializer
+        let mut visualizer = Visualizer::new(Some(filepath.clone()), code.get_positions(), true).unwrap();
+        visualizer.finish().unwrap();
+        let content: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&filepath).unwrap()).unwrap();
+        assert!(content.get("initializer").is_none());
+    }
+
+    #[test]
+    fn visualize_limits() {
+        // cargo test visualize_limits -- --nocapture
+        use std::io::Read;
+        let read_value = |filepath: &str| -> serde_json::Value {
+            let file = File::open(filepath).unwrap();
+            let mut content = String::new();
+            #[cfg(feature = "visualizer_gzip")]
+            if filepath.ends_with(".gz") {
+                flate2::read::GzDecoder::new(file).read_to_string(&mut content).unwrap();
+                return serde_json::from_str(&content).unwrap();
+            }
+            std::io::BufReader::new(file).read_to_string(&mut content).unwrap();
+            serde_json::from_str(&content).unwrap()
+        };
+        let positions = vec![VisualizePosition::new(0., 0., 0.), VisualizePosition::new(0., 1., 0.)];
+        let snapshot = |index: usize| json!({ "payload": "x".repeat(100), "index": index });
+        // stop after 3 snapshots and leave a warning
+        let filepath = visualize_data_folder() + "visualize_limits_stop.json";
+        let mut visualizer = Visualizer::new(Some(filepath.clone()), positions.clone(), true)
+            .unwrap()
+            .with_limits(Some(3), None, VisualizerLimitPolicy::Stop);
+        for index in 0..5 {
+            visualizer.incremental_save(format!("{index}"), snapshot(index)).unwrap();
+            // the file is loadable at any time
+            read_value(&filepath);
+        }
+        visualizer.finish().unwrap();
+        let value = read_value(&filepath);
+        assert_eq!(value["snapshots"].as_array().unwrap().len(), 3);
+        assert!(value["warning"].as_str().unwrap().contains("3 snapshots"));
+        assert_eq!(visualizer.part_filepaths(), vec![filepath]);
+        // roll over to the next part when the file is too large, for both plain and compressed files
+        let mut extensions = vec!["json"];
+        if cfg!(feature = "visualizer_gzip") {
+            extensions.push("json.gz");
+        }
+        for extension in extensions {
+            let filepath = visualize_data_folder() + format!("visualize_limits_roll_over.{extension}").as_str();
+            let mut visualizer = Visualizer::new(Some(filepath.clone()), positions.clone(), true)
+                .unwrap()
+                .with_limits(None, Some(400), VisualizerLimitPolicy::RollOver);
+            for index in 0..5 {
+                visualizer.incremental_save(format!("{index}"), snapshot(index)).unwrap();
+            }
+            visualizer.finish().unwrap();
+            let part_filepaths = visualizer.part_filepaths();
+            assert_eq!(part_filepaths.len(), 3);
+            assert_eq!(
+                part_filepaths[1],
+                visualize_data_folder() + format!("visualize_limits_roll_over.part2.{extension}").as_str()
+            );
+            let mut indices = vec![];
+            for (part, part_filepath) in part_filepaths.iter().enumerate() {
+                let value = read_value(part_filepath);
+                assert_eq!(value["positions"].as_array().unwrap().len(), 2);
+                for (_, snapshot) in
+                    serde_json::from_value::<Vec<(String, serde_json::Value)>>(value["snapshots"].clone()).unwrap()
+                {
+                    indices.push(snapshot["index"].as_u64().unwrap());
+                }
+                match part_filepaths.get(part + 1) {
+                    Some(next_filepath) => assert!(next_filepath.ends_with(value["next_part"].as_str().unwrap())),
+                    None => assert!(value.get("next_part").is_none()),
+                }
+            }
+            assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+        }
+        // a single snapshot larger than the limit is still recorded
+        let filepath = visualize_data_folder() + "visualize_limits_large.json";
+        let mut visualizer = Visualizer::new(Some(filepath.clone()), positions, true).unwrap().with_limits(
+            None,
+            Some(10),
+            VisualizerLimitPolicy::Stop,
+        );
+        visualizer.incremental_save("0".to_string(), snapshot(0)).unwrap();
+        visualizer.finish().unwrap();
+        assert_eq!(read_value(&filepath)["snapshots"].as_array().unwrap().len(), 1);
+    }
 }
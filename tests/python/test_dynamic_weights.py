@@ -39,6 +39,37 @@ def test_erasure_weight():
     assert subgraph == [0, 2]
 
 
+def test_erasure_regions():
+    syndrome_pattern = fb.SyndromePattern([1, 2])
+    assert syndrome_pattern.erasure_regions == []
+    syndrome_pattern.erasure_regions = [{"VertexRange": [0, 2]}, {"VertexRange": [2, 4]}]
+    assert syndrome_pattern.erasure_regions == [{"VertexRange": [0, 2]}, {"VertexRange": [2, 4]}]
+    solver = prepare_solver()
+    solver.solve(syndrome_pattern)
+    subgraph = solver.subgraph()
+    assert subgraph == [0, 2]
+
+    try:
+        syndrome_pattern.erasure_regions = [{"UnknownRegion": 0}]
+        assert False, "unknown region must be rejected"
+    except ValueError:
+        pass
+
+
+def test_erasure_mask():
+    solver = prepare_solver()
+    solver.solve_with_erasure_mask([1, 2], [True, False, True])
+    subgraph = solver.subgraph()
+    assert subgraph == [0, 2]
+    solver.clear()
+
+    try:
+        solver.solve_with_erasure_mask([1, 2], [True, False])
+        assert False, "mask length must equal the number of edges"
+    except ValueError:
+        pass
+
+
 def test_dynamic_weight():
     solver = prepare_solver()
     solver.solve(fb.SyndromePattern(
@@ -103,3 +134,13 @@ def test_repetition_code():
     solver, visualizer = prepare_repetition_code_solver(100)
     solver.solve_visualizer(fb.SyndromePattern([]), visualizer)
     solver.subgraph(visualizer)
+
+
+def test_structural_hash():
+    initializer = fb.SolverInitializer(4, [(0, 1, 100), (1, 2, 200), (2, 3, 100)], [0, 3])
+    # pinned: the same value as the Rust test `util_structural_hash_1`
+    assert initializer.structural_hash() == 0x54b29e0822753308
+    reordered = fb.SolverInitializer(4, [(3, 2, 100), (0, 1, 100), (2, 1, 200)], [3, 0])
+    assert reordered.structural_hash() == initializer.structural_hash()
+    reweighted = fb.SolverInitializer(4, [(0, 1, 100), (1, 2, 202), (2, 3, 100)], [0, 3])
+    assert reweighted.structural_hash() != initializer.structural_hash()
@@ -0,0 +1,91 @@
+"""
+Drive the primal loop from Python using the dual state inspection primitives of `SolverSerial`.
+This is slow and only meant for prototyping primal heuristics on small instances.
+"""
+
+import pytest
+import fusion_blossom as fb
+
+
+def greedy_primal(solver: fb.SolverSerial, syndrome: fb.SyndromePattern) -> dict:
+    """
+    a trivial greedy primal: a node is matched to whatever it touches first and then stops growing;
+    it never creates blossoms, so it gives up when a node touches an already matched node
+    """
+    solver.load_syndrome(syndrome)
+    matched = {}  # node index -> peer node index, or None if matched to a virtual vertex
+    while not solver.is_converged():
+        step = solver.compute_maximum_update_length()
+        if isinstance(step, int):
+            solver.grow(step)
+            continue
+        for conflict in step:
+            if conflict["type"] == "conflicting":
+                node_1, node_2 = conflict["node_1"], conflict["node_2"]
+                if node_1 in matched and node_2 in matched:
+                    continue  # the same conflict may be reported from both sides
+                if node_1 in matched or node_2 in matched:
+                    raise NotImplementedError("greedy primal cannot rematch nodes")
+                matched[node_1] = node_2
+                matched[node_2] = node_1
+                solver.set_grow_state(node_1, "stay")
+                solver.set_grow_state(node_2, "stay")
+            elif conflict["type"] == "touching_virtual":
+                node = conflict["node"]
+                if node not in matched:
+                    matched[node] = None
+                    solver.set_grow_state(node, "stay")
+            else:
+                raise NotImplementedError(f"greedy primal cannot resolve {conflict}")
+    return matched
+
+
+def test_greedy_primal():
+    code = fb.CodeCapacityRepetitionCode(d=15, p=0.1, max_half_weight=500)
+    initializer = code.get_initializer()
+    defect_vertices = [1, 2, 6, 7, 13]
+    syndrome = fb.SyndromePattern(defect_vertices)
+    solver = fb.SolverSerial(initializer)
+    matched = greedy_primal(solver, syndrome)
+    # every defect is matched exactly once, either to another defect or to a virtual vertex
+    dual_state = solver.dual_state()
+    defect_of_node = {node["index"]: node["defect_vertex"] for node in dual_state["interface_nodes"]}
+    assert sorted(defect_of_node.values()) == defect_vertices
+    assert sorted(matched.keys()) == sorted(defect_of_node.keys())
+    for node, peer in matched.items():
+        assert peer is None or matched[peer] == node
+    assert all(node["grow_state"] == "stay" for node in dual_state["interface_nodes"])
+    # the dual variables remain feasible, so their sum is a lower bound of the minimum weight
+    reference = fb.SolverSerial(initializer)
+    reference.solve(syndrome)
+    assert solver.sum_dual_variables() <= reference.sum_dual_variables()
+
+
+def test_illegal_primitives():
+    code = fb.CodeCapacityRepetitionCode(d=5, p=0.1, max_half_weight=500)
+    solver = fb.SolverSerial(code.get_initializer())
+    solver.load_syndrome(fb.SyndromePattern([1, 2]))
+    with pytest.raises(ValueError):
+        solver.set_grow_state(0, "jump")
+    with pytest.raises(ValueError):
+        solver.set_grow_state(5, "stay")
+    length = solver.compute_maximum_update_length()
+    assert isinstance(length, int)
+    with pytest.raises(ValueError):
+        solver.grow(length + 1)
+
+
+def test_grow_dual_node_checked():
+    code = fb.CodeCapacityRepetitionCode(d=5, p=0.1, max_half_weight=500)
+    solver = fb.SolverSerial(code.get_initializer())
+    solver.load_syndrome(fb.SyndromePattern([1, 2]))
+    dual_state = solver.dual_state()
+    with pytest.raises(ValueError):
+        solver.grow_dual_node(0, 1500)
+    assert solver.dual_state() == dual_state
+    solver.grow_dual_node(0, 400)
+    assert solver.sum_dual_variables() == 400
+    with pytest.raises(ValueError):  # only 600 left on the shared edge
+        solver.grow_dual_node(1, 700)
+    with pytest.raises(ValueError):
+        solver.grow_dual_node(5, 1)
@@ -43,3 +43,13 @@ def test_mwpm_decoder():
 def test_mixture_decoder():
     # http://localhost:8066/?filename=test_max_tree_size_10.json
     solver_tester(max_tree_size=10)
+
+
+def test_match_counts():
+    code = fb.CodeCapacityPlanarCode(d=11, p=0.05, max_half_weight=500)
+    syndrome = fb.SyndromePattern([39, 52, 63, 90, 100])
+    for max_tree_size in [0, None]:
+        solver = fb.SolverSerial(code.get_initializer(), max_tree_size=max_tree_size)
+        solver.solve(syndrome)
+        perfect_matching = solver.perfect_matching()
+        assert 2 * perfect_matching.peer_match_count() + perfect_matching.boundary_match_count() == 5
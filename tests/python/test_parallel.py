@@ -0,0 +1,75 @@
+"""
+Partition a phenomenological code along the time axis and check that the parallel solver finds
+the same minimum weight as the serial solver
+"""
+
+import fusion_blossom as fb
+
+
+d = 5
+noisy_measurements = 7
+
+
+def prepare_code() -> fb.PhenomenologicalPlanarCode:
+    return fb.PhenomenologicalPlanarCode(d=d, noisy_measurements=noisy_measurements, p=0.02)
+
+
+def test_time_partition_matches_serial():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    partition_config = fb.PartitionConfig.time_partition(
+        d * (d + 1), noisy_measurements + 1, 4)
+    partition_config.validate(initializer)
+    partition_info = partition_config.info()
+    assert len(partition_info.units) == 7
+    serial_solver = fb.SolverSerial(initializer)
+    parallel_solver = fb.SolverParallel(
+        initializer, partition_info, thread_pool_size=2)
+    for seed in range(20):
+        syndrome = code.generate_random_errors(seed=seed)
+        serial_solver.solve(syndrome)
+        parallel_solver.solve(syndrome)
+        assert serial_solver.sum_dual_variables() == parallel_solver.sum_dual_variables()
+        serial_solver.clear()
+        parallel_solver.clear()
+
+
+def test_tree_partition():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    partition_config = fb.PartitionConfig.time_partition(
+        d * (d + 1), noisy_measurements + 1, 4, enable_tree_fusion=True)
+    assert partition_config.fusions == [(0, 1), (2, 3), (4, 5)]
+    partition_config.validate(initializer)
+
+
+def test_invalid_partition():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    round_vertex_num = d * (d + 1)
+    # the two partitions overlap
+    partition_config = fb.PartitionConfig.from_partitions(initializer.vertex_num, [
+        fb.VertexRange(0, 3 * round_vertex_num),
+        fb.VertexRange(2 * round_vertex_num, initializer.vertex_num),
+    ])
+    try:
+        partition_config.validate(initializer)
+        assert False, "overlapping partitions must be rejected"
+    except ValueError:
+        pass
+
+
+def test_fusion_plan():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    partition_config = fb.PartitionConfig.time_partition(
+        d * (d + 1), noisy_measurements + 1, 4)
+    partition_config = partition_config.with_fusion_plan([(0, 1), (2, 3), (4, 5)])
+    partition_info = partition_config.info()
+    assert partition_info.fusion_plan() == [(0, 1), (2, 3), (4, 5)]
+    partition_config.validate(initializer)
+    try:
+        partition_config.with_fusion_plan([(0, 1), (2, 3)])
+        assert False, "the plan must fuse all units into a single tree"
+    except ValueError:
+        pass
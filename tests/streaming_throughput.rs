@@ -0,0 +1,60 @@
+//! Streaming Throughput
+//!
+//! A dedicated test binary for [`ThroughputSummary`], because counting the allocations requires a global allocator,
+//! which would otherwise be installed for every unit test of the library
+//!
+
+use fusion_blossom::example_codes::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::util::*;
+
+/// count the allocations of each thread, so that other tests running in parallel don't interfere
+struct CountingAllocator;
+
+thread_local! {
+    static THREAD_ALLOCATIONS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        std::alloc::System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn thread_allocations() -> u64 {
+    THREAD_ALLOCATIONS.with(|count| count.get())
+}
+
+/// reusing a solver across shots is faster and allocates less than constructing a new one for each shot;
+/// a regression guard against per-shot allocations creeping into `clear` or loading the defects
+#[test]
+fn streaming_throughput_1() {
+    // cargo test --release --test streaming_throughput -- --nocapture
+    let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.01, 500);
+    let initializer = code.get_initializer();
+    let syndrome_patterns: Vec<_> = (0..200).map(|seed| code.generate_random_errors(seed)).collect();
+    let mut solver = SolverSerial::new(&initializer);
+    // warm up so that the growable structures reach their steady-state capacity
+    ThroughputSummary::reused(&mut solver, &syndrome_patterns, None);
+    let reused = ThroughputSummary::reused(&mut solver, &syndrome_patterns, Some(&thread_allocations));
+    let cold = ThroughputSummary::cold(
+        || SolverSerial::new(&initializer),
+        &syndrome_patterns,
+        Some(&thread_allocations),
+    );
+    println!("reused: {}", serde_json::to_string(&reused).unwrap());
+    println!("cold: {}", serde_json::to_string(&cold).unwrap());
+    let reused_allocations = reused.allocations_per_shot().unwrap();
+    let cold_allocations = cold.allocations_per_shot().unwrap();
+    assert!(
+        reused_allocations * 10. < cold_allocations,
+        "reusing the solver should save most allocations: {reused_allocations} vs {cold_allocations} per shot"
+    );
+}
@@ -94,6 +94,7 @@ partition_config.partitions = [fb.NodeRange(0,36), # unit 0
                                 fb.NodeRange(112,132) # unit 3
                                 ]
 partition_config.fusions = [(0, 1), (2, 3), (4, 5)] # refer to tree figure in paper
+partition_config.validate(initializer) # raise ValueError listing the issues if the partition doesn't fit the decoding graph
 partition_info = partition_config.info()
 
 ## Define primal_dual_config
@@ -229,6 +229,18 @@ export const subgraph_edge_material = new THREE.MeshStandardMaterial({
     transparent: true,
     side: THREE.FrontSide,
 })
+export const matching_diff_self_edge_material = new THREE.MeshStandardMaterial({  // edges only used by the first matching
+    color: 0xff8000,
+    opacity: 1,
+    transparent: true,
+    side: THREE.FrontSide,
+})
+export const matching_diff_other_edge_material = new THREE.MeshStandardMaterial({  // edges only used by the second matching
+    color: 0x00c000,
+    opacity: 1,
+    transparent: true,
+    side: THREE.FrontSide,
+})
 export const hover_material = new THREE.MeshStandardMaterial({  // when mouse is on this object (vertex or edge)
     color: 0x6FDFDF,
     side: THREE.DoubleSide,
@@ -388,6 +400,16 @@ export async function refresh_snapshot_data() {
                 subgraph_set[edge_index] = true
             }
         }
+        let matching_diff_self_set = {}
+        let matching_diff_other_set = {}
+        if (snapshot.matching_diff != null) {
+            for (let edge_index of snapshot.matching_diff.only_in_self.edges) {
+                matching_diff_self_set[edge_index] = true
+            }
+            for (let edge_index of snapshot.matching_diff.only_in_other.edges) {
+                matching_diff_other_set[edge_index] = true
+            }
+        }
         let edge_offset = 0
         if (scaled_edge_radius.value < scaled_vertex_outline_radius.value) {
             edge_offset = Math.sqrt(Math.pow(scaled_vertex_outline_radius.value, 2) - Math.pow(scaled_edge_radius.value, 2))
@@ -462,12 +484,18 @@ export async function refresh_snapshot_data() {
                         edge_mesh.visible = false
                     }
                     edge_mesh.material = is_grown_part ? grown_edge_material : edge_material
-                    if (snapshot.subgraph != null) {
+                    if (snapshot.subgraph != null || snapshot.matching_diff != null) {
                         edge_mesh.material = edge_material  // do not display grown edges
                     }
                     if (subgraph_set[i]) {
                         edge_mesh.material = subgraph_edge_material
                     }
+                    if (matching_diff_self_set[i]) {
+                        edge_mesh.material = matching_diff_self_edge_material
+                    }
+                    if (matching_diff_other_set[i]) {
+                        edge_mesh.material = matching_diff_other_edge_material
+                    }
                 }
             }
         }
@@ -628,6 +656,8 @@ const conf = {
     subgraph_edge_color: subgraph_edge_material.color,
     subgraph_edge_opacity: subgraph_edge_material.opacity,
     subgraph_edge_side: subgraph_edge_material.side,
+    matching_diff_self_edge_color: matching_diff_self_edge_material.color,
+    matching_diff_other_edge_color: matching_diff_other_edge_material.color,
     outline_ratio: outline_ratio.value,
     vertex_radius_scale: vertex_radius_scale.value,
     edge_radius_scale: edge_radius_scale.value,
@@ -662,6 +692,8 @@ controller.grown_edge_side = edge_folder.add(conf, 'grown_edge_side', side_optio
 controller.subgraph_edge_color = edge_folder.addColor(conf, 'subgraph_edge_color').onChange(function (value) { subgraph_edge_material.color = value })
 controller.subgraph_edge_opacity = edge_folder.add(conf, 'subgraph_edge_opacity', 0, 1).onChange(function (value) { subgraph_edge_material.opacity = Number(value) })
 controller.subgraph_edge_side = edge_folder.add(conf, 'subgraph_edge_side', side_options).onChange(function (value) { subgraph_edge_material.side = Number(value) })
+controller.matching_diff_self_edge_color = edge_folder.addColor(conf, 'matching_diff_self_edge_color').onChange(function (value) { matching_diff_self_edge_material.color = value })
+controller.matching_diff_other_edge_color = edge_folder.addColor(conf, 'matching_diff_other_edge_color').onChange(function (value) { matching_diff_other_edge_material.color = value })
 const size_folder = gui.addFolder('size')
 controller.outline_ratio = size_folder.add(conf, 'outline_ratio', 0.99, 2).onChange(function (value) { outline_ratio.value = Number(value) })
 controller.vertex_radius_scale = size_folder.add(conf, 'vertex_radius_scale', 0.1, 5).onChange(function (value) { vertex_radius_scale.value = Number(value) })
@@ -25,6 +25,57 @@ const urlParams = new URLSearchParams(window.location.search)
 const filename = urlParams.get('filename') || "visualizer.json"
 
 export var fusion_data
+
+// `.json.gz` and `.json.zst` files are decompressed here unless the server already did it via Content-Encoding
+async function parse_fusion_data(response) {
+    const compression = filename.endsWith(".gz") ? "gzip" : (filename.endsWith(".zst") ? "zstd" : null)
+    if (compression == null) {
+        return await response.json()
+    }
+    const buffer = new Uint8Array(await response.arrayBuffer())
+    const is_gzip = buffer.length >= 2 && buffer[0] == 0x1f && buffer[1] == 0x8b
+    const is_zstd = buffer.length >= 4 && buffer[0] == 0x28 && buffer[1] == 0xb5 && buffer[2] == 0x2f && buffer[3] == 0xfd
+    if (!is_gzip && !is_zstd) {
+        return JSON.parse(new TextDecoder().decode(buffer))
+    }
+    let decompression_stream
+    try {
+        decompression_stream = new DecompressionStream(is_gzip ? "gzip" : "zstd")
+    } catch (e) {
+        throw `the browser cannot decompress ${compression} data, please serve the file with "Content-Encoding: ${compression}"`
+    }
+    const stream = new Blob([buffer]).stream().pipeThrough(decompression_stream)
+    return JSON.parse(await new Response(stream).text())
+}
+
+// the decoding graph may be embedded in the file header (see `Visualizer::new_with_initializer`);
+// use it to recover the static fields (edge weights and endpoints, virtual vertices) that a snapshot omits
+function apply_embedded_initializer(fusion_data) {
+    const initializer = fusion_data.initializer
+    if (initializer == null) {
+        return
+    }
+    const is_virtual = new Array(initializer.vertex_num).fill(false)
+    for (const vertex_index of initializer.virtual_vertices) {
+        is_virtual[vertex_index] = true
+    }
+    for (const [_, snapshot] of fusion_data.snapshots) {
+        for (const [vertex_index, vertex] of (snapshot.vertices || []).entries()) {
+            if (vertex != null && vertex.v == null) {
+                vertex.v = is_virtual[vertex_index] ? 1 : 0
+            }
+        }
+        for (const [edge_index, edge] of (snapshot.edges || []).entries()) {
+            if (edge == null || edge_index >= initializer.weighted_edges.length) {
+                continue
+            }
+            const [left, right, weight] = initializer.weighted_edges[edge_index]
+            if (edge.l == null) edge.l = left
+            if (edge.r == null) edge.r = right
+            if (edge.w == null) edge.w = weight
+        }
+    }
+}
 var patch_done = ref(false)
 
 // alert(navigator.userAgent)
@@ -75,12 +126,13 @@ const App = {
             throw e
         }
         if (response.ok || is_mock) {
-            fusion_data = await response.json()
+            fusion_data = await parse_fusion_data(response)
             // console.log(fusion_data)
             if (fusion_data.format != "fusion_blossom") {
                 this.error_message = `visualization file format error, get "${fusion_data.format}" expected "fusion_data"`
                 throw this.error_message
             }
+            apply_embedded_initializer(fusion_data)
         } else {
             this.error_message = `fetch file error ${response.status}: ${response.statusText}`
             throw this.error_message
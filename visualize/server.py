@@ -23,6 +23,12 @@ class MyHTTPRequestHandler(SimpleHTTPRequestHandler):
             "Cache-Control", "no-cache, no-store, must-revalidate")
         self.send_header("Pragma", "no-cache")
         self.send_header("Expires", "0")
+        # compressed visualizer data is decompressed by the browser transparently
+        path = self.path.split('?', 1)[0].split('#', 1)[0]
+        if path.endswith(".json.gz"):
+            self.send_header("Content-Encoding", "gzip")
+        elif path.endswith(".json.zst"):
+            self.send_header("Content-Encoding", "zstd")
 
     # from https://hg.python.org/cpython/file/3.5/Lib/http/server.py
     def translate_path(self, path):